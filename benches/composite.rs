@@ -3,7 +3,9 @@ mod shared;
 use {
     broot::{
         command::CommandParts,
+        content_search::ContentSearchLimits,
         pattern::*,
+        tag::TagStore,
     },
     glassbench::*,
 };
@@ -18,11 +20,13 @@ static PATTERNS: &[&str] = &[
 
 fn bench_score_of_composite(gb: &mut Bench) {
     let search_modes = SearchModeMap::default();
+    let content_search_limits = ContentSearchLimits::default();
+    let tags = TagStore::default();
     for pattern in PATTERNS {
         let name = format!("Composite({:?})::score_of", &pattern);
         gb.task(name, |b| {
             let parts = CommandParts::from(pattern.to_string());
-            let cp = Pattern::new(&parts.pattern, &search_modes, 10*1024*1024).unwrap();
+            let cp = Pattern::new(&parts.pattern, &search_modes, &content_search_limits, &tags).unwrap();
             b.iter(|| {
                 for name in shared::NAMES {
                     pretend_used(cp.score_of_string(name));
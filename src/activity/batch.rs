@@ -0,0 +1,264 @@
+use {
+    super::*,
+    crate::{
+        app::{AppContext, CmdResult},
+        display::W,
+        errors::ProgramError,
+        launchable::Launchable,
+        tr,
+        verb::Sandbox,
+    },
+    std::path::PathBuf,
+};
+
+/// what to do with a copy or move whose destination exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// don't copy or move the file
+    Skip,
+    /// replace the existing file
+    Overwrite,
+    /// keep the existing file under a numbered backup name
+    Rename,
+}
+
+impl ConflictResolution {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Overwrite => "overwrite",
+            Self::Rename => "rename",
+        }
+    }
+}
+
+/// a copy or move of a batch whose destination already exists
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    /// index of the execution in the batch
+    pub idx: usize,
+    pub kind: OpKind,
+    pub source: PathBuf,
+    pub existing: PathBuf,
+    pub resolution: Option<ConflictResolution>,
+}
+
+/// the executions of a verb on the staged paths, one per path
+#[derive(Debug, Clone)]
+pub struct Batch {
+    /// the command, as it would be typed, when the batch must be journaled
+    pub journal_command: Option<String>,
+    pub token_lists: Vec<Vec<String>>,
+    pub working_dir: Option<PathBuf>,
+    pub sandbox: Sandbox,
+}
+
+/// remove the options of `cp` and `mv` which decide what
+/// happens when the destination exists
+fn without_overwrite_options(tokens: &[String]) -> Vec<String> {
+    let n = tokens.len();
+    tokens
+        .iter()
+        .enumerate()
+        .filter(|(i, token)| {
+            *i == 0 || *i >= n - 2 || !matches!(
+                token.as_str(),
+                "-i" | "-n" | "-f" | "--backup=numbered"
+            )
+        })
+        .map(|(_, token)| token.clone())
+        .collect()
+}
+
+impl Batch {
+    /// the copies and moves whose destination already exists
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        self.token_lists
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, tokens)| {
+                let (kind, source, existing) = OpKind::of_tokens(tokens)?;
+                if kind == OpKind::Other || !existing.exists() || existing == source {
+                    return None;
+                }
+                Some(Conflict {
+                    idx,
+                    kind,
+                    source,
+                    existing,
+                    resolution: None,
+                })
+            })
+            .collect()
+    }
+
+    /// change the executions according to the resolutions of
+    /// the conflicts, removing the skipped ones
+    pub fn resolve(mut self, conflicts: &[Conflict]) -> Self {
+        let mut skipped = Vec::new();
+        for conflict in conflicts {
+            let tokens = &mut self.token_lists[conflict.idx];
+            let option = match conflict.resolution {
+                Some(ConflictResolution::Overwrite) => "-f",
+                Some(ConflictResolution::Rename) => "--backup=numbered",
+                _ => {
+                    skipped.push(conflict.idx);
+                    continue;
+                }
+            };
+            let mut resolved = without_overwrite_options(tokens);
+            resolved.insert(1, option.to_string());
+            *tokens = resolved;
+        }
+        self.token_lists = self.token_lists
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| !skipped.contains(idx))
+            .map(|(_, tokens)| tokens)
+            .collect();
+        self
+    }
+
+    /// execute all operations in sequence, journaling them if
+    /// required.
+    ///
+    /// Return a cmd result when the execution must stop on an error
+    pub fn execute(
+        self,
+        w: &mut W,
+        con: &AppContext,
+        denied: &mut Option<&mut Vec<Launchable>>,
+    ) -> Result<Option<CmdResult>, ProgramError> {
+        let Self { journal_command, token_lists, working_dir, sandbox } = self;
+        let mut journal = journal_command.and_then(|command| {
+            let ops = token_lists.iter()
+                .map(|tokens| JournalOp::new(tokens.clone(), working_dir.clone()))
+                .collect();
+            OpJournal::start(command, ops)
+                .map_err(|e| warn!("failed to write the operation journal: {}", e))
+                .ok()
+        });
+        for (idx, tokens) in token_lists.into_iter().enumerate() {
+            let launchable = Launchable::program(
+                tokens,
+                working_dir.clone(),
+                sandbox.clone(),
+                con,
+            );
+            let launchable = match launchable {
+                Ok(launchable) => launchable,
+                Err(e) => {
+                    if let Some(journal) = journal {
+                        journal.finish();
+                    }
+                    return Err(e.into());
+                }
+            };
+            if let Some(journal) = journal.as_mut() {
+                journal.set_status(idx, OpStatus::Started);
+            }
+            if let Some(res) = execute_or_escalate(w, launchable, con, denied) {
+                if let Some(journal) = journal {
+                    journal.finish();
+                }
+                return Ok(Some(res));
+            }
+            if let Some(journal) = journal.as_mut() {
+                journal.set_status(idx, OpStatus::Done);
+            }
+        }
+        if let Some(journal) = journal {
+            journal.finish();
+        }
+        Ok(None)
+    }
+}
+
+/// execute the launchable, pushing it, escalated, to `denied` when it
+/// fails for lack of permission and escalation is possible.
+///
+/// Return a cmd result when the execution must stop on an error
+pub fn execute_or_escalate(
+    w: &mut W,
+    launchable: Launchable,
+    con: &AppContext,
+    denied: &mut Option<&mut Vec<Launchable>>,
+) -> Option<CmdResult> {
+    let escalation = con.escalation_command.as_ref().zip(denied.as_mut());
+    let launchable = if escalation.is_some() {
+        launchable.with_denial_detection()
+    } else {
+        launchable
+    };
+    match launchable.execute(Some(w)) {
+        Ok(()) => None,
+        Err(ProgramError::PermissionDenied { .. }) if escalation.is_some() => {
+            if let Some((escalation_command, denied)) = escalation {
+                denied.push(launchable.escalated(escalation_command));
+            }
+            None
+        }
+        Err(e) => {
+            warn!("launchable failed : {:?}", e);
+            Some(CmdResult::error(e.to_string()))
+        }
+    }
+}
+
+/// the result of an execution, telling the user how to retry
+/// the operations denied for lack of permission, if any
+pub fn escalation_result(denied: Option<&mut Vec<Launchable>>) -> CmdResult {
+    if let Some(denied) = denied.filter(|denied| !denied.is_empty()) {
+        let commands = denied.iter()
+            .map(|launchable| launchable.description())
+            .collect::<Vec<String>>();
+        return CmdResult::error(tr!(
+            error_permission_denied,
+            commands = commands.join(" ; "),
+        ));
+    }
+    CmdResult::RefreshState { clear_cache: true }
+}
+
+#[cfg(test)]
+mod batch_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_batch_resolution() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        let dst = dir.path().join("dst");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::create_dir(&dst).unwrap();
+        let mut token_lists = Vec::new();
+        for name in ["a", "b", "c"] {
+            std::fs::write(src.join(name), name).unwrap();
+            token_lists.push(vec![
+                "cp".to_string(),
+                "-i".to_string(),
+                src.join(name).to_string_lossy().to_string(),
+                dst.to_string_lossy().to_string(),
+            ]);
+        }
+        std::fs::write(dst.join("a"), "old a").unwrap();
+        std::fs::write(dst.join("b"), "old b").unwrap();
+        let batch = Batch {
+            journal_command: None,
+            token_lists,
+            working_dir: None,
+            sandbox: Sandbox::default(),
+        };
+        let mut conflicts = batch.conflicts();
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].existing, dst.join("a"));
+        conflicts[0].resolution = Some(ConflictResolution::Skip);
+        conflicts[1].resolution = Some(ConflictResolution::Overwrite);
+        let batch = batch.resolve(&conflicts);
+        assert_eq!(batch.token_lists.len(), 2);
+        assert_eq!(&batch.token_lists[0][..2], &["cp".to_string(), "-f".to_string()]);
+        assert_eq!(batch.token_lists[0].len(), 4);
+        assert_eq!(batch.token_lists[1][1], "-i");
+    }
+}
@@ -0,0 +1,279 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        launchable::Launchable,
+        pattern::*,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::path::Path,
+    termimad::minimad::Alignment,
+};
+
+/// an application state listing the conflicts of a batch copy
+/// or move, letting the user choose, for each one, whether to
+/// skip, overwrite or rename, before the batch is executed
+pub struct ConflictState {
+    batch: Option<Batch>, // None once executed
+    conflicts: FilteredList<Conflict>, // never filtered
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+impl ConflictState {
+    pub fn new(
+        batch: Batch,
+        conflicts: Vec<Conflict>,
+        con: &AppContext,
+    ) -> ConflictState {
+        ConflictState {
+            batch: Some(batch),
+            conflicts: FilteredList::new(conflicts, |_, _| true),
+            tree_options: con.initial_tree_options.clone(),
+            mode: initial_mode(con),
+        }
+    }
+    fn unresolved_count(&self) -> usize {
+        self.conflicts.items().iter().filter(|c| c.resolution.is_none()).count()
+    }
+    /// set the resolution of the selected conflict, then
+    /// select the next one
+    fn resolve_selected(&mut self, resolution: ConflictResolution) -> CmdResult {
+        if let Some(conflict) = self.conflicts.selected_mut() {
+            conflict.resolution = Some(resolution);
+        }
+        self.conflicts.move_selection(1, false);
+        CmdResult::Keep
+    }
+
+    fn resolve_all(&mut self, resolution: ConflictResolution) -> CmdResult {
+        for conflict in self.conflicts.items_mut() {
+            conflict.resolution = Some(resolution);
+        }
+        CmdResult::Keep
+    }
+
+    /// show the differences between the existing file and the
+    /// one which would replace it
+    #[cfg(unix)]
+    fn diff_selected(&self, w: &mut W, con: &AppContext) -> Result<CmdResult, ProgramError> {
+        let conflict = match self.conflicts.selected() {
+            Some(conflict) => conflict,
+            None => {
+                return Ok(CmdResult::error(tr!(error_no_selected_conflict)));
+            }
+        };
+        // the paths are given as arguments to the shell to avoid any escaping
+        let parts = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            r#"diff -ru -- "$1" "$2" | ${PAGER:-less}"#.to_string(),
+            "sh".to_string(),
+            conflict.existing.to_string_lossy().to_string(),
+            conflict.source.to_string_lossy().to_string(),
+        ];
+        Launchable::program(parts, None, Sandbox::default(), con)?
+            .execute(Some(w))?;
+        Ok(CmdResult::Keep)
+    }
+    #[cfg(not(unix))]
+    fn diff_selected(&self, _w: &mut W, _con: &AppContext) -> Result<CmdResult, ProgramError> {
+        Ok(CmdResult::error(tr!(error_diff_unix_only)))
+    }
+
+    /// execute the batch with the chosen resolutions
+    fn execute(
+        &mut self,
+        w: &mut W,
+        app_state: &mut AppState,
+        con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        if con.read_only {
+            return Ok(CmdResult::error(tr!(error_copy_move_read_only)));
+        }
+        let unresolved_count = self.unresolved_count();
+        if unresolved_count > 0 {
+            return Ok(CmdResult::error(tr!(
+                error_unresolved_conflicts,
+                count = unresolved_count,
+            )));
+        }
+        let batch = match self.batch.take() {
+            Some(batch) => batch.resolve(self.conflicts.items()),
+            None => {
+                return Ok(CmdResult::error(tr!(error_batch_executed)));
+            }
+        };
+        let mut denied = Vec::new();
+        let mut escalable = if con.escalation_command.is_some() {
+            Some(&mut denied)
+        } else {
+            None
+        };
+        if let Some(res) = batch.execute(w, con, &mut escalable)? {
+            return Ok(res);
+        }
+        let res = escalation_result(escalable);
+        app_state.pending_escalation = denied;
+        Ok(match res {
+            CmdResult::RefreshState { .. } => CmdResult::PopStateAndExecute {
+                sequence: Sequence::new_single(":refresh".to_string()),
+            },
+            res => res,
+        })
+    }
+}
+
+impl PanelState for ConflictState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Conflicts
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn selected_path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        None
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        _pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        Ok(CmdResult::Keep)
+    }
+
+    fn no_verb_status(
+        &self,
+        _has_previous_state: bool,
+        _con: &AppContext,
+    ) -> Status {
+        let unresolved_count = self.unresolved_count();
+        if unresolved_count > 0 {
+            Status::from_message(format!(
+                "{} of {} conflicts to resolve with `:skip`, `:ow` (overwrite) or \
+                `:rename_conflict` (or `:skip_all_conflicts`, etc.), *esc* to cancel",
+                unresolved_count,
+                self.conflicts.count(),
+            ))
+        } else {
+            Status::from_message(tr!(status_batch_conflicts))
+        }
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let w_resolution = "overwrite".len();
+        let w_source = self.conflicts.items().iter()
+            .map(|c| c.source.to_string_lossy().chars().count())
+            .max().unwrap_or(0)
+            .max("source".len())
+            .min(disc.state_area.width as usize / 2);
+        let columns = [
+            ListColumn::new("action", w_resolution, Alignment::Center),
+            ListColumn::new("source", w_source, Alignment::Left),
+        ];
+        let styles = &disc.panel_skin.styles;
+        self.conflicts.queue_table_head(w, disc, &columns, |cw| {
+            cw.queue_str(&styles.default, "existing destination")?;
+            Ok(())
+        })?;
+        self.conflicts.queue_table_rows(w, disc, |cw, conflict, row| {
+            match conflict.resolution {
+                Some(resolution) => {
+                    row.queue_cell(cw, resolution.name(), row.txt_style, w_resolution, Alignment::Center)?;
+                }
+                None => {
+                    let style = row.style(&styles.file_error);
+                    row.queue_cell(cw, "?", &style, w_resolution, Alignment::Center)?;
+                }
+            }
+            row.queue_cell(cw, &conflict.source.to_string_lossy(), row.txt_style, w_source, Alignment::Left)?;
+            cw.queue_str(row.txt_style, &conflict.existing.to_string_lossy())?;
+            Ok(())
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let con = &cc.app.con;
+        if let Some(res) = self.conflicts.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::skip_conflict => self.resolve_selected(ConflictResolution::Skip),
+            Internal::overwrite_conflict => self.resolve_selected(ConflictResolution::Overwrite),
+            Internal::rename_conflict => self.resolve_selected(ConflictResolution::Rename),
+            Internal::skip_all_conflicts => self.resolve_all(ConflictResolution::Skip),
+            Internal::overwrite_all_conflicts => self.resolve_all(ConflictResolution::Overwrite),
+            Internal::rename_all_conflicts => self.resolve_all(ConflictResolution::Rename),
+            Internal::diff_conflict => self.diff_selected(w, con)?,
+            Internal::open_stay | Internal::open_leave => self.execute(w, app_state, con)?,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.conflicts.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
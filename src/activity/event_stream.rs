@@ -0,0 +1,75 @@
+use {
+    serde::Serialize,
+    std::{
+        fs::File,
+        io::Write,
+        path::{Path, PathBuf},
+    },
+};
+
+/// an event sent to the listener of the stream
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ActivityEvent<'a> {
+    /// the selection of the active panel changed
+    Selection {
+        panel: usize,
+        path: &'a Path,
+    },
+    /// a verb was executed
+    Verb {
+        command: &'a str,
+        paths: &'a [PathBuf],
+        outcome: String,
+    },
+    /// a new state was pushed (in the same panel or in a new one)
+    State {
+        state: &'static str,
+        path: Option<&'a Path>,
+    },
+}
+
+/// a stream of JSON events, one per line, written to a file
+/// descriptor given at launch (`--events-fd`) so that other
+/// programs can follow what's done in broot
+#[derive(Debug)]
+pub struct EventStream {
+    file: File,
+    /// the last selection sent, so that it's sent only on change
+    last_selection: Option<(usize, PathBuf)>,
+}
+
+impl EventStream {
+    /// take ownership of the file descriptor, which must have been
+    /// checked to be open (and not be a standard stream) when
+    /// building the app context
+    #[cfg(unix)]
+    pub fn from_fd(fd: i32) -> Self {
+        use std::os::unix::io::FromRawFd;
+        let file = unsafe { File::from_raw_fd(fd) };
+        Self {
+            file,
+            last_selection: None,
+        }
+    }
+    pub fn send(&mut self, event: &ActivityEvent) {
+        let res = serde_json::to_string(event)
+            .map_err(|e| e.to_string())
+            .and_then(|json| {
+                writeln!(self.file, "{}", json).map_err(|e| e.to_string())
+            });
+        if let Err(e) = res {
+            warn!("failed to send event {:?}: {}", event, e);
+        }
+    }
+    /// send a selection event if the selection isn't the last one sent
+    pub fn selection(&mut self, panel: usize, path: &Path) {
+        if let Some((last_panel, last_path)) = &self.last_selection {
+            if *last_panel == panel && last_path == path {
+                return;
+            }
+        }
+        self.send(&ActivityEvent::Selection { panel, path });
+        self.last_selection = Some((panel, path.to_path_buf()));
+    }
+}
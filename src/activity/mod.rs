@@ -0,0 +1,19 @@
+//! The log of the verbs executed during the session, kept so that
+//! what was done (especially the destructive actions) can be
+//! reviewed afterwards
+
+mod batch;
+mod conflict_state;
+mod event_stream;
+mod op_journal;
+mod verb_log;
+mod verb_log_state;
+
+pub use {
+    batch::*,
+    conflict_state::ConflictState,
+    event_stream::*,
+    op_journal::*,
+    verb_log::*,
+    verb_log_state::VerbLogState,
+};
@@ -0,0 +1,382 @@
+use {
+    crate::{
+        app::AppContext,
+        conf,
+        display::W,
+        errors::ProgramError,
+        launchable::Launchable,
+        verb::Sandbox,
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        fs,
+        io,
+        path::{Path, PathBuf},
+        process,
+    },
+};
+
+/// where an operation of a journaled batch is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpStatus {
+    Pending,
+    /// launched but not known to be finished: when the journal is
+    /// found in this state, the operation was interrupted
+    Started,
+    Done,
+}
+
+/// what an operation does, as far as its undoing is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpKind {
+    Copy,
+    Move,
+    /// an operation which can be resumed but not rolled back
+    Other,
+}
+
+/// one execution of a batch, on one of the staged paths
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalOp {
+    pub tokens: Vec<String>,
+    pub working_dir: Option<PathBuf>,
+    pub kind: OpKind,
+    pub source: Option<PathBuf>,
+    /// the path the source is copied or moved to, when it didn't
+    /// exist before the operation (so that removing it is safe)
+    pub target: Option<PathBuf>,
+    pub status: OpStatus,
+}
+
+impl OpKind {
+    /// recognize the copies and moves of the `cp` and `mv` commands
+    /// (like the built-in verbs) and return their kind, source, and
+    /// destination (the path the source will have after the operation)
+    pub fn of_tokens(tokens: &[String]) -> Option<(Self, PathBuf, PathBuf)> {
+        let kind = match tokens.first().map(|exe| exe.as_str()) {
+            Some("cp") => Self::Copy,
+            Some("mv") => Self::Move,
+            _ => return None,
+        };
+        if tokens.len() < 3 {
+            return None;
+        }
+        let src = PathBuf::from(&tokens[tokens.len() - 2]);
+        let dst = PathBuf::from(&tokens[tokens.len() - 1]);
+        if !src.is_absolute() || !dst.is_absolute() {
+            return None;
+        }
+        let dst = match (dst.is_dir(), src.file_name()) {
+            (true, Some(name)) => dst.join(name),
+            _ => dst,
+        };
+        Some((kind, src, dst))
+    }
+}
+
+impl JournalOp {
+    /// make a pending operation, recognizing the copies and moves
+    /// of the `cp` and `mv` commands (like the built-in verbs)
+    pub fn new(
+        tokens: Vec<String>,
+        working_dir: Option<PathBuf>,
+    ) -> Self {
+        let (kind, source, target) = match OpKind::of_tokens(&tokens) {
+            Some((kind, src, dst)) => {
+                let target = if dst.exists() { None } else { Some(dst) };
+                (kind, Some(src), target)
+            }
+            None => {
+                let kind = match tokens.first().map(|exe| exe.as_str()) {
+                    Some("cp") => OpKind::Copy,
+                    Some("mv") => OpKind::Move,
+                    _ => OpKind::Other,
+                };
+                (kind, None, None)
+            }
+        };
+        Self {
+            tokens,
+            working_dir,
+            kind,
+            source,
+            target,
+            status: OpStatus::Pending,
+        }
+    }
+    /// prepare the new execution of an operation which wasn't done,
+    /// removing the partial copy left by an interruption.
+    ///
+    /// Return whether the operation must be executed, or an error when
+    /// an interrupted copy or move can't be safely executed again.
+    fn prepare_resume(&self) -> Result<bool, ProgramError> {
+        if self.status == OpStatus::Pending || self.kind == OpKind::Other {
+            return Ok(true);
+        }
+        let unresumable = || ProgramError::Unresumable {
+            command: self.tokens.join(" "),
+        };
+        let (source, target) = match (&self.source, &self.target) {
+            (Some(source), Some(target)) => (source, target),
+            // the target existed before: we can't tell what was written
+            _ => return Err(unresumable()),
+        };
+        if !target.exists() {
+            return Ok(true);
+        }
+        match self.kind {
+            OpKind::Copy => {
+                // executing again `cp -r src dst` with an existing dst
+                // would make a dst/src copy
+                let res = if target.is_dir() {
+                    fs::remove_dir_all(target)
+                } else {
+                    fs::remove_file(target)
+                };
+                res.map_err(|_| unresumable())?;
+                Ok(true)
+            }
+            // the move is complete
+            OpKind::Move if !source.exists() => Ok(false),
+            _ => Err(unresumable()),
+        }
+    }
+    /// undo the operation if it's done or interrupted, returning
+    /// whether it could be done
+    fn rollback(&self) -> bool {
+        if self.status == OpStatus::Pending {
+            return true;
+        }
+        let (source, target) = match (&self.source, &self.target) {
+            (Some(source), Some(target)) => (source, target),
+            _ => return false,
+        };
+        if !target.exists() {
+            return true;
+        }
+        match self.kind {
+            OpKind::Copy => {
+                let res = if target.is_dir() {
+                    fs::remove_dir_all(target)
+                } else {
+                    fs::remove_file(target)
+                };
+                res.is_ok()
+            }
+            OpKind::Move if !source.exists() => {
+                // mv is used rather than a rename as the source and target
+                // may be on different file systems
+                process::Command::new("mv")
+                    .arg(target)
+                    .arg(source)
+                    .status()
+                    .map_or(false, |status| status.success())
+            }
+            // the source and the target both exist: the move was cut in
+            // the middle and there's no safe way to know what's complete
+            _ => false,
+        }
+    }
+}
+
+/// A journal of the batch of executions of a mutating verb on
+/// a multi-selection, written before and updated during the
+/// execution so that, if broot is killed in the middle, the
+/// next broot can resume or roll back the interrupted batch.
+///
+/// Journals are JSON files, one per broot process, in the
+/// `journal` directory of the data dir.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpJournal {
+    /// the command, as it would be typed (eg `:mv ../dest`)
+    pub command: String,
+    pub ops: Vec<JournalOp>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl OpJournal {
+    /// the directory where journals are stored
+    pub fn dir() -> PathBuf {
+        conf::app_dirs().data_dir().join("journal")
+    }
+    /// start a journal and write it, before any operation is executed
+    pub fn start(
+        command: String,
+        ops: Vec<JournalOp>,
+    ) -> io::Result<Self> {
+        let dir = Self::dir();
+        fs::create_dir_all(&dir)?;
+        let journal = Self {
+            command,
+            ops,
+            path: dir.join(format!("{}.json", process::id())),
+        };
+        journal.save()?;
+        Ok(journal)
+    }
+    /// write the journal, in a way which never leaves a truncated file
+    fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, &self.path)
+    }
+    pub fn set_status(&mut self, idx: usize, status: OpStatus) {
+        self.ops[idx].status = status;
+        if let Err(e) = self.save() {
+            warn!("failed to update the operation journal: {}", e);
+        }
+    }
+    /// remove the journal file, the batch being over (either complete
+    /// or stopped on an error the user saw)
+    pub fn finish(self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!("failed to remove the operation journal: {}", e);
+        }
+    }
+    /// find the journal of a batch interrupted by the death of the
+    /// broot process which was executing it
+    pub fn find_interrupted() -> Option<Self> {
+        let mut journals: Vec<(PathBuf, std::time::SystemTime)> = fs::read_dir(Self::dir())
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+            .filter(|path| !is_of_running_process(path))
+            .filter_map(|path| {
+                let modified = fs::metadata(&path).and_then(|md| md.modified()).ok()?;
+                Some((path, modified))
+            })
+            .collect();
+        journals.sort_by_key(|(_, modified)| *modified);
+        journals.into_iter()
+            .find_map(|(path, _)| {
+                let mut journal: Self = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|json| serde_json::from_str(&json).ok())?;
+                journal.path = path;
+                Some(journal)
+            })
+    }
+    pub fn done_count(&self) -> usize {
+        self.ops.iter().filter(|op| op.status == OpStatus::Done).count()
+    }
+    /// a short description of the state of the interrupted batch
+    pub fn summary(&self) -> String {
+        format!(
+            "`{}` was interrupted after {} of {} operations",
+            &self.command,
+            self.done_count(),
+            self.ops.len(),
+        )
+    }
+    /// execute the operations which weren't done, return the number
+    /// of executed operations.
+    ///
+    /// When the interrupted operation can't be safely executed again,
+    /// nothing is done and the journal is kept.
+    pub fn resume(
+        mut self,
+        w: &mut W,
+        con: &AppContext,
+    ) -> Result<usize, ProgramError> {
+        let mut count = 0;
+        for idx in 0..self.ops.len() {
+            if self.ops[idx].status == OpStatus::Done {
+                continue;
+            }
+            let op = &self.ops[idx];
+            if !op.prepare_resume()? {
+                self.set_status(idx, OpStatus::Done);
+                continue;
+            }
+            let launchable = Launchable::program(
+                op.tokens.clone(),
+                op.working_dir.clone(),
+                Sandbox::default(),
+                con,
+            )?;
+            self.set_status(idx, OpStatus::Started);
+            launchable.execute(Some(w))?;
+            self.set_status(idx, OpStatus::Done);
+            count += 1;
+        }
+        self.finish();
+        Ok(count)
+    }
+    /// undo, from the last one, the operations which were done or
+    /// started, return the number of the ones which couldn't be
+    pub fn rollback(self) -> usize {
+        let failures = self.ops.iter()
+            .rev()
+            .filter(|op| !op.rollback())
+            .count();
+        self.finish();
+        failures
+    }
+}
+
+/// tell whether the journal file is the one of a running broot
+#[cfg(unix)]
+fn is_of_running_process(path: &Path) -> bool {
+    let pid = path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.parse::<libc::pid_t>().ok());
+    match pid {
+        Some(pid) if pid as u32 == process::id() => true,
+        Some(pid) => {
+            // EPERM means there's a process, which isn't ours
+            let alive = unsafe { libc::kill(pid, 0) == 0 };
+            alive || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+        }
+        None => false,
+    }
+}
+#[cfg(not(unix))]
+fn is_of_running_process(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map_or(false, |stem| stem == process::id().to_string())
+}
+
+#[cfg(test)]
+mod op_journal_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_prepare_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        let dst = dir.path().join("dst");
+        fs::create_dir(&src).unwrap();
+        let tokens = |exe: &str| vec![
+            exe.to_string(),
+            src.to_string_lossy().to_string(),
+            dst.to_string_lossy().to_string(),
+        ];
+        let mut op = JournalOp::new(tokens("cp"), None);
+        assert_eq!(op.target.as_ref(), Some(&dst));
+        // the partial copy of an interrupted copy is removed
+        op.status = OpStatus::Started;
+        fs::create_dir(&dst).unwrap();
+        assert!(op.prepare_resume().unwrap());
+        assert!(!dst.exists());
+        // a move whose source is gone is complete
+        let mut op = JournalOp::new(tokens("mv"), None);
+        op.status = OpStatus::Started;
+        fs::rename(&src, &dst).unwrap();
+        assert!(!op.prepare_resume().unwrap());
+        // an interrupted copy over an existing file can't be resumed
+        fs::remove_dir(&dst).unwrap();
+        fs::write(&src, "a").unwrap();
+        fs::write(&dst, "b").unwrap();
+        let mut op = JournalOp::new(tokens("cp"), None);
+        assert!(op.target.is_none());
+        op.status = OpStatus::Started;
+        assert!(op.prepare_resume().is_err());
+    }
+}
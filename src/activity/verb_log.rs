@@ -0,0 +1,158 @@
+use {
+    crate::{
+        app::{CmdResult, SelInfo},
+        errors::ProgramError,
+    },
+    chrono::{DateTime, Local},
+    std::{
+        fmt,
+        path::PathBuf,
+    },
+};
+
+/// the number of entries over which the oldest ones are dropped
+const MAX_ENTRIES: usize = 5_000;
+
+/// how the execution of a verb ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerbOutcome {
+    Done,
+    /// displayed but not executed, as broot was in dry run mode
+    DryRun,
+    Failed(String),
+}
+
+impl VerbOutcome {
+    pub fn from_result(res: &Result<CmdResult, ProgramError>) -> Self {
+        match res {
+            Ok(CmdResult::DisplayError(txt)) => Self::Failed(txt.clone()),
+            Ok(_) => Self::Done,
+            Err(e) => Self::Failed(e.to_string()),
+        }
+    }
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
+}
+
+impl fmt::Display for VerbOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Done => write!(f, "done"),
+            Self::DryRun => write!(f, "dry run"),
+            Self::Failed(txt) => write!(f, "failed: {txt}"),
+        }
+    }
+}
+
+/// the record of one verb execution
+#[derive(Debug, Clone)]
+pub struct VerbLogEntry {
+    pub time: DateTime<Local>,
+    /// the command, as it would be typed (eg `:rm` or `:mv ../dest`)
+    pub command: String,
+    /// the paths the verb was executed on
+    pub paths: Vec<PathBuf>,
+    pub outcome: VerbOutcome,
+}
+
+impl VerbLogEntry {
+    /// make an entry for a verb about to be executed, its
+    /// outcome being set when known
+    pub fn new(
+        command: String,
+        sel_info: SelInfo<'_>,
+    ) -> Self {
+        let paths = match sel_info {
+            SelInfo::None => Vec::new(),
+            SelInfo::One(sel) => vec![sel.path.to_path_buf()],
+            SelInfo::More(stage) => stage.paths().to_vec(),
+        };
+        Self {
+            time: Local::now(),
+            command,
+            paths,
+            outcome: VerbOutcome::Done,
+        }
+    }
+    /// the selection as a single string, paths being separated
+    /// with spaces
+    pub fn paths_string(&self) -> String {
+        self.paths
+            .iter()
+            .map(|p| p.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// the verbs executed during the session, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct VerbLog {
+    entries: Vec<VerbLogEntry>,
+}
+
+impl VerbLog {
+    pub fn add(&mut self, entry: VerbLogEntry) {
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+    pub fn entries(&self) -> &[VerbLogEntry] {
+        &self.entries
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// the export format: one tab separated `time outcome command paths`
+/// line per entry
+impl fmt::Display for VerbLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{}\t{}\t{}\t{}",
+                entry.time.to_rfc3339(),
+                entry.outcome,
+                entry.command,
+                entry.paths_string(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod verb_log_tests {
+
+    use {
+        super::*,
+        crate::app::{Selection, SelectionType},
+        std::path::Path,
+    };
+
+    #[test]
+    fn check_verb_log_export() {
+        let mut log = VerbLog::default();
+        let sel = Selection {
+            path: Path::new("/tmp/a.txt"),
+            line: 0,
+            line_range: None,
+            text_view: None,
+            stype: SelectionType::File,
+            is_exe: false,
+        };
+        let mut entry = VerbLogEntry::new(":rm".to_string(), SelInfo::One(sel));
+        entry.outcome = VerbOutcome::from_result(&Ok(CmdResult::error("denied")));
+        log.add(entry);
+        log.add(VerbLogEntry::new(":help".to_string(), SelInfo::None));
+        let export = log.to_string();
+        let lines: Vec<&str> = export.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("\tfailed: denied\t:rm\t/tmp/a.txt"));
+        assert!(lines[1].ends_with("\tdone\t:help\t"));
+    }
+}
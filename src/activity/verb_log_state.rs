@@ -0,0 +1,203 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        browser::BrowserState,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        path,
+        pattern::*,
+        task_sync::Dam,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::path::Path,
+    termimad::minimad::Alignment,
+};
+
+/// an application state listing the verbs executed during the
+/// session, most recent first
+pub struct VerbLogState {
+    list: FilteredList<VerbLogEntry>,
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+fn recent_first(log: &VerbLog) -> Vec<VerbLogEntry> {
+    log.entries().iter().rev().cloned().collect()
+}
+
+impl VerbLogState {
+    pub fn new(
+        log: &VerbLog,
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> VerbLogState {
+        VerbLogState {
+            list: FilteredList::new(recent_first(log), matches),
+            tree_options,
+            mode: initial_mode(con),
+        }
+    }
+}
+
+fn matches(entry: &VerbLogEntry, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&entry.command).is_some()
+        || pattern.score_of_string(&entry.paths_string()).is_some()
+}
+
+impl PanelState for VerbLogState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::VerbLog
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn selected_path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        None
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let date_time_format = self.tree_options.date_time_format;
+        let w_time = date_time_format.time_width();
+        let w_command = list.visible()
+            .map(|e| e.command.chars().count())
+            .max().unwrap_or(0)
+            .max("command".len());
+        let columns = [
+            ListColumn::new("time", w_time, Alignment::Right),
+            ListColumn::new("command", w_command, Alignment::Left),
+        ];
+        let styles = &disc.panel_skin.styles;
+        list.queue_table_head(w, disc, &columns, |cw| {
+            cw.queue_str(&styles.default, "selection")?;
+            Ok(())
+        })?;
+        self.list.queue_table_rows(w, disc, |cw, entry, row| {
+            let time = date_time_format.format_time(entry.time);
+            row.queue_cell(cw, &time, row.txt_style, w_time, Alignment::Right)?;
+            let command_style = if entry.outcome.is_failure() {
+                row.style(&styles.file_error)
+            } else {
+                row.txt_style.clone()
+            };
+            row.queue_matched_cell(cw, &entry.command, &command_style, w_command, Alignment::Left)?;
+            row.queue_matched(cw, &entry.paths_string(), row.txt_style)?;
+            if let VerbOutcome::Failed(txt) = &entry.outcome {
+                cw.queue_char(row.txt_style, ' ')?;
+                cw.queue_str(&command_style, txt)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        use Internal::*;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                let in_new_panel = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                // we show the directory of the first path the verb was
+                // executed on (or its closest parent if it's gone)
+                match self.list.selected().and_then(|e| e.paths.first()) {
+                    Some(entry_path) => {
+                        let dam = Dam::unlimited();
+                        CmdResult::from_optional_state(
+                            BrowserState::new(
+                                path::closest_dir(entry_path.parent().unwrap_or(entry_path)),
+                                self.tree_options.without_pattern(),
+                                screen,
+                                con,
+                                &dam,
+                            ),
+                            None,
+                            in_new_panel,
+                        )
+                    }
+                    None => CmdResult::error(tr!(error_no_selected_path)),
+                }
+            }
+            open_leave => CmdResult::PopStateAndReapply,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
@@ -1,39 +1,55 @@
 use {
     super::*,
     crate::{
+        activity::*,
         browser::BrowserState,
         cli::TriBool,
         command::{Command, Sequence},
         conf::Conf,
-        display::{Areas, Screen, W},
+        display::{invalidate_row_caches, Areas, PanelWidths, Screen, W},
         errors::ProgramError,
+        file_index,
         file_sum,
         git,
         kitty,
         launchable::Launchable,
         path::closest_dir,
+        pattern::InputPattern,
+        preview::PreviewState,
         skin::*,
-        stage::Stage,
-        syntactic::SyntaxTheme,
+        stage::{Stage, StageState},
+        tag::TagStore,
         task_sync::{Dam, Either},
-        verb::Internal,
+        tr,
+        verb::{Internal, PrefixSearchResult, VerbUsage},
     },
     crossbeam::channel::{
         Receiver,
         Sender,
         unbounded,
     },
-    crokey::crossterm::event::Event,
+    crokey::crossterm::event::{
+        Event,
+        KeyCode,
+        KeyEvent,
+        KeyModifiers,
+        MouseButton,
+        MouseEvent,
+        MouseEventKind,
+    },
     std::{
         io::Write,
         path::PathBuf,
-        str::FromStr,
         sync::{Arc, Mutex},
+        time::{Duration, Instant},
     },
     strict::NonEmptyVec,
-    termimad::EventSource,
+    termimad::{EventSource, EventSourceOptions, TimedEvent},
 };
 
+/// default number of columns by which :panel_grow and
+/// :panel_shrink change the width of a panel
+const PANEL_RESIZE_STEP: i32 = 5;
 
 /// The GUI
 pub struct App {
@@ -49,6 +65,9 @@ pub struct App {
     /// whether the app is in the (uncancellable) process of quitting
     quitting: bool,
 
+    /// whether the last command was a quit waiting for confirmation
+    quit_pending: bool,
+
     /// what must be done after having closed the TUI
     launch_at_end: Option<Launchable>,
 
@@ -71,6 +90,17 @@ pub struct App {
 
     /// counter incremented at every draw
     drawing_count: usize,
+
+    /// the widths of the panels, when changed by the user
+    panel_widths: PanelWidths,
+
+    /// whether a row, above the panels, is taken by the tab bars
+    /// (the screen given to the panels is then one row shorter)
+    tab_bar: bool,
+
+    /// the index of the panel whose left border is being dragged
+    /// with the mouse, and whether it has already been moved
+    dragged_panel_border: Option<(usize, bool)>,
 }
 
 impl App {
@@ -79,9 +109,17 @@ impl App {
         con: &AppContext,
     ) -> Result<App, ProgramError> {
         let screen = Screen::new(con)?;
-        let panel = Panel::new(
-            PanelId::from(0),
-            Box::new(
+        let state: Box<dyn PanelState> = match (con.launch_args.pager, &con.launch_args.root) {
+            (true, Some(path)) => Box::new(
+                PreviewState::new(
+                    path.clone(),
+                    InputPattern::none(),
+                    None,
+                    con.initial_tree_options.clone(),
+                    con,
+                )
+            ),
+            _ => Box::new(
                 BrowserState::new(
                     con.initial_root.clone(),
                     con.initial_tree_options.clone(),
@@ -90,7 +128,11 @@ impl App {
                     &Dam::unlimited(),
                 )?
             ),
-            Areas::create(&mut Vec::new(), 0, screen, false),
+        };
+        let panel = Panel::new(
+            PanelId::from(0),
+            state,
+            Areas::create(&mut Vec::new(), 0, screen, false, &PanelWidths::default()),
             con,
         );
         let (tx_seqs, rx_seqs) = unbounded::<Sequence>();
@@ -99,6 +141,7 @@ impl App {
             active_panel_idx: 0,
             panels: panel.into(),
             quitting: false,
+            quit_pending: false,
             launch_at_end: None,
             created_panels_count: 1,
             preview_panel: None,
@@ -107,6 +150,9 @@ impl App {
             tx_seqs,
             rx_seqs,
             drawing_count: 0,
+            panel_widths: PanelWidths::default(),
+            tab_bar: false,
+            dragged_panel_border: None,
         })
     }
 
@@ -170,6 +216,7 @@ impl App {
                 self.panels.as_mut_slice(),
                 self.screen,
                 self.preview_panel.is_some(),
+                &self.panel_widths,
             );
             self.active_panel_idx = self
                 .panels
@@ -205,14 +252,22 @@ impl App {
         for (idx, panel) in self.panels.as_mut_slice().iter_mut().enumerate() {
             let active = idx == self.active_panel_idx;
             let panel_skin = if active { &skin.focused } else { &skin.unfocused };
+            let mut state_area = panel.areas.state.clone();
+            if self.screen.zen {
+                // the state covers the status line. The input line is kept
+                // empty as we don't want to draw the bottom right char
+                state_area.height += 1;
+            }
             let disc = DisplayContext {
                 count: self.drawing_count,
                 active,
                 screen: self.screen,
                 panel_skin,
-                state_area: panel.areas.state.clone(),
+                state_area,
                 app_state,
                 con,
+                zen: self.screen.zen,
+                tab_bar: self.tab_bar,
             };
             time!(
                 "display panel",
@@ -259,6 +314,25 @@ impl App {
         use CmdResult::*;
         let mut error: Option<String> = None;
         let is_input_invocation = cmd.is_verb_invocated_from_input();
+        // a quit waiting for confirmation is confirmed only if the next
+        // executed command is a quit too (the edition of the input doesn't count)
+        let quit_confirmed = if matches!(cmd, Command::None | Command::VerbEdit(_)) {
+            self.quit_pending
+        } else {
+            std::mem::take(&mut self.quit_pending)
+        };
+        // the operations denied for lack of permission can be escalated
+        // only by the command following the denial, so that what's run
+        // is what the user was just shown
+        if !is_escalation(&cmd, con) && !matches!(
+            cmd,
+            Command::None
+                | Command::VerbEdit(_)
+                | Command::PatternEdit { .. }
+                | Command::MouseMove(..)
+        ) {
+            app_state.pending_escalation.clear();
+        }
         let app_cmd_context = AppCmdContext {
             panel_skin,
             preview_panel: self.preview_panel,
@@ -330,6 +404,12 @@ impl App {
             DisplayError(txt) => {
                 error = Some(txt);
             }
+            DisplayMessage(md) => {
+                if is_input_invocation {
+                    self.mut_panel().clear_input_invocation(con);
+                }
+                self.mut_panel().set_message(md);
+            }
             ExecuteSequence { sequence } => {
                 self.tx_seqs.send(sequence).unwrap();
             }
@@ -418,13 +498,76 @@ impl App {
                             }
                         }
                     }
+                    Internal::toggle_zen => {
+                        self.screen.zen = !self.screen.zen;
+                        // the trees are rebuilt to fill the new height
+                        let screen = self.screen;
+                        for panel in self.panels.as_mut_slice() {
+                            if panel.state().get_type() == PanelStateType::Tree {
+                                panel.mut_state().refresh(screen, con);
+                            }
+                        }
+                        if is_input_invocation {
+                            self.mut_panel().clear_input_invocation(con);
+                        }
+                    }
+                    Internal::new_tab => {
+                        let dir = self.state().selected_path().map(closest_dir);
+                        if let Some(dir) = dir {
+                            match BrowserState::new(
+                                dir,
+                                self.state().tree_options().without_pattern(),
+                                self.screen,
+                                con,
+                                &Dam::unlimited(),
+                            ) {
+                                Ok(new_state) => {
+                                    if is_input_invocation {
+                                        self.mut_panel().clear_input_invocation(con);
+                                    }
+                                    self.mut_panel().add_tab(Box::new(new_state));
+                                }
+                                Err(e) => {
+                                    error = Some(e.to_string());
+                                }
+                            }
+                        }
+                    }
+                    Internal::close_tab | Internal::next_tab | Internal::previous_tab => {
+                        if is_input_invocation {
+                            self.mut_panel().clear_input_invocation(con);
+                        }
+                        let done = match internal {
+                            Internal::close_tab => self.mut_panel().close_tab(),
+                            Internal::next_tab => self.mut_panel().move_tab(1),
+                            _ => self.mut_panel().move_tab(-1),
+                        };
+                        if done {
+                            self.on_tab_change(panel_skin, app_state, con);
+                        } else {
+                            error = Some("This panel has no other tab".to_string());
+                        }
+                    }
+                    Internal::panel_grow | Internal::panel_shrink => {
+                        let delta = cmd
+                            .as_verb_invocation()
+                            .and_then(|vi| vi.args.as_ref())
+                            .and_then(|arg| arg.parse::<i32>().ok())
+                            .unwrap_or(PANEL_RESIZE_STEP);
+                        let delta = if internal == Internal::panel_grow { delta } else { -delta };
+                        if let Err(s) = self.resize_active_panel(delta) {
+                            error = Some(s);
+                        } else if is_input_invocation {
+                            self.mut_panel().clear_input_invocation(con);
+                        }
+                    }
                     Internal::set_syntax_theme => {
                         let arg = cmd
                             .as_verb_invocation()
                             .and_then(|vi| vi.args.as_ref());
                         match arg {
                             Some(arg) => {
-                                match SyntaxTheme::from_str(arg) {
+                                match con.syntax_theme_named(arg) {
                                     Ok(theme) => {
                                         con.syntax_theme = Some(theme);
                                         self.update_preview(con, true);
@@ -439,6 +582,18 @@ impl App {
                             }
                         }
                     }
+                    Internal::preview_theme => {
+                        let themes = con.syntax_themes();
+                        let current = con.syntax_theme.clone().unwrap_or_default();
+                        let idx = themes.iter().position(|t| *t == current).map_or(0, |i| i + 1);
+                        let theme = themes[idx % themes.len()].clone();
+                        info!("switching to syntax theme {:?}", theme.name());
+                        con.syntax_theme = Some(theme);
+                        self.update_preview(con, true);
+                        if is_input_invocation {
+                            self.mut_panel().clear_input_invocation(con);
+                        }
+                    }
                     _ => {
                         info!("unhandled propagated internal. cmd={:?}", &cmd);
                     }
@@ -454,15 +609,23 @@ impl App {
                 self.quitting = true;
             }
             NewPanel {
-                state,
+                mut state,
                 purpose,
                 direction,
             } => {
+                if let Some(guard) = self.state().root_guard() {
+                    state.set_root_guard(Arc::clone(guard));
+                }
+                send_state_event(app_state, state.as_ref());
                 if let Err(s) = self.new_panel(state, purpose, direction, is_input_invocation, con) {
                     error = Some(s);
                 }
             }
-            NewState { state, message } => {
+            NewState { mut state, message } => {
+                if let Some(guard) = self.state().root_guard() {
+                    state.set_root_guard(Arc::clone(guard));
+                }
+                send_state_event(app_state, state.as_ref());
                 self.mut_panel().clear_input();
                 self.mut_panel().push_state(state);
                 if let Some(md) = message {
@@ -478,7 +641,7 @@ impl App {
                 if self.remove_state() {
                     self.mut_state().refresh(app_cmd_context.screen, con);
                     self.mut_panel().refresh_input_status(app_state, &app_cmd_context);
-                } else if con.quit_on_last_cancel {
+                } else if con.quit_on_last_cancel || con.launch_args.pager {
                     self.quitting = true;
                 }
             }
@@ -495,12 +658,28 @@ impl App {
                         con,
                     };
                     self.mut_panel().apply_command(w, &cmd, app_state, &app_cmd_context)?;
-                } else if con.quit_on_last_cancel {
+                } else if con.quit_on_last_cancel || con.launch_args.pager {
                     self.quitting = true;
                 }
             }
+            PopStateAndExecute { sequence } => {
+                if self.remove_state() {
+                    self.tx_seqs.send(sequence).unwrap();
+                }
+            }
             Quit => {
-                self.quitting = true;
+                match self.quit_prompt(app_state).filter(|_| con.confirm_quit && !quit_confirmed) {
+                    Some(prompt) => {
+                        if is_input_invocation {
+                            self.mut_panel().clear_input_invocation(con);
+                        }
+                        self.quit_pending = true;
+                        self.mut_panel().set_message(prompt);
+                    }
+                    None => {
+                        self.quitting = true;
+                    }
+                }
             }
             RefreshState { clear_cache } => {
                 if is_input_invocation {
@@ -520,6 +699,8 @@ impl App {
         }
 
         app_state.other_panel_path = self.get_other_panel_path();
+        app_state.panel_count = self.panels.len().get();
+        app_state.filter = self.state().tree_options().pattern.raw;
         if let Some(path) = self.state().tree_root() {
             app_state.root = path.to_path_buf();
         }
@@ -532,10 +713,240 @@ impl App {
         }
 
         self.update_preview(con, false);
+        self.update_tab_bar(con);
 
         Ok(())
     }
 
+    /// refresh the state of the tab which just became the current one
+    /// of the active panel
+    fn on_tab_change(
+        &mut self,
+        panel_skin: &PanelSkin,
+        app_state: &AppState,
+        con: &AppContext,
+    ) {
+        let screen = self.screen;
+        self.mut_panel().mut_state().refresh(screen, con);
+        let app_cmd_context = AppCmdContext {
+            panel_skin,
+            preview_panel: self.preview_panel,
+            stage_panel: self.stage_panel,
+            screen,
+            con,
+        };
+        self.mut_panel().refresh_input_status(app_state, &app_cmd_context);
+    }
+
+    /// the panels, their trees and the stage, as saved on quit
+    fn session(&self, app_state: &AppState) -> Session {
+        let mut panels = Vec::new();
+        let mut active_panel_idx = 0;
+        for (idx, panel) in self.panels.iter().enumerate() {
+            let content = match panel.state().get_type() {
+                PanelStateType::Preview => match panel.state().selected_path() {
+                    Some(path) => SessionPanelContent::Preview { path: path.to_path_buf() },
+                    None => continue,
+                },
+                PanelStateType::Stage => SessionPanelContent::Stage,
+                _ => {
+                    let mut tabs = Vec::new();
+                    let mut current_tab = 0;
+                    for (tab_idx, stack) in panel.tab_stacks().into_iter().enumerate() {
+                        // the tree is the last one of the stack, other states
+                        // (eg help) aren't saved
+                        let tree = stack.iter()
+                            .rev()
+                            .find_map(|state| state.tree_root().map(|root| (state, root)));
+                        if let Some((state, root)) = tree {
+                            if tab_idx == panel.tab_idx() {
+                                current_tab = tabs.len();
+                            }
+                            tabs.push(SessionTree {
+                                root: root.to_path_buf(),
+                                pattern: state.get_starting_input(),
+                                options: (&state.tree_options()).into(),
+                            });
+                        }
+                    }
+                    if tabs.is_empty() {
+                        continue;
+                    }
+                    SessionPanelContent::Trees { tabs, current_tab }
+                }
+            };
+            if idx == self.active_panel_idx {
+                active_panel_idx = panels.len();
+            }
+            panels.push(SessionPanel {
+                width: panel.areas.state.width,
+                content,
+            });
+        }
+        Session {
+            screen_width: self.screen.width,
+            panels,
+            active_panel_idx,
+            staged: app_state.stage.paths().to_vec(),
+        }
+    }
+
+    /// save the session, if the configuration asks for it
+    fn save_session(&self, app_state: &AppState, con: &AppContext) {
+        if con.save_session {
+            if let Err(e) = self.session(app_state).save() {
+                warn!("failed to save the session: {}", e);
+            }
+        }
+    }
+
+    /// build the state of a saved tree, returning None if the
+    /// tree can't be built anymore (eg its root was removed)
+    fn session_tree_state(
+        &self,
+        tree: &SessionTree,
+        tags: &TagStore,
+        con: &AppContext,
+    ) -> Option<Box<dyn PanelState>> {
+        let mut options = con.initial_tree_options.without_pattern();
+        tree.options.apply_to(&mut options);
+        if let Command::PatternEdit { raw, expr } = Command::from_raw(tree.pattern.clone(), false) {
+            options.pattern = InputPattern::new(raw, &expr, tags, con)
+                .unwrap_or_else(|_| InputPattern::none());
+        }
+        match BrowserState::new(tree.root.clone(), options, self.screen, con, &Dam::unlimited()) {
+            Ok(state) => Some(Box::new(state)),
+            Err(e) => {
+                warn!("can't restore tree of {:?}: {:?}", &tree.root, e);
+                None
+            }
+        }
+    }
+
+    /// replace the panels with the ones of a saved session, and
+    /// restore the stage
+    fn restore_session(
+        &mut self,
+        session: Session,
+        app_state: &mut AppState,
+        con: &AppContext,
+    ) {
+        for path in session.staged {
+            if path.exists() {
+                app_state.stage.add(path);
+            }
+        }
+        let mut restored_count = 0;
+        let mut preview = None;
+        let mut with_stage = false;
+        for saved_panel in &session.panels {
+            let (tabs, current_tab) = match &saved_panel.content {
+                SessionPanelContent::Trees { tabs, current_tab } => (tabs, *current_tab),
+                SessionPanelContent::Preview { path } => {
+                    preview = Some(path.clone());
+                    continue;
+                }
+                SessionPanelContent::Stage => {
+                    with_stage = true;
+                    continue;
+                }
+            };
+            let mut states = tabs.iter()
+                .filter_map(|tree| self.session_tree_state(tree, &app_state.tags, con))
+                .collect::<Vec<_>>()
+                .into_iter();
+            let first_state = match states.next() {
+                Some(state) => state,
+                None => continue,
+            };
+            if restored_count == 0 {
+                // the first restored panel replaces the initial one
+                let areas = Areas::create(&mut Vec::new(), 0, self.screen, false, &PanelWidths::default());
+                let panel = Panel::new(self.created_panels_count.into(), first_state, areas, con);
+                self.created_panels_count += 1;
+                self.panels = panel.into();
+                self.active_panel_idx = 0;
+            } else if let Err(e) = self.new_panel(first_state, PanelPurpose::None, HDir::Right, false, con) {
+                warn!("can't restore panel: {}", e);
+                continue;
+            }
+            for state in states {
+                self.mut_panel().add_tab(state);
+            }
+            self.mut_panel().select_tab(current_tab);
+            restored_count += 1;
+        }
+        if restored_count == 0 {
+            self.mut_panel().set_message("No saved tree could be restored");
+            return;
+        }
+        if with_stage && !app_state.stage.is_empty() {
+            let state = StageState::new(app_state, self.state().tree_options().without_pattern(), con);
+            if let Err(e) = self.new_panel(Box::new(state), PanelPurpose::None, HDir::Right, false, con) {
+                warn!("can't restore stage panel: {}", e);
+            }
+        }
+        if let Some(path) = preview.filter(|path| path.is_file()) {
+            let state = PreviewState::new(
+                path,
+                InputPattern::none(),
+                None,
+                self.state().tree_options().without_pattern(),
+                con,
+            );
+            if let Err(e) = self.new_panel(Box::new(state), PanelPurpose::Preview, HDir::Right, false, con) {
+                warn!("can't restore preview panel: {}", e);
+            }
+        }
+        if self.panels.len().get() == session.panels.len() {
+            let widths: Vec<u16> = session.panels.iter().map(|p| p.width).collect();
+            self.panel_widths.set(&widths, session.screen_width);
+        }
+        self.active_panel_idx = session.active_panel_idx.min(self.panels.len().get() - 1);
+        self.update_tab_bar(con);
+        self.resize_panels(con);
+    }
+
+    /// show the tab bar row when a panel has several tabs, and hide it
+    /// when none has, resizing the panels accordingly
+    fn update_tab_bar(&mut self, con: &AppContext) {
+        let tab_bar = self.panels.iter().any(|p| p.tabs_count() > 1);
+        if tab_bar != self.tab_bar {
+            if tab_bar {
+                self.tab_bar = true;
+                self.reserve_tab_bar_row();
+            } else {
+                self.tab_bar = false;
+                self.screen.top -= 1;
+                self.screen.height += 1;
+            }
+            self.resize_panels(con);
+        }
+    }
+
+    /// take the first row of the screen for the tab bar, if needed
+    fn reserve_tab_bar_row(&mut self) {
+        if self.tab_bar {
+            self.screen.top += 1;
+            self.screen.height = self.screen.height.saturating_sub(1);
+        }
+    }
+
+    /// recompute the areas of the panels and refresh their states,
+    /// after a change of the screen's dimensions
+    fn resize_panels(&mut self, con: &AppContext) {
+        invalidate_row_caches();
+        Areas::resize_all(
+            self.panels.as_mut_slice(),
+            self.screen,
+            self.preview_panel.is_some(),
+            &self.panel_widths,
+        );
+        for panel in &mut self.panels {
+            panel.mut_state().refresh(self.screen, con);
+        }
+    }
+
     /// update the state of the preview, if there's some
     fn update_preview(&mut self, con: &AppContext, refresh: bool) {
         let preview_idx = self.preview_panel.and_then(|id| self.panel_id_to_idx(id));
@@ -552,8 +963,76 @@ impl App {
 
     /// get the index of the panel at x
     fn clicked_panel_index(&self, x: u16, _y: u16) -> usize {
-        let len = self.panels.len().get();
-        (len * x as usize) / (self.screen.width as usize + 1)
+        // panels may have different widths
+        self.panels.iter()
+            .position(|p| x < p.areas.state.left + p.areas.state.width)
+            .unwrap_or(self.panels.len().get() - 1)
+    }
+
+    /// return the index of the panel whose left border is at x
+    fn panel_border_at(&self, x: u16) -> Option<usize> {
+        (1..self.panels.len().get()).find(|&idx| {
+            let left = self.panels[idx].areas.state.left;
+            x == left || x + 1 == left
+        })
+    }
+
+    /// move the left border of the panel at idx by dx columns,
+    /// and remember the new widths for the rest of the session
+    fn move_panel_border(&mut self, idx: usize, dx: i32) {
+        let widths = Areas::widths_moving_border(self.panels.as_mut_slice(), idx, dx);
+        self.panel_widths.set(&widths, self.screen.width);
+        Areas::resize_all(
+            self.panels.as_mut_slice(),
+            self.screen,
+            self.preview_panel.is_some(),
+            &self.panel_widths,
+        );
+    }
+
+    /// handle the mouse events related to the dragging of a panel
+    /// border, return true when the event was consumed
+    fn handle_panel_border_drag(&mut self, event: Event) -> bool {
+        let (kind, column) = match event {
+            Event::Mouse(MouseEvent { kind, column, .. }) => (kind, column),
+            _ => return false,
+        };
+        match kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.dragged_panel_border = self.panel_border_at(column)
+                    .map(|idx| (idx, false));
+                false
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some((idx, _)) = self.dragged_panel_border {
+                    let left = self.panels[idx].areas.state.left;
+                    self.move_panel_border(idx, column as i32 - left as i32);
+                    self.dragged_panel_border = Some((idx, true));
+                    true
+                } else {
+                    false
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                // the end of a drag isn't a click
+                matches!(self.dragged_panel_border.take(), Some((_, true)))
+            }
+            _ => false,
+        }
+    }
+
+    /// widen the active panel (or narrow it when delta is negative),
+    /// taking the space from its neighbour
+    fn resize_active_panel(&mut self, delta: i32) -> Result<(), String> {
+        if self.panels.has_len(1) {
+            return Err("There's only one panel".to_string());
+        }
+        if self.active_panel_idx + 1 == self.panels.len().get() {
+            self.move_panel_border(self.active_panel_idx, -delta);
+        } else {
+            self.move_panel_border(self.active_panel_idx + 1, delta);
+        }
+        Ok(())
     }
 
     /// handle CmdResult::NewPanel
@@ -592,6 +1071,7 @@ impl App {
             insertion_idx,
             self.screen,
             with_preview,
+            &self.panel_widths,
         );
         let panel_id = self.created_panels_count.into();
         match state.get_type() {
@@ -619,7 +1099,7 @@ impl App {
         skin: &AppSkin,
         dam: &mut Dam,
         app_state: &mut AppState,
-        con: &AppContext,
+        con: &mut AppContext,
     ) -> Result<(), ProgramError> {
         while self.has_pending_task() && !dam.has_event() {
             let error = self.do_pending_task(app_state, con, dam).err();
@@ -643,6 +1123,10 @@ impl App {
                 return Ok(()); // breaking pending tasks chain on first error/interruption
             }
         }
+        if let Some(cmd) = self.mut_state().take_pending_command() {
+            self.apply_command(w, cmd, &skin.focused, app_state, con)?;
+            self.display_panels(w, skin, app_state, con)?;
+        }
         Ok(())
     }
 
@@ -671,10 +1155,98 @@ impl App {
         Ok(())
     }
 
+    /// the question to ask before quitting when some work would be
+    /// lost, if any
+    fn quit_prompt(&self, app_state: &AppState) -> Option<String> {
+        let staged = app_state.stage.len();
+        if staged > 0 {
+            Some(tr!(prompt_quit_staged, count = staged))
+        } else if file_index::is_indexing() || self.panels.iter().any(|p| p.has_pending_task()) {
+            Some(tr!(prompt_quit_tasks).to_string())
+        } else {
+            None
+        }
+    }
+
     fn has_pending_task(&mut self) -> bool {
         self.panels.iter().any(|p| p.has_pending_task())
     }
 
+    /// return the shortest refresh period asked by the panel states,
+    /// if any
+    fn refresh_period(&self) -> Option<Duration> {
+        self.panels.iter()
+            .filter_map(|p| p.state().refresh_period())
+            .min()
+    }
+
+    /// execute the steps of a replay script, waiting for the
+    /// completion of all tasks after each one, then return the
+    /// printing of the final state
+    fn replay(
+        &mut self,
+        w: &mut W,
+        steps: &[ReplayStep],
+        skin: &AppSkin,
+        app_state: &mut AppState,
+        con: &mut AppContext,
+    ) -> Result<Option<Launchable>, ProgramError> {
+        // a dam receiving no event never interrupts the tasks
+        let mut dam = Dam::unlimited();
+        for step in steps {
+            self.display_panels(w, skin, app_state, con)?;
+            self.do_pending_tasks(w, skin, &mut dam, app_state, con)?;
+            debug!("replay step: {:?}", step);
+            let keys = match step {
+                ReplayStep::Cmd(raw) => {
+                    for (input, cmd) in Sequence::new_local(raw.to_string()).parse(con)? {
+                        self.mut_panel().set_input_content(&input);
+                        self.apply_command(w, cmd, &skin.focused, app_state, con)?;
+                        if self.quitting {
+                            break;
+                        }
+                    }
+                    Vec::new()
+                }
+                ReplayStep::Key(key) => crokey::parse(key).into_iter().collect(),
+                ReplayStep::Input(chars) => chars
+                    .chars()
+                    .map(|c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+                    .collect(),
+            };
+            for key in keys {
+                if self.quitting {
+                    break;
+                }
+                let event = TimedEvent::new(Event::Key(key));
+                let cmd = self.mut_panel().add_event(w, event, app_state, con)?;
+                self.apply_command(w, cmd, &skin.focused, app_state, con)?;
+            }
+            if self.quitting {
+                return Ok(self.launch_at_end.take());
+            }
+        }
+        self.display_panels(w, skin, app_state, con)?;
+        self.do_pending_tasks(w, skin, &mut dam, app_state, con)?;
+        let dump = ReplayDump {
+            root: app_state.root.to_string_lossy().to_string(),
+            active_panel: self.active_panel_idx,
+            panels: self.panels.iter()
+                .map(|panel| ReplayPanelDump {
+                    state: panel.state().get_type().name(),
+                    selection: panel.state().selected_path()
+                        .map(|p| p.to_string_lossy().to_string()),
+                    input: panel.get_input_content(),
+                })
+                .collect(),
+            staged: app_state.stage.paths().iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+        };
+        let json = serde_json::to_string_pretty(&dump).unwrap(); // can't fail with strings
+        Ok(Some(Launchable::printer(json)))
+    }
+
     /// This is the main loop of the application
     pub fn run(
         mut self,
@@ -691,18 +1263,48 @@ impl App {
 
         // we listen for events in a separate thread so that we can go on listening
         // when a long search is running, and interrupt it if needed
-        let event_source = EventSource::new()?;
+        // mouse moves are only listened to for the directory summaries,
+        // as every event interrupts the background computations
+        let event_source = EventSource::with_options(EventSourceOptions {
+            discard_mouse_move: !con.dir_info_on_hover,
+            discard_mouse_drag: false,
+        })?;
         let rx_events = event_source.receiver();
         let mut dam = Dam::from(rx_events);
-        let skin = AppSkin::new(conf, con.launch_args.color == TriBool::No);
+        let skin = AppSkin::new(conf, &con.glyphs, con.launch_args.color == TriBool::No);
         let mut app_state = AppState {
             stage: Stage::default(),
+            marks: Stage::default(),
             root: con.initial_root.clone(),
             other_panel_path: None,
+            panel_count: 1,
+            filter: String::new(),
+            tags: TagStore::load(),
+            verb_log: VerbLog::default(),
+            verb_usage: VerbUsage::load(),
+            dry_run: con.launch_args.dry_run,
+            pending_escalation: Vec::new(),
+            events: None,
+            read_only_roots: Vec::new(),
         };
+        #[cfg(unix)]
+        {
+            app_state.events = con.launch_args.events_fd.map(EventStream::from_fd);
+        }
+
+        if con.launch_args.resume {
+            match Session::load() {
+                Some(session) => self.restore_session(session, &mut app_state, con),
+                None => self.mut_panel().set_message("No saved session to resume"),
+            }
+        }
 
         self.screen.clear_bottom_right_char(w, &skin.focused)?;
 
+        if con.file_index {
+            crate::file_index::index_in_background(app_state.root.clone());
+        }
+
         if let Some(raw_sequence) = &con.launch_args.cmd {
             self.tx_seqs
                 .send(Sequence::new_local(raw_sequence.to_string()))
@@ -723,28 +1325,87 @@ impl App {
             })
             .transpose()?;
 
+        if let Some(path) = con.launch_args.replay.clone() {
+            let steps = read_replay(&path)?;
+            return self.replay(w, &steps, &skin, &mut app_state, con);
+        }
+
+        if let Some(journal) = OpJournal::find_interrupted() {
+            self.mut_panel().set_message(format!(
+                "{}. Type *:resume_operation*, *:rollback_operation*, or *:discard_operation*",
+                journal.summary(),
+            ));
+        }
+
+        #[cfg(unix)]
+        if let Some(mount) = con.filesystems_usage_alert
+            .and_then(|_| crate::filesystems::mount_of(&app_state.root))
+            .filter(|mount| crate::filesystems::is_nearly_full(mount, con.filesystems_usage_alert))
+        {
+            let share = crate::filesystems::used_share(&mount).unwrap_or_default();
+            self.mut_panel().set_error(format!(
+                "The filesystem of {} is {:.0}% full",
+                mount.info.mount_point.to_string_lossy(),
+                100.0 * share,
+            ));
+        }
+
+        let mut throttle = RedrawThrottle::default();
         loop {
-            if !self.quitting {
+            if !self.quitting && !throttle.must_skip(&mut dam) {
+                let start = Instant::now();
                 self.display_panels(w, &skin, &app_state, con)?;
+                throttle.drawn(start);
+                if let Some(events) = app_state.events.as_mut() {
+                    if let Some(path) = self.state().selected_path() {
+                        events.selection(self.active_panel_idx, path);
+                    }
+                }
                 time!(
                     Info,
                     "pending_tasks",
                     self.do_pending_tasks(w, &skin, &mut dam, &mut app_state, con)?,
                 );
             }
+            let next = match self.refresh_period() {
+                Some(period) => dam.next_before(&self.rx_seqs, period),
+                None => Some(dam.next(&self.rx_seqs)),
+            };
+            let next = match next {
+                Some(next) => next,
+                None => {
+                    // nothing happened during the refresh period, we loop
+                    // so that states can check for changes
+                    continue;
+                }
+            };
             #[allow(unused_mut)]
-            match dam.next(&self.rx_seqs) {
+            match next {
                 Either::First(Some(event)) => {
                     info!("event: {:?}", &event);
                     let mut handled = false;
 
                     // app level handling
-                    if let Some((x, y)) = event.as_click() {
-                        if self.clicked_panel_index(x, y) != self.active_panel_idx {
+                    if self.handle_panel_border_drag(event.event) {
+                        handled = true;
+                    } else if let Some((x, y)) = event.as_click() {
+                        if self.tab_bar && y + 1 == self.screen.top {
+                            // click in the tab bar
+                            self.active_panel_idx = self.clicked_panel_index(x, y);
+                            let tab_idx = self.panel().tab_at(x);
+                            if tab_idx.map_or(false, |idx| self.mut_panel().select_tab(idx)) {
+                                self.on_tab_change(&skin.focused, &app_state, con);
+                            }
+                            handled = true;
+                        } else if self.clicked_panel_index(x, y) != self.active_panel_idx {
                             // panel activation click
                             self.active_panel_idx = self.clicked_panel_index(x, y);
                             handled = true;
                         }
+                    } else if let Some((x, y)) = as_middle_click(&event.event) {
+                        // the panel under the cursor is activated, then handles
+                        // the click on its own rows
+                        self.active_panel_idx = self.clicked_panel_index(x, y);
                     } else if let Event::Resize(mut width, mut height) = event.event {
                         // I don't know why but Crossterm seems to always report an
                         // understimated size on Windows
@@ -754,14 +1415,8 @@ impl App {
                             height += 1;
                         }
                         self.screen.set_terminal_size(width, height, con);
-                        Areas::resize_all(
-                            self.panels.as_mut_slice(),
-                            self.screen,
-                            self.preview_panel.is_some(),
-                        );
-                        for panel in &mut self.panels {
-                            panel.mut_state().refresh(self.screen, con);
-                        }
+                        self.reserve_tab_bar_row();
+                        self.resize_panels(con);
                         handled = true;
                     }
 
@@ -787,6 +1442,7 @@ impl App {
                         self.apply_command(w, arg_cmd, &skin.focused, &mut app_state, con)?;
                         if self.quitting {
                             // is that a 100% safe way of quitting ?
+                            self.save_session(&app_state, con);
                             return Ok(self.launch_at_end.take());
                         } else {
                             self.display_panels(w, &skin, &app_state, con)?;
@@ -803,10 +1459,50 @@ impl App {
             }
         }
 
+        self.save_session(&app_state, con);
         Ok(self.launch_at_end.take())
     }
 }
 
+/// tell the listener of the event stream, if any, that a state
+/// was created
+fn send_state_event(app_state: &mut AppState, state: &dyn PanelState) {
+    if let Some(events) = app_state.events.as_mut() {
+        events.send(&ActivityEvent::State {
+            state: state.get_type().name(),
+            path: state.selected_path(),
+        });
+    }
+}
+
+/// return the position of the event if it's a middle click
+fn as_middle_click(event: &Event) -> Option<(u16, u16)> {
+    match event {
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Up(MouseButton::Middle),
+            column,
+            row,
+            ..
+        }) => Some((*column, *row)),
+        _ => None,
+    }
+}
+
+/// tell whether the command runs the `:escalate` internal
+fn is_escalation(cmd: &Command, con: &AppContext) -> bool {
+    match cmd {
+        Command::Internal { internal, .. } => *internal == Internal::escalate,
+        Command::VerbTrigger { index, .. } => {
+            con.verb_store.verbs[*index].is_internal(Internal::escalate)
+        }
+        Command::VerbInvocate(invocation) => matches!(
+            con.verb_store.search_prefix(&invocation.name),
+            PrefixSearchResult::Match(_, verb) if verb.is_internal(Internal::escalate)
+        ),
+        _ => false,
+    }
+}
+
 /// clear the file sizes and git stats cache.
 /// This should be done on Refresh actions and after any external
 /// command.
@@ -816,3 +1512,25 @@ fn clear_caches() {
     #[cfg(unix)]
     crate::filesystems::clear_cache();
 }
+
+#[cfg(test)]
+mod app_tests {
+
+    use super::*;
+
+    #[test]
+    fn check_middle_click_detection() {
+        let mouse_event = |kind| Event::Mouse(MouseEvent {
+            kind,
+            column: 30,
+            row: 4,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            as_middle_click(&mouse_event(MouseEventKind::Up(MouseButton::Middle))),
+            Some((30, 4)),
+        );
+        assert_eq!(as_middle_click(&mouse_event(MouseEventKind::Up(MouseButton::Left))), None);
+        assert_eq!(as_middle_click(&mouse_event(MouseEventKind::Down(MouseButton::Middle))), None);
+    }
+}
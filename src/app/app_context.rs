@@ -2,23 +2,37 @@ use {
     super::*,
     crate::{
         cli::{Args, TriBool},
+        command::ClickBindings,
         conf::Conf,
         content_search,
+        display::{parse_fs_cols, FsCol, TermCapabilities, TitleTemplate},
         errors::*,
         file_sum,
+        i18n::init_catalog,
         icon::*,
-        path::SpecialPath,
-        pattern::SearchModeMap,
-        skin::ExtColorMap,
-        syntactic::SyntaxTheme,
+        lint::LintProbe,
+        opener_rules::OpenerRule,
+        quick_roots::{self, QuickRoot},
+        versions::BackupLocation,
+        path::{normalize_path, SpecialPath},
+        pattern::{PanelSearchModes, SearchModeMap},
+        preview::PreviewModeRule,
+        skin::{ExtColorMap, Glyphs},
+        syntactic::*,
         tree::TreeOptions,
-        verb::VerbStore,
+        verb::{Internal, Verb, VerbStore},
+        watch::FsWatcher,
     },
     std::{
+        collections::BTreeMap,
         convert::{TryFrom, TryInto},
         io,
         path::{Path, PathBuf},
+        str::FromStr,
+        sync::Arc,
+        time::Duration,
     },
+    syntect::highlighting::Theme,
 };
 
 /// The container that can be passed around to provide the configuration things
@@ -44,24 +58,55 @@ pub struct AppContext {
     /// the paths for which there's a special behavior to follow (comes from conf)
     pub special_paths: Vec<SpecialPath>,
 
+    /// the preview modes forced for some paths (comes from conf)
+    pub preview_modes: Vec<PreviewModeRule>,
+
+    /// the commands checking files for the lint column (comes from conf)
+    pub lint_probes: Vec<LintProbe>,
+
+    /// the programs opening files depending on their type, before
+    /// the system opener (comes from conf)
+    pub openers: Vec<OpenerRule>,
+
+    /// where to look for versions of files kept by backup tools
+    pub backup_locations: Vec<BackupLocation>,
+
+    /// the locations proposed by :quick_roots (from conf, or the XDG user dirs)
+    pub quick_roots: Vec<QuickRoot>,
+
     /// the map between search prefixes and the search mode to apply
     pub search_modes: SearchModeMap,
 
+    /// the search modes of the patterns without mode key, depending
+    /// on the purpose of the panel, when they're not the default one
+    pub panel_search_modes: PanelSearchModes,
+
+    /// the verbs to execute on mouse clicks (comes from conf)
+    pub click_bindings: ClickBindings,
+
     /// whether to show a triangle left to selected lines
     pub show_selection_mark: bool,
 
     /// mapping from file extension to colors (comes from conf)
     pub ext_colors: ExtColorMap,
 
+    /// the characters drawing borders, scrollbars and branches
+    pub glyphs: Glyphs,
+
     /// the syntect theme to use for text files previewing
     pub syntax_theme: Option<SyntaxTheme>,
 
+    /// the syntect themes loaded from the syntax_themes_dir of the conf,
+    /// keyed by name
+    pub custom_syntax_themes: BTreeMap<String, Theme>,
+
     /// precomputed status to display in standard cases
     /// (ie when no verb is involved)
     pub standard_status: StandardStatus,
 
-    /// whether we can use 24 bits colors for previewed images
-    pub true_colors: bool,
+    /// what the terminal supports (24 bits colors, graphics,
+    /// mouse, etc.), probed at startup or forced in conf
+    pub term_caps: TermCapabilities,
 
     /// map extensions to icons, icon set chosen based on config
     /// Send, Sync safely because once created, everything is immutable
@@ -70,9 +115,6 @@ pub struct AppContext {
     /// modal (aka "vim) mode enabled
     pub modal: bool,
 
-    /// Whether to support mouse interactions
-    pub capture_mouse: bool,
-
     /// max number of panels (including preview) that can be
     /// open. Guaranteed to be at least 2.
     pub max_panels_count: usize,
@@ -81,6 +123,18 @@ pub struct AppContext {
     /// and there's nothing to cancel
     pub quit_on_last_cancel: bool,
 
+    /// whether quitting must be confirmed when some paths are staged
+    /// or some tasks aren't finished
+    pub confirm_quit: bool,
+
+    /// whether the panels, their trees and the stage are saved on
+    /// quit, to be restored with `--resume`
+    pub save_session: bool,
+
+    /// whether computed sizes are recorded, and compared with the
+    /// ones of the previous visit
+    pub size_deltas: bool,
+
     /// number of threads used by file_sum (count, size, date)
     /// computation
     pub file_sum_threads_count: usize,
@@ -88,8 +142,75 @@ pub struct AppContext {
     /// number of files which may be staged in one staging operation
     pub max_staged_count: usize,
 
-    /// max file size when searching file content
-    pub content_search_max_file_size: usize,
+    /// when set, trees are rebuilt at this period while idle
+    pub auto_refresh_period: Option<Duration>,
+
+    /// when set, trees are rebuilt on changes notified by the
+    /// system, at most once per this duration
+    pub live_refresh_throttle: Option<Duration>,
+
+    /// the watcher of directories shared by the trees, for
+    /// their live refresh
+    pub fs_watcher: Option<Arc<FsWatcher>>,
+
+    /// whether directory listings are kept in an index to
+    /// speed up searches
+    pub file_index: bool,
+
+    /// the rules excluding files from content searches
+    pub content_search_limits: content_search::ContentSearchLimits,
+
+    /// when set, no tree can be displayed outside of this directory
+    pub stay_under: Option<PathBuf>,
+
+    /// whether verbs writing files are disabled
+    pub read_only: bool,
+
+    /// names of the mutating verbs which stay available in read-only mode
+    pub read_only_allowed_verbs: Vec<String>,
+
+    /// the command (eg `sudo`) with which operations failing for lack
+    /// of permission may be retried
+    pub escalation_command: Option<Vec<String>>,
+
+    /// the template of the first line of trees, if not the standard one
+    pub tree_title: Option<TitleTemplate>,
+
+    /// the template of the title of previews, if not the standard one
+    pub preview_title: Option<TitleTemplate>,
+
+    /// whether the summary of a directory pops up when the mouse
+    /// is over its line
+    pub dir_info_on_hover: bool,
+
+    /// whether the filesystems state initially lists only the
+    /// filesystems on disks
+    pub filesystems_only_disks: bool,
+
+    /// the period at which the filesystems state checks whether
+    /// the mounts changed
+    pub filesystems_refresh_period: Duration,
+
+    /// whether the filesystems state initially shows the totals
+    /// of the listed filesystems below them
+    pub filesystems_totals: bool,
+
+    /// the share of used space (between 0 and 1) from which a
+    /// filesystem is displayed as nearly full
+    pub filesystems_usage_alert: Option<f64>,
+
+    /// the columns of the filesystems table, when not chosen
+    /// according to the available width
+    pub fs_columns: Option<Vec<FsCol>>,
+
+    /// the approximate memory, in bytes, the structures of a tree
+    /// build may take before the exploration stops
+    pub tree_memory_budget: Option<usize>,
+
+    /// the terminal row at which the interface starts when it's
+    /// displayed inline, below the prompt (with `--height`), instead
+    /// of in the alternate screen
+    pub inline_top: Option<u16>,
 }
 
 impl AppContext {
@@ -98,44 +219,134 @@ impl AppContext {
         verb_store: VerbStore,
         config: &Conf,
     ) -> Result<Self, ProgramError> {
+        init_catalog(config.language.as_deref())?;
         let config_paths = config.files.clone();
-        let standard_status = StandardStatus::new(&verb_store);
-        let true_colors = if let Some(value) = config.true_colors {
-            value
-        } else {
-            are_true_colors_available()
+        let standard_status = StandardStatus::new(
+            &verb_store,
+            config.show_tips.unwrap_or(false),
+        );
+        let term_caps = TermCapabilities::new(config);
+        let icons = match config.icon_theme.as_ref() {
+            Some(_) if launch_args.ascii => None,
+            Some(_) if !term_caps.wide_chars => {
+                warn!("icons disabled: the terminal can't display them");
+                None
+            }
+            Some(itn) => icon_plugin(itn),
+            None => None,
         };
-        let icons = config.icon_theme.as_ref()
-            .and_then(|itn| icon_plugin(itn));
         let special_paths = config.special_paths
             .iter()
             .map(|(k, v)| SpecialPath::new(k.clone(), *v))
             .collect();
+        let quick_roots = if config.quick_roots.is_empty() {
+            quick_roots::default_quick_roots()
+        } else {
+            config.quick_roots.clone()
+        };
         let search_modes = config
             .search_modes
             .as_ref()
             .map(|map| map.try_into())
             .transpose()?
             .unwrap_or_default();
+        let panel_search_modes = config
+            .panel_search_modes
+            .as_ref()
+            .map(|map| map.try_into())
+            .transpose()?
+            .unwrap_or_default();
+        let click_bindings = config
+            .click_bindings
+            .as_ref()
+            .map(|map| map.try_into())
+            .transpose()?
+            .unwrap_or_default();
         let ext_colors = ExtColorMap::try_from(&config.ext_colors)
             .map_err(ConfError::from)?;
+        let glyphs = if launch_args.ascii {
+            Glyphs::ascii()
+        } else {
+            Glyphs::try_from(&config.glyphs)?
+        };
+        let custom_syntax_themes = config.syntax_themes_dir
+            .as_ref()
+            .map(|dir| load_custom_syntax_themes(Path::new(dir)))
+            .transpose()?
+            .unwrap_or_default();
+        if let Some(SyntaxTheme::Custom(name)) = &config.syntax_theme {
+            if !custom_syntax_themes.contains_key(name) {
+                return Err(ConfError::InvalidSyntaxTheme { name: name.clone() }.into());
+            }
+        }
         let file_sum_threads_count = config.file_sum_threads_count
             .unwrap_or(file_sum::DEFAULT_THREAD_COUNT);
         if file_sum_threads_count < 1 || file_sum_threads_count > 50 {
             return Err(ConfError::InvalidThreadsCount{ count: file_sum_threads_count }.into());
         }
+        let auto_refresh_period = match config.auto_refresh_period {
+            Some(secs) if secs > 0.0 && secs.is_finite() => Some(Duration::from_secs_f64(secs)),
+            Some(period) => {
+                return Err(ConfError::InvalidAutoRefreshPeriod { period }.into());
+            }
+            None => None,
+        };
+        let live_refresh_throttle = match config.live_refresh_throttle {
+            Some(secs) if secs > 0.0 && secs.is_finite() => Duration::from_secs_f64(secs),
+            Some(throttle) => {
+                return Err(ConfError::InvalidLiveRefreshThrottle { throttle }.into());
+            }
+            None => Duration::from_secs(1),
+        };
+        let live_refresh_throttle = if config.live_refresh.unwrap_or(false) {
+            Some(live_refresh_throttle)
+        } else {
+            None
+        };
+        let fs_watcher = live_refresh_throttle.and_then(|_| {
+            FsWatcher::new()
+                .map_err(|e| warn!("no live refresh: {}", e))
+                .ok()
+                .map(Arc::new)
+        });
+        let filesystems_refresh_period = match config.filesystems_refresh_period {
+            Some(secs) if secs > 0.0 && secs.is_finite() => Duration::from_secs_f64(secs),
+            Some(period) => {
+                return Err(ConfError::InvalidAutoRefreshPeriod { period }.into());
+            }
+            None => Duration::from_secs(2),
+        };
+        let filesystems_usage_alert = match config.filesystems_usage_alert {
+            Some(percent) if percent > 0.0 && percent <= 100.0 => Some(percent / 100.0),
+            Some(percent) => {
+                return Err(ConfError::InvalidUsageAlert { percent }.into());
+            }
+            None => None,
+        };
         let max_panels_count = config.max_panels_count
             .unwrap_or(2)
             .clamp(2, 100);
-        let capture_mouse = match (config.capture_mouse, config.disable_mouse_capture) {
-            (Some(b), _) => b, // the new "capture_mouse" argument takes precedence
-            (_, Some(b)) => !b,
-            _ => true,
-        };
         let max_staged_count = config.max_staged_count
             .unwrap_or(10_000)
             .clamp(10, 100_000);
+        let stay_under = launch_args.stay_under
+            .as_ref()
+            .map(|dir| {
+                if dir.is_dir() {
+                    canonicalize_root(dir)
+                } else {
+                    Err(io::Error::new(io::ErrorKind::NotFound, format!("{:?} isn't a directory", dir)))
+                }
+            })
+            .transpose()?;
         let initial_root = get_root_path(&launch_args)?;
+        if let Some(stay_under) = &stay_under {
+            if !initial_root.starts_with(stay_under) {
+                return Err(TreeBuildError::OutsidePinnedRoot {
+                    path: initial_root.to_string_lossy().to_string(),
+                }.into());
+            }
+        }
 
         // tree options are built from the default_flags
         // found in the config file(s) (if any) then overridden
@@ -147,9 +358,23 @@ impl AppContext {
             initial_tree_options.show_selection_mark = true;
         }
 
-        let content_search_max_file_size = config.content_search_max_file_size
-            .map(|u64value| usize::try_from(u64value).unwrap_or(usize::MAX))
-            .unwrap_or(content_search::DEFAULT_MAX_FILE_SIZE);
+        let content_search_limits = content_search::ContentSearchLimits {
+            max_file_size: config.content_search_max_file_size
+                .map(|u64value| usize::try_from(u64value).unwrap_or(usize::MAX))
+                .unwrap_or(content_search::DEFAULT_MAX_FILE_SIZE),
+            excluded: config.content_search_excluded.clone(),
+            binary_threshold: config.content_search_binary_threshold,
+        };
+
+        let read_only = launch_args.read_only;
+        #[cfg(unix)]
+        if let Some(fd) = launch_args.events_fd {
+            check_events_fd(fd)?;
+        }
+        let escalation_command = config.escalation_command
+            .as_ref()
+            .map(|c| c.split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>())
+            .filter(|parts| !parts.is_empty());
 
         Ok(Self {
             initial_root,
@@ -158,43 +383,98 @@ impl AppContext {
             launch_args,
             verb_store,
             special_paths,
+            preview_modes: config.preview_modes.clone(),
+            lint_probes: config.lint_probes.clone(),
+            openers: config.openers.clone(),
+            backup_locations: config.backup_locations.clone(),
+            quick_roots,
             search_modes,
+            panel_search_modes,
+            click_bindings,
             show_selection_mark: config.show_selection_mark.unwrap_or(false),
             ext_colors,
-            syntax_theme: config.syntax_theme,
+            glyphs,
+            syntax_theme: config.syntax_theme.clone(),
+            custom_syntax_themes,
             standard_status,
-            true_colors,
+            term_caps,
             icons,
             modal: config.modal.unwrap_or(false),
-            capture_mouse,
             max_panels_count,
             quit_on_last_cancel: config.quit_on_last_cancel.unwrap_or(false),
+            confirm_quit: config.confirm_quit.unwrap_or(false),
+            save_session: config.save_session.unwrap_or(false),
+            size_deltas: config.size_deltas.unwrap_or(true),
             file_sum_threads_count,
+            auto_refresh_period,
+            live_refresh_throttle,
+            fs_watcher,
+            file_index: config.file_index.unwrap_or(false),
             max_staged_count,
-            content_search_max_file_size,
+            content_search_limits,
+            stay_under,
+            read_only,
+            read_only_allowed_verbs: config.read_only_allowed_verbs.clone(),
+            escalation_command,
+            tree_title: config.tree_title.as_deref().map(TitleTemplate::from),
+            preview_title: config.preview_title.as_deref().map(TitleTemplate::from),
+            dir_info_on_hover: config.dir_info_on_hover.unwrap_or(false),
+            filesystems_only_disks: config.filesystems_only_disks.unwrap_or(false),
+            filesystems_refresh_period,
+            filesystems_totals: config.filesystems_totals.unwrap_or(false),
+            filesystems_usage_alert,
+            fs_columns: config.fs_columns.as_deref().map(parse_fs_cols).transpose()?,
+            tree_memory_budget: config.tree_memory_budget
+                .map(|u64value| usize::try_from(u64value).unwrap_or(usize::MAX)),
+            inline_top: None,
         })
     }
 }
 
-/// try to determine whether the terminal supports true
-/// colors. This doesn't work well, hence the use of an
-/// optional config setting.
-/// Based on https://gist.github.com/XVilka/8346728#true-color-detection
-fn are_true_colors_available() -> bool {
-    if let Ok(colorterm) = std::env::var("COLORTERM") {
-        debug!("COLORTERM env variable = {:?}", colorterm);
-        if colorterm.contains("truecolor") || colorterm.contains("24bit") {
-            debug!("true colors are available");
-            true
-        } else {
-            false
+impl AppContext {
+    /// return all the syntax themes which can be used for previews:
+    /// the embedded ones then the custom ones
+    pub fn syntax_themes(&self) -> Vec<SyntaxTheme> {
+        SYNTAX_THEMES
+            .iter()
+            .cloned()
+            .chain(
+                self.custom_syntax_themes
+                    .keys()
+                    .map(|name| SyntaxTheme::Custom(name.clone()))
+            )
+            .collect()
+    }
+    /// tell whether the path may be displayed or opened, which is
+    /// always the case unless broot was launched with --stay-under
+    pub fn is_allowed_path(&self, path: &Path) -> bool {
+        match &self.stay_under {
+            Some(dir) => normalize_path(path).starts_with(dir),
+            None => true,
         }
-    } else {
-        // this is debatable... I've found some terminals with COLORTERM
-        // unset but supporting true colors. As it's easy to determine
-        // that true colors aren't supported when looking at previewed
-        // images I prefer this value
-        true
+    }
+    /// tell whether the verb can be executed, which is always the
+    /// case unless broot was launched with --read-only
+    pub fn is_allowed_verb(&self, verb: &Verb) -> bool {
+        !self.read_only
+            || !verb.mutating
+            || verb.names.iter().any(|name| self.read_only_allowed_verbs.contains(name))
+    }
+    pub fn is_allowed_internal(&self, internal: Internal) -> bool {
+        !self.read_only
+            || !internal.is_mutating()
+            || self.read_only_allowed_verbs.iter().any(|name| name == internal.name())
+    }
+    /// find the syntax theme with the given name, either among the
+    /// embedded themes or among the custom ones (case insensitively)
+    pub fn syntax_theme_named(&self, name: &str) -> Result<SyntaxTheme, ConfError> {
+        SyntaxTheme::from_str(name).or_else(|e| {
+            self.custom_syntax_themes
+                .keys()
+                .find(|k| k.eq_ignore_ascii_case(name))
+                .map(|k| SyntaxTheme::Custom(k.clone()))
+                .ok_or(e)
+        })
     }
 }
 
@@ -202,6 +482,7 @@ fn get_root_path(cli_args: &Args) -> Result<PathBuf, ProgramError> {
     let mut root = cli_args
         .root
         .as_ref()
+        .or(cli_args.stay_under.as_ref())
         .map_or(std::env::current_dir()?, PathBuf::from);
     if !root.exists() {
         return Err(TreeBuildError::FileNotFound {
@@ -237,4 +518,38 @@ fn canonicalize_root(root: &Path) -> io::Result<PathBuf> {
     })
 }
 
+/// check the file descriptor given with `--events-fd` is open, and
+/// isn't one of the standard ones, used by broot
+#[cfg(unix)]
+fn check_events_fd(fd: i32) -> Result<(), ConfError> {
+    if (0..=2).contains(&fd) {
+        return Err(ConfError::InvalidEventsFd {
+            fd,
+            details: "the standard streams are used by broot".to_string(),
+        });
+    }
+    if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return Err(ConfError::InvalidEventsFd {
+            fd,
+            details: std::io::Error::last_os_error().to_string(),
+        });
+    }
+    Ok(())
+}
 
+#[cfg(all(test, unix))]
+mod app_context_tests {
+
+    use {
+        super::*,
+        std::os::unix::io::AsRawFd,
+    };
+
+    #[test]
+    fn check_events_fd_validation() {
+        assert!(check_events_fd(1).is_err());
+        assert!(check_events_fd(987_654).is_err());
+        let file = tempfile::tempfile().unwrap();
+        assert!(check_events_fd(file.as_raw_fd()).is_ok());
+    }
+}
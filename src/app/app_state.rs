@@ -1,8 +1,12 @@
 use {
     crate::{
+        activity::{EventStream, VerbLog},
+        launchable::Launchable,
         stage::Stage,
+        tag::TagStore,
+        verb::VerbUsage,
     },
-    std::path::PathBuf,
+    std::path::{Path, PathBuf},
 };
 
 
@@ -11,6 +15,10 @@ use {
 pub struct AppState {
     pub stage: Stage,
 
+    /// the paths marked in trees, on which the verbs with a
+    /// `{marked}` group are executed
+    pub marks: Stage,
+
     /// the current root, updated when a panel with this concept
     /// becomes active or changes its root
     pub root: PathBuf,
@@ -18,7 +26,42 @@ pub struct AppState {
     /// the selected path in another panel than the currently
     /// active one, if any
     pub other_panel_path: Option<PathBuf>,
+
+    /// the number of panels, including the preview one
+    pub panel_count: usize,
+
+    /// the raw pattern filtering the active panel, if any
+    pub filter: String,
+
+    /// the tags of files, kept in sync with the tags file
+    pub tags: TagStore,
+
+    /// the verbs executed since broot was launched
+    pub verb_log: VerbLog,
+
+    /// how often and recently the verbs were used, kept in sync
+    /// with the verb usage file
+    pub verb_usage: VerbUsage,
+
+    /// when true, external verbs are displayed instead of executed
+    pub dry_run: bool,
+
+    /// the operations which failed for lack of permission, ready
+    /// to be retried with the escalation command
+    pub pending_escalation: Vec<Launchable>,
+
+    /// where to write the events, when asked with `--events-fd`
+    pub events: Option<EventStream>,
+
+    /// the directories opened for browsing only (eg the root
+    /// filesystems of containers), where mutating verbs are disabled
+    pub read_only_roots: Vec<PathBuf>,
 }
 
 impl AppState {
+    /// tell whether the path is in a directory opened for
+    /// browsing only
+    pub fn is_read_only_path(&self, path: &Path) -> bool {
+        self.read_only_roots.iter().any(|root| path.starts_with(root))
+    }
 }
@@ -3,6 +3,7 @@ use {
     crate::{
         command::*,
         display::{Areas, Screen},
+        pattern::SearchMode,
         skin::PanelSkin,
     },
 };
@@ -28,4 +29,7 @@ pub struct AppCmdContext<'c> {
 pub struct PanelCmdContext<'c> {
     pub areas: &'c Areas,
     pub purpose: PanelPurpose,
+    /// the search mode of the patterns without mode key, when
+    /// it's not the default one
+    pub search_mode: Option<SearchMode>,
 }
@@ -37,6 +37,7 @@ pub enum CmdResult {
         panel_ref: PanelReference,
     },
     DisplayError(String),
+    DisplayMessage(String), // markdown
     ExecuteSequence {
         sequence: Sequence,
     },
@@ -53,6 +54,9 @@ pub enum CmdResult {
         message: Option<&'static str>, // explaining why there's a new state
     },
     PopStateAndReapply, // the state asks the command be executed on a previous state
+    PopStateAndExecute {
+        sequence: Sequence, // executed on the previous state
+    },
     PopState,
     Quit,
     RefreshState {
@@ -94,6 +98,9 @@ impl CmdResult {
     pub fn error<S: Into<String>>(message: S) -> Self {
         Self::DisplayError(message.into())
     }
+    pub fn message<S: Into<String>>(md: S) -> Self {
+        Self::DisplayMessage(md.into())
+    }
 }
 
 impl From<Launchable> for CmdResult {
@@ -116,12 +123,14 @@ impl fmt::Debug for CmdResult {
                     validate_purpose: true, ..
                 } => "OkPanel",
                 CmdResult::DisplayError(_) => "DisplayError",
+                CmdResult::DisplayMessage(_) => "DisplayMessage",
                 CmdResult::ExecuteSequence{ .. } => "ExecuteSequence",
                 CmdResult::Keep => "Keep",
                 CmdResult::Launch(_) => "Launch",
                 CmdResult::NewState { .. } => "NewState",
                 CmdResult::NewPanel { .. } => "NewPanel",
                 CmdResult::PopStateAndReapply => "PopStateAndReapply",
+                CmdResult::PopStateAndExecute { .. } => "PopStateAndExecute",
                 CmdResult::PopState => "PopState",
                 CmdResult::HandleInApp(_) => "HandleInApp",
                 CmdResult::Quit => "Quit",
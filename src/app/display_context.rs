@@ -1,7 +1,9 @@
 use {
     super::*,
     crate::{
-        display::Screen,
+        command::ScrollCommand,
+        display::{Screen, W},
+        errors::ProgramError,
         skin::PanelSkin,
     },
     termimad::Area,
@@ -17,5 +19,55 @@ pub struct DisplayContext<'c> {
     pub panel_skin: &'c PanelSkin,
     pub app_state: &'c AppState,
     pub con: &'c AppContext,
+    /// whether only the states must be displayed, without input,
+    /// status or scrollbar
+    pub zen: bool,
+    /// whether the row above the state area is kept for the tab bar
+    pub tab_bar: bool,
 }
 
+
+impl DisplayContext<'_> {
+    /// compute the thumb of the scrollbar of an area showing
+    /// `len` lines from `scroll`, None when there's no scrollbar
+    /// to display, either because everything fits or in zen mode
+    pub fn scrollbar(&self, area: &Area, scroll: usize, len: usize) -> Option<(u16, u16)> {
+        if self.zen {
+            None
+        } else {
+            area.scrollbar(scroll, len)
+        }
+    }
+    /// the width left to the content of the state area, the last
+    /// column being kept for the scrollbar unless in zen mode
+    pub fn content_width(&self) -> usize {
+        let width = self.state_area.width as usize;
+        if self.zen {
+            width
+        } else {
+            width - 1
+        }
+    }
+    /// write, at the current position which should be the last
+    /// column of the row `y`, the track or the thumb of the scrollbar,
+    /// or nothing in zen mode
+    pub fn queue_scrollbar(
+        &self,
+        w: &mut W,
+        y: u16,
+        scrollbar: Option<(u16, u16)>,
+    ) -> Result<(), ProgramError> {
+        if self.zen {
+            return Ok(());
+        }
+        let styles = &self.panel_skin.styles;
+        let thumb = ScrollCommand::is_thumb(y, scrollbar);
+        let scrollbar_style = if thumb {
+            &styles.scrollbar_thumb
+        } else {
+            &styles.scrollbar_track
+        };
+        scrollbar_style.queue(w, self.con.glyphs.scrollbar(thumb))?;
+        Ok(())
+    }
+}
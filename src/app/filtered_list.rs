@@ -0,0 +1,348 @@
+use {
+    super::*,
+    crate::{
+        command::*,
+        pattern::*,
+        verb::*,
+    },
+};
+
+/// tell whether an item matches a pattern
+type Matcher<T> = Box<dyn Fn(&T, &Pattern) -> bool>;
+
+struct ListFilter {
+    pattern: Pattern,
+    indices: Vec<usize>, // indices of the matching items, may be empty
+    selection_idx: usize, // in indices
+}
+
+/// A list of items which can be filtered with a pattern, with a
+/// selection and a scroll, as displayed by the list states (tags,
+/// containers, trash, etc.).
+///
+/// Indices given to and returned by the methods are the ones of
+/// the visible items, which are only the matching ones when the
+/// list is filtered.
+pub struct FilteredList<T> {
+    items: Vec<T>, // may be empty
+    matches: Matcher<T>,
+    selection_idx: usize, // in items
+    scroll: usize,
+    page_height: usize,
+    filter: Option<ListFilter>,
+}
+
+impl<T> FilteredList<T> {
+    /// build a list, filtered with the items for which
+    /// `matches` returns true
+    pub fn new<M>(items: Vec<T>, matches: M) -> Self
+    where
+        M: Fn(&T, &Pattern) -> bool + 'static,
+    {
+        Self {
+            items,
+            matches: Box::new(matches),
+            selection_idx: 0,
+            scroll: 0,
+            page_height: 0,
+            filter: None,
+        }
+    }
+    /// all the items, filtered out or not
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+    pub fn items_mut(&mut self) -> &mut [T] {
+        &mut self.items
+    }
+    /// the number of visible items
+    pub fn count(&self) -> usize {
+        self.filter
+            .as_ref()
+            .map_or(self.items.len(), |f| f.indices.len())
+    }
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        match &self.filter {
+            Some(f) => f.indices.get(idx).map(|&idx| &self.items[idx]),
+            None => self.items.get(idx),
+        }
+    }
+    /// the visible items
+    pub fn visible(&self) -> impl Iterator<Item = &T> {
+        (0..self.count()).filter_map(move |idx| self.get(idx))
+    }
+    pub fn selection_idx(&self) -> usize {
+        self.filter
+            .as_ref()
+            .map_or(self.selection_idx, |f| f.selection_idx)
+    }
+    pub fn selected(&self) -> Option<&T> {
+        self.get(self.selection_idx())
+    }
+    pub fn selected_mut(&mut self) -> Option<&mut T> {
+        let idx = match &self.filter {
+            Some(f) => *f.indices.get(f.selection_idx)?,
+            None => self.selection_idx,
+        };
+        self.items.get_mut(idx)
+    }
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+    pub fn set_page_height(&mut self, page_height: usize) {
+        self.page_height = page_height;
+    }
+    /// the pattern of the filter, if any
+    pub fn pattern(&self) -> Option<&Pattern> {
+        self.filter.as_ref().map(|f| &f.pattern)
+    }
+    /// search the pattern of the filter, if any, in a string
+    pub fn search_string(&self, candidate: &str) -> Option<NameMatch> {
+        self.pattern().and_then(|pattern| pattern.search_string(candidate))
+    }
+    pub fn is_filtered(&self) -> bool {
+        self.filter.is_some()
+    }
+    /// select the visible item at the given index, scrolling
+    /// if needed to keep it visible
+    pub fn select(&mut self, idx: usize) {
+        let idx = idx.min(self.count().max(1) - 1);
+        match self.filter.as_mut() {
+            Some(f) => f.selection_idx = idx,
+            None => self.selection_idx = idx,
+        }
+        self.show_selection();
+    }
+    fn show_selection(&mut self) {
+        let selection_idx = self.selection_idx();
+        if selection_idx < self.scroll {
+            self.scroll = selection_idx;
+        } else if self.page_height > 0 && selection_idx >= self.scroll + self.page_height {
+            self.scroll = selection_idx + 1 - self.page_height;
+        }
+    }
+    /// filter the list with the pattern, selecting the matching
+    /// item nearest to the current selection
+    fn filter(&mut self, pattern: Pattern) {
+        let mut selection_idx = 0;
+        let mut indices = Vec::new();
+        for (idx, item) in self.items.iter().enumerate() {
+            if !(self.matches)(item, &pattern) {
+                continue;
+            }
+            if idx <= self.selection_idx {
+                selection_idx = indices.len();
+            }
+            indices.push(idx);
+        }
+        self.filter = Some(ListFilter {
+            pattern,
+            indices,
+            selection_idx,
+        });
+    }
+    /// apply the pattern typed by the user, removing the filter
+    /// when it's empty
+    pub fn on_pattern(&mut self, pattern: InputPattern) {
+        if pattern.is_none() {
+            self.unfilter();
+        } else {
+            self.filter(pattern.pattern);
+        }
+        self.fix_scroll();
+    }
+    /// remove the filter, keeping the selected item selected.
+    ///
+    /// Return false when the list wasn't filtered
+    pub fn unfilter(&mut self) -> bool {
+        match self.filter.take() {
+            Some(f) => {
+                if let Some(&idx) = f.indices.get(f.selection_idx) {
+                    self.selection_idx = idx;
+                }
+                self.fix_scroll();
+                true
+            }
+            None => false,
+        }
+    }
+    /// replace the items, for example on a refresh, keeping the
+    /// filter, the scroll and, when it's still there, the selected
+    /// item, as recognized with `same`
+    pub fn set_items<S>(&mut self, items: Vec<T>, same: S)
+    where
+        S: Fn(&T, &T) -> bool,
+    {
+        let old_selection_idx = self.selection_idx;
+        let selected = self.filter.as_ref()
+            .and_then(|f| f.indices.get(f.selection_idx).copied())
+            .unwrap_or(self.selection_idx);
+        let new_idx = self.items.get(selected)
+            .and_then(|selected| items.iter().position(|item| same(selected, item)));
+        self.items = items;
+        self.selection_idx = new_idx
+            .unwrap_or_else(|| old_selection_idx.min(self.items.len().max(1) - 1));
+        if let Some(f) = self.filter.take() {
+            self.filter(f.pattern);
+        }
+        self.fix_scroll();
+    }
+    /// insert items before the existing ones, the selected item
+    /// staying selected unless it's the first one
+    pub fn prepend(&mut self, items: Vec<T>) {
+        let count = items.len();
+        self.items.splice(0..0, items);
+        if self.selection_idx > 0 {
+            self.selection_idx += count;
+        }
+        if let Some(f) = self.filter.take() {
+            self.filter(f.pattern);
+        }
+        self.fix_scroll();
+    }
+    /// replace the items, for example when they're sorted in
+    /// another order, keeping the filter but selecting the first
+    /// item and resetting the scroll
+    pub fn reset_items(&mut self, items: Vec<T>) {
+        self.items = items;
+        self.selection_idx = 0;
+        self.scroll = 0;
+        if let Some(f) = self.filter.take() {
+            self.filter(f.pattern);
+        }
+    }
+    fn fix_scroll(&mut self) {
+        self.scroll = fix_scroll(self.scroll, self.selection_idx(), self.count(), self.page_height);
+    }
+    /// move the selection by `dy` lines
+    pub fn move_selection(&mut self, dy: i32, cycle: bool) {
+        let idx = move_sel(self.selection_idx(), self.count(), dy, cycle);
+        self.select(idx);
+    }
+    /// change the selection, by the count given as argument
+    /// to the internal or by one line
+    pub fn move_line(
+        &mut self,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        dir: i32, // -1 for up, 1 for down
+        cycle: bool,
+    ) -> CmdResult {
+        let count: i32 = get_arg(input_invocation, internal_exec, 1);
+        self.move_selection(dir * count, cycle);
+        CmdResult::Keep
+    }
+    /// scroll, keeping the selection in the visible page.
+    ///
+    /// Return true when the scroll changed
+    pub fn try_scroll(&mut self, cmd: ScrollCommand) -> bool {
+        let old_scroll = self.scroll;
+        self.scroll = cmd.apply(self.scroll, self.count(), self.page_height);
+        let selection_idx = self.selection_idx();
+        if selection_idx < self.scroll {
+            self.select(self.scroll);
+        } else if self.page_height > 0 && selection_idx >= self.scroll + self.page_height {
+            self.select(self.scroll + self.page_height - 1);
+        }
+        self.scroll != old_scroll
+    }
+    /// scroll by pages, selecting the first or last item when
+    /// the scroll can't go further
+    pub fn scroll_pages(&mut self, pages: i32) {
+        if !self.try_scroll(ScrollCommand::Pages(pages)) {
+            if pages < 0 {
+                self.select(0);
+            } else {
+                self.select(self.count().max(1) - 1);
+            }
+        }
+    }
+    /// handle the internals common to all list states: moving the
+    /// selection, scrolling, and removing the filter on `:back`.
+    ///
+    /// Return None for the other internals
+    pub fn on_internal(
+        &mut self,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+    ) -> Option<CmdResult> {
+        Some(match internal_exec.internal {
+            Internal::back => {
+                if self.unfilter() {
+                    CmdResult::Keep
+                } else {
+                    CmdResult::PopState
+                }
+            }
+            Internal::line_down => self.move_line(internal_exec, input_invocation, 1, true),
+            Internal::line_up => self.move_line(internal_exec, input_invocation, -1, true),
+            Internal::line_down_no_cycle => self.move_line(internal_exec, input_invocation, 1, false),
+            Internal::line_up_no_cycle => self.move_line(internal_exec, input_invocation, -1, false),
+            Internal::page_down => {
+                self.scroll_pages(1);
+                CmdResult::Keep
+            }
+            Internal::page_up => {
+                self.scroll_pages(-1);
+                CmdResult::Keep
+            }
+            _ => {
+                return None;
+            }
+        })
+    }
+    /// select the item at the given row of the state area, the
+    /// rows of items starting after `header_height` rows
+    pub fn on_click(&mut self, y: u16, header_height: u16) {
+        if y >= header_height {
+            let idx = (y - header_height) as usize + self.scroll;
+            if idx < self.count() {
+                self.select(idx);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod filtered_list_tests {
+
+    use super::*;
+
+    fn list(names: &[&str]) -> FilteredList<String> {
+        let items = names.iter().map(|s| s.to_string()).collect();
+        FilteredList::new(items, |s: &String, pattern: &Pattern| pattern.score_of_string(s).is_some())
+    }
+
+    fn pattern(raw: &str) -> InputPattern {
+        InputPattern {
+            raw: raw.to_string(),
+            pattern: Pattern::NameExact(ExactPattern::from(raw)),
+        }
+    }
+
+    #[test]
+    fn test_filtered_list() {
+        let mut list = list(&["apple", "banana", "cherry", "avocado", "blueberry"]);
+        list.set_page_height(2);
+        list.move_selection(2, false);
+        assert_eq!(list.selected().unwrap(), "cherry");
+        assert_eq!(list.scroll(), 1);
+        // the filter selects the nearest matching item
+        list.on_pattern(pattern("b"));
+        assert_eq!(list.count(), 2);
+        assert_eq!(list.selected().unwrap(), "banana");
+        // moving the selection in a filtered list scrolls it
+        list.move_selection(1, false);
+        assert_eq!(list.selected().unwrap(), "blueberry");
+        assert_eq!(list.scroll(), 0);
+        // a refresh keeps the selected item and the filter
+        let items = ["blueberry", "apple", "banana"].iter().map(|s| s.to_string()).collect();
+        list.set_items(items, |a, b| a == b);
+        assert_eq!(list.count(), 2);
+        assert_eq!(list.selected().unwrap(), "blueberry");
+        // removing the filter keeps the selected item
+        assert!(list.unfilter());
+        assert_eq!(list.selection_idx(), 0);
+        assert!(!list.unfilter());
+    }
+}
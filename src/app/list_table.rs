@@ -0,0 +1,221 @@
+use {
+    super::*,
+    crate::{
+        display::*,
+        errors::ProgramError,
+        pattern::Pattern,
+    },
+    crokey::crossterm::{
+        cursor,
+        style::Color,
+        QueueableCommand,
+    },
+    termimad::{
+        minimad::Alignment,
+        *,
+    },
+};
+
+/// a column of the table of a list state, before the last
+/// one which takes the remaining width
+pub struct ListColumn<'t> {
+    pub title: &'t str,
+    pub width: usize, // not counting the selection mark
+    pub align: Alignment,
+    pub mark: Option<char>, // written after the title, eg the sort mark
+}
+
+impl<'t> ListColumn<'t> {
+    pub fn new(title: &'t str, width: usize, align: Alignment) -> Self {
+        Self { title, width, align, mark: None }
+    }
+    pub fn with_mark(mut self, mark: char) -> Self {
+        self.mark = Some(mark);
+        self
+    }
+    /// the width of the cells, including the mark
+    fn cell_width(&self) -> usize {
+        self.width + self.mark.map_or(0, |_| 1)
+    }
+}
+
+/// the styles of a row of the table of a list state, and the
+/// pattern whose matches are highlighted in its cells
+pub struct ListRow<'r> {
+    pub selected: bool,
+    pub txt_style: &'r CompoundStyle,
+    match_style: &'r CompoundStyle,
+    border_style: &'r CompoundStyle,
+    selection_bg: Color,
+    border: char,
+    pattern: Option<&'r Pattern>,
+}
+
+impl ListRow<'_> {
+    /// the given style, on the background of the selection
+    /// when the row is selected
+    pub fn style(&self, style: &CompoundStyle) -> CompoundStyle {
+        let mut style = style.clone();
+        if self.selected {
+            style.set_bg(self.selection_bg);
+        }
+        style
+    }
+    /// write a cell, whose content isn't searched, and its border
+    pub fn queue_cell<W: std::io::Write>(
+        &self,
+        cw: &mut CropWriter<'_, W>,
+        s: &str,
+        style: &CompoundStyle,
+        width: usize,
+        align: Alignment,
+    ) -> Result<(), ProgramError> {
+        let mut matched_string = MatchedString::new(None, s, style, style);
+        matched_string.fill(width, align);
+        matched_string.queue_on(cw)?;
+        cw.queue_char(self.border_style, self.border)?;
+        Ok(())
+    }
+    /// write a cell, with the chars matching the pattern highlighted,
+    /// and its border
+    pub fn queue_matched_cell<W: std::io::Write>(
+        &self,
+        cw: &mut CropWriter<'_, W>,
+        s: &str,
+        style: &CompoundStyle,
+        width: usize,
+        align: Alignment,
+    ) -> Result<(), ProgramError> {
+        let mut matched_string = self.matched_string(s, style);
+        matched_string.fill(width, align);
+        matched_string.queue_on(cw)?;
+        cw.queue_char(self.border_style, self.border)?;
+        Ok(())
+    }
+    /// write a string with the chars matching the pattern highlighted,
+    /// without filling nor border, as in the last column
+    pub fn queue_matched<W: std::io::Write>(
+        &self,
+        cw: &mut CropWriter<'_, W>,
+        s: &str,
+        style: &CompoundStyle,
+    ) -> Result<(), ProgramError> {
+        self.matched_string(s, style).queue_on(cw)?;
+        Ok(())
+    }
+    fn matched_string<'s>(
+        &'s self,
+        s: &'s str,
+        style: &'s CompoundStyle,
+    ) -> MatchedString<'s> {
+        MatchedString::new(
+            self.pattern.and_then(|p| p.search_string(s)),
+            s,
+            style,
+            self.match_style,
+        )
+    }
+}
+
+impl<T> FilteredList<T> {
+    /// write the two lines heading the table of a list state: the
+    /// titles of the columns, the last one being written by
+    /// `queue_last_title`, and the horizontal line below them.
+    ///
+    /// The first column is widened for the selection mark when it's
+    /// displayed.
+    pub fn queue_table_head<F>(
+        &self,
+        w: &mut W,
+        disc: &DisplayContext,
+        columns: &[ListColumn<'_>],
+        queue_last_title: F,
+    ) -> Result<(), ProgramError>
+    where
+        F: FnOnce(&mut CropWriter<'_, W>) -> Result<(), ProgramError>,
+    {
+        let area = &disc.state_area;
+        let styles = &disc.panel_skin.styles;
+        let glyphs = &disc.con.glyphs;
+        let border_style = &styles.help_table_border;
+        let width = area.width as usize;
+        let mark_width = if disc.con.show_selection_mark { 1 } else { 0 };
+        //- titles
+        w.queue(cursor::MoveTo(area.left, area.top))?;
+        let mut cw = CropWriter::new(w, width);
+        for (i, column) in columns.iter().enumerate() {
+            let mut title = MatchedString::new(None, column.title, &styles.default, &styles.default);
+            title.fill(column.width + if i == 0 { mark_width } else { 0 }, column.align);
+            title.queue_on(&mut cw)?;
+            if let Some(mark) = column.mark {
+                cw.queue_char(border_style, mark)?;
+            }
+            cw.queue_char(border_style, glyphs.border_vertical)?;
+        }
+        queue_last_title(&mut cw)?;
+        cw.fill(border_style, &SPACE_FILLING)?;
+        //- horizontal line
+        w.queue(cursor::MoveTo(area.left, 1 + area.top))?;
+        let mut cw = CropWriter::new(w, width);
+        for (i, column) in columns.iter().enumerate() {
+            let w = column.cell_width() + if i == 0 { mark_width } else { 0 };
+            cw.queue_g_string(border_style, glyphs.border_cross_line(w + 1))?;
+        }
+        cw.fill(border_style, glyphs.border_filling())?;
+        Ok(())
+    }
+    /// write the rows of the table of a list state, below its head,
+    /// with the selection mark and the scrollbar, the cells of the
+    /// visible items being written by `queue_item`.
+    ///
+    /// The page height of the list is set to the number of rows.
+    pub fn queue_table_rows<F>(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+        mut queue_item: F,
+    ) -> Result<(), ProgramError>
+    where
+        F: FnMut(&mut CropWriter<'_, W>, &T, &ListRow<'_>) -> Result<(), ProgramError>,
+    {
+        let area = &disc.state_area;
+        self.set_page_height(area.height as usize - 2);
+        let styles = &disc.panel_skin.styles;
+        let glyphs = &disc.con.glyphs;
+        let selection_bg = styles.selected_line.get_bg()
+            .unwrap_or(Color::AnsiValue(240));
+        let mut selected_match_style = styles.char_match.clone();
+        selected_match_style.set_bg(selection_bg);
+        let mut selected_border_style = styles.help_table_border.clone();
+        selected_border_style.set_bg(selection_bg);
+        let width = disc.content_width();
+        let scrollbar = disc.scrollbar(area, self.scroll(), self.count());
+        let selection_idx = self.selection_idx();
+        let mut idx = self.scroll();
+        for y in 2..area.height {
+            w.queue(cursor::MoveTo(area.left, y + area.top))?;
+            let selected = selection_idx == idx;
+            let mut cw = CropWriter::new(w, width);
+            let txt_style = if selected { &styles.selected_line } else { &styles.default };
+            if let Some(item) = self.get(idx) {
+                let row = ListRow {
+                    selected,
+                    txt_style,
+                    match_style: if selected { &selected_match_style } else { &styles.char_match },
+                    border_style: if selected { &selected_border_style } else { &styles.help_table_border },
+                    selection_bg,
+                    border: glyphs.border_vertical,
+                    pattern: self.pattern(),
+                };
+                if disc.con.show_selection_mark {
+                    cw.queue_char(txt_style, if selected { glyphs.selection_mark } else { ' ' })?;
+                }
+                queue_item(&mut cw, item, &row)?;
+                idx += 1;
+            }
+            cw.fill(txt_style, &SPACE_FILLING)?;
+            disc.queue_scrollbar(w, y, scrollbar)?;
+        }
+        Ok(())
+    }
+}
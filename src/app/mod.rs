@@ -4,12 +4,18 @@ mod app_state;
 mod cmd_context;
 mod cmd_result;
 mod display_context;
+mod filtered_list;
+mod list_table;
 mod mode;
 mod panel;
 mod panel_id;
 mod panel_purpose;
 mod panel_state;
+mod redraw_throttle;
+mod replay;
+mod root_guard;
 mod selection;
+mod session;
 mod standard_status;
 mod state_type;
 mod status;
@@ -21,12 +27,18 @@ pub use {
     cmd_context::*,
     cmd_result::*,
     display_context::*,
+    filtered_list::FilteredList,
+    list_table::*,
     mode::*,
     panel::Panel,
     panel_id::PanelId,
     panel_purpose::PanelPurpose,
     panel_state::*,
+    redraw_throttle::RedrawThrottle,
+    replay::*,
+    root_guard::RootGuard,
     selection::*,
+    session::*,
     standard_status::StandardStatus,
     state_type::PanelStateType,
     status::Status,
@@ -9,28 +9,51 @@ use {
             W,
             WIDE_STATUS,
             flags_display,
+            invalidate_row_caches,
+            write_text_view,
         },
         errors::ProgramError,
         keys::KEY_FORMAT,
-        skin::PanelSkin,
+        pattern::*,
+        skin::{Glyphs, PanelSkin},
         task_sync::Dam,
         verb::*,
     },
+    bet::BeTree,
     termimad::{
         minimad::{Alignment, Composite},
+        Area,
+        FmtText,
+        TextView,
         TimedEvent,
     },
 };
 
+/// A tab of a panel, with its own stack of states.
+///
+/// The stack and input of the current tab are kept in the panel
+/// itself, its Tab in the panel's list being then empty.
+#[derive(Default)]
+struct Tab {
+    states: Vec<Box<dyn PanelState>>,
+    input: String,
+}
+
 /// A colon on screen containing a stack of states, the top
-/// one being visible
+/// one being visible, and possibly other tabs, each one
+/// with its own stack
 pub struct Panel {
     pub id: PanelId,
     states: Vec<Box<dyn PanelState>>, // stack: the last one is current
+    tabs: Vec<Tab>,
+    tab_idx: usize, // index of the current tab in tabs
     pub areas: Areas,
     status: Status,
     pub purpose: PanelPurpose,
     input: PanelInput,
+    /// the last search mode explicitly chosen, with a mode key,
+    /// in this panel
+    last_search_mode: Option<SearchMode>,
 }
 
 impl Panel {
@@ -47,10 +70,13 @@ impl Panel {
         Self {
             id,
             states: vec![state],
+            tabs: vec![Tab::default()],
+            tab_idx: 0,
             areas,
             status,
             purpose: PanelPurpose::None,
             input,
+            last_search_mode: None,
         }
     }
 
@@ -72,12 +98,16 @@ impl Panel {
         app_cmd_context: &'c AppCmdContext<'c>,
     ) -> Result<CmdResult, ProgramError> {
         let state_idx = self.states.len() - 1;
+        if let Command::PatternEdit { expr, .. } = cmd {
+            self.remember_search_mode(expr, app_cmd_context.con);
+        }
         let cc = CmdContext {
             cmd,
             app: app_cmd_context,
             panel: PanelCmdContext {
                 areas: &self.areas,
                 purpose: self.purpose,
+                search_mode: self.search_mode(app_cmd_context.con),
             },
         };
         let result = self.states[state_idx].on_command(w, app_state, &cc);
@@ -86,6 +116,29 @@ impl Panel {
         result
     }
 
+    /// the search mode of the patterns without mode key: the last
+    /// one explicitly chosen in the panel, or the one configured
+    /// for the purpose of the panel
+    pub fn search_mode(&self, con: &AppContext) -> Option<SearchMode> {
+        self.last_search_mode
+            .or_else(|| con.panel_search_modes.for_purpose(self.purpose))
+    }
+
+    /// if the pattern starts with a mode key, remember its mode
+    fn remember_search_mode(
+        &mut self,
+        expr: &BeTree<PatternOperator, PatternParts>,
+        con: &AppContext,
+    ) {
+        let mode = expr.iter_atoms()
+            .find(|parts| !parts.core().is_empty())
+            .and_then(|parts| parts.mode())
+            .and_then(|key| con.search_modes.search_mode(Some(key)).ok());
+        if mode.is_some() {
+            self.last_search_mode = mode;
+        }
+    }
+
     /// called on focusing the panel and before the display,
     /// this updates the status from the command read in the input
     pub fn refresh_input_status<'c>(
@@ -100,6 +153,7 @@ impl Panel {
             panel: PanelCmdContext {
                 areas: &self.areas,
                 purpose: self.purpose,
+                search_mode: self.search_mode(app_cmd_context.con),
             },
         };
         let has_previous_state = self.states.len() > 1;
@@ -193,6 +247,151 @@ impl Panel {
         }
     }
 
+    pub fn tabs_count(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn tab_idx(&self) -> usize {
+        self.tab_idx
+    }
+
+    /// the state stacks of all the tabs, in order
+    pub fn tab_stacks(&self) -> Vec<&[Box<dyn PanelState>]> {
+        self.tabs.iter()
+            .enumerate()
+            .map(|(idx, tab)| {
+                if idx == self.tab_idx {
+                    self.states.as_slice()
+                } else {
+                    tab.states.as_slice()
+                }
+            })
+            .collect()
+    }
+
+    /// open a new tab, just after the current one, and make it current
+    pub fn add_tab(&mut self, state: Box<dyn PanelState>) {
+        self.stash_current_tab();
+        self.tab_idx += 1;
+        self.tabs.insert(self.tab_idx, Tab::default());
+        self.input.set_content(&state.get_starting_input());
+        self.states.push(state);
+    }
+
+    /// make current the tab at the given index, return false
+    /// if it's not a valid index or already the current tab
+    pub fn select_tab(&mut self, idx: usize) -> bool {
+        if idx >= self.tabs.len() || idx == self.tab_idx {
+            return false;
+        }
+        self.stash_current_tab();
+        self.tab_idx = idx;
+        self.unstash_current_tab();
+        true
+    }
+
+    /// make current the next tab (or the previous one if dir
+    /// is negative), cycling, return false if there's no other tab
+    pub fn move_tab(&mut self, dir: i32) -> bool {
+        let len = self.tabs.len();
+        let idx = if dir < 0 {
+            (self.tab_idx + len - 1) % len
+        } else {
+            (self.tab_idx + 1) % len
+        };
+        self.select_tab(idx)
+    }
+
+    /// close the current tab, return false if it's the only one
+    pub fn close_tab(&mut self) -> bool {
+        if self.tabs.len() < 2 {
+            return false;
+        }
+        self.tabs.remove(self.tab_idx);
+        self.tab_idx = self.tab_idx.min(self.tabs.len() - 1);
+        self.unstash_current_tab();
+        true
+    }
+
+    /// move the stack and input of the current tab to its Tab
+    fn stash_current_tab(&mut self) {
+        let tab = &mut self.tabs[self.tab_idx];
+        tab.states = std::mem::take(&mut self.states);
+        tab.input = self.input.get_content();
+    }
+
+    /// make the stack and input of the Tab at tab_idx the current ones
+    fn unstash_current_tab(&mut self) {
+        let tab = &mut self.tabs[self.tab_idx];
+        self.states = std::mem::take(&mut tab.states);
+        self.input.set_content(&tab.input);
+    }
+
+    /// the name of a tab in the tab bar: the name of the root of
+    /// its current state, or the type of this state
+    fn tab_title(state: &dyn PanelState) -> String {
+        state.tree_root()
+            .map(|root| root.file_name().unwrap_or(root.as_os_str()))
+            .map_or_else(
+                || state.get_type().name().to_string(),
+                |name| name.to_string_lossy().to_string(),
+            )
+    }
+
+    /// the labels of the tabs, as displayed in the tab bar
+    fn tab_labels(&self) -> Vec<String> {
+        self.tabs.iter()
+            .enumerate()
+            .map(|(idx, tab)| {
+                let title = if idx == self.tab_idx {
+                    Self::tab_title(self.state())
+                } else {
+                    tab.states.last()
+                        .map_or_else(String::new, |state| Self::tab_title(state.as_ref()))
+                };
+                format!(" {}:{} ", idx + 1, title)
+            })
+            .collect()
+    }
+
+    /// the index of the tab whose label is at x in the tab bar
+    pub fn tab_at(&self, x: u16) -> Option<usize> {
+        let mut left = self.areas.state.left as usize;
+        for (idx, label) in self.tab_labels().iter().enumerate() {
+            let right = left + label.chars().count();
+            if (x as usize) < right {
+                return Some(idx).filter(|_| x as usize >= left);
+            }
+            left = right + 1; // labels are separated by a space
+        }
+        None
+    }
+
+    /// write the tabs of the panel on the row just above the state area
+    fn write_tab_bar(
+        &self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let area = &self.areas.state;
+        let styles = &disc.panel_skin.styles;
+        disc.screen.goto(w, area.left, area.top - 1)?;
+        let mut width = area.width as usize;
+        for (idx, label) in self.tab_labels().iter().enumerate() {
+            let label: String = label.chars().take(width).collect();
+            width -= label.chars().count();
+            let style = if idx == self.tab_idx { &styles.flag_value } else { &styles.flag_label };
+            style.queue_str(w, &label)?;
+            if width == 0 {
+                return Ok(());
+            }
+            styles.default.queue(w, ' ')?;
+            width -= 1;
+        }
+        styles.default.queue_str(w, " ".repeat(width))?;
+        Ok(())
+    }
+
     /// render the whole panel (state, status, purpose, input, flags)
     pub fn display(
         &mut self,
@@ -200,8 +399,25 @@ impl Panel {
         disc: &DisplayContext,
     ) -> Result<(), ProgramError> {
         self.mut_state().display(w, disc)?;
+        if disc.tab_bar {
+            self.write_tab_bar(w, disc)?;
+        }
+        if disc.active {
+            if let Some(input) = self.input.help_input() {
+                write_input_help(w, input, disc)?;
+                // the rows under the help will have to be rewritten
+                invalidate_row_caches();
+            }
+        }
+        if disc.zen {
+            // no status or input, we just clear the input line
+            let area = &self.areas.input;
+            disc.screen.goto(w, area.left, area.top)?;
+            disc.panel_skin.styles.default.queue_str(w, " ".repeat(area.width as usize))?;
+            return Ok(());
+        }
         if disc.active || !WIDE_STATUS {
-            self.write_status(w, disc.panel_skin, disc.screen)?;
+            self.write_status(w, disc.panel_skin, &disc.con.glyphs, disc.screen)?;
         }
         let mut input_area = self.areas.input.clone();
         if disc.active {
@@ -223,6 +439,7 @@ impl Panel {
         &self,
         w: &mut W,
         panel_skin: &PanelSkin,
+        glyphs: &Glyphs,
         screen: Screen,
     ) -> Result<(), ProgramError> {
         let task = self.state().get_pending_task();
@@ -232,6 +449,7 @@ impl Panel {
             &self.status,
             &self.areas.status,
             panel_skin,
+            glyphs,
             screen,
         )
     }
@@ -277,3 +495,31 @@ impl Panel {
     }
 
 }
+
+/// write the cheat sheet of the input syntax over the bottom
+/// of the state area
+fn write_input_help(
+    w: &mut W,
+    input: &str,
+    disc: &DisplayContext,
+) -> Result<(), ProgramError> {
+    let area = &disc.state_area;
+    if area.width < 4 || area.height < 2 {
+        return Ok(());
+    }
+    let md = crate::help::input_help_md(input, disc.con);
+    let fmt_text = FmtText::from(
+        &disc.panel_skin.help_skin,
+        &md,
+        Some((area.width - 1) as usize),
+    );
+    let height = (fmt_text.lines.len() as u16).min(area.height);
+    let help_area = Area::new(
+        area.left,
+        area.top + area.height - height,
+        area.width,
+        height,
+    );
+    let text_view = TextView::from(&help_area, &fmt_text);
+    write_text_view(w, &text_view, &disc.con.glyphs)
+}
@@ -1,22 +1,33 @@
 use {
     super::*,
     crate::{
+        activity::*,
+        bookmark::{BookmarkStore, BookmarksState},
         command::*,
-        display::{Screen, W},
+        display::{Col, Screen, SizeUnits, W},
         errors::ProgramError,
         flag::Flag,
-        help::HelpState,
+        help::{HelpState, TipsState, VerbChoiceState},
+        launchable::Launchable,
         pattern::*,
         preview::{PreviewMode, PreviewState},
         print,
+        quick_roots::QuickRootsState,
+        report::{ExtensionsReportState, TopReportState, DEFAULT_TOP_COUNT},
         stage::*,
+        tag::{TagStore, MAX_RATING},
         task_sync::Dam,
+        tr,
         tree::*,
         verb::*,
+        versions::VersionsState,
+        watch::WatchState,
     },
     std::{
         path::{Path, PathBuf},
         str::FromStr,
+        sync::Arc,
+        time::Duration,
     },
 };
 
@@ -43,6 +54,17 @@ pub trait PanelState {
     }
 
     fn on_double_click(
+        &mut self,
+        _w: &mut W,
+        _x: u16,
+        _y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        Ok(CmdResult::Keep)
+    }
+
+    fn on_middle_click(
         &mut self,
         _x: u16,
         _y: u16,
@@ -52,6 +74,71 @@ pub trait PanelState {
         Ok(CmdResult::Keep)
     }
 
+    /// called when the mouse moved, with no button pressed, to the
+    /// given screen position (which may be outside the panel)
+    fn on_mouse_move(
+        &mut self,
+        _x: u16,
+        _y: u16,
+        _cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        Ok(CmdResult::Keep)
+    }
+
+    /// return the column displayed at the given screen position, if
+    /// the state has columns and there's one there
+    fn col_at(&self, _x: u16, _y: u16) -> Option<Col> {
+        None
+    }
+
+    /// handle a click, either with the verb bound to it in
+    /// configuration, or with the default behavior of the state
+    fn on_mouse_click(
+        &mut self,
+        w: &mut W,
+        kind: ClickKind,
+        x: u16,
+        y: u16,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let con = &cc.app.con;
+        let screen = cc.app.screen;
+        let col = self.col_at(x, y);
+        // the states handle clicks with rows counted from the top of
+        // the screen, which isn't the one of the terminal when inline
+        let y = y.saturating_sub(screen.top);
+        let invocation = match con.click_bindings.find(kind, col) {
+            Some(invocation) => invocation,
+            None => {
+                return match kind {
+                    ClickKind::Click => self.on_click(x, y, screen, con),
+                    ClickKind::DoubleClick => self.on_double_click(w, x, y, screen, con),
+                    ClickKind::MiddleClick => self.on_middle_click(x, y, screen, con),
+                };
+            }
+        };
+        if kind != ClickKind::DoubleClick {
+            // a double click always come after a simple click at
+            // same position, which already did the selection
+            self.on_click(x, y, screen, con)?;
+        }
+        let sel_info = self.sel_info(app_state);
+        match con.verb_store.search_sel_info(&invocation.name, sel_info) {
+            PrefixSearchResult::Match(_, verb) => {
+                self.execute_verb(
+                    w,
+                    verb,
+                    Some(invocation),
+                    TriggerType::Other,
+                    app_state,
+                    cc,
+                )
+            }
+            _ => Ok(CmdResult::verb_not_found(&invocation.name)),
+        }
+    }
+
     fn on_pattern(
         &mut self,
         _pat: InputPattern,
@@ -70,7 +157,7 @@ pub trait PanelState {
             self.set_mode(mode);
             CmdResult::Keep
         } else {
-            CmdResult::error("modal mode not enabled in configuration")
+            CmdResult::error(tr!(error_modal_disabled))
         }
     }
 
@@ -94,7 +181,7 @@ pub trait PanelState {
     /// behavior to execute
     fn on_internal_generic(
         &mut self,
-        _w: &mut W,
+        w: &mut W,
         internal_exec: &InternalExecution,
         input_invocation: Option<&VerbInvocation>,
         _trigger_type: TriggerType,
@@ -111,7 +198,7 @@ pub trait PanelState {
             Internal::copy_line | Internal::copy_path => {
                 #[cfg(not(feature = "clipboard"))]
                 {
-                    CmdResult::error("Clipboard feature not enabled at compilation")
+                    CmdResult::error(tr!(error_clipboard_disabled))
                 }
                 #[cfg(feature = "clipboard")]
                 {
@@ -119,10 +206,10 @@ pub trait PanelState {
                         let path = path.to_string_lossy().to_string();
                         match terminal_clipboard::set_string(path) {
                             Ok(()) => CmdResult::Keep,
-                            Err(_) => CmdResult::error("Clipboard error while copying path"),
+                            Err(_) => CmdResult::error(tr!(error_clipboard_path)),
                         }
                     } else {
-                        CmdResult::error("Nothing to copy")
+                        CmdResult::error(tr!(error_nothing_to_copy))
                     }
                 }
             }
@@ -135,6 +222,30 @@ pub trait PanelState {
                 panel_ref: PanelReference::Active,
             },
             #[cfg(unix)]
+            Internal::containers => {
+                let containers_state = crate::containers::ContainersState::new(
+                    self.tree_options(),
+                    con,
+                );
+                match containers_state {
+                    Ok(state) => {
+                        let bang = input_invocation
+                            .map(|inv| inv.bang)
+                            .unwrap_or(internal_exec.bang);
+                        if bang && cc.app.preview_panel.is_none() {
+                            CmdResult::NewPanel {
+                                state: Box::new(state),
+                                purpose: PanelPurpose::None,
+                                direction: HDir::Right,
+                            }
+                        } else {
+                            CmdResult::new_state(Box::new(state))
+                        }
+                    }
+                    Err(e) => CmdResult::DisplayError(format!("{}", e)),
+                }
+            }
+            #[cfg(unix)]
             Internal::filesystems => {
                 let fs_state = crate::filesystems::FilesystemState::new(
                     self.selected_path(),
@@ -159,19 +270,209 @@ pub trait PanelState {
                     Err(e) => CmdResult::DisplayError(format!("{}", e)),
                 }
             }
+            #[cfg(unix)]
+            Internal::snapshots => {
+                let path = match self.selected_path() {
+                    Some(path) => path,
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_no_selection)));
+                    }
+                };
+                let snapshots_state = crate::filesystems::SnapshotsState::new(
+                    path,
+                    self.tree_options(),
+                    con,
+                );
+                match snapshots_state {
+                    Ok(state) => {
+                        let bang = input_invocation
+                            .map(|inv| inv.bang)
+                            .unwrap_or(internal_exec.bang);
+                        if bang && cc.app.preview_panel.is_none() {
+                            CmdResult::NewPanel {
+                                state: Box::new(state),
+                                purpose: PanelPurpose::None,
+                                direction: HDir::Right,
+                            }
+                        } else {
+                            CmdResult::new_state(Box::new(state))
+                        }
+                    }
+                    Err(e) => CmdResult::DisplayError(format!("{}", e)),
+                }
+            }
+            #[cfg(unix)]
+            Internal::trash => {
+                let paths: Vec<PathBuf> = match self.sel_info(app_state) {
+                    SelInfo::None => Vec::new(),
+                    SelInfo::One(sel) => vec![sel.path.to_path_buf()],
+                    SelInfo::More(stage) => stage.paths().to_vec(),
+                };
+                if paths.is_empty() {
+                    return Ok(CmdResult::error(tr!(error_no_selection_to_trash)));
+                }
+                if app_state.dry_run {
+                    let paths: Vec<String> = paths.iter()
+                        .map(|path| format!("`{}`", path.to_string_lossy()))
+                        .collect();
+                    return Ok(CmdResult::message(tr!(
+                        status_dry_run_trash,
+                        paths = paths.join(", "),
+                    )));
+                }
+                let trash = match crate::trash::TrashDir::home() {
+                    Some(trash) => trash,
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_no_trash_home)));
+                    }
+                };
+                let mut res = CmdResult::RefreshState { clear_cache: true };
+                for path in &paths {
+                    if let Err(e) = trash.trash(path) {
+                        res = CmdResult::error(tr!(
+                            error_not_trashed,
+                            path = path.to_string_lossy(),
+                            error = e,
+                        ));
+                        break;
+                    }
+                }
+                app_state.stage.refresh();
+                res
+            }
+            #[cfg(unix)]
+            Internal::open_trash => {
+                match crate::trash::TrashState::new(self.tree_options(), con) {
+                    Ok(state) => {
+                        let bang = input_invocation
+                            .map(|inv| inv.bang)
+                            .unwrap_or(internal_exec.bang);
+                        if bang && cc.app.preview_panel.is_none() {
+                            CmdResult::NewPanel {
+                                state: Box::new(state),
+                                purpose: PanelPurpose::None,
+                                direction: HDir::Right,
+                            }
+                        } else {
+                            CmdResult::new_state(Box::new(state))
+                        }
+                    }
+                    Err(e) => CmdResult::DisplayError(format!("{}", e)),
+                }
+            }
+            Internal::versions => {
+                if con.backup_locations.is_empty() {
+                    return Ok(CmdResult::error(tr!(error_no_backup_location)));
+                }
+                match self.selection() {
+                    Some(sel) if sel.stype == SelectionType::File => {
+                        let state = VersionsState::new(sel.path, self.tree_options(), con);
+                        let bang = input_invocation
+                            .map(|inv| inv.bang)
+                            .unwrap_or(internal_exec.bang);
+                        if bang && cc.app.preview_panel.is_none() {
+                            CmdResult::NewPanel {
+                                state: Box::new(state),
+                                purpose: PanelPurpose::None,
+                                direction: HDir::Right,
+                            }
+                        } else {
+                            CmdResult::new_state(Box::new(state))
+                        }
+                    }
+                    _ => CmdResult::error(tr!(error_verb_needs_file)),
+                }
+            }
+            Internal::report_extensions | Internal::report_top => {
+                // the report is on the root, or on the selected directory
+                // when not in a tree
+                let root = match self.get_type() {
+                    PanelStateType::Tree => app_state.root.clone(),
+                    _ => match self.selection() {
+                        Some(sel) if sel.stype == SelectionType::Directory => sel.path.to_path_buf(),
+                        _ => app_state.root.clone(),
+                    },
+                };
+                let state: Box<dyn PanelState> = if internal_exec.internal == Internal::report_top {
+                    let count = get_arg(input_invocation, internal_exec, DEFAULT_TOP_COUNT);
+                    if count == 0 {
+                        return Ok(CmdResult::error(tr!(error_file_count_not_positive)));
+                    }
+                    Box::new(TopReportState::new(&root, count, self.tree_options(), con))
+                } else {
+                    Box::new(ExtensionsReportState::new(&root, self.tree_options(), con))
+                };
+                let bang = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                if bang && cc.app.preview_panel.is_none() {
+                    CmdResult::NewPanel {
+                        state,
+                        purpose: PanelPurpose::None,
+                        direction: HDir::Right,
+                    }
+                } else {
+                    CmdResult::new_state(state)
+                }
+            }
+            Internal::tips => {
+                let state = Box::new(TipsState::new(self.tree_options(), con));
+                let bang = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                if bang && cc.app.preview_panel.is_none() {
+                    CmdResult::NewPanel {
+                        state,
+                        purpose: PanelPurpose::None,
+                        direction: HDir::Right,
+                    }
+                } else {
+                    CmdResult::new_state(state)
+                }
+            }
+            Internal::quick_roots => {
+                let state = Box::new(QuickRootsState::new(self.tree_options(), con));
+                let bang = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                if bang && cc.app.preview_panel.is_none() {
+                    CmdResult::NewPanel {
+                        state,
+                        purpose: PanelPurpose::None,
+                        direction: HDir::Right,
+                    }
+                } else {
+                    CmdResult::new_state(state)
+                }
+            }
+            Internal::reindex => {
+                if !con.file_index {
+                    CmdResult::error(tr!(error_file_index_disabled))
+                } else if crate::file_index::index_in_background(app_state.root.clone()) {
+                    CmdResult::message(tr!(status_indexing))
+                } else {
+                    CmdResult::error(tr!(error_root_already_indexed))
+                }
+            }
+            Internal::restore_version => {
+                CmdResult::error(tr!(error_not_versions_panel))
+            }
+            Internal::restore_trashed | Internal::purge_trashed => {
+                CmdResult::error(tr!(error_not_trash_panel))
+            }
             Internal::help => {
                 let bang = input_invocation
                     .map(|inv| inv.bang)
                     .unwrap_or(internal_exec.bang);
                 if bang && cc.app.preview_panel.is_none() {
                     CmdResult::NewPanel {
-                        state: Box::new(HelpState::new(self.tree_options(), screen, con)),
+                        state: Box::new(HelpState::new(self.tree_options(), screen, &app_state.verb_usage, con)),
                         purpose: PanelPurpose::None,
                         direction: HDir::Right,
                     }
                 } else {
                     CmdResult::new_state(Box::new(
-                            HelpState::new(self.tree_options(), screen, con)
+                            HelpState::new(self.tree_options(), screen, &app_state.verb_usage, con)
                     ))
                 }
             }
@@ -181,14 +482,49 @@ pub trait PanelState {
                 if let Some(selection) = self.selection() {
                     selection.to_opener(con)?
                 } else {
-                    CmdResult::error("no selection to open")
+                    CmdResult::error(tr!(error_no_selection_to_open))
                 }
             }
-            Internal::open_preview => self.open_preview(None, false, cc),
-            Internal::preview_image => self.open_preview(Some(PreviewMode::Image), false, cc),
-            Internal::preview_text => self.open_preview(Some(PreviewMode::Text), false, cc),
-            Internal::preview_binary => self.open_preview(Some(PreviewMode::Hex), false, cc),
-            Internal::toggle_preview => self.open_preview(None, true, cc),
+            Internal::open_preview => self.open_preview(None, false, false, cc),
+            Internal::reveal => {
+                if let Some(path) = self.selected_path() {
+                    match crate::reveal::reveal(path) {
+                        Ok(()) => CmdResult::Keep,
+                        Err(e) => CmdResult::DisplayError(format!("{}", e)),
+                    }
+                } else {
+                    CmdResult::error(tr!(error_no_selection_to_reveal))
+                }
+            }
+            Internal::rename_all => {
+                let paths: Vec<PathBuf> = if app_state.stage.is_empty() {
+                    self.selected_path().map(|p| p.to_path_buf()).into_iter().collect()
+                } else {
+                    app_state.stage.paths().to_vec()
+                };
+                if paths.is_empty() {
+                    return Ok(CmdResult::error(tr!(error_no_path_to_rename)));
+                }
+                match crate::stage::edit_renames(w, &paths, con)? {
+                    Ok(plan) if plan.is_empty() => CmdResult::message(tr!(status_nothing_renamed)),
+                    Ok(plan) => {
+                        if let Err(e) = plan.execute() {
+                            return Ok(CmdResult::error(tr!(error_nothing_renamed, error = e)));
+                        }
+                        for (src, dst) in &plan.renames {
+                            app_state.stage.replace(src, dst.clone());
+                        }
+                        info!("renamed {} paths", plan.renames.len());
+                        CmdResult::RefreshState { clear_cache: true }
+                    }
+                    Err(conflict) => CmdResult::error(tr!(error_nothing_renamed, error = conflict)),
+                }
+            }
+            Internal::preview_image => self.open_preview(Some(PreviewMode::Image), false, false, cc),
+            Internal::preview_text => self.open_preview(Some(PreviewMode::Text), false, false, cc),
+            Internal::preview_binary => self.open_preview(Some(PreviewMode::Hex), false, false, cc),
+            Internal::preview_tail => self.open_preview(Some(PreviewMode::Text), false, true, cc),
+            Internal::toggle_preview => self.open_preview(None, true, false, cc),
             Internal::sort_by_count => self.with_new_options(
                 screen,
                 &|o| {
@@ -259,6 +595,20 @@ pub trait PanelState {
                 bang,
                 con,
             ),
+            Internal::sort_by_rating => self.with_new_options(
+                screen,
+                &|o| {
+                    if o.sort == Sort::Rating {
+                        o.sort = Sort::None;
+                        "*not sorting anymore*"
+                    } else {
+                        o.sort = Sort::Rating;
+                        "*now sorting by star rating*"
+                    }
+                },
+                bang,
+                con,
+            ),
             Internal::sort_by_type_dirs_first => self.with_new_options(
                 screen,
                 &|o| {
@@ -287,6 +637,54 @@ pub trait PanelState {
                 bang,
                 con,
             ),
+            Internal::sort_by_ext => self.with_new_options(
+                screen,
+                &|o| toggle_sort(o, Sort::Extension, "*now sorting by extension*"),
+                bang,
+                con,
+            ),
+            Internal::sort_by_ext_reversed => self.with_new_options(
+                screen,
+                &|o| toggle_sort(o, Sort::ExtensionReversed, "*now sorting by extension, in reverse order*"),
+                bang,
+                con,
+            ),
+            Internal::sort_by_ctime => self.with_new_options(
+                screen,
+                &|o| toggle_sort(o, Sort::Ctime, "*now sorting by last status change, most recent first*"),
+                bang,
+                con,
+            ),
+            Internal::sort_by_ctime_reversed => self.with_new_options(
+                screen,
+                &|o| toggle_sort(o, Sort::CtimeReversed, "*now sorting by last status change, oldest first*"),
+                bang,
+                con,
+            ),
+            Internal::sort_by_atime => self.with_new_options(
+                screen,
+                &|o| toggle_sort(o, Sort::Atime, "*now sorting by last access, most recent first*"),
+                bang,
+                con,
+            ),
+            Internal::sort_by_atime_reversed => self.with_new_options(
+                screen,
+                &|o| toggle_sort(o, Sort::AtimeReversed, "*now sorting by last access, oldest first*"),
+                bang,
+                con,
+            ),
+            Internal::sort_by_owner => self.with_new_options(
+                screen,
+                &|o| toggle_sort(o, Sort::Owner, "*now sorting by owner*"),
+                bang,
+                con,
+            ),
+            Internal::sort_by_owner_reversed => self.with_new_options(
+                screen,
+                &|o| toggle_sort(o, Sort::OwnerReversed, "*now sorting by owner, in reverse order*"),
+                bang,
+                con,
+            ),
             Internal::no_sort => self.with_new_options(
                 screen,
                 &|o| {
@@ -315,30 +713,90 @@ pub trait PanelState {
 					con,
 				)
             }
-            Internal::toggle_dates => {
+            Internal::toggle_dates => {
+                self.with_new_options(
+					screen,
+					&|o| {
+						o.show_dates ^= true;
+                        if o.show_dates {
+                            "*displaying last modified dates*"
+                        } else {
+                            "*hiding last modified dates*"
+                        }
+					},
+					bang,
+					con,
+				)
+            }
+            Internal::toggle_device_id => {
+                self.with_new_options(
+					screen,
+					&|o| {
+						o.show_device_id ^= true;
+                        if o.show_device_id {
+                            "*displaying device id*"
+                        } else {
+                            "*hiding device id*"
+                        }
+					},
+					bang,
+					con,
+				)
+            }
+            Internal::toggle_lint => {
+                self.with_new_options(
+					screen,
+					&|o| {
+						o.show_lint ^= true;
+                        if o.show_lint {
+                            "*displaying lint probe results*"
+                        } else {
+                            "*hiding lint probe results*"
+                        }
+					},
+					bang,
+					con,
+				)
+            }
+            Internal::toggle_scores => {
+                self.with_new_options(
+					screen,
+					&|o| {
+						o.show_scores ^= true;
+                        if o.show_scores {
+                            "*displaying search scores*"
+                        } else {
+                            "*hiding search scores*"
+                        }
+					},
+					bang,
+					con,
+				)
+            }
+            Internal::toggle_row_numbers => {
                 self.with_new_options(
 					screen,
 					&|o| {
-						o.show_dates ^= true;
-                        if o.show_dates {
-                            "*displaying last modified dates*"
+						o.show_row_numbers ^= true;
+                        if o.show_row_numbers {
+                            "*numbering the visible rows, use `:goto <number>` to select one*"
                         } else {
-                            "*hiding last modified dates*"
+                            "*hiding row numbers*"
                         }
 					},
 					bang,
 					con,
 				)
             }
-            Internal::toggle_device_id => {
+            Internal::toggle_match_lines => {
                 self.with_new_options(
 					screen,
 					&|o| {
-						o.show_device_id ^= true;
-                        if o.show_device_id {
-                            "*displaying device id*"
+						o.show_match_lines ^= true;
+                        if o.show_match_lines {
+                            "*writing the matching lines under content search hits*"
                         } else {
-                            "*hiding device id*"
+                            "*hiding the matching lines of content search hits*"
                         }
 					},
 					bang,
@@ -449,6 +907,50 @@ pub trait PanelState {
 					con,
 				)
             }
+            Internal::toggle_perm_mode => self.with_new_options(
+                screen,
+                &|o| toggle_permission_part(
+                    o,
+                    |p| &mut p.mode,
+                    "*displaying the mode*",
+                    "*removing the mode*",
+                ),
+                bang,
+                con,
+            ),
+            Internal::toggle_perm_owner => self.with_new_options(
+                screen,
+                &|o| toggle_permission_part(
+                    o,
+                    |p| &mut p.owner,
+                    "*displaying the owner*",
+                    "*removing the owner*",
+                ),
+                bang,
+                con,
+            ),
+            Internal::toggle_perm_group => self.with_new_options(
+                screen,
+                &|o| toggle_permission_part(
+                    o,
+                    |p| &mut p.group,
+                    "*displaying the group*",
+                    "*removing the group*",
+                ),
+                bang,
+                con,
+            ),
+            Internal::toggle_perm_acl => self.with_new_options(
+                screen,
+                &|o| toggle_permission_part(
+                    o,
+                    |p| &mut p.acl,
+                    "*displaying the ACL marker*",
+                    "*removing the ACL marker*",
+                ),
+                bang,
+                con,
+            ),
             Internal::toggle_sizes => self.with_new_options(
                 screen,
                 &|o| {
@@ -465,6 +967,63 @@ pub trait PanelState {
                 bang,
                 con,
             ),
+            Internal::toggle_exact_sizes => self.with_new_options(
+                screen,
+                &|o| {
+                    o.size_format.exact ^= true;
+                    if o.size_format.exact {
+                        "*now writing sizes as exact byte counts*"
+                    } else {
+                        "*now writing abbreviated sizes*"
+                    }
+                },
+                bang,
+                con,
+            ),
+            Internal::toggle_size_units => self.with_new_options(
+                screen,
+                &|o| {
+                    if o.size_format.units == SizeUnits::Si {
+                        o.size_format.units = SizeUnits::Binary;
+                        "*now writing sizes in binary units (KiB, MiB, ...)*"
+                    } else {
+                        o.size_format.units = SizeUnits::Si;
+                        "*now writing sizes in SI units (KB, MB, ...)*"
+                    }
+                },
+                bang,
+                con,
+            ),
+            Internal::toggle_flat => {
+                self.with_new_options(
+                    screen,
+                    &|o| {
+                        o.flat ^= true;
+                        if o.flat {
+                            "*now listing the files without their directories*"
+                        } else {
+                            "*now displaying the tree*"
+                        }
+                    },
+                    bang,
+                    con,
+                )
+            }
+            Internal::toggle_miller => {
+                self.with_new_options(
+                    screen,
+                    &|o| {
+                        o.miller ^= true;
+                        if o.miller {
+                            "*now showing the parent and selected directories in columns*"
+                        } else {
+                            "*now displaying the tree alone*"
+                        }
+                    },
+                    bang,
+                    con,
+                )
+            }
             Internal::toggle_trim_root => {
                 self.with_new_options(
 					screen,
@@ -511,8 +1070,69 @@ pub trait PanelState {
                 }
             }
             Internal::stage => self.stage(app_state, cc, con),
+            Internal::stage_export => {
+                let arg = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref());
+                let (separator, arg) = stage_list_separator(arg.map_or("", |arg| arg.trim()));
+                if arg.is_empty() {
+                    return Ok(CmdResult::error(tr!(error_stage_export_expected_path)));
+                }
+                if app_state.stage.is_empty() {
+                    return Ok(CmdResult::error(tr!(error_stage_empty)));
+                }
+                let base = self.selected_path().unwrap_or(&app_state.root);
+                let path = crate::path::path_from(base, crate::path::PathAnchor::Directory, arg);
+                std::fs::write(&path, app_state.stage.paths_string(separator))?;
+                CmdResult::message(tr!(
+                    status_stage_exported,
+                    count = app_state.stage.len(),
+                    path = path.to_string_lossy(),
+                ))
+            }
+            Internal::stage_pipe => {
+                let arg = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref());
+                let (separator, command) = stage_list_separator(arg.map_or("", |arg| arg.trim()));
+                let parts: Vec<String> = splitty::split_unquoted_whitespace(command)
+                    .unwrap_quotes(true)
+                    .map(|part| part.to_string())
+                    .collect();
+                if parts.is_empty() {
+                    return Ok(CmdResult::error(tr!(error_stage_pipe_expected_command)));
+                }
+                if app_state.stage.is_empty() {
+                    return Ok(CmdResult::error(tr!(error_stage_empty)));
+                }
+                if app_state.dry_run {
+                    return Ok(CmdResult::message(tr!(status_dry_run_command, command = command)));
+                }
+                let launchable = Launchable::program(parts, None, Sandbox::default(), con)?
+                    .with_stdin(app_state.stage.paths_string(separator));
+                match launchable.execute(Some(w)) {
+                    Ok(()) => CmdResult::RefreshState { clear_cache: true },
+                    Err(e) => CmdResult::error(e.to_string()),
+                }
+            }
             Internal::unstage => self.unstage(app_state, cc, con),
             Internal::toggle_stage => self.toggle_stage(app_state, cc, con),
+            Internal::toggle_mark => {
+                if let Some(path) = self.selected_path() {
+                    if app_state.marks.contains(path) {
+                        app_state.marks.remove(path);
+                    } else {
+                        app_state.marks.add(path.to_path_buf());
+                    }
+                    CmdResult::Keep
+                } else {
+                    CmdResult::error(tr!(error_no_selection))
+                }
+            }
+            Internal::clear_marks => {
+                app_state.marks.clear();
+                CmdResult::Keep
+            }
             Internal::close_staging_area => {
                 if let Some(id) = cc.app.stage_panel {
                     CmdResult::ClosePanel {
@@ -549,6 +1169,285 @@ pub trait PanelState {
                 }
             }
             Internal::set_syntax_theme => CmdResult::HandleInApp(Internal::set_syntax_theme),
+            Internal::preview_theme => CmdResult::HandleInApp(Internal::preview_theme),
+            Internal::toggle_zen => CmdResult::HandleInApp(Internal::toggle_zen),
+            Internal::new_tab => CmdResult::HandleInApp(Internal::new_tab),
+            Internal::close_tab => CmdResult::HandleInApp(Internal::close_tab),
+            Internal::next_tab => CmdResult::HandleInApp(Internal::next_tab),
+            Internal::previous_tab => CmdResult::HandleInApp(Internal::previous_tab),
+            Internal::tag => {
+                let arg = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref());
+                let (action, tag) = match arg.and_then(|arg| arg.trim().split_once(' ')) {
+                    Some((action, tag)) => (action, tag.trim()),
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_tag_expected_action)));
+                    }
+                };
+                if !TagStore::is_valid_tag(tag) {
+                    return Ok(CmdResult::error(tr!(error_tag_spaces)));
+                }
+                let path = match self.selected_path() {
+                    Some(path) => path.to_path_buf(),
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_no_selected_file)));
+                    }
+                };
+                let changed = match action {
+                    "add" => app_state.tags.add(&path, tag),
+                    "remove" | "rm" => app_state.tags.remove(&path, tag),
+                    _ => {
+                        return Ok(CmdResult::error(tr!(error_unknown_tag_action, action = format!("{action:?}"))));
+                    }
+                };
+                if changed {
+                    app_state.tags.save()?;
+                }
+                CmdResult::Keep
+            }
+            Internal::rate => {
+                let arg = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref());
+                let rating = match arg.and_then(|arg| arg.trim().parse::<u8>().ok()) {
+                    Some(rating) if rating <= MAX_RATING => rating,
+                    _ => {
+                        return Ok(CmdResult::error(tr!(error_rate_expected_rating, max = MAX_RATING)));
+                    }
+                };
+                let path = match self.selected_path() {
+                    Some(path) => path.to_path_buf(),
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_no_selected_file)));
+                    }
+                };
+                if app_state.tags.set_rating(&path, rating) {
+                    app_state.tags.save()?;
+                }
+                if self.tree_options().sort == Sort::Rating {
+                    // the order of the files may have changed
+                    CmdResult::RefreshState { clear_cache: false }
+                } else {
+                    CmdResult::Keep
+                }
+            }
+            Internal::toggle_dry_run => {
+                app_state.dry_run = !app_state.dry_run;
+                CmdResult::message(if app_state.dry_run {
+                    tr!(status_dry_run_on)
+                } else {
+                    tr!(status_dry_run_off)
+                })
+            }
+            Internal::escalate => {
+                if app_state.pending_escalation.is_empty() {
+                    CmdResult::error(tr!(error_nothing_to_escalate))
+                } else {
+                    for launchable in app_state.pending_escalation.drain(..) {
+                        info!("Executing escalated launchable {:?}", launchable);
+                        if let Err(e) = launchable.execute(Some(w)) {
+                            warn!("escalated launchable failed : {:?}", e);
+                            return Ok(CmdResult::error(e.to_string()));
+                        }
+                    }
+                    CmdResult::RefreshState { clear_cache: true }
+                }
+            }
+            Internal::resume_operation => match OpJournal::find_interrupted() {
+                Some(journal) => match journal.resume(w, con) {
+                    Ok(count) => {
+                        info!("resumed {} operations", count);
+                        CmdResult::RefreshState { clear_cache: true }
+                    }
+                    Err(e) => CmdResult::error(e.to_string()),
+                },
+                None => CmdResult::error(tr!(error_no_interrupted_operation)),
+            },
+            Internal::rollback_operation => match OpJournal::find_interrupted() {
+                Some(journal) => {
+                    let failures = journal.rollback();
+                    if failures == 0 {
+                        CmdResult::RefreshState { clear_cache: true }
+                    } else {
+                        CmdResult::error(tr!(
+                            error_rollback_failures,
+                            count = failures,
+                        ))
+                    }
+                }
+                None => CmdResult::error(tr!(error_no_interrupted_operation)),
+            },
+            Internal::discard_operation => match OpJournal::find_interrupted() {
+                Some(journal) => {
+                    journal.finish();
+                    CmdResult::Keep
+                }
+                None => CmdResult::error(tr!(error_no_interrupted_operation)),
+            },
+            Internal::history_verbs => {
+                let state = VerbLogState::new(&app_state.verb_log, self.tree_options(), con);
+                CmdResult::new_state(Box::new(state))
+            }
+            Internal::export_verb_log => {
+                let arg = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref());
+                let arg = match arg.map(|arg| arg.trim()).filter(|arg| !arg.is_empty()) {
+                    Some(arg) => arg,
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_verb_log_expected_path)));
+                    }
+                };
+                let base = self.selected_path().unwrap_or(&app_state.root);
+                let path = crate::path::path_from(base, crate::path::PathAnchor::Directory, arg);
+                std::fs::write(&path, app_state.verb_log.to_string())?;
+                CmdResult::Keep
+            }
+            Internal::reset_verb_usage => {
+                app_state.verb_usage.clear();
+                app_state.verb_usage.save()?;
+                CmdResult::message(tr!(status_verb_usage_forgotten))
+            }
+            Internal::bookmark => {
+                let path = match self.selected_path() {
+                    Some(path) => path.to_path_buf(),
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_no_selected_file)));
+                    }
+                };
+                let name = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref())
+                    .map(|arg| arg.trim())
+                    .filter(|arg| !arg.is_empty())
+                    .map_or_else(|| BookmarkStore::default_name(&path), |arg| arg.to_string());
+                if !BookmarkStore::is_valid_name(&name) {
+                    return Ok(CmdResult::error(tr!(error_bookmark_spaces)));
+                }
+                let mut store = BookmarkStore::load();
+                if store.add(&path, &name) {
+                    store.save()?;
+                }
+                CmdResult::message(tr!(status_bookmarked, name = name))
+            }
+            Internal::unbookmark => {
+                let path = match self.selected_path() {
+                    Some(path) => path.to_path_buf(),
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_no_selected_file)));
+                    }
+                };
+                let mut store = BookmarkStore::load();
+                if store.remove_path(&path) {
+                    store.save()?;
+                    CmdResult::Keep
+                } else {
+                    CmdResult::error(tr!(error_not_bookmarked))
+                }
+            }
+            Internal::bookmarks => {
+                let state = BookmarksState::new(&BookmarkStore::load(), self.tree_options(), con);
+                let bang = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                if bang && cc.app.preview_panel.is_none() {
+                    CmdResult::NewPanel {
+                        state: Box::new(state),
+                        purpose: PanelPurpose::None,
+                        direction: HDir::Right,
+                    }
+                } else {
+                    CmdResult::new_state(Box::new(state))
+                }
+            }
+            Internal::go_to_bookmark => {
+                let name = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref())
+                    .map_or("", |arg| arg.trim());
+                if name.is_empty() {
+                    return Ok(CmdResult::error(tr!(error_bookmark_expected_name)));
+                }
+                let bang = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                match BookmarkStore::load().find(name) {
+                    Some(path) => internal_focus::on_path(
+                        path.to_path_buf(),
+                        screen,
+                        self.tree_options().without_pattern(),
+                        bang,
+                        con,
+                    ),
+                    None => CmdResult::error(tr!(error_unknown_bookmark, name = format!("{name:?}"))),
+                }
+            }
+            Internal::watch => {
+                let path = match self.selected_path() {
+                    Some(path) => path.to_path_buf(),
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_no_selected_file)));
+                    }
+                };
+                let state = WatchState::new(path, self.tree_options(), con);
+                let bang = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                if bang && cc.app.preview_panel.is_none() {
+                    CmdResult::NewPanel {
+                        state: Box::new(state),
+                        purpose: PanelPurpose::None,
+                        direction: HDir::Right,
+                    }
+                } else {
+                    CmdResult::new_state(Box::new(state))
+                }
+            }
+            Internal::toggle_watch => CmdResult::error(tr!(error_not_watch_panel)),
+            Internal::skip_conflict
+            | Internal::overwrite_conflict
+            | Internal::rename_conflict
+            | Internal::skip_all_conflicts
+            | Internal::overwrite_all_conflicts
+            | Internal::rename_all_conflicts
+            | Internal::diff_conflict => CmdResult::error(tr!(error_not_conflicts_panel)),
+            Internal::tags => {
+                let state = crate::tag::TagsState::new(&app_state.tags, self.tree_options(), con);
+                let bang = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                if bang && cc.app.preview_panel.is_none() {
+                    CmdResult::NewPanel {
+                        state: Box::new(state),
+                        purpose: PanelPurpose::None,
+                        direction: HDir::Right,
+                    }
+                } else {
+                    CmdResult::new_state(Box::new(state))
+                }
+            }
+            Internal::set_preview_encoding => match cc.app.preview_panel {
+                Some(id) => CmdResult::ApplyOnPanel { id },
+                None => CmdResult::error(tr!(error_no_preview)),
+            },
+            Internal::compare_images => match cc.app.preview_panel {
+                Some(id) => CmdResult::ApplyOnPanel { id },
+                None => match PreviewState::new_comparison(app_state, self.tree_options(), con) {
+                    Ok(state) => CmdResult::NewPanel {
+                        state: Box::new(state),
+                        purpose: PanelPurpose::Preview,
+                        direction: HDir::Right,
+                    },
+                    Err(e) => CmdResult::error(e),
+                },
+            },
+            Internal::toggle_tail_follow => match cc.app.preview_panel {
+                Some(id) => CmdResult::ApplyOnPanel { id },
+                None => CmdResult::error(tr!(error_no_preview)),
+            },
+            Internal::panel_grow => CmdResult::HandleInApp(Internal::panel_grow),
+            Internal::panel_shrink => CmdResult::HandleInApp(Internal::panel_shrink),
             Internal::print_path => print::print_paths(self.sel_info(app_state), con)?,
             Internal::print_relative_path => print::print_relative_paths(self.sel_info(app_state), con)?,
             Internal::refresh => CmdResult::RefreshState { clear_cache: true },
@@ -612,7 +1511,7 @@ pub trait PanelState {
                 self.stage(app_state, cc, con)
             }
         } else {
-            CmdResult::error("no selection")
+            CmdResult::error(tr!(error_no_selection))
         }
     }
 
@@ -626,22 +1525,47 @@ pub trait PanelState {
         cc: &CmdContext,
     ) -> Result<CmdResult, ProgramError> {
         if verb.needs_selection && !self.has_at_least_one_selection(app_state) {
-            return Ok(CmdResult::error("This verb needs a selection"));
+            return Ok(CmdResult::error(tr!(error_verb_needs_selection)));
         }
         if verb.needs_another_panel && app_state.other_panel_path.is_none() {
-            return Ok(CmdResult::error("This verb needs another panel"));
+            return Ok(CmdResult::error(tr!(error_verb_needs_other_panel)));
         }
+        if !cc.app.con.is_allowed_verb(verb) || (verb.mutating && self.is_read_only(app_state)) {
+            return Ok(CmdResult::error(tr!(
+                error_disabled_read_only,
+                verb = verb.names.first().map_or("this verb", |n| n.as_str()),
+            )));
+        }
+        // internals triggered by a key are mostly navigation, they're
+        // not worth recording
+        let mut log_entry = match (&verb.execution, trigger_type) {
+            (VerbExecution::Internal(_), TriggerType::Other) => None,
+            _ => {
+                let command = invocation
+                    .map_or_else(|| format!(":{}", verb.names[0]), |inv| inv.to_string());
+                Some(VerbLogEntry::new(command, self.sel_info(app_state)))
+            }
+        };
         let res = match &verb.execution {
-            VerbExecution::Internal(internal_exec) => {
-                self.on_internal(
+            VerbExecution::Internal(internal_exec) => match verb.target {
+                Some(target) => self.execute_internal_on_target(
                     w,
+                    target,
                     internal_exec,
                     invocation,
                     trigger_type,
                     app_state,
                     cc,
-                )
-            }
+                ),
+                None => self.on_internal(
+                    w,
+                    internal_exec,
+                    invocation,
+                    trigger_type,
+                    app_state,
+                    cc,
+                ),
+            },
             VerbExecution::External(external) => {
                 self.execute_external(w, verb, external, invocation, app_state, cc)
             }
@@ -649,10 +1573,33 @@ pub trait PanelState {
                 self.execute_sequence(w, verb, seq_ex, invocation, app_state, cc)
             }
         };
+        if let Some(mut entry) = log_entry.take() {
+            entry.outcome = VerbOutcome::from_result(&res);
+            if app_state.dry_run && entry.outcome == VerbOutcome::Done {
+                if let VerbExecution::External(_) = verb.execution {
+                    entry.outcome = VerbOutcome::DryRun;
+                }
+            }
+            if let Some(events) = app_state.events.as_mut() {
+                events.send(&ActivityEvent::Verb {
+                    command: &entry.command,
+                    paths: &entry.paths,
+                    outcome: entry.outcome.to_string(),
+                });
+            }
+            if entry.outcome == VerbOutcome::Done {
+                app_state.verb_usage.record(&verb.names[0], chrono::Utc::now());
+                if let Err(e) = app_state.verb_usage.save() {
+                    warn!("failed to save verb usage: {}", e);
+                }
+            }
+            app_state.verb_log.add(entry);
+        }
         if res.is_ok() {
             // if the stage has been emptied by the operation (eg a "rm"), we
             // close it
             app_state.stage.refresh();
+            app_state.marks.refresh();
             if app_state.stage.is_empty() {
                 if let Some(id) = cc.app.stage_panel {
                     return Ok(CmdResult::ClosePanel {
@@ -665,6 +1612,55 @@ pub trait PanelState {
         res
     }
 
+    /// execute an internal based verb whose definition tells
+    /// where its result must appear
+    #[allow(clippy::too_many_arguments)]
+    fn execute_internal_on_target(
+        &mut self,
+        w: &mut W,
+        target: VerbTarget,
+        internal_exec: &InternalExecution,
+        invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let bang = match target {
+            VerbTarget::Preview if self.get_type() == PanelStateType::Preview => {
+                // we're already in the preview
+                return self.on_internal(w, internal_exec, invocation, trigger_type, app_state, cc);
+            }
+            VerbTarget::Preview => {
+                return Ok(match cc.app.preview_panel {
+                    Some(id) => CmdResult::ApplyOnPanel { id },
+                    None => CmdResult::error(tr!(error_verb_needs_preview)),
+                });
+            }
+            VerbTarget::Replace => false,
+            VerbTarget::NewPanelLeft | VerbTarget::NewPanelRight => true,
+        };
+        let mut internal_exec = internal_exec.clone();
+        internal_exec.bang = bang;
+        let invocation = invocation.map(|invocation| VerbInvocation {
+            bang,
+            ..invocation.clone()
+        });
+        let mut res = self.on_internal(
+            w,
+            &internal_exec,
+            invocation.as_ref(),
+            trigger_type,
+            app_state,
+            cc,
+        )?;
+        if let CmdResult::NewPanel { direction, .. } = &mut res {
+            if let Some(new_panel_direction) = target.new_panel_direction() {
+                *direction = new_panel_direction;
+            }
+        }
+        Ok(res)
+    }
+
     fn execute_external(
         &mut self,
         w: &mut W,
@@ -674,7 +1670,25 @@ pub trait PanelState {
         app_state: &mut AppState,
         cc: &CmdContext,
     ) -> Result<CmdResult, ProgramError> {
-        let sel_info = self.sel_info(app_state);
+        // verbs with a {marked} group are executed once per marked path
+        let sel_info = if external_execution.exec_pattern.has_marked_group() {
+            match app_state.marks.paths() {
+                [] => {
+                    return Ok(CmdResult::error(tr!(error_no_marked_path)));
+                }
+                [path] => SelInfo::One(Selection {
+                    path,
+                    line: 0,
+                    line_range: None,
+                    text_view: None,
+                    stype: SelectionType::from(path),
+                    is_exe: false,
+                }),
+                _ => SelInfo::More(&app_state.marks),
+            }
+        } else {
+            self.sel_info(app_state)
+        };
         if let Some(invocation) = &invocation {
             if let Some(error) = verb.check_args(sel_info, invocation, &app_state.other_panel_path) {
                 debug!("verb.check_args prevented execution: {:?}", &error);
@@ -691,7 +1705,28 @@ pub trait PanelState {
                 None
             },
         );
-        external_execution.to_cmd_result(w, exec_builder, cc.app.con)
+        if app_state.dry_run {
+            let cmd = external_execution.dry_run_string(&exec_builder);
+            return Ok(CmdResult::message(tr!(status_dry_run_command, command = cmd)));
+        }
+        // operations denied for lack of permission may be retried
+        // with the escalation command, after confirmation
+        let mut denied = Vec::new();
+        let escalable = verb.mutating && cc.app.con.escalation_command.is_some();
+        // batches of mutating verbs are journaled so that they
+        // can be recovered if broot is killed in the middle
+        let journal_command = verb.mutating.then(|| {
+            invocation.map_or_else(|| format!(":{}", verb.names[0]), |inv| inv.to_string())
+        });
+        let res = external_execution.to_cmd_result(
+            w,
+            exec_builder,
+            cc.app.con,
+            if escalable { Some(&mut denied) } else { None },
+            journal_command,
+        );
+        app_state.pending_escalation = denied;
+        res
     }
 
     fn execute_sequence(
@@ -708,7 +1743,7 @@ pub trait PanelState {
             // sequences would be hard to execute as the execution on a file can change the
             // state in too many ways (changing selection, focused panel, parent, unstage or
             // stage files, removing the staged paths, etc.)
-            return Ok(CmdResult::error("sequences can't be executed on multiple selections"));
+            return Ok(CmdResult::error(tr!(error_sequence_multiple_selection)));
         }
         let exec_builder = ExecutionStringBuilder::with_invocation(
             &verb.invocation_parser,
@@ -738,12 +1773,25 @@ pub trait PanelState {
     ) -> Result<CmdResult, ProgramError> {
         self.clear_pending();
         let con = &cc.app.con;
-        let screen = cc.app.screen;
         match &cc.cmd {
-            Command::Click(x, y) => self.on_click(*x, *y, screen, con),
-            Command::DoubleClick(x, y) => self.on_double_click(*x, *y, screen, con),
+            Command::Click(x, y) => {
+                self.on_mouse_click(w, ClickKind::Click, *x, *y, app_state, cc)
+            }
+            Command::DoubleClick(x, y) => {
+                self.on_mouse_click(w, ClickKind::DoubleClick, *x, *y, app_state, cc)
+            }
+            Command::MiddleClick(x, y) => {
+                self.on_mouse_click(w, ClickKind::MiddleClick, *x, *y, app_state, cc)
+            }
+            Command::MouseMove(x, y) => self.on_mouse_move(*x, *y, cc),
             Command::PatternEdit { raw, expr } => {
-                match InputPattern::new(raw.clone(), expr, con) {
+                match InputPattern::with_search_mode(
+                    raw.clone(),
+                    expr,
+                    cc.panel.search_mode,
+                    &app_state.tags,
+                    con,
+                ) {
                     Ok(pattern) => self.on_pattern(pattern, app_state, con),
                     Err(e) => Ok(CmdResult::DisplayError(format!("{}", e))),
                 }
@@ -759,6 +1807,14 @@ pub trait PanelState {
                 app_state,
                 cc,
             ),
+            Command::Internal {
+                internal,
+                ..
+            } if !con.is_allowed_internal(*internal)
+                || (internal.is_mutating() && self.is_read_only(app_state)) => Ok(CmdResult::error(tr!(
+                error_disabled_read_only,
+                verb = internal.name(),
+            ))),
             Command::Internal {
                 internal,
                 input_invocation,
@@ -786,7 +1842,25 @@ pub trait PanelState {
                             cc,
                         )
                     }
-                    _ => Ok(CmdResult::verb_not_found(&invocation.name)),
+                    PrefixSearchResult::Matches(_) => {
+                        // the user chooses the verb in a list
+                        let mut verbs = con.verb_store.matching_verbs_sel_info(
+                            &invocation.name,
+                            sel_info,
+                        );
+                        app_state.verb_usage.sort(&mut verbs, |(_, verb)| &verb.names[0]);
+                        Ok(CmdResult::NewState {
+                            state: Box::new(VerbChoiceState::new(
+                                verbs,
+                                invocation.clone(),
+                                self.get_starting_input(),
+                                self.tree_options(),
+                                con,
+                            )),
+                            message: None,
+                        })
+                    }
+                    PrefixSearchResult::NoMatch => Ok(CmdResult::verb_not_found(&invocation.name)),
                 }
             }
             Command::None | Command::VerbEdit(_) => {
@@ -796,11 +1870,13 @@ pub trait PanelState {
         }
     }
 
-    /// return a cmdresult asking for the opening of a preview
+    /// return a cmdresult asking for the opening of a preview,
+    /// in tail mode (starting at the end and following the file) if `tail`
     fn open_preview(
         &mut self,
         prefered_mode: Option<PreviewMode>,
         close_if_open: bool,
+        tail: bool,
         cc: &CmdContext,
     ) -> CmdResult {
         if let Some(id) = cc.app.preview_panel {
@@ -810,7 +1886,7 @@ pub trait PanelState {
                     panel_ref: PanelReference::Id(id),
                 }
             } else {
-                if prefered_mode.is_some() {
+                if prefered_mode.is_some() || tail {
                     // we'll make the preview mode change be
                     // applied on the preview panel
                     CmdResult::ApplyOnPanel { id }
@@ -821,22 +1897,26 @@ pub trait PanelState {
         } else {
             if let Some(path) = self.selected_path() {
                 if path.is_file() {
+                    let mut state = PreviewState::new(
+                        path.to_path_buf(),
+                        InputPattern::none(),
+                        prefered_mode,
+                        self.tree_options(),
+                        cc.app.con,
+                    );
+                    if tail {
+                        state.start_tail();
+                    }
                     CmdResult::NewPanel {
-                        state: Box::new(PreviewState::new(
-                            path.to_path_buf(),
-                            InputPattern::none(),
-                            prefered_mode,
-                            self.tree_options(),
-                            cc.app.con,
-                        )),
+                        state: Box::new(state),
                         purpose: PanelPurpose::Preview,
                         direction: HDir::Right,
                     }
                 } else {
-                    CmdResult::error("only regular files can be previewed")
+                    CmdResult::error(tr!(error_preview_not_regular))
                 }
             } else {
-                CmdResult::error("no selected file")
+                CmdResult::error(tr!(error_no_selected_file))
             }
         }
     }
@@ -848,6 +1928,15 @@ pub trait PanelState {
 
     fn selected_path(&self) -> Option<&Path>;
 
+    /// tell whether the state browses a directory opened for
+    /// browsing only, where mutating verbs are disabled
+    fn is_read_only(&self, app_state: &AppState) -> bool {
+        self.tree_root()
+            .into_iter()
+            .chain(self.selected_path())
+            .any(|path| app_state.is_read_only_path(path))
+    }
+
     fn selection(&self) -> Option<Selection<'_>>;
 
     fn sel_info<'c>(&'c self, _app_state: &'c AppState) -> SelInfo<'c> {
@@ -896,6 +1985,30 @@ pub trait PanelState {
         None
     }
 
+    /// take the command the state wants executed now that its
+    /// pending task is done (eg an opening which needed some
+    /// background work), if any
+    fn take_pending_command(&mut self) -> Option<Command> {
+        None
+    }
+
+    /// the guard of the resource needed to browse the root of the
+    /// state, if any, which is given to the states it opens
+    fn root_guard(&self) -> Option<&Arc<RootGuard>> {
+        None
+    }
+
+    /// give the state the guard of the state it was opened from,
+    /// which it keeps if it browses under the guarded root
+    fn set_root_guard(&mut self, _guard: Arc<RootGuard>) {}
+
+    /// when Some, the app wakes up at least at this period, even
+    /// without user event, so that the state can check whether it
+    /// has a pending task (for example because a file changed)
+    fn refresh_period(&self) -> Option<Duration> {
+        None
+    }
+
     fn display(
         &mut self,
         w: &mut W,
@@ -919,11 +2032,10 @@ pub trait PanelState {
     fn no_verb_status(
         &self,
         _has_previous_state: bool,
-        _con: &AppContext,
+        con: &AppContext,
     ) -> Status {
-        Status::from_message(
-            "Hit *esc* to get back, or a space to start a verb"
-        )
+        let stype = self.selection().map_or(SelectionType::Any, |s| s.stype);
+        con.standard_status.generic_status(self.get_type(), stype)
     }
 
     fn get_status(
@@ -936,10 +2048,7 @@ pub trait PanelState {
             Command::PatternEdit { .. } => self.no_verb_status(has_previous_state, cc.app.con),
             Command::VerbEdit(invocation) => {
                 if invocation.name.is_empty() {
-                    Status::new(
-                        "Type a verb then *enter* to execute it (*?* for the list of verbs)",
-                        false,
-                    )
+                    Status::new(tr!(verb_type), false)
                 } else {
                     let sel_info = self.sel_info(app_state);
                     match cc.app.con.verb_store.search_sel_info(
@@ -947,15 +2056,15 @@ pub trait PanelState {
                         sel_info,
                     ) {
                         PrefixSearchResult::NoMatch => {
-                            Status::new("No matching verb (*?* for the list of verbs)", true)
+                            Status::new(tr!(verb_no_match), true)
                         }
                         PrefixSearchResult::Match(_, verb) => {
                             self.get_verb_status(verb, invocation, sel_info, cc, app_state)
                         }
                         PrefixSearchResult::Matches(completions) => Status::new(
-                            format!(
-                                "Possible verbs: {}",
-                                completions
+                            tr!(
+                                verb_possible,
+                                verbs = completions
                                     .iter()
                                     .map(|c| format!("*{}*", c))
                                     .collect::<Vec<String>>()
@@ -1004,6 +2113,55 @@ pub trait PanelState {
     }
 }
 
+/// switch to the given sort, or stop sorting if it's the current one
+fn toggle_sort(o: &mut TreeOptions, sort: Sort, message: &'static str) -> &'static str {
+    if o.sort == sort {
+        o.sort = Sort::None;
+        "*not sorting anymore*"
+    } else {
+        o.sort = sort;
+        message
+    }
+}
+
+/// toggle a part of the permission column, the column being
+/// displayed when a part is switched on
+fn toggle_permission_part(
+    o: &mut TreeOptions,
+    part: fn(&mut PermissionParts) -> &mut bool,
+    on_message: &'static str,
+    off_message: &'static str,
+) -> &'static str {
+    let shown = o.shows_permissions() && *part(&mut o.permission_parts);
+    if !o.show_permissions {
+        // the column was hidden: only the toggled part is shown
+        o.permission_parts = PermissionParts {
+            mode: false,
+            owner: false,
+            group: false,
+            acl: false,
+        };
+        o.show_permissions = true;
+    }
+    *part(&mut o.permission_parts) = !shown;
+    if shown {
+        off_message
+    } else {
+        on_message
+    }
+}
+
+/// split the argument of `:stage_export` and `:stage_pipe` into
+/// the separator of the paths, a NUL char when it starts with `-0`,
+/// and the rest
+fn stage_list_separator(arg: &str) -> (char, &str) {
+    match arg.strip_prefix("-0") {
+        Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => {
+            ('\0', rest.trim_start())
+        }
+        _ => ('\n', arg),
+    }
+}
 
 pub fn get_arg<T: Copy + FromStr>(
     verb_invocation: Option<&VerbInvocation>,
@@ -0,0 +1,74 @@
+use {
+    crate::task_sync::Dam,
+    std::time::{Duration, Instant},
+};
+
+/// the drawing duration over which the terminal is considered
+/// slow (eg over a remote connection), and worth waiting for
+/// the next event before drawing
+const SLOW_DRAW: Duration = Duration::from_millis(15);
+
+/// the max time to wait for a following event before drawing
+const MAX_WAIT: Duration = Duration::from_millis(50);
+
+/// the bounds of the max time without drawing while events
+/// keep coming (eg when a key is kept pressed)
+const MIN_FRAME_GAP: Duration = Duration::from_millis(100);
+const MAX_FRAME_GAP: Duration = Duration::from_millis(500);
+
+/// Decide whether the screen must be drawn after a command or
+/// whether the drawing can be skipped because another event is
+/// already there, coalescing the intermediate frames.
+///
+/// The duration of the drawings is measured so that, on slow
+/// terminals, we wait a little for the next event, and so
+/// that the input stays responsive.
+pub struct RedrawThrottle {
+    /// average duration of the last drawings
+    draw_duration: Duration,
+    last_draw: Instant,
+    skipped_count: usize,
+}
+
+impl Default for RedrawThrottle {
+    fn default() -> Self {
+        Self {
+            draw_duration: Duration::ZERO,
+            last_draw: Instant::now(),
+            skipped_count: 0,
+        }
+    }
+}
+
+impl RedrawThrottle {
+    /// tell whether the drawing should be skipped, the next
+    /// event being handled first
+    pub fn must_skip(&mut self, dam: &mut Dam) -> bool {
+        let frame_gap = (self.draw_duration * 4).max(MIN_FRAME_GAP).min(MAX_FRAME_GAP);
+        if self.last_draw.elapsed() > frame_gap {
+            return false;
+        }
+        let has_event = if self.draw_duration >= SLOW_DRAW {
+            dam.wait_event((self.draw_duration / 2).min(MAX_WAIT))
+        } else {
+            dam.has_event()
+        };
+        if has_event {
+            self.skipped_count += 1;
+        }
+        has_event
+    }
+    /// record a drawing which started at the given instant
+    pub fn drawn(&mut self, start: Instant) {
+        let duration = start.elapsed();
+        self.draw_duration = (self.draw_duration * 3 + duration) / 4;
+        self.last_draw = Instant::now();
+        if self.skipped_count > 0 {
+            debug!(
+                "{} drawings skipped (drawing duration: {:?})",
+                self.skipped_count, self.draw_duration,
+            );
+            self.skipped_count = 0;
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use {
+    crate::errors::ProgramError,
+    serde::{Deserialize, Serialize},
+    std::{
+        fs,
+        path::Path,
+    },
+};
+
+/// a step of a replay script, written as one JSON object per
+/// line, for example `{"key":"ctrl-k"}`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayStep {
+    /// commands, as given with `--cmd` (eg `"sub;:focus"`)
+    Cmd(String),
+    /// a key, as written in key bindings (eg `"alt-enter"`)
+    Key(String),
+    /// chars typed in the input, one key at a time
+    Input(String),
+}
+
+/// read a replay script, ignoring the empty lines and the
+/// ones starting with `#`
+pub fn read_replay(path: &Path) -> Result<Vec<ReplayStep>, ProgramError> {
+    let content = fs::read_to_string(path)?;
+    let mut steps = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |details: String| ProgramError::InvalidReplay {
+            path: path.to_string_lossy().to_string(),
+            line: idx + 1,
+            details,
+        };
+        let step = serde_json::from_str(line).map_err(|e| invalid(e.to_string()))?;
+        if let ReplayStep::Key(key) = &step {
+            crokey::parse(key).map_err(|e| invalid(e.to_string()))?;
+        }
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+/// the state of a panel at the end of a replay
+#[derive(Debug, Serialize)]
+pub struct ReplayPanelDump {
+    pub state: &'static str,
+    pub selection: Option<String>,
+    pub input: String,
+}
+
+/// the state of the application at the end of a replay,
+/// printed as JSON
+#[derive(Debug, Serialize)]
+pub struct ReplayDump {
+    pub root: String,
+    pub active_panel: usize,
+    pub panels: Vec<ReplayPanelDump>,
+    pub staged: Vec<String>,
+}
+
+#[cfg(test)]
+mod replay_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_replay_steps_parsing() {
+        let steps: Vec<ReplayStep> = [
+            r#"{"cmd":"sub;:focus"}"#,
+            r#"{"key":"ctrl-k"}"#,
+            r#"{"input":"abc"}"#,
+        ].iter().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(steps, vec![
+            ReplayStep::Cmd("sub;:focus".to_string()),
+            ReplayStep::Key("ctrl-k".to_string()),
+            ReplayStep::Input("abc".to_string()),
+        ]);
+        assert!(serde_json::from_str::<ReplayStep>(r#"{"mouse":"click"}"#).is_err());
+    }
+}
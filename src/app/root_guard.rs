@@ -0,0 +1,76 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+};
+
+/// A resource needed by the states browsing under a root (eg the
+/// mount of the filesystem of a container), shared by those states
+/// and released when the last one is dropped.
+///
+/// The release is done in a background thread so that it never
+/// blocks the UI.
+pub struct RootGuard {
+    root: PathBuf,
+    release: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl RootGuard {
+    pub fn new<F>(root: PathBuf, release: F) -> Arc<Self>
+    where
+        F: FnOnce() + Send + Sync + 'static,
+    {
+        Arc::new(Self {
+            root,
+            release: Some(Box::new(release)),
+        })
+    }
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+    /// tell whether a state browsing the given path needs the guard
+    pub fn covers(&self, path: &Path) -> bool {
+        path.starts_with(&self.root)
+    }
+}
+
+impl fmt::Debug for RootGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RootGuard").field("root", &self.root).finish()
+    }
+}
+
+impl Drop for RootGuard {
+    fn drop(&mut self) {
+        if let Some(release) = self.release.take() {
+            debug!("releasing the guard of {:?}", &self.root);
+            thread::spawn(release);
+        }
+    }
+}
+
+#[cfg(test)]
+mod root_guard_tests {
+
+    use {
+        super::*,
+        crossbeam::channel::bounded,
+        std::time::Duration,
+    };
+
+    #[test]
+    fn test_release_on_last_drop() {
+        let (sender, receiver) = bounded(1);
+        let guard = RootGuard::new(PathBuf::from("/mnt/c"), move || {
+            sender.send(()).unwrap();
+        });
+        assert!(guard.covers(Path::new("/mnt/c/etc")));
+        assert!(!guard.covers(Path::new("/mnt/cd")));
+        let other = Arc::clone(&guard);
+        drop(guard);
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+        drop(other);
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+}
@@ -7,7 +7,10 @@ use {
     crate::{
         errors::ProgramError,
         launchable::Launchable,
+        opener_rules,
         stage::Stage,
+        syntactic::SyntacticView,
+        verb::Sandbox,
     },
     std::{
         fs::OpenOptions,
@@ -33,6 +36,8 @@ pub enum SelectionType {
 pub struct Selection<'s> {
     pub path: &'s Path,
     pub line: LineNumber, // the line number in the file (0 if none selected)
+    pub line_range: Option<(LineNumber, LineNumber)>, // first and last selected lines, if several
+    pub text_view: Option<&'s SyntacticView>, // the view in which the lines are selected, if any
     pub stype: SelectionType,
     pub is_exe: bool,
 }
@@ -68,6 +73,14 @@ impl SelectionType {
 
 impl Selection<'_> {
 
+    /// return the text of the selected lines of the file (the
+    /// range of lines if there's one, else the selected line), as
+    /// decoded in the view in which they're selected, each line
+    /// being optionally prefixed with its number
+    pub fn lines_text(&self, with_numbers: bool) -> Option<String> {
+        self.text_view?.get_selected_lines_text(with_numbers)
+    }
+
     /// build a CmdResult with a launchable which will be used to
     ///  1/ quit broot
     ///  2/ open the relevant file the best possible way
@@ -86,9 +99,17 @@ impl Selection<'_> {
                 CmdResult::from(Launchable::program(
                     vec![path],
                     None, // we don't set the working directory
+                    Sandbox::default(),
                     con,
                 )?)
             }
+        } else if let Some(rule) = opener_rules::find(self.path, &con.openers) {
+            CmdResult::from(Launchable::program(
+                rule.tokens(self.path),
+                None,
+                Sandbox::default(),
+                con,
+            )?)
         } else {
             CmdResult::from(Launchable::opener(self.path.to_path_buf()))
         })
@@ -101,6 +122,8 @@ impl<'a> SelInfo<'a> {
             Selection {
                 stype: SelectionType::from(path),
                 line: 0,
+                line_range: None,
+                text_view: None,
                 path,
                 is_exe: false, // OK, I don't know
             }
@@ -0,0 +1,204 @@
+use {
+    crate::{
+        conf,
+        tree::{PermissionParts, Sort, TreeOptions},
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        fs,
+        io,
+        path::PathBuf,
+    },
+};
+
+/// the tree options which are kept in a session (the other ones
+/// come from the configuration)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionTreeOptions {
+    pub show_hidden: bool,
+    pub only_folders: bool,
+    pub show_counts: bool,
+    pub show_dates: bool,
+    pub show_sizes: bool,
+    pub show_git_file_info: bool,
+    pub show_device_id: bool,
+    pub show_lint: bool,
+    pub show_root_fs: bool,
+    pub trim_root: bool,
+    pub flat: bool,
+    pub miller: bool,
+    pub show_permissions: bool,
+    #[serde(default)]
+    pub permission_parts: PermissionParts,
+    pub respect_git_ignore: bool,
+    pub filter_by_git_status: bool,
+    pub show_scores: bool,
+    #[serde(default)]
+    pub show_row_numbers: bool,
+    #[serde(default)]
+    pub show_match_lines: bool,
+    pub sort: Sort,
+}
+
+impl From<&TreeOptions> for SessionTreeOptions {
+    fn from(o: &TreeOptions) -> Self {
+        Self {
+            show_hidden: o.show_hidden,
+            only_folders: o.only_folders,
+            show_counts: o.show_counts,
+            show_dates: o.show_dates,
+            show_sizes: o.show_sizes,
+            show_git_file_info: o.show_git_file_info,
+            show_device_id: o.show_device_id,
+            show_lint: o.show_lint,
+            show_root_fs: o.show_root_fs,
+            trim_root: o.trim_root,
+            flat: o.flat,
+            miller: o.miller,
+            show_permissions: o.show_permissions,
+            permission_parts: o.permission_parts,
+            respect_git_ignore: o.respect_git_ignore,
+            filter_by_git_status: o.filter_by_git_status,
+            show_scores: o.show_scores,
+            show_row_numbers: o.show_row_numbers,
+            show_match_lines: o.show_match_lines,
+            sort: o.sort,
+        }
+    }
+}
+
+impl SessionTreeOptions {
+    pub fn apply_to(&self, o: &mut TreeOptions) {
+        o.show_hidden = self.show_hidden;
+        o.only_folders = self.only_folders;
+        o.show_counts = self.show_counts;
+        o.show_dates = self.show_dates;
+        o.show_sizes = self.show_sizes;
+        o.show_git_file_info = self.show_git_file_info;
+        o.show_device_id = self.show_device_id;
+        o.show_lint = self.show_lint;
+        o.show_root_fs = self.show_root_fs;
+        o.trim_root = self.trim_root;
+        o.flat = self.flat;
+        o.miller = self.miller;
+        o.show_permissions = self.show_permissions;
+        o.permission_parts = self.permission_parts;
+        o.respect_git_ignore = self.respect_git_ignore;
+        o.filter_by_git_status = self.filter_by_git_status;
+        o.show_scores = self.show_scores;
+        o.show_row_numbers = self.show_row_numbers;
+        o.show_match_lines = self.show_match_lines;
+        o.sort = self.sort;
+    }
+}
+
+/// a tree, in a tab of a saved panel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionTree {
+    pub root: PathBuf,
+    /// the raw filtering pattern, as typed
+    pub pattern: String,
+    pub options: SessionTreeOptions,
+}
+
+/// what's displayed in a saved panel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum SessionPanelContent {
+    Trees {
+        tabs: Vec<SessionTree>,
+        current_tab: usize,
+    },
+    Preview {
+        path: PathBuf,
+    },
+    Stage,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionPanel {
+    pub width: u16,
+    pub content: SessionPanelContent,
+}
+
+/// the state of the application saved on quit when `save_session`
+/// is set, and restored with `broot --resume`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// the width of the screen when the panel widths were saved
+    pub screen_width: u16,
+    pub panels: Vec<SessionPanel>,
+    pub active_panel_idx: usize,
+    pub staged: Vec<PathBuf>,
+}
+
+impl Session {
+    /// the file where the session is saved
+    pub fn file() -> PathBuf {
+        conf::app_dirs().data_dir().join("session.json")
+    }
+    /// read the saved session, if there's a valid one
+    pub fn load() -> Option<Self> {
+        let file = Self::file();
+        let content = fs::read_to_string(&file).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(session) => Some(session),
+            Err(e) => {
+                warn!("invalid session file {:?}: {}", file, e);
+                None
+            }
+        }
+    }
+    pub fn save(&self) -> io::Result<()> {
+        let file = Self::file();
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod session_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_session_round_trip() {
+        let tree_options = TreeOptions {
+            show_sizes: true,
+            sort: Sort::Size,
+            ..TreeOptions::default()
+        };
+        let session = Session {
+            screen_width: 120,
+            panels: vec![
+                SessionPanel {
+                    width: 60,
+                    content: SessionPanelContent::Trees {
+                        tabs: vec![SessionTree {
+                            root: PathBuf::from("/home/me"),
+                            pattern: "carg".to_string(),
+                            options: (&tree_options).into(),
+                        }],
+                        current_tab: 0,
+                    },
+                },
+                SessionPanel {
+                    width: 60,
+                    content: SessionPanelContent::Preview {
+                        path: PathBuf::from("/home/me/Cargo.toml"),
+                    },
+                },
+            ],
+            active_panel_idx: 0,
+            staged: vec![PathBuf::from("/home/me/a.txt")],
+        };
+        let json = serde_json::to_string(&session).unwrap();
+        assert_eq!(serde_json::from_str::<Session>(&json).unwrap(), session);
+        let mut restored = TreeOptions::default();
+        SessionTreeOptions::from(&tree_options).apply_to(&mut restored);
+        assert!(restored.show_sizes);
+        assert_eq!(restored.sort, Sort::Size);
+    }
+}
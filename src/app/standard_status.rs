@@ -1,6 +1,12 @@
 use {
     super::*,
     crate::{
+        help::{
+            build_tips,
+            current_tip,
+            Tip,
+        },
+        tr,
         verb::{
             Internal,
             VerbStore,
@@ -25,40 +31,46 @@ pub struct StandardStatus {
     no_verb: String,
     pub all_files_hidden: Option<String>,
     pub all_files_git_ignored: Option<String>,
+    tips: Vec<Tip>, // empty when tips aren't displayed
 }
 
 impl StandardStatus {
-    pub fn new(verb_store: &VerbStore) -> Self {
-        let tree_top_focus = "*enter* to go up".to_string(); // enter is hardcoded on focus
-        let tree_dir_focus = "*enter* to focus".to_string();
+    pub fn new(verb_store: &VerbStore, show_tips: bool) -> Self {
+        let tree_top_focus = tr!(hint_enter_go_up).to_string(); // enter is hardcoded on focus
+        let tree_dir_focus = tr!(hint_enter_focus).to_string();
         let tree_dir_cd = verb_store
             .key_desc_of_internal_stype(Internal::open_leave, SelectionType::Directory)
-            .map(|k| format!("*{}* to cd", k));
+            .map(|k| tr!(hint_key_cd, key = k));
         let tree_file_open_stay = verb_store
             .key_desc_of_internal_stype(Internal::open_stay, SelectionType::File)
-            .map(|k| format!("*{}* to open the file", k));
+            .map(|k| tr!(hint_key_open_file, key = k));
         let tree_file_open_leave = verb_store
             .key_desc_of_internal_stype(Internal::open_leave, SelectionType::File)
-            .map(|k| format!("*{}* to open and quit", k));
+            .map(|k| tr!(hint_key_open_quit, key = k));
         //let tree_file_enter = None; // TODO (for when enter is customized)
-        let tree_unfiltered = "a few letters to search".to_string();
-        let tree_filtered = "*esc* to clear the filter".to_string();
-        let preview_unfiltered = "a pattern to filter".to_string();
+        let tree_unfiltered = tr!(hint_search).to_string();
+        let tree_filtered = tr!(hint_clear_filter).to_string();
+        let preview_unfiltered = tr!(hint_filter_preview).to_string();
         let preview_filtered = verb_store
             .key_desc_of_internal(Internal::panel_right)
-            .map(|k| format!("*{}* to reveal the text", k));
+            .map(|k| tr!(hint_key_reveal_text, key = k));
         let preview_restorable_filter = verb_store
             .key_desc_of_internal(Internal::panel_left_no_open)
-            .map(|k| format!("*{}* to restore the filter", k));
-        let not_first_state = "*esc* to go back".to_string();
-        let help = "*?* for help".to_string();
-        let no_verb = "a space then a verb".to_string();
+            .map(|k| tr!(hint_key_restore_filter, key = k));
+        let not_first_state = tr!(hint_go_back).to_string();
+        let help = tr!(hint_help).to_string();
+        let no_verb = tr!(hint_verb).to_string();
         let all_files_hidden = verb_store
             .key_desc_of_internal(Internal::toggle_hidden)
-            .map(|k| format!("Some files are hidden, use *{}* to display them", k));
+            .map(|k| tr!(status_files_hidden, key = k));
         let all_files_git_ignored = verb_store
             .key_desc_of_internal(Internal::toggle_git_ignore)
-            .map(|k| format!("Some files are git-ignored, use *{}* to display them", k));
+            .map(|k| tr!(status_files_git_ignored, key = k));
+        let tips = if show_tips {
+            build_tips(verb_store)
+        } else {
+            Vec::new()
+        };
         Self {
             tree_top_focus,
             tree_dir_focus,
@@ -76,8 +88,22 @@ impl StandardStatus {
             no_verb,
             all_files_hidden,
             all_files_git_ignored,
+            tips,
         }
     }
+    /// the status of the states without specific hints, with
+    /// a tip if there's one for the context
+    pub fn generic_status(
+        &self,
+        state_type: PanelStateType,
+        stype: SelectionType,
+    ) -> Status {
+        let mut md = tr!(status_generic).to_string();
+        if let Some(tip) = current_tip(&self.tips, state_type, stype, false) {
+            md = tr!(status_generic_with_tip, tip = tip.md());
+        }
+        Status::from_message(md)
+    }
     pub fn builder<'s>(
         &'s self,
         state_type: PanelStateType,
@@ -103,17 +129,27 @@ impl<'b> StatusParts<'b> {
     fn len(&self) -> usize {
         self.md_parts.len()
     }
-    fn to_status(&self) -> Status {
-        let mut md = String::new();
+    fn to_status(&self, tip: Option<&Tip>) -> Status {
+        let mut hints = String::new();
         for (i, p) in self.md_parts.iter().enumerate() {
-            md.push_str(if i == 0 {
-                "Hit "
+            if i == 0 {
+                // no separator
             } else if i == self.md_parts.len() - 1 {
-                ", or "
+                hints.push_str(tr!(status_hints_last_separator));
             } else {
-                ", "
-            });
-            md.push_str(p);
+                hints.push_str(tr!(status_hints_separator));
+            }
+            hints.push_str(p);
+        }
+        let mut md = String::new();
+        if !hints.is_empty() {
+            md = tr!(status_hints, hints = hints);
+        }
+        if let Some(tip) = tip {
+            if !md.is_empty() {
+                md.push_str(". ");
+            }
+            md.push_str(&tr!(status_tip, tip = tip.md()));
         }
         Status::from_message(md)
     }
@@ -146,6 +182,9 @@ impl<'s> StandardStatusBuilder<'s> {
     }
     pub fn status(self) -> Status {
         let ss = &self.ss;
+        let tip = current_tip(&ss.tips, self.state_type, self.selection.stype, self.is_filtered);
+        // when there's a tip, it replaces the least useful hints
+        let max_parts = if tip.is_some() { 2 } else { 4 };
         let mut parts = StatusParts::default();
         if self.has_previous_state && !self.is_filtered {
             parts.add(&ss.not_first_state);
@@ -168,10 +207,10 @@ impl<'s> StandardStatusBuilder<'s> {
                 if self.is_filtered {
                     parts.add(&ss.tree_filtered);
                 }
-                if parts.len() < 3 {
+                if parts.len() + 1 < max_parts {
                     parts.add(&ss.help);
                 }
-                if parts.len() < 4 {
+                if parts.len() < max_parts {
                     if self.on_tree_root && !self.is_filtered {
                         parts.add(&ss.tree_unfiltered);
                     } else {
@@ -187,21 +226,42 @@ impl<'s> StandardStatusBuilder<'s> {
                 } else {
                     parts.add(&ss.preview_unfiltered);
                 }
-                parts.add(&ss.no_verb);
+                if parts.len() < max_parts {
+                    parts.add(&ss.no_verb);
+                }
             }
             PanelStateType::Help => {
                 // not yet used, help_state has its own hard status
-                if parts.len() < 4 {
+                if parts.len() < max_parts {
                     parts.add(&ss.no_verb);
                 }
             }
             PanelStateType::Fs => {
                 warn!("TODO fs status");
             }
+            PanelStateType::Containers => {
+                warn!("TODO containers status");
+            }
+            PanelStateType::Tags
+            | PanelStateType::Bookmarks
+            | PanelStateType::VerbLog
+            | PanelStateType::Snapshots
+            | PanelStateType::Versions
+            | PanelStateType::Report
+            | PanelStateType::Watch
+            | PanelStateType::Conflicts
+            | PanelStateType::Trash
+            | PanelStateType::QuickRoots
+            | PanelStateType::VerbChoice
+            | PanelStateType::Tips => {
+                if parts.len() < max_parts {
+                    parts.add(&ss.no_verb);
+                }
+            }
             PanelStateType::Stage => {
                 warn!("TODO stage status");
             }
         }
-        parts.to_status()
+        parts.to_status(tip)
     }
 }
@@ -14,6 +14,45 @@ pub enum PanelStateType {
     /// filesystems
     Fs,
 
+    /// docker and podman containers
+    Containers,
+
+    /// tags given to files
+    Tags,
+
+    /// paths bookmarked by the user
+    Bookmarks,
+
+    /// the verbs executed during the session
+    VerbLog,
+
+    /// snapshots of a filesystem
+    Snapshots,
+
+    /// versions of a file found in backups
+    Versions,
+
+    /// a report on the files under a root
+    Report,
+
+    /// the changes of a watched path
+    Watch,
+
+    /// the conflicts of a batch copy or move
+    Conflicts,
+
+    /// the items of the trash
+    Trash,
+
+    /// standard locations opened sorted by date
+    QuickRoots,
+
+    /// verbs matching an ambiguous invocation
+    VerbChoice,
+
+    /// the tips which may be shown in the status
+    Tips,
+
     /// help "screen"
     Help,
 
@@ -23,3 +62,29 @@ pub enum PanelStateType {
     /// stage panel, never alone on screen
     Stage,
 }
+
+impl PanelStateType {
+    /// the name of the type, as written in configuration
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tree => "tree",
+            Self::Fs => "fs",
+            Self::Containers => "containers",
+            Self::Tags => "tags",
+            Self::Bookmarks => "bookmarks",
+            Self::VerbLog => "verb_log",
+            Self::Snapshots => "snapshots",
+            Self::Versions => "versions",
+            Self::Report => "report",
+            Self::Watch => "watch",
+            Self::Conflicts => "conflicts",
+            Self::Trash => "trash",
+            Self::QuickRoots => "quick_roots",
+            Self::VerbChoice => "verb_choice",
+            Self::Tips => "tips",
+            Self::Help => "help",
+            Self::Preview => "preview",
+            Self::Stage => "stage",
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use {
+    crate::conf,
+    std::{
+        collections::BTreeMap,
+        fs,
+        io,
+        path::{Path, PathBuf},
+    },
+};
+
+/// the bookmarks of the user, as stored in the bookmarks file, which
+/// has one `name<TAB>path` line per bookmark
+///
+/// Names are unique, a path has at most one bookmark.
+#[derive(Debug, Clone, Default)]
+pub struct BookmarkStore {
+    bookmarks: BTreeMap<String, PathBuf>,
+}
+
+impl BookmarkStore {
+    /// the file where bookmarks are stored
+    pub fn file() -> PathBuf {
+        conf::app_dirs().data_dir().join("bookmarks")
+    }
+    /// read the bookmarks file, returning an empty store when
+    /// there's none or when it can't be read
+    pub fn load() -> Self {
+        let file = Self::file();
+        if !file.exists() {
+            return Self::default();
+        }
+        match fs::read_to_string(&file) {
+            Ok(content) => Self::parse(&content),
+            Err(e) => {
+                warn!("failed to read {:?}: {}", file, e);
+                Self::default()
+            }
+        }
+    }
+    pub fn parse(content: &str) -> Self {
+        let mut store = Self::default();
+        for line in content.lines() {
+            if let Some((name, path)) = line.split_once('\t') {
+                store.add(Path::new(path), name);
+            }
+        }
+        store
+    }
+    pub fn save(&self) -> io::Result<()> {
+        let file = Self::file();
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, self.to_string())
+    }
+    /// names are normalized: no whitespace nor tab, which would
+    /// break the storage format
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && !name.contains(char::is_whitespace)
+    }
+    /// the name given to a bookmark when none is specified: the
+    /// file name, with whitespaces replaced
+    pub fn default_name(path: &Path) -> String {
+        let name = path.file_name()
+            .map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy());
+        name.replace(char::is_whitespace, "_")
+    }
+    /// bookmark the path with the given name, replacing the previous
+    /// bookmark of the path and the previous one with this name, if any.
+    /// Return false if it was already this bookmark.
+    pub fn add(&mut self, path: &Path, name: &str) -> bool {
+        if self.bookmarks.get(name).map_or(false, |p| p == path) {
+            return false;
+        }
+        self.bookmarks.retain(|_, p| p != path);
+        self.bookmarks.insert(name.to_string(), path.to_path_buf());
+        true
+    }
+    /// remove the bookmark of the path, return false if there was none
+    pub fn remove_path(&mut self, path: &Path) -> bool {
+        let len = self.bookmarks.len();
+        self.bookmarks.retain(|_, p| p != path);
+        self.bookmarks.len() != len
+    }
+    /// find the bookmark with this name or, if there's none, the
+    /// first one whose name starts with it
+    pub fn find(&self, name: &str) -> Option<&Path> {
+        self.bookmarks.get(name)
+            .or_else(|| {
+                self.bookmarks.iter()
+                    .find(|(n, _)| n.starts_with(name))
+                    .map(|(_, path)| path)
+            })
+            .map(|path| path.as_path())
+    }
+    /// the bookmarks, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &PathBuf)> {
+        self.bookmarks.iter()
+    }
+}
+
+impl std::fmt::Display for BookmarkStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, path) in &self.bookmarks {
+            writeln!(f, "{}\t{}", name, path.to_string_lossy())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod bookmark_store_tests {
+
+    use super::*;
+
+    #[test]
+    fn check_bookmark_store_round_trip() {
+        let mut store = BookmarkStore::default();
+        assert!(store.add(Path::new("/home/me/dev"), "dev"));
+        assert!(!store.add(Path::new("/home/me/dev"), "dev"));
+        assert!(store.add(Path::new("/a dir/with space"), "space"));
+        // a path has only one bookmark
+        assert!(store.add(Path::new("/home/me/dev"), "code"));
+        let store = BookmarkStore::parse(&store.to_string());
+        assert_eq!(store.iter().count(), 2);
+        assert_eq!(store.find("code"), Some(Path::new("/home/me/dev")));
+        assert_eq!(store.find("sp"), Some(Path::new("/a dir/with space")));
+        assert_eq!(store.find("dev"), None);
+        let mut store = store;
+        assert!(store.remove_path(Path::new("/home/me/dev")));
+        assert!(!store.remove_path(Path::new("/home/me/dev")));
+        assert_eq!(BookmarkStore::default_name(Path::new("/a dir/my file")), "my_file");
+    }
+}
@@ -0,0 +1,212 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::path::{Path, PathBuf},
+    termimad::minimad::Alignment,
+};
+
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    path: PathBuf,
+}
+
+fn entries(store: &BookmarkStore) -> Vec<Entry> {
+    store.iter()
+        .map(|(name, path)| Entry { name: name.clone(), path: path.clone() })
+        .collect()
+}
+
+/// an application state listing the bookmarks, the selected one
+/// being opened with *enter*
+pub struct BookmarksState {
+    list: FilteredList<Entry>,
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+impl BookmarksState {
+    pub fn new(
+        store: &BookmarkStore,
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> BookmarksState {
+        BookmarksState {
+            list: FilteredList::new(entries(store), matches),
+            tree_options,
+            mode: initial_mode(con),
+        }
+    }
+}
+
+/// bookmarks are matched on their name or their path
+fn matches(entry: &Entry, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&entry.name).is_some()
+        || pattern.score_of_string(&entry.path.to_string_lossy()).is_some()
+}
+
+impl PanelState for BookmarksState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Bookmarks
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn selected_path(&self) -> Option<&Path> {
+        self.list.selected().map(|entry| entry.path.as_path())
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        self.selected_path().map(|path| Selection {
+            path,
+            line: 0,
+            line_range: None,
+            text_view: None,
+            stype: SelectionType::from(path),
+            is_exe: false,
+        })
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        let entries = entries(&BookmarkStore::load());
+        self.list.set_items(entries, |a, b| a.name == b.name);
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let w_name = list.visible()
+            .map(|e| e.name.chars().count())
+            .max().unwrap_or(0)
+            .max("name".len());
+        let styles = &disc.panel_skin.styles;
+        list.queue_table_head(
+            w,
+            disc,
+            &[ListColumn::new("name", w_name, Alignment::Left)],
+            |cw| {
+                cw.queue_str(&styles.default, "path")?;
+                Ok(())
+            },
+        )?;
+        self.list.queue_table_rows(w, disc, |cw, entry, row| {
+            row.queue_matched_cell(cw, &entry.name, row.txt_style, w_name, Alignment::Left)?;
+            let path_style = if entry.path.is_dir() { &styles.directory } else { &styles.file };
+            cw.queue_g_string(&row.style(path_style), entry.path.to_string_lossy().to_string())?;
+            Ok(())
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        use Internal::*;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                let in_new_panel = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                match self.list.selected() {
+                    Some(entry) => internal_focus::on_path(
+                        entry.path.clone(),
+                        screen,
+                        self.tree_options.without_pattern(),
+                        in_new_panel,
+                        con,
+                    ),
+                    None => CmdResult::error(tr!(error_no_selected_bookmark)),
+                }
+            }
+            Internal::unbookmark => {
+                let path = match self.list.selected() {
+                    Some(entry) => entry.path.clone(),
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_no_selected_bookmark)));
+                    }
+                };
+                let mut store = BookmarkStore::load();
+                if store.remove_path(&path) {
+                    store.save()?;
+                }
+                self.refresh(screen, con);
+                CmdResult::Keep
+            }
+            open_leave => CmdResult::PopStateAndReapply,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
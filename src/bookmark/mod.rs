@@ -0,0 +1,10 @@
+//! Bookmarks are named paths saved by the user in broot's data
+//! directory, listed in a dedicated state and reachable by name
+
+mod bookmark_store;
+mod bookmarks_state;
+
+pub use {
+    bookmark_store::BookmarkStore,
+    bookmarks_state::BookmarksState,
+};
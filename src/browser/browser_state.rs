@@ -1,24 +1,50 @@
 use {
+    super::{
+        miller::{SideColumn, MILLER_MIN_WIDTH},
+        DirInfo,
+    },
     crate::{
         app::*,
-        command::{Command, TriggerType},
-        display::{DisplayableTree, Screen, W},
+        command::{Command, CommandParts, Sequence, TriggerType},
+        display::{CellSpan, Col, DisplayableTree, RowCache, Screen, W},
         errors::{ProgramError, TreeBuildError},
+        file_sum::{self, SizeSnapshot},
         flag::Flag,
         git,
+        launchable::Launchable,
+        opener_rules,
         pattern::*,
         path::{self, PathAnchor},
         print,
         stage::*,
         task_sync::Dam,
+        tr,
         tree::*,
         tree_build::TreeBuilder,
         verb::*,
+        watch::FsWatch,
     },
     opener,
-    std::path::{Path, PathBuf},
+    std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::{Duration, Instant},
+    },
+    termimad::Area,
 };
 
+/// escape the chars which would be interpreted in markdown
+fn escape_md(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '*' | '`' | '~' | '|' | '\\' | '#' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 /// An application state dedicated to displaying a tree.
 /// It's the first and main screen of broot.
 pub struct BrowserState {
@@ -28,6 +54,31 @@ pub struct BrowserState {
     // pub total_search_required: bool,   // whether the pending pattern should be in total search mode
     mode: Mode, // whether we're in 'input' or 'normal' mode
     pending_task: Option<BrowserTask>, // note: there are some other pending task, see
+    cell_spans: Vec<CellSpan>, // positions of the cells at last display
+    row_cache: RowCache,
+    auto_refresh_period: Option<Duration>,
+    last_refresh: Instant,
+    /// the minimal duration between two live refreshes, when
+    /// the tree is refreshed on changes notified by the system
+    live_refresh_throttle: Option<Duration>,
+    fs_watch: Option<FsWatch>,
+    /// whether the directories of the displayed tree changed since
+    /// they were given to the fs_watch
+    fs_watch_outdated: bool,
+    /// the guard of the resource needed to browse the root, if any
+    root_guard: Option<Arc<RootGuard>>,
+    /// the summary of the last directory asked for, kept so that
+    /// it's not read again while the mouse stays over it
+    dir_info: Option<DirInfo>,
+    /// whether the summary is displayed, until the next command
+    show_dir_info: bool,
+    /// the area of the tree at last display, narrower than the one
+    /// of the state with Miller columns
+    tree_area: Area,
+    /// in Miller columns, the content of the parent of the root
+    parent_column: Option<SideColumn>,
+    /// in Miller columns, the content of the selected directory
+    child_column: Option<SideColumn>,
 }
 
 /// A task that can be computed in background
@@ -60,14 +111,191 @@ impl BrowserState {
             con,
         )?;
         let tree = builder.build_tree(false, dam)?;
+        let fs_watch = con.fs_watcher.as_ref().map(FsWatch::new);
         Ok(BrowserState {
             tree,
             filtered_tree: None,
             mode: initial_mode(con),
             pending_task,
+            cell_spans: Vec::new(),
+            row_cache: RowCache::default(),
+            auto_refresh_period: con.auto_refresh_period,
+            last_refresh: Instant::now(),
+            live_refresh_throttle: con.live_refresh_throttle,
+            fs_watch,
+            fs_watch_outdated: true,
+            root_guard: None,
+            dir_info: None,
+            show_dir_info: false,
+            tree_area: Area::uninitialized(),
+            parent_column: None,
+            child_column: None,
         })
     }
 
+    /// the sizes of the previous visit of the root, when sizes
+    /// are displayed and compared
+    fn size_reference(&self, con: &AppContext) -> Option<Arc<SizeSnapshot>> {
+        let tree = self.displayed_tree();
+        if con.size_deltas && tree.options.show_sizes {
+            file_sum::size_reference(tree.root())
+        } else {
+            None
+        }
+    }
+
+    /// save the computed sizes, as reference for the next visits
+    fn record_sizes(&self, con: &AppContext) {
+        let tree = self.displayed_tree();
+        if con.size_deltas && tree.options.show_sizes {
+            let sizes = tree.lines.iter()
+                .filter_map(|line| line.sum.map(|sum| (line.path.clone(), sum.to_size())));
+            file_sum::record_sizes(tree.root(), sizes);
+        }
+    }
+
+    /// tell whether the x coordinate is in the tree, and not in
+    /// a Miller column
+    fn is_x_in_tree(&self, x: u16) -> bool {
+        x >= self.tree_area.left && x < self.tree_area.left + self.tree_area.width
+    }
+
+    /// in Miller columns, replace the state with one of the parent
+    /// of the root, the current root being selected
+    fn go_up_selecting_root(&self, screen: Screen, con: &AppContext) -> CmdResult {
+        let root = self.tree.root();
+        match root.parent() {
+            Some(parent) => {
+                let page_height = BrowserState::page_height(screen);
+                let state = BrowserState::new(
+                    parent.to_path_buf(),
+                    self.tree.options.without_pattern(),
+                    screen,
+                    con,
+                    &Dam::unlimited(),
+                )
+                .map(|mut state| {
+                    if state.tree.try_select_path(root) {
+                        state.tree.make_selection_visible(page_height);
+                    }
+                    state
+                });
+                CmdResult::from_optional_state(state, None, false)
+            }
+            None => CmdResult::PopState,
+        }
+    }
+
+    /// write the Miller columns around the tree: the parent of the
+    /// root on the left, the selected directory on the right
+    fn write_miller_columns(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let state_area = &disc.state_area;
+        let tree_area = &self.tree_area;
+        let parent_area = Area::new(
+            state_area.left,
+            state_area.top,
+            tree_area.left - state_area.left,
+            state_area.height,
+        );
+        let child_left = tree_area.left + tree_area.width;
+        let child_area = Area::new(
+            child_left,
+            state_area.top,
+            state_area.left + state_area.width - child_left,
+            state_area.height,
+        );
+        let tree = self.filtered_tree.as_ref().unwrap_or(&self.tree);
+        let show_hidden = tree.options.show_hidden;
+        let root = tree.root();
+        SideColumn::update(&mut self.parent_column, root.parent(), show_hidden);
+        let line = tree.selected_line();
+        let child_dir = if tree.selection > 0 && line.is_dir() {
+            Some(line.path.as_path())
+        } else {
+            None
+        };
+        SideColumn::update(&mut self.child_column, child_dir, show_hidden);
+        let root_name = root.file_name().map(|name| name.to_string_lossy());
+        let styles = &disc.panel_skin.styles;
+        let glyphs = &disc.con.glyphs;
+        SideColumn::write(
+            self.parent_column.as_ref(),
+            w,
+            &parent_area,
+            root_name.as_deref(),
+            false,
+            styles,
+            glyphs,
+        )?;
+        SideColumn::write(
+            self.child_column.as_ref(),
+            w,
+            &child_area,
+            None,
+            true,
+            styles,
+            glyphs,
+        )?;
+        Ok(())
+    }
+
+    /// display the summary of the directory at the given line of the
+    /// displayed tree, reading it unless it's the last one read
+    fn show_dir_info_of(&mut self, line_idx: usize, reuse: bool) -> CmdResult {
+        let line = &self.displayed_tree().lines[line_idx];
+        if !line.is_dir() {
+            return CmdResult::error(tr!(error_not_a_directory));
+        }
+        let known = self.dir_info.as_ref().map_or(false, |info| info.path == line.path);
+        if !(reuse && known) {
+            match DirInfo::new(&line.path, line.sum) {
+                Ok(info) => self.dir_info = Some(info),
+                Err(e) => return CmdResult::error(e.to_string()),
+            }
+        }
+        self.show_dir_info = true;
+        CmdResult::Keep
+    }
+
+    /// tell whether the tree must be rebuilt because the
+    /// auto refresh period elapsed
+    fn must_auto_refresh(&self) -> bool {
+        if self.auto_refresh_period
+            .map_or(false, |period| self.last_refresh.elapsed() >= period)
+        {
+            return true;
+        }
+        // changes notified during a build are gathered into one
+        // refresh per throttle duration
+        match (&self.fs_watch, self.live_refresh_throttle) {
+            (Some(fs_watch), Some(throttle)) => {
+                self.last_refresh.elapsed() >= throttle && fs_watch.has_changes()
+            }
+            _ => false,
+        }
+    }
+
+    /// watch the directories of the displayed tree, when they may
+    /// have changed since the last call (search, refresh, etc.)
+    fn update_fs_watch(&mut self) {
+        if !self.fs_watch_outdated {
+            return;
+        }
+        self.fs_watch_outdated = false;
+        let tree = self.filtered_tree.as_ref().unwrap_or(&self.tree);
+        if let Some(fs_watch) = self.fs_watch.as_mut() {
+            fs_watch.set_dirs(
+                tree.lines.iter()
+                    .filter(|line| line.is_dir())
+                    .map(|line| line.path.as_path()),
+            );
+        }
+    }
+
     fn search(&mut self, pattern: InputPattern, total: bool) {
         self.pending_task = Some(BrowserTask::Search { pattern, total });
     }
@@ -103,7 +331,32 @@ impl BrowserState {
     }
 
     pub fn page_height(screen: Screen) -> usize {
-        screen.height as usize - 2 // br shouldn't be displayed when the screen is smaller
+        // br shouldn't be displayed when the screen is smaller
+        if screen.zen {
+            screen.height as usize - 1
+        } else {
+            screen.height as usize - 2
+        }
+    }
+
+    /// on a search of paths matching directory components of the
+    /// selection, the markdown of the selected subpath, with the
+    /// components containing matching chars in bold, so that it's
+    /// clear why the line matched
+    fn matched_path_md(&self) -> Option<String> {
+        let tree = self.filtered_tree.as_ref()?;
+        let pattern = &tree.options.pattern.pattern;
+        if !pattern.object().subpath || tree.selection == 0 {
+            return None;
+        }
+        let line = tree.selected_line();
+        let name_match = pattern.search_string(&line.subpath)?;
+        let dirs_len = line.subpath.rfind('/')
+            .map(|idx| line.subpath[..idx].chars().count())?;
+        if !name_match.pos.iter().any(|&p| p < dirs_len) {
+            return None;
+        }
+        Some(name_match.wrap_components(&line.subpath, "**", "**", escape_md))
     }
 
     /// return a reference to the currently displayed tree, which
@@ -120,6 +373,7 @@ impl BrowserState {
 
     pub fn open_selection_stay_in_broot(
         &mut self,
+        w: &mut W,
         screen: Screen,
         con: &AppContext,
         in_new_panel: bool,
@@ -152,6 +406,19 @@ impl BrowserState {
                 None,
                 in_new_panel,
             ))
+        } else if let Some(rule) = opener_rules::find(&target, &con.openers) {
+            if rule.terminal {
+                let launchable = Launchable::program(
+                    rule.tokens(&target),
+                    None,
+                    Sandbox::default(),
+                    con,
+                )?;
+                launchable.execute(Some(w))?;
+            } else {
+                rule.spawn(&target)?;
+            }
+            Ok(CmdResult::Keep)
         } else {
             match opener::open(&target) {
                 Ok(exit_status) => {
@@ -181,7 +448,7 @@ impl BrowserState {
                 None,
                 in_new_panel,
             ),
-            None => CmdResult::error("no parent found"),
+            None => CmdResult::error(tr!(error_no_parent)),
         }
     }
 
@@ -205,17 +472,36 @@ impl PanelState for BrowserState {
         self.mode
     }
 
+    fn root_guard(&self) -> Option<&Arc<RootGuard>> {
+        self.root_guard.as_ref()
+    }
+
+    fn set_root_guard(&mut self, guard: Arc<RootGuard>) {
+        if guard.covers(self.root()) {
+            self.root_guard = Some(guard);
+        }
+    }
+
     fn get_pending_task(&self) -> Option<&'static str> {
-        if self.displayed_tree().has_dir_missing_sum() {
+        if self.displayed_tree().has_late_metadata() {
+            Some("reading metadata")
+        } else if self.displayed_tree().has_dir_missing_sum() {
             Some("computing stats")
         } else if self.displayed_tree().is_missing_git_status_computation() {
             Some("computing git status")
+        } else if self.displayed_tree().has_missing_lint(self.tree_area.height as usize) {
+            Some("running lint probes")
         } else {
             self
                 .pending_task.as_ref().map(|task| match task {
                     BrowserTask::Search{ .. } => "searching",
                     BrowserTask::StageAll(_) => "staging",
                 })
+                .or_else(|| if self.must_auto_refresh() {
+                    Some("refreshing")
+                } else {
+                    None
+                })
         }
     }
 
@@ -262,28 +548,33 @@ impl PanelState for BrowserState {
 
     fn clear_pending(&mut self) {
         self.pending_task = None;
+        // the directory summary is only shown until the next command
+        self.show_dir_info = false;
     }
 
     fn on_click(
         &mut self,
-        _x: u16,
+        x: u16,
         y: u16,
         _screen: Screen,
         _con: &AppContext,
     ) -> Result<CmdResult, ProgramError> {
-        self.displayed_tree_mut().try_select_y(y as usize);
+        if self.is_x_in_tree(x) {
+            self.displayed_tree_mut().try_select_y(y as usize);
+        }
         Ok(CmdResult::Keep)
     }
 
     fn on_double_click(
         &mut self,
-        _x: u16,
+        w: &mut W,
+        x: u16,
         y: u16,
         screen: Screen,
         con: &AppContext,
     ) -> Result<CmdResult, ProgramError> {
-        if self.displayed_tree().selection == y as usize {
-            self.open_selection_stay_in_broot(screen, con, false, false)
+        if self.is_x_in_tree(x) && self.displayed_tree().selection == y as usize {
+            self.open_selection_stay_in_broot(w, screen, con, false, false)
         } else {
             // A double click always come after a simple click at
             // same position. If it's not the selected line, it means
@@ -292,6 +583,37 @@ impl PanelState for BrowserState {
         }
     }
 
+    fn on_mouse_move(
+        &mut self,
+        x: u16,
+        y: u16,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let area = &self.tree_area;
+        if !cc.app.con.dir_info_on_hover
+            || x < area.left || x >= area.left + area.width
+            || y < area.top || y >= area.top + area.height
+        {
+            return Ok(CmdResult::Keep);
+        }
+        // the first row is the root, which isn't scrolled
+        let row = (y - area.top) as usize;
+        let tree = self.displayed_tree();
+        let line_idx = row + tree.scroll;
+        let is_dir = tree.lines.get(line_idx).map_or(false, |line| line.is_dir());
+        if row > 0 && is_dir {
+            // errors (eg a directory we can't read) aren't reported on hover
+            self.show_dir_info_of(line_idx, true);
+        }
+        Ok(CmdResult::Keep)
+    }
+
+    fn col_at(&self, x: u16, y: u16) -> Option<Col> {
+        self.cell_spans.iter()
+            .find(|span| span.y == y && span.x_start <= x && x < span.x_end)
+            .map(|span| span.col)
+    }
+
     fn on_pattern(
         &mut self,
         pat: InputPattern,
@@ -300,6 +622,7 @@ impl PanelState for BrowserState {
     ) -> Result<CmdResult, ProgramError> {
         if pat.is_none() {
             self.filtered_tree = None;
+            self.fs_watch_outdated = true;
         }
         if let Some(filtered_tree) = &self.filtered_tree {
             if pat != filtered_tree.options.pattern {
@@ -334,14 +657,21 @@ impl PanelState for BrowserState {
                         self.tree.make_selection_visible(page_height);
                     }
                     self.filtered_tree = None;
+                    self.fs_watch_outdated = true;
                     CmdResult::Keep
                 } else if self.tree.selection > 0 {
                     self.tree.selection = 0;
                     CmdResult::Keep
+                } else if self.tree.options.miller {
+                    self.go_up_selecting_root(cc.app.screen, cc.app.con)
                 } else {
                     CmdResult::PopState
                 }
             }
+            Internal::dir_info => {
+                let selection = self.displayed_tree().selection;
+                self.show_dir_info_of(selection, false)
+            }
             Internal::focus => internal_focus::on_internal(
                 internal_exec,
                 input_invocation,
@@ -359,6 +689,64 @@ impl PanelState for BrowserState {
                 app_state,
                 cc,
             ),
+            Internal::add_root => {
+                let root = self.tree.root().clone();
+                let arg = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref());
+                let path = match arg {
+                    Some(arg) => path::path_from(&root, PathAnchor::Unspecified, arg),
+                    None => self.displayed_tree().selected_line().path.clone(),
+                };
+                if !path.is_dir() {
+                    CmdResult::error(tr!(error_path_not_directory, path = format!("{:?}", &path)))
+                } else if path.starts_with(&root) || root.starts_with(&path) {
+                    CmdResult::error(tr!(error_root_overlap))
+                } else if self.tree.options.extra_roots.contains(&path) {
+                    CmdResult::error(tr!(error_root_already_searched))
+                } else {
+                    self.with_new_options(
+                        screen,
+                        &|o| {
+                            o.extra_roots.push(path.clone());
+                            "*Root added to searches*"
+                        },
+                        bang,
+                        con,
+                    )
+                }
+            }
+            Internal::clear_roots => {
+                if self.tree.options.extra_roots.is_empty() {
+                    CmdResult::error(tr!(error_no_added_root))
+                } else {
+                    self.with_new_options(
+                        screen,
+                        &|o| {
+                            o.extra_roots.clear();
+                            "*Added roots removed from searches*"
+                        },
+                        bang,
+                        con,
+                    )
+                }
+            }
+            Internal::exclude => {
+                let arg = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref());
+                match arg {
+                    Some(excluded) => {
+                        let raw_pattern = &self.displayed_tree().options.pattern.raw;
+                        CmdResult::ExecuteSequence {
+                            sequence: Sequence::new_single(
+                                CommandParts::add_exclusion(raw_pattern, excluded)
+                            ),
+                        }
+                    }
+                    None => CmdResult::error(tr!(error_exclude_expected_pattern)),
+                }
+            }
             Internal::up_tree => match self.displayed_tree().root().parent() {
                 Some(path) => internal_focus::on_path(
                     path.to_path_buf(),
@@ -367,10 +755,10 @@ impl PanelState for BrowserState {
                     bang,
                     con,
                 ),
-                None => CmdResult::error("no parent found"),
+                None => CmdResult::error(tr!(error_no_parent)),
             },
-            Internal::open_stay => self.open_selection_stay_in_broot(screen, con, bang, false)?,
-            Internal::open_stay_filter => self.open_selection_stay_in_broot(screen, con, bang, true)?,
+            Internal::open_stay => self.open_selection_stay_in_broot(w, screen, con, bang, false)?,
+            Internal::open_stay_filter => self.open_selection_stay_in_broot(w, screen, con, bang, true)?,
             Internal::line_down => {
                 let count = get_arg(input_invocation, internal_exec, 1);
                 self.displayed_tree_mut().move_selection(count, page_height, true);
@@ -391,6 +779,16 @@ impl PanelState for BrowserState {
                 self.displayed_tree_mut().move_selection(-count, page_height, false);
                 CmdResult::Keep
             }
+            Internal::goto => {
+                let row = get_arg(input_invocation, internal_exec, 0);
+                if row == 0 || row >= page_height {
+                    CmdResult::error(tr!(error_expected_row, max = page_height - 1))
+                } else if self.displayed_tree_mut().try_select_y(row) {
+                    CmdResult::Keep
+                } else {
+                    CmdResult::error(tr!(error_no_line_at_row, row = row))
+                }
+            }
             Internal::previous_dir => {
                 self.displayed_tree_mut().try_select_previous_filtered(
                     |line| line.is_dir(),
@@ -486,7 +884,35 @@ impl PanelState for BrowserState {
             Internal::panel_right_no_open => CmdResult::HandleInApp(Internal::panel_right_no_open),
             Internal::parent => self.go_to_parent(screen, con, bang),
             Internal::print_tree => {
-                print::print_tree(self.displayed_tree(), cc.app.screen, cc.app.panel_skin, con)?
+                let arg = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref())
+                    .filter(|arg| !arg.trim().is_empty());
+                match arg.map(|arg| arg.parse::<print::TreePrintFormat>()).transpose() {
+                    Ok(format) => print::print_tree(
+                        self.displayed_tree(),
+                        cc.app.screen,
+                        cc.app.panel_skin,
+                        format,
+                        con,
+                    )?,
+                    Err(e) => CmdResult::error(e),
+                }
+            }
+            Internal::print_tree_to => {
+                let arg = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref())
+                    .map(|arg| arg.trim())
+                    .filter(|arg| !arg.is_empty());
+                match arg {
+                    Some(arg) => {
+                        let tree = self.displayed_tree();
+                        let path = path::path_from(tree.root(), PathAnchor::Directory, arg);
+                        print::print_tree_to(tree, &path, cc.app.screen, cc.app.panel_skin, con)?
+                    }
+                    None => CmdResult::error(tr!(error_print_tree_expected_path)),
+                }
             }
             Internal::root_up => {
                 let tree = self.displayed_tree();
@@ -501,7 +927,7 @@ impl PanelState for BrowserState {
                         con,
                     )
                 } else {
-                    CmdResult::error(format!("{:?} has no parent", root))
+                    CmdResult::error(tr!(error_path_no_parent, path = format!("{:?}", root)))
                 }
             }
             Internal::root_down => {
@@ -521,7 +947,7 @@ impl PanelState for BrowserState {
                         con,
                     )
                 } else {
-                    CmdResult::error("No selected line")
+                    CmdResult::error(tr!(error_no_selected_line))
                 }
             }
             Internal::stage_all_files => {
@@ -586,10 +1012,10 @@ impl PanelState for BrowserState {
             Internal::total_search => {
                 match self.filtered_tree.as_ref().map(|t| t.total_search) {
                     None => {
-                        CmdResult::error("this verb can be used only after a search")
+                        CmdResult::error(tr!(error_needs_search))
                     }
                     Some(true) => {
-                        CmdResult::error("search was already total: all possible matches have been ranked")
+                        CmdResult::error(tr!(error_search_already_total))
                     }
                     Some(false) => {
                         self.search(self.displayed_tree().options.pattern.clone(), true);
@@ -637,7 +1063,26 @@ impl PanelState for BrowserState {
         ssb.is_filtered = self.filtered_tree.is_some();
         ssb.has_removed_pattern = false;
         ssb.on_tree_root = tree.selection == 0;
-        ssb.status()
+        let mut status = ssb.status();
+        if tree.build_report.memory_truncated {
+            status.message = format!(
+                "*Truncated*: the tree exceeds the memory budget. {}",
+                status.message,
+            );
+        }
+        let skipped_count = tree.build_report.content_skipped_count;
+        if skipped_count > 0 {
+            status.message = format!(
+                "*{}* file{} not searched (content search limits). {}",
+                skipped_count,
+                if skipped_count > 1 { "s" } else { "" },
+                status.message,
+            );
+        }
+        match self.matched_path_md() {
+            Some(path_md) => Status::from_message(format!("{}: {}", path_md, status.message)),
+            None => status,
+        }
     }
 
     /// do some work, totally or partially, if there's some to do.
@@ -668,6 +1113,7 @@ impl PanelState for BrowserState {
                         ft.try_select_best_match();
                         ft.make_selection_visible(BrowserState::page_height(screen));
                         self.filtered_tree = Some(ft);
+                        self.fs_watch_outdated = true;
                     }
                 }
                 BrowserTask::StageAll(pattern) => {
@@ -691,12 +1137,23 @@ impl PanelState for BrowserState {
                     }
                 }
             }
+        } else if self.displayed_tree().has_late_metadata() {
+            self.displayed_tree_mut().fetch_late_metadata();
         } else if self.displayed_tree().is_missing_git_status_computation() {
             let root_path = self.displayed_tree().root();
             let git_status = git::get_tree_status(root_path, dam);
             self.displayed_tree_mut().git_status = git_status;
-        } else {
+        } else if self.displayed_tree().has_missing_lint(self.tree_area.height as usize) {
+            let page_height = self.tree_area.height as usize;
+            self.displayed_tree_mut().fetch_some_missing_lint(page_height, con);
+        } else if self.displayed_tree().has_dir_missing_sum() {
             self.displayed_tree_mut().fetch_some_missing_dir_sum(dam, con);
+            if !self.displayed_tree().has_dir_missing_sum() {
+                self.record_sizes(con);
+            }
+        } else if self.must_auto_refresh() {
+            // the filtered tree, if any, is refreshed with its pattern
+            self.refresh(screen, con);
         }
         Ok(())
     }
@@ -706,18 +1163,70 @@ impl PanelState for BrowserState {
         w: &mut W,
         disc: &DisplayContext,
     ) -> Result<(), ProgramError> {
+        self.update_fs_watch();
+        let state_area = &disc.state_area;
+        let miller = self.displayed_tree().options.miller
+            && state_area.width >= MILLER_MIN_WIDTH;
+        self.tree_area = if miller {
+            let parent_width = state_area.width / 5;
+            Area::new(
+                state_area.left + parent_width,
+                state_area.top,
+                2 * state_area.width / 5,
+                state_area.height,
+            )
+        } else {
+            state_area.clone()
+        };
         let dp = DisplayableTree {
             app_state: Some(disc.app_state),
-            tree: self.displayed_tree(),
+            tree: self.filtered_tree.as_ref().unwrap_or(&self.tree),
             skin: &disc.panel_skin.styles,
             ext_colors: &disc.con.ext_colors,
-            area: disc.state_area.clone(),
+            glyphs: &disc.con.glyphs,
+            area: self.tree_area.clone(),
             in_app: true,
+            show_scrollbar: !disc.zen,
+            title: disc.con.tree_title.as_ref(),
+            size_reference: self.size_reference(disc.con),
         };
-        dp.write_on(w)
+        self.row_cache.start(&self.tree_area, disc.count);
+        dp.write_on_with_spans(w, Some(&mut self.cell_spans), Some(&mut self.row_cache))?;
+        if miller {
+            self.write_miller_columns(w, disc)?;
+        }
+        if let Some(info) = self.dir_info.as_ref().filter(|_| self.show_dir_info) {
+            let tree = self.displayed_tree();
+            let line_y = tree.lines.iter()
+                .position(|line| line.path == info.path)
+                .and_then(|idx| match idx {
+                    0 => Some(0), // the root isn't scrolled
+                    _ if idx > tree.scroll => Some((idx - tree.scroll) as u16),
+                    _ => None,
+                })
+                .filter(|&y| y < self.tree_area.height);
+            if let Some(line_y) = line_y {
+                info.write_popup(
+                    w,
+                    &self.tree_area,
+                    self.tree_area.top + line_y,
+                    &tree.options.date_time_format,
+                    &disc.panel_skin.styles,
+                    &disc.con.glyphs,
+                )?;
+            }
+        }
+        Ok(())
     }
 
     fn refresh(&mut self, screen: Screen, con: &AppContext) -> Command {
+        self.last_refresh = Instant::now();
+        if let Some(fs_watch) = &self.fs_watch {
+            fs_watch.clear();
+        }
+        self.fs_watch_outdated = true;
+        self.parent_column = None;
+        self.child_column = None;
         let page_height = BrowserState::page_height(screen) as usize;
         // refresh the base tree
         if let Err(e) = self.tree.refresh(page_height, con) {
@@ -735,6 +1244,21 @@ impl PanelState for BrowserState {
         })
     }
 
+    /// while new lines are highlighted, we need to wake up to
+    /// remove the highlight
+    fn refresh_period(&self) -> Option<Duration> {
+        let highlight_period = if self.displayed_tree().has_new_lines() {
+            Some(Duration::from_secs(1))
+        } else {
+            None
+        };
+        let live_refresh_period = self.fs_watch.as_ref().and(self.live_refresh_throttle);
+        [highlight_period, self.auto_refresh_period, live_refresh_period]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+
     fn get_flags(&self) -> Vec<Flag> {
         let options = &self.displayed_tree().options;
         vec![
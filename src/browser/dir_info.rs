@@ -0,0 +1,166 @@
+use {
+    crate::{
+        display::{format_count, invalidate_row_caches, DateTimeFormat, W},
+        errors::ProgramError,
+        file_sum::FileSum,
+        skin::{Glyphs, StyleMap},
+    },
+    crokey::crossterm::{cursor, QueueableCommand},
+    fnv::FnvHashMap,
+    std::{
+        fs, io,
+        path::{Path, PathBuf},
+        time::SystemTime,
+    },
+    termimad::{Area, CropWriter, SPACE_FILLING},
+};
+
+/// the number of file types listed in the popup
+const MAX_TYPES: usize = 3;
+
+/// A summary of the content of a directory, displayed in a
+/// transient popup over the tree
+#[derive(Debug, Clone)]
+pub struct DirInfo {
+    pub path: PathBuf,
+    /// number of direct children
+    pub children: usize,
+    /// number of direct children which are directories
+    pub dirs: usize,
+    /// number of files in the whole tree, when already computed
+    pub recursive_count: Option<usize>,
+    /// name and modification date of the most recently modified child
+    pub newest: Option<(String, SystemTime)>,
+    /// the most frequent extensions of the direct children files,
+    /// with their count, the most frequent first
+    pub types: Vec<(String, usize)>,
+}
+
+impl DirInfo {
+    /// read the directory, the recursive count being taken from
+    /// the already computed sum, if any
+    pub fn new(path: &Path, sum: Option<FileSum>) -> io::Result<Self> {
+        let mut children = 0;
+        let mut dirs = 0;
+        let mut newest: Option<(String, SystemTime)> = None;
+        let mut type_counts: FnvHashMap<String, usize> = FnvHashMap::default();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            children += 1;
+            let path = entry.path();
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => {
+                    continue;
+                }
+            };
+            if metadata.is_dir() {
+                dirs += 1;
+            } else if let Some(ext) = path.extension() {
+                *type_counts.entry(ext.to_string_lossy().to_lowercase()).or_default() += 1;
+            }
+            if let Ok(modified) = metadata.modified() {
+                if newest.as_ref().map_or(true, |(_, date)| *date < modified) {
+                    newest = Some((entry.file_name().to_string_lossy().to_string(), modified));
+                }
+            }
+        }
+        let mut types: Vec<(String, usize)> = type_counts.into_iter().collect();
+        types.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        types.truncate(MAX_TYPES);
+        Ok(Self {
+            path: path.to_path_buf(),
+            children,
+            dirs,
+            recursive_count: sum.or_else(|| FileSum::from_cache(path)).map(FileSum::to_count),
+            newest,
+            types,
+        })
+    }
+
+    /// the lines of text of the popup, the first one being its title
+    fn lines(&self, date_time_format: &DateTimeFormat) -> Vec<String> {
+        let name = self.path.file_name()
+            .map_or_else(|| self.path.to_string_lossy(), |name| name.to_string_lossy());
+        let mut lines = vec![
+            name.to_string(),
+            format!(
+                "{} children: {} files, {} dirs",
+                format_count(self.children),
+                format_count(self.children - self.dirs),
+                format_count(self.dirs),
+            ),
+        ];
+        if let Some(count) = self.recursive_count {
+            lines.push(format!("{} files in the whole tree", format_count(count)));
+        }
+        if let Some((name, date)) = &self.newest {
+            lines.push(format!("newest: {} ({})", name, date_time_format.format_date_time(*date)));
+        }
+        if !self.types.is_empty() {
+            let types: Vec<String> = self.types.iter()
+                .map(|(ext, count)| format!("{} {}", ext, format_count(*count)))
+                .collect();
+            lines.push(format!("types: {}", types.join(", ")));
+        }
+        lines
+    }
+
+    /// draw the popup in the area, next to the tree line at line_y
+    /// (below it when there's room)
+    pub fn write_popup(
+        &self,
+        w: &mut W,
+        area: &Area,
+        line_y: u16,
+        date_time_format: &DateTimeFormat,
+        styles: &StyleMap,
+        glyphs: &Glyphs,
+    ) -> Result<(), ProgramError> {
+        let lines = self.lines(date_time_format);
+        let height = lines.len() as u16 + 2;
+        // 4 for the borders and the margins, 1 for the scrollbar
+        let width = lines.iter()
+            .map(|line| line.chars().count() + 4)
+            .max().unwrap_or(0)
+            .min(area.width as usize - 1);
+        if height > area.height || width < 8 {
+            return Ok(());
+        }
+        let left = area.left + area.width - 1 - width as u16;
+        let top = if line_y + 1 + height <= area.top + area.height {
+            line_y + 1
+        } else {
+            line_y.saturating_sub(height).max(area.top)
+        };
+        let border_style = &styles.help_table_border;
+        let txt_style = &styles.default;
+        let title_style = &styles.directory;
+        for y in 0..height {
+            w.queue(cursor::MoveTo(left, top + y))?;
+            let mut cw = CropWriter::new(w, width);
+            if y == 0 || y + 1 == height {
+                let (start, end) = if y == 0 {
+                    (glyphs.border_top_left, glyphs.border_top_right)
+                } else {
+                    (glyphs.border_bottom_left, glyphs.border_bottom_right)
+                };
+                cw.queue_char(border_style, start)?;
+                cw.repeat(border_style, glyphs.border_filling(), width - 2)?;
+                cw.queue_char(border_style, end)?;
+                continue;
+            }
+            cw.queue_char(border_style, glyphs.border_vertical)?;
+            cw.queue_char(txt_style, ' ')?;
+            let line_style = if y == 1 { title_style } else { txt_style };
+            let line: String = lines[y as usize - 1].chars().take(width - 4).collect();
+            let line_width = line.chars().count();
+            cw.queue_g_string(line_style, line)?;
+            cw.repeat(txt_style, &SPACE_FILLING, width - 3 - line_width)?;
+            cw.queue_char(border_style, glyphs.border_vertical)?;
+        }
+        // the rows under the popup will have to be written again
+        invalidate_row_caches();
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use {
+    crate::{
+        display::W,
+        errors::ProgramError,
+        skin::{Glyphs, StyleMap},
+    },
+    crokey::crossterm::{cursor, style::Color, QueueableCommand},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+    termimad::{Area, CropWriter, SPACE_FILLING},
+};
+
+/// the minimal width of the panel for the Miller columns to be
+/// displayed (the tree is displayed alone in narrower panels)
+pub const MILLER_MIN_WIDTH: u16 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SideEntryKind {
+    Dir,
+    File,
+    Link,
+}
+
+#[derive(Debug)]
+struct SideEntry {
+    name: String,
+    kind: SideEntryKind,
+}
+
+/// A side column of the Miller view, listing the entries of a
+/// directory: the parent of the root on the left, the selected
+/// directory on the right.
+///
+/// It's read again only when the directory or the options change.
+#[derive(Debug)]
+pub struct SideColumn {
+    dir: PathBuf,
+    show_hidden: bool,
+    entries: Vec<SideEntry>,
+}
+
+impl SideColumn {
+    /// read the directory, an unreadable one giving an empty column
+    pub fn read(dir: &Path, show_hidden: bool) -> Self {
+        let mut entries: Vec<SideEntry> = match fs::read_dir(dir) {
+            Ok(read_dir) => read_dir
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    if !show_hidden && name.starts_with('.') {
+                        return None;
+                    }
+                    let kind = match entry.file_type() {
+                        Ok(ft) if ft.is_symlink() => SideEntryKind::Link,
+                        Ok(ft) if ft.is_dir() => SideEntryKind::Dir,
+                        _ => SideEntryKind::File,
+                    };
+                    Some(SideEntry { name, kind })
+                })
+                .collect(),
+            Err(e) => {
+                debug!("can't read {:?} for the Miller column: {}", dir, e);
+                Vec::new()
+            }
+        };
+        entries.sort_by_cached_key(|entry| entry.name.to_lowercase());
+        Self {
+            dir: dir.to_path_buf(),
+            show_hidden,
+            entries,
+        }
+    }
+
+    /// make the column list the given directory, reading it only
+    /// when it's not the one already listed
+    pub fn update(column: &mut Option<Self>, dir: Option<&Path>, show_hidden: bool) {
+        *column = match dir {
+            Some(dir) if column.as_ref().map_or(false, |c| c.is_of(dir, show_hidden)) => {
+                column.take()
+            }
+            Some(dir) => Some(Self::read(dir, show_hidden)),
+            None => None,
+        };
+    }
+
+    fn is_of(&self, dir: &Path, show_hidden: bool) -> bool {
+        self.dir == dir && self.show_hidden == show_hidden
+    }
+
+    /// write the entries of the column, if any, in the area, the selected
+    /// one being highlighted and made visible, with a border on the side
+    /// of the tree
+    pub fn write(
+        column: Option<&Self>,
+        w: &mut W,
+        area: &Area,
+        selected: Option<&str>,
+        border_on_left: bool,
+        styles: &StyleMap,
+        glyphs: &Glyphs,
+    ) -> Result<(), ProgramError> {
+        let entries = column.map_or(&[][..], |c| c.entries.as_slice());
+        let height = area.height as usize;
+        let width = area.width as usize - 1; // one char for the border
+        let selected_idx = selected
+            .and_then(|name| entries.iter().position(|entry| entry.name == name));
+        let scroll = match selected_idx {
+            Some(idx) if idx >= height => (idx - height / 2).min(entries.len() - height),
+            _ => 0,
+        };
+        let selection_bg = styles.selected_line.get_bg()
+            .unwrap_or(Color::AnsiValue(240));
+        let border_style = &styles.help_table_border;
+        for y in 0..height {
+            w.queue(cursor::MoveTo(area.left, area.top + y as u16))?;
+            if border_on_left {
+                border_style.queue(w, glyphs.border_vertical)?;
+            }
+            let mut cw = CropWriter::new(w, width);
+            let entry_idx = scroll + y;
+            if let Some(entry) = entries.get(entry_idx) {
+                let mut style = match entry.kind {
+                    SideEntryKind::Dir => styles.directory.clone(),
+                    SideEntryKind::File => styles.file.clone(),
+                    SideEntryKind::Link => styles.link.clone(),
+                };
+                if selected_idx == Some(entry_idx) {
+                    style.set_bg(selection_bg);
+                }
+                cw.queue_char(&style, ' ')?;
+                cw.queue_str(&style, &entry.name)?;
+                cw.fill(&style, &SPACE_FILLING)?;
+            } else {
+                cw.fill(&styles.default, &SPACE_FILLING)?;
+            }
+            if !border_on_left {
+                border_style.queue(w, glyphs.border_vertical)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,8 @@
 mod browser_state;
+mod dir_info;
+mod miller;
 
-pub use browser_state::BrowserState;
+pub use {
+    browser_state::BrowserState,
+    dir_info::DirInfo,
+};
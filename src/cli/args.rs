@@ -116,6 +116,22 @@ pub struct Args {
     #[clap(short='T', long, action)]
     pub no_trim_root: bool,
 
+    /// List the files of all depths, without their directories
+    #[clap(long, action)]
+    pub flat: bool,
+
+    /// Display the tree of directories (default)
+    #[clap(long, action)]
+    pub no_flat: bool,
+
+    /// Show the parent and the selected directories in columns around the tree
+    #[clap(long, action)]
+    pub miller: bool,
+
+    /// Don't show the Miller columns (default)
+    #[clap(long, action)]
+    pub no_miller: bool,
+
     /// Where to write the produced cmd (if any)
     #[clap(long, value_parser)]
     pub outcmd: Option<PathBuf>,
@@ -124,15 +140,43 @@ pub struct Args {
     #[clap(short, long, value_parser)]
     pub cmd: Option<String>,
 
+    /// Format of the tree printed by `:print_tree` (for example
+    /// with `--cmd :pt`)
+    #[clap(long, arg_enum, value_parser, value_name="FORMAT")]
+    pub out_format: Option<OutFormat>,
+
+    /// Refuse to show or open paths outside of this directory
+    #[clap(long, value_parser, value_name="DIR")]
+    pub stay_under: Option<PathBuf>,
+
+    /// Disable the verbs writing files
+    #[clap(long, action)]
+    pub read_only: bool,
+
+    /// Display the commands of external verbs instead of executing them
+    #[clap(long, action)]
+    pub dry_run: bool,
+
+    /// Restore the panels, trees and stage saved on last quit
+    /// (see the `save_session` setting)
+    #[clap(long, action)]
+    pub resume: bool,
+
     /// Whether to have styles and colors (auto is default and usually OK)
     #[clap(long, arg_enum, value_parser, default_value="auto")]
     pub color: TriBool,
 
+    /// Only write ASCII chars, for borders, branches, bars and
+    /// marks (for legacy terminals or log viewers)
+    #[clap(long, action)]
+    pub ascii: bool,
+
     /// Semicolon separated paths to specific config files"),
     #[clap(long, value_parser)]
     pub conf: Option<String>,
 
-    /// Height (if you don't want to fill the screen or for file export)
+    /// Height (if you don't want to fill the screen, then broot is displayed
+    /// inline below the prompt, or for file export)
     #[clap(long, value_parser)]
     pub height: Option<u16>,
 
@@ -162,11 +206,37 @@ pub struct Args {
     #[clap(long, value_parser)]
     pub write_default_conf: Option<PathBuf>,
 
+    /// A file descriptor to which JSON events (selection changes,
+    /// verb executions, new states) are written
+    #[cfg(unix)]
+    #[clap(long, value_parser, value_name="FD")]
+    pub events_fd: Option<i32>,
+
+    /// Print the list of filesystems as JSON, then quit
+    #[cfg(unix)]
+    #[clap(long, action)]
+    pub list_fs_json: bool,
+
+    /// Print the messages of the interface in the format of a
+    /// translation file, then quit
+    #[clap(long, action)]
+    pub print_messages: bool,
+
     /// A socket that broot sends commands to before quitting
     #[cfg(unix)]
     #[clap(long, value_parser)]
     pub send: Option<String>,
 
+    /// A JSON lines file of commands and keys to execute, after which
+    /// the final state is printed as JSON
+    #[clap(long, value_parser, value_name="FILE")]
+    pub replay: Option<PathBuf>,
+
+    /// Open the file in the pager (the preview) without building a tree,
+    /// read stdin if no file is given
+    #[clap(long, action)]
+    pub pager: bool,
+
     /// Root Directory
     #[clap(value_parser, value_name="FILE")]
     pub root: Option<PathBuf>,
@@ -193,6 +263,16 @@ impl TriBool {
     }
 }
 
+/// The format in which `:print_tree` writes the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+pub enum OutFormat {
+    Txt,
+    Ansi,
+    Html,
+    Json,
+    Csv,
+}
+
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
 pub enum ShellInstallState {
     Undefined, // before any install, this is the initial state
@@ -15,7 +15,7 @@ use {
     crate::{
         app::{App, AppContext},
         conf::{Conf, write_default_conf_in},
-        display,
+        display::{self, Screen},
         errors::ProgramError,
         launchable::Launchable,
         shell_install::{ShellInstall, write_state},
@@ -32,6 +32,7 @@ use {
         io::{self, Write},
         path::PathBuf,
     },
+    tempfile::NamedTempFile,
 };
 
 /// run the application, and maybe return a launchable
@@ -39,7 +40,7 @@ use {
 pub fn run() -> Result<Option<Launchable>, ProgramError> {
 
     // parse the launch arguments we got from cli
-    let args = Args::parse();
+    let mut args = Args::parse();
     let mut must_quit = false;
 
     if let Some(dir) = &args.write_default_conf {
@@ -92,11 +93,30 @@ pub fn run() -> Result<Option<Launchable>, ProgramError> {
     };
     debug!("config: {:#?}", &config);
 
+    // in pager mode with no given file, we page a copy of stdin,
+    // which must live as long as the application
+    let mut _stdin_copy = None;
+    if args.pager && args.root.is_none() {
+        let stdin_copy = copy_stdin()?;
+        args.root = Some(stdin_copy.path().to_path_buf());
+        _stdin_copy = Some(stdin_copy);
+    }
+
     // verb store is completed from the config file(s)
     let verb_store = VerbStore::new(&mut config)?;
 
     let mut context = AppContext::from(args, verb_store, &config)?;
 
+    if context.launch_args.print_messages {
+        return Ok(Some(Launchable::printer(crate::i18n::translation_template())));
+    }
+
+    #[cfg(unix)]
+    if context.launch_args.list_fs_json {
+        let json = crate::filesystems::list_mounts_json(context.filesystems_only_disks)?;
+        return Ok(Some(Launchable::printer(json)));
+    }
+
     #[cfg(unix)]
     if let Some(server_name) = &context.launch_args.send {
         use crate::{
@@ -119,23 +139,69 @@ pub fn run() -> Result<Option<Launchable>, ProgramError> {
         return Ok(None);
     }
 
+    // with single_instance, an instance of the same terminal whose
+    // root is the asked directory is reused, otherwise this one listens so that
+    // it can be reused
+    #[cfg(unix)]
+    if config.single_instance == Some(true)
+        && context.launch_args.listen.is_none()
+        && context.launch_args.cmd.is_none()
+        && !context.launch_args.pager
+    {
+        if crate::net::focus_in_running_instance(&context.initial_root) {
+            return Ok(None);
+        }
+        context.launch_args.listen = crate::net::single_instance_name();
+    }
+
     let mut w = display::writer();
+    // with a height smaller than the terminal's, broot is displayed
+    // inline, below the prompt, unless there's no terminal to query
+    // (a bigger height is a virtual screen, eg for exports)
+    let inline_height = context.launch_args.height
+        .filter(|&height| height < termimad::terminal_size().1);
+    if let Some(height) = inline_height {
+        match Screen::make_inline_room(&mut w, height) {
+            Ok(top) => {
+                context.inline_top = Some(top);
+            }
+            Err(e) => {
+                warn!("can't display inline: {}", e);
+            }
+        }
+    }
     let app = App::new(&context)?;
-    w.queue(EnterAlternateScreen)?;
+    if context.inline_top.is_none() {
+        w.queue(EnterAlternateScreen)?;
+    }
     w.queue(cursor::Hide)?;
-    if context.capture_mouse {
+    if context.term_caps.mouse {
         w.queue(EnableMouseCapture)?;
     }
     let r = app.run(&mut w, &mut context, &config);
-    if context.capture_mouse {
+    if context.term_caps.mouse {
         w.queue(DisableMouseCapture)?;
     }
     w.queue(cursor::Show)?;
-    w.queue(LeaveAlternateScreen)?;
+    if context.inline_top.is_some() {
+        Screen::new(&context)?.clear_inline(&mut w)?;
+    } else {
+        w.queue(LeaveAlternateScreen)?;
+    }
     w.flush()?;
     r
 }
 
+/// copy the whole content of stdin into a temporary file
+fn copy_stdin() -> Result<NamedTempFile, ProgramError> {
+    let mut file = tempfile::Builder::new()
+        .prefix("broot-stdin-")
+        .tempfile()?;
+    io::copy(&mut io::stdin(), &mut file)?;
+    file.flush()?;
+    Ok(file)
+}
+
 /// wait for user input, return `true` if they didn't answer 'n'
 pub fn ask_authorization() -> Result<bool, ProgramError> {
     let mut answer = String::new();
@@ -0,0 +1,142 @@
+use {
+    crate::{
+        display::Col,
+        errors::ConfError,
+        verb::VerbInvocation,
+    },
+    fnv::FnvHashMap,
+    std::{
+        convert::TryFrom,
+        str::FromStr,
+    },
+};
+
+/// the kind of mouse click, as can be bound in configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickKind {
+    Click,
+    DoubleClick,
+    MiddleClick,
+}
+
+impl FromStr for ClickKind {
+    type Err = ConfError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_ref() {
+            "click" => Ok(Self::Click),
+            "double_click" => Ok(Self::DoubleClick),
+            "middle_click" => Ok(Self::MiddleClick),
+            _ => Err(ConfError::InvalidClickBinding {
+                details: format!("{s:?} isn't a click kind (expected click, double_click or middle_click)"),
+            }),
+        }
+    }
+}
+
+/// the binding of a click, anywhere on a row or on a specific
+/// column, to a verb invocation
+#[derive(Debug, Clone)]
+pub struct ClickBinding {
+    pub kind: ClickKind,
+    /// when None, the binding applies on the whole row
+    pub col: Option<Col>,
+    pub invocation: VerbInvocation,
+}
+
+impl ClickBinding {
+    /// parse a binding from its configuration, where the key is made
+    /// of the click kind optionally followed by a column name (eg
+    /// "double_click" or "click date") and the value is a verb invocation
+    pub fn parse(conf_key: &str, conf_invocation: &str) -> Result<Self, ConfError> {
+        let mut tokens = conf_key.split_whitespace();
+        let kind = tokens.next()
+            .ok_or_else(|| ConfError::InvalidClickBinding {
+                details: "empty click binding key".to_string(),
+            })?
+            .parse()?;
+        let col = tokens.next()
+            .map(|t| t.parse())
+            .transpose()?;
+        if tokens.next().is_some() {
+            return Err(ConfError::InvalidClickBinding {
+                details: format!("unexpected token in {conf_key:?}"),
+            });
+        }
+        let conf_invocation = conf_invocation.trim();
+        let invocation = VerbInvocation::from(
+            conf_invocation.strip_prefix(':').unwrap_or(conf_invocation)
+        );
+        if invocation.is_empty() {
+            return Err(ConfError::InvalidClickBinding {
+                details: format!("missing verb for {conf_key:?}"),
+            });
+        }
+        Ok(Self { kind, col, invocation })
+    }
+}
+
+/// the click bindings found in configuration
+#[derive(Debug, Clone, Default)]
+pub struct ClickBindings {
+    pub bindings: Vec<ClickBinding>,
+}
+
+impl TryFrom<&FnvHashMap<String, String>> for ClickBindings {
+    type Error = ConfError;
+    fn try_from(map: &FnvHashMap<String, String>) -> Result<Self, Self::Error> {
+        let mut bindings = Vec::new();
+        for (k, v) in map {
+            bindings.push(ClickBinding::parse(k, v)?);
+        }
+        Ok(Self { bindings })
+    }
+}
+
+impl ClickBindings {
+    /// return the invocation bound to the click, preferring the
+    /// bindings specific to the clicked column
+    pub fn find(&self, kind: ClickKind, col: Option<Col>) -> Option<&VerbInvocation> {
+        let mut row_binding = None;
+        for binding in &self.bindings {
+            if binding.kind != kind {
+                continue;
+            }
+            match binding.col {
+                None => row_binding = Some(&binding.invocation),
+                Some(bc) if Some(bc) == col => return Some(&binding.invocation),
+                _ => {}
+            }
+        }
+        row_binding
+    }
+}
+
+#[cfg(test)]
+mod click_bindings_tests {
+
+    use super::*;
+
+    #[test]
+    fn check_click_bindings() {
+        let mut map = FnvHashMap::default();
+        map.insert("double_click".to_string(), ":open_stay".to_string());
+        map.insert("middle-click".to_string(), "toggle_stage".to_string());
+        map.insert("click size".to_string(), ":sort_by_size".to_string());
+        let cb = ClickBindings::try_from(&map).unwrap();
+        assert_eq!(
+            cb.find(ClickKind::DoubleClick, Some(Col::Date)),
+            Some(&VerbInvocation::new("open_stay", None, false)),
+        );
+        assert_eq!(
+            cb.find(ClickKind::MiddleClick, None),
+            Some(&VerbInvocation::new("toggle_stage", None, false)),
+        );
+        assert_eq!(
+            cb.find(ClickKind::Click, Some(Col::Size)),
+            Some(&VerbInvocation::new("sort_by_size", None, false)),
+        );
+        assert_eq!(cb.find(ClickKind::Click, Some(Col::Date)), None);
+        assert!(ClickBinding::parse("triple_click", ":open_stay").is_err());
+        assert!(ClickBinding::parse("click nowhere", ":open_stay").is_err());
+    }
+}
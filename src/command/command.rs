@@ -51,6 +51,12 @@ pub enum Command {
     /// a mouse double-click
     /// Always come after a simple click at same position
     DoubleClick(u16, u16),
+
+    /// a click with the middle button
+    MiddleClick(u16, u16),
+
+    /// a move of the mouse, with no button pressed
+    MouseMove(u16, u16),
 }
 
 impl Command {
@@ -6,7 +6,6 @@ use {
             SelInfo,
         },
         path::{self, PathAnchor},
-        syntactic::SYNTAX_THEMES,
         verb::{
             ArgDef,
             PrefixSearchResult,
@@ -160,7 +159,7 @@ impl Completions {
             .and_then(|verb| verb.invocation_parser.as_ref())
             .and_then(|invocation_parser| invocation_parser.get_unique_arg_def());
         if matches!(arg_def, Some(ArgDef::Theme)) {
-            Self::for_theme_arg(arg)
+            Self::for_theme_arg(arg, con)
         } else {
             Self::for_path_arg(verb_name, arg, con, sel_info)
         }
@@ -169,9 +168,11 @@ impl Completions {
     /// we have a verb and it asks for a theme
     fn for_theme_arg(
         arg: &str,
+        con: &AppContext,
     ) -> Self {
         let arg = arg.to_lowercase();
-        let completions: Vec<String> = SYNTAX_THEMES
+        let completions: Vec<String> = con
+            .syntax_themes()
             .iter()
             .map(|st| st.name().to_lowercase())
             .filter_map(|name| name.strip_prefix(&arg).map(|s| s.to_string()))
@@ -1,3 +1,4 @@
+mod click;
 mod command;
 mod completion;
 mod panel_input;
@@ -8,12 +9,13 @@ mod scroll;
 mod trigger_type;
 
 pub use {
+    click::*,
     command::Command,
     completion::Completions,
     panel_input::PanelInput,
     parts::CommandParts,
     sequence::Sequence,
-    sel::move_sel,
+    sel::{fix_scroll, move_sel},
     scroll::ScrollCommand,
     trigger_type::TriggerType,
 };
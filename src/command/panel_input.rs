@@ -29,6 +29,8 @@ pub struct PanelInput {
     pub input_field: InputField,
     tab_cycle_count: usize,
     input_before_cycle: Option<String>,
+    /// the input before the cursor, when the cheat sheet is displayed
+    help_input: Option<String>,
 }
 
 impl PanelInput {
@@ -38,6 +40,7 @@ impl PanelInput {
             input_field: InputField::new(area),
             tab_cycle_count: 0,
             input_before_cycle: None,
+            help_input: None,
         }
     }
 
@@ -49,6 +52,27 @@ impl PanelInput {
         self.input_field.get_content()
     }
 
+    /// the part of the input before the cursor, if the
+    /// cheat sheet of the input syntax must be displayed
+    pub fn help_input(&self) -> Option<&str> {
+        self.help_input.as_deref()
+    }
+
+    /// return the part of the input before the cursor
+    fn content_before_cursor(&mut self) -> String {
+        // the input field doesn't expose the cursor position, so
+        // we find it with a mark we remove just after
+        const MARK: char = '\u{1}';
+        let content = self.input_field.get_content();
+        self.input_field.put_char(MARK);
+        let marked = self.input_field.get_content();
+        self.input_field.del_char_left();
+        if !self.input_field.is_content(&content) {
+            self.input_field.set_str(&content);
+        }
+        marked.split(MARK).next().unwrap_or_default().to_string()
+    }
+
     pub fn display(
         &mut self,
         w: &mut W,
@@ -204,6 +228,12 @@ impl PanelInput {
                                 Command::Click(column, row)
                             }
                         }
+                        MouseEventKind::Up(MouseButton::Middle) => {
+                            Command::MiddleClick(column, row)
+                        }
+                        MouseEventKind::Moved => {
+                            Command::MouseMove(column, row)
+                        }
                         MouseEventKind::ScrollDown => {
                             Command::Internal {
                                 internal: Internal::line_down,
@@ -225,6 +255,9 @@ impl PanelInput {
                 let raw = self.input_field.get_content();
                 let mut parts = CommandParts::from(raw.clone());
 
+                // the cheat sheet is closed by any key
+                let help_was_displayed = self.help_input.take().is_some();
+
                 // we first handle the cases that MUST absolutely
                 // not be overridden by configuration
 
@@ -322,13 +355,19 @@ impl PanelInput {
                             if *verb_key != key {
                                 continue;
                             }
+                            if verb.is_internal(Internal::input_help) {
+                                if !help_was_displayed {
+                                    self.help_input = Some(self.content_before_cursor());
+                                }
+                                return Command::None;
+                            }
                             if self.handle_input_related_verb(verb, con) {
                                 return Command::from_raw(self.input_field.get_content(), false);
                             }
                             if !verb.selection_condition.is_respected_by(sel_info.common_stype()) {
                                 continue;
                             }
-                            if !verb.can_be_called_in_panel(panel_state_type) {
+                            if !verb.keys_apply_in_panel(panel_state_type) {
                                 continue;
                             }
                             if mode != Mode::Input && verb.is_internal(Internal::mode_input) {
@@ -7,6 +7,106 @@ use {
     std::fmt,
 };
 
+/// a token of the pattern, kept in case the tree must be rebuilt
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PatternToken {
+    Atom,
+    Operator(PatternOperator),
+    OpeningPar,
+    ClosingPar,
+}
+
+/// an expression whose atoms are indexes in the atoms of the pattern
+enum Expr {
+    Atom(usize),
+    Not(Option<Box<Expr>>),
+    Binary(PatternOperator, Box<Expr>, Option<Box<Expr>>),
+}
+
+impl Expr {
+    /// parse a sequence of operands joined by binary operators,
+    /// evaluated left to right, until a closing parenthesis or the end
+    fn parse_sequence(
+        tokens: &[PatternToken],
+        idx: &mut usize,
+        atom_idx: &mut usize,
+    ) -> Option<Self> {
+        let mut left = Self::parse_operand(tokens, idx, atom_idx)?;
+        while let Some(token) = tokens.get(*idx) {
+            *idx += 1;
+            match token {
+                PatternToken::Operator(op) => {
+                    let right = Self::parse_operand(tokens, idx, atom_idx).map(Box::new);
+                    left = Self::Binary(*op, Box::new(left), right);
+                }
+                _ => break, // closing parenthesis
+            }
+        }
+        Some(left)
+    }
+    /// parse an atom, a negation or a parenthesized sequence
+    fn parse_operand(
+        tokens: &[PatternToken],
+        idx: &mut usize,
+        atom_idx: &mut usize,
+    ) -> Option<Self> {
+        let token = tokens.get(*idx)?;
+        *idx += 1;
+        match token {
+            PatternToken::Atom => {
+                *atom_idx += 1;
+                Some(Self::Atom(*atom_idx - 1))
+            }
+            PatternToken::Operator(_) => Some(Self::Not(
+                Self::parse_operand(tokens, idx, atom_idx).map(Box::new)
+            )),
+            PatternToken::OpeningPar => Self::parse_sequence(tokens, idx, atom_idx),
+            PatternToken::ClosingPar => None,
+        }
+    }
+    /// push the expression in the tree, with parenthesis around binary
+    /// operations so that the tree's structure follows the expression's
+    fn push_to(self, pt: &mut BeTree<PatternOperator, PatternParts>, atoms: &[PatternParts]) {
+        match self {
+            Self::Atom(idx) => {
+                pt.push_atom(atoms[idx].clone());
+            }
+            Self::Not(operand) => {
+                pt.push_operator(PatternOperator::Not);
+                if let Some(operand) = operand {
+                    operand.push_to(pt, atoms);
+                }
+            }
+            Self::Binary(op, left, right) => {
+                pt.open_par();
+                left.push_to(pt, atoms);
+                pt.push_operator(op);
+                if let Some(right) = right {
+                    right.push_to(pt, atoms);
+                }
+                pt.close_par();
+            }
+        }
+    }
+}
+
+/// make a tree where negations only apply to their operand.
+///
+/// When a binary operator follows a negation which was itself after a binary
+/// operator (as in `a&!b|c`), a tree built token per token would be wrong
+/// (`a&(!b|c)`), so it's rebuilt from the tokens.
+fn rebuild_pattern_tree(
+    pt: BeTree<PatternOperator, PatternParts>,
+    tokens: &[PatternToken],
+) -> BeTree<PatternOperator, PatternParts> {
+    let atoms = pt.atoms();
+    let mut rebuilt = BeTree::new();
+    if let Some(expr) = Expr::parse_sequence(tokens, &mut 0, &mut 0) {
+        expr.push_to(&mut rebuilt, &atoms);
+    }
+    rebuilt
+}
+
 /// An intermediate parsed representation of the raw string
 #[derive(Debug, Clone, PartialEq)]
 pub struct CommandParts {
@@ -25,6 +125,17 @@ impl fmt::Display for CommandParts {
     }
 }
 
+/// return the atom being written, creating it if necessary
+fn atom<'t>(
+    pt: &'t mut BeTree<PatternOperator, PatternParts>,
+    tokens: &mut Vec<PatternToken>,
+) -> &'t mut PatternParts {
+    if tokens.last() != Some(&PatternToken::Atom) {
+        tokens.push(PatternToken::Atom);
+    }
+    pt.mutate_or_create_atom(PatternParts::default)
+}
+
 impl CommandParts {
     pub fn has_not_empty_verb_invocation(&self) -> bool {
         self.verb_invocation
@@ -38,6 +149,10 @@ impl CommandParts {
         let mut chars = raw.char_indices().peekable();
         let mut escape_cur_char = false;
         let mut escape_next_char = false;
+        let mut tokens: Vec<PatternToken> = Vec::new();
+        // for each level of parenthesis, whether a negation follows a binary operator
+        let mut negations_after_binary = vec![false];
+        let mut needs_rebuild = false;
         // we loop on chars and build the pattern tree until we reach an unescaped ' ' or ':'
         while let Some((pos, cur_char)) = chars.next() {
             let between_slashes = pt.current_atom()
@@ -50,7 +165,7 @@ impl CommandParts {
                     // Escaping is used to prevent characters from being consumed at the
                     // composite pattern level (or, and, parens) or as the separator between
                     // the pattern and the verb. An escaped char is usable in a pattern atom.
-                    pt.mutate_or_create_atom(PatternParts::default).push(c);
+                    atom(&mut pt, &mut tokens).push(c);
                 }
                 '\\' => {
                     // Pattern escaping rules:
@@ -71,7 +186,7 @@ impl CommandParts {
                     };
                     if !escape_next_char {
                         // if the '\' isn't used for escaping, it's used as its char value
-                        pt.mutate_or_create_atom(PatternParts::default).push('\\');
+                        atom(&mut pt, &mut tokens).push('\\');
                     }
                 }
                 ' ' | ':' => { // ending the pattern part
@@ -79,30 +194,46 @@ impl CommandParts {
                     break;
                 }
                 '/' => { // starting an atom part
-                    pt.mutate_or_create_atom(PatternParts::default).add_part();
-                }
-                '|' if !between_slashes && pt.accept_binary_operator() => {
-                    pt.push_operator(PatternOperator::Or);
+                    atom(&mut pt, &mut tokens).add_part();
                 }
-                '&' if !between_slashes && pt.accept_binary_operator() => {
-                    pt.push_operator(PatternOperator::And);
+                '|' | '&' if !between_slashes && pt.accept_binary_operator() => {
+                    let op = if cur_char == '|' { PatternOperator::Or } else { PatternOperator::And };
+                    if let Some(negation_after_binary) = negations_after_binary.last_mut() {
+                        needs_rebuild |= *negation_after_binary;
+                        *negation_after_binary = false;
+                    }
+                    pt.push_operator(op);
+                    tokens.push(PatternToken::Operator(op));
                 }
                 '!' if !between_slashes && pt.accept_unary_operator() => {
+                    if let Some(PatternToken::Operator(PatternOperator::And | PatternOperator::Or)) = tokens.last() {
+                        if let Some(negation_after_binary) = negations_after_binary.last_mut() {
+                            *negation_after_binary = true;
+                        }
+                    }
                     pt.push_operator(PatternOperator::Not);
+                    tokens.push(PatternToken::Operator(PatternOperator::Not));
                 }
                 '(' if !between_slashes && pt.accept_opening_par() => {
                     pt.open_par();
+                    tokens.push(PatternToken::OpeningPar);
+                    negations_after_binary.push(false);
                 }
                 ')' if !between_slashes && pt.accept_closing_par() => {
                     pt.close_par();
+                    tokens.push(PatternToken::ClosingPar);
+                    negations_after_binary.pop();
                 }
                 _ => {
-                    pt.mutate_or_create_atom(PatternParts::default).push(cur_char);
+                    atom(&mut pt, &mut tokens).push(cur_char);
                 }
             }
             escape_cur_char = escape_next_char;
             escape_next_char = false;
         }
+        if needs_rebuild {
+            pt = rebuild_pattern_tree(pt, &tokens);
+        }
         let mut verb_invocation = None;
         if let Some(pos) = invocation_start_pos {
             verb_invocation = Some(VerbInvocation::from(&raw[pos + 1..]));
@@ -138,6 +269,28 @@ impl CommandParts {
         )
     }
 
+    /// build the raw pattern of the input filtering with `raw_pattern`
+    /// but excluding the matches of `excluded`
+    pub fn add_exclusion(raw_pattern: &str, excluded: &str) -> String {
+        let excluded = excluded.replace(' ', "\\ ");
+        let negation = if CommandParts::from(excluded.as_str()).pattern.is_atomic() {
+            format!("!{excluded}")
+        } else {
+            format!("!({excluded})")
+        };
+        if raw_pattern.is_empty() {
+            return negation;
+        }
+        let mut raw = raw_pattern.to_string();
+        let pattern = CommandParts::from(raw_pattern).pattern;
+        if pattern.current_atom().map_or(false, |atom| atom.is_between_slashes()) {
+            raw.push('/');
+        }
+        for _ in 0..pattern.get_openess() {
+            raw.push(')');
+        }
+        format!("{raw}&{negation}")
+    }
 }
 
 #[cfg(test)]
@@ -334,5 +487,49 @@ mod test_command_parts {
             Some("cp .."),
         );
     }
+
+    /// evaluate the pattern of the input, the atoms being true
+    /// when their core is in `true_atoms`
+    fn eval(input: &str, true_atoms: &[&str]) -> bool {
+        CommandParts::from(input).pattern.eval(
+            |pp| true_atoms.contains(&pp.core()),
+            |op, a, b| match (op, b) {
+                (PatternOperator::And, Some(b)) => a && b,
+                (PatternOperator::Or, Some(b)) => a || b,
+                (PatternOperator::Not, None) => !a,
+                _ => false,
+            },
+            |_, _| false,
+        ).unwrap()
+    }
+    #[test]
+    fn exclusion() {
+        assert_eq!(CommandParts::add_exclusion("", "jpg"), "!jpg");
+        assert_eq!(CommandParts::add_exclusion("a|b", "c"), "a|b&!c");
+        assert_eq!(CommandParts::add_exclusion("/txt$", "c/x y"), r#"/txt$/&!c/x\ y"#);
+        assert_eq!(CommandParts::add_exclusion("(a|b", "c|d"), "(a|b)&!(c|d)");
+        assert!(eval("a|b&!c", &["a"]));
+        assert!(!eval("a|b&!c", &["b", "c"]));
+    }
+    #[test]
+    fn negation_precedence() {
+        // (a&!b)|c
+        assert!(eval("a&!b|c", &["c"]));
+        assert!(eval("a&!b|c", &["a"]));
+        assert!(!eval("a&!b|c", &["a", "b"]));
+        // (a|!b)&c
+        assert!(!eval("a|!b&c", &["a"]));
+        assert!(eval("a|!b&c", &["c"]));
+        // (a&!(b|c))|d
+        assert!(eval("a&!(b|c)|d", &["d"]));
+        assert!(!eval("a&!(b|c)|d", &["a", "c"]));
+        // a&((!b&c)|d)
+        assert!(eval("a&(!b&c|d)", &["a", "c"]));
+        assert!(eval("a&(!b&c|d)", &["a", "b", "d"]));
+        assert!(!eval("a&(b|!c&d)", &["a", "b", "c"]));
+        // ((!a)&b)|c
+        assert!(eval("!a&b|c", &["b"]));
+        assert!(!eval("!a&b|c", &["a", "b"]));
+    }
 }
 
@@ -28,3 +28,43 @@ pub fn move_sel(
         ns as usize
     }
 }
+
+/// compute the scroll of a list whose content changed, keeping the
+/// previous scroll when possible but with the selection visible and
+/// without empty rows at the end when the list shrank
+pub fn fix_scroll(
+    scroll: usize,
+    selection: usize,
+    len: usize,
+    page_height: usize,
+) -> usize {
+    if page_height == 0 {
+        return 0;
+    }
+    let mut scroll = scroll.min(len.saturating_sub(page_height));
+    if selection < scroll {
+        scroll = selection;
+    } else if selection >= scroll + page_height {
+        scroll = selection + 1 - page_height;
+    }
+    scroll
+}
+
+#[cfg(test)]
+mod sel_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_fix_scroll() {
+        // nothing changed
+        assert_eq!(fix_scroll(5, 8, 30, 10), 5);
+        // the list shrank
+        assert_eq!(fix_scroll(25, 12, 20, 10), 10);
+        // the selection moved out of the page
+        assert_eq!(fix_scroll(5, 2, 30, 10), 2);
+        assert_eq!(fix_scroll(5, 17, 30, 10), 8);
+        // not yet displayed
+        assert_eq!(fix_scroll(5, 8, 30, 0), 0);
+    }
+}
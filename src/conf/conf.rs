@@ -4,16 +4,23 @@
 use {
     super::*,
     crate::{
-        display::ColsConf,
+        display::{ColsConf, SizeUnits},
         errors::{ConfError, ProgramError},
+        lint::LintProbe,
+        opener_rules::OpenerRule,
+        quick_roots::QuickRoot,
+        versions::BackupLocation,
         path::{
             Glob,
             SpecialHandling,
             path_from,
             PathAnchor,
         },
+        preview::PreviewModeRule,
         skin::SkinEntry,
         syntactic::SyntaxTheme,
+        tree::{PermissionParts, TieBreaking},
+        verb::OverwritePolicy,
     },
     ahash::AHashMap,
     crokey::crossterm::style::Attribute,
@@ -51,6 +58,17 @@ pub struct Conf {
     #[serde(alias="date-time-format")]
     pub date_time_format: Option<String>,
 
+    #[serde(alias="size-units")]
+    pub size_units: Option<SizeUnits>,
+
+    #[serde(alias="exact-sizes")]
+    pub exact_sizes: Option<bool>,
+
+    #[serde(alias="date-time-zone")]
+    pub date_time_zone: Option<String>,
+
+    pub language: Option<String>,
+
     #[serde(default)]
     pub verbs: Vec<VerbConf>,
 
@@ -59,9 +77,30 @@ pub struct Conf {
     #[serde(default, alias="special-paths")]
     pub special_paths: AHashMap<Glob, SpecialHandling>,
 
+    #[serde(default, alias="preview-modes", deserialize_with="ordered_map::deserialize")]
+    pub preview_modes: Vec<PreviewModeRule>,
+
+    #[serde(default, alias="lint-probes")]
+    pub lint_probes: Vec<LintProbe>,
+
+    #[serde(default)]
+    pub openers: Vec<OpenerRule>,
+
+    #[serde(default, alias="backup-locations")]
+    pub backup_locations: Vec<BackupLocation>,
+
+    #[serde(default, alias="quick-roots")]
+    pub quick_roots: Vec<QuickRoot>,
+
     #[serde(alias="search-modes")]
     pub search_modes: Option<FnvHashMap<String, String>>,
 
+    #[serde(alias="panel-search-modes")]
+    pub panel_search_modes: Option<FnvHashMap<String, String>>,
+
+    #[serde(alias="click-bindings")]
+    pub click_bindings: Option<FnvHashMap<String, String>>,
+
     /// Obsolete, kept for compatibility: you should now use capture_mouse
     #[serde(alias="disable-mouse-capture")]
     pub disable_mouse_capture: Option<bool>,
@@ -72,18 +111,41 @@ pub struct Conf {
     #[serde(alias="cols-order")]
     pub cols_order: Option<ColsConf>,
 
+    #[serde(alias="permission-parts")]
+    pub permission_parts: Option<PermissionParts>,
+
     #[serde(alias="show-selection-mark")]
     pub show_selection_mark: Option<bool>,
 
+    #[serde(alias="show-row-numbers")]
+    pub show_row_numbers: Option<bool>,
+
+    #[serde(alias="show-match-lines")]
+    pub show_match_lines: Option<bool>,
+
     #[serde(default, alias="ext-colors")]
     pub ext_colors: AHashMap<String, String>,
 
+    #[serde(default)]
+    pub glyphs: AHashMap<String, String>,
+
     #[serde(alias="syntax-theme")]
     pub syntax_theme: Option<SyntaxTheme>,
 
+    /// a directory containing .tmTheme files, read when set in conf
+    /// as a path relative to the conf file
+    #[serde(alias="syntax-themes-dir")]
+    pub syntax_themes_dir: Option<String>,
+
     #[serde(alias="true-colors")]
     pub true_colors: Option<bool>,
 
+    #[serde(alias="kitty-graphics")]
+    pub kitty_graphics: Option<bool>,
+
+    #[serde(alias="wide-chars")]
+    pub wide_chars: Option<bool>,
+
     #[serde(alias="icon-theme")]
     pub icon_theme: Option<String>,
 
@@ -94,8 +156,29 @@ pub struct Conf {
     #[serde(alias="quit-on-last-cancel")]
     pub quit_on_last_cancel: Option<bool>,
 
+    #[serde(alias="confirm-quit")]
+    pub confirm_quit: Option<bool>,
+
+    #[serde(alias="save-session")]
+    pub save_session: Option<bool>,
+
+    #[serde(alias="size-deltas")]
+    pub size_deltas: Option<bool>,
+
     pub file_sum_threads_count: Option<usize>,
 
+    #[serde(alias="auto-refresh-period")]
+    pub auto_refresh_period: Option<f64>,
+
+    #[serde(alias="live-refresh")]
+    pub live_refresh: Option<bool>,
+
+    #[serde(alias="live-refresh-throttle")]
+    pub live_refresh_throttle: Option<f64>,
+
+    #[serde(alias="file-index")]
+    pub file_index: Option<bool>,
+
     #[serde(alias="max_staged_count")]
     pub max_staged_count: Option<usize>,
 
@@ -105,8 +188,62 @@ pub struct Conf {
     #[serde(alias="show-matching-characters-on-path-searches")]
     pub show_matching_characters_on_path_searches: Option<bool>,
 
+    #[serde(alias="tie-breaking")]
+    pub tie_breaking: Option<TieBreaking>,
+
     #[serde(alias="content-search-max-file-size", deserialize_with="file_size::deserialize", default)]
     pub content_search_max_file_size: Option<u64>,
+
+    #[serde(default, alias="content-search-excluded")]
+    pub content_search_excluded: Vec<Glob>,
+
+    #[serde(alias="content-search-binary-threshold")]
+    pub content_search_binary_threshold: Option<f32>,
+
+    #[serde(alias="overwrite-policy")]
+    pub overwrite_policy: Option<OverwritePolicy>,
+
+    #[serde(alias="rm-to-trash")]
+    pub rm_to_trash: Option<bool>,
+
+    #[serde(alias="escalation-command")]
+    pub escalation_command: Option<String>,
+
+    #[serde(default, alias="read-only-allowed-verbs")]
+    pub read_only_allowed_verbs: Vec<String>,
+
+    #[serde(alias="tree-title")]
+    pub tree_title: Option<String>,
+
+    #[serde(alias="preview-title")]
+    pub preview_title: Option<String>,
+
+    #[serde(alias="dir-info-on-hover")]
+    pub dir_info_on_hover: Option<bool>,
+
+    #[serde(alias="filesystems-only-disks")]
+    pub filesystems_only_disks: Option<bool>,
+
+    #[serde(alias="filesystems-refresh-period")]
+    pub filesystems_refresh_period: Option<f64>,
+
+    #[serde(alias="filesystems-totals")]
+    pub filesystems_totals: Option<bool>,
+
+    #[serde(alias="filesystems-usage-alert")]
+    pub filesystems_usage_alert: Option<f64>,
+
+    #[serde(alias="fs-columns")]
+    pub fs_columns: Option<Vec<String>>,
+
+    #[serde(alias="show-tips")]
+    pub show_tips: Option<bool>,
+
+    #[serde(alias="single-instance")]
+    pub single_instance: Option<bool>,
+
+    #[serde(alias="tree-memory-budget", deserialize_with="file_size::deserialize", default)]
+    pub tree_memory_budget: Option<u64>,
 }
 
 impl Conf {
@@ -170,27 +307,73 @@ impl Conf {
         let mut conf: Conf = SerdeFormat::read_file(&path)?;
         overwrite!(self, default_flags, conf);
         overwrite!(self, date_time_format, conf);
+        overwrite!(self, size_units, conf);
+        overwrite!(self, exact_sizes, conf);
+        overwrite!(self, date_time_zone, conf);
+        overwrite!(self, language, conf);
         overwrite!(self, icon_theme, conf);
         overwrite!(self, syntax_theme, conf);
+        if let Some(dir) = conf.syntax_themes_dir.take() {
+            let dir = path_from(&path, PathAnchor::Parent, &dir);
+            self.syntax_themes_dir = Some(dir.to_string_lossy().to_string());
+        }
         overwrite!(self, disable_mouse_capture, conf);
         overwrite!(self, capture_mouse, conf);
         overwrite!(self, true_colors, conf);
+        overwrite!(self, kitty_graphics, conf);
+        overwrite!(self, wide_chars, conf);
         overwrite!(self, show_selection_mark, conf);
+        overwrite!(self, show_row_numbers, conf);
+        overwrite!(self, show_match_lines, conf);
         overwrite!(self, cols_order, conf);
+        overwrite!(self, permission_parts, conf);
         overwrite!(self, skin, conf);
         overwrite!(self, search_modes, conf);
+        overwrite!(self, panel_search_modes, conf);
+        overwrite!(self, click_bindings, conf);
         overwrite!(self, max_panels_count, conf);
         overwrite!(self, modal, conf);
         overwrite!(self, quit_on_last_cancel, conf);
+        overwrite!(self, confirm_quit, conf);
+        overwrite!(self, save_session, conf);
+        overwrite!(self, size_deltas, conf);
         overwrite!(self, file_sum_threads_count, conf);
+        overwrite!(self, auto_refresh_period, conf);
+        overwrite!(self, live_refresh, conf);
+        overwrite!(self, live_refresh_throttle, conf);
+        overwrite!(self, file_index, conf);
         overwrite!(self, max_staged_count, conf);
         overwrite!(self, show_matching_characters_on_path_searches, conf);
+        overwrite!(self, tie_breaking, conf);
         overwrite!(self, content_search_max_file_size, conf);
+        self.content_search_excluded.append(&mut conf.content_search_excluded);
+        overwrite!(self, content_search_binary_threshold, conf);
+        overwrite!(self, overwrite_policy, conf);
+        overwrite!(self, rm_to_trash, conf);
+        overwrite!(self, escalation_command, conf);
+        overwrite!(self, tree_title, conf);
+        overwrite!(self, preview_title, conf);
+        overwrite!(self, filesystems_only_disks, conf);
+        overwrite!(self, filesystems_refresh_period, conf);
+        overwrite!(self, filesystems_totals, conf);
+        overwrite!(self, filesystems_usage_alert, conf);
+        overwrite!(self, dir_info_on_hover, conf);
+        overwrite!(self, fs_columns, conf);
+        overwrite!(self, show_tips, conf);
+        overwrite!(self, single_instance, conf);
+        overwrite!(self, tree_memory_budget, conf);
+        self.read_only_allowed_verbs.append(&mut conf.read_only_allowed_verbs);
         self.verbs.append(&mut conf.verbs);
+        self.lint_probes.append(&mut conf.lint_probes);
+        self.openers.append(&mut conf.openers);
+        self.backup_locations.append(&mut conf.backup_locations);
+        self.quick_roots.append(&mut conf.quick_roots);
         // the following maps are "additive": we can add entries from several
         // config files and they still make sense
         overwrite_map!(self, special_paths, conf);
+        ordered_map::merge(&mut self.preview_modes, conf.preview_modes);
         overwrite_map!(self, ext_colors, conf);
+        overwrite_map!(self, glyphs, conf);
         self.files.push(path);
         // read the imports
         for import in &conf.imports {
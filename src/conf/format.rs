@@ -13,8 +13,9 @@ use {
 
 
 /// Formats usable for reading configuration files
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
 pub enum SerdeFormat {
+    #[default]
     Hjson,
     Toml,
 }
@@ -68,9 +69,3 @@ impl SerdeFormat {
         }
     }
 }
-
-impl Default for SerdeFormat {
-    fn default() -> Self {
-        SerdeFormat::Hjson
-    }
-}
@@ -8,6 +8,7 @@ mod default;
 mod format;
 pub mod file_size;
 mod import;
+pub mod ordered_map;
 mod verb_conf;
 
 pub use {
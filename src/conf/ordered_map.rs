@@ -0,0 +1,85 @@
+//! deserialization of a map of the configuration into the vector
+//! of its entries, so that their order is kept
+
+use {
+    serde::de::{
+        Deserialize,
+        Deserializer,
+        MapAccess,
+        Visitor,
+    },
+    std::{
+        fmt,
+        marker::PhantomData,
+    },
+};
+
+struct OrderedMapVisitor<K, V> {
+    marker: PhantomData<(K, V)>,
+}
+
+impl<'de, K, V> Visitor<'de> for OrderedMapVisitor<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = Vec<(K, V)>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::with_capacity(access.size_hint().unwrap_or(0));
+        while let Some(entry) = access.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+pub fn deserialize<'de, D, K, V>(deserializer: D) -> Result<Vec<(K, V)>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    deserializer.deserialize_map(OrderedMapVisitor { marker: PhantomData })
+}
+
+/// insert the entries of `src` into `dst`, replacing the value of the
+/// entries with the same key and appending the other ones
+pub fn merge<K: PartialEq, V>(dst: &mut Vec<(K, V)>, src: Vec<(K, V)>) {
+    for (k, v) in src {
+        match dst.iter_mut().find(|(dk, _)| *dk == k) {
+            Some(entry) => entry.1 = v,
+            None => dst.push((k, v)),
+        }
+    }
+}
+
+#[test]
+fn test_ordered_map() {
+    #[derive(serde::Deserialize)]
+    struct Conf {
+        #[serde(deserialize_with = "deserialize")]
+        modes: Vec<(String, String)>,
+    }
+    let conf: Conf = deser_hjson::from_str(r#"{
+        modes: {
+            "*.z": hex
+            "*.a": text
+            "*.m": image
+        }
+    }"#).unwrap();
+    let mut modes = conf.modes;
+    let keys: Vec<&str> = modes.iter().map(|(k, _)| k.as_str()).collect();
+    assert_eq!(keys, vec!["*.z", "*.a", "*.m"]);
+    merge(&mut modes, vec![
+        ("*.b".to_string(), "hex".to_string()),
+        ("*.a".to_string(), "hex".to_string()),
+    ]);
+    let entries: Vec<(&str, &str)> = modes.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    assert_eq!(entries, vec![("*.z", "hex"), ("*.a", "hex"), ("*.m", "image"), ("*.b", "hex")]);
+}
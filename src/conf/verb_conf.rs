@@ -9,7 +9,9 @@ use {
         keys,
         verb::*,
     },
+    fnv::FnvHashMap,
     serde::Deserialize,
+    std::time::Duration,
 };
 
 /// A deserializable verb entry in the configuration
@@ -38,6 +40,9 @@ pub struct VerbConf {
 
     shortcut: Option<String>,
 
+    #[serde(default)]
+    aliases: Vec<String>,
+
     leave_broot: Option<bool>,
 
     from_shell: Option<bool>,
@@ -48,12 +53,29 @@ pub struct VerbConf {
 
     working_dir: Option<String>,
 
+    #[serde(default)]
+    env: FnvHashMap<String, String>,
+
+    nice: Option<i32>,
+
+    ionice: Option<u8>,
+
+    /// in seconds
+    timeout: Option<f64>,
+
     description: Option<String>,
 
     auto_exec: Option<bool>,
 
+    mutating: Option<bool>,
+
     #[serde(default)]
     panels: Vec<PanelStateType>,
+
+    target: Option<VerbTarget>,
+
+    #[serde(default)]
+    contexts: Vec<PanelStateType>,
 }
 
 /// read a deserialized verb conf item into a verb,
@@ -74,6 +96,37 @@ impl VerbConf {
         let cmd = vc.cmd.as_ref().filter(|i| !i.is_empty());
         let cmd_separator = vc.cmd_separator.as_ref().filter(|i| !i.is_empty());
         let execution = vc.execution.as_ref().filter(|i| !i.is_empty());
+        if let Some(nice) = vc.nice {
+            if !(-20..=19).contains(&nice) {
+                return Err(ConfError::InvalidVerbConf {
+                    details: format!("nice must be between -20 and 19, not {}", nice),
+                });
+            }
+        }
+        if let Some(ionice) = vc.ionice {
+            if ionice > 7 {
+                return Err(ConfError::InvalidVerbConf {
+                    details: format!("ionice must be between 0 and 7, not {}", ionice),
+                });
+            }
+        }
+        let timeout = match vc.timeout {
+            Some(secs) if secs > 0.0 && secs.is_finite() => Some(Duration::from_secs_f64(secs)),
+            Some(secs) => {
+                return Err(ConfError::InvalidVerbConf {
+                    details: format!("timeout must be a positive number of seconds, not {}", secs),
+                });
+            }
+            None => None,
+        };
+        let mut env: Vec<(String, String)> = vc.env.clone().into_iter().collect();
+        env.sort();
+        let sandbox = Sandbox {
+            env,
+            nice: vc.nice,
+            ionice: vc.ionice,
+            timeout,
+        };
         let make_external_execution = |s| {
             let working_dir = match (vc.set_working_dir, &vc.working_dir) {
                 (Some(false), _) => None,
@@ -86,6 +139,7 @@ impl VerbConf {
                 ExternalExecutionMode::from_conf(vc.from_shell, vc.leave_broot),
             )
             .with_working_dir(working_dir)
+            .with_sandbox(sandbox.clone())
         };
         let execution = match (execution, internal, external, cmd) {
             // old definition with "execution": we guess whether it's an internal or
@@ -157,12 +211,31 @@ impl VerbConf {
         if let Some(shortcut) = &vc.shortcut {
             verb.names.push(shortcut.clone());
         }
+        for alias in &vc.aliases {
+            if !verb.names.contains(alias) {
+                verb.names.push(alias.clone());
+            }
+        }
         if vc.auto_exec == Some(false) {
             verb.auto_exec = false;
         }
+        if let Some(mutating) = vc.mutating {
+            verb.mutating = mutating;
+        }
         if !vc.panels.is_empty() {
             verb.panels = vc.panels.clone();
         }
+        if !vc.contexts.is_empty() {
+            verb.set_key_contexts(vc.contexts.clone());
+        }
+        if vc.target.is_some() {
+            if !matches!(verb.execution, VerbExecution::Internal(_)) {
+                return Err(ConfError::InvalidVerbConf {
+                    details: "a target can only be given to verbs based on internals".to_string(),
+                });
+            }
+            verb.target = vc.target;
+        }
         verb.selection_condition = match vc.apply_to.as_deref() {
             Some("file") => SelectionType::File,
             Some("directory") => SelectionType::Directory,
@@ -0,0 +1,151 @@
+use {
+    crate::{
+        errors::ProgramError,
+    },
+    std::{
+        path::PathBuf,
+        process::Command,
+    },
+};
+
+/// a container engine whose CLI we know how to query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerEngine {
+    Docker,
+    Podman,
+}
+
+pub static ENGINES: &[ContainerEngine] = &[ContainerEngine::Docker, ContainerEngine::Podman];
+
+impl ContainerEngine {
+    pub fn program(self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+    /// list the containers (running or not) known by this engine.
+    ///
+    /// Return an error when the engine isn't installed or doesn't answer
+    pub fn list(self) -> Result<Vec<Container>, ProgramError> {
+        let stdout = self.run(&[
+            "ps",
+            "--all",
+            "--no-trunc",
+            "--format",
+            "{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Status}}",
+        ])?;
+        Ok(stdout
+            .lines()
+            .filter_map(|line| {
+                let mut tokens = line.split('\t');
+                let id = tokens.next()?.to_string();
+                let name = tokens.next()?.to_string();
+                let image = tokens.next()?.to_string();
+                let status = tokens.next().unwrap_or("").to_string();
+                Some(Container { engine: self, id, name, image, status })
+            })
+            .collect())
+    }
+    fn run(self, args: &[&str]) -> Result<String, ProgramError> {
+        let program = self.program();
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .map_err(|source| ProgramError::LaunchError {
+                program: program.to_string(),
+                source,
+            })?;
+        if !output.status.success() {
+            return Err(ProgramError::Container {
+                details: format!(
+                    "{} {} failed: {}",
+                    program,
+                    args[0],
+                    String::from_utf8_lossy(&output.stderr).trim(),
+                ),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+/// a container, as listed by its engine
+#[derive(Debug, Clone)]
+pub struct Container {
+    pub engine: ContainerEngine,
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub status: String,
+}
+
+impl Container {
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(12)]
+    }
+    /// find the path on the host where the root filesystem of the
+    /// container can be read.
+    ///
+    /// For docker, it's the merged dir of the overlay driver (which
+    /// exists only while the container is running). For podman we
+    /// ask for the container to be mounted.
+    pub fn root_fs(&self) -> Result<PathBuf, ProgramError> {
+        let stdout = match self.engine {
+            ContainerEngine::Docker => self.engine.run(&[
+                "inspect",
+                "--format",
+                "{{.GraphDriver.Data.MergedDir}}",
+                &self.id,
+            ])?,
+            ContainerEngine::Podman => self.engine.run(&["mount", &self.id])?,
+        };
+        let path = stdout.trim();
+        if path.is_empty() || path == "<no value>" {
+            return Err(ProgramError::Container {
+                details: format!("no root filesystem found for container {:?}", self.name),
+            });
+        }
+        let path = PathBuf::from(path);
+        if !path.exists() {
+            return Err(ProgramError::Container {
+                details: format!("{:?} isn't reachable (not running or not enough rights?)", path),
+            });
+        }
+        Ok(path)
+    }
+    /// tell whether `root_fs` mounts the filesystem, which must
+    /// then be unmounted with `unmount`
+    pub fn needs_mount(&self) -> bool {
+        self.engine == ContainerEngine::Podman
+    }
+    /// release the mount made by `root_fs`, if any
+    pub fn unmount(&self) -> Result<(), ProgramError> {
+        if self.needs_mount() {
+            self.engine.run(&["unmount", &self.id])?;
+        }
+        Ok(())
+    }
+}
+
+/// list the containers of all available engines
+pub fn list_containers() -> Result<Vec<Container>, ProgramError> {
+    let mut containers = Vec::new();
+    let mut last_error = None;
+    for engine in ENGINES {
+        match engine.list() {
+            Ok(list) => {
+                debug!("{} containers found with {}", list.len(), engine.program());
+                containers.extend(list);
+            }
+            Err(e) => {
+                info!("can't list {} containers: {}", engine.program(), e);
+                last_error = Some(e);
+            }
+        }
+    }
+    match (containers.is_empty(), last_error) {
+        (true, Some(e)) => Err(e),
+        _ => Ok(containers),
+    }
+}
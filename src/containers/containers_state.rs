@@ -0,0 +1,361 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        browser::BrowserState,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        task_sync::{ComputationResult, Dam},
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    crossbeam::channel::{bounded, Receiver},
+    std::{
+        path::{Path, PathBuf},
+        sync::{Arc, Weak},
+        thread,
+    },
+    termimad::minimad::Alignment,
+};
+
+/// the search, in a background thread, of the root filesystem of
+/// a container, which may imply mounting it
+struct RootFsSearch {
+    container: Container,
+    receiver: Receiver<ComputationResult<Result<PathBuf, ProgramError>>>,
+    /// the command to execute again once the root is found
+    cmd: Command,
+}
+
+/// a root filesystem ready to be browsed
+struct RootFs {
+    container_id: String,
+    path: PathBuf,
+    guard: Option<Arc<RootGuard>>,
+}
+
+/// an application state listing the local docker and podman
+/// containers, from which the root filesystem of a container
+/// can be browsed
+pub struct ContainersState {
+    list: FilteredList<Container>,
+    tree_options: TreeOptions,
+    mode: Mode,
+    root_fs_search: Option<RootFsSearch>,
+    /// the root filesystem found for the command to execute again
+    found_root_fs: Option<RootFs>,
+    pending_command: Option<Command>,
+    /// the mounts of the container filesystems, which are released
+    /// when the last state browsing them is dropped
+    mounts: Vec<(String, Weak<RootGuard>)>,
+}
+
+impl ContainersState {
+    /// create a state listing the containers of all engines
+    /// available on the system.
+    /// Not finding any engine is considered an error and prevents
+    /// the opening of this state.
+    pub fn new(
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> Result<ContainersState, ProgramError> {
+        let containers = list_containers()?;
+        Ok(ContainersState {
+            list: FilteredList::new(containers, matches),
+            tree_options,
+            mode: initial_mode(con),
+            root_fs_search: None,
+            found_root_fs: None,
+            pending_command: None,
+            mounts: Vec::new(),
+        })
+    }
+    /// the root filesystem of the container, if it's known without
+    /// running a command: just found or still mounted
+    fn known_root_fs(&mut self, container: &Container) -> Option<RootFs> {
+        if let Some(root_fs) = self.found_root_fs.take() {
+            if root_fs.container_id == container.id {
+                return Some(root_fs);
+            }
+        }
+        self.mounts.retain(|(_, guard)| guard.strong_count() > 0);
+        self.mounts.iter()
+            .filter(|(id, _)| *id == container.id)
+            .find_map(|(_, guard)| guard.upgrade())
+            .map(|guard| RootFs {
+                container_id: container.id.clone(),
+                path: guard.root().to_path_buf(),
+                guard: Some(guard),
+            })
+    }
+    /// start searching the root filesystem of the container, which
+    /// involves calling its engine, in a background thread
+    fn search_root_fs(&mut self, container: Container, cmd: Command) {
+        let (sender, receiver) = bounded(1);
+        let searched = container.clone();
+        thread::spawn(move || {
+            let _ = sender.send(ComputationResult::Done(searched.root_fs()));
+        });
+        self.root_fs_search = Some(RootFsSearch { container, receiver, cmd });
+    }
+}
+
+fn matches(container: &Container, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&container.name).is_some()
+        || pattern.score_of_string(&container.image).is_some()
+        || pattern.score_of_string(container.short_id()).is_some()
+        || pattern.score_of_string(container.engine.program()).is_some()
+}
+
+/// build the guard unmounting the filesystem of the container, if
+/// it had to be mounted
+fn mount_guard(container: &Container, root: &Path) -> Option<Arc<RootGuard>> {
+    if !container.needs_mount() {
+        return None;
+    }
+    let container = container.clone();
+    Some(RootGuard::new(root.to_path_buf(), move || {
+        if let Err(e) = container.unmount() {
+            warn!("failed to unmount container {:?}: {}", container.name, e);
+        }
+    }))
+}
+
+impl Drop for ContainersState {
+    fn drop(&mut self) {
+        // a search still running may end with a mount nobody will use
+        if let Some(search) = self.root_fs_search.take() {
+            thread::spawn(move || {
+                if let Ok(ComputationResult::Done(Ok(root))) = search.receiver.recv() {
+                    drop(mount_guard(&search.container, &root));
+                }
+            });
+        }
+    }
+}
+
+impl PanelState for ContainersState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Containers
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// containers aren't paths (their root filesystem is only
+    /// resolved when opened)
+    fn selected_path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        None
+    }
+
+    fn get_pending_task(&self) -> Option<&'static str> {
+        self.root_fs_search.as_ref().map(|_| "searching the container filesystem")
+    }
+
+    fn do_pending_task(
+        &mut self,
+        _app_state: &mut AppState,
+        _screen: Screen,
+        _con: &AppContext,
+        dam: &mut Dam,
+    ) -> Result<(), ProgramError> {
+        let search = match self.root_fs_search.take() {
+            Some(search) => search,
+            None => {
+                return Ok(());
+            }
+        };
+        match dam.select(search.receiver.clone()) {
+            ComputationResult::Done(Ok(path)) => {
+                let guard = mount_guard(&search.container, &path);
+                if let Some(guard) = &guard {
+                    self.mounts.push((search.container.id.clone(), Arc::downgrade(guard)));
+                }
+                self.found_root_fs = Some(RootFs {
+                    container_id: search.container.id,
+                    path,
+                    guard,
+                });
+                self.pending_command = Some(search.cmd);
+            }
+            ComputationResult::Done(Err(e)) => {
+                return Err(e);
+            }
+            _ => {
+                // interrupted by an event, we'll wait again later
+                self.root_fs_search = Some(search);
+            }
+        }
+        Ok(())
+    }
+
+    fn take_pending_command(&mut self) -> Option<Command> {
+        self.pending_command.take()
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        match list_containers() {
+            Ok(containers) => {
+                self.list.set_items(containers, |a, b| a.id == b.id);
+            }
+            Err(e) => {
+                warn!("failed to refresh containers: {}", e);
+            }
+        }
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let w_engine = "engine".len();
+        let w_id = 12;
+        let w_name = list.visible()
+            .map(|c| c.name.chars().count())
+            .max().unwrap_or(0)
+            .max("name".len());
+        let w_image = list.visible()
+            .map(|c| c.image.chars().count())
+            .max().unwrap_or(0)
+            .max("image".len());
+        let columns = [
+            ListColumn::new("engine", w_engine, Alignment::Left),
+            ListColumn::new("id", w_id, Alignment::Center),
+            ListColumn::new("name", w_name, Alignment::Center),
+            ListColumn::new("image", w_image, Alignment::Center),
+        ];
+        let styles = &disc.panel_skin.styles;
+        list.queue_table_head(w, disc, &columns, |cw| {
+            cw.queue_str(&styles.default, "status")?;
+            Ok(())
+        })?;
+        self.list.queue_table_rows(w, disc, |cw, container, row| {
+            let cells = [
+                (container.engine.program(), w_engine),
+                (container.short_id(), w_id),
+                (container.name.as_str(), w_name),
+                (container.image.as_str(), w_image),
+            ];
+            for (s, width) in cells {
+                row.queue_matched_cell(cw, s, row.txt_style, width, Alignment::Left)?;
+            }
+            cw.queue_str(row.txt_style, &container.status)?;
+            Ok(())
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        use Internal::*;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                let in_new_panel = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                let container = match self.list.selected() {
+                    Some(container) => container.clone(),
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_no_selected_container)));
+                    }
+                };
+                let root_fs = match self.known_root_fs(&container) {
+                    Some(root_fs) => root_fs,
+                    None => {
+                        self.search_root_fs(container, cc.cmd.clone());
+                        return Ok(CmdResult::Keep);
+                    }
+                };
+                if !app_state.is_read_only_path(&root_fs.path) {
+                    app_state.read_only_roots.push(root_fs.path.clone());
+                }
+                let state = BrowserState::new(
+                    root_fs.path,
+                    self.tree_options(),
+                    screen,
+                    con,
+                    &Dam::unlimited(),
+                )
+                .map(|mut state| {
+                    if let Some(guard) = root_fs.guard {
+                        state.set_root_guard(guard);
+                    }
+                    state
+                });
+                CmdResult::from_optional_state(state, None, in_new_panel)
+            }
+            open_leave => CmdResult::PopStateAndReapply,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
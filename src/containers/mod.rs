@@ -0,0 +1,12 @@
+//! Listing of the local docker and podman containers, whose
+//! root filesystems can be browsed.
+//!
+//! The whole module is only available on unix
+
+mod container;
+mod containers_state;
+
+pub use {
+    container::*,
+    containers_state::ContainersState,
+};
@@ -12,8 +12,11 @@ pub enum ContentSearchResult {
     /// the needle hasn't been found
     NotFound, // no match
 
-    /// the file wasn't searched because it's binary or too big
+    /// the file wasn't searched because it's binary
     NotSuitable,
+
+    /// the file wasn't searched because of the content search limits
+    Skipped,
 }
 
 impl ContentSearchResult {
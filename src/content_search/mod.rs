@@ -4,19 +4,22 @@ mod content_search_result;
 mod magic_numbers;
 mod extensions;
 mod needle;
+mod search_limits;
 
 pub use {
     content_match::ContentMatch,
     content_search_result::ContentSearchResult,
     needle::Needle,
+    search_limits::ContentSearchLimits,
     std::io::{ BufRead, BufReader},
 };
 
 use {
+    crate::syntactic::Encoding,
     memmap2::Mmap,
     std::{
         fs::File,
-        io,
+        io::{self, Read},
         path::Path,
     },
 };
@@ -29,31 +32,86 @@ pub fn get_mmap<P: AsRef<Path>>(hay_path: P) -> io::Result<Mmap> {
     Ok(hay)
 }
 
-/// return the memmap to the file except if it was determined
-/// that the file is binary (from its extension, size, or first bytes)
-/// or is too big
-pub fn get_mmap_if_suitable<P: AsRef<Path>>(hay_path: P, max_size: usize) -> io::Result<Option<Mmap>> {
+/// what's known of a file before searching its content
+pub enum Suitability {
+    /// the file may be searched, with the given encoding
+    Suitable(Mmap, Encoding),
+    /// the file is binary, as determined from its extension or first bytes
+    Binary,
+    /// the file is excluded by the content search limits
+    Skipped,
+}
+
+/// return the memmap to the file, with the encoding guessed from
+/// its first bytes, except if it was determined that the file is
+/// binary (from its extension or first bytes) or is excluded by the
+/// content search limits (eg because it's too big).
+///
+/// UTF-16 files, whose first bytes would look binary, are kept.
+pub fn get_mmap_if_suitable<P: AsRef<Path>>(
+    hay_path: P,
+    limits: &ContentSearchLimits,
+) -> io::Result<Suitability> {
     if let Some(ext) = hay_path.as_ref().extension().and_then(|s| s.to_str()) {
         if extensions::is_known_binary(ext) {
-            return Ok(None);
+            return Ok(Suitability::Binary);
         }
     }
+    if limits.is_excluded(hay_path.as_ref()) {
+        return Ok(Suitability::Skipped);
+    }
     let hay = get_mmap(&hay_path)?;
-    if hay.len() > max_size || magic_numbers::is_known_binary(&hay) {
-        return Ok(None);
+    if hay.len() > limits.max_file_size {
+        return Ok(Suitability::Skipped);
+    }
+    let encoding = Encoding::detect_in(&hay);
+    if !encoding.is_utf16() {
+        if magic_numbers::is_known_binary(&hay) {
+            return Ok(Suitability::Binary);
+        }
+        if limits.looks_binary(&hay) {
+            return Ok(Suitability::Skipped);
+        }
     }
-    Ok(Some(hay))
+    Ok(Suitability::Suitable(hay, encoding))
 }
 
-/// return true when the file looks suitable for searching as text.
-///
-/// This function is quite slow as it creates a memmap just to check
-/// a few bytes. If the memmap can be used, prefer `get_mmap_if_not_binary`
-pub fn is_path_suitable<P: AsRef<Path>>(path: P, max_size: usize) -> bool {
-    matches!(get_mmap_if_suitable(path, max_size), Ok(Some(_)))
+/// return the lines of a text file, decoding it when it's in UTF-16
+pub fn text_lines<P: AsRef<Path>>(
+    path: P,
+) -> io::Result<Box<dyn Iterator<Item = io::Result<String>>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let encoding = Encoding::detect_in(reader.fill_buf()?);
+    if !encoding.is_utf16() {
+        return Ok(Box::new(reader.lines()));
+    }
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let lines: Vec<io::Result<String>> = encoding.decode(&bytes)
+        .lines()
+        .map(|line| Ok(line.to_string()))
+        .collect();
+    Ok(Box::new(lines.into_iter()))
+}
+
+/// count the lines of a UTF-16 text up to the given position in bytes
+fn utf16_line_count_at_pos(hay: &[u8], pos: usize, encoding: Encoding) -> usize {
+    let line_feed = match encoding {
+        Encoding::Utf16Be => [0, b'\n'],
+        _ => [b'\n', 0],
+    };
+    1 + hay[..pos.min(hay.len())]
+        .chunks_exact(2)
+        .filter(|unit| *unit == line_feed)
+        .count()
 }
 
 pub fn line_count_at_pos<P: AsRef<Path>>(path: P, pos: usize) -> io::Result<usize> {
+    let hay = get_mmap(&path)?;
+    let encoding = Encoding::detect_in(&hay);
+    if encoding.is_utf16() {
+        return Ok(utf16_line_count_at_pos(&hay, pos, encoding));
+    }
     let mut reader = BufReader::new(File::open(path)?);
     let mut line = String::new();
     let mut line_count = 1;
@@ -4,6 +4,7 @@
 
 use {
     super::*,
+    crate::syntactic::Encoding,
     memmap2::Mmap,
     std::{
         convert::TryInto,
@@ -22,7 +23,13 @@ pub struct Needle {
     /// (guaranteed to be valid UTF8 by construct)
     bytes: Box<[u8]>,
 
-    max_file_size: usize,
+    /// bytes of the searched string in UTF-16LE
+    utf16le_bytes: Box<[u8]>,
+
+    /// bytes of the searched string in UTF-16BE
+    utf16be_bytes: Box<[u8]>,
+
+    limits: ContentSearchLimits,
 }
 
 impl fmt::Debug for Needle {
@@ -36,9 +43,15 @@ impl fmt::Debug for Needle {
 
 impl Needle {
 
-    pub fn new(pat: &str, max_file_size: usize) -> Self {
+    pub fn new(pat: &str, limits: &ContentSearchLimits) -> Self {
         let bytes = pat.as_bytes().to_vec().into_boxed_slice();
-        Self { bytes, max_file_size }
+        let utf16le_bytes = Encoding::Utf16Le.encode_utf16(pat)
+            .unwrap_or_default()
+            .into_boxed_slice();
+        let utf16be_bytes = Encoding::Utf16Be.encode_utf16(pat)
+            .unwrap_or_default()
+            .into_boxed_slice();
+        Self { bytes, utf16le_bytes, utf16be_bytes, limits: limits.clone() }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -151,21 +164,41 @@ impl Needle {
         None
     }
 
+    /// find the first occurrence of the needle in an UTF-16 text,
+    /// only at positions of code unit boundaries
+    fn find_utf16(&self, hay: &[u8], encoding: Encoding) -> Option<usize> {
+        let bytes = match encoding {
+            Encoding::Utf16Be => &self.utf16be_bytes,
+            _ => &self.utf16le_bytes,
+        };
+        if hay.len() < bytes.len() {
+            return None;
+        }
+        hay.windows(bytes.len())
+            .step_by(2)
+            .position(|window| window == &**bytes)
+            .map(|idx| idx * 2)
+    }
+
     /// search the mem map to find the first occurrence of the needle.
     ///
-    /// Known limit: if the file has an encoding where the needle would
-    /// be represented in a way different than UTF-8, the needle won't
-    /// be found (I noticed the problem with other grepping tools, too,
-    /// which is understandable as detecting the encoding and translating
-    /// the needle would multiply the search time).
-    ///
+    /// UTF-16 files are searched with the needle translated in their
+    /// encoding. Other encodings where the needle would be represented
+    /// in a way different than UTF-8 (latin-1 for non ASCII needles)
+    /// aren't handled.
     ///
     /// The exact search algorithm used here (I removed Boyer-Moore)
     /// and the optimizations (loop unrolling, etc.) don't really matter
     /// as their impact is dwarfed by the whole mem map related set
     /// of problems. An alternate implementation should probably focus
     /// on avoiding mem maps.
-    fn search_mmap(&self, hay: &Mmap) -> ContentSearchResult {
+    fn search_mmap(&self, hay: &Mmap, encoding: Encoding) -> ContentSearchResult {
+        if encoding.is_utf16() {
+            return self.find_utf16(hay, encoding).map_or(
+                ContentSearchResult::NotFound,
+                |pos| ContentSearchResult::Found { pos },
+            );
+        }
         if hay.len() < self.bytes.len() {
             return ContentSearchResult::NotFound;
         }
@@ -199,11 +232,12 @@ impl Needle {
 
     /// determine whether the file contains the needle
     pub fn search<P: AsRef<Path>>(&self, hay_path: P) -> io::Result<ContentSearchResult> {
-        super::get_mmap_if_suitable(hay_path, self.max_file_size)
-            .map(|om| om.map_or(
-                ContentSearchResult::NotSuitable,
-                |hay| self.search_mmap(&hay),
-            ))
+        super::get_mmap_if_suitable(hay_path, &self.limits)
+            .map(|suitability| match suitability {
+                Suitability::Suitable(hay, encoding) => self.search_mmap(&hay, encoding),
+                Suitability::Binary => ContentSearchResult::NotSuitable,
+                Suitability::Skipped => ContentSearchResult::Skipped,
+            })
     }
 
     /// this is supposed to be called only when it's known that there's
@@ -217,7 +251,14 @@ impl Needle {
             Ok(hay) => hay,
             _ => { return None; }
         };
-        match self.search_mmap(&hay) {
+        let encoding = Encoding::detect_in(&hay);
+        if encoding.is_utf16() {
+            // the extract is built from the decoded text
+            let text = encoding.decode(&hay);
+            return text.find(self.as_str())
+                .map(|pos| ContentMatch::build(text.as_bytes(), pos, self.as_str(), desired_len));
+        }
+        match self.search_mmap(&hay, encoding) {
             ContentSearchResult::Found { pos } => {
                 Some(ContentMatch::build(&hay, pos, self.as_str(), desired_len))
             }
@@ -232,9 +273,20 @@ mod content_search_tests {
 
     #[test]
     fn test_found() -> Result<(), io::Error> {
-        let needle = Needle::new("inception", 1_000_000);
+        let needle = Needle::new("inception", &ContentSearchLimits::default());
         let res = needle.search("src/content_search/needle.rs")?;
         assert!(res.is_found());
         Ok(())
     }
+
+    #[test]
+    fn test_found_in_utf16() {
+        let needle = Needle::new("été", &ContentSearchLimits::default());
+        // "un été" in UTF-16LE, with a BOM
+        let hay = b"\xff\xfeu\x00n\x00 \x00\xe9\x00t\x00\xe9\x00";
+        assert_eq!(needle.find_utf16(hay, Encoding::Utf16Le), Some(8));
+        // the bytes of the needle at an odd position aren't a match
+        let hay = b"\x00\xe9\x00t\x00\xe9\x00";
+        assert_eq!(needle.find_utf16(hay, Encoding::Utf16Le), None);
+    }
 }
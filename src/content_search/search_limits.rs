@@ -0,0 +1,76 @@
+use {
+    super::DEFAULT_MAX_FILE_SIZE,
+    crate::path::Glob,
+    std::path::Path,
+};
+
+/// how many of the first bytes of a file are checked when
+/// sniffing binary content
+const SNIFF_LEN: usize = 1024;
+
+/// the rules excluding files from content searches, so
+/// that their cost stays under control (comes from conf)
+#[derive(Debug, Clone)]
+pub struct ContentSearchLimits {
+
+    /// files bigger than this size, in bytes, aren't searched
+    pub max_file_size: usize,
+
+    /// files whose path matches one of those globs aren't searched
+    pub excluded: Vec<Glob>,
+
+    /// when set, files whose first bytes have a greater proportion
+    /// of control characters aren't searched
+    pub binary_threshold: Option<f32>,
+}
+
+impl Default for ContentSearchLimits {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            excluded: Vec::new(),
+            binary_threshold: None,
+        }
+    }
+}
+
+impl ContentSearchLimits {
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.excluded.iter().any(|glob| glob.matches_path(path))
+    }
+    /// tell whether the first bytes look binary according to
+    /// the binary threshold, if any
+    pub fn looks_binary(&self, hay: &[u8]) -> bool {
+        let threshold = match self.binary_threshold {
+            Some(threshold) => threshold,
+            None => return false,
+        };
+        let sample = &hay[..hay.len().min(SNIFF_LEN)];
+        if sample.is_empty() {
+            return false;
+        }
+        let control_count = sample
+            .iter()
+            .filter(|&&b| b < 32 && !matches!(b, b'\t' | b'\n' | b'\r' | 12 | 27))
+            .count();
+        control_count as f32 / sample.len() as f32 > threshold
+    }
+}
+
+#[cfg(test)]
+mod search_limits_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_binary_sniffing() {
+        let mut limits = ContentSearchLimits::default();
+        let hay = b"some\x00\x01\x02text";
+        assert!(!limits.looks_binary(hay));
+        limits.binary_threshold = Some(0.2);
+        assert!(limits.looks_binary(hay));
+        assert!(!limits.looks_binary(b"some\ttext\r\n"));
+        limits.binary_threshold = Some(0.5);
+        assert!(!limits.looks_binary(hay));
+    }
+}
@@ -0,0 +1,173 @@
+//! conversion of text with ANSI escape codes (as written by
+//! broot when printing a tree) into HTML
+
+use {
+    crokey::crossterm::style::Color,
+    std::fmt::Write,
+};
+
+/// the style of the written chars, as set by SGR sequences
+#[derive(Debug, Default, Clone, PartialEq)]
+struct SgrStyle {
+    fg: Option<(u8, u8, u8)>,
+    bg: Option<(u8, u8, u8)>,
+    bold: bool,
+    italic: bool,
+    underlined: bool,
+    crossed_out: bool,
+}
+
+/// read a color given as `5;n` or `2;r;g;b` after a 38 or 48 code
+fn extended_color(params: &mut impl Iterator<Item = u16>) -> Option<(u8, u8, u8)> {
+    match params.next()? {
+        5 => Some(ansi_colours::rgb_from_ansi256(params.next()? as u8)),
+        2 => Some((params.next()? as u8, params.next()? as u8, params.next()? as u8)),
+        _ => None,
+    }
+}
+
+impl SgrStyle {
+    fn apply(&mut self, params: &[u16]) {
+        let mut params = params.iter().copied();
+        while let Some(code) = params.next() {
+            match code {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                3 => self.italic = true,
+                4 => self.underlined = true,
+                9 => self.crossed_out = true,
+                22 => self.bold = false,
+                23 => self.italic = false,
+                24 => self.underlined = false,
+                29 => self.crossed_out = false,
+                30..=37 => self.fg = Some(ansi_colours::rgb_from_ansi256(code as u8 - 30)),
+                38 => self.fg = extended_color(&mut params),
+                39 => self.fg = None,
+                40..=47 => self.bg = Some(ansi_colours::rgb_from_ansi256(code as u8 - 40)),
+                48 => self.bg = extended_color(&mut params),
+                49 => self.bg = None,
+                90..=97 => self.fg = Some(ansi_colours::rgb_from_ansi256(code as u8 - 82)),
+                100..=107 => self.bg = Some(ansi_colours::rgb_from_ansi256(code as u8 - 92)),
+                _ => {}
+            }
+        }
+    }
+    fn css(&self) -> String {
+        let mut css = String::new();
+        if let Some((r, g, b)) = self.fg {
+            let _ = write!(css, "color:#{:02x}{:02x}{:02x};", r, g, b);
+        }
+        if let Some((r, g, b)) = self.bg {
+            let _ = write!(css, "background-color:#{:02x}{:02x}{:02x};", r, g, b);
+        }
+        if self.bold {
+            css.push_str("font-weight:bold;");
+        }
+        if self.italic {
+            css.push_str("font-style:italic;");
+        }
+        match (self.underlined, self.crossed_out) {
+            (true, true) => css.push_str("text-decoration:underline line-through;"),
+            (true, false) => css.push_str("text-decoration:underline;"),
+            (false, true) => css.push_str("text-decoration:line-through;"),
+            _ => {}
+        }
+        css
+    }
+}
+
+/// the css value of a terminal color
+pub fn css_color(color: Color) -> Option<String> {
+    let (r, g, b) = match color {
+        Color::Reset => {
+            return None;
+        }
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::AnsiValue(v) => ansi_colours::rgb_from_ansi256(v),
+        Color::Black => ansi_colours::rgb_from_ansi256(0),
+        Color::DarkRed => ansi_colours::rgb_from_ansi256(1),
+        Color::DarkGreen => ansi_colours::rgb_from_ansi256(2),
+        Color::DarkYellow => ansi_colours::rgb_from_ansi256(3),
+        Color::DarkBlue => ansi_colours::rgb_from_ansi256(4),
+        Color::DarkMagenta => ansi_colours::rgb_from_ansi256(5),
+        Color::DarkCyan => ansi_colours::rgb_from_ansi256(6),
+        Color::Grey => ansi_colours::rgb_from_ansi256(7),
+        Color::DarkGrey => ansi_colours::rgb_from_ansi256(8),
+        Color::Red => ansi_colours::rgb_from_ansi256(9),
+        Color::Green => ansi_colours::rgb_from_ansi256(10),
+        Color::Yellow => ansi_colours::rgb_from_ansi256(11),
+        Color::Blue => ansi_colours::rgb_from_ansi256(12),
+        Color::Magenta => ansi_colours::rgb_from_ansi256(13),
+        Color::Cyan => ansi_colours::rgb_from_ansi256(14),
+        Color::White => ansi_colours::rgb_from_ansi256(15),
+    };
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+fn push_escaped(html: &mut String, c: char) {
+    match c {
+        '<' => html.push_str("&lt;"),
+        '>' => html.push_str("&gt;"),
+        '&' => html.push_str("&amp;"),
+        '\r' => {}
+        _ => html.push(c),
+    }
+}
+
+/// convert the text to HTML spans, interpreting the SGR escape
+/// sequences and ignoring the other ones
+pub fn ansi_to_html(ansi: &str) -> String {
+    let mut html = String::new();
+    let mut style = SgrStyle::default();
+    let mut open_style: Option<SgrStyle> = None; // style of the open span
+    let mut chars = ansi.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            chars.next();
+            let mut sequence = String::new();
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    if c == 'm' {
+                        let params: Vec<u16> = sequence
+                            .split(';')
+                            .map(|p| p.parse().unwrap_or(0))
+                            .collect();
+                        style.apply(&params);
+                    }
+                    break;
+                }
+                sequence.push(c);
+            }
+            continue;
+        }
+        if c != '\n' && open_style.as_ref() != Some(&style) {
+            if open_style.take().is_some() {
+                html.push_str("</span>");
+            }
+            if style != SgrStyle::default() {
+                let _ = write!(html, "<span style=\"{}\">", style.css());
+                open_style = Some(style.clone());
+            }
+        }
+        push_escaped(&mut html, c);
+    }
+    if open_style.is_some() {
+        html.push_str("</span>");
+    }
+    html
+}
+
+#[cfg(test)]
+mod ansi_html_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_ansi_to_html() {
+        assert_eq!(
+            ansi_to_html("\x1b[38;5;9m\x1b[1msrc\x1b[0m <a&b>\r\n\x1b[48;2;0;0;255mx\x1b[49m"),
+            "<span style=\"color:#ff0000;font-weight:bold;\">src</span> &lt;a&amp;b&gt;\n\
+                <span style=\"background-color:#0000ff;\">x</span>",
+        );
+    }
+}
@@ -6,6 +6,7 @@ use {
     crate::{
         app::Panel,
     },
+    fnv::FnvHashMap,
     termimad::Area,
 };
 
@@ -26,6 +27,34 @@ const MINIMAL_PANEL_HEIGHT: u16 = 4;
 const MINIMAL_PANEL_WIDTH: u16 = 4;
 const MINIMAL_SCREEN_WIDTH: u16 = 8;
 
+/// the widths of the panels, when changed by the user during
+/// the session, stored as shares of the screen width for each
+/// number of displayed panels
+#[derive(Debug, Clone, Default)]
+pub struct PanelWidths {
+    shares: FnvHashMap<usize, Vec<f64>>,
+}
+
+impl PanelWidths {
+    /// remember the current widths of the panels, so that they're
+    /// applied again when there's the same number of panels
+    pub fn set(&mut self, widths: &[u16], screen_width: u16) {
+        let shares = widths.iter()
+            .map(|&w| w as f64 / screen_width as f64)
+            .collect();
+        self.shares.insert(widths.len(), shares);
+    }
+    /// compute the widths of the n panels, or None if the user
+    /// never set them for this number of panels
+    fn get(&self, n: usize, screen_width: u16) -> Option<Vec<u16>> {
+        self.shares.get(&n).map(|shares| {
+            shares.iter()
+                .map(|share| ((share * screen_width as f64).round() as u16).max(MINIMAL_PANEL_WIDTH))
+                .collect()
+        })
+    }
+}
+
 enum Slot<'a> {
     Panel(usize),
     New(&'a mut Areas),
@@ -39,6 +68,7 @@ impl Areas {
         mut insertion_idx: usize,
         screen: Screen,
         with_preview: bool, // slightly larger last panel
+        widths: &PanelWidths,
     ) -> Self {
         if insertion_idx > present_panels.len() {
             insertion_idx = present_panels.len();
@@ -59,7 +89,7 @@ impl Areas {
         for i in insertion_idx..present_panels.len() {
             slots.push(Slot::Panel(i));
         }
-        Self::compute_areas(present_panels, &mut slots, screen, with_preview);
+        Self::compute_areas(present_panels, &mut slots, screen, with_preview, widths);
         areas
     }
 
@@ -67,12 +97,13 @@ impl Areas {
         panels: &mut [Panel],
         screen: Screen,
         with_preview: bool, // slightly larger last panel
+        widths: &PanelWidths,
     ) {
         let mut slots = Vec::new();
         for i in 0..panels.len() {
             slots.push(Slot::Panel(i));
         }
-        Self::compute_areas(panels, &mut slots, screen, with_preview)
+        Self::compute_areas(panels, &mut slots, screen, with_preview, widths)
     }
 
     fn compute_areas(
@@ -80,6 +111,7 @@ impl Areas {
         slots: &mut [Slot],
         screen: Screen,
         with_preview: bool, // slightly larger last panel
+        widths: &PanelWidths,
     ) {
         let screen_height = screen.height.max(MINIMAL_PANEL_HEIGHT);
         let screen_width = screen.width.max(MINIMAL_SCREEN_WIDTH);
@@ -92,10 +124,18 @@ impl Areas {
         if panel_width < MINIMAL_PANEL_WIDTH {
             panel_width = panel_width.max(MINIMAL_PANEL_WIDTH);
         }
+        let user_widths = widths.get(slots.len(), screen_width);
         let mut x = 0;
         let nb_pos = slots.len();
         #[allow(clippy::needless_range_loop)]
         for slot_idx in 0..nb_pos {
+            if let Some(user_widths) = &user_widths {
+                // we must leave enough space for the next panels
+                let next_panels_min_width = (nb_pos - slot_idx - 1) as u16 * MINIMAL_PANEL_WIDTH;
+                panel_width = user_widths[slot_idx]
+                    .min(screen_width.saturating_sub(x + next_panels_min_width))
+                    .max(MINIMAL_PANEL_WIDTH);
+            }
             if slot_idx == nb_pos - 1 {
                 panel_width = screen_width - x;
             }
@@ -103,8 +143,8 @@ impl Areas {
                 Slot::Panel(panel_idx) => &mut panels[*panel_idx].areas,
                 Slot::New(areas) => areas,
             };
-            let y = screen_height - 2;
-            areas.state = Area::new(x, 0, panel_width, y);
+            let y = screen.top + screen_height - 2;
+            areas.state = Area::new(x, screen.top, panel_width, screen_height - 2);
             areas.status = if WIDE_STATUS {
                 Area::new(0, y, screen_width, 1)
             } else {
@@ -129,6 +169,26 @@ impl Areas {
         }
     }
 
+    /// compute the widths of the panels after a move of the left
+    /// border of the panel at idx by dx columns (negative to the left)
+    pub fn widths_moving_border(
+        panels: &[Panel],
+        idx: usize,
+        dx: i32,
+    ) -> Vec<u16> {
+        let mut widths: Vec<u16> = panels.iter()
+            .map(|p| p.areas.state.width)
+            .collect();
+        if idx > 0 && idx < widths.len() {
+            let min = MINIMAL_PANEL_WIDTH as i32;
+            let (left, right) = (widths[idx - 1] as i32, widths[idx] as i32);
+            let dx = dx.max(min - left).min(right - min);
+            widths[idx - 1] = (left + dx) as u16;
+            widths[idx] = (right - dx) as u16;
+        }
+        widths
+    }
+
     pub fn is_first(&self) -> bool {
         self.pos_idx == 0
     }
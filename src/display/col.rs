@@ -2,7 +2,7 @@ use {
     crate::{
         app::AppState,
         errors::ConfError,
-        tree::Tree,
+        tree::{Sort, Tree},
     },
     serde::Deserialize,
     std::{
@@ -12,24 +12,31 @@ use {
 };
 
 // number of columns in enum
-const COLS_COUNT: usize = 10;
+const COLS_COUNT: usize = 14;
 
 /// One of the "columns" of the tree view
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Col {
+    /// number of the visible row, as used by `:goto`
+    RowNumber,
+
     /// selection mark, typically a triangle on the selected line
     Mark,
 
     /// Git file status
     Git,
 
+    /// result of the lint probes defined in conf
+    Lint,
+
     /// the branch showing filliation
     Branch,
 
     /// The filesystem's device id (unix only)
     DeviceId,
 
-    /// file mode and ownership
+    /// file mode or attributes, ownership and ACL marker,
+    /// depending on the permission parts
     Permission,
 
     /// last modified date
@@ -41,6 +48,12 @@ pub enum Col {
     /// number of files in the directory
     Count,
 
+    /// star rating given by the user to the file
+    Rating,
+
+    /// tags given by the user to the file
+    Tags,
+
     /// marks whether the path is staged (not used for now, may be removed)
     Staged,
 
@@ -61,13 +74,17 @@ pub enum ColsConf {
 
 /// Default column order
 pub static DEFAULT_COLS: Cols = [
+    Col::RowNumber,
     Col::Mark,
     Col::Git,
+    Col::Lint,
     Col::DeviceId,
     Col::Size,
     Col::Date,
     Col::Permission,
     Col::Count,
+    Col::Rating,
+    Col::Tags,
     Col::Branch,
     Col::Staged,
     Col::Name,
@@ -78,14 +95,18 @@ impl FromStr for Col {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.to_lowercase();
         match s.as_ref() {
+            "#" | "row" | "row-number" => Ok(Self::RowNumber),
             "m" | "mark" => Ok(Self::Mark),
             "g" | "git" => Ok(Self::Git),
+            "l" | "lint" => Ok(Self::Lint),
             "dev" | "device" | "device-id" => Ok(Self::DeviceId),
             "b" | "branch" => Ok(Self::Branch),
             "p" | "permission" => Ok(Self::Permission),
             "d" | "date" => Ok(Self::Date),
             "s" | "size" => Ok(Self::Size),
             "c" | "count" => Ok(Self::Count),
+            "r" | "rating" => Ok(Self::Rating),
+            "t" | "tag" | "tags" => Ok(Self::Tags),
             "staged" => Ok(Self::Staged),
             "n" | "name" => Ok(Self::Name),
             _ => Err(ConfError::InvalidCols {
@@ -108,13 +129,17 @@ impl Col {
     /// tell whether this column should have an empty character left
     pub fn needs_left_margin(self) -> bool {
         match self {
+            Col::RowNumber => false,
             Col::Mark => false,
             Col::Git => false,
+            Col::Lint => false,
             Col::DeviceId => true,
             Col::Size => true,
             Col::Date => true,
             Col::Permission => true,
             Col::Count => false,
+            Col::Rating => false,
+            Col::Tags => false,
             Col::Branch => false,
             Col::Staged => false,
             Col::Name => false,
@@ -123,17 +148,25 @@ impl Col {
     pub fn is_visible(
         self,
         tree: &Tree,
-        _app_state: Option<&AppState>,
+        app_state: Option<&AppState>,
     ) -> bool {
         let tree_options = &tree.options;
         match self {
+            Col::RowNumber => tree_options.show_row_numbers,
             Col::Mark => tree_options.show_selection_mark,
             Col::Git => tree.git_status.is_some(),
+            Col::Lint => tree_options.show_lint,
             Col::DeviceId => tree_options.show_device_id,
             Col::Size => tree_options.show_sizes,
             Col::Date => tree_options.show_dates,
-            Col::Permission => tree_options.show_permissions,
+            Col::Permission => tree_options.shows_permissions(),
             Col::Count => tree_options.show_counts,
+            Col::Rating => tree_options.sort == Sort::Rating || app_state.map_or(false, |a| {
+                tree.lines.iter().any(|line| a.tags.is_rated(&line.path))
+            }),
+            Col::Tags => app_state.map_or(false, |a| {
+                tree.lines.iter().any(|line| a.tags.is_tagged(&line.path))
+            }),
             Col::Branch => true,
             //Col::Staged => app_state.map_or(false, |a| !a.stage.is_empty()),
             Col::Staged => false,
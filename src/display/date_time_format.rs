@@ -0,0 +1,175 @@
+use {
+    crate::errors::ConfError,
+    chrono::{DateTime, FixedOffset, Local, Utc},
+    once_cell::sync::Lazy,
+    std::env,
+};
+
+/// How dates and times are written: the strftime formats
+/// (see https://docs.rs/chrono/0.4/chrono/format/strftime/index.html)
+/// and the timezone.
+///
+/// Unless configured, the formats are the ones of the locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTimeFormat {
+    /// format of the dates with their time (eg in the tree)
+    pub date_time: &'static str,
+    /// format of the dates without time
+    pub date: &'static str,
+    /// format of the times without date
+    pub time: &'static str,
+    /// the timezone, None for the local one
+    pub offset: Option<FixedOffset>,
+}
+
+static LOCALE_FORMAT: Lazy<DateTimeFormat> = Lazy::new(|| {
+    let locale = ["LC_ALL", "LC_TIME", "LANG"].iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    let format = DateTimeFormat::of_locale(&locale);
+    debug!("date format of locale {:?}: {:?}", locale, format);
+    format
+});
+
+const ISO_FORMAT: DateTimeFormat = DateTimeFormat {
+    date_time: "%Y-%m-%d %H:%M",
+    date: "%Y-%m-%d",
+    time: "%H:%M:%S",
+    offset: None,
+};
+
+impl Default for DateTimeFormat {
+    fn default() -> Self {
+        *LOCALE_FORMAT
+    }
+}
+
+impl DateTimeFormat {
+    /// the formats of a POSIX locale (eg "fr_FR.UTF-8"), with the
+    /// broot historical one when the locale isn't a known one
+    fn of_locale(locale: &str) -> Self {
+        let locale = locale.split(['.', '@']).next().unwrap_or("");
+        let (date_time, date, time) = match locale {
+            "en_US" | "en_PH" => ("%m/%d/%Y %I:%M %p", "%m/%d/%Y", "%I:%M:%S %p"),
+            "en_CA" | "fr_CA" | "sv_SE" | "lt_LT" => return ISO_FORMAT,
+            "nl_NL" | "nl_BE" => ("%d-%m-%Y %H:%M", "%d-%m-%Y", "%H:%M:%S"),
+            "hu_HU" => ("%Y.%m.%d %H:%M", "%Y.%m.%d", "%H:%M:%S"),
+            "ko_KR" => ("%Y.%m.%d %H:%M", "%Y.%m.%d", "%H:%M:%S"),
+            _ => match locale.split('_').next().unwrap_or("") {
+                "de" | "cs" | "da" | "fi" | "nb" | "no" | "nn" | "pl" | "ro" | "ru" | "sk"
+                | "sl" | "tr" | "uk" => ("%d.%m.%Y %H:%M", "%d.%m.%Y", "%H:%M:%S"),
+                "en" | "fr" | "es" | "it" | "pt" | "el" | "ca" | "ga" | "he" | "id" | "vi" => {
+                    ("%d/%m/%Y %H:%M", "%d/%m/%Y", "%H:%M:%S")
+                }
+                "ja" | "zh" => ("%Y/%m/%d %H:%M", "%Y/%m/%d", "%H:%M:%S"),
+                _ => ("%Y/%m/%d %R", "%Y/%m/%d", "%H:%M:%S"),
+            },
+        };
+        Self { date_time, date, time, offset: None }
+    }
+    /// build the format from the `date_time_format` and `date_time_zone`
+    /// settings of the configuration.
+    ///
+    /// The format may be "locale", "iso" or a strftime format for the
+    /// dates with time. The timezone may be "local", "utc" or an
+    /// offset like "+02:00".
+    pub fn new(
+        format: Option<&str>,
+        zone: Option<&str>,
+    ) -> Result<Self, ConfError> {
+        let mut dtf = match format {
+            None | Some("locale") => Self::default(),
+            Some("iso") => ISO_FORMAT,
+            Some(format) => Self {
+                date_time: Box::leak(format.to_string().into_boxed_str()),
+                ..Self::default()
+            },
+        };
+        dtf.offset = match zone {
+            None | Some("local") => None,
+            Some(zone) => Some(parse_offset(zone).ok_or_else(|| {
+                ConfError::InvalidDateTimeZone { zone: zone.to_string() }
+            })?),
+        };
+        Ok(dtf)
+    }
+    fn in_zone(&self, time: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self.offset {
+            Some(offset) => time.with_timezone(&offset),
+            None => time.with_timezone(time.with_timezone(&Local).offset()),
+        }
+    }
+    pub fn format_date_time(&self, time: impl Into<DateTime<Utc>>) -> String {
+        self.in_zone(time.into()).format(self.date_time).to_string()
+    }
+    pub fn format_date(&self, time: impl Into<DateTime<Utc>>) -> String {
+        self.in_zone(time.into()).format(self.date).to_string()
+    }
+    pub fn format_time(&self, time: impl Into<DateTime<Utc>>) -> String {
+        self.in_zone(time.into()).format(self.time).to_string()
+    }
+    /// the width of the dates with time, which is assumed to
+    /// be the same for all dates
+    pub fn date_time_width(&self) -> usize {
+        self.format_date_time(Utc::now()).chars().count()
+    }
+    pub fn date_width(&self) -> usize {
+        self.format_date(Utc::now()).chars().count()
+    }
+    pub fn time_width(&self) -> usize {
+        self.format_time(Utc::now()).chars().count()
+    }
+}
+
+/// parse a timezone given as "utc" or as an offset ("+02:00",
+/// "-0530", "+2")
+fn parse_offset(zone: &str) -> Option<FixedOffset> {
+    if zone.eq_ignore_ascii_case("utc") || zone == "Z" {
+        return FixedOffset::east_opt(0);
+    }
+    let sign = match zone.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => {
+            return None;
+        }
+    };
+    let digits = zone[1..].replace(':', "");
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (digits[..2].parse::<i32>().ok()?, digits[2..].parse::<i32>().ok()?),
+        _ => {
+            return None;
+        }
+    };
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod date_time_format_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("utc"), FixedOffset::east_opt(0));
+        assert_eq!(parse_offset("+02:00"), FixedOffset::east_opt(7200));
+        assert_eq!(parse_offset("-0530"), FixedOffset::west_opt(5 * 3600 + 1800));
+        assert_eq!(parse_offset("+2"), FixedOffset::east_opt(7200));
+        assert_eq!(parse_offset("Europe/Paris"), None);
+        assert_eq!(parse_offset("+25:00"), None);
+    }
+
+    #[test]
+    fn test_locale_formats() {
+        assert_eq!(DateTimeFormat::of_locale("en_US.UTF-8").date, "%m/%d/%Y");
+        assert_eq!(DateTimeFormat::of_locale("fr_FR.UTF-8").date, "%d/%m/%Y");
+        assert_eq!(DateTimeFormat::of_locale("de_DE@euro").date, "%d.%m.%Y");
+        assert_eq!(DateTimeFormat::of_locale("sv_SE.UTF-8").date, "%Y-%m-%d");
+        assert_eq!(DateTimeFormat::of_locale("C").date_time, "%Y/%m/%d %R");
+    }
+}
@@ -6,29 +6,47 @@ use {
         GitStatusDisplay,
         MatchedString,
         num_format::format_count,
-        SPACE_FILLING, BRANCH_FILLING,
+        RowCache,
+        SizeFormat,
+        TitlePart,
+        TitleTemplate,
+        SPACE_FILLING,
     },
     crate::{
         app::AppState,
         content_search::ContentMatch,
         errors::ProgramError,
-        file_sum::FileSum,
+        file_sum::{FileSum, SizeSnapshot},
+        lint::LintStatus,
         pattern::PatternObject,
-        skin::{ExtColorMap, StyleMap},
+        skin::{ExtColorMap, Glyphs, StyleMap},
+        tag::MAX_RATING,
         task_sync::ComputationResult,
         tree::{Tree, TreeLine, TreeLineType},
     },
-    chrono::{DateTime, Local, TimeZone},
+    chrono::{TimeZone, Utc},
     crokey::crossterm::{
         cursor,
         QueueableCommand,
     },
-    file_size,
     git2::Status,
-    std::io::Write,
-    termimad::{CompoundStyle, ProgressBar},
+    std::{
+        io::Write,
+        sync::Arc,
+    },
+    termimad::CompoundStyle,
 };
 
+/// the screen position of a cell (the intersection of a
+/// tree line and a column) as it was last written
+#[derive(Debug, Clone, Copy)]
+pub struct CellSpan {
+    pub col: Col,
+    pub y: u16,
+    pub x_start: u16,
+    pub x_end: u16, // exclusive
+}
+
 /// A tree wrapper which can be used either
 /// - to write on the screen in the application,
 /// - or to write in a file or an exported string.
@@ -42,7 +60,12 @@ pub struct DisplayableTree<'a, 's, 't> {
     pub skin: &'s StyleMap,
     pub area: termimad::Area,
     pub in_app: bool, // if true we show the selection and scrollbar
+    pub show_scrollbar: bool, // only relevant in app
     pub ext_colors: &'s ExtColorMap,
+    pub glyphs: &'s Glyphs,
+    pub title: Option<&'s TitleTemplate>, // template of the root line
+    /// the sizes of a previous visit, to show the size deltas
+    pub size_reference: Option<Arc<SizeSnapshot>>,
 }
 
 impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
@@ -51,6 +74,7 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         tree: &'t Tree,
         skin: &'s StyleMap,
         ext_colors: &'s ExtColorMap,
+        glyphs: &'s Glyphs,
         width: u16,
         height: u16,
     ) -> DisplayableTree<'a, 's, 't> {
@@ -59,6 +83,7 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
             tree,
             skin,
             ext_colors,
+            glyphs,
             area: termimad::Area {
                 left: 0,
                 top: 0,
@@ -66,6 +91,9 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
                 height,
             },
             in_app: false,
+            show_scrollbar: false,
+            title: None,
+            size_reference: None,
         }
     }
 
@@ -90,6 +118,14 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         if let Some(ext_color) = line.extension().and_then(|ext| self.ext_colors.get(ext)) {
             style.set_fg(ext_color);
         }
+        if self.tree.is_new(line) {
+            if let Some(c) = self.skin.new_file.get_fg() {
+                style.set_fg(c);
+            }
+            if let Some(c) = self.skin.new_file.get_bg() {
+                style.set_bg(c);
+            }
+        }
         if selected {
             if let Some(c) = self.skin.selected_line.get_bg() {
                 style.set_bg(c);
@@ -122,7 +158,15 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         line: &TreeLine,
         selected: bool,
     ) -> Result<usize, termimad::Error> {
-        let device_id = line.device_id();
+        let device_id = match line.device_id() {
+            Some(device_id) => device_id,
+            None => {
+                // the metadata are late
+                cond_bg!(style, self, selected, self.skin.device_id_sep);
+                cw.queue_g_string(style, format!("{:^7}", self.glyphs.ellipsis))?;
+                return Ok(0);
+            }
+        };
         cond_bg!(style, self, selected, self.skin.device_id_major);
         cw.queue_g_string(style, format!("{:>3}", device_id.major))?;
         cond_bg!(style, self, selected, self.skin.device_id_sep);
@@ -139,28 +183,120 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         selected: bool,
     ) -> Result<usize, termimad::Error> {
         Ok(if selected {
-            cw.queue_char(style, '▶')?;
+            cw.queue_char(style, self.glyphs.selection_mark)?;
             0
         } else {
             1
         })
     }
 
+    /// write the number of the visible row, right aligned
+    fn write_line_row_number<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        row: u16,
+        row_number_len: usize,
+        selected: bool,
+    ) -> Result<usize, termimad::Error> {
+        cond_bg!(row_number_style, self, selected, self.skin.row_number);
+        cw.queue_g_string(
+            row_number_style,
+            format!("{:>w$}", row, w = row_number_len),
+        )?;
+        Ok(1)
+    }
+
+    /// write, in the row below a hit of a content search, the
+    /// line matching the pattern, aligned with the name of the hit
+    fn write_match_line<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        line_index: usize,
+        branch_x: usize,
+        name_x: usize,
+        selected: bool,
+    ) -> Result<(), ProgramError> {
+        let line = &self.tree.lines[line_index];
+        cond_bg!(void_style, self, selected, self.skin.default);
+        let mut x = 0;
+        if branch_x < name_x && !self.tree.options.flat {
+            cw.repeat(void_style, &SPACE_FILLING, branch_x)?;
+            cond_bg!(branch_style, self, selected, self.skin.tree);
+            let mut branch = String::new();
+            for depth in 0..line.depth as usize {
+                if self.tree.has_branch(line_index + 1, depth) {
+                    branch.extend([self.glyphs.branch_vertical, ' ', ' ']);
+                } else {
+                    branch.push_str("   ");
+                }
+            }
+            x = branch_x + branch.chars().count();
+            cw.queue_g_string(branch_style, branch)?;
+        }
+        // the extract is preceded by two spaces
+        cw.repeat(void_style, &SPACE_FILLING, name_x.saturating_sub(x + 2))?;
+        if cw.allowed > 8 {
+            let extract = self.tree.options.pattern.pattern
+                .search_content(&line.path, cw.allowed - 2);
+            if let Some(extract) = extract {
+                self.write_content_extract(cw, extract, selected)?;
+            }
+        }
+        Ok(())
+    }
+
     fn write_line_size<'w, W: Write>(
         &self,
         cw: &mut CropWriter<'w, W>,
         line: &TreeLine,
         style: &CompoundStyle,
+        size_width: usize,
         _selected: bool,
     ) -> Result<usize, termimad::Error> {
         Ok(if let Some(s) = line.sum {
+            let size = self.tree.options.size_format.format(s.to_size());
+            cw.queue_g_string(style, format!("{:>size_width$}", size))?;
+            1
+        } else {
+            size_width + 1
+        })
+    }
+
+    fn size_delta_style(&self, delta: i64) -> &CompoundStyle {
+        if delta > 0 {
+            &self.skin.size_growth
+        } else {
+            &self.skin.size_shrink
+        }
+    }
+
+    /// write, after the size, its change since the reference
+    /// snapshot. The void_len of the size is given, and included
+    /// in the returned one when there's no delta
+    fn write_line_size_delta<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        line: &TreeLine,
+        reference: &SizeSnapshot,
+        void_len: usize,
+        selected: bool,
+    ) -> Result<usize, termimad::Error> {
+        let delta = line.sum
+            .and_then(|sum| reference.delta(&line.path, sum.to_size()))
+            .filter(|&delta| delta != 0);
+        Ok(if let Some(delta) = delta {
+            cond_bg!(void_style, self, selected, self.skin.default);
+            cw.repeat(void_style, &SPACE_FILLING, void_len)?;
+            cond_bg!(delta_style, self, selected, *self.size_delta_style(delta));
+            let size_format = self.tree.options.size_format;
+            let delta_width = size_format.abbreviated_width() + 1;
             cw.queue_g_string(
-                style,
-                format!("{:>4}", file_size::fit_4(s.to_size())),
+                delta_style,
+                format!("{:>delta_width$}", format_size_delta(delta, size_format)),
             )?;
             1
         } else {
-            5
+            void_len + self.tree.options.size_format.abbreviated_width() + 1
         })
     }
 
@@ -172,23 +308,24 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         line: &TreeLine,
         label_style: &CompoundStyle,
         total_size: FileSum,
+        size_width: usize,
         selected: bool,
     ) -> Result<usize, termimad::Error> {
         Ok(if let Some(s) = line.sum {
-            let pb = ProgressBar::new(s.part_of_size(total_size), 10);
             cond_bg!(sparse_style, self, selected, self.skin.sparse);
-            cw.queue_g_string(
-                label_style,
-                format!("{:>4}", file_size::fit_4(s.to_size())),
-            )?;
+            let size = self.tree.options.size_format.format(s.to_size());
+            cw.queue_g_string(label_style, format!("{:>size_width$}", size))?;
             cw.queue_char(
                 sparse_style,
                 if s.is_sparse() && line.is_file() { 's' } else { ' ' },
             )?;
-            cw.queue_g_string(label_style, format!("{:<10}", pb))?;
+            cw.queue_g_string(
+                label_style,
+                self.glyphs.progress_bar(s.part_of_size(total_size), 10),
+            )?;
             1
         } else {
-            16
+            size_width + 12
         })
     }
 
@@ -216,19 +353,66 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         Ok(0)
     }
 
+    fn write_line_lint<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        line: &TreeLine,
+        selected: bool,
+    ) -> Result<usize, termimad::Error> {
+        let (style, char) = match line.lint {
+            Some(LintStatus::Pass) => (&self.skin.lint_pass, self.glyphs.lint_pass),
+            Some(LintStatus::Fail) => (&self.skin.lint_fail, self.glyphs.lint_fail),
+            _ => (&self.skin.tree, ' '),
+        };
+        cond_bg!(lint_style, self, selected, style);
+        cw.queue_char(lint_style, char)?;
+        Ok(0)
+    }
+
+    /// write the star rating of the line, padded to MAX_RATING stars
+    fn write_line_rating<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        line: &TreeLine,
+        selected: bool,
+    ) -> Result<usize, termimad::Error> {
+        let rating = self.app_state
+            .map_or(0, |a| a.tags.rating_of(&line.path));
+        cond_bg!(rating_style, self, selected, self.skin.rating);
+        for _ in 0..rating {
+            cw.queue_char(rating_style, self.glyphs.rating_star)?;
+        }
+        Ok((MAX_RATING - rating) as usize + 1)
+    }
+
+    /// write the tags of the line, padded to tags_len
+    fn write_line_tags<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        line: &TreeLine,
+        tags_len: usize,
+        selected: bool,
+    ) -> Result<usize, termimad::Error> {
+        let tags = self.app_state
+            .and_then(|a| a.tags.tags_of(&line.path))
+            .map(|tags| tags.iter().map(String::as_str).collect::<Vec<&str>>().join(","))
+            .unwrap_or_default();
+        cond_bg!(tag_style, self, selected, self.skin.tag);
+        cw.queue_str(tag_style, &tags)?;
+        Ok(tags_len - tags.chars().count() + 1)
+    }
+
     fn write_date<'w, W: Write>(
         &self,
         cw: &mut CropWriter<'w, W>,
         seconds: i64,
         selected: bool,
     ) -> Result<usize, termimad::Error> {
-        let date_time: DateTime<Local> = Local.timestamp(seconds, 0);
+        let date_time = Utc.timestamp(seconds, 0);
         cond_bg!(date_style, self, selected, self.skin.dates);
         cw.queue_g_string(
             date_style,
-            date_time
-                .format(self.tree.options.date_time_format)
-                .to_string(),
+            self.tree.options.date_time_format.format_date_time(date_time),
         )?;
         Ok(1)
     }
@@ -241,33 +425,34 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         selected: bool,
         staged: bool,
     ) -> Result<usize, ProgramError> {
+        if self.tree.options.flat {
+            // the lines aren't indented
+            return Ok(0);
+        }
         cond_bg!(branch_style, self, selected, self.skin.tree);
+        let g = self.glyphs;
         let mut branch = String::new();
         for depth in 0..line.depth {
-            branch.push_str(
-                if line.left_branchs[depth as usize] {
-                    if self.tree.has_branch(line_index + 1, depth as usize) {
-                        // TODO: If a theme is on, remove the horizontal lines
-                        if depth == line.depth - 1 {
-                            if staged {
-                                "├◍─"
-                            } else {
-                                "├──"
-                            }
-                        } else {
-                            "│  "
-                        }
+            let chars = if line.left_branchs[depth as usize] {
+                let start = if self.tree.has_branch(line_index + 1, depth as usize) {
+                    if depth == line.depth - 1 {
+                        Some(g.branch_middle)
                     } else {
-                        if staged {
-                            "└◍─"
-                        } else {
-                            "└──"
-                        }
+                        None
                     }
                 } else {
-                    "   "
-                },
-            );
+                    Some(g.branch_last)
+                };
+                match start {
+                    // TODO: If a theme is on, remove the horizontal lines
+                    Some(start) if staged => [start, g.branch_staged, g.branch_horizontal],
+                    Some(start) => [start, g.branch_horizontal, g.branch_horizontal],
+                    None => [g.branch_vertical, ' ', ' '],
+                }
+            } else {
+                [' ', ' ', ' ']
+            };
+            branch.extend(chars);
         }
         if !branch.is_empty() {
             cw.queue_g_string(branch_style, branch)?;
@@ -283,7 +468,7 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         staged: bool,
     ) -> Result<usize, termimad::Error> {
         Ok(if staged {
-            cw.queue_char(style, '◍')?; // ▣
+            cw.queue_char(style, self.glyphs.staged_mark)?;
             0
         } else {
             1
@@ -300,11 +485,23 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         selected: bool,
     ) -> Result<usize, ProgramError> {
         cond_bg!(char_match_style, self, selected, self.skin.char_match);
+        if self.app_state.map_or(false, |a| a.marks.contains(&line.path)) {
+            cond_bg!(marked_style, self, selected, self.skin.marked);
+            cw.queue_char(marked_style, self.glyphs.marked_mark)?;
+            cw.queue_char(marked_style, ' ')?;
+        }
         if let Some(icon) = line.icon {
             cw.queue_char(style, icon)?;
             cw.queue_char(style, ' ')?;
             cw.queue_char(style, ' ')?;
         }
+        if self.tree.options.flat && !pattern_object.subpath {
+            // in flat mode, the name is preceded with the path of its directory
+            if let Some(idx) = line.subpath.rfind('/') {
+                cond_bg!(parent_style, self, selected, self.skin.parent);
+                cw.queue_str(parent_style, &line.subpath[..=idx])?;
+            }
+        }
         if pattern_object.subpath {
             if self.tree.options.show_matching_characters_on_path_searches && line.unlisted == 0 {
                 let name_match = self.tree.options.pattern.pattern
@@ -341,7 +538,8 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         match &line.line_type {
             TreeLineType::Dir => {
                 if line.unlisted > 0 {
-                    cw.queue_str(style, " …")?;
+                    cw.queue_char(style, ' ')?;
+                    cw.queue_char(style, self.glyphs.ellipsis)?;
                 }
             }
             TreeLineType::BrokenSymLink(direct_path) => {
@@ -401,37 +599,119 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
             if let Some(s) = line.sum {
                 cw.queue_g_string(
                     style,
-                    format!("{:>4} ", file_size::fit_4(s.to_size())),
+                    format!("{:>4} ", self.tree.options.size_format.format(s.to_size())),
                 )?;
+                if let Some(reference) = &self.size_reference {
+                    if let Some(delta) = reference.delta(&line.path, s.to_size()) {
+                        cond_bg!(delta_style, self, selected, *self.size_delta_style(delta));
+                        cw.queue_g_string(
+                            delta_style,
+                            format!("{} since {} ", format_size_delta(delta, self.tree.options.size_format), reference.age()),
+                        )?;
+                    }
+                }
             }
         }
+        if let Some(title) = self.title.filter(|_| self.in_app) {
+            for part in &title.parts {
+                self.write_title_part(cw, part, style, selected)?;
+            }
+            self.extend_line_bg(cw, selected)?;
+            return Ok(());
+        }
         let title = line.path.to_string_lossy();
         cw.queue_str(style, &title)?;
         if self.in_app && !cw.is_full() {
-            if let ComputationResult::Done(git_status) = &self.tree.git_status {
-                let git_status_display = GitStatusDisplay::from(
-                    git_status,
-                    self.skin,
-                    cw.allowed,
-                );
-                git_status_display.write(cw, selected)?;
-            }
-            #[cfg(unix)]
+            self.write_root_git_status(cw, selected)?;
             if self.tree.options.show_root_fs {
-                if let Some(mount) = line.mount() {
-                    let fs_space_display = crate::filesystems::MountSpaceDisplay::from(
-                        &mount,
-                        self.skin,
-                        cw.allowed,
-                    );
-                    fs_space_display.write(cw, selected)?;
-                }
+                self.write_root_disk(cw, selected)?;
             }
             self.extend_line_bg(cw, selected)?;
         }
         Ok(())
     }
 
+    /// write one part of the templated title of the tree
+    fn write_title_part<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        part: &TitlePart,
+        style: &CompoundStyle,
+        selected: bool,
+    ) -> Result<(), ProgramError> {
+        let root = &self.tree.lines[0];
+        match part {
+            TitlePart::Text(s) => {
+                cw.queue_str(style, s)?;
+            }
+            TitlePart::Path => {
+                cw.queue_str(style, &root.path.to_string_lossy())?;
+            }
+            TitlePart::Name => {
+                cw.queue_str(style, &root.name)?;
+            }
+            TitlePart::GitBranch => {
+                if let ComputationResult::Done(git_status) = &self.tree.git_status {
+                    if let Some(branch) = &git_status.current_branch_name {
+                        cw.queue_str(style, branch)?;
+                    }
+                }
+            }
+            TitlePart::Git => {
+                self.write_root_git_status(cw, selected)?;
+            }
+            TitlePart::Filter => {
+                cw.queue_str(style, &self.tree.options.pattern.raw)?;
+            }
+            TitlePart::Depth => {
+                let depth = self.tree.lines.iter().map(|l| l.depth).max().unwrap_or(0);
+                cw.queue_g_string(style, depth.to_string())?;
+            }
+            TitlePart::Disk => {
+                self.write_root_disk(cw, selected)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_root_git_status<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        selected: bool,
+    ) -> Result<(), ProgramError> {
+        if let ComputationResult::Done(git_status) = &self.tree.git_status {
+            let git_status_display = GitStatusDisplay::from(
+                git_status,
+                self.skin,
+                self.glyphs,
+                cw.allowed,
+            );
+            git_status_display.write(cw, selected)?;
+        }
+        Ok(())
+    }
+
+    /// write the filesystem of the root, with its space usage
+    #[allow(unused_variables)]
+    fn write_root_disk<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        selected: bool,
+    ) -> Result<(), ProgramError> {
+        #[cfg(unix)]
+        if let Some(mount) = self.tree.lines[0].mount() {
+            let fs_space_display = crate::filesystems::MountSpaceDisplay::from(
+                &mount,
+                self.skin,
+                self.glyphs,
+                self.tree.options.size_format,
+                cw.allowed,
+            );
+            fs_space_display.write(cw, selected)?;
+        }
+        Ok(())
+    }
+
     /// if in app, extend the background till the end of screen row
     pub fn extend_line_bg<'w, W: Write>(
         &self,
@@ -451,12 +731,29 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
 
     /// write the whole tree on the given `W`
     pub fn write_on<W: Write>(&self, f: &mut W) -> Result<(), ProgramError> {
-        #[cfg(not(any(target_family = "windows", target_os = "android")))]
-        let perm_writer = super::PermWriter::for_tree(self.skin, self.tree);
+        self.write_on_with_spans(f, None, None)
+    }
+
+    /// write the whole tree on the given `W`, filling `spans`,
+    /// when given, with the positions of the written cells.
+    ///
+    /// When a started row cache is given, only the rows which
+    /// changed since the previous drawing are written.
+    pub fn write_on_with_spans<W: Write>(
+        &self,
+        f: &mut W,
+        mut spans: Option<&mut Vec<CellSpan>>,
+        mut rows: Option<&mut RowCache>,
+    ) -> Result<(), ProgramError> {
+        if let Some(spans) = spans.as_mut() {
+            spans.clear();
+        }
+        let perm_writer = super::PermWriter::for_tree(self.skin, self.glyphs, self.tree);
 
         let tree = self.tree;
         let total_size = tree.total_sum();
-        let scrollbar = if self.in_app {
+        let size_width = tree.options.size_format.width(total_size.to_size());
+        let scrollbar = if self.in_app && self.show_scrollbar {
             termimad::compute_scrollbar(
                 tree.scroll,
                 tree.lines.len() - 1, // the root line isn't scrolled
@@ -466,13 +763,17 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
         } else {
             None
         };
+        // every row is prepared in a buffer, to be compared
+        // with the one of the previous drawing
+        let mut row: Vec<u8> = Vec::new();
         if self.in_app {
-            f.queue(cursor::MoveTo(self.area.left, self.area.top))?;
+            row.queue(cursor::MoveTo(self.area.left, self.area.top))?;
         }
-        let mut cw = CropWriter::new(f, self.area.width as usize);
+        let mut cw = CropWriter::new(&mut row, self.area.width as usize);
         let pattern_object = tree.options.pattern.pattern.object();
         self.write_root_line(&mut cw, self.in_app && tree.selection == 0)?;
-        self.skin.queue_reset(f)?;
+        self.skin.queue_reset(&mut row)?;
+        write_row(f, &mut rows, 0, row)?;
 
         let visible_cols: Vec<Col> = tree
             .options
@@ -494,28 +795,50 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
             0
         };
 
+        // the tags column is as wide as the longest list of tags
+        let tags_len = if visible_cols.contains(&Col::Tags) {
+            self.app_state.map_or(0, |a| {
+                tree.lines.iter()
+                    .filter_map(|l| a.tags.tags_of(&l.path))
+                    .map(|tags| tags.iter().map(|t| t.chars().count() + 1).sum::<usize>() - 1)
+                    .max()
+                    .unwrap_or(0)
+            })
+        } else {
+            0
+        };
+
+        // the row numbers are as wide as the number of the last row
+        let row_number_len = (self.area.height - 1).to_string().len();
+
         // we compute the length of the dates, depending on the format
         let date_len = if tree.options.show_dates {
-            let date_time: DateTime<Local> = Local::now();
-            date_time.format(tree.options.date_time_format).to_string().len()
+            tree.options.date_time_format.date_time_width()
         } else {
             0 // we don't care
         };
 
+        let mut visible_rows = tree.visible_rows();
+        // where the branch and the name were written in the last line,
+        // so that the matching line below a hit can be aligned
+        let mut branch_x = 0;
+        let mut name_x = 0;
         for y in 1..self.area.height {
+            let mut row: Vec<u8> = Vec::new();
             if self.in_app {
-                f.queue(cursor::MoveTo(self.area.left, y + self.area.top))?;
+                row.queue(cursor::MoveTo(self.area.left, y + self.area.top))?;
             } else {
-                write!(f, "\r\n")?;
-            }
-            let mut line_index = y as usize;
-            if line_index > 0 {
-                line_index += tree.scroll as usize;
+                write!(row, "\r\n")?;
             }
+            let (line_index, is_match_line) = visible_rows.next()
+                .unwrap_or((tree.lines.len(), false));
             let mut selected = false;
-            let mut cw = CropWriter::new(f, self.area.width as usize);
+            let mut cw = CropWriter::new(&mut row, self.area.width as usize);
             let cw = &mut cw;
-            if line_index < tree.lines.len() {
+            if is_match_line {
+                selected = self.in_app && line_index == tree.selection;
+                self.write_match_line(cw, line_index, branch_x, name_x, selected)?;
+            } else if line_index < tree.lines.len() {
                 let line = &tree.lines[line_index];
                 selected = self.in_app && line_index == tree.selection;
                 let label_style = self.label_style(line, selected);
@@ -531,8 +854,18 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
                 let staged = self.app_state
                     .map_or(false, |a| a.stage.contains(&line.path));
                 for col in &visible_cols {
+                    let x_start = self.area.left + (self.area.width as usize - cw.allowed) as u16;
+                    match col {
+                        Col::Branch => branch_x = (x_start - self.area.left) as usize,
+                        Col::Name => name_x = (x_start - self.area.left) as usize,
+                        _ => {}
+                    }
                     let void_len = match col {
 
+                        Col::RowNumber => {
+                            self.write_line_row_number(cw, y, row_number_len, selected)?
+                        }
+
                         Col::Mark => {
                             self.write_line_selection_mark(cw, &label_style, selected)?
                         }
@@ -541,6 +874,10 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
                             self.write_line_git_status(cw, line, selected)?
                         }
 
+                        Col::Lint => {
+                            self.write_line_lint(cw, line, selected)?
+                        }
+
                         Col::Branch => {
                             in_branch = true;
                             self.write_branch(cw, line_index, line, selected, staged)?
@@ -555,10 +892,6 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
                         }
 
                         Col::Permission => {
-                            #[cfg(any(target_family = "windows", target_os = "android"))]
-                            { 0 }
-
-                            #[cfg(not(any(target_family = "windows", target_os = "android")))]
                             perm_writer.write_permissions(cw, line, selected)?
                         }
 
@@ -571,11 +904,17 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
                         }
 
                         Col::Size => {
-                            if tree.options.sort.prevent_deep_display() {
+                            let void_len = if tree.options.shows_one_level() {
                                 // as soon as there's only one level displayed we can show the size bars
-                                self.write_line_size_with_bar(cw, line, &label_style, total_size, selected)?
+                                self.write_line_size_with_bar(cw, line, &label_style, total_size, size_width, selected)?
                             } else {
-                                self.write_line_size(cw, line, &label_style, selected)?
+                                self.write_line_size(cw, line, &label_style, size_width, selected)?
+                            };
+                            match &self.size_reference {
+                                Some(reference) => {
+                                    self.write_line_size_delta(cw, line, reference, void_len, selected)?
+                                }
+                                None => void_len,
                             }
                         }
 
@@ -583,6 +922,14 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
                             self.write_line_count(cw, line, count_len, selected)?
                         }
 
+                        Col::Rating => {
+                            self.write_line_rating(cw, line, selected)?
+                        }
+
+                        Col::Tags => {
+                            self.write_line_tags(cw, line, tags_len, selected)?
+                        }
+
                         Col::Staged => {
                             self.write_line_stage_mark(cw, &label_style, staged)?
                         }
@@ -596,34 +943,49 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
                     // void: intercol & replacing missing cells
                     if in_branch && void_len > 2 {
                         cond_bg!(void_style, self, selected, self.skin.tree);
-                        cw.repeat(void_style, &BRANCH_FILLING, void_len)?;
+                        cw.repeat(void_style, self.glyphs.branch_filling(), void_len)?;
                     } else {
                         cond_bg!(void_style, self, selected, self.skin.default);
                         cw.repeat(void_style, &SPACE_FILLING, void_len)?;
                     }
+                    if let Some(spans) = spans.as_mut() {
+                        spans.push(CellSpan {
+                            col: *col,
+                            y: y + self.area.top,
+                            x_start,
+                            x_end: self.area.left + (self.area.width as usize - cw.allowed) as u16,
+                        });
+                    }
                 }
 
-                if cw.allowed > 8 && pattern_object.content {
+                if cw.allowed > 8 && pattern_object.content && !tree.has_match_line(line_index) {
                     let extract = tree.options.pattern.pattern
                         .search_content(&line.path, cw.allowed - 2);
                     if let Some(extract) = extract {
                         self.write_content_extract(cw, extract, selected)?;
                     }
                 }
+                if tree.options.show_scores && line.direct_match && tree.options.pattern.is_some() {
+                    cond_bg!(score_style, self, selected, self.skin.pruning);
+                    cw.queue_g_string(score_style, format!("  score: {}", line.score))?;
+                }
             }
             self.extend_line_bg(cw, selected)?;
-            self.skin.queue_reset(f)?;
+            self.skin.queue_reset(&mut row)?;
             if self.in_app {
                 if let Some((sctop, scbottom)) = scrollbar {
-                    f.queue(cursor::MoveTo(self.area.left + self.area.width - 1, y))?;
-                    let style = if sctop <= y && y <= scbottom {
+                    let y = y + self.area.top;
+                    row.queue(cursor::MoveTo(self.area.left + self.area.width - 1, y))?;
+                    let thumb = sctop <= y && y <= scbottom;
+                    let style = if thumb {
                         &self.skin.scrollbar_thumb
                     } else {
                         &self.skin.scrollbar_track
                     };
-                    style.queue_str(f, "▐")?;
+                    style.queue(&mut row, self.glyphs.scrollbar(thumb))?;
                 }
             }
+            write_row(f, &mut rows, y, row)?;
         }
         if !self.in_app {
             write!(f, "\r\n")?;
@@ -632,3 +994,21 @@ impl<'a, 's, 't> DisplayableTree<'a, 's, 't> {
     }
 }
 
+/// write the row, unless the row cache knows it's already on screen
+fn write_row<W: Write>(
+    f: &mut W,
+    rows: &mut Option<&mut RowCache>,
+    y: u16,
+    row: Vec<u8>,
+) -> std::io::Result<()> {
+    match rows {
+        Some(rows) => rows.write_row(f, y as usize, row),
+        None => f.write_all(&row),
+    }
+}
+
+/// format a change of size, always abbreviated, eg "+1.2G"
+fn format_size_delta(delta: i64, size_format: SizeFormat) -> String {
+    let sign = if delta < 0 { '-' } else { '+' };
+    format!("{}{}", sign, size_format.abbreviate(delta.unsigned_abs()))
+}
@@ -0,0 +1,91 @@
+use {
+    crate::errors::ConfError,
+    std::str::FromStr,
+};
+
+/// a column of the filesystems table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsCol {
+    Fs,
+    Disk,
+    Type,
+    Use,
+    Free,
+    Size,
+    Inodes,
+    MountPoint,
+}
+
+/// the columns of the filesystems table when they're not set
+/// in configuration, some of them being hidden when the panel
+/// is too narrow
+pub static DEFAULT_FS_COLS: &[FsCol] = &[
+    FsCol::Fs,
+    FsCol::Disk,
+    FsCol::Type,
+    FsCol::Use,
+    FsCol::Free,
+    FsCol::Size,
+    FsCol::Inodes,
+    FsCol::MountPoint,
+];
+
+impl FromStr for FsCol {
+    type Err = ConfError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        match s.as_ref() {
+            "fs" | "filesystem" => Ok(Self::Fs),
+            "disk" | "dsk" => Ok(Self::Disk),
+            "type" => Ok(Self::Type),
+            "use" | "usage" | "used" => Ok(Self::Use),
+            "free" | "avail" => Ok(Self::Free),
+            "size" => Ok(Self::Size),
+            "inodes" => Ok(Self::Inodes),
+            "mount_point" | "mount-point" | "mp" => Ok(Self::MountPoint),
+            _ => Err(ConfError::InvalidCols {
+                details: format!("filesystems column not recognized : {}", s),
+            }),
+        }
+    }
+}
+
+/// parse the columns of the filesystems table, which must be
+/// given at most once each
+pub fn parse_fs_cols(arr: &[String]) -> Result<Vec<FsCol>, ConfError> {
+    let mut cols = Vec::new();
+    for s in arr {
+        let col = FsCol::from_str(s)?;
+        if cols.contains(&col) {
+            return Err(ConfError::InvalidCols {
+                details: format!("filesystems column given twice : {}", s),
+            });
+        }
+        cols.push(col);
+    }
+    if cols.is_empty() {
+        return Err(ConfError::InvalidCols {
+            details: "no filesystems column".to_string(),
+        });
+    }
+    Ok(cols)
+}
+
+#[cfg(test)]
+mod fs_col_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_fs_cols() {
+        let arr: Vec<String> = ["fs", "type", "size", "use", "free", "mount_point"]
+            .iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            parse_fs_cols(&arr).unwrap(),
+            vec![FsCol::Fs, FsCol::Type, FsCol::Size, FsCol::Use, FsCol::Free, FsCol::MountPoint],
+        );
+        assert!(parse_fs_cols(&["fs".to_string(), "fs".to_string()]).is_err());
+        assert!(parse_fs_cols(&["dev".to_string()]).is_err());
+        assert!(parse_fs_cols(&[]).is_err());
+    }
+}
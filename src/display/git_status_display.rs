@@ -4,13 +4,14 @@ use {
         display::cond_bg,
         errors::ProgramError,
         git::TreeGitStatus,
-        skin::StyleMap,
+        skin::{Glyphs, StyleMap},
     },
 };
 
 pub struct GitStatusDisplay<'a, 's> {
     status: &'a TreeGitStatus,
     skin: &'s StyleMap,
+    glyphs: &'s Glyphs,
     show_branch: bool,
     show_wide: bool,
     show_stats: bool,
@@ -18,7 +19,12 @@ pub struct GitStatusDisplay<'a, 's> {
 }
 
 impl<'a, 's> GitStatusDisplay<'a, 's> {
-    pub fn from(status: &'a TreeGitStatus, skin: &'s StyleMap, available_width: usize) -> Self {
+    pub fn from(
+        status: &'a TreeGitStatus,
+        skin: &'s StyleMap,
+        glyphs: &'s Glyphs,
+        available_width: usize,
+    ) -> Self {
         let mut show_branch = false;
         let mut width = 0;
         if let Some(branch) = &status.current_branch_name {
@@ -42,6 +48,7 @@ impl<'a, 's> GitStatusDisplay<'a, 's> {
         Self {
             status,
             skin,
+            glyphs,
             show_branch,
             show_wide,
             show_stats,
@@ -61,7 +68,9 @@ impl<'a, 's> GitStatusDisplay<'a, 's> {
             cond_bg!(branch_style, self, selected, self.skin.git_branch);
             if let Some(name) = &self.status.current_branch_name {
                 if self.show_wide {
-                    cw.queue_str(branch_style, " ᚜ ")?;
+                    cw.queue_char(branch_style, ' ')?;
+                    cw.queue_char(branch_style, self.glyphs.git_branch_mark)?;
+                    cw.queue_char(branch_style, ' ')?;
                 } else {
                     cw.queue_char(branch_style, ' ')?;
                 }
@@ -21,43 +21,56 @@ macro_rules! cond_bg {
     };
 }
 
+mod ansi_html;
 mod areas;
 mod cell_size;
 mod col;
+mod date_time_format;
+mod fs_col;
 mod displayable_tree;
 pub mod flags_display;
 mod git_status_display;
 mod luma;
 mod matched_string;
 mod num_format;
+mod row_cache;
 mod screen;
+mod size_format;
+mod term_capabilities;
 pub mod status_line;
+mod title_template;
 
-#[cfg(not(any(target_family="windows",target_os="android")))]
 mod permissions;
 
 pub use {
-    areas::Areas,
+    ansi_html::*,
+    areas::{Areas, PanelWidths},
     col::*,
+    date_time_format::DateTimeFormat,
+    fs_col::*,
     cond_bg,
-    displayable_tree::DisplayableTree,
+    displayable_tree::{CellSpan, DisplayableTree},
     git_status_display::GitStatusDisplay,
     luma::*,
     matched_string::MatchedString,
+    num_format::format_count,
+    row_cache::*,
     screen::Screen,
+    size_format::{SizeFormat, SizeUnits},
+    term_capabilities::TermCapabilities,
     cell_size::*,
+    title_template::*,
 };
 use {
-    once_cell::sync::Lazy,
+    crate::{
+        errors::ProgramError,
+        skin::{asciify, Glyphs},
+    },
+    std::io::Write,
     termimad::*,
 };
 
-#[cfg(not(any(target_family="windows",target_os="android")))]
-pub use {
-    permissions::PermWriter,
-};
-
-pub static BRANCH_FILLING: Lazy<Filling> = Lazy::new(|| { Filling::from_char('─') });
+pub use permissions::PermWriter;
 
 /// if true then the status of a panel covers the whole width
 /// of the terminal (over the other panels)
@@ -70,3 +83,21 @@ pub type W = std::io::BufWriter<std::io::Stderr>;
 pub fn writer() -> W {
     std::io::BufWriter::new(std::io::stderr())
 }
+
+/// write a termimad text view, whose tables and scrollbar are
+/// replaced with ASCII when the glyphs are the ASCII ones
+pub fn write_text_view(
+    w: &mut W,
+    text_view: &TextView,
+    glyphs: &Glyphs,
+) -> Result<(), ProgramError> {
+    if glyphs.ascii {
+        let mut buffer = Vec::new();
+        text_view.write_on(&mut buffer)?;
+        let s = String::from_utf8_lossy(&buffer);
+        w.write_all(asciify(&s).as_bytes())?;
+    } else {
+        text_view.write_on(w)?;
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 
 /// Format a number with commas as thousands separators
-pub fn format_count(count: usize) -> String {
+pub fn format_count<N: ToString>(count: N) -> String {
     let mut s = count.to_string();
     let l = s.len();
     for i in 1..l {
@@ -4,19 +4,35 @@ use {
         display::cond_bg,
         errors::ProgramError,
         permissions,
-        skin::StyleMap,
-        tree::{Tree, TreeLine},
+        skin::{Glyphs, StyleMap},
+        tree::{PermissionParts, Tree, TreeLine},
     },
     std::{
+        fs::Metadata,
         io::Write,
-        os::unix::fs::MetadataExt,
     },
-    umask::*,
 };
 
-/// an object which writes file permissions (mode, owner, group)
+#[cfg(unix)]
+use umask::*;
+
+#[cfg(not(any(target_family = "windows", target_os = "android")))]
+use std::os::unix::fs::MetadataExt;
+
+/// width of the mode part: rwx for user, group and others on unix,
+/// read-only, hidden, system and archive attributes on windows
+#[cfg(unix)]
+const MODE_WIDTH: usize = 9;
+#[cfg(windows)]
+const MODE_WIDTH: usize = 4;
+
+/// an object which writes the parts of the file permissions
+/// (mode or attributes, ACL marker, owner, group) which are
+/// enabled in the tree options
 pub struct PermWriter<'s> {
     pub skin: &'s StyleMap,
+    glyphs: &'s Glyphs,
+    parts: PermissionParts,
     max_user_len: usize,
     max_group_len: usize,
 }
@@ -25,26 +41,60 @@ impl<'s> PermWriter<'s> {
 
     pub fn new(
         skin: &'s StyleMap,
+        glyphs: &'s Glyphs,
+        mut parts: PermissionParts,
         max_user_len: usize,
         max_group_len: usize,
     ) -> Self {
-        Self { skin, max_user_len, max_group_len }
+        if !permissions::supports_ownership() {
+            parts.owner = false;
+            parts.group = false;
+        }
+        if !permissions::supports_acl() {
+            parts.acl = false;
+        }
+        Self { skin, glyphs, parts, max_user_len, max_group_len }
     }
 
     pub fn for_tree(
         skin: &'s StyleMap,
+        glyphs: &'s Glyphs,
         tree: &Tree,
     ) -> Self {
         let (max_user_len, max_group_len) = user_group_max_lengths(tree);
-        Self::new(skin, max_user_len, max_group_len)
+        Self::new(skin, glyphs, tree.options.permission_parts, max_user_len, max_group_len)
     }
 
+    /// the width of the column, including the space after it
+    fn width(&self) -> usize {
+        let mut width = 0;
+        if self.parts.mode || self.parts.acl {
+            if self.parts.mode {
+                width += MODE_WIDTH;
+            }
+            if self.parts.acl {
+                width += 1;
+            }
+            width += 1;
+        }
+        if self.parts.owner {
+            width += self.max_user_len + 1;
+        }
+        if self.parts.group {
+            width += self.max_group_len + 1;
+        }
+        width
+    }
+
+    #[cfg(unix)]
     fn write_mode<'w, W: Write>(
         &self,
         cw: &mut CropWriter<'w, W>,
-        mode: Mode,
+        metadata: &Metadata,
         selected: bool,
     ) -> Result<(), termimad::Error> {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = Mode::from(metadata.permissions().mode());
         cond_bg!(n_style, self, selected, self.skin.perm__);
         cond_bg!(r_style, self, selected, self.skin.perm_r);
         cond_bg!(w_style, self, selected, self.skin.perm_w);
@@ -101,46 +151,135 @@ impl<'s> PermWriter<'s> {
         Ok(())
     }
 
-    #[cfg(not(any(target_family = "windows", target_os = "android")))]
+    /// write the read-only, hidden, system and archive attributes
+    #[cfg(windows)]
+    fn write_mode<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        metadata: &Metadata,
+        selected: bool,
+    ) -> Result<(), termimad::Error> {
+        use std::os::windows::fs::MetadataExt;
+        const READONLY: u32 = 0x1;
+        const HIDDEN: u32 = 0x2;
+        const SYSTEM: u32 = 0x4;
+        const ARCHIVE: u32 = 0x20;
+        let attributes = metadata.file_attributes();
+        cond_bg!(n_style, self, selected, self.skin.perm__);
+        cond_bg!(r_style, self, selected, self.skin.perm_r);
+        cond_bg!(w_style, self, selected, self.skin.perm_w);
+        cond_bg!(x_style, self, selected, self.skin.perm_x);
+        for (flag, c, style) in [
+            (READONLY, 'r', r_style),
+            (HIDDEN, 'h', x_style),
+            (SYSTEM, 's', w_style),
+            (ARCHIVE, 'a', x_style),
+        ] {
+            if attributes & flag != 0 {
+                cw.queue_char(style, c)?;
+            } else {
+                cw.queue_char(n_style, '_')?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn write_permissions<'w, W: Write>(
         &self,
         cw: &mut CropWriter<'w, W>,
         line: &TreeLine,
         selected: bool,
     ) -> Result<usize, ProgramError> {
-        Ok(if line.is_selectable() {
-            self.write_mode(cw, line.mode(), selected)?;
-            let owner = permissions::user_name(line.metadata.uid());
+        let width = self.width();
+        if !line.is_selectable() || width == 0 {
+            return Ok(width);
+        }
+        let metadata = match &line.metadata {
+            Some(metadata) => metadata,
+            None => {
+                // the metadata are late
+                cond_bg!(n_style, self, selected, self.skin.perm__);
+                cw.queue_char(n_style, self.glyphs.ellipsis)?;
+                return Ok(width - 1);
+            }
+        };
+        if self.parts.mode {
+            self.write_mode(cw, metadata, selected)?;
+        }
+        if self.parts.acl {
+            if permissions::has_acl(&line.path) {
+                cond_bg!(acl_style, self, selected, self.skin.perm_w);
+                cw.queue_char(acl_style, '+')?;
+            } else {
+                cond_bg!(n_style, self, selected, self.skin.perm__);
+                cw.queue_char(n_style, ' ')?;
+            }
+        }
+        let void_len = if self.parts.mode || self.parts.acl { 1 } else { 0 };
+        self.write_ownership(cw, metadata, void_len, selected)
+    }
+
+    /// write the owner and group, if enabled, and return the length
+    /// of the void after them
+    #[cfg(not(any(target_family = "windows", target_os = "android")))]
+    fn write_ownership<'w, W: Write>(
+        &self,
+        cw: &mut CropWriter<'w, W>,
+        metadata: &Metadata,
+        mut void_len: usize,
+        selected: bool,
+    ) -> Result<usize, ProgramError> {
+        if self.parts.owner {
+            let owner = permissions::user_name(metadata.uid());
             cond_bg!(owner_style, self, selected, self.skin.owner);
             cw.queue_g_string(
                 owner_style,
-                format!(" {:w$}", &owner, w = self.max_user_len),
+                format!("{:v$}{:w$}", "", &owner, v = void_len, w = self.max_user_len),
             )?;
-            let group = permissions::group_name(line.metadata.gid());
+            void_len = 1;
+        }
+        if self.parts.group {
+            let group = permissions::group_name(metadata.gid());
             cond_bg!(group_style, self, selected, self.skin.group);
             cw.queue_g_string(
                 group_style,
-                format!(" {:w$}", &group, w = self.max_group_len),
+                format!("{:v$}{:w$}", "", &group, v = void_len, w = self.max_group_len),
             )?;
-            1
-        } else {
-            9 + 1 + self.max_user_len + 1 + self.max_group_len + 1
-        })
+            void_len = 1;
+        }
+        Ok(void_len)
+    }
+
+    #[cfg(any(target_family = "windows", target_os = "android"))]
+    fn write_ownership<'w, W: Write>(
+        &self,
+        _cw: &mut CropWriter<'w, W>,
+        _metadata: &Metadata,
+        void_len: usize,
+        _selected: bool,
+    ) -> Result<usize, ProgramError> {
+        Ok(void_len)
     }
 
 }
 
+#[cfg(not(any(target_family = "windows", target_os = "android")))]
 fn user_group_max_lengths(tree: &Tree) -> (usize, usize) {
     let mut max_user_len = 0;
     let mut max_group_len = 0;
-    if tree.options.show_permissions {
-        for i in 1..tree.lines.len() {
-            let line = &tree.lines[i];
-            let user = permissions::user_name(line.metadata.uid());
+    let parts = tree.options.permission_parts;
+    if tree.options.shows_permissions() && (parts.owner || parts.group) {
+        for metadata in tree.lines[1..].iter().filter_map(|line| line.metadata.as_ref()) {
+            let user = permissions::user_name(metadata.uid());
             max_user_len = max_user_len.max(user.len());
-            let group = permissions::group_name(line.metadata.gid());
+            let group = permissions::group_name(metadata.gid());
             max_group_len = max_group_len.max(group.len());
         }
     }
     (max_user_len, max_group_len)
 }
+
+#[cfg(any(target_family = "windows", target_os = "android"))]
+fn user_group_max_lengths(_tree: &Tree) -> (usize, usize) {
+    (0, 0)
+}
@@ -0,0 +1,68 @@
+use {
+    std::{
+        io::{self, Write},
+        sync::atomic::{AtomicUsize, Ordering},
+    },
+    termimad::Area,
+};
+
+/// incremented every time something may have been written over
+/// the rows known by the row caches
+static EPOCH: AtomicUsize = AtomicUsize::new(0);
+
+/// declare that the screen may have been overwritten (eg by an
+/// overlay or an external program), so that no row can be
+/// considered as already there
+pub fn invalidate_row_caches() {
+    EPOCH.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The bytes of the rows written at the last drawing of a panel
+/// state, so that the rows which didn't change since aren't written
+/// again, which avoids flickering and saves bandwidth on slow
+/// terminals (eg in tmux or over SSH).
+///
+/// All rows are written when the cache isn't valid: the area changed,
+/// the state wasn't drawn at the previous drawing, or the screen was
+/// overwritten.
+#[derive(Debug, Default)]
+pub struct RowCache {
+    area: Option<Area>,
+    drawing_count: usize,
+    epoch: usize,
+    rows: Vec<Vec<u8>>,
+}
+
+impl RowCache {
+    /// prepare the cache for a new drawing of the given area
+    pub fn start(&mut self, area: &Area, drawing_count: usize) {
+        let epoch = EPOCH.load(Ordering::Relaxed);
+        let valid = self.area.as_ref() == Some(area)
+            && self.drawing_count + 1 == drawing_count
+            && self.epoch == epoch;
+        if !valid {
+            self.rows.clear();
+        }
+        self.area = Some(area.clone());
+        self.drawing_count = drawing_count;
+        self.epoch = epoch;
+    }
+    /// write the row (which must start with its positioning) unless
+    /// it's the same as at the previous drawing
+    pub fn write_row<W: Write>(
+        &mut self,
+        w: &mut W,
+        idx: usize,
+        row: Vec<u8>,
+    ) -> io::Result<()> {
+        if self.rows.get(idx) == Some(&row) {
+            return Ok(());
+        }
+        w.write_all(&row)?;
+        if self.rows.len() <= idx {
+            self.rows.resize(idx + 1, Vec::new());
+        }
+        self.rows[idx] = row;
+        Ok(())
+    }
+}
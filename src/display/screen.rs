@@ -10,6 +10,7 @@ use {
         terminal::{Clear, ClearType},
         QueueableCommand,
     },
+    std::io::Write,
     termimad::Area,
 };
 
@@ -18,6 +19,12 @@ use {
 pub struct Screen {
     pub width: u16,
     pub height: u16,
+    /// the terminal row of the first line of the screen, which
+    /// isn't 0 only when broot is displayed inline
+    pub top: u16,
+    /// whether the input and status lines are hidden, giving
+    /// one more row to the panel states
+    pub zen: bool,
 }
 
 impl Screen {
@@ -25,6 +32,8 @@ impl Screen {
         let mut screen = Screen {
             width: 0,
             height: 0,
+            top: 0,
+            zen: false,
         };
         screen.read_size(con)?;
         Ok(screen)
@@ -35,6 +44,31 @@ impl Screen {
         if let Some(h) = con.launch_args.height {
             self.height = h;
         }
+        if let Some(top) = con.inline_top {
+            // the terminal may have been shrunk since we started
+            self.top = top.min(h.saturating_sub(self.height));
+        }
+    }
+    /// make room for an inline interface of the given height below
+    /// the cursor, scrolling the terminal when needed, and return the
+    /// terminal row at which the interface starts
+    pub fn make_inline_room(w: &mut W, height: u16) -> Result<u16, ProgramError> {
+        // we check first we can read the cursor position (we can't
+        // when there's no terminal)
+        cursor::position()?;
+        for _ in 1..height {
+            w.write_all(b"\n")?;
+        }
+        w.flush()?;
+        let (_, row) = cursor::position()?;
+        Ok((row + 1).saturating_sub(height))
+    }
+    /// erase the inline interface and put the cursor at its start,
+    /// so that the shell goes on from there
+    pub fn clear_inline(self, w: &mut W) -> Result<(), ProgramError> {
+        self.goto(w, 0, self.top)?;
+        w.queue(Clear(ClearType::FromCursorDown))?;
+        Ok(())
     }
     pub fn read_size(&mut self, con: &AppContext) -> Result<(), ProgramError> {
         let (w, h) = termimad::terminal_size();
@@ -69,7 +103,7 @@ impl Screen {
         w: &mut W,
         panel_skin: &PanelSkin,
     ) -> Result<(), ProgramError> {
-        self.goto(w, self.width, self.height)?;
+        self.goto(w, self.width, self.top + self.height)?;
         panel_skin.styles.default.queue(w, ' ')?;
         Ok(())
     }
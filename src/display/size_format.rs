@@ -0,0 +1,95 @@
+use {
+    super::format_count,
+    serde::Deserialize,
+};
+
+/// the units of the abbreviated sizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeUnits {
+    /// powers of 1000, written K, M, G, etc.
+    #[default]
+    Si,
+    /// powers of 1024, written Ki, Mi, Gi, etc.
+    Binary,
+}
+
+/// How sizes of files and filesystems are written: either
+/// abbreviated in SI or binary units, or as exact byte counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SizeFormat {
+    pub units: SizeUnits,
+    pub exact: bool,
+}
+
+impl SizeFormat {
+    /// write the size, on `width(size)` chars at most
+    pub fn format(self, size: u64) -> String {
+        if self.exact {
+            format_count(size)
+        } else {
+            self.abbreviate(size)
+        }
+    }
+    /// write the size, abbreviated even in exact mode, on 4 chars
+    /// in SI units and 5 chars in binary ones
+    pub fn abbreviate(self, size: u64) -> String {
+        match self.units {
+            SizeUnits::Si => file_size::fit_4(size),
+            SizeUnits::Binary => fit_binary(size),
+        }
+    }
+    /// the width of the column of sizes, when they're all at most
+    /// the given max size
+    pub fn width(self, max: u64) -> usize {
+        if self.exact {
+            format_count(max).len().max(4)
+        } else {
+            self.abbreviated_width()
+        }
+    }
+    pub fn abbreviated_width(self) -> usize {
+        match self.units {
+            SizeUnits::Si => 4,
+            SizeUnits::Binary => 5,
+        }
+    }
+}
+
+/// write the size in binary units on at most 5 chars, eg "12Ki"
+/// or "1.5Gi"
+fn fit_binary(size: u64) -> String {
+    const PREFIXES: &[char] = &['K', 'M', 'G', 'T', 'P', 'E'];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut idx = 0;
+    while value >= 999.5 && idx + 1 < PREFIXES.len() {
+        value /= 1024.0;
+        idx += 1;
+    }
+    if value < 9.95 {
+        format!("{:.1}{}i", value, PREFIXES[idx])
+    } else {
+        format!("{:.0}{}i", value, PREFIXES[idx])
+    }
+}
+
+#[test]
+fn test_size_format() {
+    let si = SizeFormat::default();
+    assert_eq!(si.format(12_345), "12K");
+    assert_eq!(si.width(u64::MAX), 4);
+    let binary = SizeFormat { units: SizeUnits::Binary, exact: false };
+    assert_eq!(binary.format(1023), "1023");
+    assert_eq!(binary.format(1024), "1.0Ki");
+    assert_eq!(binary.format(12_345), "12Ki");
+    assert_eq!(binary.format(1_023_000), "999Ki");
+    assert_eq!(binary.format(1_023_999), "1.0Mi");
+    assert_eq!(binary.format(3 * 1024 * 1024 * 1024 / 2), "1.5Gi");
+    let exact = SizeFormat { units: SizeUnits::Binary, exact: true };
+    assert_eq!(exact.format(1_234_567), "1,234,567");
+    assert_eq!(exact.width(1_234_567), 9);
+    assert_eq!(exact.abbreviate(1_234_567), "1.2Mi");
+}
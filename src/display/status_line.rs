@@ -3,7 +3,7 @@ use {
     crate::{
         app::Status,
         errors::ProgramError,
-        skin::PanelSkin,
+        skin::{Glyphs, PanelSkin},
     },
     termimad::{
         minimad::{Alignment, Composite},
@@ -18,13 +18,14 @@ pub fn write(
     status: &Status,
     area: &Area,
     panel_skin: &PanelSkin,
+    glyphs: &Glyphs,
     screen: Screen,
 ) -> Result<(), ProgramError> {
     let y = area.top;
     screen.goto(w, area.left, y)?;
     let mut x = area.left;
     if let Some(pending_task) = task {
-        let pending_task = format!(" {}… ", pending_task);
+        let pending_task = format!(" {}{} ", pending_task, glyphs.ellipsis);
         x += pending_task.chars().count() as u16;
         panel_skin.styles.status_job.queue(w, pending_task)?;
     }
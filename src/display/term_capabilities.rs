@@ -0,0 +1,128 @@
+use {
+    crate::{
+        conf::Conf,
+        kitty,
+    },
+    std::env,
+};
+
+/// What the terminal running broot is able to display or to
+/// report, so that the features it doesn't support can be
+/// replaced by simpler ones (eg on the Linux console).
+///
+/// The terminal is probed once, at startup, and every capability
+/// can be forced in the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TermCapabilities {
+    /// 24 bits colors, used for the previews of images
+    pub true_colors: bool,
+    /// Kitty's graphics protocol, for high resolution images
+    pub kitty_graphics: bool,
+    /// whether the chars outside of the most common ones (eg icons)
+    /// are displayed, and with the width the unicode tables give
+    pub wide_chars: bool,
+    /// mouse events reporting
+    pub mouse: bool,
+}
+
+/// whether TERM designates a terminal with the bare minimum: no
+/// 24 bits colors, a font limited to a few hundreds of chars,
+/// and no mouse reporting
+fn is_limited_term(term: &str) -> bool {
+    matches!(term, "linux" | "dumb" | "cons25" | "ansi")
+        || term
+            .strip_prefix("vt")
+            .map_or(false, |model| model.starts_with(|c: char| c.is_ascii_digit())) // eg vt100, not vte
+}
+
+/// try to determine whether the terminal supports true
+/// colors. This doesn't work well, hence the use of an
+/// optional config setting.
+/// Based on https://gist.github.com/XVilka/8346728#true-color-detection
+fn are_true_colors_available() -> bool {
+    if let Ok(colorterm) = env::var("COLORTERM") {
+        debug!("COLORTERM env variable = {:?}", colorterm);
+        if colorterm.contains("truecolor") || colorterm.contains("24bit") {
+            debug!("true colors are available");
+            true
+        } else {
+            false
+        }
+    } else {
+        // this is debatable... I've found some terminals with COLORTERM
+        // unset but supporting true colors. As it's easy to determine
+        // that true colors aren't supported when looking at previewed
+        // images I prefer this value
+        true
+    }
+}
+
+/// whether the locale (the first one set among LC_ALL, LC_CTYPE
+/// and LANG) is an UTF-8 one. When none is set, we assume it is.
+fn is_locale_utf8() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"].iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map_or(true, |locale| {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+}
+
+impl TermCapabilities {
+    /// guess the capabilities of the terminal from the environment
+    pub fn probe() -> Self {
+        let term = env::var("TERM").unwrap_or_default();
+        debug!("TERM env variable = {:?}", term);
+        if is_limited_term(&term) {
+            return Self {
+                true_colors: false,
+                kitty_graphics: false,
+                wide_chars: false,
+                mouse: false,
+            };
+        }
+        Self {
+            true_colors: are_true_colors_available(),
+            kitty_graphics: kitty::is_kitty_graphics_protocol_supported(),
+            wide_chars: is_locale_utf8(),
+            mouse: true,
+        }
+    }
+    /// probe the terminal, then apply the overrides of the configuration
+    pub fn new(config: &Conf) -> Self {
+        let mut caps = Self::probe();
+        if let Some(value) = config.true_colors {
+            caps.true_colors = value;
+        }
+        if let Some(value) = config.kitty_graphics {
+            caps.kitty_graphics = value;
+        }
+        if let Some(value) = config.wide_chars {
+            caps.wide_chars = value;
+        }
+        match (config.capture_mouse, config.disable_mouse_capture) {
+            (Some(b), _) => caps.mouse = b, // the new "capture_mouse" argument takes precedence
+            (_, Some(b)) => caps.mouse = !b,
+            _ => {}
+        }
+        info!("terminal capabilities: {:?}", caps);
+        caps
+    }
+}
+
+#[cfg(test)]
+mod term_capabilities_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_limited_terms() {
+        assert!(is_limited_term("linux"));
+        assert!(is_limited_term("vt100"));
+        assert!(is_limited_term("dumb"));
+        assert!(!is_limited_term("xterm-256color"));
+        assert!(!is_limited_term("xterm-kitty"));
+        assert!(!is_limited_term("vte-256color"));
+    }
+}
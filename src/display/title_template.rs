@@ -0,0 +1,103 @@
+/// a part of a title template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TitlePart {
+    Text(String),
+    /// `{path}`: the path of the root (or of the previewed file)
+    Path,
+    /// `{name}`: the file name of the root (or of the previewed file)
+    Name,
+    /// `{git-branch}`: the name of the current git branch
+    GitBranch,
+    /// `{git}`: the branch and the count of inserted and deleted lines
+    Git,
+    /// `{filter}`: the filtering pattern, as typed
+    Filter,
+    /// `{depth}`: the depth of the deepest displayed line
+    Depth,
+    /// `{disk}`: the filesystem of the root, with its used and free space
+    Disk,
+}
+
+/// a title template, as defined in configuration
+/// (eg `"{name} {git-branch}"`), evaluated at each redraw.
+///
+/// Unknown placeholders are kept as text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitleTemplate {
+    pub parts: Vec<TitlePart>,
+}
+
+impl TitlePart {
+    fn from_placeholder(name: &str) -> Option<Self> {
+        match name {
+            "path" => Some(Self::Path),
+            "name" => Some(Self::Name),
+            "git-branch" => Some(Self::GitBranch),
+            "git" => Some(Self::Git),
+            "filter" => Some(Self::Filter),
+            "depth" => Some(Self::Depth),
+            "disk" => Some(Self::Disk),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for TitleTemplate {
+    fn from(s: &str) -> Self {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut rest = s;
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let part = rest.find('}')
+                .and_then(|end| TitlePart::from_placeholder(&rest[1..end]).map(|p| (p, end)));
+            match part {
+                Some((part, end)) => {
+                    if !text.is_empty() {
+                        parts.push(TitlePart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(part);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            parts.push(TitlePart::Text(text));
+        }
+        Self { parts }
+    }
+}
+
+#[cfg(test)]
+mod title_template_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_title_template_parsing() {
+        assert_eq!(
+            TitleTemplate::from("{name} on {git-branch} {unknown}{depth}").parts,
+            vec![
+                TitlePart::Name,
+                TitlePart::Text(" on ".to_string()),
+                TitlePart::GitBranch,
+                TitlePart::Text(" {unknown}".to_string()),
+                TitlePart::Depth,
+            ],
+        );
+        assert_eq!(
+            TitleTemplate::from("{{path}}").parts,
+            vec![
+                TitlePart::Text("{".to_string()),
+                TitlePart::Path,
+                TitlePart::Text("}".to_string()),
+            ],
+        );
+    }
+}
@@ -18,13 +18,21 @@ custom_error! {pub ProgramError
     UnmatchingVerbArgs {name: String} = "No matching argument found for verb {name:?}",
     TreeBuild {source: TreeBuildError} = "{source}",
     LaunchError {program: String, source: io::Error} = "Unable to launch {program}: {source}",
+    LaunchTimeout {program: String} = "{program} was killed as it exceeded its timeout",
+    PermissionDenied {program: String} = "{program} failed for lack of permission",
     UnknowShell {shell: String} = "Unknown shell: {shell}",
     InternalError {details: String} = "Internal error: {details}", // should not happen
     InvalidGlobError {pattern: String} = "Invalid glob: {pattern}",
     Unrecognized {token: String} = "Unrecognized: {token}",
     NetError {source: NetError} = "{source}",
     ImageError {source: ImageError } = "{source}",
+    InvalidFont {details: String} = "Invalid font: {details}",
     Lfs {details: String} = "Failed to fetch mounts: {details}",
+    MountOperation {command: String, details: String} = "{command} failed: {details}",
+    NoSnapshot {mount_point: String, fs_type: String} = "No snapshot directory found for {mount_point} ({fs_type})",
+    Container {details: String} = "Container error: {details}",
+    Unresumable {command: String} = "`{command}` was cut in the middle and can't be safely resumed: roll it back or discard it",
+    InvalidReplay {path: String, line: usize, details: String} = "Invalid replay step in {path} line {line}: {details}",
     ZeroLenFile = "File seems empty",
     UnmappableFile = "File can't be mapped",
     UnprintableFile = "File can't be printed", // has characters that can't be printed without escaping
@@ -37,6 +45,7 @@ custom_error! {pub TreeBuildError
     FileNotFound { path: String } = "File not found: {path}",
     Interrupted = "Task Interrupted",
     TooManyMatches { max: usize } = "Too many matches (max allowed: {max})",
+    OutsidePinnedRoot { path: String } = "Not allowed: {path} isn't under the directory given with --stay-under",
 }
 
 custom_error! {pub ConfError
@@ -58,8 +67,17 @@ custom_error! {pub ConfError
     InvalidCols {details: String}                   = "invalid cols definition: {details}",
     InvalidSkin {source: InvalidSkinError}          = "invalid skin: {source}",
     InvalidThreadsCount { count: usize }            = "invalid threads count: {count}",
+    InvalidAutoRefreshPeriod { period: f64 }        = "invalid auto refresh period: {period} (must be a positive number of seconds)",
+    InvalidLiveRefreshThrottle { throttle: f64 }    = "invalid live refresh throttle: {throttle} (must be a positive number of seconds)",
+    InvalidUsageAlert { percent: f64 }              = "invalid usage alert: {percent} (must be a percentage, between 0 and 100)",
     InvalidDefaultFlags { flags: String }           = "invalid default flags: {flags:?}",
     InvalidSyntaxTheme { name: String }             = "invalid syntax theme: {name:?}",
+    InvalidGlyph { name: String, details: String }  = "invalid glyph {name:?}: {details}",
+    SyntaxThemesLoading { details: String }         = "failed to load syntax themes: {details}",
+    InvalidClickBinding { details: String }         = "invalid click binding: {details}",
+    MissingTranslation { language: String }         = "no translation file for language {language:?}",
+    InvalidDateTimeZone { zone: String }            = "invalid date time zone: {zone:?} (expected \"local\", \"utc\" or an offset like \"+02:00\")",
+    InvalidEventsFd { fd: i32, details: String }    = "invalid events file descriptor {fd}: {details}",
 }
 
 // error which can be raised when parsing a pattern the user typed
@@ -69,6 +87,7 @@ custom_error! {pub PatternError
         format!("Invalid Regular Expression: {}", source.to_string().lines().last().unwrap_or(""))
     },
     UnknownRegexFlag {bad: char} = "Unknown regular expression flag: {bad:?}",
+    InvalidFilter { filter: String } = "Invalid filter: {filter:?}",
 }
 
 custom_error! {pub InvalidSkinError
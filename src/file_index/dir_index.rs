@@ -0,0 +1,294 @@
+use {
+    super::EntryKind,
+    crate::conf,
+    fnv::{FnvHashMap, FnvHashSet, FnvHasher},
+    once_cell::sync::Lazy,
+    std::{
+        ffi::OsString,
+        fmt::Write as _,
+        fs,
+        hash::{Hash, Hasher},
+        io,
+        path::{Path, PathBuf},
+        sync::{Mutex, RwLock},
+        thread,
+        time::{Duration, Instant, SystemTime},
+    },
+};
+
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+
+/// the listings of the indexed directories, whatever their root
+static DIRS: Lazy<RwLock<FnvHashMap<PathBuf, IndexedDir>>> = Lazy::new(|| {
+    RwLock::new(FnvHashMap::default())
+});
+
+/// the roots being indexed
+static INDEXING: Lazy<Mutex<FnvHashSet<PathBuf>>> = Lazy::new(|| {
+    Mutex::new(FnvHashSet::default())
+});
+
+/// an entry of a directory listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedEntry {
+    pub name: OsString,
+    pub kind: EntryKind,
+}
+
+/// the listing of a directory, valid as long as the
+/// directory's modification date is the same
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedDir {
+    pub mtime: SystemTime,
+    pub entries: Vec<IndexedEntry>,
+}
+
+/// read the entries of a directory on disk
+fn read_entries(dir: &Path) -> io::Result<Vec<IndexedEntry>> {
+    let mut entries = Vec::new();
+    for e in fs::read_dir(dir)?.flatten() {
+        if let Ok(ft) = e.file_type() {
+            entries.push(IndexedEntry {
+                name: e.file_name(),
+                kind: ft.into(),
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// return the entries of the directory, from the index when it's
+/// enabled and the indexed listing is still valid.
+///
+/// A listing found outdated is updated.
+pub fn list_dir(dir: &Path, use_index: bool) -> io::Result<Vec<IndexedEntry>> {
+    if !use_index {
+        return read_entries(dir);
+    }
+    let mtime = fs::metadata(dir)?.modified()?;
+    if let Some(indexed) = DIRS.read().unwrap().get(dir) {
+        if indexed.mtime == mtime {
+            return Ok(indexed.entries.clone());
+        }
+    }
+    let entries = read_entries(dir)?;
+    if let Some(indexed) = DIRS.write().unwrap().get_mut(dir) {
+        indexed.mtime = mtime;
+        indexed.entries = entries.clone();
+    }
+    Ok(entries)
+}
+
+/// the file in which the index of a root is stored
+pub fn index_file(root: &Path) -> PathBuf {
+    let mut hasher = FnvHasher::default();
+    root.hash(&mut hasher);
+    conf::app_dirs()
+        .data_dir()
+        .join("index")
+        .join(format!("{:016x}", hasher.finish()))
+}
+
+/// write the listings in the format of the index files:
+/// a `R<TAB>root` line, then for each directory a
+/// `D<TAB>secs<TAB>nanos<TAB>path` line followed by one
+/// `kind<TAB>name` line per entry.
+///
+/// Directories whose entries can't be written in this format
+/// (non UTF-8 names, or names with a newline) are skipped.
+pub fn serialize_index(root: &Path, dirs: &[(PathBuf, IndexedDir)]) -> String {
+    let mut s = format!("R\t{}\n", root.to_string_lossy());
+    'dirs: for (path, dir) in dirs {
+        let path = match path.to_str() {
+            Some(path) if !path.contains('\n') => path,
+            _ => continue,
+        };
+        let mtime = dir.mtime.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+        let mut lines = format!("D\t{}\t{}\t{}\n", mtime.as_secs(), mtime.subsec_nanos(), path);
+        for entry in &dir.entries {
+            match entry.name.to_str() {
+                Some(name) if !name.contains('\n') => {
+                    let _ = writeln!(lines, "{}\t{}", entry.kind.code(), name);
+                }
+                _ => continue 'dirs,
+            }
+        }
+        s.push_str(&lines);
+    }
+    s
+}
+
+/// read the content of an index file, returning nothing if it's
+/// not the index of the given root. Invalid lines are ignored.
+pub fn parse_index(root: &Path, content: &str) -> Vec<(PathBuf, IndexedDir)> {
+    let mut lines = content.lines();
+    match lines.next().and_then(|line| line.strip_prefix("R\t")) {
+        Some(r) if Path::new(r) == root => {}
+        _ => {
+            return Vec::new();
+        }
+    }
+    let mut dirs: Vec<(PathBuf, IndexedDir)> = Vec::new();
+    for line in lines {
+        if let Some(dir_line) = line.strip_prefix("D\t") {
+            let mut parts = dir_line.splitn(3, '\t');
+            let secs = parts.next().and_then(|p| p.parse().ok());
+            let nanos = parts.next().and_then(|p| p.parse().ok());
+            if let (Some(secs), Some(nanos), Some(path)) = (secs, nanos, parts.next()) {
+                dirs.push((
+                    PathBuf::from(path),
+                    IndexedDir {
+                        mtime: SystemTime::UNIX_EPOCH + Duration::new(secs, nanos),
+                        entries: Vec::new(),
+                    },
+                ));
+            }
+        } else if let Some((code, name)) = line.split_once('\t') {
+            let kind = code.chars().next().and_then(EntryKind::from_code);
+            if let (Some(kind), Some((_, dir))) = (kind, dirs.last_mut()) {
+                dir.entries.push(IndexedEntry {
+                    name: name.into(),
+                    kind,
+                });
+            }
+        }
+    }
+    dirs
+}
+
+/// load the stored index of the root, without replacing the
+/// listings already in memory
+fn load(root: &Path) {
+    let content = match fs::read_to_string(index_file(root)) {
+        Ok(content) => content,
+        Err(_) => {
+            return;
+        }
+    };
+    let mut map = DIRS.write().unwrap();
+    for (path, dir) in parse_index(root, &content) {
+        map.entry(path).or_insert(dir);
+    }
+}
+
+/// the device of a directory, to avoid indexing other filesystems
+#[cfg(unix)]
+fn device(md: &fs::Metadata) -> u64 {
+    md.dev()
+}
+#[cfg(not(unix))]
+fn device(_md: &fs::Metadata) -> u64 {
+    0
+}
+
+/// list all the directories under the root, on the same filesystem,
+/// reusing the valid listings of the index, then store the index.
+fn index(root: &Path) -> io::Result<usize> {
+    let root_device = device(&fs::metadata(root)?);
+    let mut indexed: Vec<(PathBuf, IndexedDir)> = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let md = match fs::metadata(&dir) {
+            Ok(md) if md.is_dir() && device(&md) == root_device => md,
+            _ => continue,
+        };
+        let mtime = match md.modified() {
+            Ok(mtime) => mtime,
+            Err(_) => continue,
+        };
+        let valid = DIRS.read().unwrap()
+            .get(&dir)
+            .filter(|indexed| indexed.mtime == mtime)
+            .cloned();
+        let indexed_dir = match valid {
+            Some(indexed_dir) => indexed_dir,
+            None => match read_entries(&dir) {
+                Ok(entries) => {
+                    let indexed_dir = IndexedDir { mtime, entries };
+                    DIRS.write().unwrap().insert(dir.clone(), indexed_dir.clone());
+                    indexed_dir
+                }
+                Err(e) => {
+                    debug!("can't index {:?}: {}", &dir, e);
+                    continue;
+                }
+            },
+        };
+        for entry in &indexed_dir.entries {
+            if entry.kind.is_dir() {
+                dirs.push(dir.join(&entry.name));
+            }
+        }
+        indexed.push((dir, indexed_dir));
+    }
+    let file = index_file(root);
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(file, serialize_index(root, &indexed))?;
+    Ok(indexed.len())
+}
+
+/// load the stored index of the root then update it, in a
+/// background thread.
+///
+/// Return false if the root is already being indexed.
+pub fn index_in_background(root: PathBuf) -> bool {
+    if !INDEXING.lock().unwrap().insert(root.clone()) {
+        return false;
+    }
+    thread::spawn(move || {
+        let start = Instant::now();
+        load(&root);
+        match index(&root) {
+            Ok(count) => {
+                info!("indexed {} directories of {:?} in {:?}", count, &root, start.elapsed());
+            }
+            Err(e) => {
+                warn!("error while indexing {:?}: {}", &root, e);
+            }
+        }
+        INDEXING.lock().unwrap().remove(&root);
+    });
+    true
+}
+
+/// tell whether some root is being indexed in background
+pub fn is_indexing() -> bool {
+    !INDEXING.lock().unwrap().is_empty()
+}
+
+#[cfg(test)]
+mod dir_index_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_index_serialization() {
+        let root = Path::new("/home/dys/dev");
+        let dirs = vec![
+            (
+                root.to_path_buf(),
+                IndexedDir {
+                    mtime: SystemTime::UNIX_EPOCH + Duration::new(1_650_000_000, 123),
+                    entries: vec![
+                        IndexedEntry { name: "broot".into(), kind: EntryKind::Dir },
+                        IndexedEntry { name: "notes\twith tab.md".into(), kind: EntryKind::File },
+                        IndexedEntry { name: "latest".into(), kind: EntryKind::Link },
+                    ],
+                },
+            ),
+            (
+                root.join("broot"),
+                IndexedDir {
+                    mtime: SystemTime::UNIX_EPOCH + Duration::new(1_650_000_001, 0),
+                    entries: Vec::new(),
+                },
+            ),
+        ];
+        let content = serialize_index(root, &dirs);
+        assert_eq!(parse_index(root, &content), dirs);
+        assert!(parse_index(Path::new("/home/dys"), &content).is_empty());
+    }
+}
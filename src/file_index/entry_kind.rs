@@ -0,0 +1,55 @@
+use std::fs;
+
+/// the type of a directory entry, as given by the listing of
+/// its directory (links aren't followed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Dir,
+    File,
+    Link,
+    Other,
+}
+
+impl From<fs::FileType> for EntryKind {
+    fn from(ft: fs::FileType) -> Self {
+        if ft.is_dir() {
+            Self::Dir
+        } else if ft.is_file() {
+            Self::File
+        } else if ft.is_symlink() {
+            Self::Link
+        } else {
+            Self::Other
+        }
+    }
+}
+
+impl EntryKind {
+    pub fn is_dir(self) -> bool {
+        self == Self::Dir
+    }
+    pub fn is_file(self) -> bool {
+        self == Self::File
+    }
+    pub fn is_symlink(self) -> bool {
+        self == Self::Link
+    }
+    /// the char used in the index files
+    pub fn code(self) -> char {
+        match self {
+            Self::Dir => 'd',
+            Self::File => 'f',
+            Self::Link => 'l',
+            Self::Other => 'o',
+        }
+    }
+    pub fn from_code(c: char) -> Option<Self> {
+        match c {
+            'd' => Some(Self::Dir),
+            'f' => Some(Self::File),
+            'l' => Some(Self::Link),
+            'o' => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! An optional index of the directory listings under a root, built in
+//! background and persisted, so that searches in big trees don't have to
+//! wait for the listing of all directories.
+//!
+//! A listing is only used while the modification date of its directory
+//! is the one it was indexed with.
+
+mod dir_index;
+mod entry_kind;
+
+pub use {
+    dir_index::*,
+    entry_kind::*,
+};
@@ -2,8 +2,11 @@
 /// A cache is used to avoid recomputing the same directories again and again.
 /// On unix, hard links are checked to avoid counting twice an inode.
 
+mod size_snapshot;
 mod sum_computation;
 
+pub use size_snapshot::{record_sizes, size_reference, SizeSnapshot};
+
 use {
     crate::{
         app::*,
@@ -82,6 +85,11 @@ impl FileSum {
         }
     }
 
+    /// Return the sum of the directory if it's already in cache
+    pub fn from_cache(path: &Path) -> Option<Self> {
+        SUM_CACHE.lock().unwrap().get(path).copied()
+    }
+
     pub fn part_of_size(self, total: Self) -> f32 {
         if total.real_size == 0 {
             0.0
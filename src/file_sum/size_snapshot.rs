@@ -0,0 +1,131 @@
+use {
+    crate::conf,
+    ahash::AHashMap,
+    fnv::FnvHasher,
+    once_cell::sync::Lazy,
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::BTreeMap,
+        fs,
+        hash::Hasher,
+        io,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+        time::SystemTime,
+    },
+};
+
+/// the snapshots found on disk at the first visit of a root during
+/// the session, which are the references of the displayed deltas
+static REFERENCES: Lazy<Mutex<AHashMap<PathBuf, Option<Arc<SizeSnapshot>>>>> = Lazy::new(|| {
+    Mutex::new(AHashMap::default())
+});
+
+/// The sizes of the paths of a tree, as computed at some time.
+///
+/// There's one snapshot per root, stored in the size-snapshots
+/// directory of broot's data directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SizeSnapshot {
+    pub root: PathBuf,
+    /// seconds since epoch
+    pub time: u64,
+    pub sizes: BTreeMap<PathBuf, u64>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+impl SizeSnapshot {
+    /// the file where the snapshot of a root is stored
+    pub fn file(root: &Path) -> PathBuf {
+        let mut hasher = FnvHasher::default();
+        hasher.write(root.to_string_lossy().as_bytes());
+        conf::app_dirs()
+            .data_dir()
+            .join("size-snapshots")
+            .join(format!("{:016x}.json", hasher.finish()))
+    }
+    fn load(root: &Path) -> Option<Self> {
+        let content = fs::read_to_string(Self::file(root)).ok()?;
+        serde_json::from_str::<Self>(&content)
+            .ok()
+            .filter(|snapshot| snapshot.root == root) // hash collision
+    }
+    fn save(&self) -> io::Result<()> {
+        let file = Self::file(&self.root);
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, serde_json::to_string(self)?)
+    }
+    /// the change of size of the path since the snapshot
+    pub fn delta(&self, path: &Path, size: u64) -> Option<i64> {
+        self.sizes.get(path).map(|&old| size as i64 - old as i64)
+    }
+    /// a description of the age of the snapshot, eg "3 days ago"
+    pub fn age(&self) -> String {
+        let days = now().saturating_sub(self.time) / (24 * 60 * 60);
+        match days {
+            0 => "today".to_string(),
+            1 => "yesterday".to_string(),
+            2..=13 => format!("{} days ago", days),
+            14..=60 => format!("{} weeks ago", days / 7),
+            _ => format!("{} months ago", days / 30),
+        }
+    }
+}
+
+/// return the snapshot of the root as it was at the first
+/// visit of the root during this session, if there was one
+pub fn size_reference(root: &Path) -> Option<Arc<SizeSnapshot>> {
+    let mut references = REFERENCES.lock().unwrap();
+    references
+        .entry(root.to_path_buf())
+        .or_insert_with(|| SizeSnapshot::load(root).map(Arc::new))
+        .clone()
+}
+
+/// record the sizes of some paths under the root, replacing the
+/// ones previously recorded for the same paths
+pub fn record_sizes<I>(root: &Path, sizes: I)
+where
+    I: IntoIterator<Item = (PathBuf, u64)>,
+{
+    // the reference must be read before being overwritten
+    let reference = size_reference(root);
+    let mut snapshot = SizeSnapshot::load(root)
+        .or_else(|| reference.map(|r| (*r).clone()))
+        .unwrap_or_else(|| SizeSnapshot {
+            root: root.to_path_buf(),
+            ..SizeSnapshot::default()
+        });
+    snapshot.sizes.extend(sizes);
+    snapshot.time = now();
+    if let Err(e) = snapshot.save() {
+        warn!("failed to save the size snapshot of {:?}: {}", root, e);
+    }
+}
+
+#[cfg(test)]
+mod size_snapshot_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_size_snapshot_delta() {
+        let mut snapshot = SizeSnapshot {
+            root: PathBuf::from("/data"),
+            time: now() - 3 * 24 * 60 * 60,
+            sizes: BTreeMap::new(),
+        };
+        snapshot.sizes.insert(PathBuf::from("/data/logs"), 1_000);
+        assert_eq!(snapshot.delta(Path::new("/data/logs"), 1_500), Some(500));
+        assert_eq!(snapshot.delta(Path::new("/data/logs"), 200), Some(-800));
+        assert_eq!(snapshot.delta(Path::new("/data/new"), 200), None);
+        assert_eq!(snapshot.age(), "3 days ago");
+    }
+}
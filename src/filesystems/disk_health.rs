@@ -0,0 +1,126 @@
+use {
+    crossbeam::channel::{bounded, Receiver, RecvTimeoutError},
+    fnv::{FnvHashMap, FnvHashSet},
+    lfs_core::{Mount, MountId},
+    std::{
+        fs,
+        process::Command,
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// the time after which we stop waiting for the health checks
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// the max time a call to `receive` waits
+const WAIT_PERIOD: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskHealth {
+    Good,
+    Failing,
+}
+
+/// read the health of the disk in /sys: a degraded RAID array, or
+/// a device the kernel doesn't consider running, is failing
+fn sysfs_health(disk_name: &str) -> Option<DiskHealth> {
+    if let Ok(degraded) = fs::read_to_string(format!("/sys/block/{}/md/degraded", disk_name)) {
+        return Some(if degraded.trim() == "0" { DiskHealth::Good } else { DiskHealth::Failing });
+    }
+    let state = fs::read_to_string(format!("/sys/block/{}/device/state", disk_name)).ok()?;
+    Some(match state.trim() {
+        "running" | "live" => DiskHealth::Good,
+        _ => DiskHealth::Failing,
+    })
+}
+
+/// ask smartctl (from smartmontools) for the health of the disk,
+/// which usually works only for root
+fn smartctl_health(disk_name: &str) -> Option<DiskHealth> {
+    let output = Command::new("smartctl")
+        .args(["-H", "-j", &format!("/dev/{}", disk_name)])
+        .output()
+        .ok()?;
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let passed = json.get("smart_status")?.get("passed")?.as_bool()?;
+    Some(if passed { DiskHealth::Good } else { DiskHealth::Failing })
+}
+
+/// the health of the disk, as far as it can be known
+pub fn disk_health(disk_name: &str) -> Option<DiskHealth> {
+    match sysfs_health(disk_name) {
+        Some(DiskHealth::Failing) => Some(DiskHealth::Failing),
+        health => smartctl_health(disk_name).or(health),
+    }
+}
+
+/// The checking, in a background thread, of the health of
+/// the disks, as smartctl may be slow
+pub struct HealthLoader {
+    receiver: Receiver<FnvHashMap<String, DiskHealth>>,
+    start: Instant,
+}
+
+impl HealthLoader {
+    /// start checking the disks of the mounts whose health isn't
+    /// already known, return None if there's none
+    pub fn start(
+        mounts: &[Mount],
+        known: &FnvHashMap<String, DiskHealth>,
+    ) -> Option<Self> {
+        let names: FnvHashSet<String> = mounts.iter()
+            .filter_map(|m| m.disk.as_ref())
+            .filter(|disk| !disk.ram && !known.contains_key(&disk.name))
+            .map(|disk| disk.name.clone())
+            .collect();
+        if names.is_empty() {
+            return None;
+        }
+        let (sender, receiver) = bounded(1);
+        thread::spawn(move || {
+            let healths = names.into_iter()
+                .filter_map(|name| disk_health(&name).map(|health| (name, health)))
+                .collect();
+            let _ = sender.send(healths);
+        });
+        Some(Self { receiver, start: Instant::now() })
+    }
+    /// wait a little for the result of the checks, return
+    /// None while they're running. The map is empty when
+    /// the checks failed or took too long.
+    pub fn receive(&self) -> Option<FnvHashMap<String, DiskHealth>> {
+        if self.start.elapsed() > HEALTH_TIMEOUT {
+            warn!("disks health checks timed out");
+            return Some(FnvHashMap::default());
+        }
+        match self.receiver.recv_timeout(WAIT_PERIOD) {
+            Ok(healths) => Some(healths),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => Some(FnvHashMap::default()),
+        }
+    }
+}
+
+/// return the ids of the mounts which are read-only, read
+/// in the mount options of /proc/self/mountinfo
+pub fn read_only_mounts() -> FnvHashSet<MountId> {
+    let content = match fs::read_to_string("/proc/self/mountinfo") {
+        Ok(content) => content,
+        Err(_) => {
+            return FnvHashSet::default();
+        }
+    };
+    content.lines()
+        .filter_map(|line| {
+            let mut tokens = line.split_whitespace();
+            let id = tokens.next()?.parse::<MountId>().ok()?;
+            let options = tokens.nth(4)?;
+            if options.split(',').any(|o| o == "ro") {
+                Some(id)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
@@ -6,9 +6,12 @@ use {
         command::*,
         display::*,
         errors::ProgramError,
+        launchable::Launchable,
         pattern::*,
+        skin::Glyphs,
         task_sync::Dam,
-        tree::TreeOptions,
+        tr,
+        tree::{Sort, TreeOptions},
         verb::*,
     },
     crokey::crossterm::{
@@ -16,12 +19,14 @@ use {
         style::Color,
         QueueableCommand,
     },
-    lfs_core::Mount,
+    fnv::{FnvHashMap, FnvHashSet},
+    lfs_core::{Mount, MountId, Stats},
     std::{
         convert::TryInto,
         fs,
         os::unix::fs::MetadataExt,
         path::Path,
+        time::{Duration, Instant},
     },
     strict::NonEmptyVec,
     termimad::{
@@ -30,15 +35,132 @@ use {
     },
 };
 
+/// load the list of mounted filesystems, either all the
+/// ones with stats (or whose stats are to be loaded in background)
+/// or only the ones on disks, followed by the removable devices
+/// which aren't mounted
+pub(super) fn load_mounts(show_only_disks: bool) -> Result<NonEmptyVec<Mount>, ProgramError> {
+    let mut mount_list = MOUNTS.lock().unwrap();
+    let all_mounts = mount_list.load()?;
+    let mut mounts = all_mounts
+        .iter()
+        .filter(|mount| {
+            if show_only_disks {
+                mount.disk.is_some()
+            } else {
+                mount.stats().is_some() || has_deferred_stats(mount)
+            }
+        })
+        .cloned()
+        .collect::<Vec<Mount>>();
+    mounts.append(&mut unmounted_removable_devices(all_mounts));
+    mounts.try_into().map_err(|_| ProgramError::Lfs {
+        details: "no disk in lfs-core list".to_string(),
+    })
+}
+
+/// tell whether the column, when not reversed, is sorted in
+/// decreasing order (sizes and counts) rather than increasing
+/// order (names)
+fn sorts_decreasing(col: FsCol) -> bool {
+    matches!(col, FsCol::Use | FsCol::Free | FsCol::Size | FsCol::Inodes)
+}
+
+/// sort the mounts on a column: names are in alphabetical
+/// order, sizes and counts are decreasing. Without sort column,
+/// the mounts are in the order of the mount list (by size)
+fn sort_mounts(mounts: &mut [Mount], sort: Option<(FsCol, bool)>) {
+    let size = |m: &Mount, f: fn(&Stats) -> u64| m.stats().map_or(0, f);
+    let (col, reverse) = match sort {
+        Some(sort) => sort,
+        None => {
+            mounts.sort_by_key(|m| u64::MAX - size(m, Stats::size));
+            return;
+        }
+    };
+    mounts.sort_by(|a, b| {
+        let ord = match col {
+            FsCol::Fs => a.info.fs.cmp(&b.info.fs),
+            FsCol::Disk => {
+                let disk_type = |m: &Mount| m.disk.as_ref().map(|d| d.disk_type());
+                disk_type(a).cmp(&disk_type(b))
+            }
+            FsCol::Type => a.info.fs_type.cmp(&b.info.fs_type),
+            FsCol::Use => {
+                let used = |m: &Mount| m.stats().map_or(0, |s| volume_used(m, s));
+                used(b).cmp(&used(a))
+            }
+            FsCol::Free => size(b, Stats::available).cmp(&size(a, Stats::available)),
+            FsCol::Size => size(b, Stats::size).cmp(&size(a, Stats::size)),
+            FsCol::Inodes => {
+                let inodes = |m: &Mount| m.inodes().map_or(0, |i| i.used());
+                inodes(b).cmp(&inodes(a))
+            }
+            FsCol::MountPoint => a.info.mount_point.cmp(&b.info.mount_point),
+        };
+        if reverse { ord.reverse() } else { ord }
+    });
+}
+
+/// the space of several filesystems
+#[derive(Debug, Default)]
+struct SpaceTotals {
+    size: u64,
+    used: u64,
+    available: u64,
+}
+
+impl SpaceTotals {
+    fn use_share(&self) -> f64 {
+        if self.size == 0 {
+            0.0
+        } else {
+            self.used as f64 / self.size as f64
+        }
+    }
+}
+
 struct FilteredContent {
-    pattern: Pattern,
+    filter: MountFilter,
     mounts: Vec<Mount>, // may be empty
     selection_idx: usize,
 }
 
 /// an application state showing the currently mounted filesystems
 pub struct FilesystemState {
+    /// the displayed mounts, in which the volumes of a device are grouped
     mounts: NonEmptyVec<Mount>,
+    /// the places of the mounts in their groups
+    group_positions: Vec<GroupPos>,
+    /// the volumes hidden in their collapsed groups
+    hidden_mounts: Vec<Mount>,
+    /// the keys of the collapsed groups of volumes
+    collapsed_groups: FnvHashSet<String>,
+    show_only_disks: bool,
+    /// the mount or unmount operation, and the device, waiting
+    /// for a confirmation
+    pending_operation: Option<(Internal, String)>,
+    /// the period at which the mounts are checked for changes
+    refresh_period: Duration,
+    last_check: Instant,
+    /// signature of the mounts at the last (re)load
+    mounts_signature: Option<u64>,
+    /// the background loading of the stats of remote filesystems
+    stats_loader: Option<StatsLoader>,
+    /// whether a footer shows the totals of the listed mounts
+    show_totals: bool,
+    /// the ids of the read-only mounts
+    read_only_mounts: FnvHashSet<MountId>,
+    /// the health of the disks, by name, when known
+    disks_health: FnvHashMap<String, DiskHealth>,
+    health_loader: Option<HealthLoader>,
+    /// the column the mounts are sorted on, and whether the order
+    /// is reversed, when not in the default order (by size)
+    sort: Option<(FsCol, bool)>,
+    /// the columns hidden with a middle click on their title
+    hidden_cols: Vec<FsCol>,
+    /// the columns displayed at the last display, with their left x
+    displayed_cols: Vec<(FsCol, u16)>,
     selection_idx: usize,
     scroll: usize,
     page_height: usize,
@@ -57,28 +179,12 @@ impl FilesystemState {
         tree_options: TreeOptions,
         con: &AppContext,
     ) -> Result<FilesystemState, ProgramError> {
-        let mut mount_list = MOUNTS.lock().unwrap();
-        let show_only_disks = false;
-        let mounts = mount_list
-            .load()?
-            .iter()
-            .filter(|mount| {
-                if show_only_disks {
-                    mount.disk.is_some()
-                } else {
-                    mount.stats().is_some()
-                }
-            })
-            .cloned()
-            .collect::<Vec<Mount>>();
-        let mounts: NonEmptyVec<Mount> = match mounts.try_into() {
-            Ok(nev) => nev,
-            _ => {
-                return Err(ProgramError::Lfs {
-                    details: "no disk in lfs-core list".to_string(),
-                });
-            }
-        };
+        let show_only_disks = con.filesystems_only_disks;
+        let mounts_signature = mounts_signature();
+        let mounts = load_mounts(show_only_disks)?;
+        let stats_loader = StatsLoader::start(&mounts);
+        let disks_health = FnvHashMap::default();
+        let health_loader = HealthLoader::start(&mounts, &disks_health);
         let selection_idx = path
             .and_then(|path| fs::metadata(path).ok())
             .and_then(|md| {
@@ -86,15 +192,33 @@ impl FilesystemState {
                 mounts.iter().position(|m| m.info.dev == device_id)
             })
             .unwrap_or(0);
-        Ok(FilesystemState {
+        let mut state = FilesystemState {
             mounts,
+            group_positions: Vec::new(),
+            hidden_mounts: Vec::new(),
+            collapsed_groups: FnvHashSet::default(),
+            show_only_disks,
+            pending_operation: None,
+            refresh_period: con.filesystems_refresh_period,
+            last_check: Instant::now(),
+            mounts_signature,
+            stats_loader,
+            show_totals: con.filesystems_totals,
+            read_only_mounts: read_only_mounts(),
+            disks_health,
+            health_loader,
+            sort: None,
+            hidden_cols: Vec::new(),
+            displayed_cols: Vec::new(),
             selection_idx,
             scroll: 0,
             page_height: 0,
             tree_options,
             filtered: None,
             mode: initial_mode(con),
-        })
+        };
+        state.regroup();
+        Ok(state)
     }
     pub fn count(&self) -> usize {
         self.filtered
@@ -139,8 +263,251 @@ impl FilesystemState {
         CmdResult::Keep
     }
 
+    fn selected_mount(&self) -> &Mount {
+        self.filtered
+            .as_ref()
+            .and_then(|f| f.mounts.get(f.selection_idx))
+            .unwrap_or(&self.mounts[self.selection_idx])
+    }
+
+    /// load again the list of filesystems, keeping the selection,
+    /// the filtering and the scroll when possible
+    fn reload(&mut self, show_only_disks: bool) -> Result<(), ProgramError> {
+        self.mounts_signature = mounts_signature();
+        let mounts = load_mounts(show_only_disks)?;
+        let selected = self.selected_mount();
+        self.selection_idx = mounts.iter()
+            .position(|m| m.info.id == selected.info.id)
+            .or_else(|| mounts.iter().position(|m| m.info.fs == selected.info.fs))
+            .unwrap_or(0);
+        self.stats_loader = StatsLoader::start(&mounts);
+        self.read_only_mounts = read_only_mounts();
+        if self.health_loader.is_none() {
+            self.health_loader = HealthLoader::start(&mounts, &self.disks_health);
+        }
+        self.mounts = mounts;
+        self.hidden_mounts.clear();
+        self.regroup();
+        self.show_only_disks = show_only_disks;
+        if let Some(f) = self.filtered.take() {
+            self.filter(f.filter);
+        }
+        let selection_idx = self.filtered.as_ref().map_or(self.selection_idx, |f| f.selection_idx);
+        self.scroll = fix_scroll(self.scroll, selection_idx, self.count(), self.page_height);
+        Ok(())
+    }
+
+    /// switch between the list of all filesystems with stats and
+    /// the list of the filesystems on disks
+    fn toggle_only_disks(&mut self) -> CmdResult {
+        match self.reload(!self.show_only_disks) {
+            Ok(()) => CmdResult::Keep,
+            Err(e) => CmdResult::error(e.to_string()),
+        }
+    }
+
+    /// mount or unmount the selected device, which must be
+    /// confirmed by a second identical command
+    fn mount_operation(
+        &mut self,
+        internal: Internal,
+        pending_operation: Option<(Internal, String)>,
+    ) -> CmdResult {
+        let selected = self.selected_mount().clone();
+        let mounted = is_mounted(&selected);
+        if mounted != (internal == Internal::unmount) {
+            return CmdResult::error(if mounted {
+                format!("{} is already mounted", &selected.info.fs)
+            } else {
+                format!("{} isn't mounted", &selected.info.fs)
+            });
+        }
+        let operation = (internal, selected.info.fs.clone());
+        if pending_operation.as_ref() != Some(&operation) {
+            self.pending_operation = Some(operation);
+            return CmdResult::message(if mounted {
+                tr!(
+                    prompt_unmount,
+                    fs = &selected.info.fs,
+                    mount_point = selected.info.mount_point.to_string_lossy(),
+                )
+            } else {
+                tr!(prompt_mount, fs = &selected.info.fs)
+            });
+        }
+        let res = if mounted {
+            unmount(&selected)
+        } else {
+            mount(&selected)
+        };
+        clear_cache();
+        let res = res.and_then(|message| {
+            self.reload(self.show_only_disks)?;
+            Ok(message)
+        });
+        match res {
+            Ok(message) => CmdResult::message(message),
+            Err(e) => CmdResult::error(e.to_string()),
+        }
+    }
+
+    /// sort the mounts on the given column, or in the default
+    /// order, keeping the selected mount
+    fn set_sort(&mut self, sort: Option<(FsCol, bool)>) -> CmdResult {
+        self.sort = sort;
+        self.regroup();
+        self.scroll = 0;
+        if self.selection_idx >= self.page_height {
+            self.scroll = self.selection_idx + 1 - self.page_height;
+        }
+        if let Some(f) = self.filtered.take() {
+            self.filter(f.filter);
+        }
+        CmdResult::Keep
+    }
+
+    /// sort and group all the mounts, setting apart the other volumes
+    /// of the collapsed groups, and keep the selected mount (or, when
+    /// it's now hidden, select the head of its group)
+    fn regroup(&mut self) {
+        let selected = self.selected_mount().clone();
+        let mut mounts: Vec<Mount> = self.mounts.as_slice().to_vec();
+        mounts.append(&mut self.hidden_mounts);
+        sort_mounts(&mut mounts, self.sort);
+        let grouped = group_volumes(mounts, &self.collapsed_groups);
+        let selected_key = volume_group_key(&selected);
+        self.selection_idx = grouped.shown.iter()
+            .position(|m| m.info.id == selected.info.id)
+            .or_else(|| {
+                grouped.shown.iter()
+                    .position(|m| selected_key.is_some() && volume_group_key(m) == selected_key)
+            })
+            .unwrap_or(0);
+        if let Ok(shown) = grouped.shown.try_into() {
+            // it's never empty, as the heads of the groups are shown
+            self.mounts = shown;
+        }
+        self.group_positions = grouped.positions;
+        self.hidden_mounts = grouped.hidden;
+    }
+
+    /// the space of the listed mounts, including the ones hidden in
+    /// collapsed groups. The mounts of a same device (bind mounts, btrfs
+    /// subvolumes) share its space, which is counted once. The volumes
+    /// of an APFS container share its size and available space, but
+    /// their used spaces add up.
+    fn space_totals(&self) -> SpaceTotals {
+        let mounts: Box<dyn Iterator<Item = &Mount>> = match &self.filtered {
+            Some(filtered) => Box::new(filtered.mounts.iter()),
+            None => Box::new(self.mounts.iter().chain(self.hidden_mounts.iter())),
+        };
+        let mut devices = FnvHashSet::default();
+        let mut containers = FnvHashSet::default();
+        let mut totals = SpaceTotals::default();
+        for mount in mounts {
+            if !devices.insert((mount.info.dev.major, mount.info.dev.minor)) {
+                continue;
+            }
+            if let Some(stats) = mount.stats() {
+                totals.used += volume_used(mount, stats);
+                if apfs_container(mount).map_or(true, |container| containers.insert(container)) {
+                    totals.size += stats.size();
+                    totals.available += stats.available();
+                }
+            }
+        }
+        totals
+    }
+
+    /// collapse or expand the group of volumes of the selected filesystem
+    fn toggle_volume_group(&mut self) -> CmdResult {
+        let selected_id = self.selected_mount().info.id;
+        let in_group = self.mounts.iter()
+            .position(|m| m.info.id == selected_id)
+            .and_then(|idx| self.group_positions.get(idx))
+            .map_or(false, |&pos| pos != GroupPos::Alone);
+        let key = match volume_group_key(self.selected_mount()) {
+            Some(key) if in_group => key,
+            _ => {
+                return CmdResult::error(tr!(error_no_volume_group));
+            }
+        };
+        if !self.collapsed_groups.remove(&key) {
+            self.collapsed_groups.insert(key);
+        }
+        self.regroup();
+        self.scroll = self.scroll.min(self.selection_idx);
+        if let Some(f) = self.filtered.take() {
+            self.filter(f.filter);
+        }
+        CmdResult::Keep
+    }
+
+    /// sort on the column whose title was clicked, reversing the order
+    /// when it's already the sort column
+    fn sort_on_click(&mut self, col: FsCol) -> CmdResult {
+        let reverse = match self.sort {
+            Some((sort_col, reverse)) if sort_col == col => !reverse,
+            _ => false,
+        };
+        self.set_sort(Some((col, reverse)))
+    }
+
+    /// return the column whose title is at x, if y is on the titles row
+    fn title_col_at(&self, x: u16, y: u16) -> Option<FsCol> {
+        if y != 0 {
+            return None;
+        }
+        self.displayed_cols.iter()
+            .rev()
+            .find(|(_, left)| *left <= x)
+            .map(|(col, _)| *col)
+    }
+
+    fn filter(&mut self, filter: MountFilter) {
+        let mut selection_idx = 0;
+        let mut mounts = Vec::new();
+        for (idx, mount) in self.mounts.iter().enumerate() {
+            if !filter.accepts(mount) {
+                continue;
+            }
+            if idx <= self.selection_idx {
+                selection_idx = mounts.len();
+            }
+            mounts.push(mount.clone());
+        }
+        self.filtered = Some(FilteredContent {
+            filter,
+            mounts,
+            selection_idx,
+        });
+    }
+
+    /// the marks displayed after the disk type: failing health,
+    /// removable media, read-only filesystem
+    fn disk_marks(&self, mount: &Mount, glyphs: &Glyphs) -> String {
+        let mut marks = String::new();
+        if let Some(disk) = mount.disk.as_ref() {
+            if self.disks_health.get(&disk.name) == Some(&DiskHealth::Failing) {
+                marks.push('!');
+            }
+            if disk.removable == Some(true) && disk.disk_type() != "remov" {
+                marks.push(glyphs.removable_mark);
+            }
+        }
+        if self.read_only_mounts.contains(&mount.info.id) {
+            marks.push_str("ro");
+        }
+        marks
+    }
+
+    fn is_failing(&self, mount: &Mount) -> bool {
+        mount.disk.as_ref()
+            .map_or(false, |disk| self.disks_health.get(&disk.name) == Some(&DiskHealth::Failing))
+    }
+
     fn no_opt_selected_path(&self) -> &Path {
-        &self.mounts[self.selection_idx].info.mount_point
+        &self.selected_mount().info.mount_point
     }
 
     fn no_opt_selection(&self) -> Selection<'_> {
@@ -149,6 +516,8 @@ impl FilesystemState {
             stype: SelectionType::Directory,
             is_exe: false,
             line: 0,
+            line_range: None,
+            text_view: None,
         }
     }
 }
@@ -169,6 +538,7 @@ impl PanelState for FilesystemState {
 
     fn selected_path(&self) -> Option<&Path> {
         Some(self.no_opt_selected_path())
+            .filter(|_| is_mounted(self.selected_mount()))
     }
 
     fn tree_options(&self) -> TreeOptions {
@@ -187,41 +557,94 @@ impl PanelState for FilesystemState {
     }
 
     fn selection(&self) -> Option<Selection<'_>> {
-        Some(self.no_opt_selection())
+        self.selected_path().map(|_| self.no_opt_selection())
     }
 
     fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        clear_cache();
+        if let Err(e) = self.reload(self.show_only_disks) {
+            warn!("error while reloading mounts: {}", e);
+        }
         Command::empty()
     }
 
+    /// we wake up regularly to check whether the mounts changed
+    fn refresh_period(&self) -> Option<Duration> {
+        Some(self.refresh_period)
+    }
+
+    fn get_pending_task(&self) -> Option<&'static str> {
+        if self.stats_loader.is_some() {
+            Some("loading stats")
+        } else if self.health_loader.is_some() {
+            Some("checking disks health")
+        } else if self.last_check.elapsed() >= self.refresh_period {
+            Some("checking mounts")
+        } else {
+            None
+        }
+    }
+
+    /// receive the stats of remote filesystems, or reload the mounts
+    /// if they changed since the last load (or, when changes can't
+    /// be detected, at every period)
+    fn do_pending_task(
+        &mut self,
+        _app_state: &mut AppState,
+        _screen: Screen,
+        _con: &AppContext,
+        _dam: &mut Dam,
+    ) -> Result<(), ProgramError> {
+        if let Some(loader) = self.stats_loader.as_mut() {
+            for (id, stats) in loader.receive() {
+                let filtered_mounts = self.filtered.iter_mut().flat_map(|f| f.mounts.iter_mut());
+                let mounts = self.mounts.as_mut_slice().iter_mut()
+                    .chain(self.hidden_mounts.iter_mut())
+                    .chain(filtered_mounts);
+                for mount in mounts {
+                    if mount.info.id == id {
+                        mount.stats = stats.clone();
+                    }
+                }
+            }
+            if loader.is_done() {
+                self.stats_loader = None;
+            }
+            return Ok(());
+        }
+        if let Some(loader) = self.health_loader.as_ref() {
+            if let Some(healths) = loader.receive() {
+                self.disks_health.extend(healths);
+                self.health_loader = None;
+            }
+            return Ok(());
+        }
+        self.last_check = Instant::now();
+        let signature = mounts_signature();
+        if signature.is_none() || signature != self.mounts_signature {
+            info!("mounts changed, reloading");
+            clear_cache();
+            self.reload(self.show_only_disks)?;
+        }
+        Ok(())
+    }
+
     fn on_pattern(
         &mut self,
         pattern: InputPattern,
-        _app_state: &AppState,
-        _con: &AppContext,
+        app_state: &AppState,
+        con: &AppContext,
     ) -> Result<CmdResult, ProgramError> {
         if pattern.is_none() {
             self.filtered = None;
         } else {
-            let mut selection_idx = 0;
-            let mut mounts = Vec::new();
-            let pattern = pattern.pattern;
-            for (idx, mount) in self.mounts.iter().enumerate() {
-                if pattern.score_of_string(&mount.info.fs).is_none()
-                    && mount.disk.as_ref().and_then(|d| pattern.score_of_string(d.disk_type())).is_none()
-                    && pattern.score_of_string(&mount.info.fs_type).is_none()
-                    && pattern.score_of_string(&mount.info.mount_point.to_string_lossy()).is_none()
-                { continue; }
-                if idx <= self.selection_idx {
-                    selection_idx = mounts.len();
+            let expr = CommandParts::from(pattern.raw).pattern;
+            match MountFilter::new(&expr, &app_state.tags, con) {
+                Ok(filter) => self.filter(filter),
+                Err(e) => {
+                    return Ok(CmdResult::DisplayError(e.to_string()));
                 }
-                mounts.push(mount.clone());
             }
-            self.filtered = Some(FilteredContent {
-                pattern,
-                mounts,
-                selection_idx,
-            });
         }
         Ok(CmdResult::Keep)
     }
@@ -233,15 +656,33 @@ impl PanelState for FilesystemState {
     ) -> Result<(), ProgramError> {
         let area = &disc.state_area;
         let con = &disc.con;
-        self.page_height = area.height as usize - 2;
-        let (mounts, selection_idx) = if let Some(filtered) = &self.filtered {
-            (filtered.mounts.as_slice(), filtered.selection_idx)
+        // the lines of the mounts are between the titles and the totals
+        let content_bottom = if self.show_totals { area.height - 1 } else { area.height };
+        self.page_height = content_bottom as usize - 2;
+        let (mounts, selection_idx, group_positions) = if let Some(filtered) = &self.filtered {
+            (filtered.mounts.as_slice(), filtered.selection_idx, &[][..])
         } else {
-            (self.mounts.as_slice(), self.selection_idx)
+            (self.mounts.as_slice(), self.selection_idx, self.group_positions.as_slice())
+        };
+        let group_pos = |idx: usize| group_positions.get(idx).copied().unwrap_or(GroupPos::Alone);
+        // the volumes of a group are drawn as branches of the first one,
+        // which tells how many are hidden when the group is collapsed
+        let group_suffix = |idx: usize| match group_pos(idx) {
+            GroupPos::Head { count, collapsed: true } => format!(" +{}", count),
+            _ => String::new(),
         };
-        let scrollbar = area.scrollbar(self.scroll, mounts.len());
+        // the capacity of an APFS container is only written on the line
+        // of its first volume, the other ones only tell their used space
+        let shows_capacity = |idx: usize, mount: &Mount| {
+            apfs_container(mount).is_none()
+                || !matches!(group_pos(idx), GroupPos::Member { .. })
+        };
+        let mut scrolled_area = area.clone();
+        scrolled_area.height = content_bottom;
+        let scrollbar = disc.scrollbar(&scrolled_area, self.scroll, mounts.len());
         //- style preparation
         let styles = &disc.panel_skin.styles;
+        let glyphs = &disc.con.glyphs;
         let selection_bg = styles.selected_line.get_bg()
             .unwrap_or(Color::AnsiValue(240));
         let match_style = &styles.char_match;
@@ -250,223 +691,413 @@ impl PanelState for FilesystemState {
         let border_style = &styles.help_table_border;
         let mut selected_border_style = styles.help_table_border.clone();
         selected_border_style.set_bg(selection_bg);
+        let mut selected_alert_style = styles.usage_alert.clone();
+        selected_alert_style.set_bg(selection_bg);
         //- width computations and selection of columns to display
         let width = area.width as usize;
         let w_fs = mounts.iter()
-            .map(|m| m.info.fs.chars().count())
+            .enumerate()
+            .map(|(idx, m)| {
+                let w_branch = if matches!(group_pos(idx), GroupPos::Member { .. }) { 2 } else { 0 };
+                w_branch + m.info.fs.chars().count() + group_suffix(idx).chars().count()
+            })
             .max().unwrap_or(0)
             .max("filesystem".len());
         let mut wc_fs = w_fs; // width of the column (may include selection mark)
         if con.show_selection_mark {
             wc_fs += 1;
         }
-        let w_dsk = 5; // max width of a lfs-core disk type
+        let disk_cell_width = |mount: &Mount| {
+            let w_type = mount.disk.as_ref().map_or(0, |d| d.disk_type().len());
+            match self.disk_marks(mount, glyphs).chars().count() {
+                0 => w_type,
+                w_marks if w_type == 0 => w_marks,
+                w_marks => w_type + 1 + w_marks,
+            }
+        };
+        let w_dsk = mounts.iter()
+            .map(disk_cell_width)
+            .max().unwrap_or(0)
+            .max("disk".len());
         let w_type = mounts.iter()
             .map(|m| m.info.fs_type.chars().count())
             .max().unwrap_or(0)
             .max("type".len());
-        let w_size = 4;
-        let w_use = 4;
+        let size_format = self.tree_options.size_format;
+        let mut max_size = mounts.iter()
+            .filter_map(|m| m.stats())
+            .map(|s| s.size())
+            .max().unwrap_or(0);
+        if self.show_totals {
+            max_size = max_size.max(self.space_totals().size);
+        }
+        let w_size = size_format.width(max_size);
+        let w_use = w_size;
         let mut w_use_bar = 1; // min size, may grow if space available
         let w_use_share = 4;
         let mut wc_use = w_use; // sum of all the parts of the usage column
-        let w_free = 4;
+        let w_free = w_size;
+        let w_inodes = 9; // used/free, each fitting in 4 chars
         let w_mount_point = mounts.iter()
             .map(|m| m.info.mount_point.to_string_lossy().chars().count())
             .max().unwrap_or(0)
             .max("mount point".len());
-        let w_mandatory = wc_fs + 1 + w_size + 1 + w_free + 1 + w_mount_point;
-        let mut e_dsk = false;
-        let mut e_type = false;
+        let col_width = |col: FsCol| match col {
+            FsCol::Fs => wc_fs,
+            FsCol::Disk => w_dsk,
+            FsCol::Type => w_type,
+            FsCol::Use => w_use,
+            FsCol::Free => w_free,
+            FsCol::Size => w_size,
+            FsCol::Inodes => w_inodes,
+            FsCol::MountPoint => w_mount_point,
+        };
         let mut e_use_bar = false;
         let mut e_use_share = false;
-        let mut e_use = false;
-        if w_mandatory + 1 < width {
-            let mut rem = width - w_mandatory - 1;
-            if rem > w_use {
-                rem -= w_use + 1;
-                e_use = true;
-            }
-            if e_use && rem > w_use_share {
-                rem -= w_use_share; // no separation with use
-                e_use_share = true;
-                wc_use += w_use_share;
-            }
-            if rem > w_dsk {
-                rem -= w_dsk + 1;
-                e_dsk = true;
-            }
-            if e_use && rem > w_use_bar {
-                rem -= w_use_bar + 1;
-                e_use_bar = true;
-                wc_use += w_use_bar + 1;
-            }
-            if rem > w_type {
-                rem -= w_type + 1;
-                e_type = true;
+        let mut rem = 0; // width left for the usage bar
+        let hidden_cols = &self.hidden_cols;
+        let shown = |col: FsCol| !hidden_cols.contains(&col);
+        let cols: Vec<FsCol> = if let Some(cols) = &con.fs_columns {
+            // the columns are the ones of the configuration, only
+            // the parts of the usage column depend on the width
+            let cols: Vec<FsCol> = cols.iter().copied().filter(|&col| shown(col)).collect();
+            let w_cols: usize = cols.iter().map(|&col| col_width(col) + 1).sum();
+            if cols.contains(&FsCol::Use) && w_cols < width {
+                rem = width - w_cols;
+                if rem > w_use_share {
+                    rem -= w_use_share;
+                    e_use_share = true;
+                }
+                if rem > w_use_bar + 1 {
+                    rem -= w_use_bar + 1;
+                    e_use_bar = true;
+                }
             }
-            if e_use_bar && rem > 0 {
-                let incr = rem.min(9);
-                w_use_bar += incr;
-                wc_use += incr;
+            cols
+        } else {
+            let w_mandatory: usize = [FsCol::Fs, FsCol::Size, FsCol::Free, FsCol::MountPoint]
+                .iter()
+                .filter(|&&col| shown(col))
+                .map(|&col| col_width(col) + 1)
+                .sum::<usize>()
+                .saturating_sub(1);
+            let mut e_dsk = false;
+            let mut e_type = false;
+            let mut e_use = false;
+            let mut e_inodes = false;
+            if w_mandatory + 1 < width {
+                rem = width - w_mandatory - 1;
+                if shown(FsCol::Use) && rem > w_use {
+                    rem -= w_use + 1;
+                    e_use = true;
+                }
+                if e_use && rem > w_use_share {
+                    rem -= w_use_share; // no separation with use
+                    e_use_share = true;
+                }
+                if shown(FsCol::Disk) && rem > w_dsk {
+                    rem -= w_dsk + 1;
+                    e_dsk = true;
+                }
+                if e_use && rem > w_use_bar {
+                    rem -= w_use_bar + 1;
+                    e_use_bar = true;
+                }
+                if shown(FsCol::Inodes) && rem > w_inodes {
+                    rem -= w_inodes + 1;
+                    e_inodes = true;
+                }
+                if shown(FsCol::Type) && rem > w_type {
+                    rem -= w_type + 1;
+                    e_type = true;
+                }
             }
+            DEFAULT_FS_COLS.iter()
+                .copied()
+                .filter(|col| match col {
+                    FsCol::Disk => e_dsk,
+                    FsCol::Type => e_type,
+                    FsCol::Use => e_use,
+                    FsCol::Inodes => e_inodes,
+                    _ => shown(*col),
+                })
+                .collect()
+        };
+        if e_use_share {
+            wc_use += w_use_share;
+        }
+        if e_use_bar {
+            let incr = rem.min(9);
+            w_use_bar += incr;
+            wc_use += w_use_bar + 1;
         }
+        let col_width = |col: FsCol| match col {
+            FsCol::Use => wc_use,
+            _ => col_width(col),
+        };
         //- titles
         w.queue(cursor::MoveTo(area.left, area.top))?;
         let mut cw = CropWriter::new(w, width);
-        cw.queue_g_string(&styles.default, format!("{:wc_fs$}", "filesystem"))?;
-        cw.queue_char(border_style, '│')?;
-        if e_dsk {
-            cw.queue_g_string(&styles.default, "disk ".to_string())?;
-            cw.queue_char(border_style, '│')?;
-        }
-        if e_type {
-            cw.queue_g_string(&styles.default, format!("{:^w_type$}", "type"))?;
-            cw.queue_char(border_style, '│')?;
-        }
-        if e_use {
-            cw.queue_g_string(&styles.default, format!(
-                "{:^width$}", if wc_use > 4 { "usage" } else { "use" }, width = wc_use
-            ))?;
-            cw.queue_char(border_style, '│')?;
-        }
-        cw.queue_g_string(&styles.default, "free".to_string())?;
-        cw.queue_char(border_style, '│')?;
-        cw.queue_g_string(&styles.default, "size".to_string())?;
-        cw.queue_char(border_style, '│')?;
-        cw.queue_g_string(&styles.default, "mount point".to_string())?;
+        self.displayed_cols.clear();
+        let mut x = area.left;
+        for (col_idx, &col) in cols.iter().enumerate() {
+            self.displayed_cols.push((col, x));
+            x += col_width(col) as u16 + 1;
+            let title = match col {
+                FsCol::Fs => "filesystem",
+                FsCol::Disk => "disk",
+                FsCol::Type => "type",
+                FsCol::Use if wc_use > 4 => "usage",
+                FsCol::Use => "use",
+                FsCol::Free => "free",
+                FsCol::Size => "size",
+                FsCol::Inodes => "inodes",
+                FsCol::MountPoint => "mount point",
+            };
+            // the mark of the sort column replaces the end of the
+            // title when there's no room for it
+            let title = match self.sort {
+                Some((sort_col, reverse)) if sort_col == col => {
+                    let mut title: String = title.chars()
+                        .take(col_width(col).saturating_sub(1))
+                        .collect();
+                    title.push(if sorts_decreasing(col) != reverse {
+                        glyphs.sort_decreasing
+                    } else {
+                        glyphs.sort_increasing
+                    });
+                    title
+                }
+                _ => title.to_string(),
+            };
+            let title = match col {
+                FsCol::Fs | FsCol::Disk | FsCol::MountPoint => {
+                    format!("{:<width$}", title, width = col_width(col))
+                }
+                _ => format!("{:^width$}", title, width = col_width(col)),
+            };
+            cw.queue_g_string(&styles.default, title)?;
+            if col_idx + 1 < cols.len() {
+                cw.queue_char(border_style, glyphs.border_vertical)?;
+            }
+        }
         cw.fill(border_style, &SPACE_FILLING)?;
         //- horizontal line
         w.queue(cursor::MoveTo(area.left, 1 + area.top))?;
         let mut cw = CropWriter::new(w, width);
-        cw.queue_g_string(border_style, format!("{:─>width$}", '┼', width = wc_fs + 1))?;
-        if e_dsk {
-            cw.queue_g_string(border_style, format!("{:─>width$}", '┼', width = w_dsk + 1))?;
-        }
-        if e_type {
-            cw.queue_g_string(border_style, format!("{:─>width$}", '┼', width = w_type+1))?;
-        }
-        cw.queue_g_string(border_style, format!("{:─>width$}", '┼', width = w_size+1))?;
-        if e_use {
-            cw.queue_g_string(border_style, format!("{:─>width$}", '┼', width = wc_use+1))?;
+        for &col in &cols[..cols.len() - 1] {
+            cw.queue_g_string(border_style, glyphs.border_cross_line(col_width(col) + 1))?;
         }
-        cw.queue_g_string(border_style, format!("{:─>width$}", '┼', width = w_free+1))?;
-        cw.fill(border_style, &BRANCH_FILLING)?;
+        cw.fill(border_style, glyphs.border_filling())?;
         //- content
         let mut idx = self.scroll as usize;
-        for y in 2..area.height {
+        for y in 2..content_bottom {
             w.queue(cursor::MoveTo(area.left, y + area.top))?;
             let selected = selection_idx == idx;
-            let mut cw = CropWriter::new(w, width - 1); // -1 for scrollbar
+            let mut cw = CropWriter::new(w, disc.content_width());
             let txt_style = if selected { &styles.selected_line } else { &styles.default };
             if let Some(mount) = mounts.get(idx) {
                 let match_style = if selected { &selected_match_style } else { match_style };
                 let border_style = if selected { &selected_border_style } else { border_style };
-                if con.show_selection_mark {
-                    cw.queue_char(txt_style, if selected { '▶' } else { ' ' })?;
-                }
-                // fs
-                let s = &mount.info.fs;
-                let mut matched_string = MatchedString::new(
-                    self.filtered.as_ref().and_then(|f| f.pattern.search_string(s)),
-                    s,
-                    txt_style,
-                    match_style,
-                );
-                matched_string.fill(w_fs, Alignment::Left);
-                matched_string.queue_on(&mut cw)?;
-                cw.queue_char(border_style, '│')?;
-                // dsk
-                if e_dsk {
-                    if let Some(disk) = mount.disk.as_ref() {
-                        let s = disk.disk_type();
-                        let mut matched_string = MatchedString::new(
-                            self.filtered.as_ref().and_then(|f| f.pattern.search_string(s)),
-                            s,
-                            txt_style,
-                            match_style,
-                        );
-                        matched_string.fill(5, Alignment::Center);
-                        matched_string.queue_on(&mut cw)?;
-                    } else {
-                        cw.queue_g_string(txt_style, "     ".to_string())?;
+                let stats = mount.stats().filter(|s| s.size() > 0);
+                // the used space of a nearly full filesystem is displayed
+                // with the alert style
+                let use_style = if !is_nearly_full(mount, con.filesystems_usage_alert) {
+                    txt_style
+                } else if selected {
+                    &selected_alert_style
+                } else {
+                    &styles.usage_alert
+                };
+                for (col_idx, &col) in cols.iter().enumerate() {
+                    match col {
+                        FsCol::Fs => {
+                            if con.show_selection_mark {
+                                cw.queue_char(txt_style, if selected { glyphs.selection_mark } else { ' ' })?;
+                            }
+                            let mut w_cell = 0;
+                            if let GroupPos::Member { last } = group_pos(idx) {
+                                cw.queue_char(
+                                    border_style,
+                                    if last { glyphs.branch_last } else { glyphs.branch_middle },
+                                )?;
+                                cw.queue_char(border_style, glyphs.branch_horizontal)?;
+                                w_cell += 2;
+                            }
+                            let s = &mount.info.fs;
+                            let matched_string = MatchedString::new(
+                                self.filtered.as_ref().and_then(|f| f.filter.search_string(s)),
+                                s,
+                                txt_style,
+                                match_style,
+                            );
+                            matched_string.queue_on(&mut cw)?;
+                            w_cell += s.chars().count();
+                            let suffix = group_suffix(idx);
+                            w_cell += suffix.chars().count();
+                            cw.queue_g_string(border_style, suffix)?;
+                            cw.repeat(txt_style, &SPACE_FILLING, w_fs - w_cell)?;
+                        }
+                        FsCol::Disk => {
+                            // a failing disk is displayed in red
+                            let mut disk_style = txt_style.clone();
+                            if self.is_failing(mount) {
+                                disk_style.set_fg(Color::AnsiValue(196));
+                            }
+                            let marks = self.disk_marks(mount, glyphs);
+                            let mut w_cell = 0;
+                            if let Some(disk) = mount.disk.as_ref() {
+                                let s = disk.disk_type();
+                                let matched_string = MatchedString::new(
+                                    self.filtered.as_ref().and_then(|f| f.filter.search_string(s)),
+                                    s,
+                                    &disk_style,
+                                    match_style,
+                                );
+                                matched_string.queue_on(&mut cw)?;
+                                w_cell += s.len();
+                                if !s.is_empty() && !marks.is_empty() {
+                                    cw.queue_char(&disk_style, ' ')?;
+                                    w_cell += 1;
+                                }
+                            }
+                            cw.queue_g_string(&disk_style, marks.clone())?;
+                            w_cell += marks.chars().count();
+                            cw.repeat(txt_style, &SPACE_FILLING, w_dsk - w_cell)?;
+                        }
+                        FsCol::Type => {
+                            let s = &mount.info.fs_type;
+                            let mut matched_string = MatchedString::new(
+                                self.filtered.as_ref().and_then(|f| f.filter.search_string(s)),
+                                s,
+                                txt_style,
+                                match_style,
+                            );
+                            matched_string.fill(w_type, Alignment::Center);
+                            matched_string.queue_on(&mut cw)?;
+                        }
+                        FsCol::Use => {
+                            if let Some(stats) = stats {
+                                let used = volume_used(mount, stats);
+                                let use_share = used as f64 / stats.size() as f64;
+                                cw.queue_g_string(use_style, format!("{:>w_use$}", size_format.format(used)))?;
+                                if e_use_share {
+                                    cw.queue_g_string(use_style, format!("{:>3.0}%", 100.0*use_share))?;
+                                }
+                                if e_use_bar {
+                                    cw.queue_char(txt_style, ' ')?;
+                                    let mut bar_style = styles.default.clone();
+                                    bar_style.set_bg(super::share_color(use_share));
+                                    cw.queue_g_string(
+                                        &bar_style,
+                                        glyphs.progress_bar(use_share as f32, w_use_bar),
+                                    )?;
+                                }
+                            } else {
+                                cw.repeat(txt_style, &SPACE_FILLING, wc_use)?;
+                            }
+                        }
+                        FsCol::Free => {
+                            // a spinner is displayed while the stats are loading
+                            let loader = self.stats_loader.as_ref()
+                                .filter(|loader| loader.is_pending(mount.info.id));
+                            if let Some(stats) = stats.filter(|_| shows_capacity(idx, mount)) {
+                                let mut share_style = txt_style.clone();
+                                share_style.set_fg(super::share_color(stats.use_share()));
+                                cw.queue_g_string(&share_style, format!("{:>w_free$}", size_format.format(stats.available())))?;
+                            } else if stats.is_some() {
+                                cw.repeat(txt_style, &SPACE_FILLING, w_free)?;
+                            } else if let Some(loader) = loader {
+                                cw.queue_g_string(txt_style, format!("{:^w_free$}", loader.spinner(glyphs)))?;
+                            } else {
+                                cw.repeat(txt_style, &SPACE_FILLING, w_free)?;
+                            }
+                        }
+                        FsCol::Size => {
+                            if let Some(stats) = stats.filter(|_| shows_capacity(idx, mount)) {
+                                cw.queue_g_string(txt_style, format!("{:>w_size$}", size_format.format(stats.size())))?;
+                            } else {
+                                cw.repeat(txt_style, &SPACE_FILLING, w_size)?;
+                            }
+                        }
+                        FsCol::Inodes => {
+                            if let Some(inodes) = mount.inodes() {
+                                let mut share_style = txt_style.clone();
+                                share_style.set_fg(super::share_color(inodes.use_share()));
+                                cw.queue_g_string(txt_style, format!("{:>4}", file_size::fit_4(inodes.used())))?;
+                                cw.queue_char(border_style, '/')?;
+                                cw.queue_g_string(&share_style, format!("{:<4}", file_size::fit_4(inodes.favail)))?;
+                            } else {
+                                cw.repeat(txt_style, &SPACE_FILLING, w_inodes)?;
+                            }
+                        }
+                        FsCol::MountPoint => {
+                            let s = if is_mounted(mount) {
+                                mount.info.mount_point.to_string_lossy()
+                            } else {
+                                "(not mounted)".into()
+                            };
+                            let s = &s;
+                            let mut matched_string = MatchedString::new(
+                                self.filtered.as_ref().and_then(|f| f.filter.search_string(s)),
+                                s,
+                                txt_style,
+                                match_style,
+                            );
+                            matched_string.fill(w_mount_point, Alignment::Left);
+                            matched_string.queue_on(&mut cw)?;
+                        }
+                    }
+                    if col_idx + 1 < cols.len() {
+                        cw.queue_char(border_style, glyphs.border_vertical)?;
                     }
-                    cw.queue_char(border_style, '│')?;
-                }
-                // type
-                if e_type {
-                    let s = &mount.info.fs_type;
-                    let mut matched_string = MatchedString::new(
-                        self.filtered.as_ref().and_then(|f| f.pattern.search_string(s)),
-                        s,
-                        txt_style,
-                        match_style,
-                    );
-                    matched_string.fill(w_type, Alignment::Center);
-                    matched_string.queue_on(&mut cw)?;
-                    cw.queue_char(border_style, '│')?;
                 }
-                // size, used, free
-                if let Some(stats) = mount.stats().filter(|s| s.size() > 0) {
-                    let share_color = super::share_color(stats.use_share());
-                    // used
-                    if e_use {
-                        cw.queue_g_string(txt_style, format!("{:>4}", file_size::fit_4(stats.used())))?;
+                idx += 1;
+            }
+            cw.fill(txt_style, &SPACE_FILLING)?;
+            disc.queue_scrollbar(w, y, scrollbar)?;
+        }
+        //- totals
+        if self.show_totals {
+            w.queue(cursor::MoveTo(area.left, content_bottom + area.top))?;
+            let mut cw = CropWriter::new(w, width);
+            let txt_style = &styles.default;
+            let totals = self.space_totals();
+            let share = totals.use_share();
+            for (col_idx, &col) in cols.iter().enumerate() {
+                match col {
+                    FsCol::Fs => {
+                        cw.queue_g_string(txt_style, format!("{:<wc_fs$}", "total"))?;
+                    }
+                    FsCol::Use => {
+                        cw.queue_g_string(txt_style, format!("{:>w_use$}", size_format.format(totals.used)))?;
                         if e_use_share {
-                            cw.queue_g_string(txt_style, format!("{:>3.0}%", 100.0*stats.use_share()))?;
+                            cw.queue_g_string(txt_style, format!("{:>3.0}%", 100.0 * share))?;
                         }
                         if e_use_bar {
                             cw.queue_char(txt_style, ' ')?;
-                            let pb = ProgressBar::new(stats.use_share() as f32, w_use_bar);
                             let mut bar_style = styles.default.clone();
-                            bar_style.set_bg(share_color);
-                            cw.queue_g_string(&bar_style, format!("{:<width$}", pb, width=w_use_bar))?;
+                            bar_style.set_bg(super::share_color(share));
+                            cw.queue_g_string(&bar_style, glyphs.progress_bar(share as f32, w_use_bar))?;
                         }
-                        cw.queue_char(border_style, '│')?;
                     }
-                    // free
-                    let mut share_style = txt_style.clone();
-                    share_style.set_fg(share_color);
-                    cw.queue_g_string(&share_style, format!("{:>4}", file_size::fit_4(stats.available())))?;
-                    cw.queue_char(border_style, '│')?;
-                    // size
-                    if let Some(stats) = mount.stats() {
-                        cw.queue_g_string(txt_style, format!("{:>4}", file_size::fit_4(stats.size())))?;
-                    } else {
-                        cw.repeat(txt_style, &SPACE_FILLING, 4)?;
+                    FsCol::Free => {
+                        let mut share_style = txt_style.clone();
+                        share_style.set_fg(super::share_color(share));
+                        cw.queue_g_string(&share_style, format!("{:>w_free$}", size_format.format(totals.available)))?;
                     }
-                    cw.queue_char(border_style, '│')?;
-                } else {
-                    // used
-                    if e_use {
-                        cw.repeat(txt_style, &SPACE_FILLING, wc_use)?;
-                        cw.queue_char(border_style, '│')?;
+                    FsCol::Size => {
+                        cw.queue_g_string(txt_style, format!("{:>w_size$}", size_format.format(totals.size)))?;
+                    }
+                    _ => {
+                        cw.repeat(txt_style, &SPACE_FILLING, col_width(col))?;
                     }
-                    // free
-                    cw.repeat(txt_style, &SPACE_FILLING, w_free)?;
-                    cw.queue_char(border_style, '│')?;
-                    // size
-                    cw.repeat(txt_style, &SPACE_FILLING, w_size)?;
-                    cw.queue_char(border_style, '│')?;
                 }
-                // mount point
-                let s = &mount.info.mount_point.to_string_lossy();
-                let matched_string = MatchedString::new(
-                    self.filtered.as_ref().and_then(|f| f.pattern.search_string(s)),
-                    s,
-                    txt_style,
-                    match_style,
-                );
-                matched_string.queue_on(&mut cw)?;
-                idx += 1;
+                if col_idx + 1 < cols.len() {
+                    cw.queue_char(border_style, glyphs.border_vertical)?;
+                }
             }
             cw.fill(txt_style, &SPACE_FILLING)?;
-            let scrollbar_style = if ScrollCommand::is_thumb(y, scrollbar) {
-                &styles.scrollbar_thumb
-            } else {
-                &styles.scrollbar_track
-            };
-            scrollbar_style.queue_str(w, "▐")?;
         }
         Ok(())
     }
@@ -482,6 +1113,7 @@ impl PanelState for FilesystemState {
     ) -> Result<CmdResult, ProgramError> {
         let screen = cc.app.screen;
         let con = &cc.app.con;
+        let pending_operation = self.pending_operation.take();
         use Internal::*;
         Ok(match internal_exec.internal {
             Internal::back => {
@@ -508,13 +1140,27 @@ impl PanelState for FilesystemState {
             Internal::line_up_no_cycle => {
                 self.move_line(internal_exec, input_invocation, -1, false)
             }
-            Internal::open_stay => {
+            Internal::open_stay | Internal::open_biggest
+                | Internal::panel_left | Internal::panel_right
+                if !is_mounted(self.selected_mount()) =>
+            {
+                CmdResult::error(tr!(error_device_not_mounted))
+            }
+            Internal::mount | Internal::unmount => {
+                self.mount_operation(internal_exec.internal, pending_operation)
+            }
+            Internal::open_stay | Internal::open_biggest => {
                 let in_new_panel = input_invocation
                     .map(|inv| inv.bang)
                     .unwrap_or(internal_exec.bang);
                 let dam = Dam::unlimited();
                 let mut tree_options = self.tree_options();
                 tree_options.show_root_fs = true;
+                if internal_exec.internal == Internal::open_biggest {
+                    // the usual hunt for what fills the disk
+                    tree_options.sort = Sort::Size;
+                    tree_options.show_sizes = true;
+                }
                 CmdResult::from_optional_state(
                     BrowserState::new(
                         self.no_opt_selected_path().to_path_buf(),
@@ -565,7 +1211,7 @@ impl PanelState for FilesystemState {
             Internal::panel_right_no_open => CmdResult::HandleInApp(Internal::panel_right_no_open),
             Internal::page_down => {
                 if !self.try_scroll(ScrollCommand::Pages(1)) {
-                    self.selection_idx = self.count() - 1;
+                    self.selection_idx = self.count().saturating_sub(1);
                 }
                 CmdResult::Keep
             }
@@ -575,6 +1221,23 @@ impl PanelState for FilesystemState {
                 }
                 CmdResult::Keep
             }
+            Internal::toggle_only_disks => self.toggle_only_disks(),
+            Internal::toggle_volume_group => self.toggle_volume_group(),
+            Internal::toggle_fs_totals => {
+                self.show_totals = !self.show_totals;
+                CmdResult::Keep
+            }
+            Internal::sort_by_size => self.set_sort(Some((FsCol::Size, false))),
+            Internal::sort_by_type => self.set_sort(Some((FsCol::Type, false))),
+            Internal::no_sort => self.set_sort(None),
+            Internal::print_fs => {
+                let mounts = if let Some(filtered) = &self.filtered {
+                    filtered.mounts.as_slice()
+                } else {
+                    self.mounts.as_slice()
+                };
+                CmdResult::from(Launchable::printer(mounts_to_json(mounts)?))
+            }
             open_leave => CmdResult::PopStateAndReapply,
             _ => self.on_internal_generic(
                 w,
@@ -589,11 +1252,14 @@ impl PanelState for FilesystemState {
 
     fn on_click(
         &mut self,
-        _x: u16,
+        x: u16,
         y: u16,
         _screen: Screen,
         _con: &AppContext,
     ) -> Result<CmdResult, ProgramError> {
+        if let Some(col) = self.title_col_at(x, y) {
+            return Ok(self.sort_on_click(col));
+        }
         if y >= 2 {
             let y = y as usize - 2 + self.scroll;
             if y < self.mounts.len().into() {
@@ -602,5 +1268,21 @@ impl PanelState for FilesystemState {
         }
         Ok(CmdResult::Keep)
     }
-}
 
+    /// a middle click on a column title hides the column
+    fn on_middle_click(
+        &mut self,
+        x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        if let Some(col) = self.title_col_at(x, y) {
+            if self.displayed_cols.len() < 2 {
+                return Ok(CmdResult::error(tr!(error_last_column)));
+            }
+            self.hidden_cols.push(col);
+        }
+        Ok(CmdResult::Keep)
+    }
+}
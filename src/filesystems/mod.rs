@@ -1,19 +1,41 @@
 //! The whole module is only available on unix now
 
+mod disk_health;
 mod filesystems_state;
+mod mount_json;
+mod mount_filter;
 mod mount_list;
+mod mount_ops;
 mod mount_space_display;
+mod snapshots;
+mod snapshots_state;
+mod stats_loader;
+mod volume_groups;
 
 pub use {
+    disk_health::*,
     filesystems_state::FilesystemState,
+    mount_json::*,
+    mount_filter::MountFilter,
     mount_list::MountList,
+    mount_ops::*,
     mount_space_display::MountSpaceDisplay,
+    snapshots::*,
+    snapshots_state::SnapshotsState,
+    stats_loader::*,
+    volume_groups::*,
 };
 
 use {
     crokey::crossterm::style::Color,
+    lfs_core::Mount,
     once_cell::sync::Lazy,
-    std::sync::Mutex,
+    std::{
+        fs,
+        os::unix::fs::MetadataExt,
+        path::Path,
+        sync::Mutex,
+    },
 };
 
 pub static MOUNTS: Lazy<Mutex<MountList>> = Lazy::new(|| Mutex::new(MountList::new()));
@@ -23,6 +45,43 @@ pub fn clear_cache() {
     mount_list.clear_cache();
 }
 
+/// return the mount whose mount point is the given path or, if there's
+/// none, the one of the filesystem containing the path
+pub fn mount_of(path: &Path) -> Option<Mount> {
+    let mut mount_list = MOUNTS.lock().unwrap();
+    let mounts = mount_list.load().ok()?;
+    if let Some(mount) = mounts.iter().rev().find(|m| m.info.mount_point == path) {
+        return Some(mount.clone());
+    }
+    let dev = fs::metadata(path).ok()?.dev();
+    mount_list.get_by_device_id(dev.into()).cloned()
+}
+
+/// return the uuid of the filesystem on the given device (eg "/dev/sda1"),
+/// found in /dev/disk/by-uuid (so only on linux)
+pub fn device_uuid(device: &str) -> Option<String> {
+    let device = fs::canonicalize(device).ok()?;
+    fs::read_dir("/dev/disk/by-uuid").ok()?
+        .flatten()
+        .find(|entry| fs::canonicalize(entry.path()).map_or(false, |p| p == device))
+        .and_then(|entry| entry.file_name().to_str().map(|s| s.to_string()))
+}
+
+/// the share (between 0 and 1) of the space of the mount which
+/// is used, when known
+pub fn used_share(mount: &Mount) -> Option<f64> {
+    let stats = mount.stats().filter(|s| s.size() > 0)?;
+    Some(volume_used(mount, stats) as f64 / stats.size() as f64)
+}
+
+/// tell whether the mount is used over the alert threshold, if any
+pub fn is_nearly_full(mount: &Mount, usage_alert: Option<f64>) -> bool {
+    match (usage_alert, used_share(mount)) {
+        (Some(threshold), Some(share)) => share >= threshold,
+        _ => false,
+    }
+}
+
 static SHARE_COLORS: &[Color] = &[
     Color::AnsiValue(28),
     Color::AnsiValue(29),
@@ -0,0 +1,233 @@
+use {
+    super::volume_used,
+    crate::{
+        app::AppContext,
+        conf::file_size::parse_file_size,
+        errors::PatternError,
+        pattern::*,
+        tag::TagStore,
+    },
+    bet::BeTree,
+    lfs_core::{Mount, Stats},
+};
+
+/// an attribute of a mount a filter condition can be about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MountAttr {
+    /// the filesystem type, eg "ext4"
+    Type,
+    /// the device, eg "/dev/nvme0n1p2", or the disk name
+    Dev,
+    /// the type of the disk, eg "SSD"
+    Disk,
+    /// the mount point
+    Mount,
+    /// the used share, in percents
+    Use,
+    Size,
+    Used,
+    Free,
+}
+
+impl MountAttr {
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "type" | "t" => Some(Self::Type),
+            "dev" | "fs" => Some(Self::Dev),
+            "disk" => Some(Self::Disk),
+            "mount" | "mp" => Some(Self::Mount),
+            "use" => Some(Self::Use),
+            "size" => Some(Self::Size),
+            "used" => Some(Self::Used),
+            "free" | "avail" => Some(Self::Free),
+            _ => None,
+        }
+    }
+    fn is_numeric(self) -> bool {
+        matches!(self, Self::Use | Self::Size | Self::Used | Self::Free)
+    }
+    fn text(self, mount: &Mount) -> String {
+        match self {
+            Self::Type => mount.info.fs_type.clone(),
+            Self::Dev => match &mount.disk {
+                Some(disk) => format!("{} {}", &mount.info.fs, &disk.name),
+                None => mount.info.fs.clone(),
+            },
+            Self::Disk => mount.disk.as_ref()
+                .map_or("", |disk| disk.disk_type())
+                .to_string(),
+            Self::Mount => mount.info.mount_point.to_string_lossy().to_string(),
+            _ => String::new(),
+        }
+    }
+    fn number(self, mount: &Mount, stats: &Stats) -> f64 {
+        match self {
+            Self::Use => 100.0 * volume_used(mount, stats) as f64 / stats.size() as f64,
+            Self::Size => stats.size() as f64,
+            Self::Used => volume_used(mount, stats) as f64,
+            Self::Free => stats.available() as f64,
+            _ => 0.0,
+        }
+    }
+}
+
+/// a filter on an attribute of the mounts, eg `type=ext4`
+/// or `use>80%`
+#[derive(Debug, Clone)]
+enum MountCondition {
+    /// the attribute contains the string (lowercased)
+    Contains(MountAttr, String),
+    Greater(MountAttr, f64),
+    Lower(MountAttr, f64),
+}
+
+impl MountCondition {
+    /// parse the atom of a pattern as a condition, return None
+    /// if it's not one (and is then some text to search)
+    fn parse(s: &str) -> Result<Option<Self>, PatternError> {
+        let op_idx = match s.find([':', '=', '>', '<']) {
+            Some(idx) => idx,
+            None => {
+                return Ok(None);
+            }
+        };
+        let attr = match MountAttr::from_key(&s[..op_idx].to_lowercase()) {
+            Some(attr) => attr,
+            None => {
+                return Ok(None);
+            }
+        };
+        let op = s.as_bytes()[op_idx];
+        let value = s[op_idx + 1..].trim();
+        let invalid = || PatternError::InvalidFilter { filter: s.to_string() };
+        if !attr.is_numeric() {
+            return match op {
+                b':' | b'=' => Ok(Some(Self::Contains(attr, value.to_lowercase()))),
+                _ => Err(invalid()),
+            };
+        }
+        let number = if attr == MountAttr::Use {
+            value.trim_end_matches('%').parse::<f64>().map_err(|_| invalid())?
+        } else {
+            parse_file_size(value).map_err(|_| invalid())? as f64
+        };
+        match op {
+            b'>' => Ok(Some(Self::Greater(attr, number))),
+            b'<' => Ok(Some(Self::Lower(attr, number))),
+            _ => Err(invalid()),
+        }
+    }
+    fn accepts(&self, mount: &Mount) -> bool {
+        match self {
+            Self::Contains(attr, value) => attr.text(mount).to_lowercase().contains(value),
+            Self::Greater(attr, number) => mount.stats()
+                .map_or(false, |stats| attr.number(mount, stats) > *number),
+            Self::Lower(attr, number) => mount.stats()
+                .map_or(false, |stats| attr.number(mount, stats) < *number),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum MountFilterAtom {
+    Condition(MountCondition),
+    /// a text searched in the device, disk type, filesystem
+    /// type and mount point
+    Text(Pattern),
+}
+
+impl MountFilterAtom {
+    fn accepts(&self, mount: &Mount) -> bool {
+        match self {
+            Self::Condition(condition) => condition.accepts(mount),
+            Self::Text(pattern) => {
+                pattern.score_of_string(&mount.info.fs).is_some()
+                    || mount.disk.as_ref().and_then(|d| pattern.score_of_string(d.disk_type())).is_some()
+                    || pattern.score_of_string(&mount.info.fs_type).is_some()
+                    || pattern.score_of_string(&mount.info.mount_point.to_string_lossy()).is_some()
+            }
+        }
+    }
+}
+
+/// The filter of the filesystems state: a composition (with the
+/// usual operators of patterns) of texts and of conditions on
+/// the attributes of the mounts, eg `type=ext4&use>80%`
+#[derive(Debug, Clone)]
+pub struct MountFilter {
+    expr: BeTree<PatternOperator, MountFilterAtom>,
+}
+
+impl MountFilter {
+    pub fn new(
+        parts_expr: &BeTree<PatternOperator, PatternParts>,
+        tags: &TagStore,
+        con: &AppContext,
+    ) -> Result<Self, PatternError> {
+        let expr = parts_expr.try_map_atoms::<_, PatternError, _>(|parts| {
+            if parts.mode().is_none() {
+                if let Some(condition) = MountCondition::parse(parts.core())? {
+                    return Ok(MountFilterAtom::Condition(condition));
+                }
+            }
+            let mut atom_expr = BeTree::new();
+            atom_expr.push_atom(parts.clone());
+            let pattern = Pattern::new(
+                &atom_expr,
+                &con.search_modes,
+                &con.content_search_limits,
+                tags,
+            )?;
+            Ok(MountFilterAtom::Text(pattern))
+        })?;
+        Ok(Self { expr })
+    }
+    pub fn accepts(&self, mount: &Mount) -> bool {
+        use PatternOperator::*;
+        self.expr.eval(
+            |atom| atom.accepts(mount),
+            |op, a, b| match (op, b) {
+                (And, Some(b)) => a && b,
+                (Or, Some(b)) => a || b,
+                (Not, _) => !a,
+                _ => a,
+            },
+            |op, a| matches!((op, a), (And, false) | (Or, true)),
+        ).unwrap_or(true)
+    }
+    /// find the text searched by the filter in the candidate,
+    /// for highlighting
+    pub fn search_string(&self, candidate: &str) -> Option<NameMatch> {
+        self.expr.iter_atoms()
+            .find_map(|atom| match atom {
+                MountFilterAtom::Text(pattern) => pattern.search_string(candidate),
+                MountFilterAtom::Condition(_) => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod mount_filter_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_conditions() {
+        assert!(matches!(
+            MountCondition::parse("type:ext4"),
+            Ok(Some(MountCondition::Contains(MountAttr::Type, s))) if s == "ext4"
+        ));
+        assert!(matches!(
+            MountCondition::parse("use>80%"),
+            Ok(Some(MountCondition::Greater(MountAttr::Use, n))) if n == 80.0
+        ));
+        assert!(matches!(
+            MountCondition::parse("free<2G"),
+            Ok(Some(MountCondition::Lower(MountAttr::Free, n))) if n == 2e9
+        ));
+        assert!(matches!(MountCondition::parse("nvme"), Ok(None)));
+        assert!(matches!(MountCondition::parse("home=x"), Ok(None)));
+        assert!(MountCondition::parse("use>much").is_err());
+        assert!(MountCondition::parse("type>ext4").is_err());
+    }
+}
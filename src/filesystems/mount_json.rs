@@ -0,0 +1,91 @@
+use {
+    super::*,
+    crate::errors::ProgramError,
+    lfs_core::Mount,
+    serde::Serialize,
+    std::path::Path,
+};
+
+/// the description of a filesystem, as exported in JSON
+#[derive(Debug, Serialize)]
+struct MountJson<'m> {
+    fs: &'m str,
+    fs_type: &'m str,
+    fs_label: Option<&'m str>,
+    /// the type of disk, as displayed in the disk column (eg "SSD")
+    disk: Option<&'static str>,
+    removable: Option<bool>,
+    /// None when the device isn't mounted
+    mount_point: Option<&'m Path>,
+    stats: Option<StatsJson>,
+}
+
+/// sizes are in bytes
+#[derive(Debug, Serialize)]
+struct StatsJson {
+    size: u64,
+    used: u64,
+    available: u64,
+    use_share: f64,
+    inodes: Option<InodesJson>,
+}
+
+#[derive(Debug, Serialize)]
+struct InodesJson {
+    files: u64,
+    used: u64,
+    available: u64,
+}
+
+impl<'m> From<&'m Mount> for MountJson<'m> {
+    fn from(mount: &'m Mount) -> Self {
+        let stats = mount.stats()
+            .filter(|s| s.size() > 0)
+            .map(|s| StatsJson {
+                size: s.size(),
+                used: s.used(),
+                available: s.available(),
+                use_share: s.use_share(),
+                inodes: mount.inodes().map(|inodes| InodesJson {
+                    files: inodes.files,
+                    used: inodes.used(),
+                    available: inodes.favail,
+                }),
+            });
+        Self {
+            fs: &mount.info.fs,
+            fs_type: &mount.info.fs_type,
+            fs_label: mount.fs_label.as_deref(),
+            disk: mount.disk.as_ref().map(|disk| disk.disk_type()),
+            removable: mount.disk.as_ref().and_then(|disk| disk.removable),
+            mount_point: Some(mount.info.mount_point.as_path()).filter(|_| is_mounted(mount)),
+            stats,
+        }
+    }
+}
+
+/// make the JSON array of the given mounts
+pub fn mounts_to_json(mounts: &[Mount]) -> Result<String, ProgramError> {
+    let mounts: Vec<MountJson> = mounts.iter().map(MountJson::from).collect();
+    serde_json::to_string_pretty(&mounts)
+        .map_err(|e| ProgramError::InternalError { details: e.to_string() })
+}
+
+/// make the JSON array of the filesystems which would be listed
+/// in the filesystems state, waiting for the stats of the remote
+/// ones (up to their timeout)
+pub fn list_mounts_json(show_only_disks: bool) -> Result<String, ProgramError> {
+    let mut mounts = super::filesystems_state::load_mounts(show_only_disks)?;
+    if let Some(mut loader) = StatsLoader::start(&mounts) {
+        while !loader.is_done() {
+            for (id, stats) in loader.receive() {
+                for mount in mounts.as_mut_slice() {
+                    if mount.info.id == id {
+                        mount.stats = stats.clone();
+                    }
+                }
+            }
+        }
+    }
+    mounts_to_json(&mounts)
+}
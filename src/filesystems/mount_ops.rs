@@ -0,0 +1,169 @@
+use {
+    crate::errors::ProgramError,
+    lfs_core::*,
+    std::{
+        collections::hash_map::DefaultHasher,
+        fs,
+        hash::{Hash, Hasher},
+        path::{Path, PathBuf},
+        process::Command,
+        str::FromStr,
+    },
+};
+
+/// tell whether the mount is a mounted filesystem, and not
+/// a removable device found unmounted
+pub fn is_mounted(mount: &Mount) -> bool {
+    !mount.info.mount_point.as_os_str().is_empty()
+}
+
+/// tell whether the disk is removable or connected with USB
+fn is_removable(disk_name: &str) -> bool {
+    if Disk::new(disk_name.to_string()).removable == Some(true) {
+        return true;
+    }
+    fs::canonicalize(format!("/sys/block/{}", disk_name))
+        .map_or(false, |path| path.to_string_lossy().contains("/usb"))
+}
+
+/// read the content of a small file of /sys
+fn read_sys(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// build a mount without mount point nor stats, for a device
+/// which isn't mounted.
+///
+/// Its id, which must be distinct from the ones of the real mounts,
+/// is taken from the end of the range.
+fn unmounted(sys_dir: &Path, name: &str, disk: Disk, idx: usize) -> Option<Mount> {
+    let size = read_sys(&sys_dir.join("size"))?;
+    if size == "0" {
+        return None; // no media
+    }
+    let dev = DeviceId::from_str(&read_sys(&sys_dir.join("dev"))?).ok()?;
+    Some(Mount {
+        info: MountInfo {
+            id: MountId::MAX - idx as MountId,
+            parent: 0,
+            dev,
+            root: PathBuf::new(),
+            mount_point: PathBuf::new(),
+            fs: format!("/dev/{}", name),
+            fs_type: String::new(),
+            bound: false,
+        },
+        fs_label: None,
+        disk: Some(disk),
+        stats: Err(StatsError::Excluded),
+    })
+}
+
+/// return the partitions (or whole disks when they have no
+/// partition) of the removable disks, which aren't mounted
+pub fn unmounted_removable_devices(mounts: &[Mount]) -> Vec<Mount> {
+    let mut devices = Vec::new();
+    let entries = match fs::read_dir("/sys/block") {
+        Ok(entries) => entries,
+        Err(_) => {
+            return devices;
+        }
+    };
+    let is_mounted = |name: &str| {
+        let device = format!("/dev/{}", name);
+        mounts.iter().any(|m| {
+            m.info.fs == device
+                || fs::canonicalize(&m.info.fs).map_or(false, |p| p == Path::new(&device))
+        })
+    };
+    for entry in entries.flatten() {
+        let disk_name = entry.file_name().to_string_lossy().to_string();
+        if !is_removable(&disk_name) {
+            continue;
+        }
+        let disk_dir = entry.path();
+        let mut partitions: Vec<String> = fs::read_dir(&disk_dir)
+            .map(|entries| {
+                entries.flatten()
+                    .filter(|e| e.path().join("partition").exists())
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        partitions.sort();
+        if partitions.is_empty() {
+            if !is_mounted(&disk_name) {
+                let disk = Disk::new(disk_name.clone());
+                devices.extend(unmounted(&disk_dir, &disk_name, disk, devices.len()));
+            }
+        } else {
+            for name in partitions {
+                if !is_mounted(&name) {
+                    let disk = Disk::new(disk_name.clone());
+                    devices.extend(unmounted(&disk_dir.join(&name), &name, disk, devices.len()));
+                }
+            }
+        }
+    }
+    devices
+}
+
+/// compute a hash of the mount table and of the list of block
+/// devices (with their sizes, which change when a media is inserted),
+/// so that changes of mounts and pluggings can be detected.
+///
+/// Return None when there's no mount table to read (i.e. not on linux)
+pub fn mounts_signature() -> Option<u64> {
+    let mountinfo = fs::read("/proc/self/mountinfo").ok()?;
+    let mut hasher = DefaultHasher::new();
+    mountinfo.hash(&mut hasher);
+    if let Ok(entries) = fs::read_dir("/sys/block") {
+        let mut devices: Vec<(String, Option<String>)> = entries
+            .flatten()
+            .map(|e| (
+                e.file_name().to_string_lossy().to_string(),
+                read_sys(&e.path().join("size")),
+            ))
+            .collect();
+        devices.sort();
+        devices.hash(&mut hasher);
+    }
+    Some(hasher.finish())
+}
+
+/// run the command, returning its output on success
+fn run(program: &str, args: &[&str]) -> Result<String, ProgramError> {
+    info!("executing {} {:?}", program, args);
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|source| ProgramError::LaunchError {
+            program: program.to_string(),
+            source,
+        })?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(ProgramError::MountOperation {
+            command: format!("{} {}", program, args.join(" ")),
+            details: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
+    }
+}
+
+/// mount the device with udisks, returning the message of udisksctl
+/// (eg "Mounted /dev/sdb1 at /media/dys/KEY")
+pub fn mount(mount: &Mount) -> Result<String, ProgramError> {
+    run("udisksctl", &["mount", "--no-user-interaction", "-b", &mount.info.fs])
+}
+
+/// unmount the filesystem, with udisks when it's on a block device
+pub fn unmount(mount: &Mount) -> Result<String, ProgramError> {
+    if mount.info.fs.starts_with("/dev/") {
+        run("udisksctl", &["unmount", "--no-user-interaction", "-b", &mount.info.fs])
+    } else {
+        let mount_point = mount.info.mount_point.to_string_lossy();
+        run("umount", &[&mount_point])
+            .map(|_| format!("Unmounted {}", mount_point))
+    }
+}
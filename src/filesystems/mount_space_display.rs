@@ -3,7 +3,8 @@ use {
         display::cond_bg,
         errors::ProgramError,
         filesystems::share_color,
-        skin::StyleMap,
+        display::SizeFormat,
+        skin::{Glyphs, StyleMap},
     },
     crokey::crossterm::{
         style::{ResetColor, SetBackgroundColor, SetForegroundColor},
@@ -18,14 +19,24 @@ use {
 pub struct MountSpaceDisplay<'m, 's> {
     mount: &'m Mount,
     skin: &'s StyleMap,
+    glyphs: &'s Glyphs,
+    size_format: SizeFormat,
     pub available_width: usize,
 }
 
 impl<'m, 's> MountSpaceDisplay<'m, 's> {
-    pub fn from(mount: &'m Mount, skin: &'s StyleMap, available_width: usize) -> Self {
+    pub fn from(
+        mount: &'m Mount,
+        skin: &'s StyleMap,
+        glyphs: &'s Glyphs,
+        size_format: SizeFormat,
+        available_width: usize,
+    ) -> Self {
         Self {
             mount,
             skin,
+            glyphs,
+            size_format,
             available_width,
         }
     }
@@ -54,7 +65,9 @@ impl<'m, 's> MountSpaceDisplay<'m, 's> {
             let dsk = self.mount.disk.as_ref().map_or("", |d| d.disk_type());
             let w_dsk = dsk.chars().count();
             let mut e_dsk = false;
-            let w_fraction = 9;
+            let used = self.size_format.format(s.used());
+            let size = self.size_format.format(s.size());
+            let w_fraction = used.len() + 1 + size.len();
             let mut e_fraction = false;
             let mut w_bar = 2; // min width
             let mut e_bar = false;
@@ -101,18 +114,17 @@ impl<'m, 's> MountSpaceDisplay<'m, 's> {
                 }
                 cw.w.queue(SetForegroundColor(share_color))?;
                 cw.queue_unstyled_char(' ')?;
-                cw.queue_unstyled_g_string(file_size::fit_4(s.used()))?;
-                cw.queue_g_string(txt_style, format!("/{}", file_size::fit_4(s.size())))?;
+                cw.queue_unstyled_g_string(used)?;
+                cw.queue_g_string(txt_style, format!("/{}", size))?;
             }
             if e_bar {
-                let pb = ProgressBar::new(s.use_share() as f32, w_bar);
                 cw.w.queue(ResetColor {})?;
                 if let Some(bg_color) = bg {
                     cw.w.queue(SetBackgroundColor(bg_color))?;
                 }
                 cw.queue_unstyled_char(' ')?;
                 cw.w.queue(SetBackgroundColor(share_color))?;
-                cw.queue_unstyled_g_string(format!("{:<width$}", pb, width = w_bar))?;
+                cw.queue_unstyled_g_string(self.glyphs.progress_bar(s.use_share() as f32, w_bar))?;
             }
             if let Some(bg_color) = bg {
                 cw.w.queue(SetBackgroundColor(bg_color))?;
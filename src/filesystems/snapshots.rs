@@ -0,0 +1,105 @@
+use {
+    chrono::{DateTime, Local},
+    lfs_core::Mount,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// a read-only image of a filesystem, as kept by ZFS or by
+/// snapper on btrfs
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub name: String,
+
+    /// the directory in which the snapshotted filesystem can be browsed
+    pub root: PathBuf,
+
+    /// the date of the snapshot, if known
+    pub time: Option<DateTime<Local>>,
+
+    /// the path, in the snapshot, corresponding to the live path the
+    /// snapshots were listed for
+    pub path: PathBuf,
+}
+
+impl Snapshot {
+    /// whether the live path already existed when the snapshot was taken
+    pub fn has_path(&self) -> bool {
+        self.path.exists()
+    }
+    /// the deepest existing directory on the path, in the snapshot
+    pub fn closest_dir(&self) -> PathBuf {
+        let mut dir = self.path.as_path();
+        while dir != self.root && !dir.is_dir() {
+            match dir.parent() {
+                Some(parent) => dir = parent,
+                None => return self.root.clone(),
+            }
+        }
+        dir.to_path_buf()
+    }
+}
+
+/// return the directory in which the snapshots of the mount are
+/// visible, if the filesystem is snapshot capable and this directory
+/// exists
+pub fn snapshots_dir(mount: &Mount) -> Option<PathBuf> {
+    let dir = match mount.info.fs_type.as_str() {
+        "zfs" => mount.info.mount_point.join(".zfs/snapshot"),
+        "btrfs" => mount.info.mount_point.join(".snapshots"),
+        _ => {
+            return None;
+        }
+    };
+    if dir.is_dir() {
+        Some(dir)
+    } else {
+        None
+    }
+}
+
+/// list the snapshots of the mount, most recent first, with the paths
+/// corresponding to the given live path
+pub fn list_snapshots(mount: &Mount, live_path: &Path) -> Vec<Snapshot> {
+    let dir = match snapshots_dir(mount) {
+        Some(dir) => dir,
+        None => {
+            return Vec::new();
+        }
+    };
+    let relative_path = live_path
+        .strip_prefix(&mount.info.mount_point)
+        .unwrap_or_else(|_| Path::new(""));
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("can't read snapshots dir {:?}: {}", &dir, e);
+            return Vec::new();
+        }
+    };
+    let mut snapshots: Vec<Snapshot> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let mut root = entry.path();
+            // snapper keeps the snapshot in a "snapshot" subdirectory,
+            // next to its info.xml file
+            if mount.info.fs_type == "btrfs" {
+                root = root.join("snapshot");
+            }
+            if !root.is_dir() {
+                return None;
+            }
+            let time = fs::metadata(&root)
+                .and_then(|md| md.modified())
+                .ok()
+                .map(DateTime::<Local>::from);
+            let path = root.join(relative_path);
+            Some(Snapshot { name, root, time, path })
+        })
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.time));
+    snapshots
+}
@@ -0,0 +1,225 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        browser::BrowserState,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        task_sync::Dam,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::path::{Path, PathBuf},
+    termimad::minimad::Alignment,
+};
+
+/// an application state listing the snapshots of a filesystem,
+/// most recent first, from which the state of a path in a snapshot
+/// can be browsed
+pub struct SnapshotsState {
+    live_path: PathBuf,
+    list: FilteredList<Snapshot>,
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+impl SnapshotsState {
+    /// build a state listing the snapshots of the filesystem
+    /// containing the path
+    pub fn new(
+        live_path: &Path,
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> Result<SnapshotsState, ProgramError> {
+        let mount = mount_of(live_path)
+            .ok_or_else(|| ProgramError::Lfs {
+                details: format!("no mount found for {:?}", live_path),
+            })?;
+        if snapshots_dir(&mount).is_none() {
+            return Err(ProgramError::NoSnapshot {
+                mount_point: mount.info.mount_point.to_string_lossy().to_string(),
+                fs_type: mount.info.fs_type,
+            });
+        }
+        Ok(SnapshotsState {
+            live_path: live_path.to_path_buf(),
+            list: FilteredList::new(list_snapshots(&mount, live_path), matches),
+            tree_options,
+            mode: initial_mode(con),
+        })
+    }
+}
+
+fn matches(snapshot: &Snapshot, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&snapshot.name).is_some()
+}
+
+impl PanelState for SnapshotsState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Snapshots
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// the path, in the selected snapshot, corresponding to
+    /// the live path, if it existed when the snapshot was taken
+    fn selected_path(&self) -> Option<&Path> {
+        self.list.selected()
+            .filter(|snapshot| snapshot.has_path())
+            .map(|snapshot| snapshot.path.as_path())
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        self.selected_path().map(|path| Selection {
+            path,
+            line: 0,
+            line_range: None,
+            text_view: None,
+            stype: SelectionType::from(path),
+            is_exe: false,
+        })
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        if let Some(mount) = mount_of(&self.live_path) {
+            let snapshots = list_snapshots(&mount, &self.live_path);
+            self.list.set_items(snapshots, |a, b| a.root == b.root);
+        }
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let date_time_format = self.tree_options.date_time_format;
+        let w_date = date_time_format.date_time_width();
+        let w_name = list.visible()
+            .map(|s| s.name.chars().count())
+            .max().unwrap_or(0)
+            .max("snapshot".len());
+        let columns = [
+            ListColumn::new("date", w_date, Alignment::Right),
+            ListColumn::new("snapshot", w_name, Alignment::Left),
+        ];
+        let styles = &disc.panel_skin.styles;
+        list.queue_table_head(w, disc, &columns, |cw| {
+            cw.queue_g_string(&styles.default, self.live_path.to_string_lossy().to_string())?;
+            Ok(())
+        })?;
+        self.list.queue_table_rows(w, disc, |cw, snapshot, row| {
+            let dates_style = row.style(&styles.dates);
+            let date = snapshot.time
+                .map(|time| date_time_format.format_date_time(time))
+                .unwrap_or_default();
+            row.queue_cell(cw, &date, &dates_style, w_date, Alignment::Right)?;
+            row.queue_matched_cell(cw, &snapshot.name, row.txt_style, w_name, Alignment::Left)?;
+            if snapshot.has_path() {
+                cw.queue_g_string(row.txt_style, snapshot.path.to_string_lossy().to_string())?;
+            } else {
+                cw.queue_str(&dates_style, "absent")?;
+            }
+            Ok(())
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        use Internal::*;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                let in_new_panel = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                // we browse the snapshot at the live path, or at its
+                // deepest ancestor existing in the snapshot
+                match self.list.selected() {
+                    Some(snapshot) => {
+                        let dam = Dam::unlimited();
+                        CmdResult::from_optional_state(
+                            BrowserState::new(
+                                snapshot.closest_dir(),
+                                self.tree_options.without_pattern(),
+                                screen,
+                                con,
+                                &dam,
+                            ),
+                            None,
+                            in_new_panel,
+                        )
+                    }
+                    None => CmdResult::error(tr!(error_no_selected_snapshot)),
+                }
+            }
+            open_leave => CmdResult::PopStateAndReapply,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
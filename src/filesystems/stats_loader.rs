@@ -0,0 +1,96 @@
+use {
+    crate::skin::Glyphs,
+    crossbeam::channel::{unbounded, Receiver, RecvTimeoutError},
+    lfs_core::*,
+    std::{
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// the time after which a filesystem whose stats aren't
+/// received is considered unreachable
+const STATS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// the max time a call to `receive` waits, so that the
+/// spinners can be animated
+const WAIT_PERIOD: Duration = Duration::from_millis(100);
+
+type StatsResult = (MountId, Result<Stats, StatsError>);
+
+/// tell whether we should query the stats of this mount in
+/// background, because they weren't read on mounts listing
+pub fn has_deferred_stats(mount: &Mount) -> bool {
+    matches!(mount.stats, Err(StatsError::Excluded)) && mount.info.is_remote()
+}
+
+/// The loading, in background threads, of the stats of the remote
+/// filesystems, which may be slow or hang when they're unreachable
+pub struct StatsLoader {
+    receiver: Receiver<StatsResult>,
+    pending: Vec<MountId>,
+    start: Instant,
+}
+
+impl StatsLoader {
+    /// start querying the stats of the mounts needing it, return
+    /// None if there's none
+    pub fn start(mounts: &[Mount]) -> Option<Self> {
+        let (sender, receiver) = unbounded();
+        let mut pending = Vec::new();
+        for mount in mounts.iter().filter(|m| has_deferred_stats(m)) {
+            let id = mount.info.id;
+            let mount_point = mount.info.mount_point.clone();
+            let sender = sender.clone();
+            pending.push(id);
+            // if the filesystem hangs, the thread may never end, and
+            // there's nothing we can do about it
+            thread::spawn(move || {
+                let stats = Stats::from(&mount_point);
+                let _ = sender.send((id, stats));
+            });
+        }
+        if pending.is_empty() {
+            None
+        } else {
+            Some(Self { receiver, pending, start: Instant::now() })
+        }
+    }
+    pub fn is_pending(&self, id: MountId) -> bool {
+        self.pending.contains(&id)
+    }
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+    /// the char to display in place of the stats not yet received
+    pub fn spinner(&self, glyphs: &Glyphs) -> char {
+        let idx = self.start.elapsed().as_millis() / WAIT_PERIOD.as_millis();
+        glyphs.spinner(idx as usize)
+    }
+    /// wait a little for the stats of a filesystem.
+    ///
+    /// Return the stats received, which are errors for all the
+    /// filesystems still pending when the timeout is reached.
+    pub fn receive(&mut self) -> Vec<StatsResult> {
+        let remaining = STATS_TIMEOUT.saturating_sub(self.start.elapsed());
+        if remaining.is_zero() {
+            warn!("no stats received for mounts {:?}", &self.pending);
+            return self.pending
+                .drain(..)
+                .map(|id| (id, Err(StatsError::Unreachable)))
+                .collect();
+        }
+        match self.receiver.recv_timeout(remaining.min(WAIT_PERIOD)) {
+            Ok((id, stats)) => {
+                self.pending.retain(|&p| p != id);
+                vec![(id, stats)]
+            }
+            Err(RecvTimeoutError::Timeout) => Vec::new(),
+            Err(RecvTimeoutError::Disconnected) => {
+                // should not happen as the pending list is kept in sync
+                self.pending.clear();
+                Vec::new()
+            }
+        }
+    }
+}
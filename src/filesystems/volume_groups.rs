@@ -0,0 +1,184 @@
+use {
+    fnv::{FnvHashMap, FnvHashSet},
+    lfs_core::{Mount, Stats},
+    std::fs,
+};
+
+/// the place of a mount in the list of filesystems, when
+/// the volumes of a same device are grouped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupPos {
+    /// not in a group of several volumes
+    Alone,
+    /// the volume mounted highest in its group, displayed first
+    Head {
+        /// the number of the other volumes of the group
+        count: usize,
+        collapsed: bool,
+    },
+    Member {
+        last: bool,
+    },
+}
+
+/// the mounts in their display order, with the volumes of the
+/// collapsed groups apart
+pub struct GroupedMounts {
+    pub shown: Vec<Mount>,
+    /// the positions of the shown mounts
+    pub positions: Vec<GroupPos>,
+    pub hidden: Vec<Mount>,
+}
+
+/// the volume group in a device mapper name (eg "vg-root"), where
+/// the dashes of the names of the volume group and of the logical
+/// volume are doubled
+fn lvm_volume_group(dm_name: &str) -> Option<String> {
+    let bytes = dm_name.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'-' {
+            if bytes.get(i + 1) == Some(&b'-') {
+                i += 2;
+                continue;
+            }
+            return Some(dm_name[..i].replace("--", "-"));
+        }
+        i += 1;
+    }
+    None
+}
+
+/// the container in the device of an APFS volume, eg "disk3" for
+/// "/dev/disk3s1s1" (a snapshot of the volume "disk3s1")
+fn apfs_container_of_device(fs: &str) -> Option<&str> {
+    let name = fs.strip_prefix("/dev/").unwrap_or(fs);
+    let digits = name.strip_prefix("disk")?;
+    let len = digits.find(|c: char| !c.is_ascii_digit())?;
+    if len == 0 || !digits[len..].starts_with('s') {
+        return None;
+    }
+    Some(&name[..4 + len])
+}
+
+/// the APFS container of the volume, if it's an APFS one (macOS).
+///
+/// The volumes of a container share its space, so its size and
+/// its available space are the same for all of them.
+pub fn apfs_container(mount: &Mount) -> Option<&str> {
+    if mount.info.fs_type != "apfs" {
+        return None;
+    }
+    apfs_container_of_device(&mount.info.fs)
+}
+
+/// the space used by the volume. It's the space used on its device
+/// except for APFS volumes, which only count their own blocks and
+/// not the ones of the other volumes of their container
+pub fn volume_used(mount: &Mount, stats: &Stats) -> u64 {
+    if apfs_container(mount).is_some() {
+        stats.bsize * (stats.blocks - stats.bfree)
+    } else {
+        stats.used()
+    }
+}
+
+/// the key of the group of the volumes sharing a device: the
+/// subvolumes of a btrfs filesystem, the logical volumes of
+/// a LVM volume group, or the volumes of an APFS container
+pub fn volume_group_key(mount: &Mount) -> Option<String> {
+    if mount.info.fs_type == "btrfs" {
+        return Some(format!("btrfs:{}", &mount.info.fs));
+    }
+    if let Some(container) = apfs_container(mount) {
+        return Some(format!("apfs:{}", container));
+    }
+    let disk = mount.disk.as_ref().filter(|disk| disk.lvm)?;
+    let dm_name = fs::read_to_string(format!("/sys/block/{}/dm/name", &disk.name)).ok()?;
+    lvm_volume_group(dm_name.trim()).map(|vg| format!("lvm:{}", vg))
+}
+
+/// order the mounts so that the volumes of a group follow the one
+/// which is mounted highest, and set apart the other volumes of
+/// the collapsed groups.
+///
+/// The order of the mounts is otherwise kept.
+pub fn group_volumes(
+    mounts: Vec<Mount>,
+    collapsed_groups: &FnvHashSet<String>,
+) -> GroupedMounts {
+    let keys: Vec<Option<String>> = mounts.iter().map(volume_group_key).collect();
+    let mut heads: FnvHashMap<&str, usize> = FnvHashMap::default();
+    let mut sizes: FnvHashMap<&str, usize> = FnvHashMap::default();
+    let depth = |idx: usize| mounts[idx].info.mount_point.as_os_str().len();
+    for (idx, key) in keys.iter().enumerate() {
+        if let Some(key) = key.as_deref() {
+            *sizes.entry(key).or_default() += 1;
+            let is_higher = heads.get(key).map_or(true, |&head| depth(idx) < depth(head));
+            if is_higher {
+                heads.insert(key, idx);
+            }
+        }
+    }
+    let len = mounts.len();
+    let mut mounts: Vec<Option<Mount>> = mounts.into_iter().map(Some).collect();
+    let mut grouped = GroupedMounts {
+        shown: Vec::with_capacity(len),
+        positions: Vec::with_capacity(len),
+        hidden: Vec::new(),
+    };
+    for idx in 0..len {
+        let key = match keys[idx].as_deref() {
+            Some(key) if sizes[key] > 1 => key,
+            _ => {
+                grouped.shown.extend(mounts[idx].take());
+                grouped.positions.push(GroupPos::Alone);
+                continue;
+            }
+        };
+        if heads[key] != idx {
+            continue; // the member is placed with its head
+        }
+        let collapsed = collapsed_groups.contains(key);
+        let members: Vec<usize> = (0..len)
+            .filter(|&i| i != idx && keys[i].as_deref() == Some(key))
+            .collect();
+        grouped.shown.extend(mounts[idx].take());
+        grouped.positions.push(GroupPos::Head { count: members.len(), collapsed });
+        for (i, &member) in members.iter().enumerate() {
+            if let Some(mount) = mounts[member].take() {
+                if collapsed {
+                    grouped.hidden.push(mount);
+                } else {
+                    grouped.shown.push(mount);
+                    grouped.positions.push(GroupPos::Member { last: i + 1 == members.len() });
+                }
+            }
+        }
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod volume_groups_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_lvm_volume_group() {
+        assert_eq!(lvm_volume_group("vg0-root"), Some("vg0".to_string()));
+        assert_eq!(lvm_volume_group("my--vg-home--old"), Some("my-vg".to_string()));
+        assert_eq!(lvm_volume_group("luks-crypt"), Some("luks".to_string()));
+        assert_eq!(lvm_volume_group("nodash"), None);
+    }
+
+    #[test]
+    fn test_apfs_container_of_device() {
+        assert_eq!(apfs_container_of_device("/dev/disk3s1s1"), Some("disk3"));
+        assert_eq!(apfs_container_of_device("/dev/disk12s5"), Some("disk12"));
+        assert_eq!(apfs_container_of_device("disk1s2"), Some("disk1"));
+        assert_eq!(apfs_container_of_device("/dev/disk4"), None);
+        assert_eq!(apfs_container_of_device("/dev/diskus1"), None);
+        assert_eq!(apfs_container_of_device("/dev/sda1"), None);
+    }
+}
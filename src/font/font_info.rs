@@ -0,0 +1,369 @@
+use {
+    crate::errors::ProgramError,
+    flate2::read::ZlibDecoder,
+    std::{
+        borrow::Cow,
+        fs,
+        io::Read,
+        ops::RangeInclusive,
+        path::Path,
+    },
+};
+
+/// the container format of a font file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontFormat {
+    TrueType,
+    OpenTypeCff,
+    Collection,
+    Woff,
+}
+
+impl FontFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::TrueType => "TrueType",
+            Self::OpenTypeCff => "OpenType (CFF)",
+            Self::Collection => "TrueType collection",
+            Self::Woff => "WOFF",
+        }
+    }
+}
+
+/// the metadata of a font file, read from its name, maxp,
+/// OS/2 and cmap tables
+#[derive(Debug, Clone)]
+pub struct FontInfo {
+    pub format: FontFormat,
+    /// number of fonts, when the file is a collection (only the
+    /// first one is described)
+    pub font_count: usize,
+    pub family: Option<String>,
+    pub subfamily: Option<String>,
+    pub full_name: Option<String>,
+    pub version: Option<String>,
+    pub weight: Option<u16>,
+    pub glyph_count: Option<u16>,
+    /// the ranges of codepoints mapped to a glyph, sorted and merged
+    pub coverage: Vec<RangeInclusive<u32>>,
+}
+
+/// a table of a sfnt font, in the file or after decompression
+struct Table<'b> {
+    tag: [u8; 4],
+    data: Cow<'b, [u8]>,
+}
+
+fn u16_at(data: &[u8], idx: usize) -> Option<u16> {
+    data.get(idx..idx + 2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn u32_at(data: &[u8], idx: usize) -> Option<u32> {
+    data.get(idx..idx + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn invalid(details: &str) -> ProgramError {
+    ProgramError::InvalidFont { details: details.to_string() }
+}
+
+impl FontInfo {
+    pub fn read(path: &Path) -> Result<Self, ProgramError> {
+        let bytes = fs::read(path)?;
+        Self::parse(&bytes)
+    }
+    pub fn parse(bytes: &[u8]) -> Result<Self, ProgramError> {
+        let signature = bytes.get(0..4).ok_or_else(|| invalid("file too short"))?;
+        let (format, font_count, tables) = match signature {
+            b"wOFF" => (FontFormat::Woff, 1, woff_tables(bytes)?),
+            b"wOF2" => {
+                return Err(invalid("WOFF2 fonts aren't supported"));
+            }
+            b"ttcf" => {
+                let font_count = u32_at(bytes, 8).ok_or_else(|| invalid("truncated collection"))?;
+                let offset = u32_at(bytes, 12).ok_or_else(|| invalid("empty collection"))?;
+                (FontFormat::Collection, font_count as usize, sfnt_tables(bytes, offset as usize)?)
+            }
+            b"OTTO" => (FontFormat::OpenTypeCff, 1, sfnt_tables(bytes, 0)?),
+            [0, 1, 0, 0] | b"true" => (FontFormat::TrueType, 1, sfnt_tables(bytes, 0)?),
+            _ => {
+                return Err(invalid("not a known font format"));
+            }
+        };
+        let table = |tag: &[u8; 4]| {
+            tables.iter()
+                .find(|t| &t.tag == tag)
+                .map(|t| t.data.as_ref())
+        };
+        let mut info = Self {
+            format,
+            font_count,
+            family: None,
+            subfamily: None,
+            full_name: None,
+            version: None,
+            weight: table(b"OS/2").and_then(|data| u16_at(data, 4)),
+            glyph_count: table(b"maxp").and_then(|data| u16_at(data, 4)),
+            coverage: table(b"cmap").map(read_cmap).unwrap_or_default(),
+        };
+        if let Some(data) = table(b"name") {
+            info.family = read_name(data, 16).or_else(|| read_name(data, 1));
+            info.subfamily = read_name(data, 17).or_else(|| read_name(data, 2));
+            info.full_name = read_name(data, 4);
+            info.version = read_name(data, 5);
+        }
+        Ok(info)
+    }
+    /// the number of codepoints mapped to a glyph
+    pub fn codepoint_count(&self) -> usize {
+        self.coverage.iter()
+            .map(|r| (r.end() - r.start() + 1) as usize)
+            .sum()
+    }
+    pub fn covers(&self, c: char) -> bool {
+        let c = c as u32;
+        self.coverage.iter().any(|r| r.contains(&c))
+    }
+    /// the number of codepoints of the given range which are
+    /// mapped to a glyph
+    pub fn count_in(&self, block: &RangeInclusive<u32>) -> usize {
+        self.coverage.iter()
+            .map(|r| {
+                let start = *r.start().max(block.start());
+                let end = *r.end().min(block.end());
+                if start > end { 0 } else { (end - start + 1) as usize }
+            })
+            .sum()
+    }
+}
+
+/// read the table directory of a sfnt font starting at offset
+fn sfnt_tables(bytes: &[u8], offset: usize) -> Result<Vec<Table<'_>>, ProgramError> {
+    let num_tables = u16_at(bytes, offset + 4).ok_or_else(|| invalid("truncated header"))?;
+    let mut tables = Vec::new();
+    for i in 0..num_tables as usize {
+        let record = offset + 12 + 16 * i;
+        let tag = bytes.get(record..record + 4).ok_or_else(|| invalid("truncated table directory"))?;
+        let start = u32_at(bytes, record + 8).unwrap_or(0) as usize;
+        let len = u32_at(bytes, record + 12).unwrap_or(0) as usize;
+        if let Some(data) = bytes.get(start..start + len) {
+            tables.push(Table {
+                tag: [tag[0], tag[1], tag[2], tag[3]],
+                data: Cow::Borrowed(data),
+            });
+        }
+    }
+    Ok(tables)
+}
+
+/// the max size of a decompressed WOFF table, far over the size of
+/// the tables we read, so that a corrupt file can't make us allocate
+/// gigabytes
+const MAX_WOFF_TABLE_LEN: usize = 16 * 1024 * 1024;
+
+/// read the tables of a WOFF font, decompressing them when needed
+fn woff_tables(bytes: &[u8]) -> Result<Vec<Table<'_>>, ProgramError> {
+    let num_tables = u16_at(bytes, 12).ok_or_else(|| invalid("truncated header"))?;
+    let mut tables = Vec::new();
+    for i in 0..num_tables as usize {
+        let record = 44 + 20 * i;
+        let tag = bytes.get(record..record + 4).ok_or_else(|| invalid("truncated table directory"))?;
+        let tag = [tag[0], tag[1], tag[2], tag[3]];
+        if !matches!(&tag, b"name" | b"maxp" | b"OS/2" | b"cmap") {
+            continue; // no need to decompress tables we don't read
+        }
+        let start = u32_at(bytes, record + 4).unwrap_or(0) as usize;
+        let comp_len = u32_at(bytes, record + 8).unwrap_or(0) as usize;
+        let orig_len = u32_at(bytes, record + 12).unwrap_or(0) as usize;
+        let data = match bytes.get(start..start + comp_len) {
+            Some(data) => data,
+            None => continue,
+        };
+        let data = if comp_len < orig_len {
+            if orig_len > MAX_WOFF_TABLE_LEN {
+                return Err(invalid("table too big"));
+            }
+            let mut decompressed = Vec::with_capacity(orig_len);
+            ZlibDecoder::new(data)
+                .take(orig_len as u64)
+                .read_to_end(&mut decompressed)?;
+            Cow::Owned(decompressed)
+        } else {
+            Cow::Borrowed(data)
+        };
+        tables.push(Table { tag, data });
+    }
+    Ok(tables)
+}
+
+/// read a string of the name table, preferring the english
+/// Windows version
+fn read_name(data: &[u8], name_id: u16) -> Option<String> {
+    let count = u16_at(data, 2)? as usize;
+    let storage = u16_at(data, 4)? as usize;
+    let mut best: Option<(u8, String)> = None;
+    for i in 0..count {
+        let record = 6 + 12 * i;
+        if u16_at(data, record + 6)? != name_id {
+            continue;
+        }
+        let platform = u16_at(data, record)?;
+        let language = u16_at(data, record + 4)?;
+        let len = u16_at(data, record + 8)? as usize;
+        let start = storage + u16_at(data, record + 10)? as usize;
+        let raw = match data.get(start..start + len) {
+            Some(raw) => raw,
+            None => continue,
+        };
+        let (score, s) = match platform {
+            3 => {
+                let units: Vec<u16> = raw.chunks_exact(2)
+                    .map(|b| u16::from_be_bytes([b[0], b[1]]))
+                    .collect();
+                let score = if language == 0x409 { 3 } else { 2 };
+                (score, String::from_utf16_lossy(&units))
+            }
+            0 => {
+                let units: Vec<u16> = raw.chunks_exact(2)
+                    .map(|b| u16::from_be_bytes([b[0], b[1]]))
+                    .collect();
+                (1, String::from_utf16_lossy(&units))
+            }
+            // Mac Roman, close enough to latin-1 for names
+            1 => (0, raw.iter().map(|&b| b as char).collect()),
+            _ => continue,
+        };
+        if best.as_ref().map_or(true, |(best_score, _)| score > *best_score) {
+            best = Some((score, s));
+        }
+    }
+    best.map(|(_, s)| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// read the codepoints covered by the best unicode subtable of
+/// the cmap table
+fn read_cmap(data: &[u8]) -> Vec<RangeInclusive<u32>> {
+    let num_tables = u16_at(data, 2).unwrap_or(0) as usize;
+    let mut format_4 = None;
+    let mut format_12 = None;
+    for i in 0..num_tables {
+        let record = 4 + 8 * i;
+        let (platform, encoding, offset) = match (
+            u16_at(data, record),
+            u16_at(data, record + 2),
+            u32_at(data, record + 4),
+        ) {
+            (Some(platform), Some(encoding), Some(offset)) => (platform, encoding, offset),
+            _ => continue,
+        };
+        let offset = offset as usize;
+        let unicode = platform == 0 || (platform == 3 && (encoding == 1 || encoding == 10));
+        if !unicode {
+            continue;
+        }
+        match u16_at(data, offset) {
+            Some(4) => format_4 = format_4.or(Some(offset)),
+            Some(12) => format_12 = format_12.or(Some(offset)),
+            _ => {}
+        }
+    }
+    let ranges = if let Some(offset) = format_12 {
+        read_cmap_12(data, offset)
+    } else if let Some(offset) = format_4 {
+        read_cmap_4(data, offset)
+    } else {
+        None
+    };
+    merge(ranges.unwrap_or_default())
+}
+
+fn read_cmap_4(data: &[u8], offset: usize) -> Option<Vec<RangeInclusive<u32>>> {
+    let seg_count = u16_at(data, offset + 6)? as usize / 2;
+    let end_codes = offset + 14;
+    let start_codes = end_codes + 2 * seg_count + 2;
+    let id_deltas = start_codes + 2 * seg_count;
+    let id_range_offsets = id_deltas + 2 * seg_count;
+    let mut ranges = Vec::new();
+    for seg in 0..seg_count {
+        let end = u16_at(data, end_codes + 2 * seg)?;
+        let start = u16_at(data, start_codes + 2 * seg)?;
+        let delta = u16_at(data, id_deltas + 2 * seg)?;
+        let range_offset_pos = id_range_offsets + 2 * seg;
+        let range_offset = u16_at(data, range_offset_pos)? as usize;
+        if start == 0xFFFF || start > end {
+            continue;
+        }
+        if range_offset == 0 {
+            // glyph is c + delta, only 0 when wrapping to .notdef
+            for c in start..=end {
+                if c.wrapping_add(delta) != 0 {
+                    ranges.push(c as u32..=c as u32);
+                }
+            }
+        } else {
+            for c in start..=end {
+                let glyph_pos = range_offset_pos + range_offset + 2 * (c - start) as usize;
+                if u16_at(data, glyph_pos).map_or(false, |g| g != 0) {
+                    ranges.push(c as u32..=c as u32);
+                }
+            }
+        }
+    }
+    Some(ranges)
+}
+
+fn read_cmap_12(data: &[u8], offset: usize) -> Option<Vec<RangeInclusive<u32>>> {
+    let group_count = u32_at(data, offset + 12)? as usize;
+    let mut ranges = Vec::new();
+    for i in 0..group_count {
+        let group = offset + 16 + 12 * i;
+        let start = u32_at(data, group)?;
+        let end = u32_at(data, group + 4)?;
+        if start <= end {
+            ranges.push(start..=end);
+        }
+    }
+    Some(ranges)
+}
+
+/// sort the ranges and merge the adjacent or overlapping ones
+fn merge(mut ranges: Vec<RangeInclusive<u32>>) -> Vec<RangeInclusive<u32>> {
+    ranges.sort_by_key(|r| *r.start());
+    let mut merged: Vec<RangeInclusive<u32>> = Vec::new();
+    for r in ranges {
+        if let Some(last) = merged.last_mut() {
+            if *r.start() <= last.end().saturating_add(1) {
+                if r.end() > last.end() {
+                    *last = *last.start()..=*r.end();
+                }
+                continue;
+            }
+        }
+        merged.push(r);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod font_info_tests {
+
+    use super::*;
+
+    #[test]
+    fn check_range_merging() {
+        let merged = merge(vec![10..=12, 1..=3, 4..=4, 11..=15, 20..=20]);
+        assert_eq!(merged, vec![1..=4, 10..=15, 20..=20]);
+    }
+
+    #[test]
+    fn check_woff_table_size_limit() {
+        let mut bytes = vec![0; 68];
+        bytes[..4].copy_from_slice(b"wOFF");
+        bytes[12..14].copy_from_slice(&1u16.to_be_bytes());
+        bytes[44..48].copy_from_slice(b"name");
+        bytes[48..52].copy_from_slice(&64u32.to_be_bytes()); // offset
+        bytes[52..56].copy_from_slice(&4u32.to_be_bytes()); // compressed length
+        bytes[56..60].copy_from_slice(&u32::MAX.to_be_bytes()); // original length
+        assert!(woff_tables(&bytes).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use {
+    super::FontInfo,
+    crate::{
+        command::ScrollCommand,
+        display::{Screen, W},
+        errors::ProgramError,
+        skin::PanelSkin,
+    },
+    crokey::crossterm::{
+        cursor,
+        QueueableCommand,
+    },
+    std::{
+        ops::RangeInclusive,
+        path::Path,
+    },
+    termimad::{Area, CompoundStyle, CropWriter, SPACE_FILLING},
+};
+
+/// the extensions of the files which are previewed as fonts
+static FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "otc", "woff"];
+
+/// the unicode blocks whose coverage is summarized
+static BLOCKS: &[(&str, RangeInclusive<u32>)] = &[
+    ("Basic Latin", 0x20..=0x7E),
+    ("Latin-1 Supplement", 0xA0..=0xFF),
+    ("Latin Extended-A", 0x100..=0x17F),
+    ("Latin Extended-B", 0x180..=0x24F),
+    ("Greek", 0x370..=0x3FF),
+    ("Cyrillic", 0x400..=0x4FF),
+    ("Hebrew", 0x590..=0x5FF),
+    ("Arabic", 0x600..=0x6FF),
+    ("Devanagari", 0x900..=0x97F),
+    ("Thai", 0xE00..=0xE7F),
+    ("General Punctuation", 0x2000..=0x206F),
+    ("Currency Symbols", 0x20A0..=0x20CF),
+    ("Arrows", 0x2190..=0x21FF),
+    ("Math Operators", 0x2200..=0x22FF),
+    ("Box Drawing", 0x2500..=0x257F),
+    ("Hiragana", 0x3040..=0x309F),
+    ("Katakana", 0x30A0..=0x30FF),
+    ("CJK Ideographs", 0x4E00..=0x9FFF),
+    ("Hangul Syllables", 0xAC00..=0xD7AF),
+    ("Private Use Area", 0xE000..=0xF8FF),
+    ("Emoji", 0x1F300..=0x1FAFF),
+];
+
+static SAMPLES: &[&str] = &[
+    "The quick brown fox jumps over the lazy dog",
+    "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG",
+    "0123456789 .,;:!? ()[]{} @#&%*+-=/",
+];
+
+/// a line of the font preview
+enum FontLine {
+    Field(&'static str, String),
+    Header(&'static str),
+    Text(String),
+    Empty,
+}
+
+/// a preview describing a font file: its names, style, glyph
+/// coverage and a sample.
+///
+/// The sample is written with the terminal's font, as there's no
+/// rasterization: it's mostly useful to see which of its characters
+/// the font lacks.
+pub struct FontView {
+    lines: Vec<FontLine>,
+    scroll: usize,
+    page_height: usize,
+}
+
+impl FontView {
+    /// tell whether the extension of the file is the one of a
+    /// supported font format
+    pub fn is_font_path(path: &Path) -> bool {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map_or(false, |e| {
+                FONT_EXTENSIONS.iter().any(|fe| fe.eq_ignore_ascii_case(e))
+            })
+    }
+    pub fn new(path: &Path) -> Result<Self, ProgramError> {
+        let info = FontInfo::read(path)?;
+        let mut lines = Vec::new();
+        let mut field = |label, value: Option<String>| {
+            if let Some(value) = value {
+                lines.push(FontLine::Field(label, value));
+            }
+        };
+        field("Family", info.family.clone());
+        field("Style", info.subfamily.clone());
+        field("Full name", info.full_name.clone());
+        field("Version", info.version.clone());
+        field("Weight", info.weight.map(|w| w.to_string()));
+        let format = if info.font_count > 1 {
+            format!("{} ({} fonts, first one described)", info.format.name(), info.font_count)
+        } else {
+            info.format.name().to_string()
+        };
+        field("Format", Some(format));
+        field("Glyphs", info.glyph_count.map(|c| c.to_string()));
+        field("Codepoints", Some(info.codepoint_count().to_string()));
+        lines.push(FontLine::Empty);
+        lines.push(FontLine::Header("Coverage"));
+        for (name, block) in BLOCKS {
+            let count = info.count_in(block);
+            if count > 0 {
+                let len = (block.end() - block.start() + 1) as usize;
+                lines.push(FontLine::Field(name, format!("{count:>5} / {len}")));
+            }
+        }
+        lines.push(FontLine::Empty);
+        lines.push(FontLine::Header("Sample (in the terminal's font)"));
+        for sample in SAMPLES {
+            lines.push(FontLine::Text(sample.to_string()));
+        }
+        let mut missing: Vec<char> = SAMPLES.iter()
+            .flat_map(|s| s.chars())
+            .filter(|&c| c != ' ' && !info.covers(c))
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+        if !missing.is_empty() {
+            let missing: String = missing.into_iter().collect();
+            lines.push(FontLine::Field("Missing", missing));
+        }
+        Ok(Self {
+            lines,
+            scroll: 0,
+            page_height: 0,
+        })
+    }
+    pub fn try_scroll(
+        &mut self,
+        cmd: ScrollCommand,
+    ) -> bool {
+        let old_scroll = self.scroll;
+        self.scroll = cmd.apply(self.scroll, self.lines.len(), self.page_height);
+        self.scroll != old_scroll
+    }
+    pub fn select_first(&mut self) {
+        self.scroll = 0;
+    }
+    pub fn select_last(&mut self) {
+        if self.page_height < self.lines.len() {
+            self.scroll = self.lines.len() - self.page_height;
+        }
+    }
+    pub fn display(
+        &mut self,
+        w: &mut W,
+        _screen: Screen,
+        panel_skin: &PanelSkin,
+        area: &Area,
+    ) -> Result<(), ProgramError> {
+        let styles = &panel_skin.styles;
+        self.page_height = area.height as usize;
+        let label_style: &CompoundStyle = &styles.preview_line_number;
+        for y in 0..area.height {
+            w.queue(cursor::MoveTo(area.left, area.top + y))?;
+            let mut cw = CropWriter::new(w, area.width as usize);
+            match self.lines.get(self.scroll + y as usize) {
+                Some(FontLine::Field(label, value)) => {
+                    cw.queue_g_string(label_style, format!(" {label:<20}"))?;
+                    cw.queue_char(&styles.preview, ' ')?;
+                    cw.queue_str(&styles.preview, value)?;
+                }
+                Some(FontLine::Header(header)) => {
+                    cw.queue_str(&styles.help_headers, header)?;
+                }
+                Some(FontLine::Text(text)) => {
+                    cw.queue_char(&styles.preview, ' ')?;
+                    cw.queue_str(&styles.preview, text)?;
+                }
+                Some(FontLine::Empty) | None => {}
+            }
+            cw.fill(&styles.preview, &SPACE_FILLING)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+//! Description of font files, with their names, style and
+//! glyph coverage, for the preview
+
+mod font_info;
+mod font_view;
+
+pub use {
+    font_info::*,
+    font_view::FontView,
+};
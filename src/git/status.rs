@@ -25,6 +25,17 @@ impl LineGitStatus {
     pub fn is_interesting(self) -> bool {
         self.status.intersects(INTERESTING)
     }
+    /// the name of the status, as written in tree exports
+    pub fn name(self) -> &'static str {
+        match self.status {
+            Status::CURRENT => "current",
+            Status::WT_NEW => "new",
+            Status::CONFLICTED => "conflicted",
+            Status::WT_MODIFIED => "modified",
+            Status::IGNORED => "ignored",
+            _ => "other",
+        }
+    }
 }
 
 /// As a git repo can't tell whether a path has a status, this computer
@@ -1,8 +1,10 @@
 use {
+    crate::tr,
     termimad::minimad::{TextTemplate, TextTemplateExpander},
 };
 
-static MD: &str = r#"
+/// the English template of the help page
+pub static HELP_MD: &str = r#"
 
 # broot ${version}
 
@@ -81,6 +83,6 @@ ${features
 /// produce the markdown of the help page
 pub fn expander() -> TextTemplateExpander<'static, 'static> {
     use once_cell::sync::Lazy;
-    static TEMPLATE: Lazy<TextTemplate<'static>> = Lazy::new(|| TextTemplate::from(MD));
+    static TEMPLATE: Lazy<TextTemplate<'static>> = Lazy::new(|| TextTemplate::from(tr!(help_page)));
     TEMPLATE.expander()
 }
@@ -28,6 +28,7 @@ pub fn search_mode_help(mode: SearchMode, con: &AppContext) -> SearchModeHelp {
             SearchObject::Name => "file name",
             SearchObject::Path => "sub path",
             SearchObject::Content => "file content",
+            SearchObject::Tag => "file tags",
         },
     );
     let example = match mode {
@@ -41,6 +42,7 @@ pub fn search_mode_help(mode: SearchMode, con: &AppContext) -> SearchModeHelp {
         SearchMode::PathTokens => format!("`{prefix}help,doc` matches *website/docs/help.md*"),
         SearchMode::ContentExact => format!("`{prefix}find(` matches a file containing *a.find(b);*"),
         SearchMode::ContentRegex => format!("`{prefix}find/i` matches a file containing *A::Find(b)*"),
+        SearchMode::TagExact => format!("`{prefix}work` matches the files tagged *work*"),
     };
     SearchModeHelp {
         prefix,
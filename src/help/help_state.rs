@@ -26,6 +26,7 @@ pub struct HelpState {
     pattern: Pattern,
     tree_options: TreeOptions,
     config_path: PathBuf, // the last config path when several were used
+    verb_usage: VerbUsage, // orders the verbs, the most used first
     mode: Mode,
 }
 
@@ -33,6 +34,7 @@ impl HelpState {
     pub fn new(
         tree_options: TreeOptions,
         _screen: Screen,
+        verb_usage: &VerbUsage,
         con: &AppContext,
     ) -> HelpState {
         let text_area = Area::uninitialized(); // will be fixed at drawing time
@@ -47,6 +49,7 @@ impl HelpState {
             pattern: Pattern::None,
             tree_options,
             config_path,
+            verb_usage: verb_usage.clone(),
             mode: initial_mode(con),
         }
     }
@@ -91,6 +94,8 @@ impl PanelState for HelpState {
             stype: SelectionType::File,
             is_exe: false,
             line: 0,
+            line_range: None,
+            text_view: None,
         })
     }
 
@@ -135,7 +140,7 @@ impl PanelState for HelpState {
             expander.sub("config-files")
                 .set("path", path);
         }
-        let verb_rows = super::help_verbs::matching_verb_rows(&self.pattern, con);
+        let verb_rows = super::help_verbs::matching_verb_rows(&self.pattern, &self.verb_usage, con);
         for row in &verb_rows {
             let sub = expander
                 .sub("verb-rows")
@@ -195,8 +200,9 @@ impl PanelState for HelpState {
             Some((self.text_area.width - 1) as usize),
         );
         let mut text_view = TextView::from(&self.text_area, &fmt_text);
+        text_view.show_scrollbar = !disc.zen;
         self.scroll = text_view.set_scroll(self.scroll);
-        Ok(text_view.write_on(w)?)
+        crate::display::write_text_view(w, &text_view, &con.glyphs)
     }
 
     fn on_internal(
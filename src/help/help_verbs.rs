@@ -41,6 +41,7 @@ impl MatchingVerbRow<'_> {
 /// into account
 pub fn matching_verb_rows<'v>(
     pat: &Pattern,
+    verb_usage: &VerbUsage,
     con: &'v AppContext,
 ) -> Vec<MatchingVerbRow<'v>> {
     let mut rows = Vec::new();
@@ -74,5 +75,6 @@ pub fn matching_verb_rows<'v>(
             verb,
         });
     }
+    verb_usage.sort(&mut rows, |row| &row.verb.names[0]);
     rows
 }
@@ -0,0 +1,87 @@
+use {
+    super::search_mode_help,
+    crate::{
+        app::AppContext,
+        command::CommandParts,
+        pattern::*,
+        tr,
+        verb::*,
+    },
+    std::fmt::Write,
+};
+
+/// the maximal number of verbs listed when the typed name is ambiguous
+const MAX_LISTED_VERBS: usize = 12;
+
+/// build the markdown of the cheat sheet of the input syntax
+/// applicable at the end of `input`, which is the part of the
+/// input before the cursor
+pub fn input_help_md(input: &str, con: &AppContext) -> String {
+    let parts = CommandParts::from(input);
+    let mut md = String::new();
+    match &parts.verb_invocation {
+        Some(invocation) => write_verb_help(&mut md, invocation, con),
+        None => write_pattern_help(&mut md, &parts, con),
+    }
+    md
+}
+
+fn write_pattern_help(md: &mut String, parts: &CommandParts, con: &AppContext) {
+    let atom = parts.pattern.current_atom();
+    let mode = atom
+        .and_then(|atom| atom.mode())
+        .and_then(|key| con.search_modes.search_mode(Some(key)).ok());
+    if let Some(mode) = mode {
+        let help = search_mode_help(mode, con);
+        let _ = writeln!(
+            md,
+            "{}",
+            tr!(input_help_example, description = help.description, example = help.example),
+        );
+        if atom.map_or(false, |atom| atom.is_between_slashes()) {
+            md.push_str(tr!(input_help_slash));
+            if mode.kind() == SearchKind::Regex {
+                md.push_str(tr!(input_help_regex_flags));
+            }
+            md.push('\n');
+        }
+    } else {
+        md.push_str("|:-:|:-|:-\n|**prefix**|**search**|**example**\n|-:|:-|:-\n");
+        for mode in SEARCH_MODES {
+            let help = search_mode_help(*mode, con);
+            let _ = writeln!(md, "|`{}`|{}|{}", help.prefix, help.description, help.example);
+        }
+        md.push_str("|-\n");
+    }
+    let _ = writeln!(md, "{}", tr!(input_help_operators));
+    let _ = writeln!(md, "{}", tr!(input_help_escape));
+}
+
+fn write_verb_help(md: &mut String, invocation: &VerbInvocation, con: &AppContext) {
+    let _ = writeln!(md, "{}", tr!(input_help_verb));
+    if invocation.name.is_empty() {
+        let _ = writeln!(md, "{}", tr!(input_help_verb_start));
+        return;
+    }
+    match con.verb_store.search_prefix(&invocation.name) {
+        PrefixSearchResult::NoMatch => {
+            let _ = writeln!(md, "{}", tr!(input_help_no_verb, name = &invocation.name));
+        }
+        PrefixSearchResult::Match(_, verb) => {
+            let pattern = verb.invocation_parser
+                .as_ref()
+                .map_or_else(|| verb.names[0].clone(), |p| p.invocation_pattern.to_string_for_name(&p.invocation_pattern.name));
+            let _ = writeln!(md, "`{}` : {}", pattern, &verb.description.content);
+        }
+        PrefixSearchResult::Matches(names) => {
+            md.push_str(tr!(input_help_possible_verbs));
+            for name in names.iter().take(MAX_LISTED_VERBS) {
+                let _ = write!(md, " `{}`", name);
+            }
+            if names.len() > MAX_LISTED_VERBS {
+                md.push_str(" …");
+            }
+            md.push('\n');
+        }
+    }
+}
@@ -3,8 +3,17 @@ mod help_features;
 mod help_search_modes;
 mod help_state;
 mod help_verbs;
+mod input_help;
+mod tips;
+mod tips_state;
+mod verb_choice_state;
 
 pub use {
+    help_content::HELP_MD,
     help_state::HelpState,
     help_search_modes::*,
+    input_help::input_help_md,
+    tips::*,
+    tips_state::TipsState,
+    verb_choice_state::VerbChoiceState,
 };
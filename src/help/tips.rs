@@ -0,0 +1,254 @@
+use {
+    crate::{
+        app::{PanelStateType, SelectionType},
+        keys::KEY_FORMAT,
+        verb::VerbStore,
+    },
+    std::time::{SystemTime, UNIX_EPOCH},
+};
+
+/// how long a tip stays in the status before the next one
+const TIP_DURATION_SECS: u64 = 30;
+
+/// what the user must do to apply a tip
+#[derive(Debug, Clone, Copy)]
+enum TipTrigger {
+    /// calling a verb, by its key or by typing its shortest name
+    Verb(&'static str),
+    /// typing some text in the input
+    Typed(&'static str),
+}
+
+/// the definition of a tip, whose trigger is solved against
+/// the configured verbs
+struct TipDef {
+    trigger: TipTrigger,
+    states: &'static [PanelStateType], // empty when applying everywhere
+    stype: Option<SelectionType>,
+    filtered: Option<bool>,
+    action: &'static str,
+}
+
+use PanelStateType::*;
+
+static TIP_DEFS: &[TipDef] = &[
+    TipDef {
+        trigger: TipTrigger::Typed("c/"),
+        states: &[Tree],
+        stype: None,
+        filtered: Some(false),
+        action: "then a word to search the content of files",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("total_search"),
+        states: &[Tree],
+        stype: None,
+        filtered: Some(true),
+        action: "to search all files when the search stopped early",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("exclude"),
+        states: &[Tree],
+        stype: None,
+        filtered: Some(true),
+        action: "then a pattern to remove some matches",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("input_help"),
+        states: &[],
+        stype: None,
+        filtered: Some(true),
+        action: "to see the syntax of the input",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("panel_right"),
+        states: &[Tree],
+        stype: Some(SelectionType::File),
+        filtered: None,
+        action: "to preview the file",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("toggle_hidden"),
+        states: &[Tree],
+        stype: None,
+        filtered: None,
+        action: "to show or hide hidden files",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("toggle_sizes"),
+        states: &[Tree],
+        stype: None,
+        filtered: Some(false),
+        action: "to show the sizes of files and directories",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("sort_by_date"),
+        states: &[Tree],
+        stype: None,
+        filtered: Some(false),
+        action: "to sort by date, most recent first",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("copy_to_panel"),
+        states: &[Tree],
+        stype: None,
+        filtered: None,
+        action: "to copy the selection to the other panel",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("mkdir"),
+        states: &[Tree],
+        stype: Some(SelectionType::Directory),
+        filtered: Some(false),
+        action: "then a name to create a directory",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("toggle_stage"),
+        states: &[Tree],
+        stype: None,
+        filtered: None,
+        action: "to add the selection to the staging area",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("print_path"),
+        states: &[Tree],
+        stype: None,
+        filtered: None,
+        action: "to quit broot and print the path of the selection",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("quick_roots"),
+        states: &[Tree],
+        stype: None,
+        filtered: Some(false),
+        action: "to list your quick roots",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("filesystems"),
+        states: &[Tree],
+        stype: None,
+        filtered: Some(false),
+        action: "to see the usage of all filesystems",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("toggle_only_disks"),
+        states: &[Fs],
+        stype: None,
+        filtered: None,
+        action: "to list only the filesystems on disks",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("toggle_tail_follow"),
+        states: &[Preview],
+        stype: None,
+        filtered: Some(false),
+        action: "to pause or resume the following of a growing file",
+    },
+    TipDef {
+        trigger: TipTrigger::Verb("help"),
+        states: &[],
+        stype: None,
+        filtered: Some(false),
+        action: "to see all verbs and their keys",
+    },
+];
+
+/// a hint about a feature, shown in the status bar
+#[derive(Debug, Clone)]
+pub struct Tip {
+    pub states: &'static [PanelStateType],
+    pub stype: Option<SelectionType>,
+    pub filtered: Option<bool>,
+    /// true when the trigger is a key
+    pub is_key: bool,
+    /// the key to hit or the text to type
+    pub trigger: String,
+    pub action: &'static str,
+}
+
+impl Tip {
+    /// make the tip from its definition, unless it's about
+    /// a verb which isn't available
+    fn from_def(def: &TipDef, verb_store: &VerbStore) -> Option<Self> {
+        let (is_key, trigger) = match def.trigger {
+            TipTrigger::Typed(s) => (false, s.to_string()),
+            TipTrigger::Verb(name) => {
+                let verb = verb_store.verbs.iter().find(|v| v.has_name(name))?;
+                let key = verb.keys.first().filter(|_| {
+                    def.states.iter().all(|&t| verb.keys_apply_in_panel(t))
+                });
+                match key {
+                    Some(&key) => (true, KEY_FORMAT.to_string(key)),
+                    None => {
+                        let name = verb.names.iter().min_by_key(|n| n.len())?;
+                        (false, format!(":{}", name))
+                    }
+                }
+            }
+        };
+        Some(Self {
+            states: def.states,
+            stype: def.stype,
+            filtered: def.filtered,
+            is_key,
+            trigger,
+            action: def.action,
+        })
+    }
+    pub fn applies(
+        &self,
+        state_type: PanelStateType,
+        stype: SelectionType,
+        filtered: bool,
+    ) -> bool {
+        (self.states.is_empty() || self.states.contains(&state_type))
+            && self.stype.map_or(true, |t| t == stype)
+            && self.filtered.map_or(true, |f| f == filtered)
+    }
+    /// where the tip applies, for display in the list of tips
+    pub fn context(&self) -> String {
+        if self.states.is_empty() {
+            "any panel".to_string()
+        } else {
+            self.states.iter()
+                .map(|t| t.name())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        }
+    }
+    pub fn md(&self) -> String {
+        format!(
+            "{} *{}* {}",
+            if self.is_key { "hit" } else { "type" },
+            &self.trigger,
+            self.action,
+        )
+    }
+}
+
+/// build the tips, from the configured verbs
+pub fn build_tips(verb_store: &VerbStore) -> Vec<Tip> {
+    TIP_DEFS.iter()
+        .filter_map(|def| Tip::from_def(def, verb_store))
+        .collect()
+}
+
+/// choose, among the tips applying in the given context, the
+/// one for the current period of time
+pub fn current_tip(
+    tips: &[Tip],
+    state_type: PanelStateType,
+    stype: SelectionType,
+    filtered: bool,
+) -> Option<&Tip> {
+    let applicable: Vec<&Tip> = tips.iter()
+        .filter(|tip| tip.applies(state_type, stype, filtered))
+        .collect();
+    if applicable.is_empty() {
+        return None;
+    }
+    let period = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / TIP_DURATION_SECS);
+    Some(applicable[period as usize % applicable.len()])
+}
@@ -0,0 +1,155 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::path::Path,
+    termimad::minimad::Alignment,
+};
+
+/// an application state listing all the tips which may
+/// be displayed in the status
+pub struct TipsState {
+    list: FilteredList<Tip>,
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+impl TipsState {
+    pub fn new(
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> TipsState {
+        TipsState {
+            list: FilteredList::new(build_tips(&con.verb_store), matches),
+            tree_options,
+            mode: initial_mode(con),
+        }
+    }
+}
+
+fn matches(tip: &Tip, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&tip.trigger).is_some()
+        || pattern.score_of_string(tip.action).is_some()
+}
+
+impl PanelState for TipsState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Tips
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn selected_path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        None
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let w_context = list.visible()
+            .map(|t| t.context().chars().count())
+            .max().unwrap_or(0)
+            .max("panel".len());
+        let w_trigger = list.visible()
+            .map(|t| t.trigger.chars().count())
+            .max().unwrap_or(0)
+            .max("key or input".len());
+        let columns = [
+            ListColumn::new("panel", w_context, Alignment::Left),
+            ListColumn::new("key or input", w_trigger, Alignment::Left),
+        ];
+        let styles = &disc.panel_skin.styles;
+        list.queue_table_head(w, disc, &columns, |cw| {
+            cw.queue_str(&styles.default, "action")?;
+            Ok(())
+        })?;
+        self.list.queue_table_rows(w, disc, |cw, tip, row| {
+            row.queue_cell(cw, &tip.context(), row.txt_style, w_context, Alignment::Left)?;
+            row.queue_matched_cell(cw, &tip.trigger, row.txt_style, w_trigger, Alignment::Left)?;
+            row.queue_matched(cw, tip.action, row.txt_style)
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        self.on_internal_generic(
+            w,
+            internal_exec,
+            input_invocation,
+            trigger_type,
+            app_state,
+            cc,
+        )
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
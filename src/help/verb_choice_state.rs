@@ -0,0 +1,192 @@
+use {
+    crate::{
+        app::*,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::path::Path,
+    termimad::minimad::Alignment,
+};
+
+/// a verb whose name starts with the typed one
+#[derive(Debug, Clone)]
+struct Choice {
+    name: String,
+    description: String,
+}
+
+/// an application state letting the user choose between the
+/// verbs matching an ambiguous invocation, the chosen one being
+/// then executed on the previous state
+pub struct VerbChoiceState {
+    list: FilteredList<Choice>,
+    invocation: VerbInvocation,
+    input_pattern: String, // pattern of the input when the verb was invoked
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+impl VerbChoiceState {
+    pub fn new(
+        verbs: Vec<(&str, &Verb)>,
+        invocation: VerbInvocation,
+        input_pattern: String,
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> VerbChoiceState {
+        let choices = verbs.into_iter()
+            .map(|(name, verb)| Choice {
+                name: name.to_string(),
+                description: verb.description.content.clone(),
+            })
+            .collect();
+        VerbChoiceState {
+            list: FilteredList::new(choices, matches),
+            invocation,
+            input_pattern,
+            tree_options,
+            mode: initial_mode(con),
+        }
+    }
+    /// the sequence executing the chosen verb with the
+    /// arguments of the ambiguous invocation
+    fn sequence(&self, choice: &Choice) -> Sequence {
+        let invocation = self.invocation.to_string_for_name(&choice.name);
+        Sequence::new_single(if self.input_pattern.is_empty() {
+            format!(":{}", invocation)
+        } else {
+            format!("{} {}", self.input_pattern, invocation)
+        })
+    }
+}
+
+fn matches(choice: &Choice, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&choice.name).is_some()
+}
+
+impl PanelState for VerbChoiceState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::VerbChoice
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn selected_path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        None
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let w_name = list.visible()
+            .map(|c| c.name.chars().count())
+            .max().unwrap_or(0)
+            .max("verb".len());
+        let styles = &disc.panel_skin.styles;
+        list.queue_table_head(
+            w,
+            disc,
+            &[ListColumn::new("verb", w_name, Alignment::Left)],
+            |cw| {
+                cw.queue_str(&styles.default, "description")?;
+                Ok(())
+            },
+        )?;
+        self.list.queue_table_rows(w, disc, |cw, choice, row| {
+            row.queue_matched_cell(cw, &choice.name, row.txt_style, w_name, Alignment::Left)?;
+            cw.queue_str(row.txt_style, &choice.description)?;
+            Ok(())
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay | Internal::open_leave => {
+                match self.list.selected() {
+                    Some(choice) => CmdResult::PopStateAndExecute {
+                        sequence: self.sequence(choice),
+                    },
+                    None => CmdResult::error(tr!(error_no_selected_verb)),
+                }
+            }
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
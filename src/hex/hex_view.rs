@@ -1,6 +1,7 @@
 use {
     super::byte::Byte,
     crate::{
+        app::DisplayContext,
         command::ScrollCommand,
         display::{Screen, W},
         errors::ProgramError,
@@ -46,6 +47,12 @@ impl HexView {
     pub fn line_count(&self) -> usize {
         self.len / 16 + usize::from(self.len % 16 != 0)
     }
+    pub fn get_scroll(&self) -> usize {
+        self.scroll
+    }
+    pub fn set_scroll(&mut self, scroll: usize) {
+        self.scroll = scroll.min(self.line_count().saturating_sub(1));
+    }
     pub fn try_scroll(
         &mut self,
         cmd: ScrollCommand,
@@ -94,10 +101,11 @@ impl HexView {
     pub fn display(
         &mut self,
         w: &mut W,
-        _screen: Screen,
-        panel_skin: &PanelSkin,
+        disc: &DisplayContext,
         area: &Area,
     ) -> Result<(), ProgramError> {
+        let panel_skin = &disc.panel_skin;
+        let con = &disc.con;
         let line_count = area.height as usize;
         self.page_height = area.height as usize;
         let page = self.get_page(self.scroll, line_count)?;
@@ -149,7 +157,7 @@ impl HexView {
             margin_around_adresses = true;
             //rem -= 2;
         }
-        let scrollbar = area.scrollbar(self.scroll, self.line_count());
+        let scrollbar = disc.scrollbar(area, self.scroll, self.line_count());
         let scrollbar_fg = styles.scrollbar_thumb.get_fg()
             .or_else(|| styles.preview.get_fg())
             .unwrap_or(Color::White);
@@ -207,7 +215,7 @@ impl HexView {
             cw.fill(&styles.default, &SPACE_FILLING)?;
             if is_thumb(y as u16 + area.top, scrollbar) {
                 w.queue(SetForegroundColor(scrollbar_fg))?;
-                w.queue(Print('▐'))?;
+                w.queue(Print(con.glyphs.scrollbar_thumb))?;
             } else {
                 w.queue(Print(' '))?;
             }
@@ -0,0 +1,138 @@
+use {
+    super::Msg,
+    crate::{
+        conf::{self, SerdeFormat, FORMATS},
+        errors::{ConfError, ProgramError},
+    },
+    fnv::FnvHashMap,
+    once_cell::sync::OnceCell,
+    std::{
+        env,
+        fmt::{self, Write},
+        path::PathBuf,
+    },
+};
+
+/// The translations of the messages in the language of the user
+#[derive(Debug)]
+struct Catalog {
+    language: String,
+    texts: FnvHashMap<String, String>,
+}
+
+static CATALOG: OnceCell<Catalog> = OnceCell::new();
+
+/// the language of the user's locale (eg "fr_FR"), if any
+fn locale_language() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|locale| locale.split(['.', '@']).next().map(|s| s.to_string()))
+        .filter(|language| language != "C" && language != "POSIX")
+}
+
+/// the paths of the translation files which may hold the given
+/// language, the most specific first (eg "pt_BR" before "pt")
+fn translation_paths(language: &str) -> Vec<PathBuf> {
+    let dir = conf::dir().join("translations");
+    let mut names = vec![language];
+    if let Some((short, _)) = language.split_once('_') {
+        names.push(short);
+    }
+    let mut paths = Vec::new();
+    for name in names {
+        for format in FORMATS {
+            paths.push(dir.join(format!("{}.{}", name, format.key())));
+        }
+    }
+    paths
+}
+
+/// Read the translations of the messages in the language set in
+/// configuration or, if it's "auto" or not set, in the one of the
+/// locale.
+///
+/// When there's no translation file for the language, messages are
+/// in English. This is an error only when the language was explicitly
+/// chosen.
+pub fn init_catalog(language: Option<&str>) -> Result<(), ProgramError> {
+    let (language, explicit) = match language {
+        None | Some("auto") => match locale_language() {
+            Some(language) => (language, false),
+            None => {
+                return Ok(());
+            }
+        },
+        Some(language) => (language.to_string(), true),
+    };
+    let path = translation_paths(&language).into_iter().find(|path| path.exists());
+    let path = match path {
+        Some(path) => path,
+        None if explicit && !language.starts_with("en") => {
+            return Err(ConfError::MissingTranslation { language }.into());
+        }
+        None => {
+            debug!("no translation for {:?}", language);
+            return Ok(());
+        }
+    };
+    info!("reading translations in {:?}", &path);
+    let texts: FnvHashMap<String, String> = SerdeFormat::read_file(&path)?;
+    for id in texts.keys() {
+        if !Msg::ALL.iter().any(|msg| msg.id() == id) {
+            warn!("unknown message in translation file: {:?}", id);
+        }
+    }
+    let _ = CATALOG.set(Catalog { language, texts });
+    Ok(())
+}
+
+/// write all messages, in the current language, in the format
+/// of a translation file, so that a translator may start from it
+pub fn translation_template() -> String {
+    let mut hjson = String::new();
+    let _ = writeln!(hjson, "# messages of broot {}", env!("CARGO_PKG_VERSION"));
+    if let Some(catalog) = CATALOG.get() {
+        let _ = writeln!(hjson, "# language: {}", catalog.language);
+    }
+    hjson.push_str("{\n");
+    for msg in Msg::ALL {
+        let text = serde_json::to_string(msg.text()).unwrap_or_default();
+        let _ = writeln!(hjson, "    {}: {}", msg.id(), text);
+    }
+    hjson.push_str("}\n");
+    hjson
+}
+
+impl Msg {
+    /// the text of the message, translated if possible
+    pub fn text(self) -> &'static str {
+        CATALOG.get()
+            .and_then(|catalog| catalog.texts.get(self.id()))
+            .map_or_else(|| self.default_text(), |text| text.as_str())
+    }
+    /// the text of the message with its `{name}` placeholders
+    /// replaced with the given values
+    pub fn format(self, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let mut text = self.text().to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod catalog_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_format_message() {
+        assert_eq!(
+            Msg::prompt_unmount.format(&[("fs", &"/dev/sda1"), ("mount_point", &"/home")]),
+            "Unmount `/dev/sda1` from `/home` ? Do it again to confirm",
+        );
+        assert!(Msg::ALL.iter().all(|msg| !msg.default_text().is_empty()));
+    }
+}
@@ -0,0 +1,179 @@
+use {
+    crate::help::HELP_MD,
+};
+
+macro_rules! Messages {
+    (
+        $($name:ident: $text:expr,)*
+    ) => {
+        /// A message of the interface, whose text may be translated
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[allow(non_camel_case_types)]
+        pub enum Msg {
+            $($name,)*
+        }
+        impl Msg {
+            pub const ALL: &'static [Msg] = &[$(Msg::$name,)*];
+            /// the identifier of the message in translation files
+            pub fn id(self) -> &'static str {
+                match self {
+                    $(Msg::$name => stringify!($name),)*
+                }
+            }
+            /// the English text of the message
+            pub fn default_text(self) -> &'static str {
+                match self {
+                    $(Msg::$name => $text,)*
+                }
+            }
+        }
+    }
+}
+
+// messages:
+//  id: "English text", where {name} is a placeholder
+Messages! {
+    hint_enter_go_up: "*enter* to go up",
+    hint_enter_focus: "*enter* to focus",
+    hint_key_cd: "*{key}* to cd",
+    hint_key_open_file: "*{key}* to open the file",
+    hint_key_open_quit: "*{key}* to open and quit",
+    hint_search: "a few letters to search",
+    hint_clear_filter: "*esc* to clear the filter",
+    hint_filter_preview: "a pattern to filter",
+    hint_key_reveal_text: "*{key}* to reveal the text",
+    hint_key_restore_filter: "*{key}* to restore the filter",
+    hint_go_back: "*esc* to go back",
+    hint_help: "*?* for help",
+    hint_verb: "a space then a verb",
+    status_hints: "Hit {hints}",
+    status_hints_separator: ", ",
+    status_hints_last_separator: ", or ",
+    status_tip: "Tip: {tip}",
+    status_generic: "Hit *esc* to get back, or a space to start a verb",
+    status_generic_with_tip: "Hit *esc* to get back. Tip: {tip}",
+    status_files_hidden: "Some files are hidden, use *{key}* to display them",
+    status_files_git_ignored: "Some files are git-ignored, use *{key}* to display them",
+    status_batch_conflicts: "Hit *enter* to execute the batch, *esc* to cancel",
+    status_dry_run_on: "*dry run*: external verbs are now displayed, not executed",
+    status_dry_run_off: "external verbs are now executed",
+    status_dry_run_command: "*dry run*, not executed: `{command}`",
+    status_dry_run_trash: "*dry run*, not trashed: {paths}",
+    status_indexing: "indexing in background",
+    status_nothing_renamed: "nothing renamed",
+    status_stage_exported: "{count} staged paths written to {path}",
+    status_verb_usage_forgotten: "verb usage forgotten",
+    status_bookmarked: "bookmarked as `{name}`",
+    verb_type: "Type a verb then *enter* to execute it (*?* for the list of verbs)",
+    verb_no_match: "No matching verb (*?* for the list of verbs)",
+    verb_possible: "Possible verbs: {verbs}",
+    verb_hit_enter: "Hit *enter* to **{verb}**: {description}",
+    verb_focus: "Hit *enter* to focus `{path}`",
+    prompt_unmount: "Unmount `{fs}` from `{mount_point}` ? Do it again to confirm",
+    prompt_mount: "Mount `{fs}` ? Do it again to confirm",
+    prompt_quit_staged: "{count} paths are staged. Quit anyway ? Do it again to confirm",
+    prompt_quit_tasks: "Some tasks aren't finished. Quit anyway ? Do it again to confirm",
+    input_help_example: "**{description}**, for example {example}",
+    input_help_slash: "A `/` ends the pattern",
+    input_help_regex_flags: ", it may be followed by flags (eg `i` for case insensitive)",
+    input_help_operators: "Combine patterns with `!` (not), `&` (and), `|` (or), and parenthesis",
+    input_help_escape: "A `\\` escapes the next char, a space or `:` starts a verb",
+    input_help_verb: "Type `:verb arguments` then *enter*, a `!` (as in `:!focus`) opens a new panel",
+    input_help_verb_start: "Type the start of a verb name, shortcut or alias (*?* lists the verbs)",
+    input_help_no_verb: "No verb starts with `{name}`",
+    input_help_possible_verbs: "Possible verbs:",
+    error_batch_executed: "the batch was already executed",
+    error_bookmark_expected_name: "expected a bookmark name, for example `:gb dev`",
+    error_bookmark_spaces: "a bookmark name can't contain spaces",
+    error_clipboard_disabled: "Clipboard feature not enabled at compilation",
+    error_clipboard_lines: "Clipboard error while copying lines",
+    error_clipboard_path: "Clipboard error while copying path",
+    error_copy_move_read_only: "copying and moving are disabled in read-only mode",
+    error_device_not_mounted: "this device isn't mounted",
+    error_diff_unix_only: "diff is only available on unix",
+    error_disabled_read_only: "{verb} is disabled in read-only mode",
+    error_encoding: "Can't display as {encoding}: {error}",
+    error_exclude_expected_pattern: "a pattern to exclude is needed",
+    error_expected_row: "expected the number of a visible row, between 1 and {max}",
+    error_file_count_not_positive: "the number of files must be positive",
+    error_file_index_disabled: "the file index isn't enabled (see the file_index setting)",
+    error_last_column: "the last column can't be hidden",
+    error_modal_disabled: "modal mode not enabled in configuration",
+    error_multi_selection_leaves_broot: "only verbs returning to broot on end can be executed on a multi-selection",
+    error_needs_br: "this verb needs broot to be launched as `br`. Try `broot --install` if necessary.",
+    error_needs_search: "this verb can be used only after a search",
+    error_no_added_root: "no root was added",
+    error_no_backup_location: "no backup location in configuration",
+    error_no_encoding: "no encoding provided",
+    error_no_extension_search: "files without extension can't be searched",
+    error_no_interrupted_operation: "no interrupted operation",
+    error_no_line_at_row: "no selectable line at row {row}",
+    error_no_marked_path: "no marked path",
+    error_no_parent: "no parent found",
+    error_no_path_to_rename: "no path to rename",
+    error_no_preview: "no open preview",
+    error_no_selected_bookmark: "no selected bookmark",
+    error_no_selected_change: "no selected change",
+    error_no_selected_conflict: "no selected conflict",
+    error_no_selected_container: "no selected container",
+    error_no_selected_extension: "no selected extension",
+    error_no_selected_file: "no selected file",
+    error_no_selected_item: "no selected item",
+    error_no_selected_line: "No selected line",
+    error_no_selected_path: "no selected path",
+    error_no_selected_preview_line: "No selected line in preview",
+    error_no_selected_quick_root: "no selected quick root",
+    error_no_selected_snapshot: "no selected snapshot",
+    error_no_selected_tag: "no selected tag",
+    error_no_selected_verb: "no selected verb",
+    error_no_selected_version: "no selected version",
+    error_no_selection: "no selection",
+    error_no_selection_to_open: "no selection to open",
+    error_no_selection_to_reveal: "no selection to reveal",
+    error_no_selection_to_trash: "no selection to trash",
+    error_no_trash_home: "no home directory for the trash",
+    error_no_volume_group: "the selected filesystem isn't in a group of volumes",
+    error_not_a_directory: "not a directory",
+    error_not_bookmarked: "the selected path isn't bookmarked",
+    error_not_conflicts_panel: "this panel doesn't list conflicts",
+    error_not_trash_panel: "this panel doesn't list the items of the trash",
+    error_not_trashed: "`{path}` not trashed: {error}",
+    error_not_versions_panel: "versions can only be restored from the list of versions",
+    error_not_watch_panel: "this panel isn't watching a path",
+    error_nothing_renamed: "nothing renamed: {error}",
+    error_nothing_to_copy: "Nothing to copy",
+    error_nothing_to_escalate: "no operation to escalate",
+    error_nothing_to_stage: "nothing to stage here",
+    error_path_no_parent: "{path} has no parent",
+    error_path_not_directory: "{path} isn't a directory",
+    error_permission_denied: "Permission denied. Type :escalate to run {commands}",
+    error_preview_not_regular: "only regular files can be previewed",
+    error_preview_not_searchable: "this preview can't be searched",
+    error_print_tree_expected_path: "expected a file path, for example `:print_tree_to tree.json`",
+    error_rate_expected_rating: "expected a rating between 0 and {max}, for example `:rate 3`",
+    error_restore_failed: "restore failed: {error}",
+    error_rollback_failures: "{count} operations couldn't be rolled back and must be checked",
+    error_root_already_indexed: "this root is already being indexed",
+    error_root_already_searched: "this root is already searched",
+    error_root_overlap: "the added root can't contain or be under the current one",
+    error_scan_unfinished: "the scan isn't finished",
+    error_search_already_total: "search was already total: all possible matches have been ranked",
+    error_sequence_multiple_selection: "sequences can't be executed on multiple selections",
+    error_stage_empty: "the stage is empty",
+    error_stage_export_expected_path: "expected a file path, for example `:stage_export staged.txt`",
+    error_stage_pipe_expected_command: "expected a command, for example `:stage_pipe -0 xargs -0 du -ch`",
+    error_stage_two_panels: "stage can't be displayed in two panels",
+    error_tag_expected_action: "expected `:tag add <tag>` or `:tag remove <tag>`",
+    error_tag_spaces: "a tag can't contain spaces",
+    error_trash_browse_not_dir: "only trashed directories can be browsed",
+    error_unknown_bookmark: "no bookmark named {name}",
+    error_unknown_tag_action: "unknown tag action: {action}",
+    error_unresolved_conflicts: "conflicts still to resolve: {count}",
+    error_unstage_needs_selection: "you must select a path to unstage",
+    error_verb_log_expected_path: "expected a file path, for example `:export_verb_log verbs.log`",
+    error_verb_needs_file: "this verb needs a file",
+    error_verb_needs_other_panel: "This verb needs another panel",
+    error_verb_needs_preview: "this verb needs the preview panel to be open",
+    error_verb_needs_selection: "This verb needs a selection",
+    help_page: HELP_MD,
+}
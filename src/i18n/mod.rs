@@ -0,0 +1,31 @@
+//! The translation of the messages of the interface.
+//!
+//! Messages are declared, with their English text, in `messages.rs`,
+//! and used with the `tr!` macro. Their translations are read at
+//! startup from a file of the `translations` directory of the
+//! configuration.
+
+/// get the text of a message, in the language of the user,
+/// with its `{name}` placeholders replaced by the given values.
+///
+/// Examples: `tr!(hint_help)`, `tr!(hint_key_cd, key = k)`
+#[macro_export]
+macro_rules! tr {
+    ($msg:ident) => {
+        $crate::i18n::Msg::$msg.text()
+    };
+    ($msg:ident, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::Msg::$msg.format(&[
+            $((stringify!($name), &$value as &dyn std::fmt::Display),)+
+        ])
+    };
+}
+
+mod catalog;
+mod messages;
+
+pub use {
+    catalog::*,
+    messages::Msg,
+    tr,
+};
@@ -0,0 +1,79 @@
+use {
+    super::ImageView,
+    crate::{
+        app::*,
+        display::{Screen, W},
+        errors::ProgramError,
+        skin::PanelSkin,
+    },
+    crokey::crossterm::{
+        cursor,
+        style::Color,
+        QueueableCommand,
+    },
+    std::path::Path,
+    termimad::{fill_bg, Area},
+};
+
+/// two images displayed side by side, for example to check
+/// the before and after versions of an asset
+pub struct ImageComparison {
+    left: ImageView,
+    right: ImageView,
+}
+
+impl ImageComparison {
+    pub fn new(left: &Path, right: &Path) -> Result<Self, ProgramError> {
+        Ok(Self {
+            left: ImageView::new(left)?,
+            right: ImageView::new(right)?,
+        })
+    }
+    /// the names of the compared files, as displayed in the title
+    pub fn title(&self) -> String {
+        format!("{} | {}", self.left.file_name(), self.right.file_name())
+    }
+    pub fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+        area: &Area,
+    ) -> Result<(), ProgramError> {
+        let styles = &disc.panel_skin.styles;
+        let bg = styles.preview.get_bg()
+            .or_else(|| styles.default.get_bg())
+            .unwrap_or(Color::AnsiValue(238));
+        // one column is kept between the images
+        let left_width = area.width.saturating_sub(1) / 2;
+        let right_width = area.width.saturating_sub(1) - left_width;
+        let left_area = Area::new(area.left, area.top, left_width, area.height);
+        let right_area = Area::new(area.left + left_width + 1, area.top, right_width, area.height);
+        self.left.display(w, disc, &left_area)?;
+        self.right.display(w, disc, &right_area)?;
+        for y in area.top..area.top + area.height {
+            w.queue(cursor::MoveTo(area.left + left_width, y))?;
+            fill_bg(w, 1, bg)?;
+        }
+        Ok(())
+    }
+    pub fn display_info(
+        &mut self,
+        w: &mut W,
+        _screen: Screen,
+        panel_skin: &PanelSkin,
+        area: &Area,
+    ) -> Result<(), ProgramError> {
+        let (lw, lh) = self.left.source_dimensions();
+        let (rw, rh) = self.right.source_dimensions();
+        let s = format!("{} x {} | {} x {}", lw, lh, rw, rh);
+        if s.len() > area.width as usize {
+            return Ok(());
+        }
+        w.queue(cursor::MoveTo(
+            area.left + area.width - s.len() as u16,
+            area.top,
+        ))?;
+        panel_skin.styles.default.queue(w, s)?;
+        Ok(())
+    }
+}
@@ -72,6 +72,14 @@ impl ImageView {
             kitty_image_id: None,
         })
     }
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map_or_else(|| "???".to_string(), |n| n.to_string_lossy().to_string())
+    }
+    pub fn source_dimensions(&self) -> (u32, u32) {
+        self.source_img.dimensions()
+    }
     pub fn is_png(&self) -> bool {
         match self.path.extension() {
             Some(ext) => ext == "png" || ext == "PNG",
@@ -117,8 +125,11 @@ impl ImageView {
             return Ok(());
         }
 
-        self.kitty_image_id = kitty_manager
-            .try_print_image(w, &self.source_img, area, bg, disc.count)?;
+        self.kitty_image_id = if disc.con.term_caps.kitty_graphics {
+            kitty_manager.try_print_image(w, &self.source_img, area, bg, disc.count)?
+        } else {
+            None
+        };
 
         if self.kitty_image_id.is_some() {
             return Ok(());
@@ -148,7 +159,7 @@ impl ImageView {
         let (width, height) = img.dimensions();
         debug!("resized image dimensions: {},{}", width, height);
         debug_assert!(width <= area.width as u32);
-        let mut double_line = DoubleLine::new(width as usize, disc.con.true_colors);
+        let mut double_line = DoubleLine::new(width as usize, disc.con.term_caps.true_colors);
         let mut y = area.top;
         let img_top_offset = (area.height - (height / 2) as u16) / 2;
         for _ in 0..img_top_offset {
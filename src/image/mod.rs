@@ -1,7 +1,9 @@
 
 mod double_line;
+mod image_comparison;
 mod image_view;
 
 pub use {
+    image_comparison::ImageComparison,
     image_view::ImageView,
 };
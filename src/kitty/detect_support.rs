@@ -8,8 +8,8 @@ use {
 /// Determine whether Kitty's graphics protocol is supported
 /// by the terminal running broot.
 ///
-/// This is called only once, and cached in the KittyManager's
-/// MaybeRenderer state
+/// This is called only once, at startup, when probing the
+/// terminal's capabilities
 #[allow(unreachable_code)]
 pub fn is_kitty_graphics_protocol_supported() -> bool {
     debug!("is_kitty_graphics_protocol_supported ?");
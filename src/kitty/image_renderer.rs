@@ -1,5 +1,4 @@
 use {
-    super::detect_support::is_kitty_graphics_protocol_supported,
     crate::{
         display::{
             cell_size_in_pixels,
@@ -198,11 +197,9 @@ impl<'i> KittyImage<'i> {
 }
 
 impl KittyImageRenderer {
-    /// Called only once (at most) by the KittyManager
+    /// Called only once (at most) by the KittyManager, when the
+    /// terminal is known to support the protocol
     pub fn new() -> Option<Self> {
-        if !is_kitty_graphics_protocol_supported() {
-            return None;
-        }
         cell_size_in_pixels()
             .ok()
             .map(|(cell_width, cell_height)| Self {
@@ -2,6 +2,7 @@ mod detect_support;
 mod image_renderer;
 
 pub use {
+    detect_support::is_kitty_graphics_protocol_supported,
     image_renderer::*,
 };
 
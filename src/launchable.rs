@@ -2,29 +2,34 @@ use {
     crate::{
         app::AppContext,
         display::{
+            invalidate_row_caches,
             DisplayableTree,
             Screen,
             W,
         },
         errors::ProgramError,
+        print::{self, TreePrintFormat},
         skin::{
             ExtColorMap,
+            Glyphs,
             StyleMap,
         },
         tree::Tree,
+        verb::Sandbox,
     },
     crokey::crossterm::{
         cursor,
         event::{DisableMouseCapture, EnableMouseCapture},
-        terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+        terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
         QueueableCommand,
     },
     opener,
     std::{
         env,
-        io::{self, Write},
+        io::{self, Read, Write},
         path::PathBuf,
-        process::Command,
+        process::{ChildStderr, Command, Stdio},
+        thread,
     },
 };
 
@@ -43,8 +48,11 @@ pub enum Launchable {
         tree: Box<Tree>,
         skin: Box<StyleMap>,
         ext_colors: ExtColorMap,
+        glyphs: Glyphs,
         width: u16,
         height: u16,
+        /// plain or ANSI text, HTML, JSON or CSV
+        format: TreePrintFormat,
     },
 
     /// execute an external program
@@ -52,7 +60,15 @@ pub enum Launchable {
         exe: String,
         args: Vec<String>,
         working_dir: Option<PathBuf>,
+        sandbox: Sandbox,
         capture_mouse: bool,
+        /// whether to watch the error output of the program for
+        /// a permission denial
+        detect_denial: bool,
+        /// the first row of broot when it's displayed inline
+        inline_top: Option<u16>,
+        /// what to write on the standard input of the program
+        stdin: Option<String>,
     },
 
     /// open a path
@@ -61,6 +77,57 @@ pub enum Launchable {
     },
 }
 
+/// the messages of the EACCES and EPERM errors as programs write
+/// them: in English, and in the language of the user's locale
+fn denial_messages() -> Vec<String> {
+    #[allow(unused_mut)] // only extended on some platforms
+    let mut messages = vec![
+        "Permission denied".to_string(),
+        "Operation not permitted".to_string(),
+    ];
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    unsafe {
+        // the locale is only changed for the calling thread
+        let locale = libc::newlocale(libc::LC_MESSAGES_MASK, b"\0".as_ptr() as _, std::ptr::null_mut());
+        if !locale.is_null() {
+            let previous = libc::uselocale(locale);
+            for errno in [libc::EACCES, libc::EPERM] {
+                let message = libc::strerror(errno);
+                if !message.is_null() {
+                    let message = std::ffi::CStr::from_ptr(message).to_string_lossy().to_string();
+                    if !messages.contains(&message) {
+                        messages.push(message);
+                    }
+                }
+            }
+            libc::uselocale(previous);
+            libc::freelocale(locale);
+        }
+    }
+    messages
+}
+
+/// Forward the error output of a program to the terminal while
+/// looking for a permission denial (EACCES or EPERM) in it
+fn watch_stderr(mut stderr: ChildStderr) -> thread::JoinHandle<bool> {
+    thread::spawn(move || {
+        let denial_messages = denial_messages();
+        let mut output = Vec::new();
+        let mut buf = [0; 1024];
+        while let Ok(n) = stderr.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            let mut terminal = io::stderr();
+            let _ = terminal.write_all(&buf[..n]);
+            let _ = terminal.flush();
+            output.extend_from_slice(&buf[..n]);
+        }
+        let output = String::from_utf8_lossy(&output);
+        denial_messages.iter().any(|message| output.contains(message.as_str()))
+    })
+}
+
 /// If a part starts with a '$', replace it by the environment variable of the same name.
 /// This part is split too (because of https://github.com/Canop/broot/issues/114)
 fn resolve_env_variables(parts: Vec<String>) -> Vec<String> {
@@ -89,19 +156,24 @@ impl Launchable {
         screen: Screen,
         style_map: StyleMap,
         ext_colors: ExtColorMap,
+        glyphs: Glyphs,
+        format: TreePrintFormat,
     ) -> Launchable {
         Launchable::TreePrinter {
             tree: Box::new(tree.clone()),
             skin: Box::new(style_map),
             ext_colors,
+            glyphs,
             width: screen.width,
-            height: (tree.lines.len() as u16).min(screen.height),
+            height: (1 + tree.visible_rows().count() as u16).min(screen.height),
+            format,
         }
     }
 
     pub fn program(
         parts: Vec<String>,
         working_dir: Option<PathBuf>,
+        sandbox: Sandbox,
         con: &AppContext,
     ) -> io::Result<Launchable> {
         let mut parts = resolve_env_variables(parts).into_iter();
@@ -110,12 +182,74 @@ impl Launchable {
                 exe,
                 args: parts.collect(),
                 working_dir,
-                capture_mouse: con.capture_mouse,
+                sandbox,
+                capture_mouse: con.term_caps.mouse,
+                detect_denial: false,
+                inline_top: con.inline_top,
+                stdin: None,
             }),
             None => Err(io::Error::new(io::ErrorKind::Other, "Empty launch string")),
         }
     }
 
+    /// make the execution fail with a PermissionDenied error when
+    /// the program fails for lack of permission
+    pub fn with_denial_detection(mut self) -> Self {
+        if let Launchable::Program { detect_denial, .. } = &mut self {
+            *detect_denial = true;
+        }
+        self
+    }
+
+    /// write the given string on the standard input of the program
+    pub fn with_stdin(mut self, input: String) -> Self {
+        if let Launchable::Program { stdin, .. } = &mut self {
+            *stdin = Some(input);
+        }
+        self
+    }
+
+    /// build the launchable running the same program through the
+    /// escalation command (eg `sudo`)
+    pub fn escalated(self, escalation_command: &[String]) -> Self {
+        match self {
+            Launchable::Program {
+                exe, args, working_dir, sandbox, capture_mouse, inline_top, stdin, ..
+            } => {
+                let mut parts = escalation_command.iter().cloned();
+                match parts.next() {
+                    Some(escalation_exe) => Launchable::Program {
+                        exe: escalation_exe,
+                        args: parts.chain(std::iter::once(exe)).chain(args).collect(),
+                        working_dir,
+                        sandbox,
+                        capture_mouse,
+                        detect_denial: false,
+                        inline_top,
+                        stdin,
+                    },
+                    None => Launchable::Program {
+                        exe, args, working_dir, sandbox, capture_mouse, detect_denial: false,
+                        inline_top, stdin,
+                    },
+                }
+            }
+            _ => self,
+        }
+    }
+
+    /// a human readable description of what will be launched
+    pub fn description(&self) -> String {
+        match self {
+            Launchable::Printer { .. } => "print".to_string(),
+            Launchable::TreePrinter { .. } => "print the tree".to_string(),
+            Launchable::Program { exe, args, .. } => {
+                std::iter::once(exe).chain(args).cloned().collect::<Vec<String>>().join(" ")
+            }
+            Launchable::SystemOpen { path } => format!("open {}", path.to_string_lossy()),
+        }
+    }
+
     pub fn execute(
         &self,
         mut w: Option<&mut W>,
@@ -125,24 +259,46 @@ impl Launchable {
                 println!("{}", to_print);
                 Ok(())
             }
-            Launchable::TreePrinter { tree, skin, ext_colors, width, height } => {
-                let dp = DisplayableTree::out_of_app(tree, skin, ext_colors, *width, *height);
-                dp.write_on(&mut std::io::stdout())
+            Launchable::TreePrinter { tree, skin, ext_colors, glyphs, width, height, format } => {
+                match format {
+                    TreePrintFormat::Plain | TreePrintFormat::Ansi => {
+                        let dp = DisplayableTree::out_of_app(tree, skin, ext_colors, glyphs, *width, *height);
+                        dp.write_on(&mut std::io::stdout())
+                    }
+                    _ => {
+                        let string = print::tree_to_string(
+                            tree, skin, ext_colors, glyphs, *width, *height, *format,
+                        )?;
+                        println!("{}", string.trim_end());
+                        Ok(())
+                    }
+                }
             }
             Launchable::Program {
                 working_dir,
                 exe,
                 args,
+                sandbox,
                 capture_mouse,
+                detect_denial,
+                inline_top,
+                stdin,
             } => {
                 debug!("working_dir: {:?}", &working_dir);
                 // we restore the normal terminal in case the executable
                 // is a terminal application, and we'll switch back to
                 // broot's alternate terminal when we're back to broot
                 // (and this part of the code should be cleaned...)
+                // When broot is inline, its lines are erased and it will
+                // be drawn again at the same place.
                 if let Some(ref mut w) = &mut w {
                     w.queue(cursor::Show).unwrap();
-                    w.queue(LeaveAlternateScreen).unwrap();
+                    if let Some(top) = inline_top {
+                        w.queue(cursor::MoveTo(0, *top)).unwrap();
+                        w.queue(Clear(ClearType::FromCursorDown)).unwrap();
+                    } else {
+                        w.queue(LeaveAlternateScreen).unwrap();
+                    }
                     if *capture_mouse {
                         w.queue(DisableMouseCapture).unwrap();
                     }
@@ -154,22 +310,57 @@ impl Launchable {
                     old_working_dir = std::env::current_dir().ok();
                     std::env::set_current_dir(working_dir).unwrap();
                 }
-                let exec_res = Command::new(exe)
-                    .args(args.iter())
+                let mut command = Command::new(exe);
+                command.args(args.iter());
+                sandbox.apply(&mut command);
+                if *detect_denial {
+                    command.stderr(Stdio::piped());
+                }
+                if stdin.is_some() {
+                    command.stdin(Stdio::piped());
+                }
+                let exec_res = command
                     .spawn()
-                    .and_then(|mut p| p.wait())
-                    .map_err(|source| ProgramError::LaunchError {
-                        program: exe.clone(),
-                        source,
+                    .and_then(|mut p| {
+                        if let (Some(input), Some(mut child_stdin)) = (stdin, p.stdin.take()) {
+                            // the pipe is closed when child_stdin is dropped, and
+                            // the program may have quit without reading it all
+                            if let Err(e) = child_stdin.write_all(input.as_bytes()) {
+                                warn!("failed to write on the stdin of {}: {}", exe, e);
+                            }
+                        }
+                        let stderr_watcher = p.stderr.take().map(watch_stderr);
+                        let status = sandbox.wait(p)?;
+                        let denied = stderr_watcher
+                            .and_then(|watcher| watcher.join().ok())
+                            .unwrap_or(false);
+                        Ok(!status.success() && denied)
+                    })
+                    .map_err(|source| match source.kind() {
+                        io::ErrorKind::TimedOut => ProgramError::LaunchTimeout {
+                            program: exe.clone(),
+                        },
+                        _ => ProgramError::LaunchError {
+                            program: exe.clone(),
+                            source,
+                        },
+                    })
+                    .and_then(|denied| if denied {
+                        Err(ProgramError::PermissionDenied { program: exe.clone() })
+                    } else {
+                        Ok(())
                     });
                 if let Some(ref mut w) = &mut w {
                     terminal::enable_raw_mode().unwrap();
                     if *capture_mouse {
                         w.queue(EnableMouseCapture).unwrap();
                     }
-                    w.queue(EnterAlternateScreen).unwrap();
+                    if inline_top.is_none() {
+                        w.queue(EnterAlternateScreen).unwrap();
+                    }
                     w.queue(cursor::Hide).unwrap();
                     w.flush().unwrap();
+                    invalidate_row_caches();
                 }
                 if let Some(old_working_dir) = old_working_dir {
                     std::env::set_current_dir(old_working_dir).unwrap();
@@ -1,6 +1,8 @@
 #[macro_use] extern crate cli_log;
 
+pub mod activity;
 pub mod app;
+pub mod bookmark;
 pub mod browser;
 pub mod cli;
 pub mod command;
@@ -8,29 +10,43 @@ pub mod conf;
 pub mod content_search;
 pub mod display;
 pub mod errors;
+pub mod file_index;
 pub mod file_sum;
 pub mod flag;
+pub mod font;
 pub mod git;
 pub mod hex;
 pub mod help;
+pub mod i18n;
 pub mod icon;
 pub mod image;
 pub mod keys;
 pub mod kitty;
 pub mod launchable;
+pub mod lint;
+pub mod opener_rules;
 pub mod path;
 pub mod pattern;
 pub mod permissions;
 pub mod preview;
 pub mod print;
+pub mod quick_roots;
+pub mod report;
+pub mod reveal;
 pub mod stage;
 pub mod shell_install;
 pub mod skin;
 pub mod syntactic;
+pub mod tag;
 pub mod task_sync;
 pub mod tree;
 pub mod tree_build;
 pub mod verb;
+pub mod versions;
+pub mod watch;
+
+#[cfg(unix)]
+pub mod containers;
 
 #[cfg(unix)]
 pub mod filesystems;
@@ -38,3 +54,6 @@ pub mod filesystems;
 
 #[cfg(unix)]
 pub mod net;
+
+#[cfg(unix)]
+pub mod trash;
@@ -0,0 +1,91 @@
+use {
+    super::*,
+    crossbeam::channel::{unbounded, Receiver, RecvTimeoutError},
+    once_cell::sync::Lazy,
+    rayon::{ThreadPool, ThreadPoolBuilder},
+    std::{
+        path::{Path, PathBuf},
+        sync::{Arc, Weak},
+        time::{Duration, Instant},
+    },
+};
+
+/// the number of probes run at the same time
+const POOL_SIZE: usize = 4;
+
+/// the time after which we stop waiting for the probes, the
+/// files not probed yet being then left unprobed
+const LINT_TIMEOUT: Duration = Duration::from_secs(60);
+
+static POOL: Lazy<ThreadPool> = Lazy::new(|| {
+    ThreadPoolBuilder::new()
+        .num_threads(POOL_SIZE)
+        .thread_name(|i| format!("lint-{}", i))
+        .build()
+        .unwrap()
+});
+
+/// The running, in background threads, of the lint probes on the
+/// files of a tree, so that a slow probe doesn't freeze the
+/// application
+#[derive(Debug, Clone)]
+pub struct LintLoader {
+    receiver: Receiver<(PathBuf, LintStatus)>,
+    /// dropped with the last clone of the loader, which tells the
+    /// probes not started yet that nobody waits for them anymore
+    _alive: Arc<()>,
+    start: Instant,
+    /// the probed files
+    paths: Vec<PathBuf>,
+}
+
+impl LintLoader {
+    pub fn start(paths: Vec<PathBuf>, probes: &[LintProbe]) -> Self {
+        let (sender, receiver) = unbounded();
+        let alive = Arc::new(());
+        for path in paths.iter().cloned() {
+            let sender = sender.clone();
+            let probes = probes.to_vec();
+            let alive: Weak<()> = Arc::downgrade(&alive);
+            POOL.spawn(move || {
+                if alive.upgrade().is_none() {
+                    return;
+                }
+                let status = lint_status(&path, &probes);
+                let _ = sender.send((path, status));
+            });
+        }
+        Self {
+            receiver,
+            _alive: alive,
+            start: Instant::now(),
+            paths,
+        }
+    }
+    /// tell whether the file was given to the probes
+    pub fn is_probing(&self, path: &Path) -> bool {
+        self.paths.iter().any(|p| p == path)
+    }
+    /// wait up to the given duration for the results, return the
+    /// received ones and whether there's nothing more to wait for
+    pub fn receive(
+        &self,
+        timeout: Duration,
+    ) -> (Vec<(PathBuf, LintStatus)>, bool) {
+        let mut received = Vec::new();
+        match self.receiver.recv_timeout(timeout) {
+            Ok(result) => received.push(result),
+            Err(RecvTimeoutError::Timeout) => {
+                let done = self.start.elapsed() > LINT_TIMEOUT;
+                if done {
+                    warn!("lint probes timed out");
+                }
+                return (received, done);
+            }
+            Err(RecvTimeoutError::Disconnected) => return (received, true),
+        }
+        // we take without waiting the other results already there
+        received.extend(self.receiver.try_iter());
+        (received, false)
+    }
+}
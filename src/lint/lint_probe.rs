@@ -0,0 +1,87 @@
+use {
+    crate::path::Glob,
+    serde::Deserialize,
+    std::{
+        path::Path,
+        process::{Command, Stdio},
+    },
+};
+
+/// the result of the lint probes on a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintStatus {
+    /// all the probes applying to the file succeeded
+    Pass,
+    /// at least one probe failed
+    Fail,
+    /// no probe applies to this file (or it's not a file)
+    Unprobed,
+}
+
+/// a command checking files, defined in conf, for example
+///
+/// ```hjson
+/// {
+///     name: rustfmt
+///     files: "*.rs"
+///     command: "rustfmt --check {file}"
+/// }
+/// ```
+///
+/// The file passes when the command exits with a 0 status.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LintProbe {
+    pub name: Option<String>,
+    /// the files the probe applies to
+    pub files: Glob,
+    /// the command, where `{file}` is replaced with the path
+    pub command: String,
+}
+
+impl LintProbe {
+    pub fn applies_to(&self, path: &Path) -> bool {
+        self.files.matches_path(path)
+    }
+    /// run the probe on the file, returning whether it passed
+    pub fn run(&self, path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        let mut tokens = splitty::split_unquoted_whitespace(&self.command)
+            .unwrap_quotes(true)
+            .map(|token| token.replace("{file}", &path_str));
+        let exe = match tokens.next() {
+            Some(exe) => exe,
+            None => {
+                warn!("empty lint probe command");
+                return false;
+            }
+        };
+        let mut command = Command::new(exe);
+        command
+            .args(tokens)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if let Some(dir) = path.parent().filter(|p| p.is_dir()) {
+            command.current_dir(dir);
+        }
+        match command.status() {
+            Ok(status) => status.success(),
+            Err(e) => {
+                warn!("lint probe {:?} failed to launch: {}", &self.command, e);
+                false
+            }
+        }
+    }
+}
+
+/// run on the file all the probes applying to it
+pub fn lint_status(path: &Path, probes: &[LintProbe]) -> LintStatus {
+    let mut status = LintStatus::Unprobed;
+    for probe in probes.iter().filter(|p| p.applies_to(path)) {
+        if !probe.run(path) {
+            return LintStatus::Fail;
+        }
+        status = LintStatus::Pass;
+    }
+    status
+}
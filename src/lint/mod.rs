@@ -0,0 +1,10 @@
+//! Checks of files by external formatters or linters, whose
+//! results are displayed in the lint column
+
+mod lint_loader;
+mod lint_probe;
+
+pub use {
+    lint_loader::LintLoader,
+    lint_probe::*,
+};
@@ -45,4 +45,14 @@ impl Client {
         }
         Ok(())
     }
+    /// ask the server for its current root
+    pub fn get_root(&self) -> Result<String, NetError> {
+        let mut stream = UnixStream::connect(&self.path)?;
+        Message::GetRoot.write(&mut stream)?;
+        let mut br = BufReader::new(&stream);
+        match Message::read(&mut br)? {
+            Message::Root(root) => Ok(root),
+            _ => Err(NetError::InvalidMessage),
+        }
+    }
 }
@@ -1,13 +1,17 @@
 mod client;
 mod message;
 mod server;
+mod single_instance;
 
 pub use {
     client::Client,
     message::Message,
     server::Server,
+    single_instance::*,
 };
 
+use crate::errors::NetError;
+
 pub fn socket_file_path(server_name: &str) -> String {
     format!("/tmp/broot-server-{}.sock", server_name)
 }
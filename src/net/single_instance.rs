@@ -0,0 +1,123 @@
+use {
+    super::*,
+    std::{
+        fs,
+        env,
+        ffi::CStr,
+        io,
+        path::Path,
+        process,
+    },
+};
+
+/// the prefix of the names of the servers started to
+/// ensure a single instance
+const PREFIX: &str = "single-";
+
+/// return an identifier of the terminal broot runs in: its tty, or
+/// the tmux pane or the X window when there's no tty on stdin
+fn terminal_id() -> Option<String> {
+    let tty = unsafe {
+        let name = libc::ttyname(0);
+        if name.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(name).to_string_lossy().to_string())
+        }
+    };
+    let id = tty
+        .or_else(|| env::var("TMUX_PANE").ok().map(|pane| format!("tmux{}", pane)))
+        .or_else(|| env::var("WINDOWID").ok().map(|window| format!("window{}", window)))?;
+    // the id is part of a file name
+    Some(
+        id.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    )
+}
+
+/// the prefix of the names of the servers started to ensure
+/// a single instance in the current terminal
+fn terminal_prefix() -> Option<String> {
+    terminal_id().map(|id| format!("{}{}-", PREFIX, id))
+}
+
+/// the name of the server of this instance when `single_instance`
+/// is set in configuration, or None when the terminal can't
+/// be identified
+pub fn single_instance_name() -> Option<String> {
+    terminal_prefix().map(|prefix| format!("{}{}", prefix, process::id()))
+}
+
+/// return the names of the servers, running or not, which
+/// were started for a single instance in the current terminal
+fn single_instance_names() -> Vec<String> {
+    let prefix = match terminal_prefix() {
+        Some(prefix) => prefix,
+        None => {
+            return Vec::new();
+        }
+    };
+    let socket_path = socket_file_path(&prefix);
+    let (dir, file_prefix) = match socket_path.rsplit_once('/') {
+        Some((dir, file)) => (dir.to_string(), file.trim_end_matches(".sock").to_string()),
+        None => {
+            return Vec::new();
+        }
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => {
+            return Vec::new();
+        }
+    };
+    entries
+        .flatten()
+        .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+        .filter_map(|name| {
+            name.strip_prefix(&file_prefix)
+                .and_then(|id| id.strip_suffix(".sock"))
+                .map(|id| format!("{}{}", prefix, id))
+        })
+        .collect()
+}
+
+/// try to find a running instance whose root is the given path
+/// and, if one is found, make it focus the path.
+///
+/// Only the instances of the current terminal are considered, and
+/// instances on a parent directory aren't reused: they would take
+/// all the launches done under them.
+///
+/// Return true when the path was sent to an instance.
+pub fn focus_in_running_instance(path: &Path) -> bool {
+    for name in single_instance_names() {
+        let client = Client::new(&name);
+        let root = match client.get_root() {
+            Ok(root) => root,
+            Err(NetError::Io { source }) if source.kind() == io::ErrorKind::ConnectionRefused => {
+                // the instance died without removing its socket
+                info!("removing stale socket of {:?}", &name);
+                let _ = fs::remove_file(socket_file_path(&name));
+                continue;
+            }
+            Err(e) => {
+                warn!("failed to query {:?}: {}", &name, e);
+                continue;
+            }
+        };
+        if path == Path::new(&root) {
+            let message = Message::Command(format!(":focus {}", path.to_string_lossy()));
+            match client.send(&message) {
+                Ok(()) => {
+                    info!("path sent to the instance {:?}", &name);
+                    return true;
+                }
+                Err(e) => {
+                    warn!("failed to send to {:?}: {}", &name, e);
+                }
+            }
+        }
+    }
+    false
+}
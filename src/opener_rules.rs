@@ -0,0 +1,159 @@
+//! Programs defined in conf to open files, depending on their
+//! extension or mime type, before falling back to the system opener.
+
+use {
+    crate::errors::ProgramError,
+    serde::Deserialize,
+    std::{
+        path::Path,
+        process::{Command, Stdio},
+    },
+};
+
+/// a program opening some files, defined in conf, for example
+///
+/// ```hjson
+/// {
+///     extensions: [ "md", "txt" ]
+///     command: "nvim {file}"
+///     terminal: true
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenerRule {
+    /// the extensions of the files the rule applies to, without dot
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// the mime type of the files the rule applies to, which may
+    /// end in a wildcard (eg "image/*")
+    pub mime: Option<String>,
+    /// the command, where `{file}` is replaced with the path
+    pub command: String,
+    /// whether the program runs in the terminal, in which case broot
+    /// gives it the screen and waits for its end, or is a GUI one,
+    /// launched without waiting
+    #[serde(default)]
+    pub terminal: bool,
+}
+
+/// the mime type of the most common files, guessed from their extension
+pub fn mime_type(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    Some(match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "tif" | "tiff" => "image/tiff",
+        "ico" => "image/x-icon",
+        "avif" => "image/avif",
+        "mp4" => "video/mp4",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        "avi" => "video/x-msvideo",
+        "mov" => "video/quicktime",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        "wav" => "audio/wav",
+        "m4a" => "audio/mp4",
+        "opus" => "audio/opus",
+        "txt" => "text/plain",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" => "text/javascript",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "epub" => "application/epub+zip",
+        _ => {
+            return None;
+        }
+    })
+}
+
+impl OpenerRule {
+    pub fn applies_to(&self, path: &Path) -> bool {
+        if !self.extensions.is_empty() {
+            let ext = path.extension().and_then(|ext| ext.to_str());
+            if let Some(ext) = ext {
+                if self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
+                    return true;
+                }
+            }
+        }
+        match (&self.mime, mime_type(path)) {
+            (Some(pattern), Some(mime)) => match pattern.strip_suffix('*') {
+                Some(prefix) => mime.starts_with(prefix),
+                None => pattern == mime,
+            },
+            _ => false,
+        }
+    }
+    /// the tokens of the command opening the file
+    pub fn tokens(&self, path: &Path) -> Vec<String> {
+        let path_str = path.to_string_lossy();
+        splitty::split_unquoted_whitespace(&self.command)
+            .unwrap_quotes(true)
+            .map(|token| token.replace("{file}", &path_str))
+            .collect()
+    }
+    /// launch the (GUI) program without waiting for it to be closed
+    pub fn spawn(&self, path: &Path) -> Result<(), ProgramError> {
+        let mut tokens = self.tokens(path).into_iter();
+        let exe = tokens.next().unwrap_or_default();
+        info!("opening {:?} with {:?}", path, &self.command);
+        Command::new(&exe)
+            .args(tokens)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|source| ProgramError::LaunchError {
+                program: exe,
+                source,
+            })?;
+        Ok(())
+    }
+}
+
+/// the first rule applying to the file, if any
+pub fn find<'r>(path: &Path, rules: &'r [OpenerRule]) -> Option<&'r OpenerRule> {
+    rules.iter().find(|rule| rule.applies_to(path))
+}
+
+#[cfg(test)]
+mod opener_rules_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_opener_rules() {
+        let rules = vec![
+            OpenerRule {
+                extensions: vec!["md".to_string()],
+                mime: None,
+                command: "nvim {file}".to_string(),
+                terminal: true,
+            },
+            OpenerRule {
+                extensions: Vec::new(),
+                mime: Some("image/*".to_string()),
+                command: "feh {file}".to_string(),
+                terminal: false,
+            },
+        ];
+        let rule = find(Path::new("/a/README.MD"), &rules).unwrap();
+        assert_eq!(rule.tokens(Path::new("/a/README.MD")), vec!["nvim", "/a/README.MD"]);
+        assert_eq!(find(Path::new("/a/b.jpeg"), &rules).unwrap().command, "feh {file}");
+        assert!(find(Path::new("/a/b.pdf"), &rules).is_none());
+        assert!(find(Path::new("/a/Makefile"), &rules).is_none());
+    }
+}
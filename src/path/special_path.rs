@@ -60,6 +60,12 @@ impl<'de> Deserialize<'de> for Glob {
     }
 }
 
+impl Glob {
+    pub fn matches_path(&self, path: &Path) -> bool {
+        self.pattern.matches_path(path)
+    }
+}
+
 impl SpecialPath {
     pub fn new(glob: Glob, handling: SpecialHandling) -> Self {
         Self {
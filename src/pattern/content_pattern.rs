@@ -7,6 +7,10 @@ use {
     std::{
         fmt,
         path::Path,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
     },
 };
 
@@ -14,6 +18,8 @@ use {
 #[derive(Debug, Clone)]
 pub struct ContentExactPattern {
     needle: Needle,
+    /// number of files which weren't searched because of the limits
+    skipped_count: Arc<AtomicUsize>,
 }
 
 impl fmt::Display for ContentExactPattern {
@@ -24,8 +30,15 @@ impl fmt::Display for ContentExactPattern {
 
 impl ContentExactPattern {
 
-    pub fn new(pat: &str, max_file_size: usize) -> Self {
-        Self { needle: Needle::new(pat, max_file_size) }
+    pub fn new(pat: &str, limits: &ContentSearchLimits) -> Self {
+        Self {
+            needle: Needle::new(pat, limits),
+            skipped_count: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn skipped_count(&self) -> usize {
+        self.skipped_count.load(Ordering::Relaxed)
     }
 
     pub fn as_str(&self) -> &str {
@@ -50,6 +63,10 @@ impl ContentExactPattern {
             Ok(ContentSearchResult::NotSuitable) => {
                 None
             }
+            Ok(ContentSearchResult::Skipped) => {
+                self.skipped_count.fetch_add(1, Ordering::Relaxed);
+                None
+            }
             Err(e) => {
                 debug!("error while scanning {:?} : {:?}", &candidate.path, e);
                 None
@@ -7,9 +7,12 @@ use {
     regex,
     std::{
         fmt,
-        fs::File,
-        io::{self, BufReader, BufRead},
+        io,
         path::Path,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
     },
 };
 
@@ -18,7 +21,9 @@ use {
 pub struct ContentRegexPattern {
     rex: regex::Regex,
     flags: String,
-    max_file_size: usize
+    limits: ContentSearchLimits,
+    /// number of files which weren't searched because of the limits
+    skipped_count: Arc<AtomicUsize>,
 }
 
 impl fmt::Display for ContentRegexPattern {
@@ -29,14 +34,23 @@ impl fmt::Display for ContentRegexPattern {
 
 impl ContentRegexPattern {
 
-    pub fn new(pat: &str, flags: &str, max_file_size: usize) -> Result<Self, PatternError> {
+    pub fn new(
+        pat: &str,
+        flags: &str,
+        limits: &ContentSearchLimits,
+    ) -> Result<Self, PatternError> {
         Ok(Self {
             rex: super::build_regex(pat, flags)?,
             flags: flags.to_string(),
-            max_file_size,
+            limits: limits.clone(),
+            skipped_count: Arc::new(AtomicUsize::new(0)),
         })
     }
 
+    pub fn skipped_count(&self) -> usize {
+        self.skipped_count.load(Ordering::Relaxed)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.rex.as_str().is_empty()
     }
@@ -47,7 +61,7 @@ impl ContentRegexPattern {
 
     // TODO optimize with regex::bytes ?
     fn has_match(&self, path: &Path) -> io::Result<bool> {
-        for line in BufReader::new(File::open(path)?).lines() {
+        for line in text_lines(path)? {
             if self.rex.is_match(line?.as_str()) {
                 return Ok(true);
             }
@@ -56,9 +70,19 @@ impl ContentRegexPattern {
     }
 
     pub fn score_of(&self, candidate: Candidate) -> Option<i32> {
-        if !candidate.regular_file || !is_path_suitable(candidate.path, self.max_file_size) {
+        if !candidate.regular_file {
             return None;
         }
+        match get_mmap_if_suitable(candidate.path, &self.limits) {
+            Ok(Suitability::Suitable(..)) => {}
+            Ok(Suitability::Skipped) => {
+                self.skipped_count.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            _ => {
+                return None;
+            }
+        }
         match self.has_match(candidate.path) {
             Ok(true) => Some(1),
             Ok(false) => None,
@@ -74,7 +98,7 @@ impl ContentRegexPattern {
         path: &Path,
         desired_len: usize,
     ) -> io::Result<Option<ContentMatch>> {
-        for line in BufReader::new(File::open(path)?).lines() {
+        for line in text_lines(path)? {
             let line = line?;
             if let Some(regex_match) = self.rex.find(line.as_str()) {
                 return Ok(Some(ContentMatch::build(
@@ -94,7 +118,7 @@ impl ContentRegexPattern {
         path: &Path,
     ) -> io::Result<Option<usize>> {
         let mut line_count = 1;
-        for line in BufReader::new(File::open(path)?).lines() {
+        for line in text_lines(path)? {
             let line = line?;
             if self.rex.is_match(line.as_str()) {
                 return Ok(Some(line_count));
@@ -4,6 +4,7 @@ use {
         app::AppContext,
         errors::PatternError,
         pattern::{Pattern, PatternParts},
+        tag::TagStore,
     },
     bet::BeTree,
 };
@@ -34,11 +35,40 @@ impl InputPattern {
     pub fn new(
         raw: String,
         parts_expr: &BeTree<PatternOperator, PatternParts>,
+        tags: &TagStore,
         con: &AppContext,
     ) -> Result<Self, PatternError> {
-        let pattern = Pattern::new(parts_expr, &con.search_modes, con.content_search_max_file_size)?;
+        let pattern = Pattern::new(
+            parts_expr,
+            &con.search_modes,
+            &con.content_search_limits,
+            tags,
+        )?;
         Ok(Self { raw, pattern })
     }
+    /// build the pattern, the parts without mode key being given
+    /// the search mode of the panel, if any, instead of the default one
+    pub fn with_search_mode(
+        raw: String,
+        parts_expr: &BeTree<PatternOperator, PatternParts>,
+        search_mode: Option<SearchMode>,
+        tags: &TagStore,
+        con: &AppContext,
+    ) -> Result<Self, PatternError> {
+        match search_mode {
+            Some(mode) => {
+                let search_modes = con.search_modes.with_default(mode);
+                let pattern = Pattern::new(
+                    parts_expr,
+                    &search_modes,
+                    &con.content_search_limits,
+                    tags,
+                )?;
+                Ok(Self { raw, pattern })
+            }
+            None => Self::new(raw, parts_expr, tags, con),
+        }
+    }
     pub fn is_none(&self) -> bool {
         self.pattern.is_empty()
     }
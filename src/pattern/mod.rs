@@ -14,6 +14,7 @@ mod pattern_parts;
 mod pos;
 mod regex_pattern;
 mod search_mode;
+mod tag_pattern;
 mod tok_pattern;
 
 pub use {
@@ -32,6 +33,7 @@ pub use {
     operator::PatternOperator,
     regex_pattern::RegexPattern,
     search_mode::*,
+    tag_pattern::TagPattern,
     tok_pattern::*,
 };
 
@@ -35,6 +35,34 @@ impl NameMatch {
         }
         result
     }
+    /// wraps the components of a slash separated path which contain
+    /// matching characters with match_start and match_end, each
+    /// component being transformed with `escape`
+    pub fn wrap_components(
+        &self,
+        path: &str,
+        match_start: &str,
+        match_end: &str,
+        escape: fn(&str) -> String,
+    ) -> String {
+        let mut result = String::new();
+        let mut start = 0; // index in chars of the current component
+        for (i, component) in path.split('/').enumerate() {
+            let end = start + component.chars().count();
+            if i > 0 {
+                result.push('/');
+            }
+            if self.pos.iter().any(|&p| p >= start && p < end) {
+                result.push_str(match_start);
+                result.push_str(&escape(component));
+                result.push_str(match_end);
+            } else {
+                result.push_str(&escape(component));
+            }
+            start = end + 1;
+        }
+        result
+    }
     // cut the name match in two parts by recomputing the pos
     // arrays
     pub fn cut_after(&mut self, chars_count: usize) -> Self {
@@ -52,3 +80,20 @@ impl NameMatch {
     }
 }
 
+#[cfg(test)]
+mod name_match_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_wrap_components() {
+        let nm = NameMatch {
+            score: 1,
+            pos: SmallVec::from_slice(&[4, 5, 9]), // "de" of "deep" and "n" of "note.md"
+        };
+        assert_eq!(
+            nm.wrap_components("src/deep/note.md", "*", "*", |s| s.to_string()),
+            "src/*deep*/*note.md*",
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use {
     super::*,
     crate::{
-        content_search::ContentMatch,
+        content_search::{ContentMatch, ContentSearchLimits},
         errors::PatternError,
+        tag::TagStore,
     },
     bet::BeTree,
     std::{
@@ -24,6 +25,7 @@ pub enum Pattern {
     PathTokens(TokPattern),
     ContentExact(ContentExactPattern),
     ContentRegex(ContentRegexPattern),
+    Tag(TagPattern),
     Composite(CompositePattern),
 }
 
@@ -32,7 +34,8 @@ impl Pattern {
     pub fn new(
         raw_expr: &BeTree<PatternOperator, PatternParts>,
         search_modes: &SearchModeMap,
-        content_search_max_file_size: usize,
+        content_search_limits: &ContentSearchLimits,
+        tags: &TagStore,
     ) -> Result<Self, PatternError> {
         let expr: BeTree<PatternOperator, Pattern> = raw_expr
             .try_map_atoms::<_, PatternError, _>(|pattern_parts| {
@@ -70,15 +73,18 @@ impl Pattern {
                                 TokPattern::new(core)
                             ),
                             SearchMode::ContentExact => Self::ContentExact(
-                                ContentExactPattern::new(core, content_search_max_file_size)
+                                ContentExactPattern::new(core, content_search_limits)
                             ),
                             SearchMode::ContentRegex => Self::ContentRegex(
                                 ContentRegexPattern::new(
                                     core,
                                     flags.unwrap_or(""),
-                                    content_search_max_file_size,
+                                    content_search_limits,
                                 )?
                             ),
+                            SearchMode::TagExact => Self::Tag(
+                                TagPattern::new(core, tags)
+                            ),
                         }
                     }
                 )
@@ -105,6 +111,7 @@ impl Pattern {
             Self::ContentExact(_) | Self::ContentRegex(_) => {
                 object.content = true;
             }
+            Self::Tag(_) => {}
             Self::Composite(cp) => {
                 for atom in cp.expr.iter_atoms() {
                     object |= atom.object();
@@ -114,6 +121,20 @@ impl Pattern {
         object
     }
 
+    /// the number of files the content search skipped because
+    /// of the content search limits, since the pattern was built
+    pub fn content_skipped_count(&self) -> usize {
+        match self {
+            Self::ContentExact(cp) => cp.skipped_count(),
+            Self::ContentRegex(cp) => cp.skipped_count(),
+            Self::Composite(cp) => cp.expr
+                .iter_atoms()
+                .map(|atom| atom.content_skipped_count())
+                .sum(),
+            _ => 0,
+        }
+    }
+
     pub fn search_string(
         &self,
         candidate: &str,
@@ -168,6 +189,7 @@ impl Pattern {
             Self::PathTokens(tp) => tp.score_of(candidate.subpath),
             Self::ContentExact(cp) => cp.score_of(candidate),
             Self::ContentRegex(cp) => cp.score_of(candidate),
+            Self::Tag(tp) => tp.score_of(candidate),
             Self::Composite(cp) => cp.score_of(candidate),
             Self::None => Some(1),
         }
@@ -185,6 +207,7 @@ impl Pattern {
             Self::PathTokens(tp) => tp.score_of(candidate),
             Self::ContentExact(_) => None, // this isn't suitable
             Self::ContentRegex(_) => None, // this isn't suitable
+            Self::Tag(_) => None, // this isn't suitable
             Self::Composite(cp) => cp.score_of_string(candidate),
             Self::None => Some(1),
         }
@@ -203,6 +226,7 @@ impl Pattern {
             Self::NameFuzzy(fp) | Self::PathFuzzy(fp) => fp.is_empty(),
             Self::NameRegex(rp) | Self::PathRegex(rp) => rp.is_empty(),
             Self::ContentRegex(rp) => rp.is_empty(),
+            Self::Tag(tp) => tp.is_empty(),
             Self::NameTokens(tp) | Self::PathTokens(tp) => tp.is_empty(),
             Self::Composite(cp) => cp.is_empty(),
             Self::None => true,
@@ -1,7 +1,7 @@
 
 use {
     crate::{
-        app::AppContext,
+        app::{AppContext, PanelPurpose},
         errors::{ConfError, PatternError},
     },
     fnv::FnvHashMap,
@@ -15,6 +15,7 @@ pub enum SearchObject {
     Name,
     Path,
     Content,
+    Tag,
 }
 /// how to search
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -39,6 +40,7 @@ pub enum SearchMode {
     PathTokens,
     ContentExact,
     ContentRegex,
+    TagExact,
 }
 
 pub static SEARCH_MODES: &[SearchMode] = &[
@@ -52,6 +54,7 @@ pub static SEARCH_MODES: &[SearchMode] = &[
     SearchMode::PathTokens,
     SearchMode::ContentExact,
     SearchMode::ContentRegex,
+    SearchMode::TagExact,
 ];
 
 impl SearchMode {
@@ -75,6 +78,9 @@ impl SearchMode {
             (Content, Fuzzy) => None, // unsupported for now - could be but why ?
             (Content, Regex) => Some(Self::ContentRegex),
             (Content, Tokens) => None, // unsupported for now - could be but need bench
+
+            (Tag, Exact) => Some(Self::TagExact),
+            (Tag, _) => None,
         }
     }
     /// Return the prefix to type, eg "/" in standard for a name-regex,
@@ -90,6 +96,7 @@ impl SearchMode {
             Self::NameExact | Self::NameFuzzy | Self::NameRegex | Self::NameTokens => SearchObject::Name,
             Self::PathExact | Self::PathFuzzy | Self::PathRegex | Self::PathTokens => SearchObject::Path,
             Self::ContentExact | Self::ContentRegex => SearchObject::Content,
+            Self::TagExact => SearchObject::Tag,
         }
     }
     pub fn kind(self) -> SearchKind {
@@ -104,6 +111,7 @@ impl SearchMode {
             Self::PathTokens => SearchKind::Tokens,
             Self::ContentExact => SearchKind::Exact,
             Self::ContentRegex => SearchKind::Regex,
+            Self::TagExact => SearchKind::Exact,
         }
     }
 }
@@ -138,6 +146,7 @@ impl SearchModeMapEntry {
                 "name" => search_objects.push(SearchObject::Name),
                 "content" => search_objects.push(SearchObject::Content),
                 "path" => search_objects.push(SearchObject::Path),
+                "tag" => search_objects.push(SearchObject::Tag),
                 _ => {
                     return Err(ConfError::InvalidSearchMode {
                         details: format!("{:?} not understood in search mode definition", t),
@@ -159,7 +168,7 @@ impl SearchModeMapEntry {
         if search_objects.is_empty() {
             return Err(ConfError::InvalidSearchMode {
                 details: "missing search object in search mode definition\
-                    (the search object must be one of 'name', 'path', 'content')".to_string()
+                    (the search object must be one of 'name', 'path', 'content', 'tag')".to_string()
             });
         }
         if search_objects.len() > 1 {
@@ -209,6 +218,7 @@ impl Default for SearchModeMap {
         smm.setm(&["rx", "cr"], SearchMode::ContentRegex);
         smm.setm(&["pt", "tp", "t"], SearchMode::PathTokens);
         smm.setm(&["tn", "nt"], SearchMode::NameTokens);
+        smm.setm(&["tag"], SearchMode::TagExact);
         smm.set(SearchModeMapEntry { key: None, mode: SearchMode::PathFuzzy });
         smm
     }
@@ -253,6 +263,13 @@ impl SearchModeMap {
             },
         })
     }
+    /// a copy of the map, with the given mode for the patterns
+    /// without mode key
+    pub fn with_default(&self, mode: SearchMode) -> Self {
+        let mut smm = self.clone();
+        smm.set(SearchModeMapEntry { key: None, mode });
+        smm
+    }
     pub fn key(&self, search_mode: SearchMode) -> Option<&String> {
         for entry in self.entries.iter().rev() {
             if entry.mode == search_mode {
@@ -264,3 +281,58 @@ impl SearchModeMap {
     }
 }
 
+/// the default search modes of the panels, depending on their
+/// purpose, as defined in the `panel_search_modes` setting
+#[derive(Debug, Clone, Default)]
+pub struct PanelSearchModes {
+    pub main: Option<SearchMode>,
+    pub preview: Option<SearchMode>,
+    pub picker: Option<SearchMode>,
+}
+
+impl PanelSearchModes {
+    pub fn for_purpose(&self, purpose: PanelPurpose) -> Option<SearchMode> {
+        match purpose {
+            PanelPurpose::None => self.main,
+            PanelPurpose::Preview => self.preview,
+            PanelPurpose::ArgEdition { .. } => self.picker,
+        }
+    }
+}
+
+impl TryFrom<&FnvHashMap<String, String>> for PanelSearchModes {
+    type Error = ConfError;
+    fn try_from(map: &FnvHashMap<String, String>) -> Result<Self, Self::Error> {
+        let mut psm = Self::default();
+        for (k, v) in map {
+            let mode = Some(SearchModeMapEntry::parse("", v)?.mode);
+            match k.as_str() {
+                "main" => psm.main = mode,
+                "preview" => psm.preview = mode,
+                "picker" => psm.picker = mode,
+                _ => {
+                    return Err(ConfError::InvalidKey { raw: k.to_string() });
+                }
+            }
+        }
+        Ok(psm)
+    }
+}
+
+#[cfg(test)]
+mod search_mode_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_panel_search_modes_parsing() {
+        let mut map = FnvHashMap::default();
+        map.insert("main".to_string(), "fuzzy name".to_string());
+        map.insert("preview".to_string(), "regex content".to_string());
+        let psm = PanelSearchModes::try_from(&map).unwrap();
+        assert_eq!(psm.for_purpose(PanelPurpose::None), Some(SearchMode::NameFuzzy));
+        assert_eq!(psm.for_purpose(PanelPurpose::Preview), Some(SearchMode::ContentRegex));
+        map.insert("other".to_string(), "exact path".to_string());
+        assert!(PanelSearchModes::try_from(&map).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+use {
+    super::Candidate,
+    crate::tag::TagStore,
+    std::{
+        collections::HashSet,
+        path::PathBuf,
+        sync::Arc,
+    },
+};
+
+/// A pattern matching the files having a given tag.
+///
+/// The tagged paths are taken from the tag store when the
+/// pattern is built
+#[derive(Debug, Clone)]
+pub struct TagPattern {
+    tag: String,
+    paths: Arc<HashSet<PathBuf>>,
+}
+
+impl TagPattern {
+    pub fn new(tag: &str, tags: &TagStore) -> Self {
+        let paths = tags.paths_with(tag).cloned().collect();
+        Self {
+            tag: tag.to_string(),
+            paths: Arc::new(paths),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.tag.is_empty()
+    }
+    pub fn score_of(&self, candidate: Candidate) -> Option<i32> {
+        if self.paths.contains(candidate.path) {
+            Some(1)
+        } else {
+            None
+        }
+    }
+}
@@ -12,3 +12,48 @@ pub use permissions_unix::*;
 pub fn supported() -> bool {
     false
 }
+
+//////////////////// ALL PLATFORMS
+
+/// whether the owner and group of files can be displayed
+pub fn supports_ownership() -> bool {
+    cfg!(not(any(target_family = "windows", target_os = "android")))
+}
+
+/// whether the access control lists of files can be checked
+pub fn supports_acl() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// tell whether the file has an access control list, in
+/// addition to the permissions of its mode
+#[cfg(target_os = "linux")]
+pub fn has_acl(path: &std::path::Path) -> bool {
+    use std::{
+        ffi::CString,
+        os::unix::ffi::OsStrExt,
+    };
+    let c_path = match CString::new(path.as_os_str().as_bytes()) {
+        Ok(c_path) => c_path,
+        Err(_) => return false,
+    };
+    // the default ACL of a directory is an ACL too, as for `ls`
+    [&b"system.posix_acl_access\0"[..], &b"system.posix_acl_default\0"[..]]
+        .iter()
+        .any(|name| {
+            let size = unsafe {
+                libc::lgetxattr(
+                    c_path.as_ptr(),
+                    name.as_ptr() as *const libc::c_char,
+                    std::ptr::null_mut(),
+                    0,
+                )
+            };
+            size > 0
+        })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn has_acl(_path: &std::path::Path) -> bool {
+    false
+}
@@ -8,6 +8,15 @@ pub use {
     zero_len_file_view::ZeroLenFileView,
 };
 
+use {
+    crate::{
+        app::AppContext,
+        path::Glob,
+    },
+    serde::{de::Error, Deserialize, Deserializer},
+    std::path::Path,
+};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PreviewMode {
 
@@ -21,3 +30,34 @@ pub enum PreviewMode {
     /// show the content of the file as hex
     Hex,
 }
+
+impl PreviewMode {
+    /// return the mode the conf forces for this path, if any
+    pub fn forced_for(path: &Path, con: &AppContext) -> Option<Self> {
+        con.preview_modes
+            .iter()
+            .find(|(glob, _)| glob.matches_path(path))
+            .map(|(_, mode)| *mode)
+    }
+}
+
+/// a mapping from a glob to the preview mode to use for the
+/// matching files, as defined in conf
+pub type PreviewModeRule = (Glob, PreviewMode);
+
+impl<'de> Deserialize<'de> for PreviewMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.to_lowercase().as_ref() {
+            "image" => Ok(Self::Image),
+            "text" => Ok(Self::Text),
+            "hex" | "binary" => Ok(Self::Hex),
+            _ => Err(D::Error::custom(format!(
+                "unrecognized preview mode: {:?}",
+                s
+            ))),
+        }
+    }
+}
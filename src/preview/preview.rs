@@ -5,11 +5,12 @@ use {
         command::ScrollCommand,
         display::*,
         errors::ProgramError,
+        font::FontView,
         hex::HexView,
-        image::ImageView,
+        image::{ImageComparison, ImageView},
         pattern::InputPattern,
         skin::PanelSkin,
-        syntactic::SyntacticView,
+        syntactic::{Encoding, SyntacticView},
         task_sync::Dam,
     },
     crokey::crossterm::{cursor, QueueableCommand},
@@ -22,6 +23,8 @@ use {
 
 pub enum Preview {
     Image(ImageView),
+    Comparison(Box<ImageComparison>),
+    Font(FontView),
     Syntactic(SyntacticView),
     Hex(HexView),
     ZeroLen(ZeroLenFileView),
@@ -31,15 +34,23 @@ pub enum Preview {
 impl Preview {
     /// build a preview, never failing (but the preview can be Preview::IOError).
     /// If the preferred mode can't be applied, an other mode is chosen.
+    /// When there's no preferred mode, the one forced in conf for the
+    /// path, if any, is used.
     pub fn new(
         path: &Path,
         prefered_mode: Option<PreviewMode>,
         con: &AppContext,
     ) -> Self {
+        let prefered_mode = prefered_mode.or_else(|| PreviewMode::forced_for(path, con));
         match prefered_mode {
             Some(PreviewMode::Hex) => Self::hex(path),
             Some(PreviewMode::Image) => Self::image(path),
             Some(PreviewMode::Text) => Self::unfiltered_text(path, con),
+            None if FontView::is_font_path(path) => {
+                FontView::new(path)
+                    .map(Self::Font)
+                    .unwrap_or_else(|_| Self::hex(path))
+            }
             None => {
                 // automatic behavior: image, text, hex
                 ImageView::new(path)
@@ -72,6 +83,32 @@ impl Preview {
             }
         }
     }
+    /// try to build a text view of a file decoded with the given encoding
+    pub fn with_encoding(
+        path: &Path,
+        encoding: Encoding,
+        con: &AppContext,
+    ) -> Result<Self, ProgramError> {
+        SyntacticView::with_encoding(
+            path,
+            InputPattern::none(),
+            &mut Dam::unlimited(),
+            con,
+            false,
+            Some(encoding),
+        )
+            .transpose()
+            .expect("syntactic view without pattern shouldn't be none")
+            .map(Self::Syntactic)
+    }
+    /// build a side by side view of two images
+    pub fn comparison(left: &Path, right: &Path) -> Result<Self, ProgramError> {
+        ImageComparison::new(left, right)
+            .map(|ic| Self::Comparison(Box::new(ic)))
+    }
+    pub fn is_comparison(&self) -> bool {
+        matches!(self, Self::Comparison(_))
+    }
     /// build an image view, unless the file can't be interpreted
     /// as an image, in which case a hex view is used
     pub fn image(path: &Path) -> Self {
@@ -131,8 +168,8 @@ impl Preview {
         con: &AppContext,
     ) -> Option<Self> {
         match self {
-            Self::Syntactic(_) => {
-                match SyntacticView::new(path, pattern, dam, con, false) {
+            Self::Syntactic(sv) => {
+                match SyntacticView::with_encoding(path, pattern, dam, con, false, Some(sv.encoding)) {
 
                     // normal finished loading
                     Ok(Some(sv)) => Some(Self::Syntactic(sv)),
@@ -164,6 +201,8 @@ impl Preview {
     pub fn get_mode(&self) -> Option<PreviewMode> {
         match self {
             Self::Image(_) => Some(PreviewMode::Image),
+            Self::Comparison(_) => Some(PreviewMode::Image),
+            Self::Font(_) => Some(PreviewMode::Text),
             Self::Syntactic(_) => Some(PreviewMode::Text),
             Self::ZeroLen(_) => Some(PreviewMode::Text),
             Self::Hex(_) => Some(PreviewMode::Hex),
@@ -183,13 +222,35 @@ impl Preview {
         match self {
             Self::Syntactic(sv) => sv.try_scroll(cmd),
             Self::Hex(hv) => hv.try_scroll(cmd),
+            Self::Font(fv) => fv.try_scroll(cmd),
             _ => false,
         }
     }
+    pub fn get_scroll(&self) -> usize {
+        match self {
+            Self::Syntactic(sv) => sv.get_scroll(),
+            Self::Hex(hv) => hv.get_scroll(),
+            _ => 0,
+        }
+    }
+    pub fn set_scroll(&mut self, scroll: usize) {
+        match self {
+            Self::Syntactic(sv) => sv.set_scroll(scroll),
+            Self::Hex(hv) => hv.set_scroll(scroll),
+            _ => {}
+        }
+    }
     pub fn is_filterable(&self) -> bool {
         matches!(self, Self::Syntactic(_))
     }
 
+    pub fn syntactic_view(&self) -> Option<&SyntacticView> {
+        match self {
+            Self::Syntactic(sv) => Some(sv),
+            _ => None,
+        }
+    }
+
     pub fn get_selected_line(&self) -> Option<String> {
         match self {
             Self::Syntactic(sv) => sv.get_selected_line(),
@@ -202,6 +263,18 @@ impl Preview {
             _ => None,
         }
     }
+    pub fn get_selected_line_range(&self) -> Option<(LineNumber, LineNumber)> {
+        match self {
+            Self::Syntactic(sv) => sv.get_selected_line_range(),
+            _ => None,
+        }
+    }
+    pub fn extend_selection(&mut self, dy: i32) {
+        match self {
+            Self::Syntactic(sv) => sv.extend_selection(dy),
+            _ => self.move_selection(dy, false),
+        }
+    }
     pub fn try_select_line_number(&mut self, number: usize) -> bool {
         match self {
             Self::Syntactic(sv) => sv.try_select_line_number(number),
@@ -225,6 +298,9 @@ impl Preview {
             Self::Hex(hv) => {
                 hv.try_scroll(ScrollCommand::Lines(dy));
             }
+            Self::Font(fv) => {
+                fv.try_scroll(ScrollCommand::Lines(dy));
+            }
             _ => {}
         }
     }
@@ -232,6 +308,7 @@ impl Preview {
         match self {
             Self::Syntactic(sv) => sv.select_first(),
             Self::Hex(hv) => hv.select_first(),
+            Self::Font(fv) => fv.select_first(),
             _ => {}
         }
     }
@@ -239,9 +316,22 @@ impl Preview {
         match self {
             Self::Syntactic(sv) => sv.select_last(),
             Self::Hex(hv) => hv.select_last(),
+            Self::Font(fv) => fv.select_last(),
             _ => {}
         }
     }
+    /// read the lines appended to the file since it was read.
+    ///
+    /// Return false when the preview must be built again instead
+    pub fn read_appended_lines(&mut self, con: &AppContext) -> bool {
+        match self {
+            Self::Syntactic(sv) => sv.read_appended_lines(con).unwrap_or_else(|e| {
+                info!("appended lines can't be read: {}", e);
+                false
+            }),
+            _ => false,
+        }
+    }
     pub fn display(
         &mut self,
         w: &mut W,
@@ -250,12 +340,13 @@ impl Preview {
     ) -> Result<(), ProgramError> {
         let panel_skin = &disc.panel_skin;
         let screen = disc.screen;
-        let con = &disc.con;
         match self {
             Self::Image(iv) => iv.display(w, disc, area),
-            Self::Syntactic(sv) => sv.display(w, screen, panel_skin, area, con),
+            Self::Comparison(ic) => ic.display(w, disc, area),
+            Self::Syntactic(sv) => sv.display(w, disc, area),
             Self::ZeroLen(zlv) => zlv.display(w, screen, panel_skin, area),
-            Self::Hex(hv) => hv.display(w, screen, panel_skin, area),
+            Self::Hex(hv) => hv.display(w, disc, area),
+            Self::Font(fv) => fv.display(w, screen, panel_skin, area),
             Self::IoError(err) => {
                 let mut y = area.top;
                 w.queue(cursor::MoveTo(area.left, y))?;
@@ -287,6 +378,7 @@ impl Preview {
     ) -> Result<(), ProgramError> {
         match self {
             Self::Image(iv) => iv.display_info(w, screen, panel_skin, area),
+            Self::Comparison(ic) => ic.display_info(w, screen, panel_skin, area),
             Self::Syntactic(sv) => sv.display_info(w, screen, panel_skin, area),
             Self::Hex(hv) => hv.display_info(w, screen, panel_skin, area),
             _ => Ok(()),
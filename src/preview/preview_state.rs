@@ -3,11 +3,13 @@ use {
     crate::{
         app::*,
         command::{Command, ScrollCommand, TriggerType},
-        display::{Screen, W},
+        display::{Screen, TitlePart, W},
         errors::ProgramError,
         flag::Flag,
         pattern::InputPattern,
+        syntactic::Encoding,
         task_sync::Dam,
+        tr,
         tree::TreeOptions,
         verb::*,
     },
@@ -15,10 +17,24 @@ use {
         cursor,
         QueueableCommand,
     },
-    std::path::{Path, PathBuf},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        time::Duration,
+    },
     termimad::{Area, CropWriter, SPACE_FILLING},
 };
 
+/// how often a followed file is checked for changes
+const TAIL_REFRESH_PERIOD: Duration = Duration::from_millis(500);
+
+/// the state of the preview in tail mode, where the view starts
+/// at the end of the file and follows the appended lines
+struct Tail {
+    paused: bool,
+    len: u64, // length of the file at last read
+}
+
 /// an application state dedicated to previewing files.
 /// It's usually the only state in its panel and is kept when
 /// the selection changes (other panels indirectly call
@@ -34,6 +50,7 @@ pub struct PreviewState {
     prefered_mode: Option<PreviewMode>,
     tree_options: TreeOptions,
     mode: Mode,
+    tail: Option<Tail>,
 }
 
 impl PreviewState {
@@ -57,6 +74,52 @@ impl PreviewState {
             prefered_mode,
             tree_options,
             mode: initial_mode(con),
+            tail: None,
+        }
+    }
+    /// build a state comparing side by side the two staged images,
+    /// or return the error to display
+    pub fn new_comparison(
+        app_state: &AppState,
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> Result<PreviewState, String> {
+        let (path, preview) = staged_images_comparison(app_state)?;
+        let mut state = Self::new(path, InputPattern::none(), None, tree_options, con);
+        state.preview = preview;
+        Ok(state)
+    }
+    /// switch to tail mode: select the last line and follow
+    /// the lines appended to the file
+    pub fn start_tail(&mut self) {
+        self.mut_preview().select_last();
+        self.tail = Some(Tail {
+            paused: false,
+            len: file_len(&self.path),
+        });
+    }
+    /// tell whether the file changed since the last reading
+    /// and must be read again because we follow it
+    fn must_follow(&self) -> bool {
+        match &self.tail {
+            Some(tail) if !tail.paused => tail.len != file_len(&self.path),
+            _ => false,
+        }
+    }
+    /// read the lines appended to the followed file (or the whole
+    /// file when that's not possible) and select its last line
+    fn follow(&mut self, con: &AppContext) {
+        let appended = self.preview.read_appended_lines(con)
+            && self.filtered_preview.as_mut().map_or(true, |fp| fp.read_appended_lines(con));
+        if !appended {
+            if let Some(fp) = self.filtered_preview.take() {
+                self.pending_pattern = fp.pattern();
+            }
+            self.preview = Preview::new(&self.path, self.prefered_mode, con);
+        }
+        self.mut_preview().select_last();
+        if let Some(tail) = &mut self.tail {
+            tail.len = file_len(&self.path);
         }
     }
     fn vis_preview(&self) -> &Preview {
@@ -80,9 +143,11 @@ impl PreviewState {
                 CmdResult::Keep
             }
             Err(e) => {
-                CmdResult::DisplayError(
-                    format!("Can't display as {:?} : {:?}", mode, e)
-                )
+                CmdResult::error(tr!(
+                    error_encoding,
+                    encoding = format!("{:?}", mode),
+                    error = format!("{:?}", e),
+                ))
             }
         })
     }
@@ -93,6 +158,8 @@ impl PreviewState {
             stype: SelectionType::File,
             is_exe: false, // not always true. It means :open_leave won't execute it
             line: self.vis_preview().get_selected_line_number().unwrap_or(0),
+            line_range: self.vis_preview().get_selected_line_range(),
+            text_view: self.vis_preview().syntactic_view(),
         }
     }
 
@@ -115,6 +182,8 @@ impl PanelState for PreviewState {
     fn get_pending_task(&self) -> Option<&'static str> {
         if self.pending_pattern.is_some() {
             Some("searching")
+        } else if self.must_follow() {
+            Some("following")
         } else {
             None
         }
@@ -136,7 +205,7 @@ impl PanelState for PreviewState {
             }
         } else {
             if !self.preview.is_filterable() {
-                return Ok(CmdResult::error("this preview can't be searched"));
+                return Ok(CmdResult::error(tr!(error_preview_not_searchable)));
             }
         }
         self.pending_pattern = pat;
@@ -151,6 +220,9 @@ impl PanelState for PreviewState {
         con: &AppContext,
         dam: &mut Dam,
     ) -> Result<(), ProgramError> {
+        if self.must_follow() {
+            self.follow(con);
+        }
         if self.pending_pattern.is_some() {
             let old_selection = self
                 .filtered_preview
@@ -164,7 +236,9 @@ impl PanelState for PreviewState {
                 self.preview.filtered(&self.path, pattern, dam, con),
             ); // can be None if a cancellation was required
             if let Some(ref mut filtered_preview) = self.filtered_preview {
-                if let Some(number) = old_selection {
+                if self.tail.is_some() {
+                    filtered_preview.select_last();
+                } else if let Some(number) = old_selection {
                     filtered_preview.try_select_line_number(number);
                 }
             }
@@ -172,11 +246,22 @@ impl PanelState for PreviewState {
         Ok(())
     }
 
+    fn refresh_period(&self) -> Option<Duration> {
+        match &self.tail {
+            Some(tail) if !tail.paused => Some(TAIL_REFRESH_PERIOD),
+            _ => None,
+        }
+    }
+
     fn selected_path(&self) -> Option<&Path> {
         Some(&self.path)
     }
 
     fn set_selected_path(&mut self, path: PathBuf, con: &AppContext) {
+        if self.preview.is_comparison() {
+            // the comparison is kept until the panel is closed
+            return;
+        }
         let selected_line_number = if self.path == path {
             self.preview.get_selected_line_number()
         } else {
@@ -186,7 +271,10 @@ impl PanelState for PreviewState {
             self.pending_pattern = fp.pattern();
         };
         self.preview = Preview::new(&path, self.prefered_mode, con);
-        if let Some(number) = selected_line_number {
+        if let Some(tail) = &mut self.tail {
+            self.preview.select_last();
+            tail.len = file_len(&path);
+        } else if let Some(number) = selected_line_number {
             self.preview.try_select_line_number(number);
         }
         self.path = path;
@@ -213,7 +301,11 @@ impl PanelState for PreviewState {
 
     fn refresh(&mut self, _screen: Screen, con: &AppContext) -> Command {
         self.dirty = true;
+        let scroll = self.preview.get_scroll();
         self.set_selected_path(self.path.clone(), con);
+        if self.tail.is_none() {
+            self.preview.set_scroll(scroll);
+        }
         Command::empty()
     }
 
@@ -254,14 +346,42 @@ impl PanelState for PreviewState {
             self.dirty = false;
         }
         let styles = &disc.panel_skin.styles;
-        w.queue(cursor::MoveTo(state_area.left, 0))?;
+        w.queue(cursor::MoveTo(state_area.left, state_area.top))?;
         let mut cw = CropWriter::new(w, state_area.width as usize);
-        let file_name = self
-            .path
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| "???".to_string());
-        cw.queue_str(&styles.preview_title, &file_name)?;
+        let file_name = match &self.preview {
+            Preview::Comparison(ic) => ic.title(),
+            _ => self
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "???".to_string()),
+        };
+        match &disc.con.preview_title {
+            Some(title) => {
+                for part in &title.parts {
+                    match part {
+                        TitlePart::Text(s) => cw.queue_str(&styles.preview_title, s)?,
+                        TitlePart::Path => cw.queue_str(
+                            &styles.preview_title,
+                            &self.path.to_string_lossy(),
+                        )?,
+                        TitlePart::Name => cw.queue_str(&styles.preview_title, &file_name)?,
+                        TitlePart::Filter => {
+                            if let Some(fp) = &self.filtered_preview {
+                                cw.queue_str(&styles.preview_title, &fp.pattern().raw)?;
+                            }
+                        }
+                        _ => {} // not relevant for a file preview
+                    }
+                }
+            }
+            None => cw.queue_str(&styles.preview_title, &file_name)?,
+        }
+        match &self.tail {
+            Some(Tail { paused: true, .. }) => cw.queue_str(&styles.preview_title, " (tail, paused)")?,
+            Some(_) => cw.queue_str(&styles.preview_title, " (tail)")?,
+            None => {}
+        }
         let info_area = Area::new(
             state_area.left + state_area.width - cw.allowed as u16,
             state_area.top,
@@ -322,21 +442,88 @@ impl PanelState for PreviewState {
             Internal::copy_line => {
                 #[cfg(not(feature = "clipboard"))]
                 {
-                    Ok(CmdResult::error("Clipboard feature not enabled at compilation"))
+                    Ok(CmdResult::error(tr!(error_clipboard_disabled)))
                 }
                 #[cfg(feature = "clipboard")]
                 {
-                    Ok(match self.mut_preview().get_selected_line() {
+                    let text = if self.vis_preview().get_selected_line_range().is_some() {
+                        self.no_opt_selection().lines_text(false)
+                    } else {
+                        self.vis_preview().get_selected_line()
+                    };
+                    Ok(match text {
                         Some(line) => {
                             match terminal_clipboard::set_string(line) {
                                 Ok(()) => CmdResult::Keep,
-                                Err(_) => CmdResult::error("Clipboard error while copying path"),
+                                Err(_) => CmdResult::error(tr!(error_clipboard_path)),
                             }
                         }
-                        None => CmdResult::error("No selected line in preview"),
+                        None => CmdResult::error(tr!(error_no_selected_preview_line)),
                     })
                 }
             }
+            Internal::copy_numbered_lines => {
+                #[cfg(not(feature = "clipboard"))]
+                {
+                    Ok(CmdResult::error(tr!(error_clipboard_disabled)))
+                }
+                #[cfg(feature = "clipboard")]
+                {
+                    Ok(match self.no_opt_selection().lines_text(true) {
+                        Some(text) => {
+                            match terminal_clipboard::set_string(text) {
+                                Ok(()) => CmdResult::Keep,
+                                Err(_) => CmdResult::error(tr!(error_clipboard_lines)),
+                            }
+                        }
+                        None => CmdResult::error(tr!(error_no_selected_preview_line)),
+                    })
+                }
+            }
+            Internal::set_preview_encoding => {
+                let arg = input_invocation
+                    .and_then(|vi| vi.args.as_ref())
+                    .or(internal_exec.arg.as_ref());
+                let encoding = match arg.map(|arg| arg.parse::<Encoding>()) {
+                    Some(Ok(encoding)) => encoding,
+                    Some(Err(e)) => {
+                        return Ok(CmdResult::error(e));
+                    }
+                    None => {
+                        return Ok(CmdResult::error(tr!(error_no_encoding)));
+                    }
+                };
+                Ok(match Preview::with_encoding(&self.path, encoding, con) {
+                    Ok(preview) => {
+                        self.preview = preview;
+                        self.filtered_preview = None;
+                        self.prefered_mode = Some(PreviewMode::Text);
+                        CmdResult::Keep
+                    }
+                    Err(e) => CmdResult::error(tr!(error_encoding, encoding = encoding, error = e)),
+                })
+            }
+            Internal::compare_images => {
+                Ok(match staged_images_comparison(app_state) {
+                    Ok((path, preview)) => {
+                        self.path = path;
+                        self.preview = preview;
+                        self.filtered_preview = None;
+                        self.tail = None;
+                        self.dirty = true;
+                        CmdResult::Keep
+                    }
+                    Err(e) => CmdResult::error(e),
+                })
+            }
+            Internal::extend_selection_down => {
+                self.mut_preview().extend_selection(1);
+                Ok(CmdResult::Keep)
+            }
+            Internal::extend_selection_up => {
+                self.mut_preview().extend_selection(-1);
+                Ok(CmdResult::Keep)
+            }
             Internal::line_down => {
                 let count = get_arg(input_invocation, internal_exec, 1);
                 self.mut_preview().move_selection(count, true);
@@ -395,6 +582,31 @@ impl PanelState for PreviewState {
             Internal::preview_image => self.set_mode(PreviewMode::Image, con),
             Internal::preview_text => self.set_mode(PreviewMode::Text, con),
             Internal::preview_binary => self.set_mode(PreviewMode::Hex, con),
+            Internal::preview_tail => {
+                let res = self.set_mode(PreviewMode::Text, con)?;
+                if matches!(res, CmdResult::Keep) {
+                    self.start_tail();
+                }
+                Ok(res)
+            }
+            Internal::toggle_tail_follow => {
+                match &mut self.tail {
+                    Some(tail) => {
+                        tail.paused = !tail.paused;
+                        if !tail.paused {
+                            self.mut_preview().select_last();
+                        }
+                        Ok(CmdResult::Keep)
+                    }
+                    None => {
+                        let res = self.set_mode(PreviewMode::Text, con)?;
+                        if matches!(res, CmdResult::Keep) {
+                            self.start_tail();
+                        }
+                        Ok(res)
+                    }
+                }
+            }
             _ => self.on_internal_generic(
                 w,
                 internal_exec,
@@ -419,3 +631,20 @@ impl PanelState for PreviewState {
     }
 
 }
+
+/// return the current length of the file, or 0 if it can't be read
+fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |md| md.len())
+}
+
+/// build the preview comparing the two staged images, returning
+/// it with the path of the first one
+fn staged_images_comparison(app_state: &AppState) -> Result<(PathBuf, Preview), String> {
+    let paths = app_state.stage.paths();
+    if paths.len() != 2 {
+        return Err("exactly two images must be staged".to_string());
+    }
+    Preview::comparison(&paths[0], &paths[1])
+        .map(|preview| (paths[0].clone(), preview))
+        .map_err(|e| format!("can't compare images: {}", e))
+}
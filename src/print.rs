@@ -3,20 +3,86 @@
 use {
     crate::{
         app::*,
-        display::Screen,
+        cli::OutFormat,
+        display::{ansi_to_html, css_color, DisplayableTree, Screen},
         errors::ProgramError,
         launchable::Launchable,
-        skin::{PanelSkin, StyleMap},
-        tree::Tree,
+        skin::{ExtColorMap, Glyphs, PanelSkin, StyleMap},
+        tree::{Tree, TreeLine, TreeLineType},
     },
+    chrono::{TimeZone, Utc},
     crokey::crossterm::tty::IsTty,
     pathdiff,
+    serde::Serialize,
     std::{
+        borrow::Cow,
+        fs,
         io::{self, stdout},
         path::Path,
+        str::FromStr,
     },
 };
 
+/// how `:print_tree` renders the tree
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TreePrintFormat {
+    /// no style, whatever the output
+    Plain,
+    /// styled with ANSI escape codes, whatever the output
+    Ansi,
+    /// a HTML `pre` element, styled with the skin
+    Html,
+    /// a JSON object listing the lines with their shown columns
+    Json,
+    /// one CSV record per line, with the shown columns
+    Csv,
+}
+
+impl FromStr for TreePrintFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_ref() {
+            "plain" | "text" | "txt" | "no-color" => Ok(Self::Plain),
+            "ansi" | "color" => Ok(Self::Ansi),
+            "html" => Ok(Self::Html),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!(
+                "unknown print format {:?}: expected plain, ansi, html, json or csv", s
+            )),
+        }
+    }
+}
+
+impl From<OutFormat> for TreePrintFormat {
+    fn from(out_format: OutFormat) -> Self {
+        match out_format {
+            OutFormat::Txt => Self::Plain,
+            OutFormat::Ansi => Self::Ansi,
+            OutFormat::Html => Self::Html,
+            OutFormat::Json => Self::Json,
+            OutFormat::Csv => Self::Csv,
+        }
+    }
+}
+
+impl TreePrintFormat {
+    /// the format of a file, guessed from its extension, plain
+    /// text being the default
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::Json,
+            Some("csv") => Self::Csv,
+            Some("html") | Some("htm") => Self::Html,
+            _ => Self::Plain,
+        }
+    }
+    /// whether the skin's styles are applied
+    pub fn is_styled(self) -> bool {
+        matches!(self, Self::Ansi | Self::Html)
+    }
+}
+
 fn print_string(string: String, _con: &AppContext) -> io::Result<CmdResult> {
     Ok(
         // We write on stdout, but we must do it after app closing
@@ -81,19 +147,217 @@ pub fn print_tree(
     tree: &Tree,
     screen: Screen,
     panel_skin: &PanelSkin,
+    format: Option<TreePrintFormat>,
     con: &AppContext,
 ) -> Result<CmdResult, ProgramError> {
     // We write on stdout, but we must do it after app closing to have the normal terminal
-    let show_color = con.launch_args.color.unwrap_or_else(|| stdout().is_tty());
+    let format = format.or_else(|| con.launch_args.out_format.map(TreePrintFormat::from));
+    let show_color = match format {
+        Some(format) => format.is_styled(),
+        None => con.launch_args.color.unwrap_or_else(|| stdout().is_tty()),
+    };
     let styles = if show_color {
         panel_skin.styles.clone()
     } else {
         StyleMap::no_term()
     };
+    let format = match format {
+        Some(format) => format,
+        None if show_color => TreePrintFormat::Ansi,
+        None => TreePrintFormat::Plain,
+    };
     Ok(CmdResult::from(Launchable::tree_printer(
         tree,
         screen,
         styles,
         con.ext_colors.clone(),
+        con.glyphs.clone(),
+        format,
     )))
 }
+
+/// write the tree, as currently displayed but not limited to the
+/// screen height, in the file at the given path, in the format
+/// given by the path's extension
+pub fn print_tree_to(
+    tree: &Tree,
+    path: &Path,
+    screen: Screen,
+    panel_skin: &PanelSkin,
+    con: &AppContext,
+) -> Result<CmdResult, ProgramError> {
+    let format = TreePrintFormat::from_path(path);
+    let styles = if format.is_styled() {
+        panel_skin.styles.clone()
+    } else {
+        StyleMap::no_term()
+    };
+    let mut tree = tree.clone();
+    tree.scroll = 0;
+    let height = 1 + tree.visible_rows().count() as u16;
+    let mut string = tree_to_string(
+        &tree,
+        &styles,
+        &con.ext_colors,
+        &con.glyphs,
+        screen.width,
+        height,
+        format,
+    )?;
+    if !string.ends_with('\n') {
+        string.push('\n');
+    }
+    fs::write(path, string)?;
+    Ok(CmdResult::message(format!("tree written to {}", path.to_string_lossy())))
+}
+
+/// render the tree in the given format
+pub fn tree_to_string(
+    tree: &Tree,
+    styles: &StyleMap,
+    ext_colors: &ExtColorMap,
+    glyphs: &Glyphs,
+    width: u16,
+    height: u16,
+    format: TreePrintFormat,
+) -> Result<String, ProgramError> {
+    match format {
+        TreePrintFormat::Json => tree_to_json(tree),
+        TreePrintFormat::Csv => Ok(tree_to_csv(tree)),
+        _ => {
+            let dp = DisplayableTree::out_of_app(tree, styles, ext_colors, glyphs, width, height);
+            let mut ansi = Vec::new();
+            dp.write_on(&mut ansi)?;
+            let ansi = String::from_utf8_lossy(&ansi);
+            if format == TreePrintFormat::Html {
+                let fg = styles.default.get_fg().and_then(css_color)
+                    .unwrap_or_else(|| "#d0d0d0".to_string());
+                let bg = styles.default.get_bg().and_then(css_color)
+                    .unwrap_or_else(|| "#1c1c1c".to_string());
+                Ok(format!(
+                    "<pre style=\"color:{};background-color:{};\">\n{}</pre>",
+                    fg,
+                    bg,
+                    ansi_to_html(&ansi),
+                ))
+            } else {
+                Ok(ansi.replace("\r\n", "\n"))
+            }
+        }
+    }
+}
+
+/// a line of the tree, with the columns shown in the tree
+#[derive(Serialize)]
+struct ExportedLine {
+    path: String,
+    depth: u16,
+    #[serde(rename = "type")]
+    line_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_status: Option<&'static str>,
+}
+
+impl ExportedLine {
+    fn from(line: &TreeLine, tree: &Tree) -> Self {
+        let line_type = match &line.line_type {
+            TreeLineType::Dir => "dir",
+            TreeLineType::SymLink { .. } => "link",
+            TreeLineType::BrokenSymLink(_) => "broken_link",
+            _ => "file",
+        };
+        let size = line.sum
+            .filter(|_| tree.options.show_sizes)
+            .map(|sum| sum.to_size());
+        let date = line.sum
+            .filter(|_| tree.options.show_dates)
+            .and_then(|sum| sum.to_valid_seconds())
+            .map(|seconds| Utc.timestamp(seconds, 0).to_rfc3339());
+        let git_status = line.git_status
+            .filter(|_| tree.options.show_git_file_info)
+            .map(|status| status.name());
+        Self {
+            path: line.path.to_string_lossy().to_string(),
+            depth: line.depth,
+            line_type,
+            size,
+            date,
+            git_status,
+        }
+    }
+}
+
+fn exported_lines(tree: &Tree) -> impl Iterator<Item = ExportedLine> + '_ {
+    tree.lines.iter()
+        .filter(|line| line.line_type != TreeLineType::Pruning)
+        .map(move |line| ExportedLine::from(line, tree))
+}
+
+#[derive(Serialize)]
+struct ExportedTree {
+    root: String,
+    lines: Vec<ExportedLine>,
+}
+
+fn tree_to_json(tree: &Tree) -> Result<String, ProgramError> {
+    let exported = ExportedTree {
+        root: tree.root().to_string_lossy().to_string(),
+        lines: exported_lines(tree).collect(),
+    };
+    Ok(serde_json::to_string_pretty(&exported).map_err(io::Error::from)?)
+}
+
+/// quote the field if it contains a char needing it
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+fn tree_to_csv(tree: &Tree) -> String {
+    let options = &tree.options;
+    let mut csv = String::from("path,depth,type");
+    if options.show_sizes {
+        csv.push_str(",size");
+    }
+    if options.show_dates {
+        csv.push_str(",date");
+    }
+    if options.show_git_file_info {
+        csv.push_str(",git_status");
+    }
+    csv.push('\n');
+    for line in exported_lines(tree) {
+        csv.push_str(&csv_field(&line.path));
+        csv.push_str(&format!(",{},{}", line.depth, line.line_type));
+        if options.show_sizes {
+            csv.push(',');
+            if let Some(size) = line.size {
+                csv.push_str(&size.to_string());
+            }
+        }
+        if options.show_dates {
+            csv.push(',');
+            csv.push_str(line.date.as_deref().unwrap_or(""));
+        }
+        if options.show_git_file_info {
+            csv.push(',');
+            csv.push_str(line.git_status.unwrap_or(""));
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+#[test]
+fn test_csv_field() {
+    assert_eq!(csv_field("src/main.rs"), "src/main.rs");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+}
@@ -0,0 +1,10 @@
+//! Quick roots are standard locations (downloads, desktop, etc.)
+//! which can be opened from a picker, sorted by date
+
+mod quick_root;
+mod quick_roots_state;
+
+pub use {
+    quick_root::*,
+    quick_roots_state::QuickRootsState,
+};
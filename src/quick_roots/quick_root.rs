@@ -0,0 +1,54 @@
+use {
+    crate::path::{self, PathAnchor},
+    directories::UserDirs,
+    serde::Deserialize,
+    std::path::{Path, PathBuf},
+};
+
+/// a location proposed by the `:quick_roots` picker, defined
+/// in conf, for example
+///
+/// ```hjson
+/// {
+///     name: projects
+///     path: "~/dev"
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuickRoot {
+    pub name: String,
+    pub path: String,
+}
+
+impl QuickRoot {
+    /// the directory, with `~` expanded
+    pub fn dir(&self) -> PathBuf {
+        path::path_from(Path::new("/"), PathAnchor::Unspecified, &self.path)
+    }
+}
+
+/// the user directories defined with XDG (or their equivalent on
+/// Windows and Mac), used when no quick root is defined in conf
+pub fn default_quick_roots() -> Vec<QuickRoot> {
+    let user_dirs = match UserDirs::new() {
+        Some(user_dirs) => user_dirs,
+        None => {
+            return Vec::new();
+        }
+    };
+    [
+        ("downloads", user_dirs.download_dir()),
+        ("desktop", user_dirs.desktop_dir()),
+        ("documents", user_dirs.document_dir()),
+        ("pictures", user_dirs.picture_dir()),
+        ("videos", user_dirs.video_dir()),
+        ("music", user_dirs.audio_dir()),
+        ("home", Some(user_dirs.home_dir())),
+    ]
+        .iter()
+        .filter_map(|(name, dir)| dir.map(|dir| QuickRoot {
+            name: name.to_string(),
+            path: dir.to_string_lossy().to_string(),
+        }))
+        .collect()
+}
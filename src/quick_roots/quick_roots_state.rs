@@ -0,0 +1,210 @@
+use {
+    crate::{
+        app::*,
+        browser::BrowserState,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        task_sync::Dam,
+        tr,
+        tree::{Sort, TreeOptions},
+        verb::*,
+    },
+    std::path::{Path, PathBuf},
+    termimad::minimad::Alignment,
+};
+
+/// a quick root whose directory exists
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    dir: PathBuf,
+}
+
+/// an application state listing the quick roots, each one
+/// being opened in a new panel, sorted by date
+pub struct QuickRootsState {
+    list: FilteredList<Entry>,
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+/// the quick roots of the configuration whose directory exists
+fn entries(con: &AppContext) -> Vec<Entry> {
+    con.quick_roots.iter()
+        .map(|qr| Entry { name: qr.name.clone(), dir: qr.dir() })
+        .filter(|entry| entry.dir.is_dir())
+        .collect()
+}
+
+impl QuickRootsState {
+    pub fn new(
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> QuickRootsState {
+        QuickRootsState {
+            list: FilteredList::new(entries(con), matches),
+            tree_options,
+            mode: initial_mode(con),
+        }
+    }
+}
+
+fn matches(entry: &Entry, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&entry.name).is_some()
+}
+
+impl PanelState for QuickRootsState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::QuickRoots
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn selected_path(&self) -> Option<&Path> {
+        self.list.selected().map(|entry| entry.dir.as_path())
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        self.selected_path().map(|path| Selection {
+            path,
+            line: 0,
+            line_range: None,
+            text_view: None,
+            stype: SelectionType::Directory,
+            is_exe: false,
+        })
+    }
+
+    fn refresh(&mut self, _screen: Screen, con: &AppContext) -> Command {
+        let entries = entries(con);
+        self.list.set_items(entries, |a, b| a.dir == b.dir);
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let w_name = list.visible()
+            .map(|e| e.name.chars().count())
+            .max().unwrap_or(0)
+            .max("name".len());
+        let styles = &disc.panel_skin.styles;
+        list.queue_table_head(
+            w,
+            disc,
+            &[ListColumn::new("name", w_name, Alignment::Left)],
+            |cw| {
+                cw.queue_str(&styles.default, "directory")?;
+                Ok(())
+            },
+        )?;
+        self.list.queue_table_rows(w, disc, |cw, entry, row| {
+            row.queue_matched_cell(cw, &entry.name, row.txt_style, w_name, Alignment::Left)?;
+            let dir_style = row.style(&styles.directory);
+            cw.queue_g_string(&dir_style, entry.dir.to_string_lossy().to_string())?;
+            Ok(())
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        use Internal::*;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                // the quick root is opened in a new panel, most
+                // recent files first
+                match self.list.selected() {
+                    Some(entry) => {
+                        let mut tree_options = self.tree_options.without_pattern();
+                        tree_options.sort = Sort::Date;
+                        let state = BrowserState::new(
+                            entry.dir.clone(),
+                            tree_options,
+                            screen,
+                            con,
+                            &Dam::unlimited(),
+                        );
+                        match state {
+                            Ok(state) => CmdResult::NewPanel {
+                                state: Box::new(state),
+                                purpose: PanelPurpose::None,
+                                direction: HDir::Right,
+                            },
+                            Err(e) => CmdResult::error(e.to_string()),
+                        }
+                    }
+                    None => CmdResult::error(tr!(error_no_selected_quick_root)),
+                }
+            }
+            open_leave => CmdResult::PopStateAndReapply,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
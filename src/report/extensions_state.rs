@@ -0,0 +1,352 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        browser::BrowserState,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        task_sync::Dam,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    fnv::FnvHashMap,
+    std::path::{Path, PathBuf},
+    termimad::*,
+};
+
+/// the name under which files without extension are reported
+static NO_EXTENSION: &str = "(none)";
+
+/// the space and count of the files having a given extension
+#[derive(Debug, Clone)]
+pub struct ExtensionStat {
+    /// the lowercased extension, or "(none)"
+    pub extension: String,
+    pub count: usize,
+    pub size: u64,
+}
+
+impl ExtensionStat {
+    fn has_extension(&self) -> bool {
+        self.extension != NO_EXTENSION
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExtensionsSort {
+    Size,
+    Count,
+    Name,
+}
+
+/// scan the root and gather the stats per extension.
+///
+/// Return None if the scan was interrupted by the dam.
+fn compute_stats(root: &Path, dam: &Dam) -> Option<Vec<ExtensionStat>> {
+    let mut map: FnvHashMap<String, ExtensionStat> = FnvHashMap::default();
+    let complete = visit_files(root, dam, |path, md| {
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_else(|| NO_EXTENSION.to_string());
+        let stat = map.entry(extension.clone()).or_insert_with(|| ExtensionStat {
+            extension,
+            count: 0,
+            size: 0,
+        });
+        stat.count += 1;
+        stat.size += disk_size(md);
+    });
+    if complete {
+        Some(map.into_values().collect())
+    } else {
+        None
+    }
+}
+
+/// an application state listing, for all files under a root, the
+/// space taken and the number of files per extension
+pub struct ExtensionsReportState {
+    root: PathBuf,
+    list: FilteredList<ExtensionStat>,
+    scanned: bool, // false until the first scan is done
+    rescan: bool, // whether the stats must be computed again
+    total_size: u64,
+    total_count: usize,
+    sort: ExtensionsSort,
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+impl ExtensionsReportState {
+    /// build a state whose stats will be computed as a pending task
+    pub fn new(
+        root: &Path,
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> ExtensionsReportState {
+        ExtensionsReportState {
+            root: root.to_path_buf(),
+            list: FilteredList::new(Vec::new(), matches),
+            scanned: false,
+            rescan: false,
+            total_size: 0,
+            total_count: 0,
+            sort: ExtensionsSort::Size,
+            tree_options,
+            mode: initial_mode(con),
+        }
+    }
+    fn sort_stats(sort: ExtensionsSort, stats: &mut [ExtensionStat]) {
+        match sort {
+            ExtensionsSort::Size => {
+                stats.sort_by(|a, b| b.size.cmp(&a.size).then(a.extension.cmp(&b.extension)));
+            }
+            ExtensionsSort::Count => {
+                stats.sort_by(|a, b| b.count.cmp(&a.count).then(a.extension.cmp(&b.extension)));
+            }
+            ExtensionsSort::Name => {
+                stats.sort_by(|a, b| a.extension.cmp(&b.extension));
+            }
+        }
+    }
+    fn set_sort(&mut self, sort: ExtensionsSort) -> CmdResult {
+        self.sort = sort;
+        let mut stats = self.list.items().to_vec();
+        Self::sort_stats(sort, &mut stats);
+        self.list.reset_items(stats);
+        CmdResult::Keep
+    }
+}
+
+fn matches(stat: &ExtensionStat, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&stat.extension).is_some()
+}
+
+impl PanelState for ExtensionsReportState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Report
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn selected_path(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        Some(Selection {
+            path: &self.root,
+            line: 0,
+            line_range: None,
+            text_view: None,
+            stype: SelectionType::Directory,
+            is_exe: false,
+        })
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        // the current stats stay displayed until the new scan is done
+        self.rescan = true;
+        Command::empty()
+    }
+
+    fn get_pending_task(&self) -> Option<&'static str> {
+        if !self.scanned || self.rescan {
+            Some("scanning")
+        } else {
+            None
+        }
+    }
+
+    fn do_pending_task(
+        &mut self,
+        _app_state: &mut AppState,
+        _screen: Screen,
+        _con: &AppContext,
+        dam: &mut Dam,
+    ) -> Result<(), ProgramError> {
+        // if the scan is interrupted, stats aren't replaced and the
+        // scan will be done again
+        if let Some(mut stats) = compute_stats(&self.root, dam) {
+            Self::sort_stats(self.sort, &mut stats);
+            self.total_size = stats.iter().map(|s| s.size).sum();
+            self.total_count = stats.iter().map(|s| s.count).sum();
+            self.list.set_items(stats, |a, b| a.extension == b.extension);
+            self.scanned = true;
+            self.rescan = false;
+        }
+        Ok(())
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let size_format = self.tree_options.size_format;
+        let w_size = size_format.width(self.total_size).max("size".len());
+        let w_share = "share".len();
+        let w_count = list.visible()
+            .map(|s| s.count.to_string().len())
+            .max().unwrap_or(0)
+            .max("count".len());
+        let styles = &disc.panel_skin.styles;
+        let glyphs = &disc.con.glyphs;
+        let sort_mark = |sort: ExtensionsSort| if self.sort == sort { glyphs.sort_decreasing } else { ' ' };
+        let columns = [
+            ListColumn::new("size", w_size, Alignment::Right)
+                .with_mark(sort_mark(ExtensionsSort::Size)),
+            ListColumn::new("share", w_share, Alignment::Right),
+            ListColumn::new("count", w_count, Alignment::Right)
+                .with_mark(sort_mark(ExtensionsSort::Count)),
+        ];
+        list.queue_table_head(w, disc, &columns, |cw| {
+            cw.queue_str(&styles.default, "extension")?;
+            cw.queue_char(&styles.help_table_border, sort_mark(ExtensionsSort::Name))?;
+            if self.scanned {
+                cw.queue_g_string(
+                    &styles.default,
+                    format!(
+                        " {} files, {} in {}",
+                        self.total_count,
+                        size_format.format(self.total_size),
+                        self.root.to_string_lossy(),
+                    ),
+                )?;
+            }
+            Ok(())
+        })?;
+        let total_size = self.total_size;
+        self.list.queue_table_rows(w, disc, |cw, stat, row| {
+            // the cells with a sort mark in their title are one char wider
+            let size = format!("{} ", size_format.format(stat.size));
+            row.queue_cell(cw, &size, row.txt_style, w_size + 1, Alignment::Right)?;
+            let share = if total_size > 0 {
+                100.0 * stat.size as f64 / total_size as f64
+            } else {
+                0.0
+            };
+            row.queue_cell(cw, &format!("{:>4.0}%", share), row.txt_style, w_share, Alignment::Right)?;
+            let count = format!("{} ", stat.count);
+            row.queue_cell(cw, &count, &row.style(&styles.count), w_count + 1, Alignment::Right)?;
+            row.queue_matched(cw, &stat.extension, row.txt_style)
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        use Internal::*;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                let in_new_panel = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                match self.list.selected() {
+                    Some(stat) if stat.has_extension() => {
+                        // we list the files having this extension under the root
+                        let raw = format!(
+                            "{}\\.{}$/i",
+                            SearchMode::NameRegex.prefix(con),
+                            regex::escape(&stat.extension),
+                        );
+                        let parts = CommandParts::from(raw.clone());
+                        let mut tree_options = self.tree_options();
+                        tree_options.pattern = match InputPattern::new(raw, &parts.pattern, &app_state.tags, con) {
+                            Ok(pattern) => pattern,
+                            Err(e) => {
+                                return Ok(CmdResult::error(e.to_string()));
+                            }
+                        };
+                        let dam = Dam::unlimited();
+                        CmdResult::from_optional_state(
+                            BrowserState::new(
+                                self.root.clone(),
+                                tree_options,
+                                screen,
+                                con,
+                                &dam,
+                            ),
+                            None,
+                            in_new_panel,
+                        )
+                    }
+                    Some(_) => CmdResult::error(tr!(error_no_extension_search)),
+                    None if !self.scanned => CmdResult::error(tr!(error_scan_unfinished)),
+                    None => CmdResult::error(tr!(error_no_selected_extension)),
+                }
+            }
+            Internal::sort_by_size => self.set_sort(ExtensionsSort::Size),
+            Internal::sort_by_count => self.set_sort(ExtensionsSort::Count),
+            Internal::sort_by_type => self.set_sort(ExtensionsSort::Name),
+            open_leave => CmdResult::PopStateAndReapply,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
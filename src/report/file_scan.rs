@@ -0,0 +1,55 @@
+use {
+    crate::task_sync::Dam,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+
+/// the space taken on disk by a file, which may be less
+/// than its nominal size for sparse files (on unix)
+pub fn disk_size(md: &fs::Metadata) -> u64 {
+    #[cfg(unix)]
+    return (md.blocks() * 512).min(md.len());
+
+    #[cfg(not(unix))]
+    return md.len();
+}
+
+/// call the visitor on all the regular files under the root (links
+/// aren't followed).
+///
+/// Return false if the scan was interrupted by the dam.
+pub fn visit_files<F>(root: &Path, dam: &Dam, mut visitor: F) -> bool
+where
+    F: FnMut(&Path, &fs::Metadata),
+{
+    let mut dirs: Vec<PathBuf> = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        if dam.has_event() {
+            return false;
+        }
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                debug!("can't read {:?}: {}", &dir, e);
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let md = match entry.metadata() {
+                Ok(md) => md,
+                Err(_) => continue,
+            };
+            if md.is_dir() {
+                dirs.push(entry.path());
+            } else if md.is_file() {
+                visitor(&entry.path(), &md);
+            }
+        }
+    }
+    true
+}
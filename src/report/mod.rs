@@ -0,0 +1,12 @@
+//! Reports on the files under a root, answering questions like
+//! "what takes the space here?"
+
+mod extensions_state;
+mod file_scan;
+mod top_state;
+
+pub use {
+    extensions_state::*,
+    file_scan::*,
+    top_state::*,
+};
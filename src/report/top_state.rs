@@ -0,0 +1,341 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        browser::BrowserState,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        task_sync::Dam,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::{
+        cmp::Reverse,
+        collections::BinaryHeap,
+        path::{Path, PathBuf},
+        time::SystemTime,
+    },
+    termimad::*,
+};
+
+/// the number of files listed when no count is given to `:report_top`
+pub const DEFAULT_TOP_COUNT: usize = 50;
+
+/// a file found by the scan
+#[derive(Debug, Clone)]
+pub struct TopFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub time: SystemTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TopSort {
+    Largest,
+    Oldest,
+}
+
+/// the largest and the oldest files under a root
+struct TopFiles {
+    largest: Vec<TopFile>, // largest first
+    oldest: Vec<TopFile>, // oldest first
+}
+
+/// scan the root, keeping only the `count` largest and the `count`
+/// oldest files.
+///
+/// Return None if the scan was interrupted by the dam.
+fn compute_top(root: &Path, count: usize, dam: &Dam) -> Option<TopFiles> {
+    // the heaps' tops are the first files to drop when they're full
+    let mut largest: BinaryHeap<Reverse<(u64, SystemTime, PathBuf)>> = BinaryHeap::new();
+    let mut oldest: BinaryHeap<(SystemTime, u64, PathBuf)> = BinaryHeap::new();
+    let complete = visit_files(root, dam, |path, md| {
+        let size = disk_size(md);
+        let time = md.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        if largest.len() < count || largest.peek().map_or(false, |Reverse(f)| f.0 < size) {
+            largest.push(Reverse((size, time, path.to_path_buf())));
+            if largest.len() > count {
+                largest.pop();
+            }
+        }
+        if oldest.len() < count || oldest.peek().map_or(false, |f| f.0 > time) {
+            oldest.push((time, size, path.to_path_buf()));
+            if oldest.len() > count {
+                oldest.pop();
+            }
+        }
+    });
+    if !complete {
+        return None;
+    }
+    Some(TopFiles {
+        largest: largest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((size, time, path))| TopFile { path, size, time })
+            .collect(),
+        oldest: oldest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(time, size, path)| TopFile { path, size, time })
+            .collect(),
+    })
+}
+
+/// an application state listing the largest or the oldest files
+/// under a root, on which the usual file verbs (`:rm`, `:stage`, etc.)
+/// can be applied
+pub struct TopReportState {
+    root: PathBuf,
+    count: usize,
+    top: Option<TopFiles>, // None until the scan is done
+    sort: TopSort,
+    list: FilteredList<TopFile>, // the files in the current sort
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+impl TopReportState {
+    /// build a state whose list will be computed as a pending task
+    pub fn new(
+        root: &Path,
+        count: usize,
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> TopReportState {
+        TopReportState {
+            root: root.to_path_buf(),
+            count,
+            top: None,
+            sort: TopSort::Largest,
+            list: FilteredList::new(Vec::new(), matcher(root)),
+            tree_options,
+            mode: initial_mode(con),
+        }
+    }
+    fn files(&self) -> &[TopFile] {
+        match (&self.top, self.sort) {
+            (Some(top), TopSort::Largest) => &top.largest,
+            (Some(top), TopSort::Oldest) => &top.oldest,
+            (None, _) => &[],
+        }
+    }
+    fn set_sort(&mut self, sort: TopSort) -> CmdResult {
+        self.sort = sort;
+        self.list.reset_items(self.files().to_vec());
+        CmdResult::Keep
+    }
+}
+
+/// the path of the file, as displayed, relative to the root
+fn displayed_path<'p>(root: &Path, file: &'p TopFile) -> std::borrow::Cow<'p, str> {
+    file.path
+        .strip_prefix(root)
+        .unwrap_or(&file.path)
+        .to_string_lossy()
+}
+
+/// files are matched on their displayed path
+fn matcher(root: &Path) -> impl Fn(&TopFile, &Pattern) -> bool {
+    let root = root.to_path_buf();
+    move |file, pattern| pattern.score_of_string(&displayed_path(&root, file)).is_some()
+}
+
+impl PanelState for TopReportState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Report
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn selected_path(&self) -> Option<&Path> {
+        self.list.selected().map(|file| file.path.as_path())
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        self.selected_path().map(|path| Selection {
+            path,
+            line: 0,
+            line_range: None,
+            text_view: None,
+            stype: SelectionType::File,
+            is_exe: false,
+        })
+    }
+
+    /// remove the files which don't exist anymore, for example
+    /// after a `:rm`, without scanning again
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        if let Some(top) = self.top.as_mut() {
+            top.largest.retain(|file| file.path.exists());
+            top.oldest.retain(|file| file.path.exists());
+        }
+        self.list.set_items(self.files().to_vec(), |a, b| a.path == b.path);
+        Command::empty()
+    }
+
+    fn get_pending_task(&self) -> Option<&'static str> {
+        if self.top.is_none() {
+            Some("scanning")
+        } else {
+            None
+        }
+    }
+
+    fn do_pending_task(
+        &mut self,
+        _app_state: &mut AppState,
+        _screen: Screen,
+        _con: &AppContext,
+        dam: &mut Dam,
+    ) -> Result<(), ProgramError> {
+        // if the scan is interrupted, top stays None and the
+        // scan will be done again
+        self.top = compute_top(&self.root, self.count, dam);
+        self.list.reset_items(self.files().to_vec());
+        Ok(())
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let size_format = self.tree_options.size_format;
+        let max_size = list.visible().map(|file| file.size).max().unwrap_or(0);
+        let w_size = size_format.width(max_size).max("size".len());
+        let date_time_format = self.tree_options.date_time_format;
+        let w_date = date_time_format.date_width();
+        let styles = &disc.panel_skin.styles;
+        let glyphs = &disc.con.glyphs;
+        let sort_mark = |sort: TopSort| if self.sort == sort { glyphs.sort_decreasing } else { ' ' };
+        let columns = [
+            ListColumn::new("size", w_size, Alignment::Right)
+                .with_mark(sort_mark(TopSort::Largest)),
+            ListColumn::new("date", w_date, Alignment::Right)
+                .with_mark(sort_mark(TopSort::Oldest)),
+        ];
+        let title = match self.sort {
+            TopSort::Largest => "largest",
+            TopSort::Oldest => "oldest",
+        };
+        list.queue_table_head(w, disc, &columns, |cw| {
+            cw.queue_g_string(
+                &styles.default,
+                format!("{} {} files in {}", self.count, title, self.root.to_string_lossy()),
+            )?;
+            Ok(())
+        })?;
+        let root = &self.root;
+        self.list.queue_table_rows(w, disc, |cw, file, row| {
+            // the cells with a sort mark in their title are one char wider
+            let size = format!("{} ", size_format.format(file.size));
+            row.queue_cell(cw, &size, row.txt_style, w_size + 1, Alignment::Right)?;
+            let date = format!("{} ", date_time_format.format_date(file.time));
+            row.queue_cell(cw, &date, &row.style(&styles.dates), w_date + 1, Alignment::Right)?;
+            row.queue_matched(cw, &displayed_path(root, file), row.txt_style)
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                let in_new_panel = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                // we browse the parent of the file, with the file selected
+                match self.list.selected() {
+                    Some(file) => {
+                        let dir = file.path.parent().unwrap_or(&self.root).to_path_buf();
+                        let dam = Dam::unlimited();
+                        let state = BrowserState::new(
+                            dir,
+                            self.tree_options.without_pattern(),
+                            screen,
+                            con,
+                            &dam,
+                        ).map(|mut state| {
+                            if state.tree.try_select_path(&file.path) {
+                                state.tree.make_selection_visible(BrowserState::page_height(screen));
+                            }
+                            state
+                        });
+                        CmdResult::from_optional_state(state, None, in_new_panel)
+                    }
+                    None if self.top.is_none() => CmdResult::error(tr!(error_scan_unfinished)),
+                    None => CmdResult::error(tr!(error_no_selected_file)),
+                }
+            }
+            Internal::sort_by_size => self.set_sort(TopSort::Largest),
+            Internal::sort_by_date => self.set_sort(TopSort::Oldest),
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
@@ -0,0 +1,69 @@
+//! Opening of the system's GUI file manager, with a
+//! path highlighted when the OS makes it possible.
+
+use {
+    crate::errors::ProgramError,
+    std::{
+        path::Path,
+        process::{Command, Stdio},
+        thread,
+    },
+};
+
+/// build the command which, depending on the OS, opens the file
+/// manager with the given path selected
+#[cfg(target_os = "macos")]
+fn reveal_command(path: &Path) -> Command {
+    let mut command = Command::new("open");
+    command.arg("-R").arg(path);
+    command
+}
+
+/// build the command which, depending on the OS, opens the file
+/// manager with the given path selected
+#[cfg(windows)]
+fn reveal_command(path: &Path) -> Command {
+    use std::os::windows::process::CommandExt;
+    // explorer doesn't parse its arguments with the usual rules: the
+    // path must be quoted after the comma, not the whole argument
+    let mut command = Command::new("explorer");
+    command.raw_arg(format!("/select,\"{}\"", path.to_string_lossy()));
+    command
+}
+
+/// build the command which, depending on the OS, opens the file
+/// manager with the given path selected.
+///
+/// There's no standard way to highlight a file with xdg, so we
+/// open the parent directory (or the directory itself when it's
+/// the root)
+#[cfg(not(any(target_os = "macos", windows)))]
+fn reveal_command(path: &Path) -> Command {
+    let dir = path.parent().unwrap_or(path);
+    let mut command = Command::new("xdg-open");
+    command.arg(dir);
+    command
+}
+
+/// open the GUI file manager of the system, without waiting
+/// for it to be closed (the process is reaped in a background
+/// thread so that it doesn't stay as a zombie)
+pub fn reveal(path: &Path) -> Result<(), ProgramError> {
+    let mut command = reveal_command(path);
+    info!("revealing {:?} with {:?}", path, &command);
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|source| ProgramError::LaunchError {
+            program: format!("{:?}", command.get_program()),
+            source,
+        })?;
+    thread::spawn(move || {
+        if let Err(e) = child.wait() {
+            warn!("error while waiting for the file manager: {}", e);
+        }
+    });
+    Ok(())
+}
@@ -19,11 +19,11 @@ pub struct AppSkin {
 }
 
 impl AppSkin {
-    pub fn new(conf: &Conf, no_style: bool) -> Self {
+    pub fn new(conf: &Conf, glyphs: &Glyphs, no_style: bool) -> Self {
         if no_style {
             Self {
-                focused: PanelSkin::new(StyleMap::no_term()),
-                unfocused: PanelSkin::new(StyleMap::no_term()),
+                focused: PanelSkin::new(StyleMap::no_term(), glyphs),
+                unfocused: PanelSkin::new(StyleMap::no_term(), glyphs),
             }
         } else {
             let def_skin;
@@ -35,8 +35,8 @@ impl AppSkin {
             };
             let StyleMaps { focused, unfocused } = StyleMaps::create(skin);
             Self {
-                focused: PanelSkin::new(focused),
-                unfocused: PanelSkin::new(unfocused),
+                focused: PanelSkin::new(focused, glyphs),
+                unfocused: PanelSkin::new(unfocused, glyphs),
             }
         }
     }
@@ -0,0 +1,234 @@
+use {
+    crate::{
+        errors::ConfError,
+    },
+    ahash::AHashMap,
+    once_cell::sync::Lazy,
+    std::{
+        convert::TryFrom,
+        sync::Mutex,
+    },
+    termimad::{Filling, ProgressBar},
+};
+
+macro_rules! Glyphs {
+    (
+        $( $(#[doc = $doc:expr])* $name:ident: $default:expr, $ascii:expr, )*
+    ) => {
+        /// the characters drawing the borders of tables, the
+        /// scrollbars, the selection mark, the branches of the
+        /// tree and the various markers, which may be changed in
+        /// the `glyphs` section of the conf, for example when the
+        /// font lacks some of them
+        #[derive(Debug, Clone)]
+        pub struct Glyphs {
+            $( $(#[doc = $doc])* pub $name: char, )*
+            /// whether only ASCII must be written, which is the case
+            /// with the `--ascii` launch argument
+            pub ascii: bool,
+        }
+        impl Default for Glyphs {
+            fn default() -> Self {
+                Self {
+                    $( $name: $default, )*
+                    ascii: false,
+                }
+            }
+        }
+        impl Glyphs {
+            /// the glyphs of the `--ascii` mode, for terminals or log
+            /// viewers which can't display other chars
+            pub fn ascii() -> Self {
+                Self {
+                    $( $name: $ascii, )*
+                    ascii: true,
+                }
+            }
+            pub fn set(&mut self, name: &str, value: &str) -> Result<(), ConfError> {
+                let mut chars = value.chars();
+                let c = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => {
+                        return Err(ConfError::InvalidGlyph {
+                            name: name.to_string(),
+                            details: format!("{value:?} isn't a single char"),
+                        });
+                    }
+                };
+                // kebab-case names are accepted, as for conf fields
+                match name.replace('-', "_").as_str() {
+                    $( stringify!($name) => { self.$name = c; } )*
+                    _ => {
+                        return Err(ConfError::InvalidGlyph {
+                            name: name.to_string(),
+                            details: "unknown glyph".to_string(),
+                        });
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Glyphs defined as
+//    name: default, ascii,
+Glyphs! {
+    border_vertical: '│', '|',
+    border_horizontal: '─', '-',
+    /// where the line under the titles of a table crosses the columns
+    border_cross: '┼', '+',
+    border_top_left: '┌', '+',
+    border_top_right: '┐', '+',
+    border_bottom_left: '└', '+',
+    border_bottom_right: '┘', '+',
+    scrollbar_thumb: '▐', '#',
+    scrollbar_track: '▐', '|',
+    /// the mark before the selected line, when `show_selection_mark` is set
+    selection_mark: '▶', '>',
+    branch_vertical: '│', '|',
+    branch_horizontal: '─', '-',
+    /// the start of the branch of a child which isn't the last one
+    branch_middle: '├', '|',
+    /// the start of the branch of the last child
+    branch_last: '└', '`',
+    /// replaces the first horizontal part of the branch of staged paths
+    branch_staged: '◍', '*',
+    /// the filled part of the size and usage bars (the default
+    /// one is drawn with a precision finer than the char)
+    progress_bar: '█', '#',
+    staged_mark: '◍', '*',
+    marked_mark: '✔', '+',
+    lint_pass: '✓', 'v',
+    lint_fail: '✗', 'x',
+    rating_star: '★', '*',
+    sort_decreasing: '▼', 'v',
+    sort_increasing: '▲', '^',
+    /// the mark of the removable devices in the filesystems panel
+    removable_mark: '⏏', 'E',
+    git_branch_mark: '᚜', '@',
+    /// the mark of a truncated or not yet known content
+    ellipsis: '…', '~',
+}
+
+/// the frames of the spinners
+static SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+static ASCII_SPINNER: &[char] = &['|', '/', '-', '\\'];
+
+impl Glyphs {
+    /// the part of the line under the titles of a table which is
+    /// under a column of the given width and its left border
+    pub fn border_cross_line(&self, width: usize) -> String {
+        let mut line: String = std::iter::repeat(self.border_horizontal)
+            .take(width.saturating_sub(1))
+            .collect();
+        line.push(self.border_cross);
+        line
+    }
+    pub fn border_filling(&self) -> &'static Filling {
+        static_filling(self.border_horizontal)
+    }
+    pub fn branch_filling(&self) -> &'static Filling {
+        static_filling(self.branch_horizontal)
+    }
+    pub fn scrollbar(&self, thumb: bool) -> char {
+        if thumb {
+            self.scrollbar_thumb
+        } else {
+            self.scrollbar_track
+        }
+    }
+    /// a bar whose filled part is the given part (in [0, 1]) of
+    /// the given width in chars
+    pub fn progress_bar(&self, part: f32, width: usize) -> String {
+        if self.progress_bar == '█' {
+            // a precision of 1/8 of char is possible with the
+            // default glyph
+            format!("{:<width$}", ProgressBar::new(part, width), width = width)
+        } else {
+            let filled = ((part * width as f32).round() as usize).min(width);
+            let mut bar: String = std::iter::repeat(self.progress_bar)
+                .take(filled)
+                .collect();
+            bar.extend(std::iter::repeat(' ').take(width - filled));
+            bar
+        }
+    }
+    /// the frame of a spinner, the frame index being incremented
+    /// to animate it
+    pub fn spinner(&self, frame: usize) -> char {
+        let frames = if self.ascii { ASCII_SPINNER } else { SPINNER };
+        frames[frame % frames.len()]
+    }
+}
+
+/// replace the chars of the borders and bars drawn by termimad
+/// (for example in the help tables) with ASCII ones
+pub fn asciify(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '─' | '━' | '―' | '╌' | '═' => '-',
+            '│' | '┃' | '╎' | '║' => '|',
+            '\u{2500}'..='\u{257f}' => '+', // the other box drawing chars
+            '\u{2580}'..='\u{259f}' => '#', // the block elements
+            '•' => '*',
+            '…' => '~',
+            _ => c,
+        })
+        .collect()
+}
+
+/// return the filling made of the given char, built only once as
+/// termimad needs static fillings (there are only a few glyphs)
+fn static_filling(c: char) -> &'static Filling {
+    static FILLINGS: Lazy<Mutex<AHashMap<char, &'static Filling>>> = Lazy::new(|| {
+        Mutex::new(AHashMap::default())
+    });
+    let mut fillings = FILLINGS.lock().unwrap();
+    fillings
+        .entry(c)
+        .or_insert_with(|| Box::leak(Box::new(Filling::from_char(c))))
+}
+
+impl TryFrom<&AHashMap<String, String>> for Glyphs {
+    type Error = ConfError;
+    fn try_from(raw_map: &AHashMap<String, String>) -> Result<Self, Self::Error> {
+        let mut glyphs = Glyphs::default();
+        for (k, v) in raw_map {
+            glyphs.set(k, v)?;
+        }
+        Ok(glyphs)
+    }
+}
+
+#[cfg(test)]
+mod glyphs_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_glyphs_conf() {
+        let mut raw = AHashMap::default();
+        raw.insert("border_cross".to_string(), "+".to_string());
+        raw.insert("border_horizontal".to_string(), "-".to_string());
+        let glyphs = Glyphs::try_from(&raw).unwrap();
+        assert_eq!(glyphs.border_cross_line(4), "---+");
+        assert_eq!(glyphs.border_vertical, '│');
+        raw.insert("selection_mark".to_string(), "->".to_string());
+        assert!(Glyphs::try_from(&raw).is_err());
+        raw.remove("selection_mark");
+        raw.insert("selection-mark".to_string(), ">".to_string());
+        assert_eq!(Glyphs::try_from(&raw).unwrap().selection_mark, '>');
+        raw.insert("selection_arrow".to_string(), ">".to_string());
+        assert!(Glyphs::try_from(&raw).is_err());
+    }
+
+    #[test]
+    fn test_ascii_glyphs() {
+        let glyphs = Glyphs::ascii();
+        assert_eq!(glyphs.border_cross_line(3), "--+");
+        assert_eq!(glyphs.progress_bar(0.5, 4), "##  ");
+        assert_eq!(glyphs.spinner(5), '/');
+        assert_eq!(asciify("┌──┬─┐ │a│ ├─┼─┤ •b…"), "+--+-+ |a| +-+-+ *b~");
+    }
+}
@@ -1,13 +1,13 @@
 use {
-    super::StyleMap,
+    super::{Glyphs, StyleMap},
     termimad::{Alignment, LineStyle, MadSkin},
 };
 
 
 /// build a MadSkin, which will be used for markdown formatting
 /// for the help screen by applying the `help_*` entries
-/// of the skin.
-pub fn make_help_mad_skin(skin: &StyleMap) -> MadSkin {
+/// of the skin and the glyphs.
+pub fn make_help_mad_skin(skin: &StyleMap, glyphs: &Glyphs) -> MadSkin {
     let mut ms = MadSkin::default();
     ms.paragraph.compound_style = skin.help_paragraph.clone();
     ms.inline_code = skin.help_code.clone();
@@ -32,6 +32,13 @@ pub fn make_help_mad_skin(skin: &StyleMap) -> MadSkin {
     ms.scrollbar
         .thumb
         .set_compound_style(skin.scrollbar_thumb.clone());
+    ms.scrollbar.track.set_char(glyphs.scrollbar_track);
+    ms.scrollbar.thumb.set_char(glyphs.scrollbar_thumb);
+    if glyphs.ascii {
+        ms.bullet.set_char('*');
+        ms.quote_mark.set_char('>');
+        ms.horizontal_rule.set_char('-');
+    }
     ms
 }
 
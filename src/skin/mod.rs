@@ -2,6 +2,7 @@ mod app_skin;
 mod cli_mad_skin;
 pub mod colors;
 mod ext_colors;
+mod glyphs;
 mod help_mad_skin;
 mod panel_skin;
 mod purpose_mad_skin;
@@ -13,6 +14,7 @@ pub use {
     app_skin::AppSkin,
     cli_mad_skin::*,
     ext_colors::ExtColorMap,
+    glyphs::{asciify, Glyphs},
     help_mad_skin::*,
     panel_skin::PanelSkin,
     purpose_mad_skin::*,
@@ -17,10 +17,10 @@ pub struct PanelSkin {
 
 
 impl PanelSkin {
-    pub fn new(styles: StyleMap) -> Self {
+    pub fn new(styles: StyleMap, glyphs: &Glyphs) -> Self {
         let purpose_skin = make_purpose_mad_skin(&styles);
         let status_skin = StatusMadSkinSet::from_skin(&styles);
-        let help_skin = make_help_mad_skin(&styles);
+        let help_skin = make_help_mad_skin(&styles, glyphs);
         Self {
             styles,
             purpose_skin,
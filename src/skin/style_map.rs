@@ -168,6 +168,16 @@ StyleMap! {
     git_status_ignored: gray(17), None, []
     git_status_conflicted: ansi(88), None, []
     git_status_other: ansi(88), None, []
+    lint_pass: ansi(28), None, []
+    lint_fail: ansi(160), None, [Bold]
+    tag: ansi(174), None, []
+    rating: ansi(178), None, []
+    row_number: gray(11), None, []
+    size_growth: ansi(166), None, []
+    size_shrink: ansi(65), None, []
+    usage_alert: ansi(196), None, [Bold SlowBlink]
+    marked: ansi(214), None, [Bold]
+    new_file: None, ansi(22), []
     selected_line: None, gray(6), [] / None, gray(4), []
     char_match: Some(Green), None, []
     file_error: Some(Red), None, []
@@ -0,0 +1,257 @@
+//! Renaming of several paths in one go, by editing their list
+//! in a text editor, like `vidir`.
+
+use {
+    crate::{
+        app::AppContext,
+        display::W,
+        errors::ProgramError,
+        launchable::Launchable,
+        verb::Sandbox,
+    },
+    std::{
+        env,
+        fs,
+        io::Write,
+        path::{Path, PathBuf},
+    },
+};
+
+/// the renames to apply, checked for conflicts
+#[derive(Debug, PartialEq)]
+pub struct RenamePlan {
+    pub renames: Vec<(PathBuf, PathBuf)>,
+}
+
+/// the text buffer given to the editor: one path per line
+pub fn buffer(paths: &[PathBuf]) -> String {
+    let mut buffer = String::new();
+    for path in paths {
+        buffer.push_str(&path.to_string_lossy());
+        buffer.push('\n');
+    }
+    buffer
+}
+
+/// the command launching the editor of the user
+fn editor_command() -> Vec<String> {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .map(|editor| editor.split_whitespace().map(|s| s.to_string()).collect())
+        .unwrap_or_else(|| {
+            vec![if cfg!(windows) { "notepad" } else { "vi" }.to_string()]
+        })
+}
+
+impl RenamePlan {
+    /// build the plan from the edited buffer, which must have
+    /// one line per path, in the same order.
+    ///
+    /// Return an error describing the first conflict found, if any.
+    pub fn from_buffer(
+        paths: &[PathBuf],
+        buffer: &str,
+    ) -> Result<Self, String> {
+        let lines: Vec<&str> = buffer
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .collect();
+        let lines = match lines.split_last() {
+            Some((last, others)) if last.is_empty() && others.len() == paths.len() => others,
+            _ => &lines[..],
+        };
+        if lines.len() != paths.len() {
+            return Err(format!(
+                "expected {} lines, found {}: lines can't be added or removed",
+                paths.len(),
+                lines.len(),
+            ));
+        }
+        let mut renames = Vec::new();
+        for (idx, (src, line)) in paths.iter().zip(lines.iter()).enumerate() {
+            if line.trim().is_empty() {
+                return Err(format!("line {} is empty", idx + 1));
+            }
+            let dst = Path::new(line);
+            let dst = match src.parent() {
+                Some(parent) if dst.is_relative() => parent.join(dst),
+                _ => dst.to_path_buf(),
+            };
+            if &dst != src {
+                renames.push((src.clone(), dst));
+            }
+        }
+        let plan = Self { renames };
+        plan.check()?;
+        Ok(plan)
+    }
+
+    fn is_source(&self, path: &Path) -> bool {
+        self.renames.iter().any(|(src, _)| src == path)
+    }
+
+    /// check there's no conflict between the renames or with the
+    /// existing files
+    fn check(&self) -> Result<(), String> {
+        for (idx, (src, dst)) in self.renames.iter().enumerate() {
+            if self.renames[idx + 1..].iter().any(|(_, other)| other == dst) {
+                return Err(format!("{:?} is the target of several renames", dst));
+            }
+            if let Some((other, _)) = self.renames.iter().find(|(other, _)| {
+                other != src && src.starts_with(other)
+            }) {
+                return Err(format!("{:?} can't be renamed with its parent {:?}", src, other));
+            }
+            if dst.starts_with(src) {
+                return Err(format!("{:?} can't be moved inside itself", src));
+            }
+            if dst.exists() && !self.is_source(dst) {
+                return Err(format!("{:?} already exists", dst));
+            }
+            if let Some(parent) = dst.parent() {
+                if !parent.as_os_str().is_empty() && !parent.is_dir() {
+                    return Err(format!("directory {:?} doesn't exist", parent));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty()
+    }
+
+    /// a temporary name, in the same directory, for a source which
+    /// must be moved out of the way, not overwriting anything
+    fn tmp_path(&self, src: &Path) -> PathBuf {
+        (0..)
+            .map(|idx| {
+                let mut tmp_name = src.file_name().unwrap_or_default().to_os_string();
+                tmp_name.push(format!(".broot-rename-{}", idx));
+                src.with_file_name(tmp_name)
+            })
+            .find(|tmp| {
+                fs::symlink_metadata(tmp).is_err()
+                    && !self.renames.iter().any(|(_, dst)| dst == tmp)
+            })
+            .unwrap() // the range is infinite
+    }
+
+    /// apply the renames, all or none: if one fails, the ones
+    /// already done are reverted.
+    ///
+    /// The sources which are also targets (swaps, cycles) are first
+    /// moved to temporary names.
+    pub fn execute(&self) -> Result<(), ProgramError> {
+        let mut done = Vec::new();
+        let res = self.apply(&mut done);
+        if let Err(e) = &res {
+            warn!("renaming failed ({}), reverting {} renames", e, done.len());
+            for (src, dst) in done.iter().rev() {
+                if let Err(e) = fs::rename(dst, src) {
+                    warn!("failed to revert the rename {:?} -> {:?}: {}", src, dst, e);
+                }
+            }
+        }
+        res
+    }
+
+    /// apply the renames, recording in `done` the successful ones
+    fn apply(&self, done: &mut Vec<(PathBuf, PathBuf)>) -> Result<(), ProgramError> {
+        let mut moves = Vec::new();
+        for (src, dst) in &self.renames {
+            if self.renames.iter().any(|(_, other)| other == src) {
+                let tmp = self.tmp_path(src);
+                debug!("temporary rename {:?} -> {:?}", src, tmp);
+                fs::rename(src, &tmp)?;
+                done.push((src.clone(), tmp.clone()));
+                moves.push((tmp, dst));
+            } else {
+                moves.push((src.clone(), dst));
+            }
+        }
+        for (src, dst) in moves {
+            info!("renaming {:?} -> {:?}", src, dst);
+            fs::rename(&src, dst)?;
+            done.push((src, dst.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// let the user edit the list of paths in their editor and
+/// compute the renames to apply
+pub fn edit_renames(
+    w: &mut W,
+    paths: &[PathBuf],
+    con: &AppContext,
+) -> Result<Result<RenamePlan, String>, ProgramError> {
+    let mut file = tempfile::Builder::new()
+        .prefix("broot-rename-")
+        .suffix(".txt")
+        .tempfile()?;
+    file.write_all(buffer(paths).as_bytes())?;
+    file.flush()?;
+    let mut parts = editor_command();
+    parts.push(file.path().to_string_lossy().to_string());
+    let launchable = Launchable::program(parts, None, Sandbox::default(), con)?;
+    launchable.execute(Some(w))?;
+    let edited = fs::read_to_string(file.path())?;
+    Ok(RenamePlan::from_buffer(paths, &edited))
+}
+
+#[cfg(test)]
+mod bulk_rename_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rename_plan() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        let c = dir.path().join("c");
+        fs::write(&a, "a").unwrap();
+        fs::write(&b, "b").unwrap();
+        fs::write(&c, "c").unwrap();
+        let paths = vec![a.clone(), b.clone()];
+        // unchanged lines aren't renamed, relative names are in the same directory
+        let plan = RenamePlan::from_buffer(&paths, &format!("{}\nd\n", a.display())).unwrap();
+        assert_eq!(plan.renames, vec![(b.clone(), dir.path().join("d"))]);
+        // lines can't be removed
+        assert!(RenamePlan::from_buffer(&paths, "a\n").is_err());
+        // two paths can't get the same name
+        assert!(RenamePlan::from_buffer(&paths, "d\nd\n").is_err());
+        // an existing file which isn't renamed can't be overwritten
+        assert!(RenamePlan::from_buffer(&paths, "a\nc\n").is_err());
+        // but paths can be swapped
+        let plan = RenamePlan::from_buffer(&paths, "b\na\n").unwrap();
+        plan.execute().unwrap();
+        assert_eq!(fs::read_to_string(&a).unwrap(), "b");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "a");
+        // a file with the temporary name of a swapped path isn't overwritten
+        let tmp = dir.path().join("a.broot-rename-0");
+        fs::write(&tmp, "tmp").unwrap();
+        plan.execute().unwrap();
+        assert_eq!(fs::read_to_string(&a).unwrap(), "a");
+        assert_eq!(fs::read_to_string(&tmp).unwrap(), "tmp");
+        // when a rename fails, the ones already done are reverted
+        let plan = RenamePlan {
+            renames: vec![
+                (a.clone(), b.clone()),
+                (b.clone(), a.clone()),
+                (c.clone(), dir.path().join("d")),
+                (tmp.clone(), dir.path().join("missing/e")),
+            ],
+        };
+        assert!(plan.execute().is_err());
+        assert_eq!(fs::read_to_string(&a).unwrap(), "a");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "b");
+        assert_eq!(fs::read_to_string(&c).unwrap(), "c");
+        assert_eq!(fs::read_to_string(&tmp).unwrap(), "tmp");
+        assert!(!dir.path().join("d").exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 4);
+    }
+}
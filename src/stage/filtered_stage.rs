@@ -69,12 +69,20 @@ impl FilteredStage {
         fs
     }
     /// check whether the stage has changed, and update the
-    /// filtered list if necessary
-    pub fn update(&mut self, stage: &Stage) -> bool {
+    /// filtered list if necessary, keeping the selection on the
+    /// previously selected path when it's still there
+    pub fn update(&mut self, stage: &Stage, selected_path: Option<&Path>) -> bool {
         if stage.version() == self.stage_version {
             false
         } else {
+            self.stage_version = stage.version();
             self.compute(stage);
+            let kept = selected_path.map_or(false, |path| self.try_select_path(stage, path));
+            if !kept {
+                self.selection = self.selection
+                    .filter(|_| !self.paths_idx.is_empty())
+                    .map(|sel| sel.min(self.paths_idx.len() - 1));
+            }
             true
         }
     }
@@ -116,6 +124,14 @@ impl FilteredStage {
             false
         }
     }
+    pub fn try_select_path(&mut self, stage: &Stage, path: &Path) -> bool {
+        let pos = self.paths_idx.iter()
+            .position(|&idx| stage.paths().get(idx).map_or(false, |p| p == path));
+        if pos.is_some() {
+            self.selection = pos;
+        }
+        pos.is_some()
+    }
     pub fn selected_path<'s>(&self, stage: &'s Stage) -> Option<&'s Path> {
         self.selection
             .and_then(|pi| self.paths_idx.get(pi))
@@ -1,9 +1,11 @@
+mod bulk_rename;
 mod filtered_stage;
 mod stage;
 mod stage_state;
 mod stage_sum;
 
 pub use {
+    bulk_rename::*,
     filtered_stage::*,
     stage::*,
     stage_state::*,
@@ -51,6 +51,16 @@ impl Stage {
             false
         }
     }
+    /// replace a path, keeping its position. Return true when there's a change
+    pub fn replace(&mut self, old_path: &Path, new_path: PathBuf) -> bool {
+        if let Some(pos) = self.paths.iter().position(|p| p == old_path) {
+            self.version += 1;
+            self.paths[pos] = new_path;
+            true
+        } else {
+            false
+        }
+    }
     pub fn remove_idx(&mut self, idx: usize) {
         if idx < self.paths.len() {
             self.version += 1;
@@ -75,6 +85,16 @@ impl Stage {
     pub fn len(&self) -> usize {
         self.paths.len()
     }
+    /// the staged paths, each one followed by the separator
+    /// (a newline, or a NUL char for `xargs -0`)
+    pub fn paths_string(&self, separator: char) -> String {
+        let mut string = String::new();
+        for path in &self.paths {
+            string.push_str(&path.to_string_lossy());
+            string.push(separator);
+        }
+        string
+    }
     pub fn version(&self) -> usize {
         self.version
     }
@@ -8,6 +8,7 @@ use {
         pattern::*,
         skin::*,
         task_sync::Dam,
+        tr,
         tree::*,
         verb::*,
     },
@@ -15,7 +16,7 @@ use {
         cursor,
         QueueableCommand,
     },
-    std::path::{Path},
+    std::path::{Path, PathBuf},
     termimad::{Area, CropWriter, SPACE_FILLING},
     unicode_width::{UnicodeWidthChar, UnicodeWidthStr},
 };
@@ -23,7 +24,6 @@ use {
 static TITLE: &str = "Staging Area"; // no wide char allowed here
 static COUNT_LABEL: &str = " count: ";
 static SIZE_LABEL: &str = " size: ";
-static ELLIPSIS: char = '…';
 
 pub struct StageState {
 
@@ -40,6 +40,10 @@ pub struct StageState {
 
     stage_sum: StageSum,
 
+    /// the path selected at the last display, to be kept
+    /// selected when the stage changes
+    selected_path: Option<PathBuf>,
+
 }
 
 impl StageState {
@@ -60,6 +64,7 @@ impl StageState {
             mode: initial_mode(con),
             page_height: 0,
             stage_sum: StageSum::default(),
+            selected_path: None,
         }
     }
 
@@ -79,7 +84,9 @@ impl StageState {
 
     pub fn fix_scroll(&mut self) {
         let len = self.filtered_stage.len();
-        if self.scroll + self.page_height > len {
+        if let Some(sel) = self.filtered_stage.selection() {
+            self.scroll = fix_scroll(self.scroll, sel, len, self.page_height);
+        } else if self.scroll + self.page_height > len {
             self.scroll = if len > self.page_height {
                 len - self.page_height
             } else {
@@ -115,7 +122,7 @@ impl StageState {
             show_count_label = true;
             if self.tree_options.show_sizes {
                 if let Some(sum) = self.stage_sum.computed() {
-                    let size = file_size::fit_4(sum.to_size());
+                    let size = self.tree_options.size_format.format(sum.to_size());
                     let size_len = SIZE_LABEL.len() + size.len();
                     if size_len < rem {
                         rem -= size_len;
@@ -221,6 +228,8 @@ impl PanelState for StageState {
                 stype: SelectionType::File,
                 is_exe: false,
                 line: 0,
+                line_range: None,
+                text_view: None,
             }),
             _ => SelInfo::More(&app_state.stage),
         }
@@ -245,7 +254,7 @@ impl PanelState for StageState {
         con: &AppContext,
     ) -> CmdResult {
         if in_new_panel {
-            CmdResult::error("stage can't be displayed in two panels")
+            CmdResult::error(tr!(error_stage_two_panels))
         } else {
             let mut new_options= self.tree_options();
             let message = change_options(&mut new_options);
@@ -256,6 +265,7 @@ impl PanelState for StageState {
                 tree_options: new_options,
                 page_height: self.page_height,
                 stage_sum: self.stage_sum,
+                selected_path: self.selected_path.clone(),
             });
             CmdResult::NewState { state, message: Some(message) }
         }
@@ -293,25 +303,27 @@ impl PanelState for StageState {
     ) -> Result<(), ProgramError> {
         let stage = &disc.app_state.stage;
         self.stage_sum.see_stage(stage); // this may invalidate the sum
-        if self.filtered_stage.update(stage) {
+        if self.filtered_stage.update(stage, self.selected_path.as_deref()) {
             self.fix_scroll();
         }
+        self.selected_path = self.filtered_stage.selected_path(stage).map(Path::to_path_buf);
         let area = &disc.state_area;
         let styles = &disc.panel_skin.styles;
+        let glyphs = &disc.con.glyphs;
         let width = area.width as usize;
-        w.queue(cursor::MoveTo(area.left, 0))?;
+        w.queue(cursor::MoveTo(area.left, area.top))?;
         let mut cw = CropWriter::new(w, width);
         self.write_title_line(stage, &mut cw, styles)?;
         let list_area = Area::new(area.left, area.top + 1, area.width, area.height - 1);
         self.page_height = list_area.height as usize;
         let pattern = &self.filtered_stage.pattern().pattern;
         let pattern_object = pattern.object();
-        let scrollbar = list_area.scrollbar(self.scroll, self.filtered_stage.len());
+        let scrollbar = disc.scrollbar(&list_area, self.scroll, self.filtered_stage.len());
         for idx in 0..self.page_height {
             let y = list_area.top + idx as u16;
             let stage_idx = idx + self.scroll;
             w.queue(cursor::MoveTo(area.left, y))?;
-            let mut cw = CropWriter::new(w, width - 1);
+            let mut cw = CropWriter::new(w, disc.content_width());
             let cw = &mut cw;
             if let Some((path, selected)) = self.filtered_stage.path_sel(stage, stage_idx) {
                 let mut style = if path.is_dir() {
@@ -337,7 +349,7 @@ impl PanelState for StageState {
                     style_match = &bg_style_match;
                 }
                 if disc.con.show_selection_mark && self.filtered_stage.has_selection() {
-                    cw.queue_char(style, if selected { '▶' } else { ' ' })?;
+                    cw.queue_char(style, if selected { glyphs.selection_mark } else { ' ' })?;
                 }
                 if pattern_object.subpath {
                     let label = path.to_string_lossy();
@@ -390,7 +402,7 @@ impl PanelState for StageState {
                                 }
                                 cw.queue_char(
                                     parent_style,
-                                    ELLIPSIS,
+                                    glyphs.ellipsis,
                                 )?;
                                 cw.queue_str(
                                     parent_style,
@@ -418,12 +430,7 @@ impl PanelState for StageState {
                 cw.fill(style, &SPACE_FILLING)?;
             }
             cw.fill(&styles.default, &SPACE_FILLING)?;
-            let scrollbar_style = if ScrollCommand::is_thumb(y, scrollbar) {
-                &styles.scrollbar_thumb
-            } else {
-                &styles.scrollbar_track
-            };
-            scrollbar_style.queue_str(w, "▐")?;
+            disc.queue_scrollbar(w, y, scrollbar)?;
         }
         Ok(())
     }
@@ -484,13 +491,13 @@ impl PanelState for StageState {
             }
             Internal::stage => {
                 // shall we restage what we just unstaged ?
-                CmdResult::error("nothing to stage here")
+                CmdResult::error(tr!(error_nothing_to_stage))
             }
             Internal::unstage | Internal::toggle_stage => {
                 if self.filtered_stage.unstage_selection(&mut app_state.stage) {
                     CmdResult::Keep
                 } else {
-                    CmdResult::error("you must select a path to unstage")
+                    CmdResult::error(tr!(error_unstage_needs_selection))
                 }
             }
             _ => self.on_internal_generic(
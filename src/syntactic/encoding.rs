@@ -0,0 +1,214 @@
+//! Detection and decoding of the text encodings supported
+//! in previews, besides UTF-8.
+
+use {
+    crate::errors::ProgramError,
+    std::{
+        char::REPLACEMENT_CHARACTER,
+        fmt,
+        fs::File,
+        io::Read,
+        path::Path,
+        str::FromStr,
+    },
+};
+
+/// how many bytes are read at the start of a file to guess its encoding
+const DETECTION_SIZE: u64 = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+    ShiftJis,
+}
+
+impl Encoding {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::Utf16Le => "utf-16le",
+            Self::Utf16Be => "utf-16be",
+            Self::Latin1 => "latin-1",
+            Self::ShiftJis => "shift-jis",
+        }
+    }
+
+    /// guess the encoding of a file from its first bytes.
+    ///
+    /// UTF-16 is recognized from its BOM or, for texts mostly made of
+    /// ASCII chars, from the positions of the zero bytes. Files which
+    /// aren't valid UTF-8 are assumed to be Shift-JIS when they look
+    /// like it, else latin-1 (binary files will later be rejected
+    /// because of their control chars).
+    pub fn detect(path: &Path) -> Result<Self, ProgramError> {
+        let mut head = Vec::new();
+        File::open(path)?
+            .take(DETECTION_SIZE)
+            .read_to_end(&mut head)?;
+        Ok(Self::detect_in(&head))
+    }
+
+    /// guess the encoding from the first bytes of a text, only
+    /// looking at the first few kilobytes
+    pub fn detect_in(head: &[u8]) -> Self {
+        let head = &head[..head.len().min(DETECTION_SIZE as usize)];
+        if head.starts_with(&[0xFF, 0xFE]) {
+            return Self::Utf16Le;
+        }
+        if head.starts_with(&[0xFE, 0xFF]) {
+            return Self::Utf16Be;
+        }
+        let pairs = head.len() / 2;
+        if pairs >= 2 {
+            let even_zeros = head.iter().step_by(2).filter(|&&b| b == 0).count();
+            let odd_zeros = head.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+            if even_zeros == 0 && odd_zeros * 10 >= pairs * 9 {
+                return Self::Utf16Le;
+            }
+            if odd_zeros == 0 && even_zeros * 10 >= pairs * 9 {
+                return Self::Utf16Be;
+            }
+        }
+        match std::str::from_utf8(head) {
+            Ok(_) => Self::Utf8,
+            // the head may end in the middle of a char
+            Err(e) if e.error_len().is_none() => Self::Utf8,
+            Err(_) if looks_like_shift_jis(head) => Self::ShiftJis,
+            Err(_) => Self::Latin1,
+        }
+    }
+
+    /// read the whole file and decode it. Should not be
+    /// called for UTF-8 files, which are read line per line.
+    pub fn decode_file(self, path: &Path) -> Result<String, ProgramError> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        Ok(self.decode(&bytes))
+    }
+
+    pub fn is_utf16(self) -> bool {
+        matches!(self, Self::Utf16Le | Self::Utf16Be)
+    }
+
+    /// the bytes of the given string once encoded, when
+    /// they're not the UTF-8 ones
+    pub fn encode_utf16(self, s: &str) -> Option<Vec<u8>> {
+        let to_bytes: fn(u16) -> [u8; 2] = match self {
+            Self::Utf16Le => u16::to_le_bytes,
+            Self::Utf16Be => u16::to_be_bytes,
+            _ => {
+                return None;
+            }
+        };
+        Some(s.encode_utf16().flat_map(to_bytes).collect())
+    }
+
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes).to_string(),
+            Self::Utf16Le => decode_utf16(bytes, u16::from_le_bytes),
+            Self::Utf16Be => decode_utf16(bytes, u16::from_be_bytes),
+            Self::Latin1 => bytes.iter().map(|&b| b as char).collect(),
+            Self::ShiftJis => encoding_rs::SHIFT_JIS
+                .decode_without_bom_handling(bytes)
+                .0
+                .into_owned(),
+        }
+    }
+}
+
+/// tell whether the bytes are valid Shift-JIS with at least one
+/// double byte char whose lead byte (in 0x81-0x9F, the C1 controls)
+/// can't be found in a latin-1 text
+fn looks_like_shift_jis(bytes: &[u8]) -> bool {
+    let mut has_c1_lead = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match b {
+            0x00..=0x7F | 0xA1..=0xDF => {
+                i += 1;
+            }
+            0x81..=0x9F | 0xE0..=0xFC => {
+                match bytes.get(i + 1) {
+                    Some(0x40..=0x7E | 0x80..=0xFC) => {}
+                    Some(_) => return false,
+                    None => break, // the head may end in the middle of a char
+                }
+                has_c1_lead |= b <= 0x9F;
+                i += 2;
+            }
+            _ => {
+                return false;
+            }
+        }
+    }
+    has_c1_lead
+}
+
+fn decode_utf16(bytes: &[u8], to_u16: fn([u8; 2]) -> u16) -> String {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|c| to_u16([c[0], c[1]]));
+    let s: String = char::decode_utf16(units)
+        .map(|r| r.unwrap_or(REPLACEMENT_CHARACTER))
+        .collect();
+    match s.strip_prefix('\u{FEFF}') {
+        Some(s) => s.to_string(),
+        None => s,
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_ref() {
+            "utf-8" | "utf8" => Ok(Self::Utf8),
+            "utf-16le" | "utf16le" | "utf-16" | "utf16" => Ok(Self::Utf16Le),
+            "utf-16be" | "utf16be" => Ok(Self::Utf16Be),
+            "latin-1" | "latin1" | "iso-8859-1" => Ok(Self::Latin1),
+            "shift-jis" | "shiftjis" | "sjis" => Ok(Self::ShiftJis),
+            _ => Err(format!(
+                "unsupported encoding {:?} (expected utf-8, utf-16le, utf-16be, latin-1 or shift-jis)",
+                s,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod encoding_tests {
+
+    use super::*;
+
+    #[test]
+    fn check_encoding_detection() {
+        assert_eq!(Encoding::detect_in(b"plain ascii\n"), Encoding::Utf8);
+        assert_eq!(Encoding::detect_in("déjà vu".as_bytes()), Encoding::Utf8);
+        assert_eq!(Encoding::detect_in(b"d\xe9j\xe0 vu"), Encoding::Latin1);
+        assert_eq!(Encoding::detect_in(b"\xff\xfea\x00b\x00"), Encoding::Utf16Le);
+        assert_eq!(Encoding::detect_in(b"a\x00b\x00c\x00\n\x00"), Encoding::Utf16Le);
+        assert_eq!(Encoding::detect_in(b"\x00a\x00b\x00c\x00\n"), Encoding::Utf16Be);
+        assert_eq!(Encoding::detect_in(b"\x82\xb1\x82\xf1\x82\xc9\x82\xbf\x82\xcd\n"), Encoding::ShiftJis);
+        assert_eq!(Encoding::detect_in(b"caf\xe9 cr\xe8me\n"), Encoding::Latin1);
+    }
+
+    #[test]
+    fn check_decoding() {
+        assert_eq!(Encoding::Latin1.decode(b"d\xe9j\xe0 vu"), "déjà vu");
+        assert_eq!(Encoding::Utf16Le.decode(b"\xff\xfe\xe9\x00t\x00\xe9\x00"), "été");
+        assert_eq!(Encoding::Utf16Be.decode(b"\x00\xe9\x00t\x00\xe9"), "été");
+        assert_eq!(Encoding::ShiftJis.decode(b"\x82\xb1\x82\xf1\x82\xc9\x82\xbf\x82\xcd"), "こんにちは");
+        assert_eq!(Encoding::Utf16Le.encode_utf16("été").unwrap(), b"\xe9\x00t\x00\xe9\x00");
+        assert_eq!(Encoding::Latin1.encode_utf16("été"), None);
+    }
+}
@@ -1,8 +1,10 @@
+mod encoding;
 mod syntactic_view;
 mod syntax_theme;
 mod syntaxer;
 
 pub use {
+    encoding::Encoding,
     syntactic_view::SyntacticView,
     syntaxer::{SYNTAXER, Syntaxer},
     syntax_theme::*,
@@ -1,11 +1,12 @@
 use {
     super::*,
     crate::{
-        app::{AppContext, LineNumber},
+        app::{AppContext, DisplayContext, LineNumber},
         command::{ScrollCommand, move_sel},
         display::{Screen, W},
         errors::*,
         pattern::{InputPattern, NameMatch},
+        preview::PreviewMode,
         skin::PanelSkin,
         task_sync::Dam,
     },
@@ -17,8 +18,9 @@ use {
     memmap2::Mmap,
     once_cell::sync::Lazy,
     std::{
+        borrow::Cow,
         fs::File,
-        io::{BufRead, BufReader},
+        io::{BufRead, BufReader, Cursor, Seek, SeekFrom},
         path::{Path, PathBuf},
         str,
     },
@@ -59,14 +61,20 @@ pub struct Line {
     pub name_match: Option<NameMatch>,
 }
 
+#[derive(Debug)]
 pub struct SyntacticView {
     pub path: PathBuf,
     pub pattern: InputPattern,
+    pub encoding: Encoding,
+    decoded: Option<String>,      // whole content, when not read from the file as UTF-8
     lines: Vec<Line>,
     scroll: usize,
     page_height: usize,
     selection_idx: Option<usize>, // index in lines of the selection, if any
+    range_anchor: Option<usize>,  // index in lines of the other end of a range selection
     total_lines_count: usize,     // including lines not filtered out
+    complete_len: usize,          // offset of the end of the last line ending with a newline
+    partial_line: bool,           // whether the last read line had no newline
 }
 
 impl SyntacticView {
@@ -81,14 +89,36 @@ impl SyntacticView {
         con: &AppContext,
         no_style: bool,
     ) -> Result<Option<Self>, ProgramError> {
+        Self::with_encoding(path, pattern, dam, con, no_style, None)
+    }
+
+    /// Return a prepared text view, decoding the file with the given
+    /// encoding or, when none is given, with the detected one
+    pub fn with_encoding(
+        path: &Path,
+        pattern: InputPattern,
+        dam: &mut Dam,
+        con: &AppContext,
+        no_style: bool,
+        encoding: Option<Encoding>,
+    ) -> Result<Option<Self>, ProgramError> {
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None => Encoding::detect(path)?,
+        };
         let mut sv = Self {
             path: path.to_path_buf(),
             pattern,
+            encoding,
+            decoded: None,
             lines: Vec::new(),
             scroll: 0,
             page_height: 0,
             selection_idx: None,
+            range_anchor: None,
             total_lines_count: 0,
+            complete_len: 0,
+            partial_line: false,
         };
         if sv.read_lines(dam, con, no_style)? {
             sv.select_first();
@@ -119,12 +149,66 @@ impl SyntacticView {
             return Err(ProgramError::ZeroLenFile);
         }
         let with_style = !no_style && md.len() < MAX_SIZE_FOR_STYLING;
-        let mut reader = BufReader::new(f);
+        let reader: Box<dyn BufRead> = if self.encoding == Encoding::Utf8 {
+            Box::new(BufReader::new(f))
+        } else {
+            let decoded = self.encoding.decode_file(&self.path)?;
+            let reader = Box::new(Cursor::new(decoded.clone().into_bytes()));
+            self.decoded = Some(decoded);
+            reader
+        };
         self.lines.clear();
-        let mut line = String::new();
         self.total_lines_count = 0;
-        let mut offset = 0;
-        let mut number = 0;
+        self.read_from(reader, 0, with_style, dam, con)
+    }
+
+    /// Read the lines appended to the file since the last reading,
+    /// the last line being read again when it had no newline.
+    ///
+    /// Return false when the file must be read again from the start
+    /// instead (not read as UTF-8, truncated, or interrupted)
+    pub fn read_appended_lines(
+        &mut self,
+        con: &AppContext,
+    ) -> Result<bool, ProgramError> {
+        if self.encoding != Encoding::Utf8 || self.decoded.is_some() {
+            return Ok(false);
+        }
+        let mut f = File::open(&self.path)?;
+        let len = f.metadata()?.len();
+        if len < (self.complete_len as u64) {
+            debug!("followed file was truncated");
+            return Ok(false);
+        }
+        if self.partial_line {
+            let complete_len = self.complete_len;
+            self.lines.retain(|line| line.start < complete_len);
+            self.total_lines_count -= 1;
+            self.partial_line = false;
+        }
+        f.seek(SeekFrom::Start(self.complete_len as u64))?;
+        let with_style = len < MAX_SIZE_FOR_STYLING;
+        let reader = Box::new(BufReader::new(f));
+        self.read_from(reader, self.complete_len, with_style, &mut Dam::unlimited(), con)
+    }
+
+    /// Read and append the lines given by the reader, whose first
+    /// byte is at `offset` in the file.
+    ///
+    /// Return true when there was no interruption
+    fn read_from(
+        &mut self,
+        mut reader: Box<dyn BufRead>,
+        mut offset: usize,
+        with_style: bool,
+        dam: &mut Dam,
+        con: &AppContext,
+    ) -> Result<bool, ProgramError> {
+        // when the conf forces the text mode, control chars don't make
+        // us give up (they're replaced on display)
+        let forced_text = PreviewMode::forced_for(&self.path, con) == Some(PreviewMode::Text);
+        let mut line = String::new();
+        let mut number = self.total_lines_count;
         static SYNTAXER: Lazy<Syntaxer> = Lazy::new(Syntaxer::default);
         let mut highlighter = if with_style {
             SYNTAXER.highlighter_for(&self.path, con)
@@ -137,8 +221,13 @@ impl SyntacticView {
             self.total_lines_count += 1;
             let start = offset;
             offset += line.len();
+            if line.ends_with('\n') {
+                self.complete_len = offset;
+            } else {
+                self.partial_line = true;
+            }
             for c in line.chars() {
-                if !is_char_printable(c) {
+                if !forced_text && !is_char_printable(c) {
                     debug!("unprintable char: {:?}", c);
                     return Err(ProgramError::UnprintableFile);
                 }
@@ -207,27 +296,78 @@ impl SyntacticView {
     pub fn get_selected_line(&self) -> Option<String> {
         self.selection_idx
             .and_then(|idx| self.lines.get(idx))
-            .and_then(|line| {
-                File::open(&self.path)
-                    .and_then(|file| unsafe { Mmap::map(&file) })
-                    .ok()
-                    .filter(|mmap| mmap.len() >= line.start + line.len)
-                    .and_then(|mmap| {
-                        String::from_utf8(
-                            (mmap[line.start..line.start + line.len]).to_vec(),
-                        ).ok()
-                    })
+            .and_then(|line| self.line_content(line))
+    }
+
+    /// read the content of a line, either in the decoded text
+    /// or in the file
+    fn line_content(&self, line: &Line) -> Option<String> {
+        if let Some(decoded) = &self.decoded {
+            return decoded
+                .get(line.start..line.start + line.len)
+                .map(|s| s.to_string());
+        }
+        File::open(&self.path)
+            .and_then(|file| unsafe { Mmap::map(&file) })
+            .ok()
+            .filter(|mmap| mmap.len() >= line.start + line.len)
+            .and_then(|mmap| {
+                String::from_utf8(
+                    (mmap[line.start..line.start + line.len]).to_vec(),
+                ).ok()
             })
     }
 
+    /// return the text of the selected lines (the range of lines if
+    /// there's one, else the selected line), as decoded, each line
+    /// being optionally prefixed with its number.
+    ///
+    /// When the view is filtered, only the matching lines are given.
+    pub fn get_selected_lines_text(&self, with_numbers: bool) -> Option<String> {
+        let idx = self.selection_idx?;
+        let anchor = self.range_anchor.unwrap_or(idx);
+        let lines = self.lines.get(anchor.min(idx)..=anchor.max(idx))?;
+        let number_width = lines.last()?.number.to_string().len();
+        let mut text = String::new();
+        for line in lines {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            if with_numbers {
+                text.push_str(&format!("{:>w$} ", line.number, w = number_width));
+            }
+            let content = self.line_content(line)?;
+            text.push_str(content.trim_end_matches(&['\n', '\r'][..]));
+        }
+        Some(text)
+    }
+
     pub fn get_selected_line_number(&self) -> Option<LineNumber> {
         self.selection_idx
             .map(|idx| self.lines[idx].number)
     }
+    /// return the numbers of the first and last lines of the
+    /// selected range, if there's one
+    pub fn get_selected_line_range(&self) -> Option<(LineNumber, LineNumber)> {
+        let anchor = self.range_anchor?;
+        let idx = self.selection_idx?;
+        let a = self.lines.get(anchor)?.number;
+        let b = self.lines.get(idx)?.number;
+        Some((a.min(b), a.max(b)))
+    }
+    fn is_in_range(&self, line_idx: usize) -> bool {
+        match (self.range_anchor, self.selection_idx) {
+            (Some(anchor), Some(idx)) => {
+                anchor.min(idx) <= line_idx && line_idx <= anchor.max(idx)
+            }
+            _ => false,
+        }
+    }
     pub fn unselect(&mut self) {
         self.selection_idx = None;
     }
     pub fn try_select_y(&mut self, y: u16) -> bool {
+        self.range_anchor = None;
         let idx = y as usize + self.scroll;
         if idx < self.lines.len() {
             self.selection_idx = Some(idx);
@@ -250,6 +390,15 @@ impl SyntacticView {
         }
     }
 
+    pub fn get_scroll(&self) -> usize {
+        self.scroll
+    }
+    /// set the scroll, as long as it doesn't hide the whole text
+    /// (the selection is made visible at next display)
+    pub fn set_scroll(&mut self, scroll: usize) {
+        self.scroll = scroll.min(self.lines.len().saturating_sub(1));
+    }
+
     pub fn try_select_line_number(&mut self, number: LineNumber) -> bool {
         // this could obviously be optimized
         for (idx, line) in self.lines.iter().enumerate() {
@@ -263,6 +412,7 @@ impl SyntacticView {
     }
 
     pub fn move_selection(&mut self, dy: i32, cycle: bool) {
+        self.range_anchor = None;
         if let Some(idx) = self.selection_idx {
             self.selection_idx = Some(move_sel(idx, self.lines.len(), dy, cycle));
         } else if !self.lines.is_empty() {
@@ -271,6 +421,14 @@ impl SyntacticView {
         self.ensure_selection_is_visible();
     }
 
+    /// move the selection, extending the range of selected lines
+    /// (starting one at the current selection if there's none)
+    pub fn extend_selection(&mut self, dy: i32) {
+        let anchor = self.range_anchor.or(self.selection_idx);
+        self.move_selection(dy, false);
+        self.range_anchor = anchor;
+    }
+
     pub fn try_scroll(
         &mut self,
         cmd: ScrollCommand,
@@ -302,11 +460,11 @@ impl SyntacticView {
     pub fn display(
         &mut self,
         w: &mut W,
-        _screen: Screen,
-        panel_skin: &PanelSkin,
+        disc: &DisplayContext,
         area: &Area,
-        con: &AppContext,
     ) -> Result<(), ProgramError> {
+        let panel_skin = &disc.panel_skin;
+        let con = &disc.con;
         if area.height as usize != self.page_height {
             self.page_height = area.height as usize;
             self.ensure_selection_is_visible();
@@ -325,7 +483,7 @@ impl SyntacticView {
             .unwrap_or(Color::AnsiValue(240));
         let match_bg = styles.preview_match.get_bg().unwrap_or(Color::AnsiValue(28));
         let code_width = area.width as usize - 1; // 1 char left for scrollbar
-        let scrollbar = area.scrollbar(self.scroll, self.lines.len());
+        let scrollbar = disc.scrollbar(area, self.scroll, self.lines.len());
         let scrollbar_fg = styles.scrollbar_thumb.get_fg()
             .or_else(|| styles.preview.get_fg())
             .unwrap_or(Color::White);
@@ -334,12 +492,20 @@ impl SyntacticView {
             let mut cw = CropWriter::new(w, code_width);
             let line_idx = self.scroll as usize + y;
             let selected = self.selection_idx == Some(line_idx);
-            let bg = if selected { selection_bg } else { normal_bg };
+            let in_range = selected || self.is_in_range(line_idx);
+            let bg = if in_range { selection_bg } else { normal_bg };
             let mut op_mmap: Option<Mmap> = None;
             if let Some(line) = self.lines.get(line_idx) {
                 let mut regions = &line.regions;
                 let regions_ur;
-                if regions.is_empty() && line.len > 0 {
+                if regions.is_empty() && line.len > 0 && self.decoded.is_some() {
+                    let string = self.line_content(line).unwrap_or_default();
+                    regions_ur = vec![Region {
+                        fg: normal_fg,
+                        string,
+                    }];
+                    regions = &regions_ur;
+                } else if regions.is_empty() && line.len > 0 {
                     if op_mmap.is_none() {
                         let file = File::open(&self.path)?;
                         let mmap = unsafe { Mmap::map(&file)? };
@@ -372,7 +538,7 @@ impl SyntacticView {
                 }
                 cw.w.queue(SetBackgroundColor(bg))?;
                 if con.show_selection_mark {
-                    cw.queue_unstyled_char(if selected { '▶' } else { ' ' })?;
+                    cw.queue_unstyled_char(if selected { con.glyphs.selection_mark } else { ' ' })?;
                 }
                 if let Some(nm) = &line.name_match {
                     let mut dec = 0;
@@ -385,33 +551,34 @@ impl SyntacticView {
                             for (cand_idx, cand_char) in s.chars().enumerate() {
                                 if pos_idx < pos.len() && pos[pos_idx] == cand_idx + dec {
                                     cw.w.queue(SetBackgroundColor(match_bg))?;
-                                    cw.queue_unstyled_char(cand_char)?;
+                                    cw.queue_unstyled_char(printable_char(cand_char))?;
                                     cw.w.queue(SetBackgroundColor(bg))?;
                                     pos_idx += 1;
                                 } else {
-                                    cw.queue_unstyled_char(cand_char)?;
+                                    cw.queue_unstyled_char(printable_char(cand_char))?;
                                 }
                             }
                             dec += s.chars().count();
                         } else {
-                            cw.queue_unstyled_str(s)?;
+                            cw.queue_unstyled_str(&printable_string(s))?;
                         }
                     }
                 } else {
                     for content in regions {
                         cw.w.queue(SetForegroundColor(content.fg))?;
-                        cw.queue_unstyled_str(content.string.trim_end_matches(is_char_end_of_line))?;
+                        let s = content.string.trim_end_matches(is_char_end_of_line);
+                        cw.queue_unstyled_str(&printable_string(s))?;
                     }
                 }
             }
             cw.fill(
-                if selected { &styles.selected_line } else { &styles.preview },
+                if in_range { &styles.selected_line } else { &styles.preview },
                 &SPACE_FILLING,
             )?;
             w.queue(SetBackgroundColor(bg))?;
             if is_thumb(y + area.top as usize, scrollbar) {
                 w.queue(SetForegroundColor(scrollbar_fg))?;
-                w.queue(Print('▐'))?;
+                w.queue(Print(con.glyphs.scrollbar_thumb))?;
             } else {
                 w.queue(Print(' '))?;
             }
@@ -438,6 +605,9 @@ impl SyntacticView {
         if s.len() + "lines: ".len() < width {
             s = format!("lines: {}", s);
         }
+        if self.encoding != Encoding::Utf8 && s.len() + self.encoding.name().len() + 3 < width {
+            s = format!("{} | {}", self.encoding, s);
+        }
         w.queue(cursor::MoveTo(
             area.left + area.width - s.len() as u16,
             area.top,
@@ -461,6 +631,20 @@ fn is_char_printable(c: char) -> bool {
     c == '\t' || c == '\n' || c == '\r' || !c.is_control()
 }
 
+/// Replace the char, when it can't be displayed (which is only possible
+/// when the text mode is forced), with a replacement char
+fn printable_char(c: char) -> char {
+    if is_char_printable(c) { c } else { '\u{FFFD}' }
+}
+
+fn printable_string(s: &str) -> Cow<'_, str> {
+    if s.chars().all(is_char_printable) {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(s.chars().map(printable_char).collect())
+    }
+}
+
 fn is_char_end_of_line(c: char) -> bool {
     c == '\n' || c == '\r'
 }
@@ -2,22 +2,32 @@
 //!
 //! This enumeration may change but right now the values are the ones from
 //!  https://docs.rs/syntect/latest/syntect/highlighting/struct.ThemeSet.html
+//! plus the themes loaded from the .tmTheme files of the `syntax_themes_dir`
+//! set in conf.
 
 use {
     crate::{
         errors::ConfError,
     },
     serde::{Deserialize, Deserializer},
-    std::str::FromStr,
+    std::{
+        collections::BTreeMap,
+        path::Path,
+        str::FromStr,
+    },
+    syntect::highlighting::{Theme, ThemeSet},
 };
 
 macro_rules! Themes {
     (
         $($enum_name:ident: $syntect_name: literal,)*
     ) => {
-        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[derive(Debug, Clone, PartialEq, Eq)]
         pub enum SyntaxTheme {
             $($enum_name,)*
+            /// a theme loaded from a .tmTheme file, identified
+            /// by the file stem
+            Custom(String),
         }
         impl FromStr for SyntaxTheme {
             type Err = ConfError;
@@ -36,16 +46,18 @@ macro_rules! Themes {
             }
         }
         impl SyntaxTheme {
-            pub fn name(self) -> &'static str {
+            pub fn name(&self) -> &str {
                 use crate::syntactic::SyntaxTheme::*;
                 match self {
                     $($enum_name => stringify!($enum_name),)*
+                    Custom(name) => name,
                 }
             }
-            pub fn syntect_name(self) -> &'static str {
+            pub fn syntect_name(&self) -> &str {
                 use crate::syntactic::SyntaxTheme::*;
                 match self {
                     $($enum_name => $syntect_name,)*
+                    Custom(name) => name,
                 }
             }
         }
@@ -71,11 +83,26 @@ Themes! {
 }
 
 impl<'de> Deserialize<'de> for SyntaxTheme {
+    /// Names which aren't the ones of embedded themes are assumed to
+    /// be the ones of custom themes. They're checked when the custom
+    /// themes are loaded.
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where D: Deserializer<'de>
     {
         let s = String::deserialize(deserializer)?;
-        FromStr::from_str(&s).map_err(serde::de::Error::custom)
+        Ok(SyntaxTheme::from_str(&s).unwrap_or(SyntaxTheme::Custom(s)))
     }
 }
 
+
+/// Load the themes of the .tmTheme files found in a directory,
+/// keyed by their file stems
+pub fn load_custom_syntax_themes(
+    dir: &Path,
+) -> Result<BTreeMap<String, Theme>, ConfError> {
+    ThemeSet::load_from_folder(dir)
+        .map(|theme_set| theme_set.themes)
+        .map_err(|e| ConfError::SyntaxThemesLoading {
+            details: format!("{dir:?}: {e}"),
+        })
+}
@@ -1,6 +1,7 @@
 use {
     crate::{
         app::AppContext,
+        syntactic::SyntaxTheme,
     },
     once_cell::sync::Lazy,
     std::path::Path,
@@ -39,14 +40,18 @@ impl Syntaxer {
     pub fn highlighter_for<'s, 'p>(
         &'s self,
         path: &'p Path,
-        con: &AppContext,
+        con: &'s AppContext,
     ) -> Option<HighlightLines<'s>> {
         path.extension()
             .and_then(|e| e.to_str())
             .and_then(|ext| self.syntax_set.find_syntax_by_extension(ext))
             .map(|syntax| {
-                let theme = con.syntax_theme.unwrap_or_default();
-                let theme = self.theme_set.themes.get(theme.syntect_name())
+                let theme = con.syntax_theme.clone().unwrap_or_default();
+                let theme = match &theme {
+                    SyntaxTheme::Custom(name) => con.custom_syntax_themes.get(name),
+                    _ => self.theme_set.themes.get(theme.syntect_name()),
+                };
+                let theme = theme
                     .unwrap_or_else(|| self.theme_set.themes.iter().next().unwrap().1);
                 HighlightLines::new(syntax, theme)
             })
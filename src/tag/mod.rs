@@ -0,0 +1,10 @@
+//! Tags and star ratings given by the user to files, stored in
+//! sidecar files of broot's data directory
+
+mod tag_store;
+mod tags_state;
+
+pub use {
+    tag_store::{TagStore, MAX_RATING},
+    tags_state::TagsState,
+};
@@ -0,0 +1,195 @@
+use {
+    crate::conf,
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fs,
+        io,
+        path::{Path, PathBuf},
+    },
+};
+
+/// the highest rating a file can be given
+pub const MAX_RATING: u8 = 5;
+
+/// the tags of files, as stored in the tags file, which
+/// has one `tag<TAB>path` line per tagged file and tag,
+/// and their star ratings, stored in the ratings file with
+/// one `rating<TAB>path` line per rated file
+#[derive(Debug, Clone, Default)]
+pub struct TagStore {
+    tags: BTreeMap<PathBuf, BTreeSet<String>>,
+    ratings: BTreeMap<PathBuf, u8>,
+}
+
+impl TagStore {
+    /// the file where tags are stored
+    pub fn file() -> PathBuf {
+        conf::app_dirs().data_dir().join("tags")
+    }
+    /// the file where ratings are stored
+    pub fn ratings_file() -> PathBuf {
+        conf::app_dirs().data_dir().join("ratings")
+    }
+    /// read the tags and ratings files, returning an empty store when
+    /// there's none or when they can't be read
+    pub fn load() -> Self {
+        let mut store = Self::parse(&read_or_empty(&Self::file()));
+        store.parse_ratings(&read_or_empty(&Self::ratings_file()));
+        store
+    }
+    pub fn parse(content: &str) -> Self {
+        let mut store = Self::default();
+        for line in content.lines() {
+            if let Some((tag, path)) = line.split_once('\t') {
+                store.add(Path::new(path), tag);
+            }
+        }
+        store
+    }
+    /// read the content of a ratings file, ignoring invalid lines
+    pub fn parse_ratings(&mut self, content: &str) {
+        for line in content.lines() {
+            if let Some((rating, path)) = line.split_once('\t') {
+                if let Ok(rating) = rating.parse() {
+                    self.set_rating(Path::new(path), rating);
+                }
+            }
+        }
+    }
+    pub fn save(&self) -> io::Result<()> {
+        let file = Self::file();
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, self.to_string())?;
+        fs::write(Self::ratings_file(), self.ratings_to_string())
+    }
+    pub fn ratings_to_string(&self) -> String {
+        let mut s = String::new();
+        for (path, rating) in &self.ratings {
+            s.push_str(&format!("{}\t{}\n", rating, path.to_string_lossy()));
+        }
+        s
+    }
+    /// tags are normalized: no whitespace nor tab, which would
+    /// break the storage format
+    pub fn is_valid_tag(tag: &str) -> bool {
+        !tag.is_empty() && !tag.contains(char::is_whitespace)
+    }
+    /// add a tag to a path, return false if it was already there
+    pub fn add(&mut self, path: &Path, tag: &str) -> bool {
+        self.tags
+            .entry(path.to_path_buf())
+            .or_default()
+            .insert(tag.to_string())
+    }
+    /// remove a tag from a path, return false if it wasn't there
+    pub fn remove(&mut self, path: &Path, tag: &str) -> bool {
+        let removed = self.tags
+            .get_mut(path)
+            .map_or(false, |tags| tags.remove(tag));
+        if self.tags.get(path).map_or(false, |tags| tags.is_empty()) {
+            self.tags.remove(path);
+        }
+        removed
+    }
+    pub fn tags_of(&self, path: &Path) -> Option<&BTreeSet<String>> {
+        self.tags.get(path)
+    }
+    pub fn is_tagged(&self, path: &Path) -> bool {
+        self.tags.contains_key(path)
+    }
+    /// the paths having the given tag
+    pub fn paths_with<'s>(&'s self, tag: &'s str) -> impl Iterator<Item = &'s PathBuf> + 's {
+        self.tags
+            .iter()
+            .filter(move |(_, tags)| tags.contains(tag))
+            .map(|(path, _)| path)
+    }
+    /// set the rating of a path, 0 meaning no rating, return false
+    /// if it was already this one
+    ///
+    /// Ratings above MAX_RATING are capped.
+    pub fn set_rating(&mut self, path: &Path, rating: u8) -> bool {
+        let rating = rating.min(MAX_RATING);
+        let old = if rating == 0 {
+            self.ratings.remove(path)
+        } else {
+            self.ratings.insert(path.to_path_buf(), rating)
+        };
+        old.unwrap_or(0) != rating
+    }
+    /// the rating of a path, 0 when it's not rated
+    pub fn rating_of(&self, path: &Path) -> u8 {
+        self.ratings.get(path).copied().unwrap_or(0)
+    }
+    pub fn is_rated(&self, path: &Path) -> bool {
+        self.ratings.contains_key(path)
+    }
+    /// all the tags, with the number of paths having them
+    pub fn counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for tag in self.tags.values().flatten() {
+            *counts.entry(tag.as_str()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+fn read_or_empty(file: &Path) -> String {
+    if !file.exists() {
+        return String::new();
+    }
+    fs::read_to_string(file).unwrap_or_else(|e| {
+        warn!("failed to read {:?}: {}", file, e);
+        String::new()
+    })
+}
+
+impl std::fmt::Display for TagStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (path, tags) in &self.tags {
+            for tag in tags {
+                writeln!(f, "{}\t{}", tag, path.to_string_lossy())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tag_store_tests {
+
+    use super::*;
+
+    #[test]
+    fn check_tag_store_round_trip() {
+        let mut store = TagStore::default();
+        assert!(store.add(Path::new("/a/b.txt"), "work"));
+        assert!(store.add(Path::new("/a/b.txt"), "urgent"));
+        assert!(!store.add(Path::new("/a/b.txt"), "work"));
+        assert!(store.add(Path::new("/c with space"), "work"));
+        let store = TagStore::parse(&store.to_string());
+        assert_eq!(store.counts().get("work"), Some(&2));
+        assert_eq!(store.paths_with("urgent").count(), 1);
+        let mut store = store;
+        assert!(store.remove(Path::new("/a/b.txt"), "urgent"));
+        assert!(store.remove(Path::new("/a/b.txt"), "work"));
+        assert!(!store.is_tagged(Path::new("/a/b.txt")));
+    }
+
+    #[test]
+    fn check_ratings_round_trip() {
+        let mut store = TagStore::default();
+        assert!(store.set_rating(Path::new("/photos/a.jpg"), 4));
+        assert!(!store.set_rating(Path::new("/photos/a.jpg"), 4));
+        assert!(store.set_rating(Path::new("/photos/b c.jpg"), 9));
+        let mut read = TagStore::default();
+        read.parse_ratings(&store.ratings_to_string());
+        assert_eq!(read.rating_of(Path::new("/photos/a.jpg")), 4);
+        assert_eq!(read.rating_of(Path::new("/photos/b c.jpg")), MAX_RATING);
+        assert!(read.set_rating(Path::new("/photos/a.jpg"), 0));
+        assert!(!read.is_rated(Path::new("/photos/a.jpg")));
+        assert_eq!(read.rating_of(Path::new("/photos/d.jpg")), 0);
+    }
+}
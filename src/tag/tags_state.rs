@@ -0,0 +1,208 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        browser::BrowserState,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        task_sync::Dam,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::path::Path,
+    termimad::minimad::Alignment,
+};
+
+/// a tag with the number of files having it
+#[derive(Debug, Clone)]
+struct TagEntry {
+    name: String,
+    count: usize,
+}
+
+/// an application state listing all the tags given to files,
+/// from which the files having a tag can be listed
+pub struct TagsState {
+    list: FilteredList<TagEntry>,
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+fn tag_entries(store: &TagStore) -> Vec<TagEntry> {
+    store.counts()
+        .into_iter()
+        .map(|(name, count)| TagEntry { name: name.to_string(), count })
+        .collect()
+}
+
+impl TagsState {
+    pub fn new(
+        store: &TagStore,
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> TagsState {
+        TagsState {
+            list: FilteredList::new(tag_entries(store), matches),
+            tree_options,
+            mode: initial_mode(con),
+        }
+    }
+}
+
+fn matches(tag: &TagEntry, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&tag.name).is_some()
+}
+
+impl PanelState for TagsState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Tags
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn selected_path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        None
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        let tags = tag_entries(&TagStore::load());
+        self.list.set_items(tags, |a, b| a.name == b.name);
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let w_count = list.visible()
+            .map(|t| t.count.to_string().len())
+            .max().unwrap_or(0)
+            .max("files".len());
+        let styles = &disc.panel_skin.styles;
+        list.queue_table_head(
+            w,
+            disc,
+            &[ListColumn::new("files", w_count, Alignment::Right)],
+            |cw| {
+                cw.queue_str(&styles.default, "tag")?;
+                Ok(())
+            },
+        )?;
+        self.list.queue_table_rows(w, disc, |cw, tag, row| {
+            row.queue_cell(cw, &tag.count.to_string(), row.txt_style, w_count, Alignment::Right)?;
+            row.queue_matched(cw, &tag.name, &row.style(&styles.tag))
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        use Internal::*;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                let in_new_panel = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                match self.list.selected() {
+                    Some(entry) => {
+                        // we list the tagged files under the current root
+                        let raw = format!("{}{}", SearchMode::TagExact.prefix(con), entry.name);
+                        let parts = CommandParts::from(raw.clone());
+                        let mut tree_options = self.tree_options();
+                        tree_options.pattern = match InputPattern::new(raw, &parts.pattern, &app_state.tags, con) {
+                            Ok(pattern) => pattern,
+                            Err(e) => {
+                                return Ok(CmdResult::error(e.to_string()));
+                            }
+                        };
+                        let dam = Dam::unlimited();
+                        CmdResult::from_optional_state(
+                            BrowserState::new(
+                                app_state.root.clone(),
+                                tree_options,
+                                screen,
+                                con,
+                                &dam,
+                            ),
+                            None,
+                            in_new_panel,
+                        )
+                    }
+                    None => CmdResult::error(tr!(error_no_selected_tag)),
+                }
+            }
+            open_leave => CmdResult::PopStateAndReapply,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
@@ -1,6 +1,9 @@
 use {
     crossbeam::channel::{self, bounded, select, Receiver},
-    std::thread,
+    std::{
+        thread,
+        time::Duration,
+    },
     termimad::TimedEvent,
 };
 
@@ -111,7 +114,16 @@ impl Dam {
 
     /// non blocking
     pub fn has_event(&self) -> bool {
-        !self.receiver.is_empty()
+        self.in_dam.is_some() || !self.receiver.is_empty()
+    }
+
+    /// wait, at most for the given duration, for an event, which
+    /// is then kept in the dam. Return true when there's one
+    pub fn wait_event(&mut self, timeout: Duration) -> bool {
+        if self.in_dam.is_none() {
+            self.in_dam = self.receiver.recv_timeout(timeout).ok();
+        }
+        self.in_dam.is_some()
     }
 
     /// block until next event (including the one which
@@ -133,6 +145,24 @@ impl Dam {
         }
     }
 
+    /// like next, but return None when nothing came before
+    /// the end of the given period
+    pub fn next_before<T>(
+        &mut self,
+        other: &Receiver<T>,
+        period: Duration,
+    ) -> Option<Either<Option<TimedEvent>, Option<T>>> {
+        if self.in_dam.is_some() {
+            Some(Either::First(self.in_dam.take()))
+        } else {
+            select! {
+                recv(self.receiver) -> event => Some(Either::First(event.ok())),
+                recv(other) -> o => Some(Either::Second(o.ok())),
+                default(period) => None,
+            }
+        }
+    }
+
     // or maybed return either Option<TimedEvent> or Option<T> ?
     pub fn next<T>(&mut self, other: &Receiver<T>) -> Either<Option<TimedEvent>, Option<T>> {
         if self.in_dam.is_some() {
@@ -0,0 +1,10 @@
+//! The freedesktop.org trash, in which files can be moved instead
+//! of being removed, then restored or purged
+
+mod trash_dir;
+mod trash_state;
+
+pub use {
+    trash_dir::*,
+    trash_state::TrashState,
+};
@@ -0,0 +1,267 @@
+use {
+    chrono::{DateTime, Local, NaiveDateTime, TimeZone},
+    std::{
+        ffi::OsString,
+        fs,
+        io::{self, Write},
+        os::unix::{
+            ffi::{OsStrExt, OsStringExt},
+            fs::MetadataExt,
+        },
+        path::{Path, PathBuf},
+    },
+};
+
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// an item of the trash, as described by its .trashinfo file
+#[derive(Debug, Clone)]
+pub struct TrashedItem {
+    /// the name of the item in the "files" directory of the trash
+    pub name: String,
+
+    /// where the item was before being trashed
+    pub original_path: PathBuf,
+
+    pub deletion_date: Option<DateTime<Local>>,
+
+    /// the path of the item, in the trash
+    pub path: PathBuf,
+}
+
+/// a trash directory, as specified by freedesktop.org, with a "files"
+/// directory holding the trashed items and an "info" one telling where
+/// they come from
+#[derive(Debug, Clone)]
+pub struct TrashDir {
+    dir: PathBuf,
+}
+
+/// encode the path as required in a .trashinfo file (like in a URL)
+fn encode_path(path: &Path) -> String {
+    let mut s = String::new();
+    for &b in path.as_os_str().as_bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            s.push(b as char);
+        } else {
+            s.push_str(&format!("%{:02X}", b));
+        }
+    }
+    s
+}
+
+fn decode_path(s: &str) -> PathBuf {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(b) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    PathBuf::from(OsString::from_vec(decoded))
+}
+
+impl TrashDir {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+    /// the trash of the user, in `$XDG_DATA_HOME/Trash`
+    pub fn home() -> Option<Self> {
+        directories::BaseDirs::new().map(|dirs| Self::new(dirs.data_dir().join("Trash")))
+    }
+    fn files_dir(&self) -> PathBuf {
+        self.dir.join("files")
+    }
+    fn info_dir(&self) -> PathBuf {
+        self.dir.join("info")
+    }
+    fn info_path(&self, name: &str) -> PathBuf {
+        self.info_dir().join(format!("{}.trashinfo", name))
+    }
+
+    /// move the file or directory to the trash
+    pub fn trash(&self, path: &Path) -> io::Result<TrashedItem> {
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            std::env::current_dir()?.join(path)
+        };
+        let file_name = path.file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "this path can't be trashed"))?
+            .to_string_lossy()
+            .to_string();
+        fs::create_dir_all(self.files_dir())?;
+        fs::create_dir_all(self.info_dir())?;
+        // the trash can only be filled with a rename, so not across filesystems
+        if fs::symlink_metadata(&path)?.dev() != fs::metadata(&self.dir)?.dev() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("{:?} isn't on the filesystem of the trash", path),
+            ));
+        }
+        let deletion_date = Local::now();
+        // the info file is created first, which reserves the name. The
+        // name must also be free in the files dir, where another tool
+        // may have left an orphan
+        let mut n = 1;
+        let (name, mut info_file) = loop {
+            let name = if n == 1 {
+                file_name.clone()
+            } else {
+                format!("{}.{}", file_name, n)
+            };
+            if fs::symlink_metadata(self.files_dir().join(&name)).is_ok() {
+                n += 1;
+                continue;
+            }
+            let info_file = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(self.info_path(&name));
+            match info_file {
+                Ok(info_file) => break (name, info_file),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => n += 1,
+                Err(e) => return Err(e),
+            }
+        };
+        let written = write!(
+            info_file,
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            encode_path(&path),
+            deletion_date.format(DATE_FORMAT),
+        );
+        let trashed_path = self.files_dir().join(&name);
+        if let Err(e) = written.and_then(|_| fs::rename(&path, &trashed_path)) {
+            let _ = fs::remove_file(self.info_path(&name));
+            return Err(e);
+        }
+        Ok(TrashedItem {
+            name,
+            original_path: path,
+            deletion_date: Some(deletion_date),
+            path: trashed_path,
+        })
+    }
+
+    fn read_item(&self, info_path: &Path) -> Option<TrashedItem> {
+        let name = info_path.file_name()?
+            .to_str()?
+            .strip_suffix(".trashinfo")?
+            .to_string();
+        let path = self.files_dir().join(&name);
+        if fs::symlink_metadata(&path).is_err() {
+            return None;
+        }
+        let content = fs::read_to_string(info_path).ok()?;
+        let mut original_path = None;
+        let mut deletion_date = None;
+        for line in content.lines() {
+            if let Some(value) = line.strip_prefix("Path=") {
+                original_path = Some(decode_path(value));
+            } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+                deletion_date = NaiveDateTime::parse_from_str(value, DATE_FORMAT)
+                    .ok()
+                    .and_then(|date| Local.from_local_datetime(&date).single());
+            }
+        }
+        Some(TrashedItem {
+            name,
+            original_path: original_path?,
+            deletion_date,
+            path,
+        })
+    }
+
+    /// list the items of the trash, the most recently trashed first
+    pub fn list(&self) -> Vec<TrashedItem> {
+        let entries = match fs::read_dir(self.info_dir()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                debug!("can't read trash info dir: {}", e);
+                return Vec::new();
+            }
+        };
+        let mut items: Vec<TrashedItem> = entries
+            .flatten()
+            .filter_map(|entry| self.read_item(&entry.path()))
+            .collect();
+        items.sort_by_key(|item| std::cmp::Reverse(item.deletion_date));
+        items
+    }
+
+    /// move the item back to its original location, which must be free
+    pub fn restore(&self, item: &TrashedItem) -> io::Result<()> {
+        if fs::symlink_metadata(&item.original_path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{:?} already exists", item.original_path),
+            ));
+        }
+        if let Some(parent) = item.original_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&item.path, &item.original_path)?;
+        fs::remove_file(self.info_path(&item.name))
+    }
+
+    /// definitively remove the item
+    pub fn purge(&self, item: &TrashedItem) -> io::Result<()> {
+        if fs::symlink_metadata(&item.path)?.is_dir() {
+            fs::remove_dir_all(&item.path)?;
+        } else {
+            fs::remove_file(&item.path)?;
+        }
+        fs::remove_file(self.info_path(&item.name))
+    }
+}
+
+#[cfg(test)]
+mod trash_dir_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_trash_restore_purge() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash = TrashDir::new(dir.path().join("Trash"));
+        fs::create_dir(dir.path().join("Trash")).unwrap();
+        let a = dir.path().join("some file.txt");
+        fs::write(&a, "a").unwrap();
+        let item = trash.trash(&a).unwrap();
+        assert!(!a.exists());
+        fs::write(&a, "b").unwrap();
+        let other = trash.trash(&a).unwrap();
+        assert_eq!(other.name, "some file.txt.2");
+        let items = trash.list();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].original_path, a);
+        trash.restore(&item).unwrap();
+        assert_eq!(fs::read_to_string(&a).unwrap(), "a");
+        assert!(trash.restore(&other).is_err());
+        trash.purge(&other).unwrap();
+        assert!(trash.list().is_empty());
+        // an orphan file, without info file, isn't overwritten
+        fs::write(trash.files_dir().join("orphan"), "orphan").unwrap();
+        let b = dir.path().join("orphan");
+        fs::write(&b, "b").unwrap();
+        let item = trash.trash(&b).unwrap();
+        assert_eq!(item.name, "orphan.2");
+        assert_eq!(fs::read_to_string(trash.files_dir().join("orphan")).unwrap(), "orphan");
+    }
+
+    #[test]
+    fn test_path_encoding() {
+        let path = Path::new("/home/me/été 2022/a%b.txt");
+        let encoded = encode_path(path);
+        assert_eq!(encoded, "/home/me/%C3%A9t%C3%A9%202022/a%25b.txt");
+        assert_eq!(decode_path(&encoded), path);
+    }
+}
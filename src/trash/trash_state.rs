@@ -0,0 +1,229 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        browser::BrowserState,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        task_sync::Dam,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::path::Path,
+    termimad::minimad::Alignment,
+};
+
+/// an application state listing the items of the trash, most
+/// recently trashed first, which can be restored or purged
+pub struct TrashState {
+    trash: TrashDir,
+    list: FilteredList<TrashedItem>,
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+impl TrashState {
+    pub fn new(
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> Result<TrashState, ProgramError> {
+        let trash = TrashDir::home()
+            .ok_or_else(|| ProgramError::InternalError {
+                details: "no home directory for the trash".to_string(),
+            })?;
+        Ok(TrashState {
+            list: FilteredList::new(trash.list(), matches),
+            trash,
+            tree_options,
+            mode: initial_mode(con),
+        })
+    }
+    /// apply the operation to the selected item, then reload the list
+    fn on_selected_item(
+        &mut self,
+        operation: fn(&TrashDir, &TrashedItem) -> std::io::Result<()>,
+        done: &str,
+        screen: Screen,
+        con: &AppContext,
+    ) -> CmdResult {
+        let item = match self.list.selected() {
+            Some(item) => item.clone(),
+            None => {
+                return CmdResult::error(tr!(error_no_selected_item));
+            }
+        };
+        let res = operation(&self.trash, &item);
+        self.refresh(screen, con);
+        match res {
+            Ok(()) => CmdResult::message(format!(
+                "`{}` {}",
+                item.original_path.to_string_lossy(),
+                done,
+            )),
+            Err(e) => CmdResult::error(e.to_string()),
+        }
+    }
+}
+
+fn matches(item: &TrashedItem, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&item.original_path.to_string_lossy()).is_some()
+}
+
+impl PanelState for TrashState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Trash
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// the path of the selected item, in the trash
+    fn selected_path(&self) -> Option<&Path> {
+        self.list.selected().map(|item| item.path.as_path())
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        self.selected_path().map(|path| Selection {
+            path,
+            line: 0,
+            line_range: None,
+            text_view: None,
+            stype: SelectionType::from(path),
+            is_exe: false,
+        })
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        let items = self.trash.list();
+        self.list.set_items(items, |a, b| a.name == b.name);
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let date_time_format = self.tree_options.date_time_format;
+        let w_date = date_time_format.date_time_width();
+        let styles = &disc.panel_skin.styles;
+        self.list.queue_table_head(
+            w,
+            disc,
+            &[ListColumn::new("trashed", w_date, Alignment::Right)],
+            |cw| {
+                cw.queue_str(&styles.default, "original location")?;
+                Ok(())
+            },
+        )?;
+        self.list.queue_table_rows(w, disc, |cw, item, row| {
+            let date = item.deletion_date
+                .map(|time| date_time_format.format_date_time(time))
+                .unwrap_or_default();
+            row.queue_cell(cw, &date, &row.style(&styles.dates), w_date, Alignment::Right)?;
+            row.queue_matched(cw, &item.original_path.to_string_lossy(), row.txt_style)
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        use Internal::*;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                let in_new_panel = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                match self.list.selected() {
+                    Some(item) if item.path.is_dir() => {
+                        let dam = Dam::unlimited();
+                        CmdResult::from_optional_state(
+                            BrowserState::new(
+                                item.path.clone(),
+                                self.tree_options.without_pattern(),
+                                screen,
+                                con,
+                                &dam,
+                            ),
+                            None,
+                            in_new_panel,
+                        )
+                    }
+                    Some(_) => CmdResult::error(tr!(error_trash_browse_not_dir)),
+                    None => CmdResult::error(tr!(error_no_selected_item)),
+                }
+            }
+            Internal::restore_trashed => {
+                self.on_selected_item(TrashDir::restore, "restored", screen, con)
+            }
+            Internal::purge_trashed => {
+                self.on_selected_item(TrashDir::purge, "purged", screen, con)
+            }
+            open_leave => CmdResult::PopStateAndReapply,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
@@ -1,12 +1,16 @@
 
+mod permission_parts;
 mod sort;
+mod tie_breaking;
 mod tree;
 mod tree_line;
 mod tree_line_type;
 mod tree_options;
 
 pub use {
+    permission_parts::PermissionParts,
     sort::Sort,
+    tie_breaking::{TieBreaking, TieKey},
     tree::Tree,
     tree_line::TreeLine,
     tree_line_type::TreeLineType,
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// The parts of the permission column, each one being toggled
+/// independently, the column being displayed when at least one
+/// of them is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PermissionParts {
+    /// the rwx mode on unix, the read-only, hidden, system and
+    /// archive attributes on windows
+    pub mode: bool,
+    /// the user owning the file (not on windows)
+    pub owner: bool,
+    /// the group owning the file (not on windows)
+    pub group: bool,
+    /// a `+` when the file has an access control list (linux only)
+    pub acl: bool,
+}
+
+impl Default for PermissionParts {
+    fn default() -> Self {
+        Self {
+            mode: true,
+            owner: true,
+            group: true,
+            acl: false,
+        }
+    }
+}
+
+impl PermissionParts {
+    pub fn any(self) -> bool {
+        self.mode || self.owner || self.group || self.acl
+    }
+}
+
+#[cfg(test)]
+mod permission_parts_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_permission_parts_conf() {
+        let parts: PermissionParts = deser_hjson::from_str("{ group: false, acl: true }").unwrap();
+        assert_eq!(parts, PermissionParts { mode: true, owner: true, group: false, acl: true });
+        let parts: PermissionParts = toml::from_str("mode = false\nowner = false\ngroup = false").unwrap();
+        assert!(!parts.any());
+    }
+}
@@ -1,9 +1,11 @@
+use serde::{Deserialize, Serialize};
 
 /// A sort key.
 /// A non None sort mode implies only one level of the tree
 /// is displayed.
 /// When in None mode, paths are alpha sorted
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Sort {
     None,
     Count,
@@ -11,6 +13,15 @@ pub enum Sort {
     Size,
     TypeDirsFirst,
     TypeDirsLast,
+    Rating,
+    Extension,
+    ExtensionReversed,
+    Ctime,
+    CtimeReversed,
+    Atime,
+    AtimeReversed,
+    Owner,
+    OwnerReversed,
 }
 
 impl Sort {
@@ -22,6 +33,36 @@ impl Sort {
             Self::Size => true,
             Self::TypeDirsFirst => false,
             Self::TypeDirsLast => false,
+            Self::Rating => true,
+            Self::Extension | Self::ExtensionReversed => true,
+            Self::Ctime | Self::CtimeReversed => true,
+            Self::Atime | Self::AtimeReversed => true,
+            Self::Owner | Self::OwnerReversed => true,
         }
     }
+    /// whether the sort is made on the metadata of the files, and
+    /// so must be done again when late metadata are received
+    pub fn needs_metadata(self) -> bool {
+        matches!(
+            self,
+            Self::Ctime | Self::CtimeReversed
+                | Self::Atime | Self::AtimeReversed
+                | Self::Owner | Self::OwnerReversed
+        )
+    }
+    /// whether the lines are sorted once built, without needing
+    /// the sums of the directories
+    pub fn sorts_lines(self) -> bool {
+        matches!(self, Self::Rating | Self::Extension | Self::ExtensionReversed)
+            || self.needs_metadata()
+    }
+    /// whether the order is the reverse of the natural one of the
+    /// sort key (alphabetical for extensions and owners, most recent
+    /// first for dates)
+    pub fn is_reversed(self) -> bool {
+        matches!(
+            self,
+            Self::ExtensionReversed | Self::CtimeReversed | Self::AtimeReversed | Self::OwnerReversed
+        )
+    }
 }
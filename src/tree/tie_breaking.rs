@@ -0,0 +1,73 @@
+use {
+    serde::Deserialize,
+    std::{
+        cmp::Ordering,
+        path::Path,
+        time::SystemTime,
+    },
+};
+
+/// How entries with the same search score are ordered, which
+/// decides which one is selected as best match and which ones
+/// are kept when there are too many matches to display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreaking {
+    /// shallowest entries first, then alphabetical order
+    #[default]
+    Depth,
+    /// alphabetical order of the paths
+    Alphabetical,
+    /// most recently modified entries first, then alphabetical order
+    Recency,
+}
+
+/// what's needed of an entry to break a tie
+pub struct TieKey<'p> {
+    pub path: &'p Path,
+    pub depth: u16,
+    pub mtime: Option<SystemTime>,
+}
+
+impl TieBreaking {
+    /// whether the modification dates must be read
+    pub fn needs_dates(self) -> bool {
+        self == Self::Recency
+    }
+    /// compare two entries of same score, `Less` meaning the
+    /// first one is preferred
+    pub fn compare(self, a: &TieKey, b: &TieKey) -> Ordering {
+        let ordering = match self {
+            Self::Depth => a.depth.cmp(&b.depth),
+            Self::Alphabetical => Ordering::Equal,
+            Self::Recency => b.mtime.cmp(&a.mtime),
+        };
+        ordering.then_with(|| a.path.cmp(b.path))
+    }
+}
+
+#[cfg(test)]
+mod tie_breaking_tests {
+
+    use {
+        super::*,
+        std::time::Duration,
+    };
+
+    #[test]
+    fn test_tie_breaking() {
+        let old = TieKey {
+            path: Path::new("/a/b/old.txt"),
+            depth: 2,
+            mtime: Some(SystemTime::UNIX_EPOCH),
+        };
+        let recent = TieKey {
+            path: Path::new("/a/recent.txt"),
+            depth: 1,
+            mtime: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(60)),
+        };
+        assert_eq!(TieBreaking::Depth.compare(&recent, &old), Ordering::Less);
+        assert_eq!(TieBreaking::Alphabetical.compare(&old, &recent), Ordering::Less);
+        assert_eq!(TieBreaking::Recency.compare(&recent, &old), Ordering::Less);
+    }
+}
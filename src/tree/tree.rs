@@ -5,18 +5,35 @@ use {
         errors,
         file_sum::FileSum,
         git::TreeGitStatus,
+        lint::{LintLoader, LintStatus},
         task_sync::ComputationResult,
         task_sync::Dam,
-        tree_build::{BId, BuildReport, TreeBuilder},
+        tree_build::{
+            BId,
+            BuildReport,
+            LineMetadata,
+            MetadataLoader,
+            TreeBuilder,
+            METADATA_WAIT_PERIOD,
+        },
     },
-    fnv::FnvHashMap,
+    fnv::{FnvHashMap, FnvHashSet},
     std::{
-        cmp::Ord,
+        cmp::{Ord, Ordering},
         mem,
+        ops::Range,
         path::{Path, PathBuf},
+        time::{Duration, Instant},
     },
 };
 
+/// how long the entries appearing on a refresh stay highlighted
+pub const APPEARANCE_HIGHLIGHT_DURATION: Duration = Duration::from_secs(5);
+
+/// the number of lines, after the visible ones, whose files are
+/// probed too, so that a small scroll shows them already probed
+const LINT_MARGIN: usize = 20;
+
 /// The tree which may be displayed, with onle line per visible line of the panel.
 ///
 /// In the tree structure, every "node" is just a line, there's
@@ -30,6 +47,15 @@ pub struct Tree {
     pub total_search: bool, // whether the search was made on all children
     pub git_status: ComputationResult<TreeGitStatus>,
     pub build_report: BuildReport,
+    /// the paths which appeared on recent refreshes, with the
+    /// time they were noticed
+    pub appeared: FnvHashMap<PathBuf, Instant>,
+    /// the reading of the metadata which weren't there yet
+    /// when the tree was built
+    pub late_metadata: Option<MetadataLoader>,
+    /// the running of the lint probes, started when the lint
+    /// column is displayed
+    pub lint_loader: Option<LintLoader>,
 }
 
 impl Tree {
@@ -55,7 +81,11 @@ impl Tree {
                        // we save the old selection to try restore it
         let selected_path = self.selected_line().path.to_path_buf();
         mem::swap(&mut self.lines, &mut tree.lines);
-        self.scroll = 0;
+        self.late_metadata = tree.late_metadata.take();
+        self.lint_loader = None;
+        self.note_appearances(&tree.lines);
+        // we keep the scroll, unless the tree is now too short
+        self.scroll = self.scroll.min(self.lines.len().saturating_sub(page_height));
         if !self.try_select_path(&selected_path) {
             if self.selection >= self.lines.len() {
                 self.selection = 0;
@@ -65,10 +95,81 @@ impl Tree {
         Ok(())
     }
 
+    /// remember the lines which weren't in the old ones, so that
+    /// they can be highlighted for a few seconds
+    fn note_appearances(&mut self, old_lines: &[TreeLine]) {
+        let now = Instant::now();
+        self.appeared.retain(|_, time| now.duration_since(*time) < APPEARANCE_HIGHLIGHT_DURATION);
+        let old_paths: FnvHashSet<&Path> = old_lines.iter()
+            .map(|line| line.path.as_path())
+            .collect();
+        // a missing line is only new if its parent was displayed
+        // with all its children
+        let complete_dirs: FnvHashSet<&Path> = old_lines.iter()
+            .filter(|line| line.unlisted == 0)
+            .filter_map(|line| line.path.parent())
+            .filter(|parent| old_paths.contains(parent))
+            .collect();
+        for line in self.lines.iter() {
+            if old_paths.contains(line.path.as_path()) {
+                continue;
+            }
+            if line.path.parent().map_or(false, |parent| complete_dirs.contains(parent)) {
+                self.appeared.insert(line.path.clone(), now);
+            }
+        }
+    }
+
+    /// whether the line appeared on a recent refresh
+    pub fn is_new(&self, line: &TreeLine) -> bool {
+        self.appeared.get(&line.path)
+            .map_or(false, |time| time.elapsed() < APPEARANCE_HIGHLIGHT_DURATION)
+    }
+
+    /// whether some lines are still highlighted as new
+    pub fn has_new_lines(&self) -> bool {
+        self.appeared.values()
+            .any(|time| time.elapsed() < APPEARANCE_HIGHLIGHT_DURATION)
+    }
+
+    /// in flat mode, keep only the lines which aren't directories (nor
+    /// pruning lines) and sort them by path, or by extension then path
+    /// with the sorts on types
+    fn flatten(&mut self) {
+        let lines = mem::take(&mut self.lines).into_vec();
+        let mut lines: Vec<TreeLine> = lines.into_iter()
+            .enumerate()
+            .filter(|(idx, line)| {
+                *idx == 0 || !matches!(line.line_type, TreeLineType::Dir | TreeLineType::Pruning)
+            })
+            .map(|(_, line)| line)
+            .collect();
+        let by_type = matches!(self.options.sort, Sort::TypeDirsFirst | Sort::TypeDirsLast);
+        lines[1..].sort_by_cached_key(|line| {
+            let ext = if by_type {
+                line.path.extension().map_or(String::new(), |ext| ext.to_string_lossy().to_lowercase())
+            } else {
+                String::new()
+            };
+            (ext, line.subpath.to_lowercase())
+        });
+        for line in lines.iter_mut() {
+            for branch in line.left_branchs.iter_mut() {
+                *branch = false;
+            }
+        }
+        self.lines = lines.into_boxed_slice();
+    }
+
     /// do what must be done after line additions or removals:
     /// - sort the lines
     /// - compute left branches
     pub fn after_lines_changed(&mut self) {
+        if self.options.flat {
+            self.flatten();
+            self.sort_on_sums();
+            return;
+        }
 
         // we need to order the lines to build the tree.
         // It's a little complicated because
@@ -109,6 +210,12 @@ impl Tree {
                     }
                     _ => { "" }
                 };
+                // the extra roots come after the children of the root
+                let sort_prefix = if l.depth == 1 && self.options.extra_roots.contains(&l.path) {
+                    "\u{10FFFF}"
+                } else {
+                    sort_prefix
+                };
                 sort_path = format!(
                     "{}{}-{}/{}",
                     sort_prefix,
@@ -179,9 +286,17 @@ impl Tree {
                 self.lines[i].left_branchs[depth] = true;
             }
         }
+        self.sort_on_sums();
+    }
+
+    /// fetch the sums of files when they're needed, and sort
+    /// the lines on them, or on ratings, extensions or metadata
+    fn sort_on_sums(&mut self) {
         if self.options.needs_sum() {
             time!("fetch_file_sum", self.fetch_regular_file_sums()); // not the dirs, only simple files
             self.sort_siblings(); // does nothing when sort mode is None
+        } else if self.options.sort.sorts_lines() {
+            self.sort_siblings();
         }
     }
 
@@ -201,6 +316,7 @@ impl Tree {
     ///
     /// For example the following one if dy is 1.
     pub fn move_selection(&mut self, dy: i32, page_height: usize, cycle: bool) {
+        let page_height = self.lines_page_height(page_height);
         let l = self.lines.len();
         // we find the new line to select
         loop {
@@ -238,6 +354,7 @@ impl Tree {
     /// Scroll the desired amount and return true, or return false if it's
     /// already at end or the tree fits the page
     pub fn try_scroll(&mut self, dy: i32, page_height: usize) -> bool {
+        let page_height = self.lines_page_height(page_height);
         if self.lines.len() <= page_height {
             return false;
         }
@@ -263,15 +380,62 @@ impl Tree {
         true
     }
 
-    /// try to select a line by index of visible line
-    /// (works if y+scroll falls on a selectable line)
+    /// whether the line is a hit of a content search, written
+    /// with its matching line in the row below
+    pub fn has_match_line(&self, line_index: usize) -> bool {
+        line_index > 0
+            && self.options.shows_match_lines()
+            && self.lines.get(line_index).map_or(false, |line| line.direct_match && line.is_file())
+    }
+
+    /// the indexes of the lines displayed in the rows below the
+    /// root one, each with whether the row is the one of the
+    /// matching line of a hit
+    pub fn visible_rows(&self) -> impl Iterator<Item = (usize, bool)> + '_ {
+        let mut next_index = self.scroll + 1;
+        let mut pending_match_line = None;
+        std::iter::from_fn(move || {
+            if let Some(line_index) = pending_match_line.take() {
+                return Some((line_index, true));
+            }
+            if next_index >= self.lines.len() {
+                return None;
+            }
+            let line_index = next_index;
+            next_index += 1;
+            if self.has_match_line(line_index) {
+                pending_match_line = Some(line_index);
+            }
+            Some((line_index, false))
+        })
+    }
+
+    /// the number of lines sure to fit in the given number of rows
+    fn lines_page_height(&self, page_height: usize) -> usize {
+        if self.options.shows_match_lines() {
+            (page_height / 2).max(1)
+        } else {
+            page_height
+        }
+    }
+
+    /// try to select a line by index of visible row
+    /// (works if the row is the one of a selectable line, or
+    /// of its matching line)
     pub fn try_select_y(&mut self, y: usize) -> bool {
-        let y = y + self.scroll;
-        if y < self.lines.len() {
-            if self.lines[y].is_selectable() {
-                self.selection = y;
-                return true;
+        let line_index = if y == 0 {
+            self.scroll
+        } else {
+            match self.visible_rows().nth(y - 1) {
+                Some((line_index, _)) => line_index,
+                None => {
+                    return false;
+                }
             }
+        };
+        if line_index < self.lines.len() && self.lines[line_index].is_selectable() {
+            self.selection = line_index;
+            return true;
         }
         false
     }
@@ -290,6 +454,7 @@ impl Tree {
     }
 
     pub fn make_selection_visible(&mut self, page_height: usize) {
+        let page_height = self.lines_page_height(page_height);
         if page_height >= self.lines.len() || self.selection < 3 {
             self.scroll = 0;
         } else if self.selection <= self.scroll {
@@ -317,8 +482,13 @@ impl Tree {
                 continue;
             }
             if line.score == best_score {
-                // in case of equal scores, we prefer the shortest path
-                if self.lines[idx].depth >= self.lines[self.selection].depth {
+                // in case of equal scores, the tie breaking option decides
+                let tie_breaking = self.options.tie_breaking;
+                let ordering = tie_breaking.compare(
+                    &line.tie_key(tie_breaking),
+                    &self.lines[self.selection].tie_key(tie_breaking),
+                );
+                if ordering != Ordering::Less {
                     continue;
                 }
             }
@@ -446,10 +616,110 @@ impl Tree {
                 .any(|line| line.line_type == TreeLineType::Dir && line.sum.is_none())
     }
 
+    pub fn has_late_metadata(&self) -> bool {
+        self.late_metadata.is_some()
+    }
+
+    /// fill the lines with the metadata which arrived since the tree
+    /// was built. The lines whose metadata couldn't be read are
+    /// marked in error.
+    ///
+    /// This should be called until has_late_metadata returns false
+    pub fn fetch_late_metadata(&mut self) {
+        let (received, done) = match &self.late_metadata {
+            Some(loader) => loader.receive(METADATA_WAIT_PERIOD),
+            None => return,
+        };
+        for (bid, res) in received {
+            let line = match self.lines.iter_mut().find(|line| line.bid == bid) {
+                Some(line) => line,
+                None => continue,
+            };
+            match res {
+                Ok(LineMetadata { metadata, line_type }) => {
+                    if line.line_type != TreeLineType::Pruning {
+                        line.line_type = line_type;
+                    }
+                    line.metadata = Some(metadata);
+                }
+                Err(e) => {
+                    warn!("Error while reading metadata of {:?}: {}", &line.path, e);
+                    line.has_error = true;
+                }
+            }
+        }
+        if done {
+            for line in self.lines.iter_mut().filter(|line| line.metadata.is_none()) {
+                line.has_error = true;
+            }
+            self.late_metadata = None;
+        }
+        if self.options.sort.needs_metadata() {
+            self.sort_siblings();
+        }
+    }
+
     pub fn is_missing_git_status_computation(&self) -> bool {
         self.git_status.is_not_computed()
     }
 
+    /// the range of the lines which are displayed, or soon may be
+    /// after a small scroll, the root excluded
+    fn lint_range(&self, page_height: usize) -> Range<usize> {
+        let start = (self.scroll + 1).min(self.lines.len());
+        let end = (self.scroll + page_height + LINT_MARGIN).min(self.lines.len());
+        start..end.max(start)
+    }
+
+    pub fn has_missing_lint(&self, page_height: usize) -> bool {
+        self.options.show_lint
+            && self.lines[self.lint_range(page_height)]
+                .iter()
+                .any(|line| line.is_selectable() && line.lint.is_none())
+    }
+
+    /// start the lint probes in background, on the displayable lines,
+    /// if they're not started, and fill the lines with the results
+    /// which arrived.
+    ///
+    /// To check all of them, this should be called until
+    ///  has_missing_lint returns false
+    pub fn fetch_some_missing_lint(&mut self, page_height: usize, con: &AppContext) {
+        if self.lint_loader.is_none() {
+            let range = self.lint_range(page_height);
+            let mut paths = Vec::new();
+            for line in &mut self.lines[range] {
+                if line.is_selectable() && line.lint.is_none() {
+                    if line.is_file() {
+                        paths.push(line.path.clone());
+                    } else {
+                        line.lint = Some(LintStatus::Unprobed);
+                    }
+                }
+            }
+            self.lint_loader = Some(LintLoader::start(paths, &con.lint_probes));
+        }
+        let (received, done) = match &self.lint_loader {
+            Some(loader) => loader.receive(METADATA_WAIT_PERIOD),
+            None => return,
+        };
+        for (path, status) in received {
+            if let Some(line) = self.lines.iter_mut().find(|line| line.path == path) {
+                line.lint = Some(status);
+            }
+        }
+        if done {
+            // the files whose probes didn't answer in time are left unprobed
+            if let Some(loader) = self.lint_loader.take() {
+                for line in self.lines.iter_mut().filter(|line| line.lint.is_none()) {
+                    if loader.is_probing(&line.path) {
+                        line.lint = Some(LintStatus::Unprobed);
+                    }
+                }
+            }
+        }
+    }
+
     /// fetch the file_sums of regular files (thus avoiding the
     /// long computation which is needed for directories)
     pub fn fetch_regular_file_sums(&mut self) {
@@ -513,10 +783,50 @@ impl Tree {
                 });
                 self.try_select_path(&selected_path);
             }
+            Sort::Rating => {
+                // the sort is stable so files with the same rating
+                // stay alphabetically sorted
+                let selected_path = self.selected_line().path.to_path_buf();
+                self.lines[1..].sort_by_key(|line| std::cmp::Reverse(line.rating));
+                self.try_select_path(&selected_path);
+            }
+            Sort::Extension | Sort::ExtensionReversed => {
+                self.sort_siblings_by_key(|line| {
+                    line.path.extension()
+                        .map(|ext| ext.to_string_lossy().to_lowercase())
+                });
+            }
+            Sort::Ctime | Sort::CtimeReversed => {
+                // most recent first, lines without date last
+                self.sort_siblings_by_key(|line| std::cmp::Reverse(line.ctime()));
+            }
+            Sort::Atime | Sort::AtimeReversed => {
+                self.sort_siblings_by_key(|line| std::cmp::Reverse(line.atime()));
+            }
+            Sort::Owner | Sort::OwnerReversed => {
+                self.sort_siblings_by_key(|line| line.owner());
+            }
             _ => {}
         }
     }
 
+    /// sort the lines on a key, in the order of the key or in the
+    /// reverse one according to the sort option. The sort is stable
+    /// so lines with the same key stay alphabetically sorted
+    fn sort_siblings_by_key<K, F>(&mut self, key: F)
+    where
+        K: Ord,
+        F: Fn(&TreeLine) -> K,
+    {
+        let selected_path = self.selected_line().path.to_path_buf();
+        if self.options.sort.is_reversed() {
+            self.lines[1..].sort_by_cached_key(|line| std::cmp::Reverse(key(line)));
+        } else {
+            self.lines[1..].sort_by_cached_key(key);
+        }
+        self.try_select_path(&selected_path);
+    }
+
     /// compute and return the size of the root
     pub fn total_sum(&self) -> FileSum {
         if let Some(sum) = self.lines[0].sum {
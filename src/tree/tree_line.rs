@@ -4,12 +4,14 @@ use {
         app::{Selection, SelectionType},
         file_sum::FileSum,
         git::LineGitStatus,
+        lint::LintStatus,
         tree_build::BId,
     },
     lazy_regex::regex_captures,
     std::{
         fs,
         path::{Path, PathBuf},
+        time::SystemTime,
     },
 };
 
@@ -37,8 +39,10 @@ pub struct TreeLine {
     pub score: i32,      // 0 if there's no pattern
     pub direct_match: bool,
     pub sum: Option<FileSum>, // None when not measured
-    pub metadata: fs::Metadata,
+    pub metadata: Option<fs::Metadata>, // None while late (eg on a slow network mount)
     pub git_status: Option<LineGitStatus>,
+    pub lint: Option<LintStatus>, // None when not computed
+    pub rating: u8, // only filled when sorting by rating
 }
 
 impl TreeLine {
@@ -53,6 +57,19 @@ impl TreeLine {
             .map(|(_, ext)| ext)
     }
 
+    /// what's needed to order this line among lines of same score
+    pub fn tie_key(&self, tie_breaking: TieBreaking) -> TieKey<'_> {
+        TieKey {
+            path: &self.path,
+            depth: self.depth,
+            mtime: if tie_breaking.needs_dates() {
+                self.metadata.as_ref().and_then(|md| md.modified().ok())
+            } else {
+                None
+            },
+        }
+    }
+
     pub fn is_selectable(&self) -> bool {
         !matches!(&self.line_type, TreeLineType::Pruning)
     }
@@ -97,23 +114,26 @@ impl TreeLine {
             stype: self.selection_type(),
             is_exe: self.is_exe(),
             line: 0,
+            line_range: None,
+            text_view: None,
         }
     }
     #[cfg(unix)]
-    pub fn mode(&self) -> Mode {
-        Mode::from(self.metadata.mode())
+    pub fn mode(&self) -> Option<Mode> {
+        self.metadata.as_ref().map(|md| Mode::from(md.mode()))
     }
     #[cfg(unix)]
-    pub fn device_id(&self) -> lfs_core::DeviceId {
-        self.metadata.dev().into()
+    pub fn device_id(&self) -> Option<lfs_core::DeviceId> {
+        self.metadata.as_ref().map(|md| md.dev().into())
     }
     #[cfg(unix)]
     pub fn mount(&self) -> Option<lfs_core::Mount> {
         use crate::filesystems::*;
+        let device_id = self.device_id()?;
         let mut mount_list = MOUNTS.lock().unwrap();
         if mount_list.load().is_ok() {
             mount_list
-                .get_by_device_id(self.metadata.dev().into())
+                .get_by_device_id(device_id)
                 .cloned()
         } else {
             None
@@ -121,11 +141,37 @@ impl TreeLine {
     }
     pub fn is_exe(&self) -> bool {
         #[cfg(unix)]
-        return self.mode().is_exe();
+        return self.mode().map_or(false, |mode| mode.is_exe());
 
         #[cfg(windows)]
         return self.path.is_executable();
     }
+    /// the last change of the file's status (its creation on windows)
+    pub fn ctime(&self) -> Option<SystemTime> {
+        let md = self.metadata.as_ref()?;
+        #[cfg(unix)]
+        return u64::try_from(md.ctime()).ok().map(|secs| {
+            SystemTime::UNIX_EPOCH
+                + std::time::Duration::new(secs, md.ctime_nsec() as u32)
+        });
+
+        #[cfg(not(unix))]
+        return md.created().ok();
+    }
+    /// the last access to the file
+    pub fn atime(&self) -> Option<SystemTime> {
+        self.metadata.as_ref().and_then(|md| md.accessed().ok())
+    }
+    /// the name of the user owning the file, when the system has
+    /// this concept
+    pub fn owner(&self) -> Option<String> {
+        #[cfg(not(any(target_family="windows",target_os="android")))]
+        return self.metadata.as_ref()
+            .map(|md| crate::permissions::user_name(md.uid()));
+
+        #[cfg(any(target_family="windows",target_os="android"))]
+        return None;
+    }
     /// build and return the absolute targeted path: either self.path or the
     ///  solved canonicalized symlink
     pub fn target(&self) -> &Path {
@@ -1,20 +1,25 @@
 use {
-    super::Sort,
+    super::{PermissionParts, Sort, TieBreaking},
     crate::{
         cli::Args,
         conf::Conf,
-        display::{Cols, DEFAULT_COLS},
+        display::{Cols, DateTimeFormat, SizeFormat, DEFAULT_COLS},
         errors::ConfError,
         pattern::*,
     },
     clap::Parser,
-    std::convert::TryFrom,
+    std::{
+        convert::TryFrom,
+        path::PathBuf,
+    },
 };
 
 /// Options defining how the tree should be build and|or displayed
 #[derive(Debug, Clone)]
 pub struct TreeOptions {
     pub show_selection_mark: bool, // whether to have a triangle left of selected line
+    pub show_row_numbers: bool, // whether to number the visible rows, for `:goto`
+    pub show_match_lines: bool, // whether to write the matching line under content search hits
     pub show_hidden: bool, // whether files whose name starts with a dot should be shown
     pub only_folders: bool, // whether to hide normal files and links
     pub show_counts: bool, // whether to show the number of files (> 1 only for dirs)
@@ -22,16 +27,24 @@ pub struct TreeOptions {
     pub show_sizes: bool,  // whether to show sizes of files and dirs
     pub show_git_file_info: bool,
     pub show_device_id: bool,
+    pub show_lint: bool, // show the result of the lint probes
     pub show_root_fs: bool, // show information relative to the fs of the root
     pub trim_root: bool,    // whether to cut out direct children of root
-    pub show_permissions: bool, // show classic rwx unix permissions (only on unix)
+    pub flat: bool, // whether to list the files at all depths, without their directories
+    pub miller: bool, // whether to show the parent and selected directories in columns around the tree
+    pub show_permissions: bool, // show the permission column
+    pub permission_parts: PermissionParts, // which parts of the permission column are shown
     pub respect_git_ignore: bool, // hide files as requested by .gitignore ?
     pub filter_by_git_status: bool, // only show files whose git status is not nul
     pub pattern: InputPattern, // an optional filtering/scoring pattern
-    pub date_time_format: &'static str,
+    pub date_time_format: DateTimeFormat,
+    pub size_format: SizeFormat, // units of the sizes, or exact byte counts
     pub sort: Sort,
     pub cols_order: Cols, // order of columns
     pub show_matching_characters_on_path_searches: bool,
+    pub extra_roots: Vec<PathBuf>, // other roots whose content is searched too
+    pub show_scores: bool, // show the search score of matching lines
+    pub tie_breaking: TieBreaking, // order of the lines with the same score
 }
 
 impl TreeOptions {
@@ -39,25 +52,44 @@ impl TreeOptions {
     pub fn without_pattern(&self) -> Self {
         TreeOptions {
             show_selection_mark: self.show_selection_mark,
+            show_row_numbers: self.show_row_numbers,
+            show_match_lines: self.show_match_lines,
             show_hidden: self.show_hidden,
             only_folders: self.only_folders,
             show_counts: self.show_counts,
             show_dates: self.show_dates,
             show_sizes: self.show_sizes,
             show_permissions: self.show_permissions,
+            permission_parts: self.permission_parts,
             respect_git_ignore: self.respect_git_ignore,
             filter_by_git_status: self.filter_by_git_status,
             show_git_file_info: self.show_git_file_info,
             show_device_id: self.show_device_id,
+            show_lint: self.show_lint,
             show_root_fs: self.show_root_fs,
             trim_root: self.trim_root,
+            flat: self.flat,
+            miller: self.miller,
             pattern: InputPattern::none(),
             date_time_format: self.date_time_format,
+            size_format: self.size_format,
             sort: self.sort,
             cols_order: self.cols_order,
             show_matching_characters_on_path_searches: self.show_matching_characters_on_path_searches,
+            extra_roots: self.extra_roots.clone(),
+            show_scores: self.show_scores,
+            tie_breaking: self.tie_breaking,
         }
     }
+    /// whether the permission column is displayed
+    pub fn shows_permissions(&self) -> bool {
+        self.show_permissions && self.permission_parts.any()
+    }
+    /// whether the hits of a content search take two rows, the
+    /// second one showing the matching line
+    pub fn shows_match_lines(&self) -> bool {
+        self.show_match_lines && self.pattern.pattern.object().content
+    }
     /// counts must be computed, either for sorting or just for display
     pub fn needs_counts(&self) -> bool {
         self.show_counts || self.sort == Sort::Count
@@ -73,10 +105,10 @@ impl TreeOptions {
     pub fn needs_sum(&self) -> bool {
         self.needs_counts() || self.needs_dates() || self.needs_sizes()
     }
-    /// this method does not exist, you saw nothing
-    /// (at least don't call it other than with the config, once)
-    pub fn set_date_time_format(&mut self, format: String) {
-        self.date_time_format = Box::leak(format.into_boxed_str());
+    /// whether only the direct children of the root are listed, which
+    /// is the case when sorting, or in Miller columns when not searching
+    pub fn shows_one_level(&self) -> bool {
+        self.sort.prevent_deep_display() || (self.miller && self.pattern.is_none())
     }
     /// change tree options according to configuration
     pub fn apply_config(&mut self, config: &Conf) -> Result<(), ConfError> {
@@ -91,12 +123,33 @@ impl TreeOptions {
         if let Some(b) = config.show_selection_mark {
             self.show_selection_mark = b;
         }
-        if let Some(format) = &config.date_time_format {
-            self.set_date_time_format(format.clone());
+        if let Some(b) = config.show_row_numbers {
+            self.show_row_numbers = b;
+        }
+        if let Some(b) = config.show_match_lines {
+            self.show_match_lines = b;
+        }
+        if config.date_time_format.is_some() || config.date_time_zone.is_some() {
+            self.date_time_format = DateTimeFormat::new(
+                config.date_time_format.as_deref(),
+                config.date_time_zone.as_deref(),
+            )?;
+        }
+        if let Some(units) = config.size_units {
+            self.size_format.units = units;
+        }
+        if let Some(b) = config.exact_sizes {
+            self.size_format.exact = b;
         }
         if let Some(b) = config.show_matching_characters_on_path_searches {
             self.show_matching_characters_on_path_searches = b;
         }
+        if let Some(permission_parts) = config.permission_parts {
+            self.permission_parts = permission_parts;
+        }
+        if let Some(tie_breaking) = config.tie_breaking {
+            self.tie_breaking = tie_breaking;
+        }
         self.cols_order = config
             .cols_order
             .as_ref()
@@ -183,6 +236,16 @@ impl TreeOptions {
         } else if cli_args.no_trim_root {
             self.trim_root = false;
         }
+        if cli_args.flat {
+            self.flat = true;
+        } else if cli_args.no_flat {
+            self.flat = false;
+        }
+        if cli_args.miller {
+            self.miller = true;
+        } else if cli_args.no_miller {
+            self.miller = false;
+        }
     }
 }
 
@@ -190,6 +253,8 @@ impl Default for TreeOptions {
     fn default() -> Self {
         Self {
             show_selection_mark: false,
+            show_row_numbers: false,
+            show_match_lines: false,
             show_hidden: false,
             only_folders: false,
             show_counts: false,
@@ -197,16 +262,24 @@ impl Default for TreeOptions {
             show_sizes: false,
             show_git_file_info: false,
             show_device_id: false,
+            show_lint: false,
             show_root_fs: false,
             trim_root: false,
+            flat: false,
+            miller: false,
             show_permissions: false,
+            permission_parts: PermissionParts::default(),
             respect_git_ignore: true,
             filter_by_git_status: false,
             pattern: InputPattern::none(),
-            date_time_format: "%Y/%m/%d %R",
+            date_time_format: DateTimeFormat::default(),
+            size_format: SizeFormat::default(),
             sort: Sort::None,
             cols_order: DEFAULT_COLS,
             show_matching_characters_on_path_searches: true,
+            extra_roots: Vec::new(),
+            show_scores: false,
+            tie_breaking: TieBreaking::default(),
         }
     }
 }
@@ -8,20 +8,23 @@ pub type BId = Id<BLine>;
 
 /// a structure making it possible to keep bline references
 ///  sorted in a binary heap with the line with the smallest
-///  score at the top
+///  score at the top (and, among lines of same score, the one
+///  with the biggest tie rank)
 pub struct SortableBId {
     pub id: BId,
     pub score: i32,
+    pub tie_rank: usize, // 0 for the line preferred in case of tie
 }
 impl Eq for SortableBId {}
 impl PartialEq for SortableBId {
     fn eq(&self, other: &SortableBId) -> bool {
-        self.score == other.score // unused but required by spec of Ord
+        self.score == other.score && self.tie_rank == other.tie_rank
     }
 }
 impl Ord for SortableBId {
     fn cmp(&self, other: &SortableBId) -> Ordering {
         other.score.cmp(&self.score)
+            .then(self.tie_rank.cmp(&other.tie_rank))
     }
 }
 impl PartialOrd for SortableBId {
@@ -1,8 +1,12 @@
 use {
-    super::bid::BId,
+    super::{
+        bid::BId,
+        LineMetadata,
+    },
     crate::{
         app::AppContext,
         errors::TreeBuildError,
+        file_index::{self, EntryKind, IndexedEntry},
         git::GitIgnoreChain,
         path::{normalize_path, SpecialHandling},
         tree::*,
@@ -24,7 +28,7 @@ pub struct BLine {
     pub depth: u16,
     pub subpath: String,
     pub name: String,
-    pub file_type: fs::FileType,
+    pub file_type: EntryKind,
     pub children: Option<Vec<BId>>, // sorted and filtered
     pub next_child_idx: usize,      // index for iteration, among the children
     pub has_error: bool,
@@ -37,6 +41,14 @@ pub struct BLine {
 }
 
 impl BLine {
+    /// an estimation of the memory taken by the line, not
+    /// counting its children
+    pub fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.path.as_os_str().len()
+            + self.subpath.capacity()
+            + self.name.capacity()
+    }
     /// a special constructor, checking nothing
     pub fn from_root(
         blines: &mut Arena<BLine>,
@@ -49,7 +61,7 @@ impl BLine {
             None => String::from("???"), // should not happen
         };
         if let Ok(md) = fs::metadata(&path) {
-            let file_type = md.file_type();
+            let file_type = md.file_type().into();
             Ok(blines.alloc(BLine {
                 parent_id: None,
                 path,
@@ -73,11 +85,9 @@ impl BLine {
             })
         }
     }
-    /// execute read_dir either on the link if we're a link,
-    /// or on the path otherwise.
-    ///
-    /// Assume the can_enter check has already be done.
-    pub(crate) fn read_dir(&self) -> io::Result<fs::ReadDir> {
+    /// the directory to list to get the children: the
+    /// target if we're a link, the path otherwise.
+    pub(crate) fn dir_to_read(&self) -> PathBuf {
         if self.file_type.is_symlink() {
             if let Ok(target) = fs::read_link(&self.path) {
                 let mut target_path = PathBuf::from(&target);
@@ -85,10 +95,19 @@ impl BLine {
                     target_path = self.path.parent().unwrap().join(target_path);
                     target_path = normalize_path(target_path);
                 }
-                return fs::read_dir(&target_path);
+                return target_path;
             }
         }
-        fs::read_dir(&self.path)
+        self.path.clone()
+    }
+    /// list the entries of the directory to read, possibly
+    /// from the file index
+    ///
+    /// Assume the can_enter check has already be done.
+    pub(crate) fn read_dir(&self, use_index: bool) -> io::Result<(PathBuf, Vec<IndexedEntry>)> {
+        let dir = self.dir_to_read();
+        let entries = file_index::list_dir(&dir, use_index)?;
+        Ok((dir, entries))
     }
     /// tell whether we should list the childs of the present line
     pub fn can_enter(&self) -> bool {
@@ -118,16 +137,38 @@ impl BLine {
         }
         false
     }
-    pub fn to_tree_line(&self, bid: BId, con: &AppContext) -> std::io::Result<TreeLine> {
+    /// the line type to display while the metadata are late,
+    /// guessed from the directory entry
+    fn guessed_line_type(&self) -> TreeLineType {
+        match self.file_type {
+            EntryKind::Dir => TreeLineType::Dir,
+            EntryKind::Link => TreeLineType::SymLink {
+                direct_target: "…".to_string(),
+                final_is_dir: false,
+                final_target: self.path.clone(),
+            },
+            _ => TreeLineType::File,
+        }
+    }
+    /// make the tree line, with placeholders when the metadata
+    /// aren't there yet
+    pub fn to_tree_line(
+        &self,
+        bid: BId,
+        line_metadata: Option<LineMetadata>,
+        con: &AppContext,
+    ) -> TreeLine {
         let has_error = self.has_error;
-        let line_type = TreeLineType::new(&self.path, &self.file_type);
+        let (metadata, line_type) = match line_metadata {
+            Some(LineMetadata { metadata, line_type }) => (Some(metadata), line_type),
+            None => (None, self.guessed_line_type()),
+        };
         let unlisted = if let Some(children) = &self.children {
             // number of not listed children
             children.len() - self.next_child_idx
         } else {
             0
         };
-        let metadata = fs::symlink_metadata(&self.path)?;
         let subpath = self.subpath.replace('\n', "");
         let name = self.name.replace('\n', "");
         let icon = con.icons.as_ref()
@@ -143,7 +184,7 @@ impl BLine {
                 )
             });
 
-        Ok(TreeLine {
+        TreeLine {
             bid,
             parent_bid: self.parent_id,
             left_branchs: vec![false; self.depth as usize].into_boxed_slice(),
@@ -161,6 +202,8 @@ impl BLine {
             sum: None,
             metadata,
             git_status: None,
-        })
+            lint: None,
+            rating: 0,
+        }
     }
 }
@@ -17,4 +17,12 @@ pub struct BuildReport {
     /// number of errors excluding a file
     pub error_count: usize,
 
+    /// number of files the content search didn't look into
+    /// because of the content search limits
+    pub content_skipped_count: usize,
+
+    /// whether the exploration was stopped because the
+    /// memory budget was exceeded
+    pub memory_truncated: bool,
+
 }
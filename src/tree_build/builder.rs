@@ -3,25 +3,32 @@ use {
         bid::{BId, SortableBId},
         BuildReport,
         bline::BLine,
+        LineMetadata,
+        MetadataLoader,
+        METADATA_BUILD_WAIT,
     },
     crate::{
         app::AppContext,
         errors::TreeBuildError,
+        file_index::{EntryKind, IndexedEntry},
         git::{GitIgnoreChain, GitIgnorer, LineStatusComputer},
         pattern::Candidate,
+        tag::TagStore,
         path::{SpecialHandling, SpecialPathList},
         task_sync::ComputationResult,
         task_sync::Dam,
         tree::*,
     },
+    fnv::FnvHashMap,
     git2::Repository,
     id_arena::Arena,
     std::{
         collections::{BinaryHeap, VecDeque},
         fs,
-        path::PathBuf,
+        io,
+        path::{Path, PathBuf},
         result::Result,
-        time::{Duration, Instant},
+        time::{Duration, Instant, SystemTime},
     },
 };
 
@@ -65,6 +72,8 @@ pub struct TreeBuilder<'c> {
     pub matches_max: Option<usize>, // optional hard limit
     trim_root: bool,
     report: BuildReport,
+    /// estimation of the memory taken by the blines
+    memory_size: usize,
 }
 impl<'c> TreeBuilder<'c> {
 
@@ -74,6 +83,11 @@ impl<'c> TreeBuilder<'c> {
         targeted_size: usize,
         con: &'c AppContext,
     ) -> Result<TreeBuilder<'c>, TreeBuildError> {
+        if !con.is_allowed_path(&path) {
+            return Err(TreeBuildError::OutsidePinnedRoot {
+                path: path.to_string_lossy().to_string(),
+            });
+        }
         let mut blines = Arena::new();
         let mut git_ignorer = time!(GitIgnorer::default());
         let root_ignore_chain = git_ignorer.root_chain(&path);
@@ -88,8 +102,8 @@ impl<'c> TreeBuilder<'c> {
             None
         };
         let root_id = BLine::from_root(&mut blines, path, root_ignore_chain, &options)?;
-        let trim_root = match (options.trim_root, options.pattern.is_some(), options.sort.prevent_deep_display()) {
-            // we never want to trim the root if there's a sort
+        let trim_root = match (options.trim_root, options.pattern.is_some(), options.shows_one_level()) {
+            // we never want to trim the root if there's a sort or only one level
             (_, _, true) => false,
             // if the user don't want root trimming, we don't trim
             (false, _, _) => false,
@@ -98,6 +112,12 @@ impl<'c> TreeBuilder<'c> {
             // in other cases, as the user wants trimming, we trim
             _ => true,
         };
+        let targeted_size = if options.shows_match_lines() {
+            // every hit takes two rows
+            targeted_size / 2
+        } else {
+            targeted_size
+        };
         Ok(TreeBuilder {
             options,
             targeted_size,
@@ -110,17 +130,19 @@ impl<'c> TreeBuilder<'c> {
             trim_root,
             matches_max: None,
             report: BuildReport::default(),
+            memory_size: 0,
         })
     }
 
-    /// Return a bline if the dir_entry directly matches the options and there's no error
+    /// Return a bline if the entry directly matches the options and there's no error
     fn make_line(
         &mut self,
         parent_id: BId,
-        e: &fs::DirEntry,
+        dir: &Path,
+        e: &IndexedEntry,
         depth: u16,
     ) -> Option<BLine> {
-        let name = e.file_name();
+        let name = &e.name;
         if name.is_empty() {
             self.report.error_count += 1;
             return None;
@@ -129,17 +151,11 @@ impl<'c> TreeBuilder<'c> {
             self.report.hidden_count += 1;
             return None;
         }
+        let path = dir.join(name);
         let name = name.to_string_lossy();
         let mut has_match = true;
         let mut score = 10000 - i32::from(depth); // we dope less deep entries
-        let path = e.path();
-        let file_type = match e.file_type() {
-            Ok(ft) => ft,
-            Err(_) => {
-                self.report.error_count += 1;
-                return None;
-            }
-        };
+        let file_type = e.kind;
         let parent_subpath = &self.blines[parent_id].subpath;
         let subpath = if !parent_subpath.is_empty() {
             format!("{}/{}", parent_subpath, &name)
@@ -208,16 +224,22 @@ impl<'c> TreeBuilder<'c> {
         })
     }
 
+    /// whether the line, if kept, will be a line of the tree: the
+    /// directories aren't listed in flat mode
+    fn is_listed(&self, bline: &BLine) -> bool {
+        !self.options.flat || bline.file_type != EntryKind::Dir
+    }
+
     /// Return true when there are direct matches among children
     fn load_children(&mut self, bid: BId) -> bool {
         let mut has_child_match = false;
-        match self.blines[bid].read_dir() {
-            Ok(entries) => {
+        match self.blines[bid].read_dir(self.con.file_index) {
+            Ok((dir, entries)) => {
                 let mut children: Vec<BId> = Vec::new();
                 let child_depth = self.blines[bid].depth + 1;
                 let mut lines = Vec::new();
-                for e in entries.flatten() {
-                    if let Some(line) = self.make_line(bid, &e, child_depth) {
+                for e in &entries {
+                    if let Some(line) = self.make_line(bid, &dir, e, child_depth) {
                         lines.push(line);
                     }
                 }
@@ -234,6 +256,7 @@ impl<'c> TreeBuilder<'c> {
                         self.blines[bid].has_match = true;
                         has_child_match = true;
                     }
+                    self.memory_size += bl.memory_size() + std::mem::size_of::<BId>();
                     let child_id = self.blines.alloc(bl);
                     children.push(child_id);
                 }
@@ -253,6 +276,40 @@ impl<'c> TreeBuilder<'c> {
         has_child_match
     }
 
+    /// add the extra roots as last children of the root, so that
+    /// their content is searched too
+    fn add_extra_roots(&mut self) {
+        for path in self.options.extra_roots.clone() {
+            if !path.is_dir() {
+                continue;
+            }
+            // the name is the whole path, to make clear it's not under the root
+            let name = path.to_string_lossy().to_string();
+            let git_ignore_chain = self.git_ignorer.root_chain(&path);
+            let special_handling = self.con.special_paths.find(&path);
+            let child_id = self.blines.alloc(BLine {
+                parent_id: Some(self.root_id),
+                path,
+                depth: 1,
+                subpath: name.clone(),
+                name,
+                file_type: EntryKind::Dir,
+                children: None,
+                next_child_idx: 0,
+                has_error: false,
+                has_match: false,
+                direct_match: false,
+                score: 10000 - 1,
+                nb_kept_children: 0,
+                git_ignore_chain,
+                special_handling,
+            });
+            if let Some(children) = self.blines[self.root_id].children.as_mut() {
+                children.push(child_id);
+            }
+        }
+    }
+
     /// return the next child.
     /// load_children must have been called before on parent_id
     fn next_child(&mut self, parent_id: BId) -> Option<BId> {
@@ -270,6 +327,24 @@ impl<'c> TreeBuilder<'c> {
         }
     }
 
+    /// tell whether the blines exceed the memory budget, in which
+    /// case the exploration must stop where it is
+    fn is_over_memory_budget(&mut self) -> bool {
+        match self.con.tree_memory_budget {
+            Some(budget) if self.memory_size > budget => {
+                if !self.report.memory_truncated {
+                    info!("tree memory budget exceeded: {} bytes", self.memory_size);
+                    self.report.memory_truncated = true;
+                    self.total_search = false;
+                    // the directory sizes will be computed again if needed
+                    crate::file_sum::clear_cache();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// first step of the build: we explore the directories and gather lines.
     /// If there's no search pattern we stop when we have enough lines to fill the screen.
     /// If there's a pattern, we try to gather more lines that will be sorted afterwards.
@@ -286,6 +361,9 @@ impl<'c> TreeBuilder<'c> {
         let mut open_dirs: VecDeque<BId> = VecDeque::new();
         let mut next_level_dirs: Vec<BId> = Vec::new();
         self.load_children(self.root_id);
+        if self.options.pattern.is_some() {
+            self.add_extra_roots();
+        }
         open_dirs.push_back(self.root_id);
         loop {
             if !total_search && (
@@ -304,7 +382,7 @@ impl<'c> TreeBuilder<'c> {
                 if let Some(child_id) = self.next_child(open_dir_id) {
                     open_dirs.push_back(open_dir_id);
                     let child = &self.blines[child_id];
-                    if child.has_match {
+                    if child.has_match && self.is_listed(child) {
                         nb_lines_ok += 1;
                     }
                     if child.can_enter() {
@@ -314,28 +392,38 @@ impl<'c> TreeBuilder<'c> {
                 }
             } else {
                 // this depth is finished, we must go deeper
-                if self.options.sort.prevent_deep_display() {
-                    // in sort mode, only one level is displayed
+                if self.options.shows_one_level() && !self.options.flat {
+                    // in sort mode or in Miller columns, only one level is displayed
                     break;
                 }
                 if next_level_dirs.is_empty() {
                     // except there's nothing deeper
                     break;
                 }
+                if self.is_over_memory_budget() {
+                    // the deeper directories are left unexplored
+                    break;
+                }
                 for next_level_dir_id in &next_level_dirs {
                     if dam.has_event() {
                         info!("task expired (core build - inner loop)");
                         return Err(TreeBuildError::Interrupted);
                     }
+                    if self.is_over_memory_budget() {
+                        break;
+                    }
                     let has_child_match = self.load_children(*next_level_dir_id);
                     if has_child_match {
                         // we must ensure the ancestors are made Ok
                         let mut id = *next_level_dir_id;
                         loop {
+                            let flat = self.options.flat;
                             let mut bline = &mut self.blines[id];
                             if !bline.has_match {
                                 bline.has_match = true;
-                                nb_lines_ok += 1;
+                                if !flat {
+                                    nb_lines_ok += 1;
+                                }
                             }
                             if let Some(pid) = bline.parent_id {
                                 id = pid;
@@ -364,17 +452,49 @@ impl<'c> TreeBuilder<'c> {
         Ok(out_blines)
     }
 
+    /// rank the lines according to the tie breaking option, so
+    /// that the trimming of lines with the same score is predictable
+    fn tie_ranks(&self, out_blines: &[BId]) -> FnvHashMap<BId, usize> {
+        let tie_breaking = self.options.tie_breaking;
+        let mtimes: Vec<Option<SystemTime>> = out_blines.iter()
+            .map(|id| {
+                if tie_breaking.needs_dates() {
+                    fs::symlink_metadata(&self.blines[*id].path)
+                        .and_then(|md| md.modified())
+                        .ok()
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let mut indexes: Vec<usize> = (0..out_blines.len()).collect();
+        indexes.sort_by(|&a, &b| {
+            let (ba, bb) = (&self.blines[out_blines[a]], &self.blines[out_blines[b]]);
+            tie_breaking.compare(
+                &TieKey { path: &ba.path, depth: ba.depth, mtime: mtimes[a] },
+                &TieKey { path: &bb.path, depth: bb.depth, mtime: mtimes[b] },
+            )
+        });
+        indexes.iter()
+            .enumerate()
+            .map(|(rank, &idx)| (out_blines[idx], rank))
+            .collect()
+    }
+
     /// Post search trimming
     /// When there's a pattern, gathering normally brings many more lines than
     ///  strictly necessary to fill the screen.
     /// This function keeps only the best ones while taking care of not
     ///  removing a parent before its children.
     fn trim_excess(&mut self, out_blines: &[BId]) {
+        let tie_ranks = self.tie_ranks(out_blines);
         let mut count = 1;
         for id in out_blines[1..].iter() {
             if self.blines[*id].has_match {
                 //debug!("bline before trimming: {:?}", &self.blines[*idx].path);
-                count += 1;
+                if self.is_listed(&self.blines[*id]) {
+                    count += 1;
+                }
                 let parent_id = self.blines[*id].parent_id.unwrap();
                 // (we can unwrap because only the root can have a None parent)
                 self.blines[parent_id].nb_kept_children += 1;
@@ -389,6 +509,7 @@ impl<'c> TreeBuilder<'c> {
                 remove_queue.push(SortableBId {
                     id: *id,
                     score: bline.score,
+                    tie_rank: tie_ranks[id],
                 });
             }
         }
@@ -403,9 +524,12 @@ impl<'c> TreeBuilder<'c> {
                     remove_queue.push(SortableBId {
                         id: parent_id,
                         score: parent.score,
+                        tie_rank: tie_ranks[&parent_id],
                     });
                 }
-                count -= 1;
+                if self.is_listed(&self.blines[sli.id]) {
+                    count -= 1;
+                }
             } else {
                 debug!("trimming prematurely interrupted");
                 break;
@@ -415,22 +539,42 @@ impl<'c> TreeBuilder<'c> {
 
     /// make a tree from the builder's specific structure
     fn take_as_tree(mut self, out_blines: &[BId]) -> Tree {
-        let mut lines: Vec<TreeLine> = Vec::new();
+        let mut ids: Vec<BId> = Vec::new();
         for id in out_blines.iter() {
             if self.blines[*id].has_match {
                 // we need to count the children, so we load them
                 if self.blines[*id].can_enter() && self.blines[*id].children.is_none() {
                     self.load_children(*id);
                 }
-                if let Ok(tree_line) = self.blines[*id].to_tree_line(*id, self.con) {
-                    lines.push(tree_line);
-                } else {
+                ids.push(*id);
+            }
+        }
+        // the metadata are read in background threads, and the lines
+        // whose metadata are late get placeholders, filled later
+        let metadata_loader = MetadataLoader::start(
+            &self.blines[self.root_id].path,
+            ids.iter().map(|&id| (id, self.blines[id].path.clone())).collect()
+        );
+        let (received, done) = metadata_loader.receive(METADATA_BUILD_WAIT);
+        let mut received: FnvHashMap<BId, io::Result<LineMetadata>> = received
+            .into_iter()
+            .collect();
+        let mut lines: Vec<TreeLine> = Vec::new();
+        for id in ids {
+            match received.remove(&id) {
+                Some(Ok(line_metadata)) => {
+                    lines.push(self.blines[id].to_tree_line(id, Some(line_metadata), self.con));
+                }
+                Some(Err(_)) => {
                     // I guess the file went missing during tree computation
                     warn!(
                         "Error while builind treeline for {:?}",
-                        self.blines[*id].path,
+                        self.blines[id].path,
                     );
                 }
+                None => {
+                    lines.push(self.blines[id].to_tree_line(id, None, self.con));
+                }
             }
         }
         let mut tree = Tree {
@@ -441,7 +585,16 @@ impl<'c> TreeBuilder<'c> {
             total_search: self.total_search,
             git_status: ComputationResult::None,
             build_report: self.report,
+            appeared: Default::default(),
+            late_metadata: if done { None } else { Some(metadata_loader) },
+            lint_loader: None,
         };
+        if tree.options.sort == Sort::Rating {
+            let store = TagStore::load();
+            for line in tree.lines.iter_mut() {
+                line.rating = store.rating_of(&line.path);
+            }
+        }
         tree.after_lines_changed();
         if let Some(computer) = self.line_status_computer {
             // tree git status is slow to compute, we just mark it should be
@@ -461,8 +614,11 @@ impl<'c> TreeBuilder<'c> {
     /// Return None if the lifetime expires before end of computation
     /// (usually because the user hit a key)
     pub fn build_tree(mut self, total_search: bool, dam: &Dam) -> Result<Tree, TreeBuildError> {
+        let skipped_before = self.options.pattern.pattern.content_skipped_count();
         self.gather_lines(total_search, dam)
             .map(|blines_ids| {
+                self.report.content_skipped_count = self.options.pattern.pattern
+                    .content_skipped_count() - skipped_before;
                 debug!("blines before trimming: {}", blines_ids.len());
                 if !self.total_search {
                     self.trim_excess(&blines_ids);
@@ -0,0 +1,141 @@
+use {
+    super::BId,
+    crate::tree::TreeLineType,
+    crossbeam::channel::{unbounded, Receiver, RecvTimeoutError},
+    once_cell::sync::Lazy,
+    rayon::{ThreadPool, ThreadPoolBuilder},
+    std::{
+        fs,
+        io,
+        path::{Path, PathBuf},
+        time::{Duration, Instant},
+    },
+};
+
+/// the number of paths whose metadata are read in sequence by
+/// one job: an entry whose stat call hangs only delays the
+/// other entries of its batch
+const BATCH_SIZE: usize = 8;
+
+/// the number of threads reading metadata. They're shared by all
+/// trees, so that their number stays bounded even when threads
+/// are stuck on an unresponsive mount
+const POOL_SIZE: usize = 8;
+
+static POOL: Lazy<ThreadPool> = Lazy::new(|| {
+    ThreadPoolBuilder::new()
+        .num_threads(POOL_SIZE)
+        .thread_name(|i| format!("metadata-{}", i))
+        .build()
+        .unwrap()
+});
+
+/// how long the tree builder waits for the metadata before making
+/// the tree with placeholders for the late lines
+pub const METADATA_BUILD_WAIT: Duration = Duration::from_millis(150);
+
+/// the max time a call to fetch the late metadata waits
+pub const METADATA_WAIT_PERIOD: Duration = Duration::from_millis(100);
+
+/// the time after which we stop waiting for the late metadata
+const METADATA_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// what a stat call gives for a tree line
+pub struct LineMetadata {
+    pub metadata: fs::Metadata,
+    pub line_type: TreeLineType,
+}
+
+impl LineMetadata {
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let metadata = fs::symlink_metadata(path)?;
+        let line_type = TreeLineType::new(path, &metadata.file_type());
+        Ok(Self { metadata, line_type })
+    }
+}
+
+/// tell whether the metadata of the paths under the root can be
+/// read without risk of hanging, because neither the root nor any
+/// mount below it is a network filesystem
+#[cfg(unix)]
+fn is_local(root: &Path) -> bool {
+    let mut mount_list = crate::filesystems::MOUNTS.lock().unwrap();
+    let mounts = match mount_list.load() {
+        Ok(mounts) => mounts,
+        Err(_) => return false,
+    };
+    let root_dev = match fs::metadata(root) {
+        Ok(md) => std::os::unix::fs::MetadataExt::dev(&md),
+        Err(_) => return false,
+    };
+    !mounts.iter().any(|m| {
+        m.info.is_remote()
+            && (m.info.dev == root_dev.into() || m.info.mount_point.starts_with(root))
+    })
+}
+#[cfg(not(unix))]
+fn is_local(_root: &Path) -> bool {
+    false // we don't know, so we play safe
+}
+
+/// The reading, in background threads, of the metadata of the
+/// lines of a tree, so that a hung entry (eg on an unresponsive
+/// network mount) doesn't freeze the application.
+///
+/// On local filesystems, the metadata are read immediately.
+#[derive(Debug, Clone)]
+pub struct MetadataLoader {
+    receiver: Receiver<(BId, io::Result<LineMetadata>)>,
+    start: Instant,
+}
+
+impl MetadataLoader {
+    pub fn start(root: &Path, paths: Vec<(BId, PathBuf)>) -> Self {
+        let (sender, receiver) = unbounded();
+        if is_local(root) {
+            for (bid, path) in paths {
+                let _ = sender.send((bid, LineMetadata::read(&path)));
+            }
+            return Self { receiver, start: Instant::now() };
+        }
+        for batch in paths.chunks(BATCH_SIZE) {
+            let batch = batch.to_vec();
+            let sender = sender.clone();
+            POOL.spawn(move || {
+                for (bid, path) in batch {
+                    if sender.send((bid, LineMetadata::read(&path))).is_err() {
+                        break; // nobody waits for them anymore
+                    }
+                }
+            });
+        }
+        Self { receiver, start: Instant::now() }
+    }
+    /// wait up to the given duration for the metadata, return the
+    /// received ones and whether there's nothing more to wait for
+    /// (all were read, or the remaining ones took too long)
+    pub fn receive(
+        &self,
+        timeout: Duration,
+    ) -> (Vec<(BId, io::Result<LineMetadata>)>, bool) {
+        let deadline = Instant::now() + timeout;
+        let mut received = Vec::new();
+        loop {
+            match self.receiver.recv_deadline(deadline) {
+                Ok(line_metadata) => {
+                    received.push(line_metadata);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let done = self.start.elapsed() > METADATA_TIMEOUT;
+                    if done {
+                        warn!("reading metadata timed out");
+                    }
+                    return (received, done);
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return (received, true);
+                }
+            }
+        }
+    }
+}
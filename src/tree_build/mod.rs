@@ -2,9 +2,11 @@ mod bid;
 mod bline;
 mod build_report;
 mod builder;
+mod metadata_loader;
 
 pub use {
     bid::BId,
     builder::TreeBuilder,
     build_report::BuildReport,
+    metadata_loader::*,
 };
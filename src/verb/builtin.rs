@@ -1,7 +1,7 @@
 use {
     super::*,
     crate::{
-        app::SelectionType,
+        app::{PanelStateType, SelectionType},
     },
     crokey::*,
 };
@@ -47,8 +47,26 @@ fn external(
 
 /// declare the built_in verbs, the ones which are available
 /// in standard (they still may be overridden by configuration)
-pub fn builtin_verbs() -> Vec<Verb> {
+pub fn builtin_verbs(
+    overwrite_policy: OverwritePolicy,
+    rm_to_trash: bool,
+) -> Vec<Verb> {
     use super::{ExternalExecutionMode::*, Internal::*};
+    #[cfg(unix)]
+    let ow = overwrite_policy.unix_options();
+    #[cfg(windows)]
+    let _ = overwrite_policy;
+    #[cfg(unix)]
+    let rm = if rm_to_trash {
+        let execution = VerbExecution::Internal(InternalExecution::from_internal(trash));
+        let description = VerbDescription::from_text(trash.description().to_string());
+        Verb::new(Some("rm"), execution, description).unwrap()
+    } else {
+        external("rm", "rm -rf {file}", StayInBroot)
+            .mutating()
+    };
+    #[cfg(windows)]
+    let _ = rm_to_trash;
     vec![
         internal(back),
 
@@ -65,15 +83,20 @@ pub fn builtin_verbs() -> Vec<Verb> {
         internal(input_go_to_start).with_key(key!(home)).no_doc(),
         internal(input_go_word_left).no_doc(),
         internal(input_go_word_right).no_doc(),
+        internal(input_help).with_key(key!(ctrl-k)).no_doc(),
 
         // arrow keys bindings
         internal(back).with_key(key!(left)),
         internal(open_stay).with_key(key!(right)),
         internal(line_down).with_key(key!(down)).with_key(key!('j')),
         internal(line_up).with_key(key!(up)).with_key(key!('k')),
+        internal(extend_selection_down).with_key(key!(shift-down)),
+        internal(extend_selection_up).with_key(key!(shift-up)),
 
         //
         internal(set_syntax_theme),
+        internal(set_preview_encoding),
+        internal(preview_theme),
 
         // those two operations are mapped on ALT-ENTER, one
         // for directories and the other one for the other files
@@ -89,9 +112,11 @@ pub fn builtin_verbs() -> Vec<Verb> {
 
         #[cfg(unix)]
         external("chmod {args}", "chmod {args} {file}", StayInBroot)
+            .mutating()
             .with_stype(SelectionType::File),
         #[cfg(unix)]
         external("chmod {args}", "chmod -R {args} {file}", StayInBroot)
+            .mutating()
             .with_stype(SelectionType::Directory),
         internal(open_preview),
         internal(close_preview),
@@ -102,15 +127,20 @@ pub fn builtin_verbs() -> Vec<Verb> {
             .with_shortcut("txt"),
         internal(preview_binary)
             .with_shortcut("hex"),
+        internal(preview_tail)
+            .with_shortcut("tail"),
+        internal(compare_images)
+            .with_shortcut("cmpi"),
         internal(close_panel_ok),
         internal(close_panel_cancel)
             .with_key(key!(ctrl-w)),
         #[cfg(unix)]
         external(
             "copy {newpath}",
-            "cp -r {file} {newpath:path-from-parent}",
+            &format!("cp -r {ow}{{file}} {{newpath:path-from-parent}}"),
             StayInBroot,
         )
+            .mutating()
             .with_shortcut("cp"),
         #[cfg(windows)]
         external(
@@ -118,21 +148,70 @@ pub fn builtin_verbs() -> Vec<Verb> {
             "xcopy /Q /H /Y /I {file} {newpath:path-from-parent}",
             StayInBroot,
         )
+            .mutating()
             .with_shortcut("cp"),
         #[cfg(feature = "clipboard")]
         internal(copy_line)
             .with_key(key!(alt-c)),
         #[cfg(feature = "clipboard")]
+        internal(copy_numbered_lines),
+        #[cfg(feature = "clipboard")]
         internal(copy_path),
+        #[cfg(unix)]
+        external(
+            "copy_to_panel",
+            &format!("cp -r {ow}{{file}} {{other-panel-directory}}"),
+            StayInBroot,
+        )
+            .mutating()
+            .with_shortcut("cpp"),
+        #[cfg(windows)]
         external(
             "copy_to_panel",
             "cp -r {file} {other-panel-directory}",
             StayInBroot,
         )
+            .mutating()
             .with_shortcut("cpp"),
         #[cfg(unix)]
+        internal(containers)
+            .with_shortcut("ctn"),
+        internal(dir_info)
+            .with_key(key!(alt-d))
+            .with_shortcut("di"),
+        #[cfg(unix)]
         internal(filesystems)
             .with_shortcut("fs"),
+        #[cfg(unix)]
+        internal(mount),
+        #[cfg(unix)]
+        internal(unmount)
+            .with_shortcut("umount"),
+        #[cfg(unix)]
+        internal(snapshots)
+            .with_shortcut("snap"),
+        internal(versions)
+            .with_shortcut("ver"),
+        internal(restore_version),
+        #[cfg(unix)]
+        internal(trash),
+        #[cfg(unix)]
+        internal(open_trash),
+        #[cfg(unix)]
+        internal(restore_trashed),
+        #[cfg(unix)]
+        internal(purge_trashed),
+        internal(report_extensions)
+            .with_shortcut("rx"),
+        internal(report_top)
+            .with_shortcut("top"),
+        internal(quick_roots)
+            .with_shortcut("qr"),
+        internal(tips),
+        internal(reindex),
+        internal(add_root),
+        internal(clear_roots),
+        internal(exclude),
         // :focus is also hardcoded on Enter on directories
         // but ctrl-f is useful for focusing on a file's parent
         // (and keep the filter)
@@ -151,6 +230,7 @@ pub fn builtin_verbs() -> Vec<Verb> {
             "mkdir -p {subpath:path-from-directory}",
             StayInBroot,
         )
+            .mutating()
             .with_shortcut("md"),
         #[cfg(windows)]
         external(
@@ -158,13 +238,15 @@ pub fn builtin_verbs() -> Vec<Verb> {
             "cmd /c mkdir {subpath:path-from-directory}",
             StayInBroot,
         )
+            .mutating()
             .with_shortcut("md"),
         #[cfg(unix)]
         external(
             "move {newpath}",
-            "mv {file} {newpath:path-from-parent}",
+            &format!("mv {ow}{{file}} {{newpath:path-from-parent}}"),
             StayInBroot,
         )
+            .mutating()
             .with_shortcut("mv"),
         #[cfg(windows)]
         external(
@@ -172,13 +254,15 @@ pub fn builtin_verbs() -> Vec<Verb> {
             "cmd /c move /Y {file} {newpath:path-from-parent}",
             StayInBroot,
         )
+            .mutating()
             .with_shortcut("mv"),
         #[cfg(unix)]
         external(
             "move_to_panel",
-            "mv {file} {other-panel-directory}",
+            &format!("mv {ow}{{file}} {{other-panel-directory}}"),
             StayInBroot,
         )
+            .mutating()
             .with_shortcut("mvp"),
         #[cfg(windows)]
         external(
@@ -186,6 +270,7 @@ pub fn builtin_verbs() -> Vec<Verb> {
             "cmd /c move /Y {file} {other-panel-directory}",
             StayInBroot,
         )
+            .mutating()
             .with_shortcut("mvp"),
         #[cfg(unix)]
         external(
@@ -193,6 +278,7 @@ pub fn builtin_verbs() -> Vec<Verb> {
             "mv {file} {parent}/{new_filename}",
             StayInBroot,
         )
+            .mutating()
             .with_auto_exec(false)
             .with_key(key!(f2)),
         #[cfg(windows)]
@@ -201,10 +287,16 @@ pub fn builtin_verbs() -> Vec<Verb> {
             "cmd /c move /Y {file} {parent}/{new_filename}",
             StayInBroot,
         )
+            .mutating()
             .with_auto_exec(false)
             .with_key(key!(f2)),
         internal_bang(start_end_panel)
             .with_key(key!(ctrl-p)),
+        // in trees, the space key marks paths instead of entering
+        // input mode (it still goes to the input in input mode)
+        internal(toggle_mark)
+            .with_key(key!(' '))
+            .with_key_contexts(vec![PanelStateType::Tree]),
         // the char keys for mode_input are handled differently as they're not
         // consumed by the command
         internal(mode_input)
@@ -223,6 +315,9 @@ pub fn builtin_verbs() -> Vec<Verb> {
             .with_shortcut("os"),
         internal(open_stay_filter)
             .with_shortcut("osf"),
+        #[cfg(unix)]
+        internal(open_biggest)
+            .with_shortcut("ob"),
         internal(parent)
             .with_key(key!(h))
             .with_shortcut("p"),
@@ -236,14 +331,29 @@ pub fn builtin_verbs() -> Vec<Verb> {
             .with_key(key!(ctrl-left)),
         internal(panel_right)
             .with_key(key!(ctrl-right)),
+        internal(panel_grow),
+        internal(panel_shrink),
+        internal(new_tab)
+            .with_key(key!(alt-t)),
+        internal(close_tab)
+            .with_key(key!(alt-w)),
+        internal(next_tab)
+            .with_key(key!(alt-right)),
+        internal(previous_tab)
+            .with_key(key!(alt-left)),
         internal(print_path).with_shortcut("pp"),
         internal(print_relative_path).with_shortcut("prp"),
         internal(print_tree).with_shortcut("pt"),
+        internal(print_tree_to).with_shortcut("ptt"),
+        #[cfg(unix)]
+        internal(print_fs),
         internal(quit)
             .with_key(key!(ctrl-c))
             .with_key(key!(ctrl-q))
             .with_shortcut("q"),
         internal(refresh).with_key(key!(f5)),
+        internal(rename_all),
+        internal(reveal),
         internal(root_up)
             .with_key(key!(ctrl-up)),
         internal(root_down)
@@ -251,6 +361,7 @@ pub fn builtin_verbs() -> Vec<Verb> {
         internal(select_first),
         internal(select_last),
         internal(select),
+        internal(goto),
         internal(clear_stage).with_shortcut("cls"),
         internal(stage)
             .with_key(key!('+')),
@@ -260,6 +371,9 @@ pub fn builtin_verbs() -> Vec<Verb> {
             .with_key(key!(ctrl-a)),
         internal(toggle_stage)
             .with_key(key!(ctrl-g)),
+        internal(stage_export).with_shortcut("stx"),
+        internal(stage_pipe).with_shortcut("stp"),
+        internal(clear_marks).with_shortcut("clm"),
         internal(open_staging_area).with_shortcut("osa"),
         internal(close_staging_area).with_shortcut("csa"),
         internal(toggle_staging_area).with_shortcut("tsa"),
@@ -267,31 +381,84 @@ pub fn builtin_verbs() -> Vec<Verb> {
         internal(sort_by_date).with_shortcut("sd"),
         internal(sort_by_size).with_shortcut("ss"),
         internal(sort_by_type).with_shortcut("st"),
+        internal(sort_by_rating).with_shortcut("sr"),
+        internal(sort_by_ext).with_shortcut("sx"),
+        internal(sort_by_ext_reversed),
+        internal(sort_by_ctime),
+        internal(sort_by_ctime_reversed),
+        internal(sort_by_atime),
+        internal(sort_by_atime_reversed),
+        internal(sort_by_owner).with_shortcut("so"),
+        internal(sort_by_owner_reversed),
         #[cfg(unix)]
-        external("rm", "rm -rf {file}", StayInBroot),
+        rm,
         #[cfg(windows)]
         external("rm", "cmd /c rmdir /Q /S {file}", StayInBroot)
+            .mutating()
             .with_stype(SelectionType::Directory),
         #[cfg(windows)]
         external("rm", "cmd /c del /Q {file}", StayInBroot)
+            .mutating()
             .with_stype(SelectionType::File),
         internal(toggle_counts).with_shortcut("counts"),
         internal(toggle_dates).with_shortcut("dates"),
         internal(toggle_device_id).with_shortcut("dev"),
+        internal(toggle_dry_run).with_shortcut("dry"),
+        internal(toggle_lint).with_shortcut("lint"),
+        internal(toggle_miller).with_shortcut("miller"),
+        internal(toggle_scores).with_shortcut("scores"),
+        internal(toggle_row_numbers).with_shortcut("rows"),
+        internal(toggle_match_lines).with_shortcut("ml"),
+        internal(tag),
+        internal(tags),
+        internal(bookmark).with_shortcut("bk"),
+        internal(unbookmark),
+        internal(bookmarks).with_shortcut("bks"),
+        internal(go_to_bookmark).with_shortcut("gb"),
+        internal(watch),
+        internal(toggle_watch),
+        internal(skip_conflict).with_shortcut("skip"),
+        internal(overwrite_conflict).with_shortcut("ow"),
+        internal(rename_conflict),
+        internal(skip_all_conflicts),
+        internal(overwrite_all_conflicts),
+        internal(rename_all_conflicts),
+        internal(diff_conflict).with_shortcut("diff"),
+        internal(rate),
+        internal(history_verbs).with_shortcut("hv"),
+        internal(export_verb_log),
+        internal(reset_verb_usage),
+        internal(escalate),
+        internal(resume_operation),
+        internal(rollback_operation),
+        internal(discard_operation),
         internal(toggle_files).with_shortcut("files"),
+        internal(toggle_flat).with_shortcut("flat"),
         internal(toggle_git_ignore)
             .with_key(key!(alt-i))
             .with_shortcut("gi"),
         internal(toggle_git_file_info).with_shortcut("gf"),
         internal(toggle_git_status).with_shortcut("gs"),
         internal(toggle_root_fs).with_shortcut("rfs"),
+        internal(toggle_only_disks).with_shortcut("disks"),
+        #[cfg(unix)]
+        internal(toggle_volume_group).with_shortcut("tvg"),
+        #[cfg(unix)]
+        internal(toggle_fs_totals).with_shortcut("tft"),
         internal(toggle_hidden)
             .with_key(key!(alt-h))
             .with_shortcut("h"),
-        #[cfg(unix)]
         internal(toggle_perm).with_shortcut("perm"),
+        internal(toggle_perm_mode).with_shortcut("permm"),
+        internal(toggle_perm_owner).with_shortcut("permo"),
+        internal(toggle_perm_group).with_shortcut("permg"),
+        internal(toggle_perm_acl).with_shortcut("perma"),
         internal(toggle_sizes).with_shortcut("sizes"),
+        internal(toggle_exact_sizes).with_shortcut("exact"),
+        internal(toggle_size_units).with_shortcut("units"),
         internal(toggle_trim_root),
+        internal(toggle_tail_follow).with_shortcut("follow"),
+        internal(toggle_zen).with_shortcut("zen"),
         internal(total_search).with_key(key!(ctrl-s)),
         internal(up_tree).with_shortcut("up"),
 
@@ -30,6 +30,12 @@ impl ExecPattern {
             Self::Array(v) => v.iter().any(|s| str_has_selection_group(s)),
         }
     }
+    pub fn has_marked_group(&self) -> bool {
+        match self {
+            Self::String(s) => str_has_marked_group(s),
+            Self::Array(v) => v.iter().any(|s| str_has_marked_group(s)),
+        }
+    }
     pub fn has_other_panel_group(&self) -> bool {
         match self {
             Self::String(s) => str_has_other_panel_group(s),
@@ -9,6 +9,9 @@ use {
     std::path::{Path, PathBuf},
 };
 
+#[cfg(unix)]
+use crate::filesystems;
+
 /// a temporary structure gathering selection and invocation
 /// parameters and able to generate an executable string from
 /// a verb's execution pattern
@@ -22,6 +25,15 @@ pub struct ExecutionStringBuilder<'b> {
     /// the selection in the other panel, when there are exactly two
     other_file: Option<&'b PathBuf>,
 
+    /// the number of panels
+    panel_count: usize,
+
+    /// the raw pattern filtering the active panel
+    filter: &'b str,
+
+    /// the number of staged paths
+    staged_count: usize,
+
     /// parsed arguments
     invocation_values: Option<AHashMap<String, String>>,
 }
@@ -38,6 +50,9 @@ impl<'b> ExecutionStringBuilder<'b> {
             sel_info,
             root: &app_state.root,
             other_file: app_state.other_panel_path.as_ref(),
+            panel_count: app_state.panel_count,
+            filter: &app_state.filter,
+            staged_count: app_state.stage.len(),
             invocation_values: None,
         }
     }
@@ -55,6 +70,9 @@ impl<'b> ExecutionStringBuilder<'b> {
             sel_info,
             root: &app_state.root,
             other_file: app_state.other_panel_path.as_ref(),
+            panel_count: app_state.panel_count,
+            filter: &app_state.filter,
+            staged_count: app_state.stage.len(),
             invocation_values,
         }
     }
@@ -73,6 +91,8 @@ impl<'b> ExecutionStringBuilder<'b> {
                     .map(|path| Selection {
                         path,
                         line: 0,
+                        line_range: None,
+                        text_view: None,
                         stype: SelectionType::from(path),
                         is_exe: false,
                     });
@@ -103,8 +123,12 @@ impl<'b> ExecutionStringBuilder<'b> {
         debug!("repl name : {:?}", name);
         match name {
             "root" => Some(path_to_string(self.root)),
+            "panel-count" => Some(self.panel_count.to_string()),
+            "filter" => Some(self.filter.to_string()),
+            "staged-count" => Some(self.staged_count.to_string()),
             "line" => sel.map(|s| s.line.to_string()),
-            "file" => sel.map(|s| s.path)
+            "selection-text" => sel.and_then(|s| s.lines_text(false)),
+            "file" | "marked" => sel.map(|s| s.path)
                 .map(path_to_string),
             "file-name" => sel.map(|s| s.path)
                 .and_then(|path| path.file_name())
@@ -148,6 +172,18 @@ impl<'b> ExecutionStringBuilder<'b> {
                 .other_file
                 .and_then(|p| p.parent())
                 .map(path_to_string),
+            #[cfg(unix)]
+            "mount-point" | "mount_point" => sel.and_then(|s| filesystems::mount_of(s.path))
+                .map(|m| path_to_string(m.info.mount_point)),
+            #[cfg(unix)]
+            "device" => sel.and_then(|s| filesystems::mount_of(s.path))
+                .map(|m| m.info.fs),
+            #[cfg(unix)]
+            "fs-type" | "fs_type" => sel.and_then(|s| filesystems::mount_of(s.path))
+                .map(|m| m.info.fs_type),
+            #[cfg(unix)]
+            "uuid" => sel.and_then(|s| filesystems::mount_of(s.path))
+                .and_then(|m| filesystems::device_uuid(&m.info.fs)),
             _ => None,
         }
     }
@@ -329,7 +365,9 @@ mod execution_builder_test {
     use {
         super::*,
         crate::{
+            activity::VerbLog,
             stage::*,
+            tag::TagStore,
         },
     };
 
@@ -343,13 +381,25 @@ mod execution_builder_test {
         let sel = Selection {
             path: &path,
             line: 0,
+            line_range: None,
+            text_view: None,
             stype: SelectionType::File,
             is_exe: false,
         };
         let app_state = AppState {
             stage: Stage::default(),
+            marks: Stage::default(),
             root: PathBuf::from("/".to_owned()),
             other_panel_path: None,
+            panel_count: 1,
+            filter: String::new(),
+            tags: TagStore::default(),
+            verb_log: VerbLog::default(),
+            verb_usage: VerbUsage::default(),
+            dry_run: false,
+            pending_escalation: Vec::new(),
+            events: None,
+            read_only_roots: Vec::new(),
         };
         let mut builder = ExecutionStringBuilder::without_invocation(
             SelInfo::One(sel),
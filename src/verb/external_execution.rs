@@ -1,10 +1,12 @@
 use {
     super::*,
     crate::{
+        activity::{self, Batch, ConflictState},
         app::*,
         display::W,
         errors::ProgramError,
         launchable::Launchable,
+        tr,
     },
     std::{
         fs::OpenOptions,
@@ -28,6 +30,11 @@ pub struct ExternalExecution {
     /// * {other-panel-file}
     /// * {other-panel-directory}
     /// * {other-panel-parent}
+    /// * {root}
+    /// * {filter}
+    /// * {panel-count}
+    /// * {staged-count}
+    /// * {marked}, which makes the pattern executed once per marked path
     pub exec_pattern: ExecPattern,
 
     /// how the external process must be launched
@@ -36,6 +43,9 @@ pub struct ExternalExecution {
     /// the working directory of the new process, or none if we don't
     /// want to set it
     pub working_dir: Option<String>,
+
+    /// the environment and limits of the new process
+    pub sandbox: Sandbox,
 }
 
 impl ExternalExecution {
@@ -47,6 +57,7 @@ impl ExternalExecution {
             exec_pattern,
             exec_mode,
             working_dir: None,
+            sandbox: Sandbox::default(),
         }
     }
 
@@ -55,14 +66,28 @@ impl ExternalExecution {
         self
     }
 
+    pub fn with_sandbox(mut self, sandbox: Sandbox) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
     /// goes from the external execution command to the CmdResult:
     /// - by executing the command if it can be executed from a subprocess
     /// - by building a command to be executed in parent shell in other cases
+    ///
+    /// When `denied` is given, the executions failing for lack of
+    /// permission are pushed to it, escalated, instead of being errors.
+    ///
+    /// When `journal_command` is given, the executions on a multi-selection
+    /// are recorded in an operation journal so that they can be resumed
+    /// or rolled back if broot is killed before the end.
     pub fn to_cmd_result(
         &self,
         w: &mut W,
         builder: ExecutionStringBuilder<'_>,
         con: &AppContext,
+        denied: Option<&mut Vec<Launchable>>,
+        journal_command: Option<String>,
     ) -> Result<CmdResult, ProgramError> {
         match self.exec_mode {
             ExternalExecutionMode::FromParentShell => self.cmd_result_exec_from_parent_shell(
@@ -77,10 +102,35 @@ impl ExternalExecution {
                 w,
                 builder,
                 con,
+                denied,
+                journal_command,
             ),
         }
     }
 
+    /// the command which would be executed, with all arguments
+    /// expanded (one per selected path when there are several)
+    pub fn dry_run_string(
+        &self,
+        builder: &ExecutionStringBuilder<'_>,
+    ) -> String {
+        match &builder.sel_info {
+            SelInfo::None | SelInfo::One(_) => builder.shell_exec_string(&self.exec_pattern),
+            SelInfo::More(stage) => stage.paths().iter()
+                .map(|path| Selection {
+                    path,
+                    line: 0,
+                    line_range: None,
+                    text_view: None,
+                    stype: SelectionType::from(path),
+                    is_exe: false,
+                })
+                .map(|sel| builder.sel_shell_exec_string(&self.exec_pattern, Some(sel)))
+                .collect::<Vec<String>>()
+                .join(" ; "),
+        }
+    }
+
     fn working_dir_path(
         &self,
         builder: &ExecutionStringBuilder<'_>,
@@ -106,9 +156,7 @@ impl ExternalExecution {
         con: &AppContext,
     ) -> Result<CmdResult, ProgramError> {
         if builder.sel_info.count_paths() > 1 {
-            return Ok(CmdResult::error(
-                "only verbs returning to broot on end can be executed on a multi-selection"
-            ));
+            return Ok(CmdResult::error(tr!(error_multi_selection_leaves_broot)));
         }
         if let Some(ref export_path) = con.launch_args.outcmd {
             // Broot was probably launched as br.
@@ -117,9 +165,7 @@ impl ExternalExecution {
             writeln!(&f, "{}", builder.shell_exec_string(&self.exec_pattern))?;
             Ok(CmdResult::Quit)
         } else {
-            Ok(CmdResult::error(
-                "this verb needs broot to be launched as `br`. Try `broot --install` if necessary."
-            ))
+            Ok(CmdResult::error(tr!(error_needs_br)))
         }
     }
 
@@ -131,13 +177,12 @@ impl ExternalExecution {
         con: &AppContext,
     ) -> Result<CmdResult, ProgramError> {
         if builder.sel_info.count_paths() > 1 {
-            return Ok(CmdResult::error(
-                "only verbs returning to broot on end can be executed on a multi-selection"
-            ));
+            return Ok(CmdResult::error(tr!(error_multi_selection_leaves_broot)));
         }
         let launchable = Launchable::program(
             builder.exec_token(&self.exec_pattern),
             self.working_dir_path(&builder),
+            self.sandbox.clone(),
             con,
         )?;
         Ok(CmdResult::from(launchable))
@@ -150,6 +195,8 @@ impl ExternalExecution {
         w: &mut W,
         builder: ExecutionStringBuilder<'_>,
         con: &AppContext,
+        mut denied: Option<&mut Vec<Launchable>>,
+        journal_command: Option<String>,
     ) -> Result<CmdResult, ProgramError> {
         let working_dir_path = self.working_dir_path(&builder);
         match &builder.sel_info {
@@ -158,36 +205,48 @@ impl ExternalExecution {
                 let launchable = Launchable::program(
                     builder.exec_token(&self.exec_pattern),
                     working_dir_path,
+                    self.sandbox.clone(),
                     con,
                 )?;
                 info!("Executing not leaving, launchable {:?}", launchable);
-                if let Err(e) = launchable.execute(Some(w)) {
-                    warn!("launchable failed : {:?}", e);
-                    return Ok(CmdResult::error(e.to_string()));
+                if let Some(res) = activity::execute_or_escalate(w, launchable, con, &mut denied) {
+                    return Ok(res);
                 }
             }
             SelInfo::More(stage) => {
                 // multiselection -> we must execute on all paths
-                let sels = stage.paths().iter()
+                let token_lists: Vec<Vec<String>> = stage.paths().iter()
                     .map(|path| Selection {
                         path,
                         line: 0,
+                        line_range: None,
+                        text_view: None,
                         stype: SelectionType::from(path),
                         is_exe: false,
+                    })
+                    .map(|sel| builder.sel_exec_token(&self.exec_pattern, Some(sel)))
+                    .collect();
+                let batch = Batch {
+                    journal_command,
+                    token_lists,
+                    working_dir: working_dir_path,
+                    sandbox: self.sandbox.clone(),
+                };
+                let conflicts = batch.conflicts();
+                if conflicts.len() > 1 {
+                    // the user chooses what to do for each conflict
+                    // before anything is executed
+                    let state = ConflictState::new(batch, conflicts, con);
+                    return Ok(CmdResult::NewState {
+                        state: Box::new(state),
+                        message: None,
                     });
-                for sel in sels {
-                    let launchable = Launchable::program(
-                        builder.sel_exec_token(&self.exec_pattern, Some(sel)),
-                        working_dir_path.clone(),
-                        con,
-                    )?;
-                    if let Err(e) = launchable.execute(Some(w)) {
-                        warn!("launchable failed : {:?}", e);
-                        return Ok(CmdResult::error(e.to_string()));
-                    }
+                }
+                if let Some(res) = batch.execute(w, con, &mut denied)? {
+                    return Ok(res);
                 }
             }
         }
-        Ok(CmdResult::RefreshState { clear_cache: true })
+        Ok(activity::escalation_result(denied))
     }
 }
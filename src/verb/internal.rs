@@ -53,12 +53,33 @@ macro_rules! Internals {
 // internals:
 //  name: "description" needs_a_path
 Internals! {
+    add_root: "search also in the given directory" false,
     back: "revert to the previous state (mapped to *esc*)" false,
     close_panel_ok: "close the panel, validating the selected path" false,
     close_panel_cancel: "close the panel, not using the selected path" false,
+    clear_roots: "stop searching in the roots added with add_root" false,
+    compare_images: "compare side by side the two staged images" false,
+    containers: "list docker and podman containers" false,
+    exclude: "filter out the matches of the given pattern" false,
     copy_line: "copy selected line (in tree or preview)" true,
+    copy_numbered_lines: "copy selected lines of the preview, with their numbers" true,
     copy_path: "copy path to system clipboard" true,
+    dir_info: "show the counts, newest child and main file types of the selected directory" true,
     filesystems: "list mounted filesystems" false,
+    mount: "mount the selected removable device" false,
+    unmount: "unmount the selected filesystem" false,
+    snapshots: "list the snapshots of the filesystem of the selection" false,
+    versions: "list the versions of the selected file found in backups" false,
+    restore_version: "copy the selected version next to the original file" false,
+    trash: "move the selection to the trash" true,
+    open_trash: "list the items of the trash" false,
+    restore_trashed: "move the selected item of the trash back to its original location" false,
+    purge_trashed: "definitively remove the selected item of the trash" false,
+    report_extensions: "list space and count of files per extension" false,
+    report_top: "list the largest and the oldest files" false,
+    quick_roots: "list standard locations to open sorted by date" false,
+    tips: "list the tips which may be displayed in the status" false,
+    reindex: "update the file index of the current root" false,
     focus: "display the directory (mapped to *enter*)" true,
     help: "display broot's help" false,
     input_clear: "empty the input" false,
@@ -72,6 +93,7 @@ Internals! {
     input_go_to_start: "move the cursor to the start of input" false,
     input_go_word_left: "move the cursor one word to the left" false,
     input_go_word_right: "move the cursor one word to the right" false,
+    input_help: "show a cheat sheet of the input syntax at the cursor position" false,
     input_selection_copy: "copy the selected part of the input into the selection" false,
     input_selection_cut: "cut the selected part of the input into the selection" false,
     input_paste: "paste the clipboard content into the input" false,
@@ -79,9 +101,13 @@ Internals! {
     line_up: "move one line up" false,
     line_down_no_cycle: "move one line down" false,
     line_up_no_cycle: "move one line up" false,
+    goto: "select the visible row of the given number" false,
+    extend_selection_down: "extend the selection of lines of the preview down" false,
+    extend_selection_up: "extend the selection of lines of the preview up" false,
     open_stay: "open file or directory according to OS (stay in broot)" true,
     open_stay_filter: "display the directory, keeping the current pattern" true,
     open_leave: "open file or directory according to OS (quit broot)" true,
+    open_biggest: "open the filesystem's mount point, sorted by size" true,
     mode_input: "enter the input mode" false,
     mode_command: "enter the command mode" false,
     previous_dir: "select the previous directory" false,
@@ -97,6 +123,8 @@ Internals! {
     panel_right: "focus or open panel on right" false,
     panel_left_no_open: "focus panel on left" false,
     panel_right_no_open: "focus panel on right" false,
+    panel_grow: "widen the current panel" false,
+    panel_shrink: "narrow the current panel" false,
     previous_same_depth: "select the previous file at the same depth" false,
     open_preview: "open the preview panel" true,
     close_preview: "close the preview panel" false,
@@ -104,18 +132,25 @@ Internals! {
     preview_image: "preview the selection as image" true,
     preview_text: "preview the selection as text" true,
     preview_binary: "preview the selection as binary" true,
+    preview_tail: "preview the selection as text from its end, following appended lines" true,
+    preview_theme: "switch to the next syntax theme of previews" false,
     print_path: "print path and leaves broot" true,
     print_relative_path: "print relative path and leaves broot" true,
     print_tree: "print tree and leaves broot" true,
+    print_tree_to: "write the displayed tree to a file (txt, json, csv or html)" false,
+    print_fs: "print the listed filesystems as JSON and leaves broot" false,
     start_end_panel: "either open or close an additional panel" true,
     quit: "quit Broot" false,
     refresh: "refresh tree and clear size cache" false,
+    rename_all: "rename the staged paths, or the selection, in your text editor" false,
+    reveal: "show the selection in the system's file manager" true,
     root_up: "move tree root up" true,
     root_down: "move tree root down" true,
     //restore_pattern: "restore a pattern which was just removed" false,
     select_first: "select the first item" false,
     select_last: "select the last item" false,
     select: "select a file by path" true,
+    set_preview_encoding: "decode the previewed file with the given encoding" false,
     set_syntax_theme: "set the theme of code preview" false,
     sort_by_count: "sort by count" false,
     sort_by_date: "sort by date" false,
@@ -123,6 +158,15 @@ Internals! {
     sort_by_type: "sort by type" false,
     sort_by_type_dirs_first: "sort by type, dirs first" false,
     sort_by_type_dirs_last: "sort by type, dirs last" false,
+    sort_by_rating: "sort by star rating" false,
+    sort_by_ext: "sort by extension" false,
+    sort_by_ext_reversed: "sort by extension, in reverse order" false,
+    sort_by_ctime: "sort by last status change, most recent first" false,
+    sort_by_ctime_reversed: "sort by last status change, oldest first" false,
+    sort_by_atime: "sort by last access, most recent first" false,
+    sort_by_atime_reversed: "sort by last access, oldest first" false,
+    sort_by_owner: "sort by owner" false,
+    sort_by_owner_reversed: "sort by owner, in reverse order" false,
     clear_stage: "empty the staging area" false,
     stage: "add selection to staging area" true,
     unstage: "remove selection from staging area" true,
@@ -131,19 +175,68 @@ Internals! {
     toggle_staging_area: "open/close the staging area panel" false,
     stage_all_files: "stage all matching files" true,
     toggle_stage: "add or remove selection to staging area" true,
+    stage_export: "write the staged paths to a file" false,
+    stage_pipe: "write the staged paths on the standard input of a command" false,
+    toggle_mark: "mark or unmark the selection, for the verbs with a {marked} group" true,
+    clear_marks: "unmark all the marked paths" false,
+    tag: "add or remove a tag on the selected file" false,
+    tags: "list the tags given to files" false,
+    bookmark: "bookmark the selected path, with the given name or its file name" false,
+    unbookmark: "remove the bookmark of the selected path" false,
+    bookmarks: "list the bookmarks" false,
+    go_to_bookmark: "focus the bookmark with the given name (or the start of it)" false,
+    watch: "record and list the changes of the selected file or directory" true,
+    toggle_watch: "stop or resume recording the changes of the watched path" false,
+    new_tab: "open a tab in the panel, on the selected directory" true,
+    close_tab: "close the current tab of the panel" false,
+    next_tab: "switch to the next tab of the panel" false,
+    previous_tab: "switch to the previous tab of the panel" false,
+    skip_conflict: "don't copy or move the file of the selected conflict" false,
+    overwrite_conflict: "replace the existing file of the selected conflict" false,
+    rename_conflict: "keep the existing file of the selected conflict as a numbered backup" false,
+    skip_all_conflicts: "don't copy or move the files of all conflicts" false,
+    overwrite_all_conflicts: "replace the existing files of all conflicts" false,
+    rename_all_conflicts: "keep the existing files of all conflicts as numbered backups" false,
+    diff_conflict: "show the differences between the files of the selected conflict" false,
+    rate: "give a star rating to the selected file" false,
+    history_verbs: "list the verbs executed during the session" false,
+    export_verb_log: "write the log of the executed verbs to a file" false,
+    reset_verb_usage: "forget how often the verbs were used, which orders them in help" false,
+    escalate: "retry with the escalation command the operations denied for lack of permission" false,
+    resume_operation: "execute the rest of a batch interrupted when broot was killed" false,
+    rollback_operation: "undo the copies and moves of a batch interrupted when broot was killed" false,
+    discard_operation: "forget a batch interrupted when broot was killed, leaving files as they are" false,
     toggle_counts: "toggle showing number of files in directories" false,
     toggle_dates: "toggle showing last modified dates" false,
     toggle_device_id: "toggle showing device id" false,
+    toggle_dry_run: "toggle displaying external verbs instead of executing them" false,
+    toggle_lint: "toggle showing the result of the lint probes" false,
+    toggle_miller: "toggle showing the parent and selected directories in columns around the tree" false,
+    toggle_scores: "toggle showing the search score of matching lines" false,
+    toggle_row_numbers: "toggle numbering the visible rows" false,
+    toggle_match_lines: "toggle writing the matching line under the hits of content searches" false,
     toggle_files: "toggle showing files (or just folders)" false,
+    toggle_flat: "toggle listing the files of all depths without the tree of directories" false,
     toggle_git_ignore: "toggle use of .gitignore" false,
     toggle_git_file_info: "toggle display of git file information" false,
     toggle_git_status: "toggle showing only files relevant for git status" false,
     toggle_root_fs: "toggle showing filesystem info on top" false,
     toggle_hidden: "toggle showing hidden files" false,
+    toggle_only_disks: "toggle showing only the filesystems on disks" false,
+    toggle_volume_group: "collapse or expand the group of volumes of the selected filesystem" false,
+    toggle_fs_totals: "toggle showing the totals of the listed filesystems" false,
     toggle_perm: "toggle showing file permissions" false,
+    toggle_perm_mode: "toggle showing the mode (or attributes) in the permission column" false,
+    toggle_perm_owner: "toggle showing the owner in the permission column" false,
+    toggle_perm_group: "toggle showing the group in the permission column" false,
+    toggle_perm_acl: "toggle showing the ACL marker in the permission column" false,
     toggle_sizes: "toggle showing sizes" false,
+    toggle_exact_sizes: "toggle writing sizes as exact byte counts" false,
+    toggle_size_units: "switch between SI (MB) and binary (MiB) units of sizes" false,
     toggle_trim_root: "toggle removing nodes at first level too" false,
     toggle_second_tree: "toggle display of a second tree panel" true,
+    toggle_tail_follow: "pause or resume the following of the previewed file in tail mode" false,
+    toggle_zen: "toggle display of just the panels, without input, status or scrollbar" false,
     total_search: "search again but on all children" false,
     up_tree: "focus the parent of the current root" true,
 }
@@ -156,8 +249,19 @@ impl Internal {
             Internal::line_down => r"line_down (?P<count>\d*)?",
             Internal::line_up => r"line_up (?P<count>\d*)?",
             Internal::line_down_no_cycle => r"line_down_no_cycle (?P<count>\d*)?",
+            Internal::goto => r"goto (?P<row>\d*)?",
             Internal::line_up_no_cycle => r"line_up_no_cycle (?P<count>\d*)?",
             Internal::set_syntax_theme => r"set_syntax_theme {theme:theme}",
+            Internal::set_preview_encoding => r"set_preview_encoding {encoding}",
+            Internal::tag => r"tag {action} {tag}",
+            Internal::bookmark => r"bookmark (?P<name>\S*)?",
+            Internal::go_to_bookmark => r"go_to_bookmark {name}",
+            Internal::rate => r"rate {rating}",
+            Internal::export_verb_log => r"export_verb_log {path}",
+            Internal::stage_export => r"stage_export {path}",
+            Internal::stage_pipe => r"stage_pipe {command}",
+            Internal::print_tree => r"print_tree (?P<format>\w*)?",
+            Internal::print_tree_to => r"print_tree_to {path}",
             _ => self.name(),
         }
     }
@@ -177,4 +281,37 @@ impl Internal {
             _ => self.need_path(),
         }
     }
+    /// tell whether the internal writes files, and so is disabled
+    /// in read-only mode
+    pub fn is_mutating(self) -> bool {
+        matches!(
+            self,
+            Internal::bookmark
+                | Internal::escalate
+                | Internal::discard_operation
+                | Internal::overwrite_all_conflicts
+                | Internal::overwrite_conflict
+                | Internal::export_verb_log
+                | Internal::mount
+                | Internal::print_tree_to
+                | Internal::purge_trashed
+                | Internal::rate
+                | Internal::rename_all
+                | Internal::rename_all_conflicts
+                | Internal::rename_conflict
+                | Internal::reset_verb_usage
+                | Internal::restore_trashed
+                | Internal::restore_version
+                | Internal::skip_all_conflicts
+                | Internal::skip_conflict
+                | Internal::stage_export
+                | Internal::stage_pipe
+                | Internal::resume_operation
+                | Internal::rollback_operation
+                | Internal::tag
+                | Internal::trash
+                | Internal::unbookmark
+                | Internal::unmount
+        )
+    }
 }
@@ -10,6 +10,7 @@ use {
         path::{self, PathAnchor},
         preview::PreviewState,
         task_sync::Dam,
+        tr,
         tree::TreeOptions,
     },
     std::path::{Path, PathBuf},
@@ -150,7 +151,7 @@ pub fn get_status_markdown(
         invocation.args.as_ref(),
         app_state,
     );
-    format!("Hit *enter* to focus `{}`", path.to_string_lossy())
+    tr!(verb_focus, path = path.to_string_lossy())
 }
 
 /// general implementation for verbs based on the :focus internal with optionally
@@ -9,12 +9,16 @@ mod internal_execution;
 pub mod internal_focus;
 pub mod internal_select;
 mod invocation_parser;
+mod overwrite_policy;
+mod sandbox;
 mod sequence_execution;
 mod verb;
 mod verb_description;
 mod verb_execution;
 mod verb_invocation;
 mod verb_store;
+mod verb_target;
+mod verb_usage;
 
 pub use {
     arg_def::*,
@@ -25,6 +29,8 @@ pub use {
     internal::Internal,
     internal_execution::InternalExecution,
     invocation_parser::InvocationParser,
+    overwrite_policy::OverwritePolicy,
+    sandbox::Sandbox,
     once_cell::sync::Lazy,
     sequence_execution::SequenceExecution,
     verb::Verb,
@@ -32,6 +38,8 @@ pub use {
     verb_execution::VerbExecution,
     verb_invocation::*,
     verb_store::{PrefixSearchResult, VerbStore},
+    verb_target::VerbTarget,
+    verb_usage::VerbUsage,
 };
 use {
     lazy_regex::*,
@@ -47,6 +55,9 @@ pub fn str_has_selection_group(s: &str) -> bool {
             "{file}" | "{file-name}" | "{parent}" | "{directory}",
         ))
 }
+pub fn str_has_marked_group(s: &str) -> bool {
+    GROUP.find_iter(s).any(|group| group.as_str() == "{marked}")
+}
 pub fn str_has_other_panel_group(s: &str) -> bool {
     for group in GROUP.find_iter(s) {
         if group.as_str().starts_with("{other-panel-") {
@@ -0,0 +1,32 @@
+use {
+    serde::Deserialize,
+};
+
+/// what the built-in copy and move verbs do when
+/// the destination already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OverwritePolicy {
+    /// ask, for every conflicting file, whether to overwrite it
+    Ask,
+    /// replace the existing file (the historical behavior)
+    #[default]
+    Overwrite,
+    /// keep the existing file, don't copy or move
+    Skip,
+    /// keep the existing file under a numbered backup name
+    Rename,
+}
+
+impl OverwritePolicy {
+    /// the options to give to `cp` and `mv` to apply the policy
+    /// (`rename` needs the GNU versions of those tools)
+    pub fn unix_options(self) -> &'static str {
+        match self {
+            Self::Ask => "-i ",
+            Self::Overwrite => "",
+            Self::Skip => "-n ",
+            Self::Rename => "--backup=numbered ",
+        }
+    }
+}
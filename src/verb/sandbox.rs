@@ -0,0 +1,86 @@
+use {
+    std::{
+        io,
+        process::{Child, Command, ExitStatus},
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+/// how often a process with a timeout is checked
+const TIMEOUT_POLL_PERIOD: Duration = Duration::from_millis(50);
+
+/// the constraints applied to the process of an external verb,
+/// so that heavy commands don't degrade the interactive session
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sandbox {
+    /// environment variables set for the process
+    pub env: Vec<(String, String)>,
+    /// niceness, from -20 (favorable) to 19 (least favorable)
+    pub nice: Option<i32>,
+    /// io priority in the best-effort class, from 0 (highest) to 7
+    /// (lowest). Only applied on linux
+    pub ionice: Option<u8>,
+    /// the duration after which the process is killed
+    pub timeout: Option<Duration>,
+}
+
+impl Sandbox {
+    /// set the environment of the command and prepare the application
+    /// of the priorities in the child process
+    pub fn apply(&self, command: &mut Command) {
+        command.envs(self.env.iter().map(|(k, v)| (k, v)));
+        #[cfg(unix)]
+        if self.nice.is_some() || self.ionice.is_some() {
+            let nice = self.nice;
+            let ionice = self.ionice;
+            // safety: only async-signal-safe functions are called in the closure
+            unsafe {
+                command.pre_exec(move || {
+                    if let Some(nice) = nice {
+                        if libc::setpriority(libc::PRIO_PROCESS as _, 0, nice) != 0 {
+                            return Err(io::Error::last_os_error());
+                        }
+                    }
+                    #[cfg(target_os = "linux")]
+                    if let Some(level) = ionice {
+                        const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+                        const IOPRIO_CLASS_BE: libc::c_long = 2;
+                        let ioprio = (IOPRIO_CLASS_BE << 13) | level as libc::c_long;
+                        if libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio) != 0 {
+                            return Err(io::Error::last_os_error());
+                        }
+                    }
+                    #[cfg(not(target_os = "linux"))]
+                    let _ = ionice;
+                    Ok(())
+                });
+            }
+        }
+    }
+    /// wait for the end of the child process, killing it if it
+    /// goes past the timeout
+    pub fn wait(&self, mut child: Child) -> io::Result<ExitStatus> {
+        let timeout = match self.timeout {
+            Some(timeout) => timeout,
+            None => {
+                return child.wait();
+            }
+        };
+        let start = Instant::now();
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok(status);
+            }
+            if start.elapsed() > timeout {
+                child.kill()?;
+                child.wait()?;
+                return Err(io::ErrorKind::TimedOut.into());
+            }
+            thread::sleep(TIMEOUT_POLL_PERIOD);
+        }
+    }
+}
@@ -5,6 +5,7 @@ use {
         errors::ConfError,
         keys::KEY_FORMAT,
         path::PathAnchor,
+        tr,
     },
     crokey::crossterm::event::KeyEvent,
     std::{
@@ -32,8 +33,8 @@ pub struct Verb {
     /// names (like "cd", "focus", "focus_tab", "c") by which
     /// a verb can be called.
     /// Can be empty if the verb is only called with a key shortcut.
-    /// Right now there's no way for it to contain more than 2 elements
-    /// but this may change.
+    /// The aliases defined in configuration come after the name
+    /// and the shortcut.
     pub names: Vec<String>,
 
     /// key shortcuts
@@ -74,6 +75,18 @@ pub struct Verb {
     pub show_in_doc: bool,
 
     pub panels: Vec<PanelStateType>,
+
+    /// types of the states in which the keys trigger the verb.
+    /// If empty, the keys work in all states where the verb can be called
+    pub key_contexts: Vec<PanelStateType>,
+
+    /// where the result of an internal based verb must appear,
+    /// overriding the bang
+    pub target: Option<VerbTarget>,
+
+    /// whether the verb changes files, and so is disabled in
+    /// read-only mode
+    pub mutating: bool,
 }
 
 impl PartialEq for Verb {
@@ -94,6 +107,10 @@ impl Verb {
         if let Some(ref invocation_parser) = invocation_parser {
             names.push(invocation_parser.name().to_string());
         }
+        let mutating = match &execution {
+            VerbExecution::Internal(ie) => ie.internal.is_mutating(),
+            _ => false,
+        };
         let (
             needs_selection,
             needs_another_panel,
@@ -125,6 +142,9 @@ impl Verb {
             auto_exec: true,
             show_in_doc: true,
             panels: Vec::new(),
+            key_contexts: Vec::new(),
+            target: None,
+            mutating,
         })
     }
     fn update_key_desc(&mut self) {
@@ -134,6 +154,14 @@ impl Verb {
             .map(|&k| KEY_FORMAT.to_string(k))
             .collect::<Vec<String>>() // no way to join an iterator today ?
             .join(", ");
+        if !self.keys.is_empty() && !self.key_contexts.is_empty() {
+            let contexts = self.key_contexts
+                .iter()
+                .map(|c| c.name())
+                .collect::<Vec<&str>>()
+                .join(", ");
+            self.keys_desc = format!("{} (in {})", self.keys_desc, contexts);
+        }
     }
     pub fn with_key(mut self, key: KeyEvent) -> Self {
         self.keys.push(key);
@@ -146,6 +174,14 @@ impl Verb {
         }
         self.update_key_desc();
     }
+    pub fn set_key_contexts(&mut self, key_contexts: Vec<PanelStateType>) {
+        self.key_contexts = key_contexts;
+        self.update_key_desc();
+    }
+    pub fn with_key_contexts(mut self, key_contexts: Vec<PanelStateType>) -> Self {
+        self.set_key_contexts(key_contexts);
+        self
+    }
     pub fn no_doc(mut self) -> Self {
         self.show_in_doc = false;
         self
@@ -170,6 +206,10 @@ impl Verb {
         self.auto_exec = b;
         self
     }
+    pub fn mutating(mut self) -> Self {
+        self.mutating = true;
+        self
+    }
 
     pub fn has_name(&self, searched_name: &str) -> bool {
         self.names.iter().any(|name| name == searched_name)
@@ -193,6 +233,8 @@ impl Verb {
                         let sel = Selection {
                             path,
                             line: 0,
+                            line_range: None,
+                            text_view: None,
                             stype: SelectionType::from(path),
                             is_exe: false,
                         };
@@ -259,14 +301,14 @@ impl Verb {
             let exec_desc = builder().shell_exec_string(
                 &ExecPattern::from_string(&seq_ex.sequence.raw)
             );
-            format!("Hit *enter* to **{}**: `{}`", name, &exec_desc)
+            tr!(verb_hit_enter, verb = name, description = format!("`{}`", &exec_desc))
         } else if let VerbExecution::External(external_exec) = &self.execution {
             let exec_desc = builder().shell_exec_string(&external_exec.exec_pattern);
-            format!("Hit *enter* to **{}**: `{}`", name, &exec_desc)
+            tr!(verb_hit_enter, verb = name, description = format!("`{}`", &exec_desc))
         } else if self.description.code {
-            format!("Hit *enter* to **{}**: `{}`", name, &self.description.content)
+            tr!(verb_hit_enter, verb = name, description = format!("`{}`", &self.description.content))
         } else {
-            format!("Hit *enter* to **{}**: {}", name, &self.description.content)
+            tr!(verb_hit_enter, verb = name, description = &self.description.content)
         }
     }
 
@@ -302,4 +344,10 @@ impl Verb {
     pub fn can_be_called_in_panel(&self, panel_state_type: PanelStateType) -> bool {
         self.panels.is_empty() || self.panels.contains(&panel_state_type)
     }
+
+    /// tell whether the keys of the verb trigger it in a state of the given type
+    pub fn keys_apply_in_panel(&self, panel_state_type: PanelStateType) -> bool {
+        self.can_be_called_in_panel(panel_state_type)
+            && (self.key_contexts.is_empty() || self.key_contexts.contains(&panel_state_type))
+    }
 }
@@ -17,8 +17,8 @@ use {
 /// - the user defined ones
 /// A user defined verb can replace a built-in.
 /// When the user types some keys, we select a verb
-/// - if the input exactly matches a shortcut or the name
-/// - if only one verb name starts with the input
+/// - if the input exactly matches a shortcut, an alias or the name
+/// - if only one verb has a name starting with the input
 pub struct VerbStore {
     pub verbs: Vec<Verb>,
 }
@@ -37,7 +37,9 @@ impl VerbStore {
             let verb = vc.make_verb(&verbs)?;
             verbs.push(verb);
         }
-        verbs.append(&mut builtin_verbs()); // at the end so that we can override them
+        let overwrite_policy = conf.overwrite_policy.unwrap_or_default();
+        let rm_to_trash = conf.rm_to_trash.unwrap_or(false);
+        verbs.append(&mut builtin_verbs(overwrite_policy, rm_to_trash)); // at the end so that we can override them
         Ok(Self { verbs })
     }
 
@@ -58,6 +60,21 @@ impl VerbStore {
         self.search(prefix, None, None, None)
     }
 
+    /// Return the verbs having a name starting with the prefix, with
+    /// the first matching name of each one
+    pub fn matching_verbs_sel_info<'v>(
+        &'v self,
+        prefix: &str,
+        sel_info: SelInfo<'_>,
+    ) -> Vec<(&'v str, &'v Verb)> {
+        let stype = sel_info.common_stype();
+        let count = sel_info.count_paths();
+        self.matching_verbs(prefix, stype, Some(count), sel_info.extension())
+            .into_iter()
+            .map(|(names, verb)| (names[0], verb))
+            .collect()
+    }
+
     /// Return either the only match, or None if there's not
     /// exactly one match
     pub fn search_sel_info_unique <'v>(
@@ -78,45 +95,82 @@ impl VerbStore {
         sel_count: Option<usize>,
         extension: Option<&str>,
     ) -> PrefixSearchResult<'v, &Verb> {
-        let mut found_index = 0;
-        let mut nb_found = 0;
-        let mut completions: Vec<&str> = Vec::new();
-        for (index, verb) in self.verbs.iter().enumerate() {
-            if let Some(stype) = stype {
-                if !stype.respects(verb.selection_condition) {
-                    continue;
+        for verb in &self.verbs {
+            if let Some(name) = verb.names.iter().find(|name| *name == prefix) {
+                if self.is_applicable(verb, stype, sel_count, extension) {
+                    return PrefixSearchResult::Match(name, verb);
                 }
             }
-            if let Some(count) = sel_count {
-                if count > 1 && verb.is_sequence() {
-                    continue;
-                }
-                if count == 0 && verb.needs_selection {
-                    continue;
-                }
+        }
+        let mut matching_verbs = self.matching_verbs(prefix, stype, sel_count, extension);
+        match matching_verbs.len() {
+            0 => PrefixSearchResult::NoMatch,
+            1 => {
+                // several names of the same verb (eg its name and its
+                // aliases) may start with the prefix
+                let (names, verb) = matching_verbs.remove(0);
+                PrefixSearchResult::Match(names[0], verb)
             }
-            if !verb.file_extensions.is_empty() {
-                if !extension.map_or(false, |ext| verb.file_extensions.iter().any(|ve| ve == ext)) {
-                    continue;
+            _ => PrefixSearchResult::Matches(
+                matching_verbs
+                    .into_iter()
+                    .flat_map(|(names, _)| names)
+                    .collect()
+            ),
+        }
+    }
+
+    /// Return the verbs applicable to the selection and having names
+    /// starting with the prefix, with those names
+    fn matching_verbs<'v>(
+        &'v self,
+        prefix: &str,
+        stype: Option<SelectionType>,
+        sel_count: Option<usize>,
+        extension: Option<&str>,
+    ) -> Vec<(Vec<&'v str>, &'v Verb)> {
+        self.verbs.iter()
+            .filter(|verb| self.is_applicable(verb, stype, sel_count, extension))
+            .filter_map(|verb| {
+                let names: Vec<&str> = verb.names.iter()
+                    .filter(|name| name.starts_with(prefix))
+                    .map(|name| name.as_str())
+                    .collect();
+                if names.is_empty() {
+                    None
+                } else {
+                    Some((names, verb))
                 }
+            })
+            .collect()
+    }
+
+    fn is_applicable(
+        &self,
+        verb: &Verb,
+        stype: Option<SelectionType>,
+        sel_count: Option<usize>,
+        extension: Option<&str>,
+    ) -> bool {
+        if let Some(stype) = stype {
+            if !stype.respects(verb.selection_condition) {
+                return false;
             }
-            for name in &verb.names {
-                if name.starts_with(prefix) {
-                    if name == prefix {
-                        return PrefixSearchResult::Match(name, verb);
-                    }
-                    found_index = index;
-                    nb_found += 1;
-                    completions.push(name);
-                    continue;
-                }
+        }
+        if let Some(count) = sel_count {
+            if count > 1 && verb.is_sequence() {
+                return false;
+            }
+            if count == 0 && verb.needs_selection {
+                return false;
             }
         }
-        match nb_found {
-            0 => PrefixSearchResult::NoMatch,
-            1 => PrefixSearchResult::Match(completions[0], &self.verbs[found_index]),
-            _ => PrefixSearchResult::Matches(completions),
+        if !verb.file_extensions.is_empty() {
+            if !extension.map_or(false, |ext| verb.file_extensions.iter().any(|ve| ve == ext)) {
+                return false;
+            }
         }
+        true
     }
 
     pub fn key_desc_of_internal_stype(
@@ -125,7 +179,10 @@ impl VerbStore {
         stype: SelectionType,
     ) -> Option<String> {
         for verb in &self.verbs {
-            if verb.get_internal() == Some(internal) && stype.respects(verb.selection_condition) {
+            if verb.get_internal() == Some(internal)
+                && stype.respects(verb.selection_condition)
+                && verb.keys_apply_in_panel(PanelStateType::Tree)
+            {
                 return verb.keys.get(0).map(|&k| KEY_FORMAT.to_string(k));
             }
         }
@@ -137,7 +194,7 @@ impl VerbStore {
         internal: Internal,
     ) -> Option<String> {
         for verb in &self.verbs {
-            if verb.get_internal() == Some(internal) {
+            if verb.get_internal() == Some(internal) && verb.keys_apply_in_panel(PanelStateType::Tree) {
                 return verb.keys.get(0).map(|&k| KEY_FORMAT.to_string(k));
             }
         }
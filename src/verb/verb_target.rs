@@ -0,0 +1,30 @@
+use {
+    crate::app::HDir,
+    serde::Deserialize,
+};
+
+/// where the result of a verb must appear, when it's not
+/// decided by the bang of the invocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerbTarget {
+    /// the current panel, as without bang
+    Replace,
+    /// a new panel at the left of the current one
+    NewPanelLeft,
+    /// a new panel at the right of the current one, as with a bang
+    NewPanelRight,
+    /// the preview panel, the verb being executed on the preview
+    Preview,
+}
+
+impl VerbTarget {
+    /// the side of the new panel, if any
+    pub fn new_panel_direction(self) -> Option<HDir> {
+        match self {
+            Self::NewPanelLeft => Some(HDir::Left),
+            Self::NewPanelRight => Some(HDir::Right),
+            _ => None,
+        }
+    }
+}
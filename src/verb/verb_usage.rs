@@ -0,0 +1,142 @@
+use {
+    crate::conf,
+    chrono::{DateTime, Duration, TimeZone, Utc},
+    std::{
+        collections::BTreeMap,
+        fs,
+        io,
+        path::PathBuf,
+    },
+};
+
+/// how many times a verb was used, and when for the last time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Usage {
+    count: u32,
+    last: DateTime<Utc>,
+}
+
+impl Usage {
+    /// the count, weighted by the recency of the last use
+    fn frecency(self, now: DateTime<Utc>) -> f64 {
+        let age = now - self.last;
+        let weight = if age < Duration::days(1) {
+            4.0
+        } else if age < Duration::days(7) {
+            2.0
+        } else if age < Duration::days(30) {
+            1.0
+        } else {
+            0.5
+        };
+        self.count as f64 * weight
+    }
+}
+
+/// the usage of the verbs, by name, stored in the verb usage file
+/// with one `count<TAB>timestamp<TAB>name` line per used verb, and
+/// used to list the most frequently and recently used verbs first
+#[derive(Debug, Clone, Default)]
+pub struct VerbUsage {
+    usages: BTreeMap<String, Usage>,
+}
+
+impl VerbUsage {
+    /// the file where verb usage is stored
+    pub fn file() -> PathBuf {
+        conf::app_dirs().data_dir().join("verb-usage")
+    }
+    /// read the verb usage file, returning an empty usage when
+    /// there's none or when it can't be read
+    pub fn load() -> Self {
+        fs::read_to_string(Self::file())
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+    /// read the content of a verb usage file, ignoring invalid lines
+    pub fn parse(content: &str) -> Self {
+        let mut usages = BTreeMap::new();
+        for line in content.lines() {
+            let mut tokens = line.splitn(3, '\t');
+            let count = tokens.next().and_then(|t| t.parse().ok());
+            let last = tokens.next()
+                .and_then(|t| t.parse().ok())
+                .and_then(|secs| Utc.timestamp_opt(secs, 0).single());
+            if let (Some(count), Some(last), Some(name)) = (count, last, tokens.next()) {
+                usages.insert(name.to_string(), Usage { count, last });
+            }
+        }
+        Self { usages }
+    }
+    pub fn save(&self) -> io::Result<()> {
+        let file = Self::file();
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, self.to_string())
+    }
+    /// count a use of the verb
+    pub fn record(&mut self, name: &str, now: DateTime<Utc>) {
+        let usage = self.usages
+            .entry(name.to_string())
+            .or_insert(Usage { count: 0, last: now });
+        usage.count += 1;
+        usage.last = now;
+    }
+    pub fn is_empty(&self) -> bool {
+        self.usages.is_empty()
+    }
+    pub fn clear(&mut self) {
+        self.usages.clear();
+    }
+    /// the score of the verb, 0 if it was never used
+    pub fn frecency(&self, name: &str, now: DateTime<Utc>) -> f64 {
+        self.usages.get(name).map_or(0.0, |usage| usage.frecency(now))
+    }
+    /// sort the items, the ones of the most used verbs first, the
+    /// order of the other ones being kept
+    pub fn sort<T, F>(&self, items: &mut [T], name: F)
+    where
+        F: Fn(&T) -> &str,
+    {
+        if self.is_empty() {
+            return;
+        }
+        let now = Utc::now();
+        items.sort_by(|a, b| {
+            let (a, b) = (self.frecency(name(a), now), self.frecency(name(b), now));
+            b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+}
+
+impl std::fmt::Display for VerbUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, usage) in &self.usages {
+            writeln!(f, "{}\t{}\t{}", usage.count, usage.last.timestamp(), name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod verb_usage_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_verb_usage_order() {
+        let now = Utc::now();
+        let mut usage = VerbUsage::default();
+        for _ in 0..3 {
+            usage.record("edit", now - Duration::days(60));
+        }
+        usage.record("copy", now);
+        usage.record("rm", now - Duration::days(3));
+        let usage = VerbUsage::parse(&usage.to_string());
+        assert_eq!(usage.frecency("edit", now), 1.5);
+        let mut names = vec!["back", "rm", "edit", "help", "copy"];
+        usage.sort(&mut names, |name| name);
+        assert_eq!(names, vec!["copy", "rm", "edit", "back", "help"]);
+    }
+}
@@ -0,0 +1,154 @@
+use {
+    chrono::{DateTime, Local},
+    serde::Deserialize,
+    std::{
+        fs,
+        io,
+        path::{Path, PathBuf},
+    },
+};
+
+/// a place where a backup tool keeps versions of files, defined
+/// in conf, for example
+///
+/// ```hjson
+/// {
+///     name: rsnapshot
+///     path: "/backup/*/localhost{file}"
+/// }
+/// ```
+///
+/// `*` stands for the name of a version and `{file}` for the
+/// absolute path of the file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackupLocation {
+    pub name: Option<String>,
+    pub path: String,
+}
+
+/// a version of a file, found in a backup location
+#[derive(Debug, Clone)]
+pub struct FileVersion {
+    /// the name of the backup location
+    pub location: String,
+    /// the name of the version in the location (eg "daily.0")
+    pub name: String,
+    pub path: PathBuf,
+    pub time: Option<DateTime<Local>>,
+    pub size: u64,
+}
+
+impl BackupLocation {
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.path)
+    }
+    /// list the versions of the file found in this location
+    pub fn versions_of(&self, file: &Path) -> Vec<FileVersion> {
+        let pattern = self.path.replace("{file}", &file.to_string_lossy());
+        let (prefix, suffix) = match pattern.split_once('*') {
+            Some((prefix, suffix)) if !suffix.contains('*') => (prefix, suffix),
+            _ => {
+                warn!("backup location path must contain exactly one '*': {:?}", &self.path);
+                return Vec::new();
+            }
+        };
+        // the star may be preceded by the start of the version name
+        let (dir, name_start) = match prefix.rfind('/') {
+            Some(idx) => (&prefix[..=idx], &prefix[idx + 1..]),
+            None => ("./", prefix),
+        };
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("can't read backup location {:?}: {}", dir, e);
+                return Vec::new();
+            }
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let entry_name = entry.file_name().to_string_lossy().to_string();
+                let name = entry_name.strip_prefix(name_start)?.to_string();
+                let path = PathBuf::from(format!("{dir}{entry_name}{suffix}"));
+                let metadata = fs::metadata(&path).ok().filter(|md| md.is_file())?;
+                Some(FileVersion {
+                    location: self.name().to_string(),
+                    name,
+                    path,
+                    time: metadata.modified().ok().map(DateTime::<Local>::from),
+                    size: metadata.len(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// list the versions of the file found in all the locations,
+/// most recent first
+pub fn versions_of(file: &Path, locations: &[BackupLocation]) -> Vec<FileVersion> {
+    let mut versions: Vec<FileVersion> = locations
+        .iter()
+        .flat_map(|location| location.versions_of(file))
+        .collect();
+    versions.sort_by_key(|v| std::cmp::Reverse(v.time));
+    versions
+}
+
+impl FileVersion {
+    /// the path, next to the original file, at which this
+    /// version is restored: "notes.txt" version "daily.0"
+    /// is restored as "notes.daily.0.txt"
+    pub fn restore_path(&self, original: &Path) -> PathBuf {
+        let version: String = self.name
+            .chars()
+            .map(|c| if c == '/' || c == ':' || c == '\\' { '-' } else { c })
+            .collect();
+        let stem = original.file_stem().map_or_else(
+            || "restored".to_string(),
+            |s| s.to_string_lossy().to_string(),
+        );
+        let name = match original.extension() {
+            Some(ext) => format!("{}.{}.{}", stem, version, ext.to_string_lossy()),
+            None => format!("{}.{}", stem, version),
+        };
+        original.with_file_name(name)
+    }
+    /// copy this version next to the original file, never
+    /// overwriting an existing file
+    pub fn restore(&self, original: &Path) -> io::Result<PathBuf> {
+        let target = self.restore_path(original);
+        if target.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", target.to_string_lossy()),
+            ));
+        }
+        fs::copy(&self.path, &target)?;
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod backup_location_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_restore_path() {
+        let version = FileVersion {
+            location: "restic".to_string(),
+            name: "2023-01-04T10:00:00+01:00".to_string(),
+            path: PathBuf::from("/mnt/restic/snapshots/2023-01-04T10:00:00+01:00/home/dys/notes.txt"),
+            time: None,
+            size: 0,
+        };
+        assert_eq!(
+            version.restore_path(Path::new("/home/dys/notes.txt")),
+            PathBuf::from("/home/dys/notes.2023-01-04T10-00-00+01-00.txt"),
+        );
+        assert_eq!(
+            version.restore_path(Path::new("/home/dys/Makefile")),
+            PathBuf::from("/home/dys/Makefile.2023-01-04T10-00-00+01-00"),
+        );
+    }
+}
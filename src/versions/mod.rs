@@ -0,0 +1,10 @@
+//! Versions of files kept by backup tools (rsnapshot, Time Machine,
+//! mounted borg or restic repositories, etc.)
+
+mod backup_location;
+mod versions_state;
+
+pub use {
+    backup_location::*,
+    versions_state::VersionsState,
+};
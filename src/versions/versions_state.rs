@@ -0,0 +1,221 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        browser::BrowserState,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        path,
+        pattern::*,
+        task_sync::Dam,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::path::{Path, PathBuf},
+    termimad::minimad::Alignment,
+};
+
+/// an application state listing the versions of a file found
+/// in the backup locations, most recent first
+pub struct VersionsState {
+    file: PathBuf,
+    list: FilteredList<FileVersion>,
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+impl VersionsState {
+    pub fn new(
+        file: &Path,
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> VersionsState {
+        VersionsState {
+            file: file.to_path_buf(),
+            list: FilteredList::new(versions_of(file, &con.backup_locations), matches),
+            tree_options,
+            mode: initial_mode(con),
+        }
+    }
+}
+
+fn matches(version: &FileVersion, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&version.name).is_some()
+        || pattern.score_of_string(&version.location).is_some()
+}
+
+impl PanelState for VersionsState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Versions
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// the path of the selected version
+    fn selected_path(&self) -> Option<&Path> {
+        self.list.selected()
+            .map(|version| version.path.as_path())
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        self.selected_path().map(|path| Selection {
+            path,
+            line: 0,
+            line_range: None,
+            text_view: None,
+            stype: SelectionType::from(path),
+            is_exe: false,
+        })
+    }
+
+    fn refresh(&mut self, _screen: Screen, con: &AppContext) -> Command {
+        let versions = versions_of(&self.file, &con.backup_locations);
+        self.list.set_items(versions, |a, b| a.path == b.path);
+        Command::empty()
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let list = &self.list;
+        let date_time_format = self.tree_options.date_time_format;
+        let w_date = date_time_format.date_time_width();
+        let size_format = self.tree_options.size_format;
+        let max_size = list.visible().map(|v| v.size).max().unwrap_or(0);
+        let w_size = size_format.width(max_size).max("size".len());
+        let w_location = list.visible()
+            .map(|v| v.location.chars().count())
+            .max().unwrap_or(0)
+            .max("location".len());
+        let columns = [
+            ListColumn::new("date", w_date, Alignment::Right),
+            ListColumn::new("size", w_size, Alignment::Right),
+            ListColumn::new("location", w_location, Alignment::Left),
+        ];
+        let styles = &disc.panel_skin.styles;
+        list.queue_table_head(w, disc, &columns, |cw| {
+            cw.queue_g_string(&styles.default, format!("versions of {}", self.file.to_string_lossy()))?;
+            Ok(())
+        })?;
+        self.list.queue_table_rows(w, disc, |cw, version, row| {
+            let date = version.time
+                .map(|time| date_time_format.format_date_time(time))
+                .unwrap_or_default();
+            row.queue_cell(cw, &date, &row.style(&styles.dates), w_date, Alignment::Right)?;
+            let size = size_format.format(version.size);
+            row.queue_cell(cw, &size, row.txt_style, w_size, Alignment::Right)?;
+            row.queue_matched_cell(cw, &version.location, row.txt_style, w_location, Alignment::Left)?;
+            row.queue_matched(cw, &version.name, row.txt_style)
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        use Internal::*;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                let in_new_panel = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                // we browse the directory of the version
+                match self.list.selected() {
+                    Some(version) => {
+                        let dam = Dam::unlimited();
+                        CmdResult::from_optional_state(
+                            BrowserState::new(
+                                path::closest_dir(&version.path),
+                                self.tree_options.without_pattern(),
+                                screen,
+                                con,
+                                &dam,
+                            ),
+                            None,
+                            in_new_panel,
+                        )
+                    }
+                    None => CmdResult::error(tr!(error_no_selected_version)),
+                }
+            }
+            Internal::restore_version => {
+                match self.list.selected() {
+                    Some(version) => match version.restore(&self.file) {
+                        Ok(target) => CmdResult::message(format!(
+                            "version restored as `{}`",
+                            target.to_string_lossy(),
+                        )),
+                        Err(e) => CmdResult::error(tr!(error_restore_failed, error = e)),
+                    },
+                    None => CmdResult::error(tr!(error_no_selected_version)),
+                }
+            }
+            open_leave => CmdResult::PopStateAndReapply,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}
@@ -0,0 +1,237 @@
+use {
+    notify::{
+        event::{AccessKind, AccessMode, EventKind},
+        Event,
+        RecommendedWatcher,
+        RecursiveMode,
+        Watcher,
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        io,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+            Mutex,
+        },
+    },
+};
+
+/// A watcher of directories, notified by the system (inotify on
+/// linux, FSEvents on Mac) when their entries are created, removed
+/// or modified.
+///
+/// There's only one, shared by all trees, as the system limits
+/// the number of watchers. Each tree watches its directories
+/// through a `FsWatch`.
+///
+/// It's non blocking: the notifications are received in the thread
+/// of the watcher and only recorded until asked with `has_changes`.
+pub struct FsWatcher {
+    watcher: Mutex<RecommendedWatcher>,
+    /// the change flags of the watches interested in each watched
+    /// directory
+    dirs: Arc<Mutex<HashMap<PathBuf, Vec<Arc<AtomicBool>>>>>,
+}
+
+impl FsWatcher {
+    pub fn new() -> io::Result<Self> {
+        let dirs: Arc<Mutex<HashMap<PathBuf, Vec<Arc<AtomicBool>>>>> = Arc::default();
+        let notified_dirs = Arc::clone(&dirs);
+        let watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            match res {
+                Ok(event) => {
+                    if is_change(&event.kind) {
+                        let dirs = notified_dirs.lock().unwrap();
+                        for path in &event.paths {
+                            // the path is the one of the watched directory
+                            // or of one of its entries
+                            let flags = dirs.get(path)
+                                .into_iter()
+                                .chain(path.parent().and_then(|parent| dirs.get(parent)));
+                            for flag in flags.flatten() {
+                                flag.store(true, Ordering::Relaxed);
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    debug!("watch error: {}", e);
+                }
+            }
+        })
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        Ok(Self {
+            watcher: Mutex::new(watcher),
+            dirs,
+        })
+    }
+    /// register the flag as interested in the directory, watching it
+    /// if it's not already watched. Return false when it can't be watched.
+    fn add(&self, dir: &Path, flag: &Arc<AtomicBool>) -> bool {
+        // the lock on dirs isn't kept while calling the watcher, which
+        // may wait for its thread, itself waiting for the lock
+        let first = {
+            let mut dirs = self.dirs.lock().unwrap();
+            let flags = dirs.entry(dir.to_path_buf()).or_default();
+            flags.push(Arc::clone(flag));
+            flags.len() == 1
+        };
+        if first {
+            if let Err(e) = self.watcher.lock().unwrap().watch(dir, RecursiveMode::NonRecursive) {
+                // most often, the limit of watches is reached
+                debug!("can't watch {:?}: {}", dir, e);
+                self.dirs.lock().unwrap().remove(dir);
+                return false;
+            }
+        }
+        true
+    }
+    /// unregister the flag, unwatching the directory when no
+    /// other flag is interested in it
+    fn remove(&self, dir: &Path, flag: &Arc<AtomicBool>) {
+        let last = {
+            let mut dirs = self.dirs.lock().unwrap();
+            match dirs.get_mut(dir) {
+                Some(flags) => {
+                    flags.retain(|f| !Arc::ptr_eq(f, flag));
+                    if flags.is_empty() {
+                        dirs.remove(dir);
+                        true
+                    } else {
+                        false
+                    }
+                }
+                None => false,
+            }
+        };
+        if last {
+            let _ = self.watcher.lock().unwrap().unwatch(dir);
+        }
+    }
+}
+
+/// The watching of some directories with the shared `FsWatcher`,
+/// released on drop
+pub struct FsWatch {
+    watcher: Arc<FsWatcher>,
+    dirs: HashSet<PathBuf>,
+    changed: Arc<AtomicBool>,
+}
+
+impl FsWatch {
+    pub fn new(watcher: &Arc<FsWatcher>) -> Self {
+        Self {
+            watcher: Arc::clone(watcher),
+            dirs: HashSet::new(),
+            changed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+    /// make the given directories the watched ones, adding and
+    /// removing watches as needed
+    pub fn set_dirs<'p, I>(&mut self, dirs: I)
+    where
+        I: IntoIterator<Item = &'p Path>,
+    {
+        let dirs: HashSet<&Path> = dirs.into_iter()
+            .filter(|dir| dir.file_name().map_or(true, |name| name != ".git"))
+            .collect();
+        let watcher = &self.watcher;
+        let changed = &self.changed;
+        self.dirs.retain(|dir| {
+            let keep = dirs.contains(dir.as_path());
+            if !keep {
+                watcher.remove(dir, changed);
+            }
+            keep
+        });
+        for dir in dirs {
+            if !self.dirs.contains(dir) && self.watcher.add(dir, &self.changed) {
+                self.dirs.insert(dir.to_path_buf());
+            }
+        }
+    }
+    /// tell whether a change was notified since the last call to `clear`
+    pub fn has_changes(&self) -> bool {
+        self.changed.load(Ordering::Relaxed)
+    }
+    pub fn clear(&self) {
+        self.changed.store(false, Ordering::Relaxed);
+    }
+}
+
+impl Drop for FsWatch {
+    fn drop(&mut self) {
+        for dir in &self.dirs {
+            self.watcher.remove(dir, &self.changed);
+        }
+    }
+}
+
+/// tell whether the event is a change of the watched directories,
+/// and not just a reading
+fn is_change(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Access(AccessKind::Close(AccessMode::Write)) => true,
+        EventKind::Access(_) | EventKind::Other => false,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod fs_watcher_tests {
+
+    use {
+        super::*,
+        std::{
+            fs,
+            thread,
+            time::{Duration, Instant},
+        },
+    };
+
+    /// wait for the notification of a change, which comes from
+    /// another thread
+    fn wait_changes(watch: &FsWatch) -> bool {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(2) {
+            if watch.has_changes() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        false
+    }
+
+    #[test]
+    fn test_fs_watcher() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("sub");
+        fs::create_dir(&sub).unwrap();
+        let watcher = Arc::new(FsWatcher::new().unwrap());
+        let mut watch = FsWatch::new(&watcher);
+        watch.set_dirs(vec![dir.path()]);
+        assert!(!watch.has_changes());
+        fs::write(sub.join("a"), "a").unwrap(); // not watched
+        thread::sleep(Duration::from_millis(200));
+        watch.clear(); // on some systems the modification of sub is notified
+        fs::write(dir.path().join("b"), "b").unwrap();
+        assert!(wait_changes(&watch));
+        watch.set_dirs(vec![sub.as_path()]);
+        thread::sleep(Duration::from_millis(200));
+        watch.clear();
+        assert!(!watch.has_changes());
+        fs::remove_file(sub.join("a")).unwrap();
+        assert!(wait_changes(&watch));
+        // a second watch of the same directory shares the system watch,
+        // which stays when the first watch is dropped
+        let mut other_watch = FsWatch::new(&watcher);
+        other_watch.set_dirs(vec![sub.as_path()]);
+        drop(watch);
+        fs::write(sub.join("c"), "c").unwrap();
+        assert!(wait_changes(&other_watch));
+        drop(other_watch);
+        assert!(watcher.dirs.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,19 @@
+//! The watching of a file or directory, recording the changes of
+//! its content while broot runs, like inotifywait would do
+//!
+//! The watched path is checked periodically, which doesn't need any
+//! support from the system but misses changes cancelling each other
+//! between two checks.
+//!
+//! The module also holds the watcher notified by the system of the
+//! changes in the directories of a tree, for its live refresh.
+
+mod fs_watcher;
+mod snapshot;
+mod watch_state;
+
+pub use {
+    fs_watcher::{FsWatch, FsWatcher},
+    snapshot::*,
+    watch_state::WatchState,
+};
@@ -0,0 +1,121 @@
+use {
+    fnv::FnvHashMap,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        time::SystemTime,
+    },
+};
+
+/// the kind of a change detected between two snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+impl ChangeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Modified => "modified",
+            Self::Deleted => "deleted",
+        }
+    }
+}
+
+/// what's compared to detect a modification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl From<fs::Metadata> for Stamp {
+    fn from(md: fs::Metadata) -> Self {
+        Self {
+            modified: md.modified().ok(),
+            len: md.len(),
+        }
+    }
+}
+
+/// The state, at some time, of a watched path: the file itself or,
+/// for a directory, its direct children
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    stamps: FnvHashMap<PathBuf, Stamp>,
+}
+
+impl Snapshot {
+    /// read the path, a missing or unreadable one giving
+    /// an empty snapshot
+    pub fn of(path: &Path) -> Self {
+        let mut stamps = FnvHashMap::default();
+        if path.is_dir() {
+            if let Ok(read_dir) = fs::read_dir(path) {
+                for entry in read_dir.filter_map(|entry| entry.ok()) {
+                    if let Ok(md) = entry.metadata() {
+                        stamps.insert(entry.path(), md.into());
+                    }
+                }
+            }
+        } else if let Ok(md) = fs::metadata(path) {
+            stamps.insert(path.to_path_buf(), md.into());
+        }
+        Self { stamps }
+    }
+
+    /// the changes from this snapshot to a newer one, sorted by path
+    pub fn changes(&self, newer: &Snapshot) -> Vec<(ChangeKind, PathBuf)> {
+        let mut changes = Vec::new();
+        for (path, stamp) in &newer.stamps {
+            match self.stamps.get(path) {
+                None => changes.push((ChangeKind::Created, path.clone())),
+                Some(old) if old != stamp => changes.push((ChangeKind::Modified, path.clone())),
+                _ => {}
+            }
+        }
+        for path in self.stamps.keys() {
+            if !newer.stamps.contains_key(path) {
+                changes.push((ChangeKind::Deleted, path.clone()));
+            }
+        }
+        changes.sort_by(|a, b| a.1.cmp(&b.1));
+        changes
+    }
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_snapshot_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        fs::write(&a, "a").unwrap();
+        fs::write(&b, "b").unwrap();
+        let before = Snapshot::of(dir.path());
+        assert!(before.changes(&Snapshot::of(dir.path())).is_empty());
+        fs::write(&a, "longer").unwrap();
+        fs::remove_file(&b).unwrap();
+        let c = dir.path().join("c");
+        fs::write(&c, "c").unwrap();
+        assert_eq!(
+            before.changes(&Snapshot::of(dir.path())),
+            vec![
+                (ChangeKind::Modified, a.clone()),
+                (ChangeKind::Deleted, b),
+                (ChangeKind::Created, c),
+            ],
+        );
+        // a watched file
+        let before = Snapshot::of(&a);
+        fs::remove_file(&a).unwrap();
+        assert_eq!(before.changes(&Snapshot::of(&a)), vec![(ChangeKind::Deleted, a)]);
+    }
+}
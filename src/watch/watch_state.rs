@@ -0,0 +1,285 @@
+use {
+    super::*,
+    crate::{
+        app::*,
+        command::*,
+        display::*,
+        errors::ProgramError,
+        pattern::*,
+        task_sync::Dam,
+        tr,
+        tree::TreeOptions,
+        verb::*,
+    },
+    std::{
+        path::{Path, PathBuf},
+        time::{Duration, Instant, SystemTime},
+    },
+    termimad::minimad::Alignment,
+};
+
+/// the delay between two checks of the watched path
+const WATCH_PERIOD: Duration = Duration::from_secs(1);
+
+/// a change detected on the watched path
+#[derive(Debug, Clone)]
+struct WatchEvent {
+    time: SystemTime,
+    kind: ChangeKind,
+    path: PathBuf,
+    /// the path, relative to the watched directory
+    name: String,
+}
+
+/// an application state recording the changes (creations,
+/// modifications, deletions) of a file or of the direct children
+/// of a directory, most recent first, until stopped
+pub struct WatchState {
+    path: PathBuf,
+    snapshot: Snapshot,
+    last_check: Instant,
+    stopped: bool,
+    list: FilteredList<WatchEvent>,
+    tree_options: TreeOptions,
+    mode: Mode,
+}
+
+impl WatchState {
+    pub fn new(
+        path: PathBuf,
+        tree_options: TreeOptions,
+        con: &AppContext,
+    ) -> WatchState {
+        WatchState {
+            snapshot: Snapshot::of(&path),
+            path,
+            last_check: Instant::now(),
+            stopped: false,
+            list: FilteredList::new(Vec::new(), matches),
+            tree_options,
+            mode: initial_mode(con),
+        }
+    }
+    fn must_check(&self) -> bool {
+        !self.stopped && self.last_check.elapsed() >= WATCH_PERIOD
+    }
+
+    /// read the watched path again and record the changes, the
+    /// selected event staying selected unless it's the most recent one
+    fn check(&mut self) {
+        self.last_check = Instant::now();
+        let snapshot = Snapshot::of(&self.path);
+        let changes = self.snapshot.changes(&snapshot);
+        self.snapshot = snapshot;
+        if changes.is_empty() {
+            return;
+        }
+        let time = SystemTime::now();
+        let new_events = changes.into_iter()
+            .map(|(kind, path)| {
+                let name = path.strip_prefix(&self.path)
+                    .ok()
+                    .filter(|name| !name.as_os_str().is_empty())
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .to_string();
+                WatchEvent { time, kind, path, name }
+            })
+            .collect();
+        self.list.prepend(new_events);
+    }
+}
+
+fn matches(event: &WatchEvent, pattern: &Pattern) -> bool {
+    pattern.score_of_string(&event.name).is_some()
+        || pattern.score_of_string(event.kind.name()).is_some()
+}
+
+impl PanelState for WatchState {
+
+    fn get_type(&self) -> PanelStateType {
+        PanelStateType::Watch
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn selected_path(&self) -> Option<&Path> {
+        self.list.selected().map(|event| event.path.as_path())
+    }
+
+    fn tree_options(&self) -> TreeOptions {
+        self.tree_options.clone()
+    }
+
+    fn with_new_options(
+        &mut self,
+        _screen: Screen,
+        change_options: &dyn Fn(&mut TreeOptions) -> &'static str,
+        _in_new_panel: bool,
+        _con: &AppContext,
+    ) -> CmdResult {
+        change_options(&mut self.tree_options);
+        CmdResult::Keep
+    }
+
+    fn selection(&self) -> Option<Selection<'_>> {
+        self.selected_path().map(|path| Selection {
+            path,
+            line: 0,
+            line_range: None,
+            text_view: None,
+            stype: SelectionType::from(path),
+            is_exe: false,
+        })
+    }
+
+    fn refresh(&mut self, _screen: Screen, _con: &AppContext) -> Command {
+        if !self.stopped {
+            self.check();
+        }
+        Command::empty()
+    }
+
+    fn get_pending_task(&self) -> Option<&'static str> {
+        if self.must_check() {
+            Some("watching")
+        } else {
+            None
+        }
+    }
+
+    fn do_pending_task(
+        &mut self,
+        _app_state: &mut AppState,
+        _screen: Screen,
+        _con: &AppContext,
+        _dam: &mut Dam,
+    ) -> Result<(), ProgramError> {
+        if self.must_check() {
+            self.check();
+        }
+        Ok(())
+    }
+
+    fn refresh_period(&self) -> Option<Duration> {
+        if self.stopped {
+            None
+        } else {
+            Some(WATCH_PERIOD)
+        }
+    }
+
+    fn on_pattern(
+        &mut self,
+        pattern: InputPattern,
+        _app_state: &AppState,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_pattern(pattern);
+        Ok(CmdResult::Keep)
+    }
+
+    fn display(
+        &mut self,
+        w: &mut W,
+        disc: &DisplayContext,
+    ) -> Result<(), ProgramError> {
+        let date_time_format = self.tree_options.date_time_format;
+        let w_time = date_time_format.time_width();
+        let w_kind = "modified".len();
+        let columns = [
+            ListColumn::new("time", w_time, Alignment::Right),
+            ListColumn::new("change", w_kind, Alignment::Left),
+        ];
+        let styles = &disc.panel_skin.styles;
+        self.list.queue_table_head(w, disc, &columns, |cw| {
+            cw.queue_g_string(&styles.directory, self.path.to_string_lossy().to_string())?;
+            if self.stopped {
+                cw.queue_str(&styles.default, " (stopped)")?;
+            }
+            Ok(())
+        })?;
+        self.list.queue_table_rows(w, disc, |cw, event, row| {
+            let time = date_time_format.format_time(event.time);
+            row.queue_cell(cw, &time, row.txt_style, w_time, Alignment::Right)?;
+            let kind_style = row.style(match event.kind {
+                ChangeKind::Created => &styles.git_status_new,
+                ChangeKind::Modified => &styles.git_status_modified,
+                ChangeKind::Deleted => &styles.file_error,
+            });
+            row.queue_cell(cw, event.kind.name(), &kind_style, w_kind, Alignment::Left)?;
+            row.queue_matched(cw, &event.name, row.txt_style)
+        })
+    }
+
+    fn on_internal(
+        &mut self,
+        w: &mut W,
+        internal_exec: &InternalExecution,
+        input_invocation: Option<&VerbInvocation>,
+        trigger_type: TriggerType,
+        app_state: &mut AppState,
+        cc: &CmdContext,
+    ) -> Result<CmdResult, ProgramError> {
+        let screen = cc.app.screen;
+        let con = &cc.app.con;
+        use Internal::*;
+        if let Some(res) = self.list.on_internal(internal_exec, input_invocation) {
+            return Ok(res);
+        }
+        Ok(match internal_exec.internal {
+            Internal::open_stay => {
+                let in_new_panel = input_invocation
+                    .map(|inv| inv.bang)
+                    .unwrap_or(internal_exec.bang);
+                // the directory of the changed file (or its closest
+                // parent if it's gone) is displayed
+                match self.list.selected() {
+                    Some(event) => internal_focus::on_path(
+                        event.path.clone(),
+                        screen,
+                        self.tree_options.without_pattern(),
+                        in_new_panel,
+                        con,
+                    ),
+                    None => CmdResult::error(tr!(error_no_selected_change)),
+                }
+            }
+            Internal::toggle_watch => {
+                self.stopped = !self.stopped;
+                if !self.stopped {
+                    // the changes done while stopped aren't recorded
+                    self.snapshot = Snapshot::of(&self.path);
+                    self.last_check = Instant::now();
+                }
+                CmdResult::Keep
+            }
+            open_leave => CmdResult::PopStateAndReapply,
+            _ => self.on_internal_generic(
+                w,
+                internal_exec,
+                input_invocation,
+                trigger_type,
+                app_state,
+                cc,
+            )?,
+        })
+    }
+
+    fn on_click(
+        &mut self,
+        _x: u16,
+        y: u16,
+        _screen: Screen,
+        _con: &AppContext,
+    ) -> Result<CmdResult, ProgramError> {
+        self.list.on_click(y, 2);
+        Ok(CmdResult::Keep)
+    }
+}